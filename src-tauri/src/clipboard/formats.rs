@@ -1,41 +1,48 @@
-//! Windows 剪贴板多格式读取模块
+//! 剪贴板多格式读取模块
 //!
 //! ## 职责
 //! - 枚举当前剪贴板中的所有格式
-//! - 读取 HTML Format / Rich Text Format 等注册格式
+//! - 读取 HTML / RTF 等富文本格式，识别图片与文件列表
 //! - 提供上下文检测帮助上层判断内容类型
+//! - 读取剪贴板提供的原始编码图片（GIF / WebP / PNG），入库时免去重新编码
 //!
 //! ## 设计决策
 //!
 //! ### 为什么不用 arboard？
 //! `arboard` 仅提供 text / image / html 三种高级接口。
 //! 本模块需要枚举所有格式并读取自定义注册格式（HTML Format、RTF），
-//! 因此直接使用 Win32 API 操作系统剪贴板。
+//! 因此直接使用平台 API 操作系统剪贴板。
 //!
 //! ### 格式枚举的原子性
-//! `OpenClipboard` 到 `CloseClipboard` 之间其他进程无法写入，
+//! Windows：`OpenClipboard` 到 `CloseClipboard` 之间其他进程无法写入，
 //! 保证枚举和读取在同一个快照上完成。
+//! Linux (X11)：数据由 selection 所有者按需提供，无法加锁；
+//! 先枚举 `TARGETS` 再逐个转换，所有者在期间变更时以各目标的读取结果为准。
 //!
 //! ## 实现约束
-//! - 仅 Windows 平台可用，其他平台返回空/None
-//! - 所有 Win32 调用封装在 `unsafe` 块中
+//! - Windows 使用 Win32 剪贴板 API，Linux 使用 X11 selection（见 `x11_selection`）
+//! - 其他平台（及无法连接 X Server 的 Wayland 会话）返回空信息
+//! - 所有 FFI 调用封装在 `unsafe` 块中
+
+use crate::i18n::message;
+use crate::image_handler::PreservedImageFormat;
 
 /// 剪贴板格式上下文信息
 ///
-/// 在单次 `OpenClipboard` 期间收集的完整信息。
+/// Windows 在单次 `OpenClipboard` 期间收集，Linux 由一次 `TARGETS` 枚举及后续转换收集。
 #[derive(Debug, Clone, Default)]
 pub struct ClipboardFormatsInfo {
     /// 所有可用格式名称（用于调试日志）
     pub format_names: Vec<String>,
-    /// 是否包含文本格式（CF_UNICODETEXT）
+    /// 是否包含文本格式（CF_UNICODETEXT / UTF8_STRING）
     pub has_text: bool,
-    /// 是否包含图片格式（CF_BITMAP / CF_DIB）
+    /// 是否包含图片格式（CF_BITMAP / CF_DIB / image/png）
     pub has_image: bool,
-    /// 是否包含 HTML Format（注册格式）
+    /// 是否包含 HTML（HTML Format / text/html）
     pub has_html: bool,
-    /// 是否包含 Rich Text Format（注册格式）
+    /// 是否包含 RTF（Rich Text Format / text/rtf）
     pub has_rtf: bool,
-    /// 是否包含文件列表（CF_HDROP）
+    /// 是否包含文件列表（CF_HDROP / text/uri-list）
     pub has_files: bool,
     /// HTML 格式内容（仅提取 Fragment 部分）
     pub html_content: Option<String>,
@@ -43,6 +50,17 @@ pub struct ClipboardFormatsInfo {
     pub rtf_content: Option<String>,
 }
 
+/// RTF 内容保存上限（超过 64KB 的 RTF 存储价值低）
+#[cfg_attr(not(any(target_os = "windows", target_os = "linux")), allow(dead_code))]
+const MAX_RTF_BYTES: usize = 65536;
+
+/// 原始编码图片的读取上限，超出时回退为位图重新编码
+const MAX_ORIGINAL_IMAGE_BYTES: usize = 64 * 1024 * 1024;
+
+/// 原始编码图片的候选格式（Windows 注册格式名与 X11 MIME 目标），按优先级排列：
+/// 动图格式在前，PNG 最后
+const ORIGINAL_IMAGE_FORMATS: &[&str] = &["GIF", "image/gif", "image/webp", "PNG", "image/png"];
+
 impl ClipboardFormatsInfo {
     /// 判断是否为富文本上下文（Office/WPS/浏览器表格等）
     ///
@@ -60,6 +78,7 @@ impl ClipboardFormatsInfo {
 #[cfg(target_os = "windows")]
 mod win_impl {
     use super::ClipboardFormatsInfo;
+    use crate::i18n::message;
     use std::ffi::OsString;
     use std::os::windows::ffi::OsStringExt;
     use windows::Win32::System::DataExchange::{
@@ -131,6 +150,25 @@ mod win_impl {
         Some(bytes)
     }
 
+    /// 单独打开剪贴板读取指定注册格式的原始字节
+    pub fn read_named_format(name: &str) -> Option<Vec<u8>> {
+        let wide: Vec<u16> = name.encode_utf16().chain(std::iter::once(0)).collect();
+        let format_id = register_format(PCWSTR(wide.as_ptr()));
+        if format_id == 0 {
+            return None;
+        }
+
+        unsafe {
+            if OpenClipboard(None).is_err() {
+                log::warn!("{}", message("clipboard_log.read_format_open_failed", &[]));
+                return None;
+            }
+        }
+        let bytes = unsafe { read_format_bytes(format_id) };
+        let _ = unsafe { CloseClipboard() };
+        bytes
+    }
+
     /// 从 HTML Format 原始数据中提取 Fragment 部分
     ///
     /// HTML Format 有如下头部：
@@ -202,7 +240,7 @@ mod win_impl {
 
         unsafe {
             if OpenClipboard(None).is_err() {
-                log::warn!("📋 collect_clipboard_formats: 无法打开剪贴板");
+                log::warn!("{}", message("clipboard_log.collect_formats_open_failed", &[]));
                 return info;
             }
         }
@@ -246,12 +284,15 @@ mod win_impl {
             // 读取 RTF
             if info.has_rtf && rtf_format_id != 0 {
                 if let Some(bytes) = unsafe { read_format_bytes(rtf_format_id) } {
-                    // RTF 内容过大时截断（超过 64KB 的 RTF 存储价值低）
+                    // RTF 内容过大时跳过（超过 MAX_RTF_BYTES 的 RTF 存储价值低）
                     let rtf_text = String::from_utf8_lossy(&bytes);
-                    if rtf_text.len() <= 65536 {
+                    if rtf_text.len() <= super::MAX_RTF_BYTES {
                         info.rtf_content = Some(rtf_text.to_string());
                     } else {
-                        log::debug!("📋 RTF 内容过大（{} 字节），跳过保存", rtf_text.len());
+                        log::debug!(
+                            "{}",
+                            message("clipboard_log.rtf_too_large", &[("bytes", &rtf_text.len())])
+                        );
                     }
                 }
             }
@@ -261,13 +302,109 @@ mod win_impl {
         let _ = result;
 
         log::debug!(
-            "📋 剪贴板格式: [{}] text={} image={} html={} rtf={} files={}",
-            info.format_names.join(", "),
-            info.has_text,
-            info.has_image,
-            info.has_html,
-            info.has_rtf,
-            info.has_files,
+            "{}",
+            message(
+                "clipboard_log.formats",
+                &[
+                    ("formats", &info.format_names.join(", ")),
+                    ("text", &info.has_text),
+                    ("image", &info.has_image),
+                    ("html", &info.has_html),
+                    ("rtf", &info.has_rtf),
+                    ("files", &info.has_files),
+                ],
+            )
+        );
+
+        info
+    }
+}
+
+// ============================================================================
+// Linux (X11) 实现
+// ============================================================================
+
+#[cfg(target_os = "linux")]
+mod x11_impl {
+    use super::{ClipboardFormatsInfo, MAX_RTF_BYTES};
+    use crate::clipboard::x11_selection::{self, X11Session};
+    use crate::i18n::message;
+
+    const SELECTION: &str = "CLIPBOARD";
+
+    /// 表示纯文本的目标
+    const TEXT_TARGETS: &[&str] = &["UTF8_STRING", "text/plain;charset=utf-8", "text/plain", "STRING", "TEXT"];
+    const HTML_TARGET: &str = "text/html";
+    const RTF_TARGETS: &[&str] = &["text/rtf", "application/rtf", "text/richtext"];
+
+    /// 读取 CLIPBOARD 指定目标的原始字节
+    pub fn read_named_format(target: &str) -> Option<Vec<u8>> {
+        X11Session::open()?.read(SELECTION, target)
+    }
+
+    /// 是否为位图目标（SVG 按文本处理，不计入图片）
+    fn is_image_target(target: &str) -> bool {
+        target.starts_with("image/") && target != "image/svg+xml"
+    }
+
+    /// 枚举 CLIPBOARD 的 TARGETS 并读取关键数据
+    ///
+    /// # 返回
+    /// `ClipboardFormatsInfo`，字段语义与 Windows 实现一致
+    pub fn collect_clipboard_formats() -> ClipboardFormatsInfo {
+        let mut info = ClipboardFormatsInfo::default();
+        let Some(session) = X11Session::open() else {
+            return info;
+        };
+
+        info.format_names = session.targets(SELECTION);
+        let has = |name: &str| info.format_names.iter().any(|t| t == name);
+
+        info.has_text = TEXT_TARGETS.iter().any(|t| has(t));
+        info.has_html = has(HTML_TARGET);
+        let rtf_target = RTF_TARGETS.iter().copied().find(|t| has(t));
+        info.has_rtf = rtf_target.is_some();
+        info.has_image = info.format_names.iter().any(|t| is_image_target(t));
+
+        // 浏览器复制链接时也可能提供 text/uri-list，只有包含本地文件时才视为文件列表
+        if has(x11_selection::GNOME_COPIED_FILES) || has(x11_selection::URI_LIST) {
+            info.has_files = x11_selection::read_file_list(&session, SELECTION, &info.format_names).is_some();
+        }
+
+        if info.has_html {
+            info.html_content = session
+                .read(SELECTION, HTML_TARGET)
+                .map(|bytes| x11_selection::decode_html(&bytes))
+                .filter(|html| !html.trim().is_empty());
+        }
+
+        if let Some(bytes) = rtf_target.and_then(|target| session.read(SELECTION, target)) {
+            // 与 Windows 一致：超过 64KB 的 RTF 存储价值低
+            let rtf_text = String::from_utf8_lossy(&bytes);
+            let rtf_text = rtf_text.trim_end_matches('\0');
+            if rtf_text.len() <= MAX_RTF_BYTES {
+                info.rtf_content = Some(rtf_text.to_string());
+            } else {
+                log::debug!(
+                    "{}",
+                    message("clipboard_log.rtf_too_large", &[("bytes", &rtf_text.len())])
+                );
+            }
+        }
+
+        log::debug!(
+            "{}",
+            message(
+                "clipboard_log.targets",
+                &[
+                    ("targets", &info.format_names.join(", ")),
+                    ("text", &info.has_text),
+                    ("image", &info.has_image),
+                    ("html", &info.has_html),
+                    ("rtf", &info.has_rtf),
+                    ("files", &info.has_files),
+                ],
+            )
         );
 
         info
@@ -275,16 +412,20 @@ mod win_impl {
 }
 
 // ============================================================================
-// 非 Windows 平台占位实现
+// 其他平台占位实现
 // ============================================================================
 
-#[cfg(not(target_os = "windows"))]
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
 mod fallback_impl {
     use super::ClipboardFormatsInfo;
 
     pub fn collect_clipboard_formats() -> ClipboardFormatsInfo {
         ClipboardFormatsInfo::default()
     }
+
+    pub fn read_named_format(_name: &str) -> Option<Vec<u8>> {
+        None
+    }
 }
 
 // ============================================================================
@@ -294,13 +435,72 @@ mod fallback_impl {
 #[cfg(target_os = "windows")]
 pub use win_impl::collect_clipboard_formats;
 
-#[cfg(not(target_os = "windows"))]
+#[cfg(target_os = "linux")]
+pub use x11_impl::collect_clipboard_formats;
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
 pub use fallback_impl::collect_clipboard_formats;
 
+#[cfg(target_os = "windows")]
+use win_impl::read_named_format;
+
+#[cfg(target_os = "linux")]
+use x11_impl::read_named_format;
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+use fallback_impl::read_named_format;
+
+/// 读取剪贴板提供的原始编码图片（GIF / WebP / PNG）
+///
+/// 按 `ORIGINAL_IMAGE_FORMATS` 的优先级依次尝试 `info` 中已枚举到的格式，
+/// 以文件头校验内容；均不可用时返回 `None`，由调用方回退为位图编码。
+pub fn read_original_image(info: &ClipboardFormatsInfo) -> Option<(PreservedImageFormat, Vec<u8>)> {
+    ORIGINAL_IMAGE_FORMATS
+        .iter()
+        .filter(|name| info.format_names.iter().any(|n| n == *name))
+        .find_map(|name| read_named_format(name).and_then(|bytes| identify_original_image(name, bytes)))
+}
+
+/// 校验原始编码字节：超出上限或文件头不是 GIF / WebP / PNG 时丢弃
+fn identify_original_image(name: &str, bytes: Vec<u8>) -> Option<(PreservedImageFormat, Vec<u8>)> {
+    if bytes.len() > MAX_ORIGINAL_IMAGE_BYTES {
+        log::debug!(
+            "{}",
+            message(
+                "clipboard_log.original_image_too_large",
+                &[
+                    ("format", &name),
+                    ("bytes", &bytes.len()),
+                ],
+            )
+        );
+        return None;
+    }
+    let format = PreservedImageFormat::sniff(&bytes);
+    if format.is_none() {
+        log::debug!(
+            "{}",
+            message("clipboard_log.original_image_unrecognized", &[("format", &name)])
+        );
+    }
+    format.map(|format| (format, bytes))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn original_image_is_identified_by_content() {
+        let gif = b"GIF89a\x01\x00\x01\x00\x00\x00\x00;".to_vec();
+        assert_eq!(
+            identify_original_image("PNG", gif.clone()),
+            Some((PreservedImageFormat::Gif, gif))
+        );
+        assert_eq!(identify_original_image("image/png", b"not an image".to_vec()), None);
+        assert_eq!(identify_original_image("GIF", vec![0; MAX_ORIGINAL_IMAGE_BYTES + 1]), None);
+    }
+
     #[test]
     fn default_formats_info_is_empty() {
         let info = ClipboardFormatsInfo::default();
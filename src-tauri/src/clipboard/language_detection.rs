@@ -0,0 +1,433 @@
+//! 编程语言识别模块
+//!
+//! # 设计思路
+//!
+//! `code_detection` 只回答“是不是代码”，语法高亮所需的语言原先完全由前端猜测。
+//! 本模块在捕获时给出 `{language, confidence}`，结果写入 `history.language` 列，
+//! 既能按语言筛选历史，也让前端优先使用后端结论。
+//!
+//! # 实现思路
+//!
+//! - 与 `code_detection` 相同，所有特征编译进一个 `RegexSet`，一次扫描得到全部命中。
+//! - 每条特征带权重（1 = 弱线索，2 = 中等特征，3 = 决定性标志），按语言累加得分。
+//! - 再叠加行级 token 统计（分号结尾、冒号结尾、`key: value` 行占比等），
+//!   区分正则难以区分的 Python / YAML / C 系语言。
+//! - 超集语言合并证据：出现 TS 专有特征时 JS 得分并入 TS，C++ 同理合并 C。
+//! - 能被完整解析的 JSON 对象 / 数组直接判定，不走评分。
+//! - 置信度 = 领先幅度（最高分 / 最高分 + 次高分）× 证据强度，保留两位小数。
+
+use once_cell::sync::Lazy;
+use regex::RegexSet;
+use serde::Serialize;
+
+/// 参与识别的最短文本（字节），更短的内容不足以判断语言
+const MIN_DETECT_BYTES: usize = 8;
+
+/// 长文本只取开头这部分参与识别，避免对整段大文本跑正则
+const MAX_SAMPLE_BYTES: usize = 16 * 1024;
+
+/// 最高分低于该值时不给出结论
+const MIN_SCORE: f64 = 3.0;
+
+/// 得分达到该值视为证据充分（证据强度 = 1）
+const STRONG_EVIDENCE_SCORE: f64 = 9.0;
+
+/// 可完整解析为 JSON 的文本直接给出的置信度
+const JSON_CONFIDENCE: f64 = 0.99;
+
+/// 可识别的语言数量
+const LANGUAGE_COUNT: usize = 14;
+
+/// 特征权重
+const WEAK: f64 = 1.0;
+const MID: f64 = 2.0;
+const STRONG: f64 = 3.0;
+
+/// 可识别的语言（标识与前端 `LanguageId` 保持一致）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    Rust,
+    TypeScript,
+    JavaScript,
+    Python,
+    Go,
+    Sql,
+    Shell,
+    Yaml,
+    Json,
+    Html,
+    Css,
+    Java,
+    C,
+    Cpp,
+}
+
+impl Language {
+    pub const ALL: [Language; LANGUAGE_COUNT] = [
+        Language::Rust,
+        Language::TypeScript,
+        Language::JavaScript,
+        Language::Python,
+        Language::Go,
+        Language::Sql,
+        Language::Shell,
+        Language::Yaml,
+        Language::Json,
+        Language::Html,
+        Language::Css,
+        Language::Java,
+        Language::C,
+        Language::Cpp,
+    ];
+
+    /// 存库与前端使用的标识
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Language::Rust => "rust",
+            Language::TypeScript => "typescript",
+            Language::JavaScript => "javascript",
+            Language::Python => "python",
+            Language::Go => "go",
+            Language::Sql => "sql",
+            Language::Shell => "shell",
+            Language::Yaml => "yaml",
+            Language::Json => "json",
+            Language::Html => "html",
+            Language::Css => "css",
+            Language::Java => "java",
+            Language::C => "c",
+            Language::Cpp => "cpp",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|lang| lang.as_str() == id)
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    /// 行尾分号是否为该语言的常态
+    fn uses_semicolons(self) -> bool {
+        matches!(
+            self,
+            Language::Rust
+                | Language::TypeScript
+                | Language::JavaScript
+                | Language::Java
+                | Language::C
+                | Language::Cpp
+                | Language::Css
+                | Language::Sql
+        )
+    }
+}
+
+/// 识别结果
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct LanguageGuess {
+    pub language: Language,
+    /// 0~1，越大越可信
+    pub confidence: f64,
+}
+
+// ============================================================================
+// 特征表
+// ============================================================================
+
+/// 特征表：`(语言, 权重, 正则)`，整体编译为一个 `RegexSet`
+const RULES: &[(Language, f64, &str)] = &[
+    // ── Rust ──
+    (Language::Rust, STRONG, r"\bfn\s+\w+\s*(<[^>]*>)?\s*\("),
+    (Language::Rust, STRONG, r"#!?\[(derive|cfg|test|allow|warn|deny|inline|path|tauri::command)\b"),
+    (Language::Rust, STRONG, r"\b(println|eprintln|format|vec|write|writeln|panic|assert|assert_eq)!\s*[\(\[\{]"),
+    (Language::Rust, STRONG, r"\blet\s+mut\s+\w+"),
+    (Language::Rust, STRONG, r"\bimpl(<[^>]*>)?\s+[\w:<>]+(\s+for\s+[\w:<>]+)?\s*\{"),
+    (Language::Rust, MID, r"(?m)^\s*(pub(\([\w:]+\))?\s+)?(struct|enum|trait|mod)\s+[A-Za-z_]\w*"),
+    (Language::Rust, MID, r"(?m)^\s*use\s+(std|crate|super|self|[a-z_]+)::"),
+    (Language::Rust, MID, r"&(mut\s+)?self\b"),
+    (Language::Rust, MID, r"->\s*(Result|Option|Self|Vec|String|&|impl\b)"),
+    (Language::Rust, MID, r"\.(unwrap|expect|map_err|and_then|unwrap_or_else|as_ref|to_owned)\("),
+    (Language::Rust, WEAK, r"\b(Ok|Err|Some)\("),
+    (Language::Rust, WEAK, r"\b(i32|u8|u32|u64|i64|usize|f32|f64|&str)\b"),
+    // ── TypeScript（仅 TS 专有特征，通用 JS 特征由 JavaScript 承担）──
+    (Language::TypeScript, STRONG, r"\binterface\s+\w+(<[^>]*>)?\s*(extends\s+[\w<>, ]+)?\{"),
+    (Language::TypeScript, STRONG, r"(?m)^\s*(export\s+)?type\s+\w+(<[^>]*>)?\s*=\s*"),
+    (Language::TypeScript, STRONG, r"[\w)?]\s*:\s*(string|number|boolean|void|unknown|never|any)(\[\])?\s*[,;=)|{]"),
+    (Language::TypeScript, MID, r"\bas\s+(const|string|number|unknown|any)\b"),
+    (Language::TypeScript, MID, r"\b(public|private|protected|readonly)\s+\w+\s*[:?]"),
+    (Language::TypeScript, MID, r"\b(enum|namespace|declare)\s+\w+\s*\{"),
+    (Language::TypeScript, MID, r"\):\s*(Promise<|[A-Z]\w*(<[^>]*>)?\s*\{)"),
+    // ── JavaScript ──
+    (Language::JavaScript, MID, r"\b(const|let|var)\s+\w+\s*=\s*(function\b|async\b|\(|\[|\{|require\(|new\s|await\s)"),
+    (Language::JavaScript, MID, r"\bfunction\s*\w*\s*\([^)]*\)\s*\{"),
+    (Language::JavaScript, STRONG, r"\b(module\.exports|require\(['\x22])"),
+    (Language::JavaScript, STRONG, r"\b(console\.(log|warn|error|info)|document\.(getElementById|querySelector)|window\.\w+)"),
+    (Language::JavaScript, MID, r"(?m)^\s*import\s+[\w{},*\s]+\s+from\s+['\x22]"),
+    (Language::JavaScript, MID, r"(?m)^\s*export\s+(default|const|function|class|async)\b"),
+    (Language::JavaScript, MID, r"\)\s*=>\s*[\{\(]?"),
+    (Language::JavaScript, WEAK, r"===|!=="),
+    (Language::JavaScript, WEAK, r"\b(undefined|null)\b"),
+    // ── Python ──
+    (Language::Python, STRONG, r"(?m)^\s*(async\s+)?def\s+\w+\s*\([^)]*\)\s*(->\s*[\w\[\], .]+)?:"),
+    (Language::Python, STRONG, r"(?m)^\s*class\s+\w+\s*(\([\w., =]*\))?:\s*$"),
+    (Language::Python, STRONG, r"(?m)^\s*from\s+[\w.]+\s+import\s+[\w*(]"),
+    (Language::Python, STRONG, r"if\s+__name__\s*==\s*['\x22]__main__['\x22]"),
+    (Language::Python, MID, r"\bself\.\w+"),
+    (Language::Python, MID, r"(?m)^\s*(elif|except(\s+\w+)?|finally|else|try|with\s.+|for\s+\w+(,\s*\w+)*\s+in\s.+|while\s.+)\s*:\s*$"),
+    (Language::Python, MID, r"(?m)^\s*import\s+[\w.]+(\s+as\s+\w+)?\s*$"),
+    (Language::Python, MID, r"\b(True|False|None)\b"),
+    (Language::Python, WEAK, r"\bprint\(|\blambda\s+\w*:|\bf['\x22]"),
+    (Language::Python, WEAK, r"(?m)^\s*@\w+(\.\w+)*(\(.*\))?\s*$"),
+    // ── Go ──
+    (Language::Go, STRONG, r"(?m)^package\s+\w+\s*$"),
+    (Language::Go, STRONG, r"\bfunc\s+(\(\w+\s+\*?\w+\)\s+)?\w+\s*\("),
+    (Language::Go, STRONG, r"\bfmt\.(Print|Sprint|Fprint|Errorf)\w*\("),
+    (Language::Go, MID, r"\w+(\s*,\s*\w+)*\s*:=\s*"),
+    (Language::Go, MID, r"\bif\s+err\s*!=\s*nil\b"),
+    (Language::Go, MID, r"\b(go\s+func|defer\s+\w|chan\s+\w|make\(\[\]|map\[\w+\]\w+)"),
+    (Language::Go, WEAK, r"(?m)^import\s+\(\s*$"),
+    // ── SQL ──
+    (Language::Sql, STRONG, r"\bSELECT\s+[\s\S]+?\s+FROM\s+\w+"),
+    (Language::Sql, STRONG, r"\b(INSERT\s+INTO|DELETE\s+FROM|CREATE\s+(TABLE|INDEX|VIEW|UNIQUE\s+INDEX)|ALTER\s+TABLE|DROP\s+TABLE)\b"),
+    (Language::Sql, STRONG, r"\bUPDATE\s+\w+\s+SET\b"),
+    (Language::Sql, MID, r"(?i)\b(inner|left|right|full|outer|cross)\s+join\b"),
+    (Language::Sql, MID, r"(?i)\b(group|order)\s+by\s+\w+"),
+    (Language::Sql, MID, r"(?i)\bwhere\s+\w+(\.\w+)?\s*(=|<>|!=|>=|<=|>|<|\blike\b|\bin\b|\bis\b)"),
+    (Language::Sql, MID, r"(?i)^\s*select\s+[\w*,.\s()]+\s+from\s+\w+[\s\S]*;\s*$"),
+    (Language::Sql, WEAK, r"(?i)\b(varchar|integer|primary\s+key|not\s+null|limit\s+\d+)\b"),
+    // ── Shell ──
+    (Language::Shell, STRONG, r"^#!\s*/(usr/)?bin/(env\s+)?(ba|z|da|k)?sh\b"),
+    (Language::Shell, STRONG, r"\|\s*(grep|awk|sed|xargs|sort|uniq|head|tail|wc|tee|cut)\b"),
+    (Language::Shell, MID, r"(?m)^\s*(\$\s+)?(sudo|apt|apt-get|yum|dnf|brew|npm|npx|yarn|pnpm|cargo|pip3?|git|docker|kubectl|curl|wget|cd|ls|mkdir|rm|cp|mv|chmod|chown|export|source|systemctl|ssh|tar)\s"),
+    (Language::Shell, MID, r"(?m)^\s*(fi|done|esac|then|do)\s*$"),
+    (Language::Shell, MID, r"\$\{\w+(:-[^}]*)?\}|\$\(\s*\w"),
+    (Language::Shell, WEAK, r"\s--?[a-zA-Z][\w-]*"),
+    (Language::Shell, WEAK, r"\s(&&|\|\|)\s|\s2>&1\b|\s>\s*/dev/null"),
+    (Language::Shell, WEAK, r"\$[A-Z_][A-Z0-9_]*\b"),
+    // ── YAML ──
+    (Language::Yaml, MID, r"(?m)^---\s*$"),
+    (Language::Yaml, MID, r"(?m)^\s*-\s+[\w.-]+:\s+\S"),
+    (Language::Yaml, MID, r"(?m)^[\w.-]+:\s*$\n^\s+[\w.-]+:"),
+    (Language::Yaml, WEAK, r"(?m)^\s*[\w.-]+:\s+[|>]-?\s*$"),
+    // ── JSON（无法完整解析时的兜底特征）──
+    (Language::Json, MID, r#"(?m)^\s*"[^"\n]+"\s*:\s*("|\d|\{|\[|true\b|false\b|null\b)"#),
+    (Language::Json, MID, r#"\A[\{\[]\s*\n\s*[\{"]"#),
+    // ── HTML ──
+    (Language::Html, STRONG, r"(?i)<!DOCTYPE\s+html|<html[\s>]"),
+    (Language::Html, MID, r"<(div|span|p|a|ul|ol|li|table|tr|td|head|body|section|article|nav|header|footer|form|button|h[1-6])(\s[^>]*)?>"),
+    (Language::Html, MID, r"</(div|span|p|a|ul|ol|li|table|tr|td|head|body|section|article|nav|header|footer|form|button|h[1-6])>"),
+    (Language::Html, MID, r"<\w+\s[^>]*\b(class|href|src|id|style)=['\x22]"),
+    (Language::Html, WEAK, r"<(meta|link|br|img|input)\b[^>]*/?>"),
+    // ── CSS ──
+    (Language::Css, STRONG, r"(?m)^\s*(color|background(-color)?|margin(-\w+)?|padding(-\w+)?|display|font-(size|family|weight)|border(-\w+)?|width|height|position|flex(-\w+)?|grid-template-\w+|justify-content|align-items|z-index|opacity|transition|box-shadow)\s*:\s*[^;{}\n]+;"),
+    (Language::Css, STRONG, r"@(media|keyframes|font-face|import|supports)\b"),
+    (Language::Css, MID, r"(?m)^\s*[.#:]?[\w-]+([\s>+~,.#:\[\]=\x22'-]*[\w-]+\)?)*\s*\{\s*$"),
+    (Language::Css, MID, r"\d(px|rem|em|vh|vw|%)\b"),
+    (Language::Css, WEAK, r"!important\b|var\(--[\w-]+\)"),
+    // ── Java ──
+    (Language::Java, STRONG, r"\bpublic\s+(final\s+|abstract\s+)?class\s+\w+"),
+    (Language::Java, STRONG, r"\bpublic\s+static\s+void\s+main\s*\("),
+    (Language::Java, STRONG, r"\bSystem\.(out|err)\.print(ln|f)?\("),
+    (Language::Java, STRONG, r"(?m)^package\s+[\w.]+;\s*$"),
+    (Language::Java, MID, r"(?m)^import\s+(static\s+)?[\w.]+(\.\*)?;\s*$"),
+    (Language::Java, MID, r"@(Override|Autowired|Service|Component|Test|SpringBootApplication)\b"),
+    (Language::Java, MID, r"\b(private|protected|public)\s+(static\s+)?(final\s+)?[A-Z]?\w*(<[^>]*>)?\s+\w+\s*[=;(]"),
+    (Language::Java, WEAK, r"\bnew\s+[A-Z]\w*(<[^>]*>)?\("),
+    // ── C ──
+    (Language::C, STRONG, r"#include\s*<(stdio|stdlib|string|math|unistd|stdint|stdbool|errno)\.h>"),
+    (Language::C, STRONG, r"\b(malloc|calloc|free|printf|scanf|fprintf|sprintf)\s*\("),
+    (Language::C, MID, r"\btypedef\s+(struct|enum|union)\b"),
+    (Language::C, MID, r"(?m)^\s*#(include|define|ifdef|ifndef|endif|pragma)\b"),
+    (Language::C, MID, r"\bint\s+main\s*\("),
+    (Language::C, WEAK, r"\b(void|char|int|unsigned|size_t)\s*\*+\s*\w+"),
+    // ── C++ ──
+    (Language::Cpp, STRONG, r"#include\s*<(iostream|vector|string|map|memory|algorithm|unordered_map|thread)>"),
+    (Language::Cpp, STRONG, r"\bstd::(cout|cin|endl|vector|string|map|unique_ptr|shared_ptr|move)\b"),
+    (Language::Cpp, STRONG, r"\busing\s+namespace\s+\w+;"),
+    (Language::Cpp, MID, r"\b(template\s*<|nullptr|constexpr|virtual\s|override\b)"),
+    (Language::Cpp, MID, r"\bclass\s+\w+\s*(:\s*(public|private|protected)\s+\w+)?\s*\{"),
+    (Language::Cpp, WEAK, r"(cout|cerr)\s*<<|::\w+\("),
+];
+
+static RULE_SET: Lazy<RegexSet> =
+    Lazy::new(|| RegexSet::new(RULES.iter().map(|(_, _, pattern)| *pattern)).unwrap());
+
+// ============================================================================
+// Token 统计
+// ============================================================================
+
+/// 行级 token 统计：补充单条正则无法表达的“整体形状”
+#[derive(Debug, Default)]
+struct TokenStats {
+    lines: usize,
+    semicolon_lines: usize,
+    colon_block_lines: usize,
+    brace_lines: usize,
+    key_value_lines: usize,
+}
+
+impl TokenStats {
+    fn collect(text: &str) -> Self {
+        let mut stats = Self::default();
+        for line in text.lines().map(str::trim_end).filter(|line| !line.trim().is_empty()) {
+            stats.lines += 1;
+            let trimmed = line.trim_start();
+            if line.ends_with(';') {
+                stats.semicolon_lines += 1;
+            }
+            if line.ends_with(':') && !trimmed.starts_with('-') {
+                stats.colon_block_lines += 1;
+            }
+            if line.ends_with('{') || trimmed.starts_with('}') {
+                stats.brace_lines += 1;
+            }
+            if is_key_value_line(trimmed) {
+                stats.key_value_lines += 1;
+            }
+        }
+        stats
+    }
+
+    fn ratio(&self, count: usize) -> f64 {
+        if self.lines == 0 {
+            0.0
+        } else {
+            count as f64 / self.lines as f64
+        }
+    }
+}
+
+/// YAML 风格的 `key: value` / `key:` / `- item` 行
+fn is_key_value_line(line: &str) -> bool {
+    if let Some(item) = line.strip_prefix("- ") {
+        return !item.trim().is_empty();
+    }
+    let Some((key, value)) = line.split_once(':') else {
+        return false;
+    };
+    let key_ok = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | '"' | '\''));
+    key_ok && (value.is_empty() || value.starts_with(' '))
+}
+
+/// 按 token 统计调整各语言得分
+fn apply_token_stats(scores: &mut [f64; LANGUAGE_COUNT], stats: &TokenStats) {
+    if stats.lines < 2 {
+        return;
+    }
+
+    let semicolons = stats.ratio(stats.semicolon_lines);
+    if semicolons >= 0.3 {
+        for lang in Language::ALL {
+            if lang.uses_semicolons() && scores[lang.index()] > 0.0 {
+                scores[lang.index()] += WEAK;
+            }
+        }
+        scores[Language::Python.index()] -= MID;
+        scores[Language::Yaml.index()] -= STRONG;
+    }
+
+    let braces = stats.ratio(stats.brace_lines);
+    if stats.ratio(stats.colon_block_lines) >= 0.15 && braces < 0.1 {
+        scores[Language::Python.index()] += MID;
+    }
+
+    // 两行的 `key: value` 常见于邮件头、备注，至少三行才按 YAML 加分
+    if stats.lines >= 3
+        && stats.ratio(stats.key_value_lines) >= 0.8
+        && braces == 0.0
+        && semicolons == 0.0
+    {
+        scores[Language::Yaml.index()] += STRONG;
+    }
+}
+
+// ============================================================================
+// 入口
+// ============================================================================
+
+/// 识别文本的编程语言；证据不足时返回 `None`
+pub fn detect_language(text: &str) -> Option<LanguageGuess> {
+    let trimmed = text.trim();
+    if trimmed.len() < MIN_DETECT_BYTES {
+        return None;
+    }
+
+    if is_json_document(trimmed) {
+        return Some(LanguageGuess { language: Language::Json, confidence: JSON_CONFIDENCE });
+    }
+
+    let sample = sample_prefix(trimmed, MAX_SAMPLE_BYTES);
+    let mut scores = [0.0_f64; LANGUAGE_COUNT];
+    for idx in RULE_SET.matches(sample).iter() {
+        let (lang, weight, _) = RULES[idx];
+        scores[lang.index()] += weight;
+    }
+    apply_token_stats(&mut scores, &TokenStats::collect(sample));
+    merge_superset(&mut scores, Language::TypeScript, Language::JavaScript);
+    merge_superset(&mut scores, Language::Cpp, Language::C);
+
+    let (best, best_score) = Language::ALL
+        .into_iter()
+        .map(|lang| (lang, scores[lang.index()]))
+        .max_by(|a, b| a.1.total_cmp(&b.1))?;
+    if best_score < MIN_SCORE {
+        return None;
+    }
+    let runner_up = Language::ALL
+        .into_iter()
+        .filter(|&lang| lang != best)
+        .map(|lang| scores[lang.index()].max(0.0))
+        .fold(0.0_f64, f64::max);
+
+    let margin = best_score / (best_score + runner_up);
+    let strength = (best_score / STRONG_EVIDENCE_SCORE).min(1.0);
+    let confidence = margin * (0.5 + 0.5 * strength);
+    Some(LanguageGuess {
+        language: best,
+        confidence: (confidence * 100.0).round() / 100.0,
+    })
+}
+
+/// 超集语言出现专有特征时，并入子集语言的得分
+fn merge_superset(scores: &mut [f64; LANGUAGE_COUNT], superset: Language, subset: Language) {
+    if scores[superset.index()] > 0.0 {
+        scores[superset.index()] += scores[subset.index()].max(0.0);
+        scores[subset.index()] = 0.0;
+    }
+}
+
+/// 是否为可完整解析的 JSON 对象 / 数组
+fn is_json_document(text: &str) -> bool {
+    let bracketed = (text.starts_with('{') && text.ends_with('}'))
+        || (text.starts_with('[') && text.ends_with(']'));
+    if !bracketed {
+        return false;
+    }
+    match serde_json::from_str::<serde_json::Value>(text) {
+        Ok(serde_json::Value::Object(map)) => !map.is_empty(),
+        Ok(serde_json::Value::Array(items)) => !items.is_empty(),
+        _ => false,
+    }
+}
+
+/// 截取不超过 `max` 字节的前缀（保证落在字符边界）
+fn sample_prefix(text: &str, max: usize) -> &str {
+    if text.len() <= max {
+        return text;
+    }
+    let mut end = max;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+#[cfg(test)]
+#[path = "tests/language_detection_tests.rs"]
+mod tests;
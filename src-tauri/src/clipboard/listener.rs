@@ -6,6 +6,8 @@ use std::time::{Duration, Instant};
 use clipboard_master::{CallbackResult, ClipboardHandler, Master};
 use tauri::{AppHandle, Emitter, Wry};
 
+use crate::i18n::message;
+
 use super::try_consume_ignore_budget;
 
 // ── 常量 ──────────────────────────────────────────────────────
@@ -35,7 +37,7 @@ pub(crate) fn apply_event_min_interval_from_settings(settings: &serde_json::Valu
         .unwrap_or(CLIPBOARD_EVENT_MIN_INTERVAL_DEFAULT_MS);
     let normalized = normalize_clipboard_event_min_interval_ms(from_settings);
     CLIPBOARD_EVENT_MIN_INTERVAL_MS.store(normalized, Ordering::Relaxed);
-    log::debug!("📋 剪贴板监听节流间隔已更新: {}ms", normalized);
+    log::debug!("{}", message("clipboard_log.throttle_updated", &[("interval", &normalized)]));
 }
 
 fn current_event_min_interval_ms() -> u64 {
@@ -75,7 +77,7 @@ fn emit_external_clipboard_changed(app: &AppHandle<Wry>) {
         "clipboard-changed",
         ClipboardEventPayload { source: "external" },
     ) {
-        log::warn!("发送剪贴板变化事件失败: {}", err);
+        log::warn!("{}", message("clipboard_log.change_emit_failed", &[("detail", &err)]));
     }
 }
 
@@ -148,7 +150,7 @@ impl TailEmitter {
     }
 
     fn worker_loop(shared: Arc<TailEmitterShared>) {
-        log::debug!("📋 尾沿发射线程已启动");
+        log::debug!("{}", message("clipboard_log.trailing_emitter_started", &[]));
         let mut state = shared.state.lock().unwrap_or_else(|e| e.into_inner());
 
         loop {
@@ -191,7 +193,7 @@ impl TailEmitter {
             state = shared.state.lock().unwrap_or_else(|e| e.into_inner());
         }
 
-        log::debug!("📋 尾沿发射线程已退出");
+        log::debug!("{}", message("clipboard_log.trailing_emitter_stopped", &[]));
     }
 }
 
@@ -227,12 +229,15 @@ impl ClipboardHandler for Handler {
     fn on_clipboard_change(&mut self) -> CallbackResult {
         if let Some(remaining) = try_consume_ignore_budget() {
             log::debug!(
-                "⏭️  忽略应用主动触发的剪贴板变化，剩余预算: {}",
-                remaining
+                "{}",
+                message("clipboard_log.own_change_ignored", &[("remaining", &remaining)])
             );
             return CallbackResult::Next;
         }
 
+        #[cfg(target_os = "linux")]
+        super::primary::on_clipboard_changed();
+
         let now = Instant::now();
         let min_interval_ms = current_event_min_interval_ms();
         let min_interval = Duration::from_millis(min_interval_ms);
@@ -254,9 +259,14 @@ impl ClipboardHandler for Handler {
             drop(state);
             self.tail_emitter.schedule();
             log::trace!(
-                "⏱️ 剪贴板变化事件节流：{}ms < {}ms（尾沿补发）",
-                elapsed.as_millis(),
-                min_interval_ms
+                "{}",
+                message(
+                    "clipboard_log.change_throttled",
+                    &[
+                        ("elapsed", &elapsed.as_millis()),
+                        ("interval", &min_interval_ms),
+                    ],
+                )
             );
         }
 
@@ -264,7 +274,7 @@ impl ClipboardHandler for Handler {
     }
 
     fn on_clipboard_error(&mut self, error: std::io::Error) -> CallbackResult {
-        log::error!("剪贴板错误：{}", error);
+        log::error!("{}", message("clipboard_log.listener_error", &[("detail", &error)]));
         CallbackResult::Next
     }
 }
@@ -277,7 +287,7 @@ impl ClipboardHandler for Handler {
 /// * `app` - Tauri 应用句柄，用于向前端发送事件
 pub fn start_monitoring(app: AppHandle<Wry>) {
     if let Ok(Some(settings)) = crate::settings::get_app_settings(app.clone()) {
-        apply_event_min_interval_from_settings(&settings);
+        super::apply_runtime_settings(&settings);
     }
 
     thread::spawn(move || {
@@ -286,21 +296,29 @@ pub fn start_monitoring(app: AppHandle<Wry>) {
             match Master::new(Handler::new(app.clone())) {
                 Ok(mut master) => {
                     restart_attempt = 0;
-                    log::info!("📋 剪贴板监听已启动");
+                    log::info!("{}", message("clipboard_log.listener_started", &[]));
                     let _ = master.run();
-                    log::warn!("📋 剪贴板监听已退出，将尝试重启");
+                    log::warn!("{}", message("clipboard_log.listener_exited", &[]));
                 }
                 Err(err) => {
-                    log::error!("📋 创建剪贴板监听失败: {}", err);
+                    log::error!(
+                        "{}",
+                        message("clipboard_log.listener_create_failed", &[("detail", &err)])
+                    );
                 }
             }
 
             restart_attempt = restart_attempt.saturating_add(1);
             let backoff_ms = compute_restart_backoff_ms(restart_attempt);
             log::warn!(
-                "📋 剪贴板监听 {}ms 后重试（attempt={}）",
-                backoff_ms,
-                restart_attempt
+                "{}",
+                message(
+                    "clipboard_log.listener_retry",
+                    &[
+                        ("delay", &backoff_ms),
+                        ("attempt", &restart_attempt),
+                    ],
+                )
             );
             thread::sleep(Duration::from_millis(backoff_ms));
         }
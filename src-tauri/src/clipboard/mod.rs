@@ -7,7 +7,9 @@
 //! - **忽略标志 + RAII Guard**：防止应用自身写入剪贴板时触发重复保存，
 //!   使用 `IgnoreGuard` 确保即使 panic 也能正确恢复标志
 //! - **代码检测**：识别代码内容，避免将浏览器复制代码时附带的预览图误存
+//! - **内容识别**：识别文本的编程语言与语义子类型（URL、JSON、表格……），供筛选与建议操作使用
 //! - **保存**：将剪贴板中的图片/SVG 持久化到磁盘
+//! - **回写**：将历史条目的纯文本 / HTML / RTF 在一次事务中写回剪贴板
 //!
 //! # 实现思路
 //!
@@ -15,13 +17,24 @@
 //! - `IgnoreGuard` 采用 RAII 模式：构造时设置标志，`Drop` 时自动清除。
 //! - 监控器运行在独立线程中，通过 Tauri 事件通知前端。
 //! - 事件携带 `source` 字段区分外部变化与内部操作。
-//! - `listener` 子模块承载监听器实现，对外仅暴露 `start_monitoring()` 工厂函数。
-//! - 子模块按职责拆分：检测归 `code_detection`，持久化归 `save`。
+//! - `listener` 子模块承载监听器实现，对外仅暴露 `start_monitoring()` 工厂函数；
+//!   Linux 另有可选的 PRIMARY selection 监听器 `primary`（`start_primary_monitoring()`）。
+//! - 子模块按职责拆分：代码检测归 `code_detection`，语言识别归 `language_detection`，
+//!   内容子类型识别归 `subtype_detection`，
+//!   持久化归 `save`，多格式回写归 `restore`，
+//!   多格式读取归 `formats`（Linux 下经由 `x11_selection` 访问 X11 selection）。
 
 pub mod code_detection;
+pub mod language_detection;
+pub mod subtype_detection;
 pub mod formats;
+pub mod restore;
 pub mod save;
 mod listener;
+#[cfg(target_os = "linux")]
+mod primary;
+#[cfg(target_os = "linux")]
+pub mod x11_selection;
 
 use std::collections::VecDeque;
 use std::hash::{DefaultHasher, Hash, Hasher};
@@ -30,7 +43,11 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::{Duration, Instant};
 use once_cell::sync::Lazy;
 
+use crate::i18n::message;
+
 pub use listener::start_monitoring;
+#[cfg(target_os = "linux")]
+pub use primary::start_primary_monitoring;
 
 // ============================================================================
 // 剪贴板忽略标志
@@ -62,8 +79,8 @@ static RECENT_INTERNAL_IMAGE_FINGERPRINTS: Lazy<Mutex<VecDeque<RecentInternalIma
 pub fn set_ignore_flag() {
     let previous = IGNORE_CLIPBOARD_CHANGE_BUDGET.fetch_add(1, Ordering::SeqCst);
     log::debug!(
-        "🚫 已设置剪贴板忽略预算 - 当前预算: {}",
-        previous.saturating_add(1)
+        "{}",
+        message("clipboard_log.ignore_budget_set", &[("budget", &previous.saturating_add(1))])
     );
 }
 
@@ -160,6 +177,9 @@ impl IgnoreGuard {
 
 pub(crate) fn apply_runtime_settings(settings: &serde_json::Value) {
     listener::apply_event_min_interval_from_settings(settings);
+    save::apply_strip_exif_from_settings(settings);
+    #[cfg(target_os = "linux")]
+    primary::apply_settings_from_value(settings);
 }
 
 pub(crate) fn try_consume_ignore_budget() -> Option<usize> {
@@ -0,0 +1,372 @@
+//! X11 PRIMARY selection 监听模块（Linux）
+//!
+//! # 设计思路
+//!
+//! Linux 下大量复制是"选中即复制"（PRIMARY selection），`clipboard-master` 只关注
+//! CLIPBOARD，看不到这类内容。本模块在主监听器之外提供一个**可选**的 PRIMARY 监听器：
+//! - 拖选过程中 PRIMARY 会连续变化，因此使用独立的去抖间隔：内容稳定后才捕获
+//! - 最小长度过滤，避免双击单词、误触选中等碎片进入历史
+//! - 捕获的条目带 `selection = primary` 标记，可单独筛选与自动过期
+//! - 可选 PRIMARY ↔ CLIPBOARD 同步，兼容习惯 Windows / macOS 复制语义的用户
+//!
+//! # 实现思路
+//!
+//! - X11 不会为 PRIMARY 变化发送通知给非所有者，这里按固定间隔轮询文本内容
+//!   （与 parcellite / clipit 等剪贴板管理器相同的策略）
+//! - `PrimaryDebouncer`：纯状态机，输入每次轮询读到的文本，输出"稳定且需要处理"的文本（可测试）
+//! - 设置通过 `apply_settings_from_value` 热更新；关闭时线程仅低频休眠，不访问 X Server
+//! - 本模块成功写入 PRIMARY 后登记 `SELF_WRITTEN`，监听器据此跳过自身写入，避免同步回环
+//! - 写入 CLIPBOARD 时构造 `IgnoreGuard`（仅在 PRIMARY 捕获开启时），避免同一内容被记录两次
+
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+use tauri::{AppHandle, Emitter, Manager, Wry};
+
+use crate::db::{self, DbState};
+use crate::i18n::message;
+
+use super::x11_selection::{self, X11Session};
+use super::IgnoreGuard;
+
+// ── 常量 ──────────────────────────────────────────────────────
+
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+const IDLE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+const EXPIRE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+const DEBOUNCE_DEFAULT_MS: u64 = 600;
+const DEBOUNCE_MIN_MS: u64 = 100;
+const DEBOUNCE_MAX_MS: u64 = 5_000;
+const MIN_LENGTH_DEFAULT: usize = 3;
+const MIN_LENGTH_MAX: usize = 1_000;
+const EXPIRE_MINUTES_MAX: u64 = 7 * 24 * 60;
+/// 超过该长度的选中内容不捕获（整页全选等场景）
+const MAX_CAPTURE_BYTES: usize = 256 * 1024;
+
+/// PRIMARY 条目入库后发送给前端的事件
+const CAPTURED_EVENT: &str = "primary-selection-captured";
+/// PRIMARY 条目过期清理后发送给前端的事件
+const EXPIRED_EVENT: &str = "primary-selection-expired";
+
+// ── 设置 ──────────────────────────────────────────────────────
+
+/// PRIMARY ↔ CLIPBOARD 同步方向
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum SyncMode {
+    #[default]
+    Off,
+    /// 选中文本同时写入 CLIPBOARD
+    ToClipboard,
+    /// 复制（CLIPBOARD）的文本同时写入 PRIMARY，可直接中键粘贴
+    ToPrimary,
+    Both,
+}
+
+impl SyncMode {
+    fn parse(value: &str) -> Self {
+        match value {
+            "to_clipboard" => Self::ToClipboard,
+            "to_primary" => Self::ToPrimary,
+            "both" => Self::Both,
+            _ => Self::Off,
+        }
+    }
+
+    fn primary_to_clipboard(self) -> bool {
+        matches!(self, Self::ToClipboard | Self::Both)
+    }
+
+    fn clipboard_to_primary(self) -> bool {
+        matches!(self, Self::ToPrimary | Self::Both)
+    }
+}
+
+/// PRIMARY 监听设置（来自 settings.json）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct PrimarySettings {
+    /// 是否捕获 PRIMARY 到历史（`primarySelectionCapture`）
+    pub capture: bool,
+    /// 内容稳定多久后才处理（`primarySelectionDebounceMs`）
+    pub debounce: Duration,
+    /// 去除首尾空白后的最少字符数（`primarySelectionMinLength`）
+    pub min_length: usize,
+    /// 同步方向（`primarySelectionSync`）
+    pub sync: SyncMode,
+    /// PRIMARY 条目过期时间，`None` 表示不过期（`primarySelectionExpireMinutes`）
+    pub expire_after: Option<Duration>,
+}
+
+impl Default for PrimarySettings {
+    fn default() -> Self {
+        Self {
+            capture: false,
+            debounce: Duration::from_millis(DEBOUNCE_DEFAULT_MS),
+            min_length: MIN_LENGTH_DEFAULT,
+            sync: SyncMode::Off,
+            expire_after: None,
+        }
+    }
+}
+
+impl PrimarySettings {
+    pub(crate) fn from_value(settings: &serde_json::Value) -> Self {
+        let defaults = Self::default();
+        let debounce_ms = settings
+            .get("primarySelectionDebounceMs")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(DEBOUNCE_DEFAULT_MS)
+            .clamp(DEBOUNCE_MIN_MS, DEBOUNCE_MAX_MS);
+        let min_length = settings
+            .get("primarySelectionMinLength")
+            .and_then(|v| v.as_u64())
+            .map(|v| (v as usize).clamp(1, MIN_LENGTH_MAX))
+            .unwrap_or(defaults.min_length);
+        let expire_minutes = settings
+            .get("primarySelectionExpireMinutes")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0)
+            .min(EXPIRE_MINUTES_MAX);
+
+        Self {
+            capture: settings
+                .get("primarySelectionCapture")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(defaults.capture),
+            debounce: Duration::from_millis(debounce_ms),
+            min_length,
+            sync: settings
+                .get("primarySelectionSync")
+                .and_then(|v| v.as_str())
+                .map(SyncMode::parse)
+                .unwrap_or_default(),
+            expire_after: (expire_minutes > 0).then(|| Duration::from_secs(expire_minutes * 60)),
+        }
+    }
+
+    /// 是否需要轮询 PRIMARY
+    fn needs_polling(&self) -> bool {
+        self.capture || self.sync.primary_to_clipboard()
+    }
+}
+
+static SETTINGS: Lazy<Mutex<PrimarySettings>> = Lazy::new(|| Mutex::new(PrimarySettings::default()));
+
+/// 本模块最近一次写入 PRIMARY 的文本（监听器读到后跳过）
+static SELF_WRITTEN: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
+
+pub(crate) fn apply_settings_from_value(settings: &serde_json::Value) {
+    let parsed = PrimarySettings::from_value(settings);
+    *SETTINGS.lock().unwrap_or_else(|e| e.into_inner()) = parsed;
+    log::debug!(
+        "{}",
+        message("clipboard_log.primary_settings_updated", &[("settings", &format!("{:?}", parsed))])
+    );
+}
+
+fn current_settings() -> PrimarySettings {
+    *SETTINGS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+// ── 纯逻辑：去抖状态机 ───────────────────────────────────────
+
+/// PRIMARY 内容去抖器
+///
+/// 连续轮询读到相同文本且持续 `debounce` 后才输出一次；
+/// 已处理过的文本（含自身写入）不会重复输出。
+#[derive(Debug, Default)]
+pub(crate) struct PrimaryDebouncer {
+    last_seen: Option<String>,
+    changed_at: Option<Instant>,
+    last_handled: Option<String>,
+}
+
+impl PrimaryDebouncer {
+    /// 将文本视为已处理（启动时的既有内容、自身写入的内容）
+    pub(crate) fn acknowledge(&mut self, text: Option<String>) {
+        self.last_seen = text.clone();
+        self.last_handled = text;
+        self.changed_at = None;
+    }
+
+    /// 输入一次轮询结果，返回需要处理的稳定文本
+    pub(crate) fn observe(
+        &mut self,
+        text: Option<String>,
+        now: Instant,
+        settings: &PrimarySettings,
+    ) -> Option<String> {
+        if text != self.last_seen {
+            self.last_seen = text;
+            self.changed_at = Some(now);
+            return None;
+        }
+
+        let changed_at = self.changed_at?;
+        if now.saturating_duration_since(changed_at) < settings.debounce {
+            return None;
+        }
+        self.changed_at = None;
+
+        let text = self.last_seen.clone()?;
+        if self.last_handled.as_ref() == Some(&text) {
+            return None;
+        }
+        self.last_handled = Some(text.clone());
+
+        let trimmed = text.trim();
+        if trimmed.chars().count() < settings.min_length || text.len() > MAX_CAPTURE_BYTES {
+            return None;
+        }
+        Some(text)
+    }
+}
+
+// ── 处理 ──────────────────────────────────────────────────────
+
+fn handle_primary_text(app: &AppHandle<Wry>, text: &str, settings: &PrimarySettings) {
+    if settings.sync.primary_to_clipboard() {
+        // 捕获开启时 PRIMARY 条目已入库，CLIPBOARD 的同一内容无需再记录
+        let _ignore = settings.capture.then(IgnoreGuard::new);
+        if let Err(err) = x11_selection::own_selection("CLIPBOARD", x11_selection::build_text_targets(text)) {
+            log::warn!(
+                "{}",
+                message("clipboard_log.primary_to_clipboard_failed", &[("detail", &err)])
+            );
+        }
+    }
+
+    if !settings.capture {
+        return;
+    }
+    // 数据库初始化失败时不受管，跳过入库而不是让轮询线程 panic
+    let Some(state) = app.try_state::<DbState>() else {
+        log::warn!("{}", message("clipboard_log.primary_capture_no_db", &[]));
+        return;
+    };
+    let analysis = db::AssetAnalysis::of_text(text);
+    match db::with_conn_mut(&state, |conn| db::add_selection_clip(conn, text, db::SELECTION_PRIMARY, &analysis)) {
+        Ok(Some(item)) => {
+            log::debug!("{}", message("clipboard_log.primary_captured", &[("id", &item.id)]));
+            if let Err(err) = app.emit(CAPTURED_EVENT, item) {
+                log::warn!(
+                    "{}",
+                    message("clipboard_log.primary_capture_emit_failed", &[("detail", &err)])
+                );
+            }
+        }
+        Ok(None) => log::trace!("{}", message("clipboard_log.primary_duplicate", &[])),
+        Err(err) => log::warn!("{}", message("clipboard_log.primary_capture_failed", &[("detail", &err)])),
+    }
+}
+
+fn expire_primary_clips(app: &AppHandle<Wry>, expire_after: Duration) {
+    let cutoff = chrono::Utc::now().timestamp_millis() - expire_after.as_millis() as i64;
+    let Some(state) = app.try_state::<DbState>() else {
+        log::warn!("{}", message("clipboard_log.primary_expire_no_db", &[]));
+        return;
+    };
+    match db::with_conn_mut(&state, |conn| {
+        db::auto_clear_selection_before(conn, db::SELECTION_PRIMARY, cutoff)
+    }) {
+        Ok(ids) if !ids.is_empty() => {
+            log::info!("{}", message("clipboard_log.primary_expired", &[("count", &ids.len())]));
+            if let Err(err) = app.emit(EXPIRED_EVENT, ids) {
+                log::warn!(
+                    "{}",
+                    message("clipboard_log.primary_expire_emit_failed", &[("detail", &err)])
+                );
+            }
+        }
+        Ok(_) => {}
+        Err(err) => log::warn!("{}", message("clipboard_log.primary_expire_failed", &[("detail", &err)])),
+    }
+}
+
+/// CLIPBOARD 发生外部变化时调用：按同步设置把文本写入 PRIMARY
+///
+/// 在独立线程中读取与写入，不阻塞主监听器回调。
+pub(crate) fn on_clipboard_changed() {
+    if !current_settings().sync.clipboard_to_primary() {
+        return;
+    }
+    thread::spawn(|| {
+        let Some(text) = X11Session::open().and_then(|session| session.read_text("CLIPBOARD")) else {
+            return;
+        };
+        if text.trim().is_empty() {
+            return;
+        }
+        match x11_selection::own_selection("PRIMARY", x11_selection::build_text_targets(&text)) {
+            // 获得所有权后再登记，保证监听器此后读到的就是这段文本
+            Ok(()) => *SELF_WRITTEN.lock().unwrap_or_else(|e| e.into_inner()) = Some(text),
+            Err(err) => log::warn!(
+                "{}",
+                message("clipboard_log.clipboard_to_primary_failed", &[("detail", &err)])
+            ),
+        }
+    });
+}
+
+// ── 公共 API ─────────────────────────────────────────────────
+
+/// 在后台线程启动 PRIMARY 监听（设置关闭时线程空转休眠）
+pub fn start_primary_monitoring(app: AppHandle<Wry>) {
+    let spawned = thread::Builder::new()
+        .name("x11-primary-monitor".into())
+        .spawn(move || run(app));
+    if let Err(err) = spawned {
+        log::error!("{}", message("clipboard_log.primary_thread_failed", &[("detail", &err)]));
+    }
+}
+
+fn run(app: AppHandle<Wry>) {
+    let mut session: Option<X11Session> = None;
+    let mut debouncer = PrimaryDebouncer::default();
+    let mut last_expire_check: Option<Instant> = None;
+
+    loop {
+        let settings = current_settings();
+
+        let expire_due = settings
+            .expire_after
+            .filter(|_| last_expire_check.is_none_or(|t| t.elapsed() >= EXPIRE_CHECK_INTERVAL));
+        if let Some(expire_after) = expire_due {
+            last_expire_check = Some(Instant::now());
+            expire_primary_clips(&app, expire_after);
+        }
+
+        if !settings.needs_polling() {
+            session = None;
+            thread::sleep(IDLE_POLL_INTERVAL);
+            continue;
+        }
+
+        let Some(active) = session.as_ref() else {
+            session = X11Session::open();
+            match &session {
+                // 开启时已存在的选中内容不算新捕获
+                Some(opened) => debouncer.acknowledge(opened.read_text("PRIMARY")),
+                None => thread::sleep(RECONNECT_DELAY),
+            }
+            continue;
+        };
+
+        if let Some(written) = SELF_WRITTEN.lock().unwrap_or_else(|e| e.into_inner()).take() {
+            debouncer.acknowledge(Some(written));
+        }
+
+        let text = active.read_text("PRIMARY");
+        if let Some(text) = debouncer.observe(text, Instant::now(), &settings) {
+            handle_primary_text(&app, &text, &settings);
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/primary_tests.rs"]
+mod tests;
@@ -0,0 +1,238 @@
+//! 历史条目多格式回写模块
+//!
+//! # 设计思路
+//!
+//! `capture_clipboard_snapshot` 把 HTML / RTF 存入 `clip_formats`，
+//! 但此前回写只走 `write_text_to_clipboard`，粘贴到 Word / 邮件客户端时格式全部丢失。
+//! 本模块按 `CopyMode` 组装纯文本 + HTML + RTF，并在**一次剪贴板事务**中写入，
+//! 目标应用可自行挑选最合适的格式。
+//!
+//! # 实现思路
+//!
+//! - `build_payload`：按模式从条目文本与附加格式中挑选要写入的内容（纯函数，可测试）
+//! - Windows：Open → Empty → Set(CF_UNICODETEXT / HTML Format / Rich Text Format) → Close，
+//!   所有编码（UTF-16、CF_HTML 头）在打开剪贴板前完成
+//! - 其它平台：`arboard::set_html` 同时写入 HTML 与纯文本替代内容；RTF 暂不支持
+//! - 写入前构造 `IgnoreGuard`，回写内容不会被监听器再次记入历史
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::db::{self, ClipFormat, DbState};
+use crate::error::AppError;
+use crate::i18n::message;
+
+use super::IgnoreGuard;
+
+/// 回写模式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CopyMode {
+    /// 纯文本 + HTML + RTF（按条目实际保存的格式）
+    #[default]
+    Full,
+    /// 仅纯文本
+    Plain,
+    /// HTML（附带纯文本替代内容），不写 RTF
+    Html,
+}
+
+/// 一次回写要写入的全部格式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ClipPayload {
+    pub text: String,
+    pub html: Option<String>,
+    pub rtf: Option<String>,
+}
+
+fn find_format(formats: &[ClipFormat], name: &str) -> Option<String> {
+    formats
+        .iter()
+        .find(|f| f.format == name && !f.content.trim().is_empty())
+        .map(|f| f.content.clone())
+}
+
+/// 按模式挑选要写入的格式
+pub(crate) fn build_payload(mode: CopyMode, text: String, formats: &[ClipFormat]) -> ClipPayload {
+    let (html, rtf) = match mode {
+        CopyMode::Full => (find_format(formats, "html"), find_format(formats, "rtf")),
+        CopyMode::Plain => (None, None),
+        CopyMode::Html => (find_format(formats, "html"), None),
+    };
+    if mode == CopyMode::Html && html.is_none() {
+        log::debug!("{}", message("clipboard_log.restore_no_html", &[]));
+    }
+    ClipPayload { text, html, rtf }
+}
+
+// ============================================================================
+// CF_HTML 编码
+// ============================================================================
+
+const CF_HTML_START_MARKER: &str = "<!--StartFragment-->";
+const CF_HTML_END_MARKER: &str = "<!--EndFragment-->";
+
+/// 将 HTML 片段包装为 Windows `HTML Format`（CF_HTML）
+///
+/// 头部偏移均为 UTF-8 字节偏移，固定 10 位宽度，保证头部长度与偏移值无关。
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+pub(crate) fn build_cf_html(fragment: &str) -> String {
+    const HEADER_TEMPLATE_LEN: usize = "Version:0.9\r\nStartHTML:0000000000\r\nEndHTML:0000000000\r\nStartFragment:0000000000\r\nEndFragment:0000000000\r\n".len();
+
+    let prefix = format!("<html><body>\r\n{}", CF_HTML_START_MARKER);
+    let suffix = format!("{}\r\n</body>\r\n</html>", CF_HTML_END_MARKER);
+
+    let start_html = HEADER_TEMPLATE_LEN;
+    let start_fragment = start_html + prefix.len();
+    let end_fragment = start_fragment + fragment.len();
+    let end_html = end_fragment + suffix.len();
+
+    format!(
+        "Version:0.9\r\nStartHTML:{:010}\r\nEndHTML:{:010}\r\nStartFragment:{:010}\r\nEndFragment:{:010}\r\n{}{}{}",
+        start_html, end_html, start_fragment, end_fragment, prefix, fragment, suffix
+    )
+}
+
+// ============================================================================
+// 平台写入
+// ============================================================================
+
+#[cfg(target_os = "windows")]
+fn write_payload(payload: &ClipPayload) -> Result<(), AppError> {
+    use windows::Win32::Foundation::{GlobalFree, HANDLE};
+    use windows::Win32::System::DataExchange::{
+        CloseClipboard, EmptyClipboard, OpenClipboard, RegisterClipboardFormatW, SetClipboardData,
+    };
+    use windows::Win32::System::Memory::{GlobalAlloc, GlobalLock, GlobalUnlock, GMEM_MOVEABLE};
+    use windows::Win32::System::Ole::CF_UNICODETEXT;
+    use windows::core::w;
+
+    // ── 打开剪贴板前完成全部编码 ──
+    let mut entries: Vec<(u32, &str, Vec<u8>)> = Vec::with_capacity(3);
+    let text_bytes: Vec<u8> = payload
+        .text
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .flat_map(u16::to_le_bytes)
+        .collect();
+    entries.push((CF_UNICODETEXT.0 as u32, "CF_UNICODETEXT", text_bytes));
+    if let Some(html) = &payload.html {
+        let format_id = unsafe { RegisterClipboardFormatW(w!("HTML Format")) };
+        let mut bytes = build_cf_html(html).into_bytes();
+        bytes.push(0);
+        entries.push((format_id, "HTML Format", bytes));
+    }
+    if let Some(rtf) = &payload.rtf {
+        let format_id = unsafe { RegisterClipboardFormatW(w!("Rich Text Format")) };
+        let mut bytes = rtf.as_bytes().to_vec();
+        bytes.push(0);
+        entries.push((format_id, "Rich Text Format", bytes));
+    }
+
+    unsafe {
+        OpenClipboard(None).map_err(|e| AppError::ClipboardBusy(message("clipboard.open_failed", &[("detail", &format!("{:?}", e))])))?;
+    }
+
+    let result = (|| -> Result<(), AppError> {
+        unsafe {
+            EmptyClipboard().map_err(|e| AppError::Clipboard(message("clipboard.empty_failed", &[("detail", &format!("{:?}", e))])))?;
+
+            for (format_id, name, bytes) in &entries {
+                if *format_id == 0 {
+                    log::warn!(
+                        "{}",
+                        message(
+                            "clipboard_log.restore_register_format_failed",
+                            &[("format", &name)],
+                        )
+                    );
+                    continue;
+                }
+                let hglobal = GlobalAlloc(GMEM_MOVEABLE, bytes.len())
+                    .map_err(|e| AppError::Clipboard(message(
+                        "clipboard.alloc_failed",
+                        &[("format", name), ("detail", &format!("{:?}", e))],
+                    )))?;
+                let ptr = GlobalLock(hglobal) as *mut u8;
+                if ptr.is_null() {
+                    let _ = GlobalFree(Some(hglobal));
+                    return Err(AppError::Clipboard(message("clipboard.lock_memory_failed", &[("format", name)])));
+                }
+                std::ptr::copy_nonoverlapping(bytes.as_ptr(), ptr, bytes.len());
+                let _ = GlobalUnlock(hglobal);
+
+                // 成功时系统接管 hglobal 所有权，失败时手动释放
+                if let Err(e) = SetClipboardData(*format_id, Some(HANDLE(hglobal.0))) {
+                    let _ = GlobalFree(Some(hglobal));
+                    return Err(AppError::Clipboard(message(
+                        "clipboard.set_data_failed",
+                        &[("format", name), ("detail", &format!("{:?}", e))],
+                    )));
+                }
+            }
+        }
+        Ok(())
+    })();
+
+    let _ = unsafe { CloseClipboard() };
+    result
+}
+
+#[cfg(not(target_os = "windows"))]
+fn write_payload(payload: &ClipPayload) -> Result<(), AppError> {
+    let mut clipboard = arboard::Clipboard::new().map_err(AppError::from)?;
+    if payload.rtf.is_some() {
+        log::debug!("{}", message("clipboard_log.restore_rtf_unsupported", &[]));
+    }
+    match &payload.html {
+        Some(html) => clipboard.set_html(html.as_str(), Some(payload.text.as_str())),
+        None => clipboard.set_text(payload.text.as_str()),
+    }
+    .map_err(AppError::from)
+}
+
+// ============================================================================
+// Tauri Command
+// ============================================================================
+
+/// 将历史条目按指定模式写回剪贴板（纯文本 + HTML + RTF 一次写入）
+///
+/// `mode` 缺省为 `full`。
+#[tauri::command]
+pub async fn copy_clip_to_clipboard(
+    state: State<'_, DbState>,
+    id: i64,
+    mode: Option<CopyMode>,
+) -> Result<(), AppError> {
+    let mode = mode.unwrap_or_default();
+    let (text, formats) = db::with_read_conn(&state, |conn| {
+        let text = db::load_clip_text(conn, id)?;
+        let formats = match mode {
+            CopyMode::Plain => Vec::new(),
+            _ => db::load_formats(conn, id)?,
+        };
+        Ok((text, formats))
+    })?;
+    let text = text.ok_or_else(|| AppError::NotFound(message("db.item_not_found", &[("id", &id)])))?;
+
+    let payload = build_payload(mode, text, &formats);
+    log::debug!(
+        "{}",
+        message(
+            "clipboard_log.restore_item",
+            &[
+                ("id", &id),
+                ("mode", &format!("{:?}", mode)),
+                ("html", &payload.html.is_some()),
+                ("rtf", &payload.rtf.is_some()),
+            ],
+        )
+    );
+
+    let _guard = IgnoreGuard::new();
+    write_payload(&payload)
+}
+
+#[cfg(test)]
+#[path = "tests/restore_tests.rs"]
+mod tests;
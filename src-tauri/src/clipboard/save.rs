@@ -23,21 +23,55 @@
 //! - 所有函数均为 `#[tauri::command]`，由前端通过 IPC 调用。
 //! - 统一返回 `Result<T, AppError>`，前端收到一致的错误格式。
 //! - 图片读写委托 `image` crate，SVG 按纯文本处理。
-//! - 文件路径由 `storage::get_images_dir` 统一管理。
+//! - 剪贴板同时提供原始编码（GIF / WebP / PNG）且尺寸与位图一致时直接落盘，
+//!   保留动画与元数据；否则将位图重新编码为 PNG。
+//! - 文件路径由 `storage::get_images_dir` 统一管理，图片/SVG 以内容哈希命名（`storage::write_content_addressed`）。
 //! - 写入剪贴板前使用 `IgnoreGuard` RAII 设置忽略标志，防止触发重复保存。
 
 use std::fs;
-use chrono::Local;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use image::ImageFormat;
 use serde::{Deserialize, Serialize};
 use crate::error::AppError;
-use crate::storage::get_images_dir;
+use crate::i18n::message;
+use crate::image_handler::{strip_exif, PreservedImageFormat};
+use crate::storage::{get_images_dir, write_content_addressed};
 use super::code_detection::is_likely_code;
-use super::formats::collect_clipboard_formats;
+use super::formats::{collect_clipboard_formats, read_original_image};
 use super::{IgnoreGuard, remember_internal_image_fingerprint, should_ignore_internal_image_by_fingerprint};
 
 const FILES_PREFIX: &str = "[FILES]\n";
 
+/// 落盘前是否剥离 EXIF（含 GPS），由设置项 `stripImageExif` 控制
+static STRIP_IMAGE_EXIF: AtomicBool = AtomicBool::new(false);
+
+pub(crate) fn apply_strip_exif_from_settings(settings: &serde_json::Value) {
+    let enabled = settings
+        .get("stripImageExif")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    STRIP_IMAGE_EXIF.store(enabled, Ordering::Relaxed);
+}
+
+/// 按设置处理即将落盘的编码字节
+fn prepare_stored_image_bytes(bytes: Vec<u8>) -> Vec<u8> {
+    if STRIP_IMAGE_EXIF.load(Ordering::Relaxed) {
+        strip_exif(&bytes)
+    } else {
+        bytes
+    }
+}
+
+/// 将图片编码字节按内容寻址写入 `dir`
+///
+/// 捕获、链接转存、图片编辑等所有落盘路径都经过这里，保证 `stripImageExif` 无遗漏。
+pub(crate) fn write_stored_image(dir: &Path, bytes: Vec<u8>, ext: &str) -> Result<PathBuf, AppError> {
+    let bytes = prepare_stored_image_bytes(bytes);
+    write_content_addressed(dir, &bytes, ext)
+}
+
 // ============================================================================
 // 剪贴板快照数据结构
 // ============================================================================
@@ -64,7 +98,7 @@ pub struct ClipboardSnapshot {
 
 /// 打开系统剪贴板，统一错误转换
 fn open_clipboard() -> Result<arboard::Clipboard, AppError> {
-    arboard::Clipboard::new().map_err(|e| AppError::Clipboard(e.to_string()))
+    arboard::Clipboard::new().map_err(AppError::from)
 }
 
 fn encode_file_list(files: &[String]) -> Option<String> {
@@ -77,43 +111,69 @@ fn encode_file_list(files: &[String]) -> Option<String> {
 fn should_skip_image_by_text(text: &str) -> bool {
     if text.contains('\n') {
         if is_likely_code(text) {
-            log::debug!("🚫 检测到代码内容（多行），跳过保存图片");
+            log::debug!("{}", message("clipboard_log.skip_image_multiline_code", &[]));
             return true;
         }
         if text.len() > 500 {
             log::debug!(
-                "🚫 多行长文本（{} 字符）带图片，可能是网页复制，跳过保存",
-                text.len()
+                "{}",
+                message("clipboard_log.skip_image_long_text", &[("chars", &text.len())])
             );
             return true;
         }
     }
 
     if is_likely_code(text) {
-        log::debug!("🚫 检测到代码内容（单行），跳过保存图片");
+        log::debug!("{}", message("clipboard_log.skip_image_single_line_code", &[]));
         return true;
     }
 
     false
 }
 
+/// 读取编码字节头部声明的尺寸
+fn encoded_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    image::ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .ok()?
+        .into_dimensions()
+        .ok()
+}
+
 fn save_image_data(
     app: &tauri::AppHandle,
     custom_dir: Option<String>,
     image_data: arboard::ImageData<'_>,
+    original: Option<(PreservedImageFormat, Vec<u8>)>,
 ) -> Result<Option<String>, AppError> {
     let width = image_data.width as u32;
     let height = image_data.height as u32;
-    let image = image::RgbaImage::from_raw(width, height, image_data.bytes.into_owned())
-        .ok_or_else(|| AppError::Clipboard("创建图像缓冲区失败".to_string()))?;
 
-    let timestamp = Local::now().format("%Y%m%d%H%M%S%f");
-    let file_name = format!("img_{}.png", timestamp);
-    let file_path = get_images_dir(app, custom_dir)?.join(&file_name);
+    // 原始编码与位图尺寸一致才视为同一张图，避免剪贴板残留的旧格式被误存
+    if let Some((format, bytes)) = original.filter(|(_, bytes)| encoded_dimensions(bytes) == Some((width, height))) {
+        let file_path = write_stored_image(&get_images_dir(app, custom_dir)?, bytes, format.extension())?;
+        log::debug!(
+            "{}",
+            message(
+                "clipboard_log.original_encoding_kept",
+                &[
+                    ("mime", &format.mime()),
+                    ("path", &file_path.display()),
+                ],
+            )
+        );
+        return Ok(Some(file_path.to_string_lossy().to_string()));
+    }
+    let image = image::RgbaImage::from_raw(width, height, image_data.bytes.into_owned())
+        .ok_or_else(|| AppError::Clipboard(message("clipboard.image_buffer_failed", &[])))?;
 
+    let mut png = Vec::new();
     image
-        .save_with_format(&file_path, ImageFormat::Png)
-        .map_err(|e| AppError::Clipboard(format!("保存图片失败: {}", e)))?;
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(|e| AppError::Clipboard(message("clipboard.encode_image_failed", &[("detail", &e)])))?;
+
+    // 按内容寻址落盘：相同截图重复复制只保留一份文件
+    let file_path = write_stored_image(&get_images_dir(app, custom_dir)?, png, "png")?;
 
     Ok(Some(file_path.to_string_lossy().to_string()))
 }
@@ -123,10 +183,7 @@ fn save_svg_text(app: &tauri::AppHandle, custom_dir: Option<String>, text: &str)
     if (trimmed.contains("<svg") && trimmed.contains("</svg>"))
         || (trimmed.starts_with("<?xml") && trimmed.contains("<svg"))
     {
-        let timestamp = Local::now().format("%Y%m%d%H%M%S%f");
-        let file_name = format!("svg_{}.svg", timestamp);
-        let file_path = get_images_dir(app, custom_dir)?.join(&file_name);
-        fs::write(&file_path, text)?;
+        let file_path = write_content_addressed(&get_images_dir(app, custom_dir)?, text.as_bytes(), "svg")?;
         return Ok(Some(file_path.to_string_lossy().to_string()));
     }
     Ok(None)
@@ -179,7 +236,7 @@ fn read_clipboard_files_sync() -> Result<Option<Vec<String>>, AppError> {
             if files.is_empty() {
                 Ok(None)
             } else {
-                log::info!("📁 从剪贴板读取到 {} 个文件", files.len());
+                log::info!("{}", message("clipboard_log.files_read", &[("count", &files.len())]));
                 Ok(Some(files))
             }
         })();
@@ -189,7 +246,16 @@ fn read_clipboard_files_sync() -> Result<Option<Vec<String>>, AppError> {
     }
 }
 
-#[cfg(not(target_os = "windows"))]
+#[cfg(target_os = "linux")]
+fn read_clipboard_files_sync() -> Result<Option<Vec<String>>, AppError> {
+    let files = super::x11_selection::read_clipboard_file_list();
+    if let Some(files) = &files {
+        log::info!("{}", message("clipboard_log.files_read", &[("count", &files.len())]));
+    }
+    Ok(files)
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
 fn read_clipboard_files_sync() -> Result<Option<Vec<String>>, AppError> {
     Ok(None)
 }
@@ -198,28 +264,21 @@ fn read_clipboard_files_sync() -> Result<Option<Vec<String>>, AppError> {
 // 读取剪贴板文件列表
 // ============================================================================
 
-/// 从剪贴板读取文件列表（CF_HDROP 格式，Windows 专用）
+/// 从剪贴板读取文件列表
 ///
-/// 当用户在资源管理器中复制文件时，剪贴板中包含 CF_HDROP 数据。
-/// 此命令读取这些文件路径并返回。
+/// 当用户在资源管理器 / 文件管理器中复制文件时，剪贴板中包含文件列表：
+/// Windows 为 CF_HDROP，Linux (X11) 为 `x-special/gnome-copied-files` 或 `text/uri-list`。
+/// 此命令读取这些文件路径并返回；其他平台始终返回 `None`。
 ///
 /// # 返回
 /// - `Ok(Some(Vec<String>))`：包含一个或多个文件路径
 /// - `Ok(None)`：剪贴板中没有文件
 /// - `Err(msg)`：操作失败
-#[cfg(target_os = "windows")]
 #[tauri::command]
 pub async fn read_clipboard_files() -> Result<Option<Vec<String>>, AppError> {
     read_clipboard_files_sync()
 }
 
-/// 非 Windows 平台的占位实现
-#[cfg(not(target_os = "windows"))]
-#[tauri::command]
-pub async fn read_clipboard_files() -> Result<Option<Vec<String>>, AppError> {
-    Ok(None)
-}
-
 // ============================================================================
 // 保存剪贴板图片
 // ============================================================================
@@ -253,7 +312,8 @@ pub async fn save_clipboard_image(
                 return Ok(None);
             }
         }
-        return save_image_data(&app, custom_dir, image_data);
+        let original = read_original_image(&collect_clipboard_formats());
+        return save_image_data(&app, custom_dir, image_data, original);
     }
 
     Ok(None)
@@ -297,7 +357,7 @@ pub async fn capture_clipboard_snapshot(
     app: tauri::AppHandle,
     custom_dir: Option<String>,
 ) -> Result<Option<ClipboardSnapshot>, AppError> {
-    // 第一步：枚举格式并读取 HTML/RTF（Win32 API，一次 Open/Close）
+    // 第一步：枚举格式并读取 HTML/RTF（Windows 一次 Open/Close，Linux 枚举 X11 TARGETS）
     let formats_info = collect_clipboard_formats();
 
     // ── 1) 文件列表优先 ──
@@ -326,10 +386,15 @@ pub async fn capture_clipboard_snapshot(
                 // 富文本场景：文本是主体，HTML/RTF 是附加格式
                 // 图片是渲染截图，信息冗余，不保存
                 log::info!(
-                    "📋 富文本复制：text={}字符, html={}, rtf={}",
-                    text.len(),
-                    formats_info.has_html,
-                    formats_info.has_rtf,
+                    "{}",
+                    message(
+                        "clipboard_log.rich_text_copied",
+                        &[
+                            ("chars", &text.len()),
+                            ("html", &formats_info.has_html),
+                            ("rtf", &formats_info.has_rtf),
+                        ],
+                    )
                 );
 
                 // 检查文本是否为 SVG
@@ -362,7 +427,7 @@ pub async fn capture_clipboard_snapshot(
             image_data.height,
             image_data.bytes.as_ref(),
         ) {
-            log::debug!("⏭️ 检测到应用自身写入的图片指纹，跳过本次剪贴板捕获");
+            log::debug!("{}", message("clipboard_log.own_image_skipped", &[]));
             return Ok(None);
         }
 
@@ -371,7 +436,8 @@ pub async fn capture_clipboard_snapshot(
             .is_some_and(|t| should_skip_image_by_text(t));
 
         if !skip_image {
-            if let Some(image_path) = save_image_data(&app, custom_dir.clone(), image_data)? {
+            let original = read_original_image(&formats_info);
+            if let Some(image_path) = save_image_data(&app, custom_dir.clone(), image_data, original)? {
                 // 纯图片（截图工具等）
                 return Ok(Some(ClipboardSnapshot {
                     content_type: "image".to_string(),
@@ -381,7 +447,7 @@ pub async fn capture_clipboard_snapshot(
                 }));
             }
         }
-        log::debug!("⏭️ 跳过图片，回退到文本处理");
+        log::debug!("{}", message("clipboard_log.image_skipped_use_text", &[]));
     }
 
     // ── 4) 纯文本 ──
@@ -418,7 +484,7 @@ pub async fn copy_image_from_file(file_path: String) -> Result<(), AppError> {
     let mut clipboard = open_clipboard()?;
 
     let img = image::open(&file_path)
-        .map_err(|e| AppError::Clipboard(format!("打开图片失败: {}", e)))?;
+        .map_err(|e| AppError::Clipboard(message("clipboard.open_image_failed", &[("detail", &e)])))?;
     let rgba = img.to_rgba8();
     let (width, height) = rgba.dimensions();
     let raw = rgba.into_raw();
@@ -433,7 +499,7 @@ pub async fn copy_image_from_file(file_path: String) -> Result<(), AppError> {
 
     let _guard = IgnoreGuard::new();
     clipboard.set_image(image_data)
-        .map_err(|e| AppError::Clipboard(e.to_string()))?;
+        .map_err(AppError::from)?;
     Ok(())
 }
 
@@ -449,7 +515,7 @@ pub async fn copy_svg_from_file(file_path: String) -> Result<(), AppError> {
 
     let _guard = IgnoreGuard::new();
     clipboard.set_text(content)
-        .map_err(|e| AppError::Clipboard(e.to_string()))?;
+        .map_err(AppError::from)?;
     Ok(())
 }
 
@@ -457,19 +523,24 @@ pub async fn copy_svg_from_file(file_path: String) -> Result<(), AppError> {
 // 写入纯文本到剪贴板
 // ============================================================================
 
-/// 将纯文本写入剪贴板
-///
-/// 统一由后端处理，自动使用 IgnoreGuard 防止重复捕获。
-#[tauri::command]
-pub async fn write_text_to_clipboard(text: String) -> Result<(), AppError> {
+/// 在 IgnoreGuard 保护下写入纯文本，供后端其它模块复用
+pub(crate) fn write_plain_text(text: String) -> Result<(), AppError> {
     let mut clipboard = open_clipboard()?;
 
     let _guard = IgnoreGuard::new();
     clipboard.set_text(text)
-        .map_err(|e| AppError::Clipboard(e.to_string()))?;
+        .map_err(AppError::from)?;
     Ok(())
 }
 
+/// 将纯文本写入剪贴板
+///
+/// 统一由后端处理，自动使用 IgnoreGuard 防止重复捕获。
+#[tauri::command]
+pub async fn write_text_to_clipboard(text: String) -> Result<(), AppError> {
+    write_plain_text(text)
+}
+
 #[cfg(test)]
 #[path = "tests/save_tests.rs"]
 mod tests;
@@ -0,0 +1,510 @@
+//! 内容子类型识别模块
+//!
+//! # 设计思路
+//!
+//! `content_type` 只区分 text / image / files / rich，无法回答“这段文本是什么”。
+//! 本模块在捕获时把文本归入一个语义子类型（URL、邮箱、JSON、CSV 表格……），
+//! 结果写入 `history.content_subtype` 列用于筛选，并决定条目可用的建议操作
+//! （打开链接、格式化 JSON、转换时间戳等）。
+//!
+//! # 实现思路
+//!
+//! - 识别规则是一张有序表 `(子类型, 判定函数)`，按表顺序取第一个命中项；
+//!   越具体的规则越靠前（UUID、时间戳先于普通数字，JSON 先于代码）。
+//! - 单行规则要求整条内容完全匹配，避免长文本中的片段造成误判。
+//! - 日期时间用 `chrono` 按格式表严格解析，IP 地址用标准库解析，不靠正则猜测。
+//! - 代码复用 `code_detection` 与 `language_detection` 的结论。
+//! - 建议操作同样是静态表；`transform` 类操作的 ID 直接对应转换注册表中的转换，
+//!   显示文案在序列化时从文案目录渲染。
+
+use std::net::IpAddr;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime};
+use once_cell::sync::Lazy;
+use regex::{Regex, RegexSet};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::i18n::message;
+
+use super::code_detection::is_likely_code;
+use super::language_detection::detect_language;
+
+/// 单行规则参与识别的最大长度（字节）
+const MAX_SINGLE_LINE_BYTES: usize = 2048;
+
+/// 多行规则参与识别的最大长度（字节），更长的文本只做代码判断
+const MAX_STRUCTURED_BYTES: usize = 256 * 1024;
+
+/// Unix 时间戳（秒）的合理范围：2001-09-09 ~ 2100-01-01
+const UNIX_SECONDS_RANGE: std::ops::RangeInclusive<i64> = 1_000_000_000..=4_102_444_800;
+
+/// 内容子类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentSubtype {
+    Url,
+    Email,
+    Phone,
+    FilePath,
+    IpAddress,
+    Uuid,
+    Json,
+    Xml,
+    Table,
+    Markdown,
+    Number,
+    DateTime,
+    UnixTimestamp,
+    HexColor,
+    Code,
+}
+
+impl ContentSubtype {
+    pub const ALL: [ContentSubtype; 15] = [
+        ContentSubtype::Url,
+        ContentSubtype::Email,
+        ContentSubtype::Phone,
+        ContentSubtype::FilePath,
+        ContentSubtype::IpAddress,
+        ContentSubtype::Uuid,
+        ContentSubtype::Json,
+        ContentSubtype::Xml,
+        ContentSubtype::Table,
+        ContentSubtype::Markdown,
+        ContentSubtype::Number,
+        ContentSubtype::DateTime,
+        ContentSubtype::UnixTimestamp,
+        ContentSubtype::HexColor,
+        ContentSubtype::Code,
+    ];
+
+    /// 存库与前端使用的标识（与 serde 序列化一致）
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ContentSubtype::Url => "url",
+            ContentSubtype::Email => "email",
+            ContentSubtype::Phone => "phone",
+            ContentSubtype::FilePath => "file_path",
+            ContentSubtype::IpAddress => "ip_address",
+            ContentSubtype::Uuid => "uuid",
+            ContentSubtype::Json => "json",
+            ContentSubtype::Xml => "xml",
+            ContentSubtype::Table => "table",
+            ContentSubtype::Markdown => "markdown",
+            ContentSubtype::Number => "number",
+            ContentSubtype::DateTime => "date_time",
+            ContentSubtype::UnixTimestamp => "unix_timestamp",
+            ContentSubtype::HexColor => "hex_color",
+            ContentSubtype::Code => "code",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|subtype| subtype.as_str() == id)
+    }
+
+    /// 该子类型的建议操作（按推荐顺序）
+    pub fn suggested_actions(self) -> &'static [SuggestedAction] {
+        SUBTYPE_ACTIONS
+            .iter()
+            .find(|(subtype, _)| *subtype == self)
+            .map(|(_, actions)| *actions)
+            .unwrap_or(&[])
+    }
+}
+
+// ============================================================================
+// 建议操作
+// ============================================================================
+
+/// 建议操作的执行方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionKind {
+    /// 交给系统默认程序打开（浏览器、邮件、电话、文件管理器）
+    Open,
+    /// 执行同名的文本转换（见 `transform` 注册表）
+    Transform,
+    /// 在编辑器中打开
+    Edit,
+    /// 在前端展示格式换算结果
+    Convert,
+    /// 以文件形式复制到剪贴板
+    File,
+}
+
+/// 建议操作
+///
+/// 显示文案不随表保存：序列化时按当前语言渲染文案目录中的 `action.<id>`，
+/// 前端收到的仍是 `{ id, label, kind }`。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SuggestedAction {
+    pub id: &'static str,
+    pub kind: ActionKind,
+}
+
+impl SuggestedAction {
+    /// 显示文案的消息 ID
+    pub fn label_id(&self) -> String {
+        format!("action.{}", self.id)
+    }
+}
+
+impl Serialize for SuggestedAction {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("SuggestedAction", 3)?;
+        state.serialize_field("id", self.id)?;
+        state.serialize_field("label", &message(&self.label_id(), &[]))?;
+        state.serialize_field("kind", &self.kind)?;
+        state.end()
+    }
+}
+
+const fn action(id: &'static str, kind: ActionKind) -> SuggestedAction {
+    SuggestedAction { id, kind }
+}
+
+const OPEN_URL: SuggestedAction = action("open_url", ActionKind::Open);
+const OPEN_IN_EDITOR: SuggestedAction = action("open_in_editor", ActionKind::Edit);
+
+/// 子类型 → 建议操作（按推荐顺序）
+const SUBTYPE_ACTIONS: &[(ContentSubtype, &[SuggestedAction])] = &[
+    (ContentSubtype::Url, &[OPEN_URL, action("url_decode", ActionKind::Transform)]),
+    (ContentSubtype::Email, &[action("compose_email", ActionKind::Open)]),
+    (ContentSubtype::Phone, &[action("dial_phone", ActionKind::Open)]),
+    (
+        ContentSubtype::FilePath,
+        &[
+            action("open_path", ActionKind::Open),
+            action("reveal_path", ActionKind::Open),
+            action("copy_as_file", ActionKind::File),
+        ],
+    ),
+    (ContentSubtype::IpAddress, &[OPEN_URL]),
+    (
+        ContentSubtype::Uuid,
+        &[
+            action("upper_case", ActionKind::Transform),
+            action("lower_case", ActionKind::Transform),
+        ],
+    ),
+    (
+        ContentSubtype::Json,
+        &[
+            action("json_pretty", ActionKind::Transform),
+            action("json_minify", ActionKind::Transform),
+            OPEN_IN_EDITOR,
+        ],
+    ),
+    (ContentSubtype::Xml, &[action("html_escape", ActionKind::Transform), OPEN_IN_EDITOR]),
+    (
+        ContentSubtype::Table,
+        &[
+            action("sort_lines", ActionKind::Transform),
+            action("dedupe_lines", ActionKind::Transform),
+            OPEN_IN_EDITOR,
+        ],
+    ),
+    (ContentSubtype::Markdown, &[OPEN_IN_EDITOR]),
+    (ContentSubtype::Number, &[action("convert_number", ActionKind::Convert)]),
+    (ContentSubtype::DateTime, &[action("convert_datetime", ActionKind::Convert)]),
+    (ContentSubtype::UnixTimestamp, &[action("convert_timestamp", ActionKind::Convert)]),
+    (ContentSubtype::HexColor, &[action("convert_color", ActionKind::Convert)]),
+    (ContentSubtype::Code, &[OPEN_IN_EDITOR]),
+];
+
+// ============================================================================
+// 规则表
+// ============================================================================
+
+type Detector = fn(&str) -> bool;
+
+/// 单行规则：整条内容（去除首尾空白）必须是一行
+const SINGLE_LINE_RULES: &[(ContentSubtype, Detector)] = &[
+    (ContentSubtype::Uuid, is_uuid),
+    (ContentSubtype::HexColor, is_hex_color),
+    (ContentSubtype::Url, is_url),
+    (ContentSubtype::Email, is_email),
+    (ContentSubtype::IpAddress, is_ip_address),
+    (ContentSubtype::UnixTimestamp, is_unix_timestamp),
+    (ContentSubtype::Phone, is_phone),
+    (ContentSubtype::Number, is_number),
+    (ContentSubtype::DateTime, is_date_time),
+    (ContentSubtype::FilePath, is_file_path),
+];
+
+/// 结构化规则：单行或多行均可
+const STRUCTURED_RULES: &[(ContentSubtype, Detector)] = &[
+    (ContentSubtype::Json, is_json),
+    (ContentSubtype::Xml, is_xml),
+    (ContentSubtype::Table, is_tab_separated_table),
+    (ContentSubtype::Markdown, is_markdown),
+    (ContentSubtype::Code, is_code),
+    (ContentSubtype::Table, is_comma_separated_table),
+];
+
+static UUID_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\{?[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}\}?$").unwrap()
+});
+
+static HEX_COLOR_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^#([0-9a-fA-F]{3}|[0-9a-fA-F]{4}|[0-9a-fA-F]{6}|[0-9a-fA-F]{8})$").unwrap());
+
+static URL_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^((https?|ftp)://[^\s/?#]+|www\.[\w-]+(\.[\w-]+)+)([/?#]\S*)?$").unwrap()
+});
+
+static EMAIL_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(mailto:)?[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}$").unwrap()
+});
+
+/// 国际 / 带分隔符的电话号码；纯数字串仅接受中国大陆手机号，其余归为数字
+static PHONE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\+\d{1,3}[\s-]?)?(\(\d{1,4}\)[\s-]?)?\d{2,4}([\s.-]\d{2,4}){1,4}$|^\+\d{7,15}$|^1[3-9]\d{9}$").unwrap()
+});
+
+/// 年-月-日 / 日-月-年形状（无论是否为合法日期），不应被当作电话号码
+static DATE_SHAPE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\d{4}[-./]\d{1,2}[-./]\d{1,2}|\d{1,2}[-./]\d{1,2}[-./]\d{4})$").unwrap()
+});
+
+static NUMBER_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^[+-]?(\d{1,3}(,\d{3})+|\d+)(\.\d+)?([eE][+-]?\d+)?%?$|^[+-]?\.\d+$|^0[xX][0-9a-fA-F]+$").unwrap()
+});
+
+static FILE_PATH_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"^(~?/[^/\s<>|"*?][^<>|"*?]*|\.{1,2}/[^<>|"*?]+|[A-Za-z]:[\\/][^<>|"*?]*|\\\\[^\\\s]+\\[^<>|"*?]+)$"#).unwrap()
+});
+
+/// chrono 严格解析的日期时间格式
+const NAIVE_DATE_TIME_FORMATS: &[&str] = &[
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y/%m/%d %H:%M:%S",
+    "%Y/%m/%d %H:%M",
+    "%Y年%m月%d日 %H:%M:%S",
+    "%Y年%m月%d日 %H:%M",
+];
+const NAIVE_DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%Y/%m/%d", "%Y.%m.%d", "%Y年%m月%d日"];
+const NAIVE_TIME_FORMATS: &[&str] = &["%H:%M:%S", "%H:%M"];
+
+static MARKDOWN_FEATURES: Lazy<RegexSet> = Lazy::new(|| {
+    RegexSet::new([
+        // 标题
+        r"(?m)^#{1,6}\s+\S",
+        // 无序 / 有序列表
+        r"(?m)^\s*([-*+]|\d+\.)\s+\S",
+        // 链接 / 图片
+        r"!?\[[^\]\n]+\]\([^)\s]+\)",
+        // 代码围栏
+        r"(?m)^(```|~~~)",
+        // 粗体 / 行内代码
+        r"\*\*[^*\n]+\*\*|__[^_\n]+__|`[^`\n]+`",
+        // 引用
+        r"(?m)^>\s+\S",
+        // 表格分隔行
+        r"(?m)^\|?\s*:?-{3,}:?\s*(\|\s*:?-{3,}:?\s*)+\|?\s*$",
+    ])
+    .unwrap()
+});
+
+/// 下标 3：代码围栏，单独出现即可判定
+const MARKDOWN_FENCE_FEATURE: usize = 3;
+
+// ============================================================================
+// 入口
+// ============================================================================
+
+/// 识别文本的内容子类型；普通文本返回 `None`
+pub fn detect_subtype(text: &str) -> Option<ContentSubtype> {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+
+    if !trimmed.contains('\n') && trimmed.len() <= MAX_SINGLE_LINE_BYTES {
+        let single = SINGLE_LINE_RULES
+            .iter()
+            .find(|(_, detect)| detect(trimmed))
+            .map(|(subtype, _)| *subtype);
+        if single.is_some() {
+            return single;
+        }
+    }
+
+    if trimmed.len() > MAX_STRUCTURED_BYTES {
+        return is_code(trimmed).then_some(ContentSubtype::Code);
+    }
+    STRUCTURED_RULES
+        .iter()
+        .find(|(_, detect)| detect(trimmed))
+        .map(|(subtype, _)| *subtype)
+}
+
+// ============================================================================
+// 单行判定
+// ============================================================================
+
+fn is_uuid(text: &str) -> bool {
+    UUID_RE.is_match(text)
+}
+
+fn is_hex_color(text: &str) -> bool {
+    HEX_COLOR_RE.is_match(text)
+}
+
+fn is_url(text: &str) -> bool {
+    URL_RE.is_match(text)
+}
+
+fn is_email(text: &str) -> bool {
+    EMAIL_RE.is_match(text)
+}
+
+/// IPv4 / IPv6 地址，允许 CIDR 前缀或端口
+fn is_ip_address(text: &str) -> bool {
+    let parses = |value: &str| value.parse::<IpAddr>().is_ok();
+    if parses(text) || text.parse::<std::net::SocketAddr>().is_ok() {
+        return true;
+    }
+    match text.split_once('/') {
+        Some((addr, prefix)) => {
+            let max_prefix = if addr.contains(':') { 128 } else { 32 };
+            parses(addr) && prefix.parse::<u8>().is_ok_and(|p| p <= max_prefix)
+        }
+        None => false,
+    }
+}
+
+/// 10 位秒级或 13 位毫秒级时间戳，且落在合理年份范围内
+fn is_unix_timestamp(text: &str) -> bool {
+    if !text.bytes().all(|b| b.is_ascii_digit()) {
+        return false;
+    }
+    let Ok(value) = text.parse::<i64>() else {
+        return false;
+    };
+    match text.len() {
+        10 => UNIX_SECONDS_RANGE.contains(&value),
+        13 => UNIX_SECONDS_RANGE.contains(&(value / 1000)),
+        _ => false,
+    }
+}
+
+fn is_phone(text: &str) -> bool {
+    let digits = text.bytes().filter(u8::is_ascii_digit).count();
+    (7..=15).contains(&digits)
+        && PHONE_RE.is_match(text)
+        && !DATE_SHAPE_RE.is_match(text)
+        && !is_date_time(text)
+}
+
+fn is_number(text: &str) -> bool {
+    NUMBER_RE.is_match(text)
+}
+
+fn is_date_time(text: &str) -> bool {
+    DateTime::parse_from_rfc3339(text).is_ok()
+        || DateTime::parse_from_rfc2822(text).is_ok()
+        || NAIVE_DATE_TIME_FORMATS
+            .iter()
+            .any(|fmt| NaiveDateTime::parse_from_str(text, fmt).is_ok())
+        || NAIVE_DATE_FORMATS
+            .iter()
+            .any(|fmt| NaiveDate::parse_from_str(text, fmt).is_ok())
+        || NAIVE_TIME_FORMATS
+            .iter()
+            .any(|fmt| NaiveTime::parse_from_str(text, fmt).is_ok())
+}
+
+/// Unix 绝对路径 / `~/` / 相对路径 / Windows 盘符路径 / UNC 路径
+fn is_file_path(text: &str) -> bool {
+    !text.starts_with("//") && FILE_PATH_RE.is_match(text)
+}
+
+// ============================================================================
+// 结构化判定
+// ============================================================================
+
+/// 可完整解析的非空 JSON 对象 / 数组
+fn is_json(text: &str) -> bool {
+    let bracketed = (text.starts_with('{') && text.ends_with('}'))
+        || (text.starts_with('[') && text.ends_with(']'));
+    if !bracketed {
+        return false;
+    }
+    match serde_json::from_str::<serde_json::Value>(text) {
+        Ok(serde_json::Value::Object(map)) => !map.is_empty(),
+        Ok(serde_json::Value::Array(items)) => !items.is_empty(),
+        _ => false,
+    }
+}
+
+/// XML 声明开头，或首个元素的闭合标签恰好结束全文
+fn is_xml(text: &str) -> bool {
+    if text.starts_with("<?xml") {
+        return true;
+    }
+    let Some(rest) = text.strip_prefix('<') else {
+        return false;
+    };
+    let name: String = rest
+        .chars()
+        .take_while(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | ':' | '.'))
+        .collect();
+    if name.is_empty() || !name.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+        return false;
+    }
+    let self_closing = !text.contains('\n') && text.ends_with("/>");
+    self_closing || text.ends_with(&format!("</{}>", name))
+}
+
+/// 表格：不少于 `min_rows` 行，每行按分隔符切分后的列数一致且不少于两列
+fn is_delimited_table(text: &str, delimiter: char, min_rows: usize) -> bool {
+    let mut lines = text.lines().map(str::trim_end).filter(|line| !line.is_empty());
+    let Some(first) = lines.next() else {
+        return false;
+    };
+    let columns = first.split(delimiter).count();
+    let mut rows = 1;
+    for line in lines {
+        if line.split(delimiter).count() != columns {
+            return false;
+        }
+        rows += 1;
+    }
+    rows >= min_rows && columns >= 2
+}
+
+/// TSV：表格软件复制出的内容，制表符足以确定是表格
+fn is_tab_separated_table(text: &str) -> bool {
+    is_delimited_table(text, '\t', 2)
+}
+
+/// CSV：逗号在普通文本中很常见，排在代码判定之后，
+/// 并要求至少三行且每列都不是长句
+fn is_comma_separated_table(text: &str) -> bool {
+    is_delimited_table(text, ',', 3)
+        && text
+            .lines()
+            .flat_map(|line| line.split(','))
+            .all(|cell| cell.trim().len() <= 64 && !cell.trim().ends_with('.'))
+}
+
+/// Markdown：代码围栏单独成立，其余特征需至少命中两种
+fn is_markdown(text: &str) -> bool {
+    let matches = MARKDOWN_FEATURES.matches(text);
+    matches.matched(MARKDOWN_FENCE_FEATURE) || matches.iter().count() >= 2
+}
+
+fn is_code(text: &str) -> bool {
+    is_likely_code(text) || detect_language(text).is_some()
+}
+
+#[cfg(test)]
+#[path = "tests/subtype_detection_tests.rs"]
+mod tests;
@@ -0,0 +1,136 @@
+use super::*;
+
+fn detected(text: &str) -> Option<Language> {
+    detect_language(text).map(|guess| guess.language)
+}
+
+// ── 语料：每种语言若干真实片段 ─────────────────────────
+
+const CORPUS: &[(Language, &str)] = &[
+    (Language::Rust, "fn main() {\n    let mut count = 0;\n    println!(\"{}\", count);\n}"),
+    (Language::Rust, "#[derive(Debug, Clone)]\npub struct Config {\n    pub name: String,\n}"),
+    (Language::Rust, "impl Display for AppError {\n    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {\n        write!(f, \"{}\", self.0)\n    }\n}"),
+    (Language::Rust, "use std::collections::HashMap;\nlet map: HashMap<u32, &str> = HashMap::new();"),
+    (Language::TypeScript, "interface User {\n  id: number;\n  name: string;\n}"),
+    (Language::TypeScript, "export type Mode = 'a' | 'b';\nconst mode: Mode = 'a';"),
+    (Language::TypeScript, "function add(a: number, b: number): number {\n  return a + b;\n}"),
+    (Language::TypeScript, "const items = await invoke<ClipItem[]>('db_get_history', { limit }) as unknown;"),
+    (Language::JavaScript, "const fs = require('fs');\nmodule.exports = { read };"),
+    (Language::JavaScript, "document.querySelector('#app').addEventListener('click', () => {\n  console.log('clicked');\n});"),
+    (Language::JavaScript, "import React from 'react';\nexport default function App() {\n  return null;\n}"),
+    (Language::Python, "def greet(name):\n    print(f\"hello {name}\")\n\nif __name__ == '__main__':\n    greet('x')"),
+    (Language::Python, "class Stack:\n    def __init__(self):\n        self.items = []"),
+    (Language::Python, "from pathlib import Path\nfor p in Path('.').iterdir():\n    if p.is_file():\n        print(p)"),
+    (Language::Python, "import numpy as np\narr = np.zeros(3)\nresult = None"),
+    (Language::Go, "package main\n\nimport \"fmt\"\n\nfunc main() {\n\tfmt.Println(\"hi\")\n}"),
+    (Language::Go, "data, err := os.ReadFile(path)\nif err != nil {\n\treturn err\n}"),
+    (Language::Go, "func (s *Server) Start() error {\n\tgo func() { s.run() }()\n\treturn nil\n}"),
+    (Language::Sql, "SELECT id, name FROM users WHERE age > 18 ORDER BY name;"),
+    (Language::Sql, "INSERT INTO history (text, timestamp) VALUES ('a', 1);"),
+    (Language::Sql, "select u.id, count(*) from users u\nleft join orders o on o.user_id = u.id\ngroup by u.id;"),
+    (Language::Sql, "CREATE TABLE tags (\n  id INTEGER PRIMARY KEY,\n  name TEXT NOT NULL\n);"),
+    (Language::Shell, "#!/bin/bash\nset -e\nfor f in *.log; do\n  echo \"$f\"\ndone"),
+    (Language::Shell, "cat access.log | grep 404 | wc -l"),
+    (Language::Shell, "sudo apt-get install -y build-essential"),
+    (Language::Shell, "export PATH=\"$HOME/.cargo/bin:$PATH\" && cargo build --release"),
+    (Language::Yaml, "name: CI\non:\n  push:\n    branches: [main]\njobs:\n  build:\n    runs-on: ubuntu-latest"),
+    (Language::Yaml, "---\nversion: 2\nservices:\n  - name: web\n    image: nginx"),
+    (Language::Json, "{\"name\": \"clipboard-history\", \"version\": \"1.0.0\"}"),
+    (Language::Json, "[\n  {\"id\": 1},\n  {\"id\": 2}\n]"),
+    (Language::Json, "{\n  \"compilerOptions\": {\n    \"strict\": true,\n  }\n}"),
+    (Language::Html, "<!DOCTYPE html>\n<html>\n<head><title>x</title></head>\n<body></body>\n</html>"),
+    (Language::Html, "<div class=\"card\">\n  <a href=\"/home\">Home</a>\n</div>"),
+    (Language::Css, ".card {\n  display: flex;\n  padding: 8px 12px;\n}"),
+    (Language::Css, "@media (max-width: 600px) {\n  body { font-size: 14px; }\n}"),
+    (Language::Css, "#header > .nav:hover {\n  color: #333 !important;\n}"),
+    (Language::Java, "public class Main {\n    public static void main(String[] args) {\n        System.out.println(\"hi\");\n    }\n}"),
+    (Language::Java, "package com.example.demo;\n\nimport java.util.List;\n\n@Service\npublic class UserService {}"),
+    (Language::Java, "@Override\nprivate final List<String> names = new ArrayList<>();"),
+    (Language::C, "#include <stdio.h>\n\nint main(void) {\n    printf(\"hello\\n\");\n    return 0;\n}"),
+    (Language::C, "char *buf = malloc(len + 1);\nif (buf == NULL) return -1;\nfree(buf);"),
+    (Language::C, "typedef struct node {\n    int value;\n    struct node *next;\n} node_t;"),
+    (Language::Cpp, "#include <iostream>\n\nint main() {\n    std::cout << \"hi\" << std::endl;\n}"),
+    (Language::Cpp, "template <typename T>\nclass Box {\npublic:\n    T value;\n};"),
+    (Language::Cpp, "using namespace std;\nvector<int> v;\nauto p = make_unique<int>(1);"),
+];
+
+#[test]
+fn corpus_snippets_are_classified() {
+    let failures: Vec<String> = CORPUS
+        .iter()
+        .filter_map(|&(expected, text)| {
+            let actual = detect_language(text);
+            (actual.map(|guess| guess.language) != Some(expected))
+                .then(|| format!("expected {:?}, got {:?}: {:?}", expected, actual, text))
+        })
+        .collect();
+    assert!(failures.is_empty(), "misclassified:\n{}", failures.join("\n"));
+}
+
+#[test]
+fn corpus_covers_every_language() {
+    for lang in Language::ALL {
+        assert!(
+            CORPUS.iter().any(|(expected, _)| *expected == lang),
+            "corpus lacks {:?}",
+            lang
+        );
+    }
+}
+
+// ── 非代码文本 ─────────────────────────────────────────
+
+#[test]
+fn plain_text_is_not_classified() {
+    for text in [
+        "",
+        "hello",
+        "Meeting moved to 3pm tomorrow, please update the calendar.",
+        "Please select the items from the table where you left them.",
+        "https://example.com/path?query=1",
+        "今天的会议改到下午三点，请大家准时参加。",
+        "Note: remember to buy milk",
+        "To: bob@example.com\nSubject: hello",
+        "1234567890",
+    ] {
+        assert_eq!(detected(text), None, "unexpected language for {:?}", text);
+    }
+}
+
+#[test]
+fn empty_json_containers_are_not_classified() {
+    assert_eq!(detected("[]      "), None);
+    assert_eq!(detected("{   }    "), None);
+}
+
+// ── 置信度 ─────────────────────────────────────────────
+
+#[test]
+fn confidence_reflects_amount_of_evidence() {
+    let weak = detect_language("cargo build --release").expect("shell");
+    let strong = detect_language(CORPUS[0].1).expect("rust");
+    assert!(weak.confidence > 0.0 && weak.confidence < strong.confidence);
+    assert!(strong.confidence <= 1.0);
+
+    let json = detect_language("{\"a\": 1, \"b\": [1, 2]}").expect("json");
+    assert_eq!(json.language, Language::Json);
+    assert!(json.confidence > 0.9);
+}
+
+#[test]
+fn language_ids_round_trip() {
+    for lang in Language::ALL {
+        assert_eq!(Language::from_id(lang.as_str()), Some(lang));
+        assert_eq!(serde_json::to_value(lang).unwrap(), lang.as_str());
+    }
+    assert_eq!(Language::from_id("cobol"), None);
+}
+
+#[test]
+fn long_text_is_sampled_without_splitting_chars() {
+    let mut text = String::from("fn main() {\n    let mut v = vec![1];\n");
+    while text.len() < MAX_SAMPLE_BYTES + 10 {
+        text.push_str("    // 中文注释\n");
+    }
+    assert_eq!(detected(&text), Some(Language::Rust));
+}
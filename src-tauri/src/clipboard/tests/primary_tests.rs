@@ -0,0 +1,81 @@
+use super::*;
+use serde_json::json;
+
+fn settings(debounce_ms: u64, min_length: usize) -> PrimarySettings {
+    PrimarySettings {
+        capture: true,
+        debounce: Duration::from_millis(debounce_ms),
+        min_length,
+        ..PrimarySettings::default()
+    }
+}
+
+fn text(value: &str) -> Option<String> {
+    Some(value.to_string())
+}
+
+#[test]
+fn settings_parse_with_defaults_and_bounds() {
+    assert_eq!(PrimarySettings::from_value(&json!({})), PrimarySettings::default());
+
+    let parsed = PrimarySettings::from_value(&json!({
+        "primarySelectionCapture": true,
+        "primarySelectionDebounceMs": 10,
+        "primarySelectionMinLength": 0,
+        "primarySelectionSync": "both",
+        "primarySelectionExpireMinutes": 30,
+    }));
+    assert!(parsed.capture);
+    assert_eq!(parsed.debounce, Duration::from_millis(DEBOUNCE_MIN_MS));
+    assert_eq!(parsed.min_length, 1);
+    assert!(parsed.sync.primary_to_clipboard() && parsed.sync.clipboard_to_primary());
+    assert_eq!(parsed.expire_after, Some(Duration::from_secs(30 * 60)));
+
+    let sync_only = PrimarySettings::from_value(&json!({ "primarySelectionSync": "to_clipboard" }));
+    assert!(sync_only.needs_polling() && !sync_only.capture);
+    assert!(!PrimarySettings::from_value(&json!({ "primarySelectionSync": "to_primary" })).needs_polling());
+    assert_eq!(SyncMode::parse("unknown"), SyncMode::Off);
+}
+
+#[test]
+fn debouncer_emits_once_after_selection_settles() {
+    let cfg = settings(500, 3);
+    let start = Instant::now();
+    let at = |ms: u64| start + Duration::from_millis(ms);
+    let mut debouncer = PrimaryDebouncer::default();
+
+    // 拖选过程中内容持续变化：不输出
+    assert_eq!(debouncer.observe(text("hel"), at(0), &cfg), None);
+    assert_eq!(debouncer.observe(text("hello"), at(200), &cfg), None);
+    assert_eq!(debouncer.observe(text("hello wor"), at(400), &cfg), None);
+    assert_eq!(debouncer.observe(text("hello world"), at(600), &cfg), None);
+    assert_eq!(debouncer.observe(text("hello world"), at(800), &cfg), None);
+
+    // 稳定满 500ms 后输出一次，之后不再重复
+    assert_eq!(debouncer.observe(text("hello world"), at(1_100), &cfg), text("hello world"));
+    assert_eq!(debouncer.observe(text("hello world"), at(2_000), &cfg), None);
+
+    // 选中消失再重新选中同一段文本：不重复捕获
+    assert_eq!(debouncer.observe(None, at(2_200), &cfg), None);
+    assert_eq!(debouncer.observe(text("hello world"), at(2_400), &cfg), None);
+    assert_eq!(debouncer.observe(text("hello world"), at(3_000), &cfg), None);
+}
+
+#[test]
+fn debouncer_skips_short_selections_and_acknowledged_text() {
+    let cfg = settings(100, 4);
+    let start = Instant::now();
+    let at = |ms: u64| start + Duration::from_millis(ms);
+    let mut debouncer = PrimaryDebouncer::default();
+
+    debouncer.observe(text("  ab "), at(0), &cfg);
+    assert_eq!(debouncer.observe(text("  ab "), at(200), &cfg), None);
+
+    debouncer.observe(text("中文内容"), at(300), &cfg);
+    assert_eq!(debouncer.observe(text("中文内容"), at(500), &cfg), text("中文内容"));
+
+    // 自身写入的文本被确认后不会被当作新选中
+    debouncer.acknowledge(text("synced text"));
+    assert_eq!(debouncer.observe(text("synced text"), at(600), &cfg), None);
+    assert_eq!(debouncer.observe(text("synced text"), at(900), &cfg), None);
+}
@@ -0,0 +1,70 @@
+use super::*;
+
+fn formats() -> Vec<ClipFormat> {
+    vec![
+        ClipFormat { format: "html".into(), content: "<b>粗体</b>".into() },
+        ClipFormat { format: "rtf".into(), content: r"{\rtf1 \b bold\b0}".into() },
+        ClipFormat { format: "color".into(), content: "{}".into() },
+    ]
+}
+
+#[test]
+fn full_mode_includes_every_stored_format() {
+    let payload = build_payload(CopyMode::Full, "粗体".into(), &formats());
+    assert_eq!(payload.text, "粗体");
+    assert_eq!(payload.html.as_deref(), Some("<b>粗体</b>"));
+    assert_eq!(payload.rtf.as_deref(), Some(r"{\rtf1 \b bold\b0}"));
+}
+
+#[test]
+fn plain_and_html_modes_drop_formats() {
+    let plain = build_payload(CopyMode::Plain, "粗体".into(), &formats());
+    assert_eq!((plain.html, plain.rtf), (None, None));
+
+    let html = build_payload(CopyMode::Html, "粗体".into(), &formats());
+    assert!(html.html.is_some());
+    assert!(html.rtf.is_none());
+
+    // 没有 HTML 的条目退化为纯文本
+    let no_html = build_payload(CopyMode::Html, "text".into(), &[]);
+    assert_eq!(no_html, ClipPayload { text: "text".into(), html: None, rtf: None });
+}
+
+#[test]
+fn blank_formats_are_ignored() {
+    let blank = vec![ClipFormat { format: "html".into(), content: "  ".into() }];
+    assert!(build_payload(CopyMode::Full, "x".into(), &blank).html.is_none());
+}
+
+#[test]
+fn copy_mode_deserializes_lowercase_and_defaults_to_full() {
+    assert_eq!(serde_json::from_str::<CopyMode>("\"plain\"").unwrap(), CopyMode::Plain);
+    assert_eq!(serde_json::from_str::<CopyMode>("\"html\"").unwrap(), CopyMode::Html);
+    assert_eq!(CopyMode::default(), CopyMode::Full);
+}
+
+fn header_offset(cf_html: &str, key: &str) -> usize {
+    cf_html
+        .lines()
+        .find_map(|line| line.strip_prefix(key))
+        .and_then(|value| value.trim().parse().ok())
+        .expect(key)
+}
+
+#[test]
+fn cf_html_offsets_point_at_fragment_and_document() {
+    let fragment = "<p>中文 &amp; <b>bold</b></p>";
+    let cf_html = build_cf_html(fragment);
+    let bytes = cf_html.as_bytes();
+
+    let start_html = header_offset(&cf_html, "StartHTML:");
+    let end_html = header_offset(&cf_html, "EndHTML:");
+    let start_fragment = header_offset(&cf_html, "StartFragment:");
+    let end_fragment = header_offset(&cf_html, "EndFragment:");
+
+    assert_eq!(&bytes[start_fragment..end_fragment], fragment.as_bytes());
+    assert!(cf_html[start_html..].starts_with("<html>"));
+    assert_eq!(end_html, bytes.len());
+    assert!(cf_html[..start_fragment].ends_with(CF_HTML_START_MARKER));
+    assert!(cf_html[end_fragment..].starts_with(CF_HTML_END_MARKER));
+}
@@ -1,4 +1,11 @@
-use super::{encode_file_list, should_skip_image_by_text, FILES_PREFIX};
+use super::{
+    apply_strip_exif_from_settings, encode_file_list, should_skip_image_by_text, write_stored_image,
+    FILES_PREFIX,
+};
+use crate::image_handler::extract_image_metadata;
+use image::{DynamicImage, ImageFormat};
+use std::io::Cursor;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 #[test]
 fn encode_file_list_returns_none_for_empty() {
@@ -33,3 +40,49 @@ fn should_skip_image_by_text_detects_long_multiline_text() {
 fn should_skip_image_by_text_allows_normal_text() {
     assert!(!should_skip_image_by_text("hello world, clipboard history"));
 }
+
+/// 8×8 JPEG，SOI 之后插入 APP1 Exif 段：大端 TIFF，IFD0 仅含 GPS IFD 指针（GPSVersionID 2.2.0.0）
+fn jpeg_with_gps_exif() -> Vec<u8> {
+    let mut tiff = b"MM\0\x2a\0\0\0\x08".to_vec();
+    tiff.extend_from_slice(&1u16.to_be_bytes());
+    tiff.extend_from_slice(&[0x88, 0x25, 0, 4, 0, 0, 0, 1, 0, 0, 0, 26]);
+    tiff.extend_from_slice(&0u32.to_be_bytes());
+    tiff.extend_from_slice(&1u16.to_be_bytes());
+    tiff.extend_from_slice(&[0, 0, 0, 1, 0, 0, 0, 4, 2, 2, 0, 0]);
+    tiff.extend_from_slice(&0u32.to_be_bytes());
+
+    let mut app1 = vec![0xFF, 0xE1];
+    app1.extend_from_slice(&((tiff.len() + 8) as u16).to_be_bytes());
+    app1.extend_from_slice(b"Exif\0\0");
+    app1.extend_from_slice(&tiff);
+
+    let mut jpeg = Vec::new();
+    DynamicImage::new_rgb8(8, 8)
+        .write_to(&mut Cursor::new(&mut jpeg), ImageFormat::Jpeg)
+        .expect("encode jpeg");
+    jpeg.splice(2..2, app1);
+    jpeg
+}
+
+#[test]
+fn stored_images_drop_gps_exif_when_stripping_is_enabled() {
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+    let dir = std::env::temp_dir().join(format!("cliphist_strip_exif_{}", nanos));
+    let jpeg = jpeg_with_gps_exif();
+
+    apply_strip_exif_from_settings(&serde_json::json!({ "stripImageExif": false }));
+    let kept = write_stored_image(&dir, jpeg.clone(), "jpg").expect("store original");
+    assert!(extract_image_metadata(&kept).expect("metadata").has_gps);
+
+    apply_strip_exif_from_settings(&serde_json::json!({ "stripImageExif": true }));
+    let stored = write_stored_image(&dir, jpeg, "jpg");
+    apply_strip_exif_from_settings(&serde_json::json!({}));
+    let stored = stored.expect("store stripped");
+
+    assert_ne!(stored, kept);
+    assert!(!extract_image_metadata(&stored).expect("metadata").has_gps);
+    let bytes = std::fs::read(&stored).expect("read stored");
+    assert!(!bytes.windows(6).any(|w| w == b"Exif\0\0"));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,182 @@
+use super::*;
+
+// ── 语料：每种子类型的正例 ─────────────────────────────
+
+const CORPUS: &[(ContentSubtype, &str)] = &[
+    (ContentSubtype::Url, "https://example.com/path?query=1#top"),
+    (ContentSubtype::Url, "http://localhost:8080"),
+    (ContentSubtype::Url, "ftp://files.example.org/pub/readme.txt"),
+    (ContentSubtype::Url, "www.rust-lang.org/learn"),
+    (ContentSubtype::Email, "alice.smith+tag@example.co.uk"),
+    (ContentSubtype::Email, "mailto:bob@example.com"),
+    (ContentSubtype::Phone, "+86 138 0013 8000"),
+    (ContentSubtype::Phone, "(020) 1234-5678"),
+    (ContentSubtype::Phone, "555-123-4567"),
+    (ContentSubtype::Phone, "13800138000"),
+    (ContentSubtype::Phone, "+14155552671"),
+    (ContentSubtype::FilePath, "/usr/local/bin/cargo"),
+    (ContentSubtype::FilePath, "~/Documents/notes.md"),
+    (ContentSubtype::FilePath, "./src/main.rs"),
+    (ContentSubtype::FilePath, "C:\\Program Files\\App\\app.exe"),
+    (ContentSubtype::FilePath, "\\\\server\\share\\report.xlsx"),
+    (ContentSubtype::IpAddress, "192.168.1.10"),
+    (ContentSubtype::IpAddress, "10.0.0.0/8"),
+    (ContentSubtype::IpAddress, "127.0.0.1:3000"),
+    (ContentSubtype::IpAddress, "2001:db8::1"),
+    (ContentSubtype::IpAddress, "[::1]:8080"),
+    (ContentSubtype::Uuid, "550e8400-e29b-41d4-a716-446655440000"),
+    (ContentSubtype::Uuid, "{6F9619FF-8B86-D011-B42D-00C04FC964FF}"),
+    (ContentSubtype::Json, "{\"name\": \"clipboard-history\", \"version\": \"1.0.0\"}"),
+    (ContentSubtype::Json, "[\n  {\"id\": 1},\n  {\"id\": 2}\n]"),
+    (ContentSubtype::Xml, "<?xml version=\"1.0\"?>\n<root><item/></root>"),
+    (ContentSubtype::Xml, "<note>\n  <to>Tove</to>\n  <from>Jani</from>\n</note>"),
+    (ContentSubtype::Table, "name\tage\tcity\nAlice\t30\tBeijing\nBob\t25\tShanghai"),
+    (ContentSubtype::Table, "id,name,price\n1,apple,3.5\n2,banana,2"),
+    (ContentSubtype::Markdown, "# 标题\n\n- 第一项\n- 第二项"),
+    (ContentSubtype::Markdown, "See [the docs](https://example.com) for **details**."),
+    (ContentSubtype::Markdown, "示例：\n```\nnpm install\n```"),
+    (ContentSubtype::Number, "42"),
+    (ContentSubtype::Number, "-3.14"),
+    (ContentSubtype::Number, "1,234,567.89"),
+    (ContentSubtype::Number, "6.02e23"),
+    (ContentSubtype::Number, "0xFF"),
+    (ContentSubtype::Number, "99.5%"),
+    (ContentSubtype::DateTime, "2024-01-15"),
+    (ContentSubtype::DateTime, "2024-01-15 08:30:00"),
+    (ContentSubtype::DateTime, "2024-01-15T08:30:00+08:00"),
+    (ContentSubtype::DateTime, "Mon, 15 Jan 2024 08:30:00 +0000"),
+    (ContentSubtype::DateTime, "2024年1月15日"),
+    (ContentSubtype::DateTime, "23:59:59"),
+    (ContentSubtype::UnixTimestamp, "1700000000"),
+    (ContentSubtype::UnixTimestamp, "1700000000123"),
+    (ContentSubtype::HexColor, "#fff"),
+    (ContentSubtype::HexColor, "#1E90FF"),
+    (ContentSubtype::HexColor, "#1e90ff80"),
+    (ContentSubtype::Code, "fn main() {\n    let mut count = 0;\n    println!(\"{}\", count);\n}"),
+    (ContentSubtype::Code, "def greet(name):\n    print(f\"hello {name}\")\n\ngreet('x')"),
+    (ContentSubtype::Code, "SELECT id, name FROM users WHERE age > 18 ORDER BY name;"),
+];
+
+#[test]
+fn corpus_samples_are_classified() {
+    let failures: Vec<String> = CORPUS
+        .iter()
+        .filter_map(|&(expected, text)| {
+            let actual = detect_subtype(text);
+            (actual != Some(expected)).then(|| format!("expected {:?}, got {:?}: {:?}", expected, actual, text))
+        })
+        .collect();
+    assert!(failures.is_empty(), "misclassified:\n{}", failures.join("\n"));
+}
+
+#[test]
+fn corpus_covers_every_subtype() {
+    for subtype in ContentSubtype::ALL {
+        assert!(
+            CORPUS.iter().any(|(expected, _)| *expected == subtype),
+            "corpus lacks {:?}",
+            subtype
+        );
+    }
+}
+
+// ── 普通文本与边界 ─────────────────────────────────────
+
+#[test]
+fn plain_text_has_no_subtype() {
+    for text in [
+        "",
+        "   \n  ",
+        "hello",
+        "Meeting moved to 3pm tomorrow, please update the calendar.",
+        "今天的会议改到下午三点，请大家准时参加。",
+        "Hello, world\nGoodbye, world",
+        "{}",
+        "[]",
+        "<not closed",
+        "#zzzzzz",
+    ] {
+        assert_eq!(detect_subtype(text), None, "unexpected subtype for {:?}", text);
+    }
+}
+
+#[test]
+fn near_misses_fall_back_to_less_specific_subtypes() {
+    // 超出时间戳年份范围的 10 位数字只是数字
+    assert_eq!(detect_subtype("9999999999"), Some(ContentSubtype::Number));
+    // 位数不足的数字不是电话
+    assert_eq!(detect_subtype("12345"), Some(ContentSubtype::Number));
+    // 非法日期 / IP 不会被当作日期 / IP，日期形状也不会被当作电话
+    assert_eq!(detect_subtype("2024-13-45"), None);
+    assert_eq!(detect_subtype("45.13.2024"), None);
+    assert_ne!(detect_subtype("300.1.1.1"), Some(ContentSubtype::IpAddress));
+    assert_ne!(detect_subtype("10.0.0.0/33"), Some(ContentSubtype::IpAddress));
+    // 协议相对 URL 不是文件路径
+    assert_ne!(detect_subtype("//cdn.example.com/lib.js"), Some(ContentSubtype::FilePath));
+    // 列数不一致的逗号文本不是表格
+    assert_eq!(detect_subtype("a,b,c\nd,e"), None);
+}
+
+#[test]
+fn surrounding_whitespace_is_ignored() {
+    assert_eq!(detect_subtype("  https://example.com  \n"), Some(ContentSubtype::Url));
+    assert_eq!(detect_subtype("\t#abc\n"), Some(ContentSubtype::HexColor));
+}
+
+#[test]
+fn single_line_rules_do_not_match_multiline_text() {
+    assert_ne!(
+        detect_subtype("https://example.com\nhttps://example.org"),
+        Some(ContentSubtype::Url)
+    );
+}
+
+// ── 标识与建议操作 ─────────────────────────────────────
+
+#[test]
+fn subtype_ids_round_trip() {
+    for subtype in ContentSubtype::ALL {
+        assert_eq!(ContentSubtype::from_id(subtype.as_str()), Some(subtype));
+        assert_eq!(serde_json::to_value(subtype).unwrap(), subtype.as_str());
+    }
+    assert_eq!(ContentSubtype::from_id("movie"), None);
+}
+
+#[test]
+fn every_subtype_has_suggested_actions() {
+    for subtype in ContentSubtype::ALL {
+        assert!(!subtype.suggested_actions().is_empty(), "{:?} has no actions", subtype);
+    }
+}
+
+#[test]
+fn suggested_action_labels_come_from_the_catalog() {
+    use crate::i18n::{message_in, Locale};
+
+    for subtype in ContentSubtype::ALL {
+        for action in subtype.suggested_actions() {
+            let id = action.label_id();
+            assert_ne!(message_in(Locale::En, &id, &[]), id, "{} has no label", action.id);
+        }
+    }
+
+    let json = serde_json::to_value(ContentSubtype::Url.suggested_actions()[0]).unwrap();
+    assert_eq!((json["id"].as_str(), json["kind"].as_str()), (Some("open_url"), Some("open")));
+    assert!(json["label"].as_str().is_some_and(|label| !label.is_empty() && label != "action.open_url"));
+}
+
+#[test]
+fn transform_actions_reference_registered_transforms() {
+    for subtype in ContentSubtype::ALL {
+        for action in subtype.suggested_actions() {
+            if action.kind == ActionKind::Transform {
+                assert!(
+                    crate::transform::validate_chain(&[action.id.to_string()]).is_ok(),
+                    "{:?} references unknown transform {}",
+                    subtype,
+                    action.id
+                );
+            }
+        }
+    }
+}
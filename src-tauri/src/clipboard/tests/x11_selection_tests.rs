@@ -0,0 +1,112 @@
+use super::*;
+
+#[test]
+fn uri_list_keeps_local_files_and_decodes_percent_escapes() {
+    let list = b"# copied by test\r\nfile:///home/user/My%20Docs/a.txt\r\n\r\nhttps://example.com/x.png\r\nfile://localhost/tmp/%E4%B8%AD%E6%96%87.md\r\n";
+    assert_eq!(
+        parse_uri_list(list),
+        vec!["/home/user/My Docs/a.txt".to_string(), "/tmp/中文.md".to_string()]
+    );
+}
+
+#[test]
+fn uri_list_skips_malformed_entries() {
+    assert!(parse_uri_list(b"file:///bad%zz\nfile:relative\n").is_empty());
+    assert!(parse_uri_list(b"").is_empty());
+}
+
+#[test]
+fn gnome_copied_files_reports_cut_operation() {
+    let (is_cut, files) = parse_gnome_copied_files(b"cut\nfile:///a/b.txt\nfile:///c");
+    assert!(is_cut);
+    assert_eq!(files, vec!["/a/b.txt".to_string(), "/c".to_string()]);
+
+    let (is_cut, files) = parse_gnome_copied_files(b"copy\nfile:///x\0");
+    assert!(!is_cut);
+    assert_eq!(files, vec!["/x".to_string()]);
+}
+
+#[test]
+fn html_is_decoded_from_utf16_and_meta_prefix_is_stripped() {
+    let mut utf16 = vec![0xFF, 0xFE];
+    utf16.extend("<b>粗体</b>".encode_utf16().flat_map(u16::to_le_bytes));
+    assert_eq!(decode_html(&utf16), "<b>粗体</b>");
+
+    assert_eq!(decode_html(b"<meta charset='utf-8'><p>hi</p>\0"), "<p>hi</p>");
+    assert_eq!(decode_html("<p>中</p>".as_bytes()), "<p>中</p>");
+}
+
+#[test]
+fn file_targets_round_trip_through_parsers() {
+    let paths = vec!["/tmp/a b/报告.pdf".to_string(), "/home/u/#1%.txt".to_string()];
+    let targets = build_file_targets(&paths, false);
+    let target = |name: &str| targets.iter().find(|(t, _)| t == name).map(|(_, data)| data.clone()).unwrap();
+
+    assert_eq!(parse_uri_list(&target(URI_LIST)), paths);
+    assert_eq!(parse_gnome_copied_files(&target(GNOME_COPIED_FILES)), (false, paths.clone()));
+    assert_eq!(target("UTF8_STRING"), b"/tmp/a b/\xE6\x8A\xA5\xE5\x91\x8A.pdf\n/home/u/#1%.txt".to_vec());
+    assert_eq!(target(KDE_CUT_SELECTION), b"0".to_vec());
+
+    let cut = build_file_targets(&paths[..1], true);
+    assert!(cut.iter().any(|(t, data)| t == GNOME_COPIED_FILES && data.starts_with(b"cut\nfile:///tmp/a%20b/")));
+    assert!(cut.iter().any(|(t, data)| t == KDE_CUT_SELECTION && data == b"1"));
+}
+
+// ============================================================================
+// Xvfb 集成测试（默认忽略，需在 X Server 下以 `--ignored` 运行：
+// `xvfb-run cargo test x11_selection -- --ignored`）
+// ============================================================================
+
+mod xvfb {
+    use super::*;
+    use crate::clipboard::formats::collect_clipboard_formats;
+    use std::sync::Mutex;
+
+    /// 各测试都会抢占 CLIPBOARD 所有权，需串行执行
+    static CLIPBOARD_LOCK: Mutex<()> = Mutex::new(());
+
+    /// 以本进程作为 CLIPBOARD 所有者提供给定目标
+    fn serve_clipboard(targets: Vec<(&str, Vec<u8>)>) {
+        let contents = targets.into_iter().map(|(name, data)| (name.to_string(), data)).collect();
+        own_selection("CLIPBOARD", contents).expect("own CLIPBOARD (requires an X server, e.g. Xvfb)");
+    }
+
+    #[test]
+    #[ignore = "requires an X server (run under xvfb-run with --ignored)"]
+    fn collects_rich_text_targets_from_x11_clipboard() {
+        let _lock = CLIPBOARD_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        serve_clipboard(vec![
+            ("UTF8_STRING", "粗体".as_bytes().to_vec()),
+            ("text/html", b"<meta charset='utf-8'><b>\xE7\xB2\x97\xE4\xBD\x93</b>".to_vec()),
+            ("text/rtf", br"{\rtf1 \b bold\b0}".to_vec()),
+            ("image/png", vec![0x89, b'P', b'N', b'G']),
+        ]);
+
+        let info = collect_clipboard_formats();
+        assert!(info.format_names.iter().any(|t| t == "TARGETS"));
+        assert!(info.has_text && info.has_html && info.has_rtf && info.has_image);
+        assert!(!info.has_files);
+        assert!(info.is_rich_text_context());
+        assert_eq!(info.html_content.as_deref(), Some("<b>粗体</b>"));
+        assert_eq!(info.rtf_content.as_deref(), Some(r"{\rtf1 \b bold\b0}"));
+    }
+
+    #[test]
+    #[ignore = "requires an X server (run under xvfb-run with --ignored)"]
+    fn detects_file_lists_but_not_web_uri_lists() {
+        let _lock = CLIPBOARD_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        serve_clipboard(vec![(URI_LIST, b"https://example.com/\r\n".to_vec())]);
+        assert!(!collect_clipboard_formats().has_files);
+
+        let paths = vec!["/tmp/a b.txt".to_string(), "/tmp/中文".to_string()];
+        copy_files_to_clipboard(&paths, true).expect("own clipboard");
+        let info = collect_clipboard_formats();
+        assert!(info.has_files && info.has_text);
+        assert_eq!(read_clipboard_file_list(), Some(paths.clone()));
+
+        let session = X11Session::open().expect("display");
+        let gnome = session.read("CLIPBOARD", GNOME_COPIED_FILES).expect("gnome-copied-files");
+        assert_eq!(parse_gnome_copied_files(&gnome), (true, paths));
+        assert!(session.read("CLIPBOARD", "application/x-unknown").is_none());
+    }
+}
@@ -0,0 +1,655 @@
+//! X11 selection 读写模块（Linux）
+//!
+//! # 设计思路
+//!
+//! `arboard` 只暴露 text / image / html 三种高级接口，无法枚举格式，也读不到
+//! RTF 和文件列表。X11 下剪贴板是由 selection 所有者按需提供的"目标"（target），
+//! 因此本模块直接走 ICCCM 协议：先请求 `TARGETS` 得到可用目标列表，再按需转换
+//! `text/html`、`text/rtf`、`text/uri-list` 等目标，为 `formats` 模块提供与
+//! Windows 实现一致的数据来源。
+//!
+//! 写入同理：X11 没有"把数据放进剪贴板"的操作，写入方必须成为 selection 所有者，
+//! 并在粘贴方请求时提供数据。`own_selection` 为此启动一个后台所有者线程。
+//!
+//! # 实现思路
+//!
+//! - `X11Session`：RAII 持有 Display 连接与一个隐藏窗口（作为转换请求方 / 所有者），`Drop` 时释放
+//! - `convert`：`XConvertSelection` → 轮询等待 `SelectionNotify`（带超时，所有者无响应时不阻塞）
+//!   → `XGetWindowProperty` 读取并删除属性；支持 `INCR` 分块传输
+//! - `own_selection`：抢占 selection 后循环响应 `SelectionRequest`，收到 `SelectionClear`
+//!   （其他程序成为所有者）时退出线程
+//! - `parse_uri_list` / `parse_gnome_copied_files` / `build_file_targets`：纯函数，
+//!   解析与构造文件列表（可测试）
+//! - 无法连接 X Server（纯 Wayland、无 `DISPLAY`）时读取返回 `None`，写入返回错误
+
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_int, c_long, c_uchar, c_ulong};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use x11::xlib::{
+    self, Atom, Display, Window, XChangeProperty, XCheckTypedWindowEvent, XCloseDisplay,
+    XConvertSelection, XCreateSimpleWindow, XDefaultRootWindow, XDestroyWindow, XEvent, XFlush,
+    XFree, XGetAtomName, XGetSelectionOwner, XGetWindowProperty, XInternAtom, XNextEvent,
+    XOpenDisplay, XSelectInput, XSelectionRequestEvent, XSendEvent, XSetSelectionOwner,
+};
+
+use crate::error::AppError;
+use crate::i18n::message;
+
+// ============================================================================
+// 常量
+// ============================================================================
+
+/// 单次转换等待所有者响应的超时
+const CONVERT_TIMEOUT: Duration = Duration::from_millis(300);
+/// INCR 分块传输的总超时
+const INCR_TIMEOUT: Duration = Duration::from_secs(2);
+/// 单个目标允许读取的最大字节数（超出视为异常数据）
+const MAX_TARGET_BYTES: usize = 32 * 1024 * 1024;
+/// 等待事件时的轮询间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(2);
+
+/// 等待所有者线程完成抢占的超时
+const OWN_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// 转换结果写入的窗口属性名
+const TRANSFER_PROPERTY: &str = "CLIPBOARD_HISTORY_TRANSFER";
+
+/// GNOME / Nautilus 系文件管理器使用的文件列表目标
+pub const GNOME_COPIED_FILES: &str = "x-special/gnome-copied-files";
+/// freedesktop 标准文件列表目标（Dolphin、Thunar 等）
+pub const URI_LIST: &str = "text/uri-list";
+/// KDE 用于区分剪切 / 复制的目标（"1" 表示剪切）
+const KDE_CUT_SELECTION: &str = "application/x-kde-cutselection";
+/// 纯文本目标（文件列表的路径回退、PRIMARY / CLIPBOARD 文本同步）
+const PLAIN_TEXT_TARGETS: &[&str] = &["UTF8_STRING", "text/plain;charset=utf-8", "text/plain"];
+
+// ============================================================================
+// X11 会话
+// ============================================================================
+
+/// 一次剪贴板读取所用的 X11 连接
+///
+/// 持有独立的 Display 连接与 1×1 隐藏窗口，`Drop` 时销毁窗口并关闭连接。
+pub struct X11Session {
+    display: *mut Display,
+    window: Window,
+    property: Atom,
+    incr: Atom,
+}
+
+/// selection 转换得到的原始属性数据
+#[derive(Debug, Clone)]
+struct PropertyData {
+    type_: Atom,
+    format: c_int,
+    bytes: Vec<u8>,
+}
+
+impl X11Session {
+    /// 连接 X Server 并创建隐藏窗口
+    ///
+    /// 无 `DISPLAY` 或连接失败（如纯 Wayland 会话）时返回 `None`。
+    pub fn open() -> Option<Self> {
+        let display = unsafe { XOpenDisplay(std::ptr::null()) };
+        if display.is_null() {
+            log::debug!("{}", message("clipboard_log.x11_unavailable", &[]));
+            return None;
+        }
+
+        let window = unsafe {
+            let root = XDefaultRootWindow(display);
+            XCreateSimpleWindow(display, root, 0, 0, 1, 1, 0, 0, 0)
+        };
+        // 订阅属性变化，INCR 传输依赖 PropertyNotify
+        unsafe { XSelectInput(display, window, xlib::PropertyChangeMask) };
+
+        let mut session = Self { display, window, property: 0, incr: 0 };
+        session.property = session.atom(TRANSFER_PROPERTY);
+        session.incr = session.atom("INCR");
+        Some(session)
+    }
+
+    /// 获取（必要时创建）指定名称的 Atom
+    pub fn atom(&self, name: &str) -> Atom {
+        let Ok(c_name) = CString::new(name) else {
+            return 0;
+        };
+        unsafe { XInternAtom(self.display, c_name.as_ptr(), xlib::False) }
+    }
+
+    /// 获取 Atom 对应的名称
+    pub fn atom_name(&self, atom: Atom) -> Option<String> {
+        if atom == 0 {
+            return None;
+        }
+        unsafe {
+            let ptr = XGetAtomName(self.display, atom);
+            if ptr.is_null() {
+                return None;
+            }
+            let name = CStr::from_ptr(ptr).to_string_lossy().into_owned();
+            XFree(ptr.cast());
+            Some(name)
+        }
+    }
+
+    /// 枚举 selection 当前提供的全部目标名称
+    ///
+    /// 所有者不存在或不响应时返回空列表。
+    pub fn targets(&self, selection: &str) -> Vec<String> {
+        let Some(data) = self.convert(selection, "TARGETS") else {
+            return Vec::new();
+        };
+        if data.format != 32 {
+            log::debug!(
+                "{}",
+                message("clipboard_log.x11_targets_bad_format", &[("format", &data.format)])
+            );
+            return Vec::new();
+        }
+
+        // Xlib 对 32 位格式的属性按 C long 存放
+        let mut names: Vec<String> = data
+            .bytes
+            .chunks_exact(std::mem::size_of::<c_ulong>())
+            .filter_map(|chunk| {
+                let atom = c_ulong::from_ne_bytes(chunk.try_into().ok()?);
+                self.atom_name(atom)
+            })
+            .collect();
+        names.dedup();
+        names
+    }
+
+    /// 读取 selection 指定目标的原始字节
+    pub fn read(&self, selection: &str, target: &str) -> Option<Vec<u8>> {
+        self.convert(selection, target).map(|data| data.bytes)
+    }
+
+    /// 读取 selection 的 UTF-8 文本（依次尝试 `UTF8_STRING` 与 `text/plain;charset=utf-8`）
+    pub fn read_text(&self, selection: &str) -> Option<String> {
+        PLAIN_TEXT_TARGETS[..2].iter().find_map(|target| {
+            let bytes = self.read(selection, target)?;
+            Some(String::from_utf8_lossy(&bytes).trim_end_matches('\0').to_string())
+        })
+    }
+
+    /// 请求所有者把 selection 转换为指定目标，并读取结果
+    fn convert(&self, selection: &str, target: &str) -> Option<PropertyData> {
+        let selection_atom = self.atom(selection);
+        let target_atom = self.atom(target);
+
+        // 丢弃上一次转换残留的属性通知，避免干扰 INCR 读取
+        let mut stale: XEvent = unsafe { std::mem::zeroed() };
+        while unsafe { XCheckTypedWindowEvent(self.display, self.window, xlib::PropertyNotify, &mut stale) } != 0 {}
+
+        unsafe {
+            XConvertSelection(
+                self.display,
+                selection_atom,
+                target_atom,
+                self.property,
+                self.window,
+                xlib::CurrentTime,
+            );
+            XFlush(self.display);
+        }
+
+        let event = self.wait_event(xlib::SelectionNotify, CONVERT_TIMEOUT, |event| {
+            let selection_event = unsafe { event.selection };
+            selection_event.selection == selection_atom
+        });
+        let Some(event) = event else {
+            log::debug!(
+                "{}",
+                message(
+                    "clipboard_log.x11_target_timeout",
+                    &[
+                        ("selection", &selection),
+                        ("target", &target),
+                    ],
+                )
+            );
+            return None;
+        };
+
+        // property 为 None 表示所有者拒绝转换
+        if unsafe { event.selection }.property == 0 {
+            return None;
+        }
+
+        let data = self.take_property()?;
+        if data.type_ == self.incr {
+            return self.read_incr();
+        }
+        Some(data)
+    }
+
+    /// 按 INCR 协议逐块读取大数据
+    ///
+    /// 读取（并删除）初始 INCR 属性后，所有者每写入一块就触发一次
+    /// `PropertyNewValue`，长度为 0 的块表示传输结束。
+    fn read_incr(&self) -> Option<PropertyData> {
+        let deadline = Instant::now() + INCR_TIMEOUT;
+        let mut result: Option<PropertyData> = None;
+
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            self.wait_event(xlib::PropertyNotify, remaining, |event| {
+                let property_event = unsafe { event.property };
+                property_event.atom == self.property && property_event.state == xlib::PropertyNewValue
+            })?;
+
+            let chunk = self.take_property()?;
+            // 属性已被删除（残留的旧通知），继续等待下一块
+            if chunk.type_ == 0 {
+                continue;
+            }
+            if chunk.bytes.is_empty() {
+                return result;
+            }
+
+            match result.as_mut() {
+                Some(data) => {
+                    if data.bytes.len() + chunk.bytes.len() > MAX_TARGET_BYTES {
+                        log::warn!(
+                            "{}",
+                            message(
+                                "clipboard_log.x11_incr_too_large",
+                                &[("limit", &MAX_TARGET_BYTES)],
+                            )
+                        );
+                        return None;
+                    }
+                    data.bytes.extend_from_slice(&chunk.bytes);
+                }
+                None => result = Some(chunk),
+            }
+        }
+    }
+
+    /// 读取并删除传输属性
+    fn take_property(&self) -> Option<PropertyData> {
+        let mut type_: Atom = 0;
+        let mut format: c_int = 0;
+        let mut nitems: c_ulong = 0;
+        let mut bytes_after: c_ulong = 0;
+        let mut data: *mut c_uchar = std::ptr::null_mut();
+
+        let status = unsafe {
+            XGetWindowProperty(
+                self.display,
+                self.window,
+                self.property,
+                0,
+                (MAX_TARGET_BYTES / 4) as c_long,
+                xlib::True,
+                xlib::AnyPropertyType as Atom,
+                &mut type_,
+                &mut format,
+                &mut nitems,
+                &mut bytes_after,
+                &mut data,
+            )
+        };
+        if status != xlib::Success as c_int {
+            return None;
+        }
+
+        // Xlib 在客户端内存中按 char / short / long 存放 8 / 16 / 32 位数据
+        let unit = match format {
+            8 => 1,
+            16 => std::mem::size_of::<std::os::raw::c_short>(),
+            32 => std::mem::size_of::<c_long>(),
+            _ => 0,
+        };
+        let bytes = if data.is_null() || unit == 0 {
+            Vec::new()
+        } else {
+            unsafe { std::slice::from_raw_parts(data, nitems as usize * unit) }.to_vec()
+        };
+        if !data.is_null() {
+            unsafe { XFree(data.cast()) };
+        }
+
+        if bytes_after > 0 {
+            log::warn!(
+                "{}",
+                message("clipboard_log.x11_data_too_large", &[("limit", &MAX_TARGET_BYTES)])
+            );
+            return None;
+        }
+        Some(PropertyData { type_, format, bytes })
+    }
+
+    /// 响应 selection 请求，直到失去所有权
+    fn serve(&self, selection: Atom, served: &[(Atom, Vec<u8>)]) {
+        let targets_atom = self.atom("TARGETS");
+        let mut target_list: Vec<c_ulong> = vec![targets_atom];
+        target_list.extend(served.iter().map(|(atom, _)| *atom));
+
+        let mut event: XEvent = unsafe { std::mem::zeroed() };
+        loop {
+            unsafe { XNextEvent(self.display, &mut event) };
+            match event.get_type() {
+                xlib::SelectionClear if unsafe { event.selection_clear }.selection == selection => {
+                    log::debug!("{}", message("clipboard_log.x11_ownership_lost", &[]));
+                    return;
+                }
+                xlib::SelectionRequest => {
+                    let request = unsafe { event.selection_request };
+                    self.answer_request(&request, targets_atom, &target_list, served);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// 将请求的目标写入请求方窗口属性，并回复 `SelectionNotify`
+    fn answer_request(
+        &self,
+        request: &XSelectionRequestEvent,
+        targets_atom: Atom,
+        target_list: &[c_ulong],
+        served: &[(Atom, Vec<u8>)],
+    ) {
+        // ICCCM：property 为 None 的旧式请求方以 target 作为属性名
+        let property = if request.property == 0 { request.target } else { request.property };
+
+        let reply_property = unsafe {
+            if request.target == targets_atom {
+                XChangeProperty(
+                    self.display,
+                    request.requestor,
+                    property,
+                    xlib::XA_ATOM,
+                    32,
+                    xlib::PropModeReplace,
+                    target_list.as_ptr().cast(),
+                    target_list.len() as c_int,
+                );
+                property
+            } else if let Some((_, data)) = served.iter().find(|(atom, _)| *atom == request.target) {
+                XChangeProperty(
+                    self.display,
+                    request.requestor,
+                    property,
+                    request.target,
+                    8,
+                    xlib::PropModeReplace,
+                    data.as_ptr(),
+                    data.len() as c_int,
+                );
+                property
+            } else {
+                // 不支持的目标：以 property = None 拒绝
+                0
+            }
+        };
+
+        let mut reply: XEvent = unsafe { std::mem::zeroed() };
+        reply.selection = xlib::XSelectionEvent {
+            type_: xlib::SelectionNotify,
+            serial: 0,
+            send_event: xlib::True,
+            display: self.display,
+            requestor: request.requestor,
+            selection: request.selection,
+            target: request.target,
+            property: reply_property,
+            time: request.time,
+        };
+        unsafe {
+            XSendEvent(self.display, request.requestor, xlib::False, 0, &mut reply);
+            XFlush(self.display);
+        }
+    }
+
+    /// 轮询等待发往隐藏窗口的指定类型事件
+    fn wait_event(
+        &self,
+        event_type: c_int,
+        timeout: Duration,
+        mut accept: impl FnMut(&XEvent) -> bool,
+    ) -> Option<XEvent> {
+        let deadline = Instant::now() + timeout;
+        let mut event: XEvent = unsafe { std::mem::zeroed() };
+        loop {
+            while unsafe { XCheckTypedWindowEvent(self.display, self.window, event_type, &mut event) } != 0 {
+                if accept(&event) {
+                    return Some(event);
+                }
+            }
+            if Instant::now() >= deadline {
+                return None;
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+impl Drop for X11Session {
+    fn drop(&mut self) {
+        unsafe {
+            XDestroyWindow(self.display, self.window);
+            XCloseDisplay(self.display);
+        }
+    }
+}
+
+// ============================================================================
+// selection 所有权
+// ============================================================================
+
+/// 抢占 selection，并在后台线程中按请求提供给定目标
+///
+/// 数据始终保存在所有者进程中，因此每次写入都会启动一个所有者线程；
+/// 其他程序抢占 selection（收到 `SelectionClear`）后线程自动退出。
+/// `contents` 为 `(目标名称, 数据)` 列表，`TARGETS` 会自动提供。
+///
+/// # 错误
+/// 无法连接 X Server 或未能获得所有权时返回 `AppError::Clipboard`
+pub fn own_selection(selection: &'static str, contents: Vec<(String, Vec<u8>)>) -> Result<(), AppError> {
+    let (ready_tx, ready_rx) = mpsc::channel::<Result<(), String>>();
+
+    std::thread::Builder::new()
+        .name(format!("x11-{}-owner", selection.to_ascii_lowercase()))
+        .spawn(move || {
+            let Some(session) = X11Session::open() else {
+                let _ = ready_tx.send(Err(message("clipboard.x11_connect_failed", &[])));
+                return;
+            };
+            let selection_atom = session.atom(selection);
+            let served: Vec<(Atom, Vec<u8>)> = contents
+                .into_iter()
+                .map(|(name, data)| (session.atom(&name), data))
+                .collect();
+
+            let owner = unsafe {
+                XSetSelectionOwner(session.display, selection_atom, session.window, xlib::CurrentTime);
+                XGetSelectionOwner(session.display, selection_atom)
+            };
+            if owner != session.window {
+                let _ = ready_tx.send(Err(message("clipboard.x11_ownership_failed", &[("selection", &selection)])));
+                return;
+            }
+            let _ = ready_tx.send(Ok(()));
+            session.serve(selection_atom, &served);
+        })
+        .map_err(|e| AppError::Clipboard(message("clipboard.x11_owner_thread_failed", &[("detail", &e)])))?;
+
+    match ready_rx.recv_timeout(OWN_TIMEOUT) {
+        Ok(result) => result.map_err(AppError::Clipboard),
+        Err(_) => Err(AppError::Clipboard(message("clipboard.x11_ownership_timeout", &[("selection", &selection)]))),
+    }
+}
+
+// ============================================================================
+// 文件列表
+// ============================================================================
+
+/// 构造纯文本对应的 selection 目标
+pub fn build_text_targets(text: &str) -> Vec<(String, Vec<u8>)> {
+    PLAIN_TEXT_TARGETS
+        .iter()
+        .map(|target| (target.to_string(), text.as_bytes().to_vec()))
+        .collect()
+}
+
+/// 将文件列表写入 CLIPBOARD
+///
+/// 同时提供 `x-special/gnome-copied-files`（Nautilus、Thunar 等）、`text/uri-list`
+/// （Dolphin 等）与纯文本路径回退；`cut` 为 `true` 时文件管理器粘贴后会移动文件。
+pub fn copy_files_to_clipboard(paths: &[String], cut: bool) -> Result<(), AppError> {
+    own_selection("CLIPBOARD", build_file_targets(paths, cut))
+}
+
+/// 构造文件列表对应的全部 selection 目标
+pub fn build_file_targets(paths: &[String], cut: bool) -> Vec<(String, Vec<u8>)> {
+    let uris: Vec<String> = paths.iter().map(|path| path_to_file_uri(path)).collect();
+    let operation = if cut { "cut" } else { "copy" };
+
+    let gnome = std::iter::once(operation.to_string())
+        .chain(uris.iter().cloned())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let uri_list: String = uris.iter().map(|uri| format!("{}\r\n", uri)).collect();
+    let mut targets = vec![
+        (GNOME_COPIED_FILES.to_string(), gnome.into_bytes()),
+        (URI_LIST.to_string(), uri_list.into_bytes()),
+        (KDE_CUT_SELECTION.to_string(), if cut { b"1".to_vec() } else { b"0".to_vec() }),
+    ];
+    targets.extend(build_text_targets(&paths.join("\n")));
+    targets
+}
+
+/// 将本地路径编码为 `file://` URI（保留 `/` 与 RFC 3986 非保留字符）
+fn path_to_file_uri(path: &str) -> String {
+    let mut uri = String::from("file://");
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'/' | b'-' | b'.' | b'_' | b'~') {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    uri
+}
+
+/// 读取 CLIPBOARD 中的文件列表
+///
+/// 优先使用 `x-special/gnome-copied-files`（携带复制/剪切语义），
+/// 其次使用 `text/uri-list`；只保留 `file://` 本地路径。
+pub fn read_clipboard_file_list() -> Option<Vec<String>> {
+    let session = X11Session::open()?;
+    let targets = session.targets("CLIPBOARD");
+    read_file_list(&session, "CLIPBOARD", &targets)
+}
+
+/// 在已枚举目标的会话上读取文件列表
+pub fn read_file_list(session: &X11Session, selection: &str, targets: &[String]) -> Option<Vec<String>> {
+    let has = |name: &str| targets.iter().any(|t| t == name);
+
+    let files = if has(GNOME_COPIED_FILES) {
+        session
+            .read(selection, GNOME_COPIED_FILES)
+            .map(|bytes| parse_gnome_copied_files(&bytes).1)
+    } else {
+        None
+    };
+    let files = match files {
+        Some(files) if !files.is_empty() => files,
+        _ if has(URI_LIST) => parse_uri_list(&session.read(selection, URI_LIST)?),
+        _ => return None,
+    };
+
+    (!files.is_empty()).then_some(files)
+}
+
+/// 解析 `text/uri-list`（RFC 2483）
+///
+/// 忽略空行与 `#` 注释行，仅返回 `file://` URI 对应的本地路径。
+pub fn parse_uri_list(bytes: &[u8]) -> Vec<String> {
+    String::from_utf8_lossy(bytes)
+        .lines()
+        .map(|line| line.trim_matches(|c: char| c.is_whitespace() || c == '\0'))
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(file_uri_to_path)
+        .collect()
+}
+
+/// 解析 `x-special/gnome-copied-files`
+///
+/// 格式为首行 `copy` / `cut`，其后每行一个 URI。返回 `(是否为剪切, 路径列表)`。
+pub fn parse_gnome_copied_files(bytes: &[u8]) -> (bool, Vec<String>) {
+    let text = String::from_utf8_lossy(bytes);
+    let mut lines = text.lines();
+    let is_cut = lines.next().map(str::trim) == Some("cut");
+    let rest: Vec<&str> = lines.collect();
+    (is_cut, parse_uri_list(rest.join("\n").as_bytes()))
+}
+
+/// 将 `file://` URI 转换为本地路径（百分号解码）
+///
+/// 支持 `file:///path` 与 `file://host/path` 两种形式，其它 scheme 返回 `None`。
+fn file_uri_to_path(uri: &str) -> Option<String> {
+    let rest = uri.strip_prefix("file://")?;
+    let path = &rest[rest.find('/')?..];
+    let decoded = percent_decode(path)?;
+    Some(String::from_utf8_lossy(&decoded).into_owned())
+}
+
+fn percent_decode(input: &str) -> Option<Vec<u8>> {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = input.get(i + 1..i + 3)?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    Some(out)
+}
+
+// ============================================================================
+// 文本解码
+// ============================================================================
+
+/// 解码 selection 中的 HTML 文本
+///
+/// Firefox 以带 BOM 的 UTF-16 提供 `text/html`，Chromium 则在开头附加
+/// `<meta charset='utf-8'>`；此处统一转为去除上述包装的 UTF-8 字符串。
+pub fn decode_html(bytes: &[u8]) -> String {
+    let text = match bytes {
+        [0xFF, 0xFE, rest @ ..] => decode_utf16(rest, u16::from_le_bytes),
+        [0xFE, 0xFF, rest @ ..] => decode_utf16(rest, u16::from_be_bytes),
+        _ => String::from_utf8_lossy(bytes).into_owned(),
+    };
+    let text = text.trim_end_matches('\0');
+
+    let trimmed = text.trim_start();
+    let meta_end = trimmed
+        .get(..5)
+        .filter(|tag| tag.eq_ignore_ascii_case("<meta"))
+        .and_then(|_| trimmed.find('>'));
+    match meta_end {
+        Some(end) => trimmed[end + 1..].to_string(),
+        None => text.to_string(),
+    }
+}
+
+fn decode_utf16(bytes: &[u8], from_bytes: fn([u8; 2]) -> u16) -> String {
+    let units: Vec<u16> = bytes.chunks_exact(2).map(|c| from_bytes([c[0], c[1]])).collect();
+    String::from_utf16_lossy(&units)
+}
+
+#[cfg(test)]
+#[path = "tests/x11_selection_tests.rs"]
+mod tests;
@@ -0,0 +1,76 @@
+//! WCAG 2.x 相对亮度与对比度
+//!
+//! 前景色带透明度时先按 alpha 合成到背景上再计算，背景色的透明度被忽略。
+
+use serde::Serialize;
+
+use super::{format_color, Color, ColorFormat};
+
+/// 两种颜色的对比度及 WCAG 等级判定
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ContrastReport {
+    /// 前景色（`#rrggbb` 或 `#rrggbbaa`）
+    pub foreground: String,
+    /// 背景色（`#rrggbb`）
+    pub background: String,
+    /// 对比度，1 ~ 21，保留两位小数
+    pub ratio: f64,
+    /// 正文 AA（≥ 4.5）
+    pub aa_normal: bool,
+    /// 大字号 AA（≥ 3）
+    pub aa_large: bool,
+    /// 正文 AAA（≥ 7）
+    pub aaa_normal: bool,
+    /// 大字号 AAA（≥ 4.5）
+    pub aaa_large: bool,
+}
+
+fn channel_luminance(v: f64) -> f64 {
+    if v <= 0.04045 {
+        v / 12.92
+    } else {
+        ((v + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// WCAG 相对亮度（0 ~ 1），忽略 alpha
+pub fn relative_luminance(color: Color) -> f64 {
+    let c = color.clamped();
+    0.2126 * channel_luminance(c.r) + 0.7152 * channel_luminance(c.g) + 0.0722 * channel_luminance(c.b)
+}
+
+fn composite(foreground: Color, background: Color) -> Color {
+    let a = foreground.alpha.clamp(0.0, 1.0);
+    let mix = |f: f64, b: f64| f * a + b * (1.0 - a);
+    Color {
+        r: mix(foreground.r, background.r),
+        g: mix(foreground.g, background.g),
+        b: mix(foreground.b, background.b),
+        alpha: 1.0,
+    }
+}
+
+/// 前景色相对背景色的对比度（1 ~ 21）
+pub fn contrast_ratio(foreground: Color, background: Color) -> f64 {
+    let background = Color { alpha: 1.0, ..background.clamped() };
+    let foreground = composite(foreground.clamped(), background);
+    let (l1, l2) = (relative_luminance(foreground), relative_luminance(background));
+    (l1.max(l2) + 0.05) / (l1.min(l2) + 0.05)
+}
+
+/// 计算对比度并给出 WCAG 等级判定
+pub fn contrast_report(foreground: Color, background: Color) -> ContrastReport {
+    let ratio = contrast_ratio(foreground, background);
+    // 先截断到两位小数再判定，避免 4.499 被显示为 4.5 却判为不通过；
+    // 加一个极小量吸收浮点误差（如 20.9999…）
+    let ratio = (ratio * 100.0 + 1e-9).floor() / 100.0;
+    ContrastReport {
+        foreground: format_color(foreground, ColorFormat::Hex),
+        background: format_color(Color { alpha: 1.0, ..background }, ColorFormat::Hex),
+        ratio,
+        aa_normal: ratio >= 4.5,
+        aa_large: ratio >= 3.0,
+        aaa_normal: ratio >= 7.0,
+        aaa_large: ratio >= 4.5,
+    }
+}
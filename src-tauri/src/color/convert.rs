@@ -0,0 +1,201 @@
+//! 色彩空间换算与格式输出
+//!
+//! - HSL / HWB：CSS Color 4 定义的 sRGB 变换
+//! - OKLCH：经线性 sRGB → LMS → OKLab（Björn Ottosson 矩阵）
+//! - CMYK：不带 ICC 配置的朴素换算，仅用于展示和互转
+
+use super::{Color, ColorFormat};
+
+// ── sRGB ↔ HSL / HWB ────────────────────────────────────────
+
+pub(crate) fn hsl_to_rgb(h: f64, s: f64, l: f64, alpha: f64) -> Color {
+    let s = s.clamp(0.0, 1.0);
+    let l = l.clamp(0.0, 1.0);
+    let f = |n: f64| {
+        let k = (n + h / 30.0).rem_euclid(12.0);
+        let a = s * l.min(1.0 - l);
+        l - a * (k - 3.0).min(9.0 - k).clamp(-1.0, 1.0)
+    };
+    Color { r: f(0.0), g: f(8.0), b: f(4.0), alpha }
+}
+
+pub(crate) fn hwb_to_rgb(h: f64, w: f64, b: f64, alpha: f64) -> Color {
+    let (w, b) = (w.clamp(0.0, 1.0), b.clamp(0.0, 1.0));
+    if w + b >= 1.0 {
+        let gray = w / (w + b);
+        return Color { r: gray, g: gray, b: gray, alpha };
+    }
+    let base = hsl_to_rgb(h, 1.0, 0.5, alpha);
+    let scale = |v: f64| v * (1.0 - w - b) + w;
+    Color { r: scale(base.r), g: scale(base.g), b: scale(base.b), alpha }
+}
+
+/// 返回 (色相°, 饱和度 0~1, 亮度 0~1)
+fn rgb_to_hsl(c: Color) -> (f64, f64, f64) {
+    let max = c.r.max(c.g).max(c.b);
+    let min = c.r.min(c.g).min(c.b);
+    let l = (max + min) / 2.0;
+    let d = max - min;
+    if d == 0.0 {
+        return (0.0, 0.0, l);
+    }
+    let s = d / (1.0 - (2.0 * l - 1.0).abs());
+    (hue(c, max, d), s, l)
+}
+
+/// 返回 (色相°, 白度 0~1, 黑度 0~1)
+fn rgb_to_hwb(c: Color) -> (f64, f64, f64) {
+    let max = c.r.max(c.g).max(c.b);
+    let min = c.r.min(c.g).min(c.b);
+    let d = max - min;
+    let h = if d == 0.0 { 0.0 } else { hue(c, max, d) };
+    (h, min, 1.0 - max)
+}
+
+fn hue(c: Color, max: f64, d: f64) -> f64 {
+    let h = if max == c.r {
+        (c.g - c.b) / d
+    } else if max == c.g {
+        (c.b - c.r) / d + 2.0
+    } else {
+        (c.r - c.g) / d + 4.0
+    };
+    (h * 60.0).rem_euclid(360.0)
+}
+
+// ── sRGB ↔ OKLCH ────────────────────────────────────────────
+
+fn to_linear(v: f64) -> f64 {
+    if v.abs() <= 0.04045 {
+        v / 12.92
+    } else {
+        v.signum() * ((v.abs() + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn to_gamma(v: f64) -> f64 {
+    if v.abs() <= 0.003_130_8 {
+        v * 12.92
+    } else {
+        v.signum() * (1.055 * v.abs().powf(1.0 / 2.4) - 0.055)
+    }
+}
+
+pub(crate) fn oklch_to_rgb(l: f64, c: f64, h: f64, alpha: f64) -> Color {
+    let c = c.max(0.0);
+    let (a, b) = (c * h.to_radians().cos(), c * h.to_radians().sin());
+
+    let l_ = (l + 0.396_337_777_4 * a + 0.215_803_757_3 * b).powi(3);
+    let m_ = (l - 0.105_561_345_8 * a - 0.063_854_172_8 * b).powi(3);
+    let s_ = (l - 0.089_484_177_5 * a - 1.291_485_548_0 * b).powi(3);
+
+    let r = 4.076_741_662_1 * l_ - 3.307_711_591_3 * m_ + 0.230_969_929_2 * s_;
+    let g = -1.268_438_004_6 * l_ + 2.609_757_401_1 * m_ - 0.341_319_396_5 * s_;
+    let b = -0.004_196_086_3 * l_ - 0.703_418_614_7 * m_ + 1.707_614_701_0 * s_;
+    Color { r: to_gamma(r), g: to_gamma(g), b: to_gamma(b), alpha }
+}
+
+/// 返回 (亮度 0~1, 色度, 色相°)
+fn rgb_to_oklch(c: Color) -> (f64, f64, f64) {
+    let (r, g, b) = (to_linear(c.r), to_linear(c.g), to_linear(c.b));
+    let l_ = (0.412_221_470_8 * r + 0.536_332_536_3 * g + 0.051_445_992_9 * b).cbrt();
+    let m_ = (0.211_903_498_2 * r + 0.680_699_545_1 * g + 0.107_396_956_6 * b).cbrt();
+    let s_ = (0.088_302_461_9 * r + 0.281_718_837_6 * g + 0.629_978_700_5 * b).cbrt();
+
+    let l = 0.210_454_255_3 * l_ + 0.793_617_785_0 * m_ - 0.004_072_046_8 * s_;
+    let a = 1.977_998_495_1 * l_ - 2.428_592_205_0 * m_ + 0.450_593_709_9 * s_;
+    let b = 0.025_904_037_1 * l_ + 0.782_771_766_2 * m_ - 0.808_675_766_0 * s_;
+
+    let chroma = a.hypot(b);
+    // 灰色的色相无意义，固定为 0 避免输出浮点噪声
+    let h = if chroma < 1e-4 { 0.0 } else { b.atan2(a).to_degrees().rem_euclid(360.0) };
+    (l, chroma, h)
+}
+
+// ── sRGB ↔ CMYK ─────────────────────────────────────────────
+
+pub(crate) fn cmyk_to_rgb(c: f64, m: f64, y: f64, k: f64, alpha: f64) -> Color {
+    Color {
+        r: (1.0 - c) * (1.0 - k),
+        g: (1.0 - m) * (1.0 - k),
+        b: (1.0 - y) * (1.0 - k),
+        alpha,
+    }
+}
+
+fn rgb_to_cmyk(c: Color) -> [f64; 4] {
+    let k = 1.0 - c.r.max(c.g).max(c.b);
+    if k >= 1.0 {
+        return [0.0, 0.0, 0.0, 1.0];
+    }
+    let ink = |v: f64| (1.0 - v - k) / (1.0 - k);
+    [ink(c.r), ink(c.g), ink(c.b), k]
+}
+
+// ── 格式输出 ─────────────────────────────────────────────────
+
+/// 按指定小数位数输出，并去掉末尾多余的 0
+fn num(value: f64, decimals: usize) -> String {
+    let s = format!("{:.*}", decimals, value);
+    let s = if s.contains('.') { s.trim_end_matches('0').trim_end_matches('.') } else { &s };
+    if s == "-0" { "0".to_string() } else { s.to_string() }
+}
+
+fn pct(value: f64) -> String {
+    format!("{}%", num(value * 100.0, 1))
+}
+
+/// 现代语法的 ` / a` 后缀；不透明时为空
+fn slash_alpha(alpha: f64) -> String {
+    if alpha < 1.0 {
+        format!(" / {}", num(alpha, 3))
+    } else {
+        String::new()
+    }
+}
+
+/// 将颜色输出为目标格式的 CSS 文本
+pub fn format_color(color: Color, format: ColorFormat) -> String {
+    let color = color.clamped();
+    let opaque = color.alpha >= 1.0;
+    match format {
+        ColorFormat::Hex => {
+            let [r, g, b] = color.to_rgb8();
+            if opaque {
+                format!("#{:02x}{:02x}{:02x}", r, g, b)
+            } else {
+                let a = (color.alpha * 255.0).round() as u8;
+                format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a)
+            }
+        }
+        ColorFormat::Rgb => {
+            let [r, g, b] = color.to_rgb8();
+            if opaque {
+                format!("rgb({}, {}, {})", r, g, b)
+            } else {
+                format!("rgba({}, {}, {}, {})", r, g, b, num(color.alpha, 3))
+            }
+        }
+        ColorFormat::Hsl => {
+            let (h, s, l) = rgb_to_hsl(color);
+            let body = format!("{}, {}, {}", num(h, 1), pct(s), pct(l));
+            if opaque {
+                format!("hsl({})", body)
+            } else {
+                format!("hsla({}, {})", body, num(color.alpha, 3))
+            }
+        }
+        ColorFormat::Hwb => {
+            let (h, w, b) = rgb_to_hwb(color);
+            format!("hwb({} {} {}{})", num(h, 1), pct(w), pct(b), slash_alpha(color.alpha))
+        }
+        ColorFormat::Oklch => {
+            let (l, c, h) = rgb_to_oklch(color);
+            format!("oklch({} {} {}{})", pct(l), num(c, 4), num(h, 2), slash_alpha(color.alpha))
+        }
+        ColorFormat::Cmyk => {
+            let [c, m, y, k] = rgb_to_cmyk(color);
+            format!("cmyk({}, {}, {}, {})", pct(c), pct(m), pct(y), pct(k))
+        }
+    }
+}
@@ -0,0 +1,163 @@
+//! # 颜色值模块（color）
+//!
+//! ## 设计思路
+//!
+//! 设计同学日常复制的 `#1e90ff`、`rgb(30,144,255)`、`hsl(...)`、`oklch(...)`
+//! 在入库时被识别为颜色，解析结果以 `clip_formats` 中的 `color` 条目保存，
+//! 前端可直接展示色块、互转格式，并计算两条颜色记录的 WCAG 对比度。
+//!
+//! - `parse`：CSS 颜色字面量解析（hex / rgb / hsl / hwb / oklch / cmyk）
+//! - `convert`：色彩空间换算与各格式输出
+//! - `contrast`：相对亮度与 WCAG 对比度
+//!
+//! ## 实现思路
+//!
+//! - 内部统一使用 sRGB（gamma 编码，0~1 浮点）+ alpha 表示，所有格式经它中转
+//! - 捕获时只识别“整条内容就是一个颜色”的文本（允许 `color: ...;` 这类声明），
+//!   避免在长文本中误判
+//! - 超出 sRGB 色域的 oklch 值按通道裁剪
+//! - 不识别颜色名（`red` 等），避免普通单词被误判为颜色
+
+mod contrast;
+mod convert;
+mod parse;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+
+pub use contrast::{contrast_ratio, contrast_report, relative_luminance, ContrastReport};
+pub use convert::format_color;
+pub use parse::parse_color;
+
+/// `clip_formats` 中保存解析结果的格式名
+pub const COLOR_FORMAT_KEY: &str = "color";
+
+/// 捕获时参与识别的最大文本长度
+const MAX_DETECT_LEN: usize = 96;
+
+/// sRGB 颜色（gamma 编码，各通道 0~1）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Color {
+    pub r: f64,
+    pub g: f64,
+    pub b: f64,
+    pub alpha: f64,
+}
+
+impl Color {
+    pub fn from_rgb8(r: u8, g: u8, b: u8) -> Self {
+        Self {
+            r: f64::from(r) / 255.0,
+            g: f64::from(g) / 255.0,
+            b: f64::from(b) / 255.0,
+            alpha: 1.0,
+        }
+    }
+
+    pub(crate) fn clamped(self) -> Self {
+        Self {
+            r: self.r.clamp(0.0, 1.0),
+            g: self.g.clamp(0.0, 1.0),
+            b: self.b.clamp(0.0, 1.0),
+            alpha: self.alpha.clamp(0.0, 1.0),
+        }
+    }
+
+    pub fn to_rgb8(self) -> [u8; 3] {
+        let c = self.clamped();
+        let to8 = |v: f64| (v * 255.0).round() as u8;
+        [to8(c.r), to8(c.g), to8(c.b)]
+    }
+}
+
+/// 支持的颜色格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorFormat {
+    Hex,
+    Rgb,
+    Hsl,
+    Hwb,
+    Oklch,
+    Cmyk,
+}
+
+/// 入库时识别到的颜色（序列化后存入 `clip_formats.content`）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DetectedColor {
+    /// 原始文本使用的格式
+    pub source: ColorFormat,
+    /// `#rrggbb` 或 `#rrggbbaa`
+    pub hex: String,
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub alpha: f64,
+}
+
+impl DetectedColor {
+    pub fn color(&self) -> Color {
+        Color {
+            alpha: self.alpha,
+            ..Color::from_rgb8(self.r, self.g, self.b)
+        }
+    }
+}
+
+fn is_declaration_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// 去掉 `color: ...;` / `--brand: ...` 这类声明外壳，返回值部分
+fn strip_declaration(text: &str) -> &str {
+    let text = text.trim().trim_end_matches(';').trim_end();
+    match text.split_once(':') {
+        Some((name, value)) if is_declaration_name(name.trim()) => value.trim(),
+        _ => text,
+    }
+}
+
+/// 识别整条文本是否为一个颜色值
+pub fn detect_color(text: &str) -> Option<DetectedColor> {
+    let trimmed = text.trim();
+    if trimmed.is_empty() || trimmed.len() > MAX_DETECT_LEN || trimmed.contains('\n') {
+        return None;
+    }
+    let (color, source) = parse_color(strip_declaration(trimmed))?;
+    let [r, g, b] = color.to_rgb8();
+    Some(DetectedColor {
+        source,
+        hex: format_color(color, ColorFormat::Hex),
+        r,
+        g,
+        b,
+        alpha: (color.alpha.clamp(0.0, 1.0) * 1000.0).round() / 1000.0,
+    })
+}
+
+/// 将颜色参数解析为 8 位 RGB（忽略 alpha）
+///
+/// 供按颜色搜索、图片背景色与标注色等参数共用：接受全部 CSS 颜色字面量，
+/// 十六进制可省略 `#`。
+pub fn parse_rgb8(text: &str) -> Option<[u8; 3]> {
+    let text = text.trim();
+    let parsed = match parse_color(text) {
+        Some(parsed) => Some(parsed),
+        None if !text.starts_with('#') => parse_color(&format!("#{}", text)),
+        None => None,
+    };
+    parsed.map(|(color, _)| color.to_rgb8())
+}
+
+// ── Tauri Commands ───────────────────────────────────────────
+
+/// 将颜色文本转换为目标格式；文本不是颜色时返回 `None`
+#[tauri::command]
+pub fn convert_color(text: String, target_format: ColorFormat) -> Result<Option<String>, AppError> {
+    Ok(parse_color(strip_declaration(&text)).map(|(color, _)| format_color(color, target_format)))
+}
+
+#[cfg(test)]
+#[path = "tests/color_tests.rs"]
+mod tests;
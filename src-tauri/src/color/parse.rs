@@ -0,0 +1,187 @@
+//! CSS 颜色字面量解析
+//!
+//! 覆盖 CSS Color 4 的常用写法：
+//! - `#rgb` / `#rgba` / `#rrggbb` / `#rrggbbaa`
+//! - `rgb()` / `rgba()`：逗号或空格分隔，通道可为数字或百分比
+//! - `hsl()` / `hsla()` / `hwb()`：色相支持 `deg` / `turn` / `rad` / `grad`
+//! - `oklch()`：亮度支持数字（0~1）或百分比，色度 `100%` = 0.4
+//! - `cmyk()` / `device-cmyk()`：数字（0~1）或百分比
+//!
+//! alpha 可写为 `/ a` 或旧式第 4 个逗号参数，`none` 按 0 处理。
+
+use super::convert::{cmyk_to_rgb, hsl_to_rgb, hwb_to_rgb, oklch_to_rgb};
+use super::{Color, ColorFormat};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Unit {
+    None,
+    Percent,
+    Deg,
+    Rad,
+    Grad,
+    Turn,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Component {
+    value: f64,
+    unit: Unit,
+}
+
+impl Component {
+    /// 按 `percent_scale` 将百分比换算为数值，其余单位原样返回
+    fn scaled(self, percent_scale: f64) -> f64 {
+        match self.unit {
+            Unit::Percent => self.value / 100.0 * percent_scale,
+            _ => self.value,
+        }
+    }
+
+    fn hue_degrees(self) -> Option<f64> {
+        let deg = match self.unit {
+            Unit::None | Unit::Deg => self.value,
+            Unit::Rad => self.value.to_degrees(),
+            Unit::Grad => self.value * 0.9,
+            Unit::Turn => self.value * 360.0,
+            Unit::Percent => return None,
+        };
+        Some(deg.rem_euclid(360.0))
+    }
+
+    fn is_angle(self) -> bool {
+        matches!(self.unit, Unit::Deg | Unit::Rad | Unit::Grad | Unit::Turn)
+    }
+}
+
+fn parse_component(token: &str) -> Option<Component> {
+    let token = token.trim();
+    if token.eq_ignore_ascii_case("none") {
+        return Some(Component { value: 0.0, unit: Unit::None });
+    }
+    let lower = token.to_ascii_lowercase();
+    let (number, unit) = [
+        ("%", Unit::Percent),
+        ("deg", Unit::Deg),
+        ("grad", Unit::Grad),
+        ("rad", Unit::Rad),
+        ("turn", Unit::Turn),
+    ]
+    .iter()
+    .find_map(|(suffix, unit)| lower.strip_suffix(suffix).map(|n| (n.to_string(), *unit)))
+    .unwrap_or((lower, Unit::None));
+
+    // 拒绝 `inf` / `nan` 这类 f64 能解析但 CSS 不接受的写法
+    if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e')) {
+        return None;
+    }
+    let value: f64 = number.parse().ok()?;
+    value.is_finite().then_some(Component { value, unit })
+}
+
+fn parse_alpha(component: Component) -> Option<f64> {
+    match component.unit {
+        Unit::None | Unit::Percent => Some(component.scaled(1.0).clamp(0.0, 1.0)),
+        _ => None,
+    }
+}
+
+/// 拆分函数参数：返回 (主参数, 可选 alpha)
+fn split_arguments(inner: &str) -> Option<(Vec<Component>, Option<f64>)> {
+    let (main, slash_alpha) = match inner.split_once('/') {
+        Some((main, alpha)) => (main, Some(alpha)),
+        None => (inner, None),
+    };
+    let components = main
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|t| !t.is_empty())
+        .map(parse_component)
+        .collect::<Option<Vec<_>>>()?;
+
+    let alpha = match slash_alpha {
+        Some(alpha) => Some(parse_alpha(parse_component(alpha)?)?),
+        None => None,
+    };
+    Some((components, alpha))
+}
+
+fn parse_hex(hex: &str) -> Option<Color> {
+    if !hex.is_ascii() || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let nibble = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).ok().map(|v| v * 17);
+    let byte = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    let (r, g, b, a) = match hex.len() {
+        3 => (nibble(0)?, nibble(1)?, nibble(2)?, 255),
+        4 => (nibble(0)?, nibble(1)?, nibble(2)?, nibble(3)?),
+        6 => (byte(0)?, byte(2)?, byte(4)?, 255),
+        8 => (byte(0)?, byte(2)?, byte(4)?, byte(6)?),
+        _ => return None,
+    };
+    Some(Color {
+        alpha: f64::from(a) / 255.0,
+        ..Color::from_rgb8(r, g, b)
+    })
+}
+
+/// 主参数为 3 个（或旧式写法 4 个，第 4 个为 alpha）
+fn take_three(components: Vec<Component>, alpha: Option<f64>) -> Option<([Component; 3], f64)> {
+    match (components.as_slice(), alpha) {
+        ([a, b, c], alpha) => Some(([*a, *b, *c], alpha.unwrap_or(1.0))),
+        ([a, b, c, d], None) => Some(([*a, *b, *c], parse_alpha(*d)?)),
+        _ => None,
+    }
+}
+
+fn parse_function(name: &str, inner: &str) -> Option<(Color, ColorFormat)> {
+    let (components, alpha) = split_arguments(inner)?;
+    match name {
+        "rgb" | "rgba" => {
+            let ([r, g, b], alpha) = take_three(components, alpha)?;
+            if [r, g, b].iter().any(|c| c.is_angle()) {
+                return None;
+            }
+            let channel = |c: Component| c.scaled(255.0) / 255.0;
+            Some((Color { r: channel(r), g: channel(g), b: channel(b), alpha }, ColorFormat::Rgb))
+        }
+        "hsl" | "hsla" => {
+            let ([h, s, l], alpha) = take_three(components, alpha)?;
+            let color = hsl_to_rgb(h.hue_degrees()?, s.scaled(100.0) / 100.0, l.scaled(100.0) / 100.0, alpha);
+            Some((color, ColorFormat::Hsl))
+        }
+        "hwb" => {
+            let ([h, w, b], alpha) = take_three(components, alpha)?;
+            let color = hwb_to_rgb(h.hue_degrees()?, w.scaled(100.0) / 100.0, b.scaled(100.0) / 100.0, alpha);
+            Some((color, ColorFormat::Hwb))
+        }
+        "oklch" => {
+            let ([l, c, h], alpha) = take_three(components, alpha)?;
+            let color = oklch_to_rgb(l.scaled(1.0), c.scaled(0.4), h.hue_degrees()?, alpha);
+            Some((color, ColorFormat::Oklch))
+        }
+        "cmyk" | "device-cmyk" => {
+            let (cmyk, alpha) = match (components.as_slice(), alpha) {
+                ([c, m, y, k], alpha) => ([*c, *m, *y, *k], alpha.unwrap_or(1.0)),
+                _ => return None,
+            };
+            let [c, m, y, k] = cmyk.map(|v| v.scaled(1.0).clamp(0.0, 1.0));
+            Some((cmyk_to_rgb(c, m, y, k, alpha), ColorFormat::Cmyk))
+        }
+        _ => None,
+    }
+}
+
+/// 解析单个颜色字面量，返回颜色及其原始格式
+pub fn parse_color(text: &str) -> Option<(Color, ColorFormat)> {
+    let text = text.trim();
+    if let Some(hex) = text.strip_prefix('#') {
+        return parse_hex(hex).map(|color| (color, ColorFormat::Hex));
+    }
+
+    let open = text.find('(')?;
+    let inner = text[open + 1..].strip_suffix(')')?;
+    let name = text[..open].trim().to_ascii_lowercase();
+    if inner.contains(['(', ')']) {
+        return None;
+    }
+    parse_function(&name, inner).map(|(color, format)| (color.clamped(), format))
+}
@@ -0,0 +1,163 @@
+use super::*;
+
+fn rgb8(text: &str) -> [u8; 3] {
+    parse_color(text).expect("parse color").0.to_rgb8()
+}
+
+#[test]
+fn parses_hex_forms() {
+    assert_eq!(rgb8("#1e90ff"), [30, 144, 255]);
+    assert_eq!(rgb8("#1E90FF"), [30, 144, 255]);
+    assert_eq!(rgb8("#f0a"), [255, 0, 170]);
+
+    let (color, format) = parse_color("#1e90ff80").expect("8 digit hex");
+    assert_eq!(format, ColorFormat::Hex);
+    assert!((color.alpha - 128.0 / 255.0).abs() < 1e-9);
+    assert!((parse_color("#f0a8").unwrap().0.alpha - 136.0 / 255.0).abs() < 1e-9);
+
+    for invalid in ["1e90ff", "#1e90f", "#ggg", "#", "#1e90ff0"] {
+        assert!(parse_color(invalid).is_none(), "{}", invalid);
+    }
+}
+
+#[test]
+fn parse_rgb8_accepts_bare_hex_and_css_literals() {
+    assert_eq!(parse_rgb8("#ff8000"), Some([255, 128, 0]));
+    assert_eq!(parse_rgb8("F80"), Some([255, 136, 0]));
+    assert_eq!(parse_rgb8(" #abc "), Some([170, 187, 204]));
+    assert_eq!(parse_rgb8("rgb(30 144 255 / 50%)"), Some([30, 144, 255]));
+    assert_eq!(parse_rgb8("#12345"), None);
+    assert_eq!(parse_rgb8("#zzzzzz"), None);
+    assert_eq!(parse_rgb8("#ééé"), None);
+    assert_eq!(parse_rgb8("red"), None);
+}
+
+#[test]
+fn parses_functional_notations() {
+    assert_eq!(rgb8("rgb(30, 144, 255)"), [30, 144, 255]);
+    assert_eq!(rgb8("RGB(30 144 255)"), [30, 144, 255]);
+    assert_eq!(rgb8("rgb(100% 0% 50%)"), [255, 0, 128]);
+    assert_eq!(rgb8("hsl(210, 100%, 56%)"), [31, 143, 255]);
+    assert_eq!(rgb8("hsl(0.5turn 100% 50%)"), [0, 255, 255]);
+    assert_eq!(rgb8("hwb(120 0% 50%)"), [0, 128, 0]);
+    assert_eq!(rgb8("hwb(0 60% 60%)"), [128, 128, 128]);
+    assert_eq!(rgb8("oklch(62.8% 0.2577 29.23)"), [255, 0, 0]);
+    assert_eq!(rgb8("oklch(1 0 none)"), [255, 255, 255]);
+    assert_eq!(rgb8("cmyk(88%, 44%, 0%, 0%)"), [31, 143, 255]);
+    assert_eq!(rgb8("device-cmyk(0 0 0 1)"), [0, 0, 0]);
+
+    let (color, _) = parse_color("rgba(30, 144, 255, 0.5)").expect("legacy alpha");
+    assert_eq!(color.alpha, 0.5);
+    let (color, _) = parse_color("hsl(210deg 100% 56% / 25%)").expect("slash alpha");
+    assert_eq!(color.alpha, 0.25);
+
+    for invalid in ["rgb(1, 2)", "rgb(1deg, 2, 3)", "hsl(10%, 50%, 50%)", "rgb(inf, 0, 0)", "calc(1)", "rgb(1, 2, 3"] {
+        assert!(parse_color(invalid).is_none(), "{}", invalid);
+    }
+}
+
+#[test]
+fn formats_every_target() {
+    let color = Color::from_rgb8(30, 144, 255);
+    assert_eq!(format_color(color, ColorFormat::Hex), "#1e90ff");
+    assert_eq!(format_color(color, ColorFormat::Rgb), "rgb(30, 144, 255)");
+    assert_eq!(format_color(color, ColorFormat::Hsl), "hsl(209.6, 100%, 55.9%)");
+    assert_eq!(format_color(color, ColorFormat::Hwb), "hwb(209.6 11.8% 0%)");
+    assert_eq!(format_color(color, ColorFormat::Cmyk), "cmyk(88.2%, 43.5%, 0%, 0%)");
+    assert!(format_color(color, ColorFormat::Oklch).starts_with("oklch(65."));
+
+    let translucent = Color { alpha: 0.5, ..color };
+    assert_eq!(format_color(translucent, ColorFormat::Hex), "#1e90ff80");
+    assert_eq!(format_color(translucent, ColorFormat::Rgb), "rgba(30, 144, 255, 0.5)");
+    assert_eq!(format_color(translucent, ColorFormat::Hwb), "hwb(209.6 11.8% 0% / 0.5)");
+
+    let gray = Color::from_rgb8(128, 128, 128);
+    assert_eq!(format_color(gray, ColorFormat::Oklch), "oklch(60% 0 0)");
+}
+
+#[test]
+fn conversions_round_trip_through_every_format() {
+    let formats = [
+        ColorFormat::Hex,
+        ColorFormat::Rgb,
+        ColorFormat::Hsl,
+        ColorFormat::Hwb,
+        ColorFormat::Oklch,
+        ColorFormat::Cmyk,
+    ];
+    for rgb in [[30, 144, 255], [255, 0, 0], [12, 200, 90], [0, 0, 0], [255, 255, 255]] {
+        let color = Color::from_rgb8(rgb[0], rgb[1], rgb[2]);
+        for format in formats {
+            let text = format_color(color, format);
+            let (parsed, parsed_format) = parse_color(&text).expect(&text);
+            assert_eq!(parsed_format, format, "{}", text);
+            let back = parsed.to_rgb8();
+            for i in 0..3 {
+                assert!(back[i].abs_diff(rgb[i]) <= 1, "{} -> {:?}", text, back);
+            }
+        }
+    }
+}
+
+#[test]
+fn convert_color_command_accepts_declarations() {
+    assert_eq!(
+        convert_color("color: #1e90ff;".to_string(), ColorFormat::Rgb).unwrap().as_deref(),
+        Some("rgb(30, 144, 255)")
+    );
+    assert_eq!(convert_color("hello".to_string(), ColorFormat::Hex).unwrap(), None);
+}
+
+#[test]
+fn detect_color_only_matches_whole_values() {
+    let detected = detect_color("  --brand-blue: rgb(30 144 255 / 50%);  ").expect("declaration");
+    assert_eq!(detected.source, ColorFormat::Rgb);
+    assert_eq!(detected.hex, "#1e90ff80");
+    assert_eq!((detected.r, detected.g, detected.b), (30, 144, 255));
+    assert_eq!(detected.alpha, 0.5);
+
+    assert_eq!(detect_color("#FFF").map(|d| d.hex), Some("#ffffff".to_string()));
+    for text in [
+        "red",
+        "issue #123",
+        "the color is #1e90ff",
+        "#1e90ff\n#000000",
+        "a: b: #fff",
+        "fn rgb(r: u8, g: u8, b: u8)",
+    ] {
+        assert!(detect_color(text).is_none(), "{}", text);
+    }
+}
+
+#[test]
+fn contrast_matches_wcag_reference_values() {
+    let black = Color::from_rgb8(0, 0, 0);
+    let white = Color::from_rgb8(255, 255, 255);
+    assert!((contrast_ratio(black, white) - 21.0).abs() < 1e-9);
+    assert_eq!(contrast_ratio(white, black), contrast_ratio(black, white));
+    assert_eq!(relative_luminance(white), 1.0);
+
+    let report = contrast_report(Color::from_rgb8(0x76, 0x76, 0x76), white);
+    assert_eq!(report.ratio, 4.54);
+    assert!(report.aa_normal && report.aa_large && report.aaa_large);
+    assert!(!report.aaa_normal);
+
+    let report = contrast_report(Color::from_rgb8(0x77, 0x77, 0x77), white);
+    assert_eq!(report.ratio, 4.47);
+    assert!(!report.aa_normal && report.aa_large);
+
+    let report = contrast_report(black, white);
+    assert_eq!(report.ratio, 21.0);
+    assert_eq!((report.foreground.as_str(), report.background.as_str()), ("#000000", "#ffffff"));
+}
+
+#[test]
+fn translucent_foreground_is_composited_over_background() {
+    let white = Color::from_rgb8(255, 255, 255);
+    let half_black = Color { alpha: 0.5, ..Color::from_rgb8(0, 0, 0) };
+    let mid_gray = Color { r: 0.5, g: 0.5, b: 0.5, alpha: 1.0 };
+    assert!((contrast_ratio(half_black, white) - contrast_ratio(mid_gray, white)).abs() < 1e-9);
+
+    let invisible = Color { alpha: 0.0, ..Color::from_rgb8(0, 0, 0) };
+    assert!((contrast_ratio(invisible, white) - 1.0).abs() < 1e-9);
+}
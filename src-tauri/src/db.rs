@@ -20,13 +20,24 @@ use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, State};
 
 use crate::error::AppError;
+use crate::i18n::message;
+use crate::image_handler::ImageMetadata;
 
+mod assets;
 mod config;
 mod cleanup;
+mod color;
 mod history;
+mod image_download;
+mod image_edit;
+mod image_meta;
+mod link_preview;
+mod palette;
 mod schema;
+mod similarity;
 mod storage;
 mod tags;
+mod transforms;
 
 // ── 子模块共享 helper ──────────────────────────────────────
 
@@ -42,14 +53,33 @@ pub(crate) fn sql_placeholders(n: usize) -> String {
     s
 }
 
-/// 统一 `rusqlite` 错误到 `AppError::Database`
-pub(crate) fn db_err(context: &str, e: impl std::fmt::Display) -> AppError {
-    AppError::Database(format!("{}: {}", context, e))
+/// 统一 `rusqlite` 错误到 `AppError`（锁定 / 损坏 / 磁盘已满细分为专用变体）
+///
+/// `id` 为文案目录中的上下文消息 ID，按当前语言渲染后与底层错误拼接。
+pub(crate) fn db_err(id: &str, e: rusqlite::Error) -> AppError {
+    db_err_with(id, &[], e)
+}
+
+/// 同 `db_err`，上下文消息带参数
+pub(crate) fn db_err_with(id: &str, args: &[(&str, &dyn std::fmt::Display)], e: rusqlite::Error) -> AppError {
+    AppError::from_sqlite(&message(id, args), &e)
 }
 
+pub use color::*;
 pub use history::*;
+pub use image_download::*;
+pub use image_edit::*;
+pub use link_preview::*;
+pub use palette::*;
+pub use similarity::*;
 pub use storage::*;
 pub use tags::*;
+pub use transforms::*;
+
+pub(crate) use cleanup::AssetAnalysis;
+pub(crate) use history::{
+    add_selection_clip, auto_clear_selection_before, load_clip_text, load_formats, SELECTION_PRIMARY,
+};
 
 // ============================================================================
 // 数据模型
@@ -84,9 +114,21 @@ pub struct ClipItem {
     pub picked_color: Option<String>,
     /// 内容类型：text | image | files | rich
     pub content_type: String,
+    /// 捕获时识别出的编程语言（如 `rust`），非代码为 `None`
+    #[serde(default)]
+    pub language: Option<String>,
+    /// 语言识别置信度（0~1）
+    #[serde(default)]
+    pub language_confidence: Option<f64>,
+    /// 捕获时识别出的内容子类型（如 `url`、`json`），普通文本为 `None`
+    #[serde(default)]
+    pub content_subtype: Option<String>,
     /// 附加格式数据（HTML/RTF/图片路径等）
     #[serde(default)]
     pub formats: Vec<ClipFormat>,
+    /// 图片元数据（仅图片条目存在）
+    #[serde(default)]
+    pub image_meta: Option<ImageMetadata>,
 }
 
 /// 应用统计信息
@@ -110,14 +152,14 @@ pub struct DbState {
 
 pub(crate) fn with_conn_mut<T>(state: &State<'_, DbState>, op: impl FnOnce(&mut Connection) -> Result<T, AppError>) -> Result<T, AppError> {
     let mut conn = state.write_conn.lock().map_err(|e| {
-        AppError::Database(format!("获取数据库锁失败: {}", e))
+        AppError::Database(e.to_string())
     })?;
     op(&mut conn)
 }
 
 pub(crate) fn with_read_conn<T>(state: &State<'_, DbState>, op: impl FnOnce(&Connection) -> Result<T, AppError>) -> Result<T, AppError> {
     let conn = state.read_conn.lock().map_err(|e| {
-        AppError::Database(format!("获取数据库读锁失败: {}", e))
+        AppError::Database(e.to_string())
     })?;
     op(&conn)
 }
@@ -127,10 +169,10 @@ pub(crate) fn with_conn_pair_mut<T>(
     op: impl FnOnce(&mut Connection, &mut Connection) -> Result<T, AppError>,
 ) -> Result<T, AppError> {
     let mut write_conn = state.write_conn.lock().map_err(|e| {
-        AppError::Database(format!("获取数据库写锁失败: {}", e))
+        AppError::Database(e.to_string())
     })?;
     let mut read_conn = state.read_conn.lock().map_err(|e| {
-        AppError::Database(format!("获取数据库读锁失败: {}", e))
+        AppError::Database(e.to_string())
     })?;
     op(&mut write_conn, &mut read_conn)
 }
@@ -150,18 +192,13 @@ pub(crate) fn with_conn_pair_mut<T>(
 pub fn init_db(app: &AppHandle) -> Result<DbState, AppError> {
     let db_path = config::resolve_db_path(app)?;
     if let Some(parent) = db_path.parent() {
-        fs::create_dir_all(parent).map_err(|e| {
-            AppError::Database(format!("创建数据库目录失败: {}", e))
-        })?;
+        fs::create_dir_all(parent)
+            .map_err(|e| AppError::from_io(&message("db.create_dir", &[]), &e, AppError::Database))?;
     }
-    log::info!("数据库路径: {}", db_path.display());
+    log::info!("{}", message("db_log.path", &[("path", &db_path.display())]));
 
-    let write_conn = Connection::open(&db_path).map_err(|e| {
-        AppError::Database(format!("打开数据库失败: {}", e))
-    })?;
-    let read_conn = Connection::open(&db_path).map_err(|e| {
-        AppError::Database(format!("打开数据库读连接失败: {}", e))
-    })?;
+    let write_conn = Connection::open(&db_path).map_err(|e| db_err("db.open", e))?;
+    let read_conn = Connection::open(&db_path).map_err(|e| db_err("db.open_reader", e))?;
 
     schema::initialize_schema(&write_conn)?;
 
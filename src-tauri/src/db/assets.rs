@@ -0,0 +1,246 @@
+//! 内容寻址资源子模块
+//!
+//! ## 职责
+//! - 维护 `assets` 表：每个哈希对应一份磁盘文件及其元数据
+//! - 为 `history_assets` 映射解析资源哈希
+//! - 迁移时对旧版 `img_*` / `svg_*` 文件做内容去重
+//!
+//! ## 设计决策
+//!
+//! ### 引用计数
+//! `assets.refcount` 由 `history_assets` 上的触发器维护（见 `schema.rs`），
+//! 业务代码只需写入/删除映射行，无需手动增减计数。
+//!
+//! ### 哈希来源
+//! 新资源文件名本身就是 SHA-256，直接取文件名即可；
+//! 旧版时间戳命名的文件需要读取内容计算哈希。
+//!
+//! ## 输入/输出
+//! - 输入：`Connection`、资源文件路径
+//! - 输出：资源哈希或 `Result<(), AppError>`
+//!
+//! ## 错误语义
+//! - SQL 操作失败返回 `AppError::Database`
+//! - 文件不存在时视为"无哈希"，不报错
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+
+use crate::error::AppError;
+use crate::i18n::message;
+use crate::storage::{content_hash, is_content_hash};
+
+use super::{db_err, sql_placeholders};
+
+// ── 元数据 ───────────────────────────────────────────────────
+
+fn mime_for_path(path: &Path) -> &'static str {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match ext.as_str() {
+        "png" => "image/png",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "jpg" | "jpeg" => "image/jpeg",
+        "svg" => "image/svg+xml",
+        _ => "application/octet-stream",
+    }
+}
+
+/// 解析资源文件的内容哈希
+///
+/// 内容寻址文件直接取文件名；旧版文件读取内容计算。文件不存在返回 `None`。
+fn hash_for_path(path: &Path) -> Option<String> {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+    if is_content_hash(stem) && path.is_file() {
+        return Some(stem.to_string());
+    }
+    fs::read(path).ok().map(|bytes| content_hash(&bytes))
+}
+
+// ── 资源登记 ─────────────────────────────────────────────────
+
+/// 登记资源文件到 `assets` 表，返回其哈希
+///
+/// 同一哈希已登记时复用已有行；若已有行指向的文件已丢失，则改指向当前路径。
+/// 文件不存在时返回 `Ok(None)`，调用方以 `hash = NULL` 写入映射。
+pub(crate) fn register_asset(conn: &Connection, path: &Path) -> Result<Option<String>, AppError> {
+    let Some(hash) = hash_for_path(path) else {
+        return Ok(None);
+    };
+    let path_str = path.to_string_lossy();
+
+    let existing: Option<String> = conn
+        .query_row("SELECT path FROM assets WHERE hash = ?1", params![hash], |row| row.get(0))
+        .optional()
+        .map_err(|e| db_err("db.query_assets", e))?;
+
+    match existing {
+        Some(existing_path) => {
+            if existing_path != path_str && !Path::new(&existing_path).is_file() {
+                conn.execute(
+                    "UPDATE assets SET path = ?1 WHERE hash = ?2",
+                    params![path_str, hash],
+                )
+                .map_err(|e| db_err("db.update_asset_path", e))?;
+            }
+        }
+        None => {
+            let byte_size = fs::metadata(path).map(|m| m.len() as i64).unwrap_or(0);
+            let mime = mime_for_path(path);
+            let (width, height) = if mime == "image/png" {
+                match image::image_dimensions(path) {
+                    Ok((w, h)) => (Some(w as i64), Some(h as i64)),
+                    Err(_) => (None, None),
+                }
+            } else {
+                (None, None)
+            };
+            let now = chrono::Utc::now().timestamp_millis();
+            conn.execute(
+                "INSERT INTO assets (hash, path, mime, byte_size, width, height, refcount, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, 0, ?7)",
+                params![hash, path_str, mime, byte_size, width, height, now],
+            )
+            .map_err(|e| db_err("db.write_asset", e))?;
+        }
+    }
+
+    Ok(Some(hash))
+}
+
+/// 删除已无引用的资源记录（孤儿文件清理后调用）
+pub(crate) fn delete_unreferenced_assets(
+    conn: &Connection,
+    paths: &HashSet<PathBuf>,
+) -> Result<(), AppError> {
+    if paths.is_empty() {
+        return Ok(());
+    }
+
+    let path_strs: Vec<String> = paths.iter().map(|p| p.to_string_lossy().into_owned()).collect();
+    let sql = format!(
+        "DELETE FROM assets WHERE refcount <= 0 AND path IN ({})",
+        sql_placeholders(path_strs.len())
+    );
+    conn.execute(&sql, params_from_iter(path_strs.iter()))
+        .map_err(|e| db_err("db.delete_unreferenced_assets", e))?;
+    Ok(())
+}
+
+/// 按 `history_assets` 实际引用重新计算 `assets.refcount`
+pub(crate) fn recompute_asset_refcounts(conn: &Connection) -> Result<(), AppError> {
+    conn.execute(
+        "UPDATE assets SET refcount = (
+            SELECT COUNT(*) FROM history_assets WHERE history_assets.hash = assets.hash
+         )",
+        [],
+    )
+    .map_err(|e| db_err("db.recount_asset_refs", e))?;
+    Ok(())
+}
+
+// ── 迁移去重 ─────────────────────────────────────────────────
+
+/// 为现有 `history_assets` 登记哈希，并合并内容相同的重复文件
+///
+/// ## 流程
+/// 1. 逐个路径计算哈希并登记到 `assets`，首个出现的路径作为规范路径
+/// 2. 事务内将重复路径在 `history.text` / `clip_formats` / `history_assets` 中改写为规范路径
+/// 3. 提交后删除重复文件（先改库再删文件，失败时不会出现悬空引用）
+pub(crate) fn dedup_existing_assets(conn: &Connection) -> Result<(), AppError> {
+    let paths: Vec<String> = {
+        let mut stmt = conn
+            .prepare("SELECT DISTINCT path FROM history_assets ORDER BY path")
+            .map_err(|e| db_err("db.prepare_history_assets", e))?;
+        let rows = stmt
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(|e| db_err("db.query_history_assets", e))?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| db_err("db.read_history_assets", e))?
+    };
+
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| db_err("db.begin_dedup_tx", e))?;
+
+    let mut duplicates: Vec<PathBuf> = Vec::new();
+
+    for path_str in &paths {
+        let Some(hash) = register_asset(&tx, Path::new(path_str))? else {
+            continue;
+        };
+        let canonical_path: String = tx
+            .query_row("SELECT path FROM assets WHERE hash = ?1", params![hash], |row| row.get(0))
+            .map_err(|e| db_err("db.query_canonical_asset", e))?;
+
+        tx.execute(
+            "UPDATE history_assets SET hash = ?1 WHERE path = ?2",
+            params![hash, path_str],
+        )
+        .map_err(|e| db_err("db.write_asset_hash", e))?;
+
+        if canonical_path == *path_str {
+            continue;
+        }
+
+        tx.execute(
+            "UPDATE history SET text = replace(text, ?1, ?2)
+             WHERE id IN (SELECT item_id FROM history_assets WHERE path = ?1)",
+            params![path_str, canonical_path],
+        )
+        .map_err(|e| db_err("db.rewrite_duplicate_text", e))?;
+        tx.execute(
+            "UPDATE clip_formats SET content = replace(content, ?1, ?2)
+             WHERE item_id IN (SELECT item_id FROM history_assets WHERE path = ?1)",
+            params![path_str, canonical_path],
+        )
+        .map_err(|e| db_err("db.rewrite_duplicate_formats", e))?;
+        // 同一条目可能同时引用两份重复文件，冲突行直接删除
+        tx.execute(
+            "UPDATE OR IGNORE history_assets SET path = ?2 WHERE path = ?1",
+            params![path_str, canonical_path],
+        )
+        .map_err(|e| db_err("db.merge_duplicate_assets", e))?;
+        tx.execute("DELETE FROM history_assets WHERE path = ?1", params![path_str])
+            .map_err(|e| db_err("db.delete_duplicate_assets", e))?;
+
+        duplicates.push(PathBuf::from(path_str));
+    }
+
+    recompute_asset_refcounts(&tx)?;
+    tx.commit().map_err(|e| db_err("db.commit_dedup_tx", e))?;
+
+    if !duplicates.is_empty() {
+        log::info!("{}", message("db_log.assets_deduplicated", &[("count", &duplicates.len())]));
+    }
+    for path in &duplicates {
+        match fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                log::warn!(
+                    "{}",
+                    message(
+                        "db_log.delete_duplicate_failed",
+                        &[
+                            ("path", &path.display()),
+                            ("detail", &e),
+                        ],
+                    )
+                );
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "tests/assets_tests.rs"]
+mod tests;
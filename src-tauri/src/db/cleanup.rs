@@ -2,7 +2,7 @@
 //!
 //! ## 职责
 //! - 解析历史文本中的受管资源路径（图片/SVG）
-//! - 维护 `history_assets` 映射并执行删除后的孤儿文件清理
+//! - 维护 `history_assets` 映射与 `assets` 记录，并执行删除后的孤儿文件清理
 //! - 提供单条、批量、清空等删除流程的复用逻辑
 //!
 //! ## 设计决策
@@ -27,14 +27,21 @@
 //! - SQL 操作失败返回 `AppError::Database`
 //! - 文件删除失败返回 `AppError::Storage`
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 
 use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
 
 use crate::error::AppError;
-
+use crate::i18n::message;
+use crate::image_handler::{invalidate_thumbnails, PaletteColor};
+use crate::storage::is_content_hash;
+
+use super::assets::{delete_unreferenced_assets, register_asset};
+use super::image_meta::{pick_raster_path, sync_item_image_metadata};
+use super::palette::{compute_palette, sync_item_palette};
+use super::similarity::{compute_dhash, store_phash};
 use super::{db_err, sql_placeholders};
 
 // ── 路径工具 ─────────────────────────────────────────────────
@@ -62,7 +69,10 @@ fn normalize_local_path(raw: &str) -> Option<PathBuf> {
     path.is_absolute().then_some(path)
 }
 
-/// 判断路径是否为应用生成的受管资源（`img_*.png` / `svg_*.svg`）
+/// 判断路径是否为应用生成的受管资源
+///
+/// 包括内容寻址文件（`<sha256>.png` / `.gif` / `.webp` / `.jpg` / `.svg`）
+/// 与旧版时间戳命名文件（`img_*.png` / `svg_*.svg`）。
 fn is_generated_clipboard_asset(path: &Path) -> bool {
     let name = path
         .file_name()
@@ -75,6 +85,11 @@ fn is_generated_clipboard_asset(path: &Path) -> bool {
         .unwrap_or_default()
         .to_ascii_lowercase();
 
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+    if is_content_hash(stem) && matches!(ext.as_str(), "png" | "gif" | "webp" | "jpg" | "jpeg" | "svg") {
+        return true;
+    }
+
     (name.starts_with("img_") && ext == "png") || (name.starts_with("svg_") && ext == "svg")
 }
 
@@ -106,32 +121,99 @@ pub(crate) fn extract_generated_asset_paths(text: &str) -> HashSet<PathBuf> {
     paths
 }
 
+// ── 锁外预计算 ───────────────────────────────────────────────
+
+/// 条目资源中需要完整解码的计算结果（感知哈希与主色）
+///
+/// 由命令在获取写锁之前生成，`sync_item_assets_for_text` 在锁内只写入结果。
+/// 未包含在内的资源不写入，由相似图片/按颜色搜索在锁外补算。
+#[derive(Debug, Default)]
+pub(crate) struct AssetAnalysis {
+    /// 位图路径 → dHash（`None` 表示无法解码）
+    phashes: HashMap<PathBuf, Option<u64>>,
+    /// 条目代表图片路径 → 主色（`None` 表示无法解码）
+    pub(super) palettes: HashMap<PathBuf, Option<Vec<PaletteColor>>>,
+}
+
+impl AssetAnalysis {
+    pub(crate) fn of_text(text: &str) -> Self {
+        Self::of_texts([text])
+    }
+
+    /// 分析多段文本中引用的全部受管位图（同一路径只解码一次）
+    pub(crate) fn of_texts<'a>(texts: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut phashes = HashMap::new();
+        let mut palettes = HashMap::new();
+        for text in texts {
+            let paths = extract_generated_asset_paths(text);
+            if let Some(path) = pick_raster_path(&paths)
+                && !palettes.contains_key(path)
+            {
+                palettes.insert(path.clone(), compute_palette(path));
+            }
+            for path in paths {
+                let is_raster = path
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .is_some_and(|e| !e.eq_ignore_ascii_case("svg"));
+                if is_raster && !phashes.contains_key(&path) {
+                    let phash = compute_dhash(&path);
+                    phashes.insert(path, phash);
+                }
+            }
+        }
+        Self { phashes, palettes }
+    }
+
+    /// 在阻塞线程池中执行 `of_texts`，供异步命令在获取写锁前调用
+    ///
+    /// 完整解码大图耗时较长，不能占用命令线程；分析任务异常时返回空结果，
+    /// 缺失的感知哈希与主色由相似图片/按颜色搜索补算。
+    pub(crate) async fn compute(texts: Vec<String>) -> Self {
+        tokio::task::spawn_blocking(move || Self::of_texts(texts.iter().map(String::as_str)))
+            .await
+            .unwrap_or_else(|e| {
+                log::warn!("{}", message("db_log.asset_analysis_failed", &[("detail", &e)]));
+                Self::default()
+            })
+    }
+}
+
 // ── history_assets 映射维护 ──────────────────────────────────
 
 /// 将指定条目的 `history_assets` 映射同步为 `text` 中提取的路径
+///
+/// `analysis` 中已预计算的感知哈希随资源记录一并写入。
 pub(crate) fn sync_item_assets_for_text(
     conn: &Connection,
     item_id: i64,
     text: &str,
+    analysis: &AssetAnalysis,
 ) -> Result<(), AppError> {
     conn.execute(
         "DELETE FROM history_assets WHERE item_id = ?1",
         params![item_id],
     )
-    .map_err(|e| db_err("清理历史资源映射失败", e))?;
+    .map_err(|e| db_err("db.delete_history_assets", e))?;
 
     let paths = extract_generated_asset_paths(text);
+    sync_item_image_metadata(conn, item_id, &paths)?;
+    sync_item_palette(conn, item_id, &paths, analysis)?;
     if paths.is_empty() {
         return Ok(());
     }
 
     let mut stmt = conn
-        .prepare("INSERT OR IGNORE INTO history_assets (item_id, path) VALUES (?1, ?2)")
-        .map_err(|e| db_err("准备插入历史资源映射失败", e))?;
+        .prepare("INSERT OR IGNORE INTO history_assets (item_id, path, hash) VALUES (?1, ?2, ?3)")
+        .map_err(|e| db_err("db.prepare_history_assets_insert", e))?;
 
     for path in &paths {
-        stmt.execute(params![item_id, path.to_string_lossy()])
-            .map_err(|e| db_err("写入历史资源映射失败", e))?;
+        let hash = register_asset(conn, path)?;
+        if let (Some(hash), Some(phash)) = (hash.as_deref(), analysis.phashes.get(path)) {
+            store_phash(conn, hash, *phash)?;
+        }
+        stmt.execute(params![item_id, path.to_string_lossy(), hash])
+            .map_err(|e| db_err("db.write_history_assets", e))?;
     }
 
     Ok(())
@@ -149,14 +231,15 @@ pub(crate) fn sync_item_assets_from_history_text(
             |row| row.get(0),
         )
         .optional()
-        .map_err(|e| db_err("读取条目文本失败", e))?;
+        .map_err(|e| db_err("db.read_item_text", e))?;
 
     let Some(text) = text else {
         return Ok(HashSet::new());
     };
 
+    // 删除流程持有写锁，不在此解码；感知哈希留给查询时补算
     let paths = extract_generated_asset_paths(&text);
-    sync_item_assets_for_text(conn, item_id, &text)?;
+    sync_item_assets_for_text(conn, item_id, &text, &AssetAnalysis::default())?;
     Ok(paths)
 }
 
@@ -173,14 +256,14 @@ fn collect_paths_from_history_assets(
         "SELECT DISTINCT path FROM history_assets WHERE item_id IN ({})",
         sql_placeholders(ids.len())
     );
-    let mut stmt = conn.prepare(&sql).map_err(|e| db_err("准备查询资源映射失败", e))?;
+    let mut stmt = conn.prepare(&sql).map_err(|e| db_err("db.prepare_asset_mapping", e))?;
     let rows = stmt
         .query_map(params_from_iter(ids.iter()), |row| row.get::<_, String>(0))
-        .map_err(|e| db_err("查询资源映射失败", e))?;
+        .map_err(|e| db_err("db.query_asset_mapping", e))?;
 
     let mut result = HashSet::new();
     for row in rows {
-        let path_str = row.map_err(|e| db_err("读取资源映射失败", e))?;
+        let path_str = row.map_err(|e| db_err("db.read_asset_mapping", e))?;
         if let Some(path) = normalize_local_path(&path_str) {
             result.insert(path);
         }
@@ -202,7 +285,7 @@ pub(crate) fn delete_history_assets_for_ids(
         sql_placeholders(ids.len())
     );
     conn.execute(&sql, params_from_iter(ids.iter()))
-        .map_err(|e| db_err("删除历史资源映射失败", e))?;
+        .map_err(|e| db_err("db.delete_item_assets", e))?;
     Ok(())
 }
 
@@ -224,16 +307,16 @@ fn query_still_referenced_paths(
         "SELECT DISTINCT path FROM history_assets WHERE path IN ({})",
         sql_placeholders(path_strs.len())
     );
-    let mut stmt = conn.prepare(&sql).map_err(|e| db_err("准备查询被引用路径失败", e))?;
+    let mut stmt = conn.prepare(&sql).map_err(|e| db_err("db.prepare_referenced_paths", e))?;
     let rows = stmt
         .query_map(params_from_iter(path_strs.iter()), |row| {
             row.get::<_, String>(0)
         })
-        .map_err(|e| db_err("查询被引用路径失败", e))?;
+        .map_err(|e| db_err("db.query_referenced_paths", e))?;
 
     let mut referenced = HashSet::new();
     for row in rows {
-        let path_str = row.map_err(|e| db_err("读取被引用路径失败", e))?;
+        let path_str = row.map_err(|e| db_err("db.read_referenced_paths", e))?;
         if let Some(path) = normalize_local_path(&path_str) {
             referenced.insert(path);
         }
@@ -259,22 +342,22 @@ fn fallback_repair_assets(
 
     let mut select_stmt = conn
         .prepare("SELECT id, text FROM history WHERE id > ?1 ORDER BY id ASC LIMIT ?2")
-        .map_err(|e| db_err("准备回退引用扫描失败", e))?;
+        .map_err(|e| db_err("db.prepare_fallback_ref_scan", e))?;
     let mut insert_stmt = conn
-        .prepare("INSERT OR IGNORE INTO history_assets (item_id, path) VALUES (?1, ?2)")
-        .map_err(|e| db_err("准备回退写入资源映射失败", e))?;
+        .prepare("INSERT OR IGNORE INTO history_assets (item_id, path, hash) VALUES (?1, ?2, ?3)")
+        .map_err(|e| db_err("db.prepare_fallback_asset_mapping", e))?;
 
     loop {
         let rows = select_stmt
             .query_map([last_id, batch_size], |row| {
                 Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
             })
-            .map_err(|e| db_err("执行回退引用扫描失败", e))?;
+            .map_err(|e| db_err("db.run_fallback_ref_scan", e))?;
 
         let mut batch_count = 0u32;
         for row in rows {
             let (item_id, text) =
-                row.map_err(|e| db_err("读取回退引用扫描数据失败", e))?;
+                row.map_err(|e| db_err("db.read_fallback_ref_scan", e))?;
             last_id = item_id;
             batch_count += 1;
 
@@ -282,9 +365,10 @@ fn fallback_repair_assets(
             for candidate in orphan_candidates {
                 if text_paths.contains(candidate) {
                     let path_str = candidate.to_string_lossy();
+                    let hash = register_asset(conn, candidate)?;
                     insert_stmt
-                        .execute(params![item_id, path_str])
-                        .map_err(|e| db_err("回退写入资源映射失败", e))?;
+                        .execute(params![item_id, path_str, hash])
+                        .map_err(|e| db_err("db.write_fallback_asset_mapping", e))?;
                     repaired.insert(candidate.clone());
                 }
             }
@@ -298,18 +382,21 @@ fn fallback_repair_assets(
     Ok(repaired)
 }
 
-/// 删除磁盘上的孤儿文件（忽略 NotFound）
+/// 删除磁盘上的孤儿文件（忽略 NotFound），并使其缩略图失效
 fn remove_file_if_exists(path: &Path) -> Result<(), AppError> {
     match fs::remove_file(path) {
         Ok(()) => {
-            log::debug!("🗑️ 已删除孤儿资源: {}", path.display());
+            log::debug!("{}", message("db_log.orphan_deleted", &[("path", &path.display())]));
+            invalidate_thumbnails(path);
+            Ok(())
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            invalidate_thumbnails(path);
             Ok(())
         }
-        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
-        Err(e) => Err(AppError::Storage(format!(
-            "删除图片文件失败 '{}': {}",
-            path.display(),
-            e
+        Err(e) => Err(AppError::Storage(message(
+            "db.delete_image_file_failed",
+            &[("path", &path.display()), ("detail", &e)],
         ))),
     }
 }
@@ -320,7 +407,7 @@ fn remove_file_if_exists(path: &Path) -> Result<(), AppError> {
 /// 1. 一次查询获取候选中仍被引用的路径
 /// 2. 集合差得到真正的孤儿
 /// 3. 若 `history_assets` 映射为空（旧数据），触发回退扫描修复
-/// 4. 删除最终确认的孤儿文件
+/// 4. 删除最终确认的孤儿文件及其 `assets` 记录
 pub(crate) fn cleanup_generated_assets(
     conn: &Connection,
     candidates: HashSet<PathBuf>,
@@ -347,6 +434,7 @@ pub(crate) fn cleanup_generated_assets(
     for path in &orphans {
         remove_file_if_exists(path)?;
     }
+    delete_unreferenced_assets(conn, &orphans)?;
 
     Ok(())
 }
@@ -376,22 +464,22 @@ pub(crate) fn backfill_missing_history_assets(
         .prepare(
             "SELECT id, text FROM history WHERE id > ?1 ORDER BY id ASC LIMIT ?2",
         )
-        .map_err(|e| db_err("准备增量回填查询失败", e))?;
+        .map_err(|e| db_err("db.prepare_incremental_backfill", e))?;
 
     let mut insert_stmt = conn
         .prepare("INSERT OR IGNORE INTO history_assets (item_id, path) VALUES (?1, ?2)")
-        .map_err(|e| db_err("准备增量回填插入失败", e))?;
+        .map_err(|e| db_err("db.prepare_incremental_backfill_insert", e))?;
 
     loop {
         let rows = select_stmt
             .query_map([last_id, batch_size], |row| {
                 Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
             })
-            .map_err(|e| db_err("查询增量回填数据失败", e))?;
+            .map_err(|e| db_err("db.query_incremental_backfill", e))?;
 
         let pending: Vec<(i64, String)> = rows
             .collect::<Result<Vec<_>, _>>()
-            .map_err(|e| db_err("读取增量回填数据失败", e))?;
+            .map_err(|e| db_err("db.read_incremental_backfill", e))?;
 
         if pending.is_empty() {
             break;
@@ -402,7 +490,7 @@ pub(crate) fn backfill_missing_history_assets(
             for path in extract_generated_asset_paths(text) {
                 insert_stmt
                     .execute(params![item_id, path.to_string_lossy()])
-                    .map_err(|e| db_err("写入增量回填映射失败", e))?;
+                    .map_err(|e| db_err("db.write_incremental_backfill", e))?;
             }
         }
     }
@@ -430,13 +518,13 @@ pub(crate) fn collect_generated_asset_paths_from_ids(
         "SELECT text FROM history WHERE id IN ({})",
         sql_placeholders(ids.len())
     );
-    let mut stmt = conn.prepare(&sql).map_err(|e| db_err("准备查询待删条目失败", e))?;
+    let mut stmt = conn.prepare(&sql).map_err(|e| db_err("db.prepare_delete_items", e))?;
     let rows = stmt
         .query_map(params_from_iter(ids.iter()), |row| row.get::<_, String>(0))
-        .map_err(|e| db_err("查询待删条目失败", e))?;
+        .map_err(|e| db_err("db.query_delete_items", e))?;
 
     for row in rows {
-        let text = row.map_err(|e| db_err("读取待删条目失败", e))?;
+        let text = row.map_err(|e| db_err("db.read_delete_items", e))?;
         result.extend(extract_generated_asset_paths(&text));
     }
 
@@ -454,12 +542,12 @@ pub(crate) fn delete_clip_with_cleanup(conn: &mut Connection, id: i64) -> Result
 
     let tx = conn
         .unchecked_transaction()
-        .map_err(|e| db_err("开始删除事务失败", e))?;
+        .map_err(|e| db_err("db.begin_delete_tx", e))?;
 
     tx.execute("DELETE FROM history WHERE id = ?1", params![id])
-        .map_err(|e| db_err("删除记录失败", e))?;
+        .map_err(|e| db_err("db.delete_item", e))?;
     delete_history_assets_for_ids(&tx, &[id])?;
-    tx.commit().map_err(|e| db_err("提交删除事务失败", e))?;
+    tx.commit().map_err(|e| db_err("db.commit_delete_tx", e))?;
 
     cleanup_generated_assets(conn, candidates)?;
     Ok(())
@@ -475,12 +563,12 @@ pub(crate) fn clear_all_with_cleanup(conn: &mut Connection) -> Result<(), AppErr
     {
         let mut stmt = conn
             .prepare("SELECT DISTINCT path FROM history_assets")
-            .map_err(|e| db_err("准备查询历史资源映射失败", e))?;
+            .map_err(|e| db_err("db.prepare_history_assets", e))?;
         let rows = stmt
             .query_map([], |row| row.get::<_, String>(0))
-            .map_err(|e| db_err("查询历史资源映射失败", e))?;
+            .map_err(|e| db_err("db.query_history_assets", e))?;
         for row in rows {
-            let path_str = row.map_err(|e| db_err("读取历史资源映射失败", e))?;
+            let path_str = row.map_err(|e| db_err("db.read_history_assets", e))?;
             if let Some(path) = normalize_local_path(&path_str) {
                 candidates.insert(path);
             }
@@ -493,18 +581,18 @@ pub(crate) fn clear_all_with_cleanup(conn: &mut Connection) -> Result<(), AppErr
         let mut last_id: i64 = 0;
         let mut stmt = conn
             .prepare("SELECT id, text FROM history WHERE id > ?1 ORDER BY id ASC LIMIT ?2")
-            .map_err(|e| db_err("准备清空扫描失败", e))?;
+            .map_err(|e| db_err("db.prepare_clear_scan", e))?;
 
         loop {
             let rows = stmt
                 .query_map([last_id, batch_size], |row| {
                     Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
                 })
-                .map_err(|e| db_err("查询清空扫描失败", e))?;
+                .map_err(|e| db_err("db.query_clear_scan", e))?;
 
             let batch: Vec<(i64, String)> = rows
                 .collect::<Result<Vec<_>, _>>()
-                .map_err(|e| db_err("读取清空扫描数据失败", e))?;
+                .map_err(|e| db_err("db.read_clear_scan", e))?;
 
             if batch.is_empty() {
                 break;
@@ -520,12 +608,18 @@ pub(crate) fn clear_all_with_cleanup(conn: &mut Connection) -> Result<(), AppErr
     // 第 3 步：事务内清空表
     let tx = conn
         .unchecked_transaction()
-        .map_err(|e| db_err("开始清空事务失败", e))?;
+        .map_err(|e| db_err("db.begin_clear_tx", e))?;
     tx.execute("DELETE FROM history", [])
-        .map_err(|e| db_err("清空记录失败", e))?;
+        .map_err(|e| db_err("db.clear_history", e))?;
     tx.execute("DELETE FROM history_assets", [])
-        .map_err(|e| db_err("清空历史资源映射失败", e))?;
-    tx.commit().map_err(|e| db_err("提交清空事务失败", e))?;
+        .map_err(|e| db_err("db.clear_history_assets", e))?;
+    tx.execute("DELETE FROM assets", [])
+        .map_err(|e| db_err("db.clear_assets", e))?;
+    tx.execute("DELETE FROM image_metadata", [])
+        .map_err(|e| db_err("db.clear_image_meta", e))?;
+    tx.execute("DELETE FROM image_palette", [])
+        .map_err(|e| db_err("db.clear_palette", e))?;
+    tx.commit().map_err(|e| db_err("db.commit_clear_tx", e))?;
 
     // 第 4 步：清空后表已无数据，直接删除文件（无需再查引用）
     for path in &candidates {
@@ -552,12 +646,12 @@ pub(crate) fn bulk_delete_with_cleanup(
     );
     let tx = conn
         .unchecked_transaction()
-        .map_err(|e| db_err("开始批量删除事务失败", e))?;
+        .map_err(|e| db_err("db.begin_batch_delete_tx", e))?;
     tx.execute(&sql, params_from_iter(ids.iter()))
-        .map_err(|e| db_err("批量删除失败", e))?;
+        .map_err(|e| db_err("db.batch_delete", e))?;
     delete_history_assets_for_ids(&tx, ids)?;
     tx.commit()
-        .map_err(|e| db_err("提交批量删除事务失败", e))?;
+        .map_err(|e| db_err("db.commit_batch_delete_tx", e))?;
 
     cleanup_generated_assets(conn, candidates)?;
     Ok(())
@@ -0,0 +1,111 @@
+//! 颜色条目子模块
+//!
+//! ## 职责
+//! - 条目入库/编辑时识别颜色字面量，解析结果写入 `clip_formats` 的 `color` 条目
+//! - 计算两条颜色记录之间的 WCAG 对比度
+//!
+//! ## 设计决策
+//! - 复用 `clip_formats` 存储解析结果，无需新增表和迁移
+//! - 条目颜色优先级：用户取色（`picked_color`）> 入库时识别结果 > 当场解析文本，
+//!   兼容识别逻辑上线前的旧条目
+//!
+//! ## 输入/输出
+//! - 输入：`Connection`、条目 ID 与文本
+//! - 输出：`Option<Color>` / `Option<ContrastReport>`
+//!
+//! ## 错误语义
+//! - SQL 操作失败返回 `AppError::Database`
+//! - 条目不存在或不是颜色时返回 `None`，不视为错误
+
+use rusqlite::{params, Connection, OptionalExtension};
+use tauri::State;
+
+use crate::color::{
+    contrast_report, detect_color, parse_color, Color, ContrastReport, DetectedColor,
+    COLOR_FORMAT_KEY,
+};
+use crate::error::AppError;
+use crate::i18n::message;
+
+use super::{db_err, DbState};
+
+// ── 写入 ─────────────────────────────────────────────────────
+
+/// 按条目文本重新识别颜色，同步 `clip_formats` 中的 `color` 条目
+pub(crate) fn sync_item_color(conn: &Connection, item_id: i64, text: &str) -> Result<(), AppError> {
+    conn.execute(
+        "DELETE FROM clip_formats WHERE item_id = ?1 AND format = ?2",
+        params![item_id, COLOR_FORMAT_KEY],
+    )
+    .map_err(|e| db_err("db.delete_color_formats", e))?;
+
+    let Some(detected) = detect_color(text) else {
+        return Ok(());
+    };
+    let content = serde_json::to_string(&detected)
+        .map_err(|e| AppError::Database(message("db.serialize_color_failed", &[("detail", &e)])))?;
+    conn.execute(
+        "INSERT INTO clip_formats (item_id, format, content) VALUES (?1, ?2, ?3)",
+        params![item_id, COLOR_FORMAT_KEY, content],
+    )
+    .map_err(|e| db_err("db.write_color_formats", e))?;
+    Ok(())
+}
+
+// ── 查询 ─────────────────────────────────────────────────────
+
+/// 读取条目代表的颜色
+pub(crate) fn load_clip_color(conn: &Connection, item_id: i64) -> Result<Option<Color>, AppError> {
+    let row: Option<(String, Option<String>, Option<String>)> = conn
+        .query_row(
+            "SELECT h.text, h.picked_color,
+                    (SELECT content FROM clip_formats f WHERE f.item_id = h.id AND f.format = ?2)
+             FROM history h WHERE h.id = ?1",
+            params![item_id, COLOR_FORMAT_KEY],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()
+        .map_err(|e| db_err("db.query_item_colors", e))?;
+    let Some((text, picked, stored)) = row else {
+        return Ok(None);
+    };
+
+    let picked = picked.and_then(|value| parse_color(&value)).map(|(color, _)| color);
+    let stored = || {
+        stored
+            .and_then(|json| serde_json::from_str::<DetectedColor>(&json).ok())
+            .map(|detected| detected.color())
+    };
+    Ok(picked
+        .or_else(stored)
+        .or_else(|| detect_color(&text).map(|detected| detected.color())))
+}
+
+/// 计算两条颜色记录的对比度；任一条目不是颜色时返回 `None`
+pub(crate) fn clip_contrast(
+    conn: &Connection,
+    foreground_id: i64,
+    background_id: i64,
+) -> Result<Option<ContrastReport>, AppError> {
+    let foreground = load_clip_color(conn, foreground_id)?;
+    let background = load_clip_color(conn, background_id)?;
+    Ok(foreground
+        .zip(background)
+        .map(|(fg, bg)| contrast_report(fg, bg)))
+}
+
+// ── Tauri Commands ───────────────────────────────────────────
+
+/// 计算两条颜色记录之间的 WCAG 对比度
+#[tauri::command]
+pub fn db_color_contrast(
+    state: State<'_, DbState>,
+    foreground_id: i64,
+    background_id: i64,
+) -> Result<Option<ContrastReport>, AppError> {
+    super::with_read_conn(&state, |conn| clip_contrast(conn, foreground_id, background_id))
+}
+
+#[cfg(test)]
+#[path = "tests/color_tests.rs"]
+mod tests;
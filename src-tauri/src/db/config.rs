@@ -18,8 +18,7 @@ use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Manager};
 
 use crate::error::AppError;
-
-use super::db_err;
+use crate::i18n::message;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct DbConfig {
@@ -31,7 +30,7 @@ fn get_config_path(app: &AppHandle) -> Result<PathBuf, AppError> {
     let app_data_dir = app
         .path()
         .app_data_dir()
-        .map_err(|e| db_err("获取应用数据目录失败", e))?;
+        .map_err(|e| AppError::Database(message("db.app_data_dir_failed", &[("detail", &e)])))?;
     Ok(app_data_dir.join("config.json"))
 }
 
@@ -57,9 +56,9 @@ fn load_db_config(app: &AppHandle) -> DbConfig {
 fn save_db_config_to_path(config_path: &Path, db_dir: Option<String>) -> Result<(), AppError> {
     let config = DbConfig { db_dir };
     let content = serde_json::to_string_pretty(&config)
-        .map_err(|e| db_err("序列化配置失败", e))?;
+        .map_err(|e| AppError::Database(message("db.serialize_config_failed", &[("detail", &e)])))?;
     fs::write(config_path, content)
-        .map_err(|e| db_err("写入配置文件失败", e))?;
+        .map_err(|e| AppError::from_io(&message("db.write_config", &[]), &e, AppError::Database))?;
     Ok(())
 }
 
@@ -68,7 +67,7 @@ fn resolve_db_path_from_config(app_data_dir: &Path, config: &DbConfig) -> Result
         if !dir.is_empty() {
             let dir_path = PathBuf::from(dir);
             fs::create_dir_all(&dir_path)
-                .map_err(|e| db_err("创建数据库目录失败", e))?;
+                .map_err(|e| AppError::from_io(&message("db.create_dir", &[]), &e, AppError::Database))?;
             return Ok(dir_path.join("clipboard.db"));
         }
     }
@@ -84,7 +83,7 @@ pub(crate) fn resolve_db_path(app: &AppHandle) -> Result<PathBuf, AppError> {
     let app_data_dir = app
         .path()
         .app_data_dir()
-        .map_err(|e| db_err("获取应用数据目录失败", e))?;
+        .map_err(|e| AppError::Database(message("db.app_data_dir_failed", &[("detail", &e)])))?;
     let config = load_db_config(app);
     resolve_db_path_from_config(&app_data_dir, &config)
 }
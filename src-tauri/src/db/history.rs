@@ -4,6 +4,8 @@
 //! - 提供历史记录增删改查、置顶/收藏、统计与导入能力
 //! - 封装自动清理逻辑，并与资源清理子模块协同
 //! - 暴露对应的 Tauri command 给前端调用
+//! - 写入前需要解码图片（感知哈希、主色）的命令为 `async`，解码经 `AssetAnalysis::compute`
+//!   在阻塞线程池中完成，不占用主线程
 //!
 //! ## 输入/输出
 //! - 输入：`State<DbState>`、历史记录参数、导入数据集合
@@ -18,9 +20,13 @@ use rusqlite::{params, params_from_iter, Connection, OptionalExtension, ToSql};
 use serde::Deserialize;
 use tauri::State;
 
+use crate::clipboard::language_detection::detect_language;
+use crate::clipboard::subtype_detection::{detect_subtype, ContentSubtype, SuggestedAction};
 use crate::error::AppError;
 
-use super::{db_err, sql_placeholders, AppStats, ClipFormat, ClipItem, DbState, Tag};
+use super::cleanup::AssetAnalysis;
+use super::image_meta::load_image_metadata_batch;
+use super::{db_err, db_err_with, sql_placeholders, AppStats, ClipFormat, ClipItem, DbState, Tag};
 
 // ── 数据结构 ─────────────────────────────────────────────────
 
@@ -33,6 +39,47 @@ pub struct ImportItem {
     pub is_snippet: Option<i32>,
 }
 
+/// 条目的捕获来源 selection
+///
+/// CLIPBOARD 条目不写标记；PRIMARY 条目在 `clip_formats` 中写入
+/// `selection = primary`，便于单独筛选与过期清理。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SelectionSource {
+    Clipboard,
+    Primary,
+}
+
+/// 条目来源标记在 `clip_formats` 中的格式名
+pub(crate) const SELECTION_FORMAT_KEY: &str = "selection";
+/// PRIMARY 来源标记值
+pub(crate) const SELECTION_PRIMARY: &str = "primary";
+
+/// 历史列表筛选条件（字段均可选，缺省表示不限制）
+#[derive(Debug, Default, Deserialize)]
+pub struct HistoryFilter {
+    /// 图片最小宽度（像素）
+    pub min_image_width: Option<u32>,
+    /// 图片最小高度（像素）
+    pub min_image_height: Option<u32>,
+    /// 图片最小像素总数（与方向无关，如 4K = 3840 × 2160）
+    pub min_image_pixels: Option<u64>,
+    /// 仅返回指定 selection 捕获的条目
+    pub selection: Option<SelectionSource>,
+    /// 仅返回识别为指定编程语言的条目（如 `rust`、`python`）
+    pub language: Option<String>,
+    /// 仅返回指定内容子类型的条目（如 `url`、`json`、`table`）
+    pub content_subtype: Option<String>,
+}
+
+impl HistoryFilter {
+    fn has_image_constraint(&self) -> bool {
+        self.min_image_width.is_some()
+            || self.min_image_height.is_some()
+            || self.min_image_pixels.is_some()
+    }
+}
+
 // ── 内部 helper ──────────────────────────────────────────────
 
 fn normalize_flag(value: i32) -> i32 {
@@ -56,7 +103,7 @@ fn load_tags_batch(conn: &Connection, ids: &[i64]) -> Result<HashMap<i64, Vec<Ta
         sql_placeholders(ids.len())
     );
     let params: Vec<&dyn ToSql> = ids.iter().map(|id| id as &dyn ToSql).collect();
-    let mut stmt = conn.prepare(&sql).map_err(|e| db_err("准备标签查询失败", e))?;
+    let mut stmt = conn.prepare(&sql).map_err(|e| db_err("db.prepare_tags", e))?;
     let rows = stmt
         .query_map(params.as_slice(), |row| {
             Ok((
@@ -68,11 +115,11 @@ fn load_tags_batch(conn: &Connection, ids: &[i64]) -> Result<HashMap<i64, Vec<Ta
                 },
             ))
         })
-        .map_err(|e| db_err("查询标签失败", e))?;
+        .map_err(|e| db_err("db.query_tags", e))?;
 
     let mut map: HashMap<i64, Vec<Tag>> = HashMap::new();
     for row in rows {
-        let (item_id, tag) = row.map_err(|e| db_err("读取标签行失败", e))?;
+        let (item_id, tag) = row.map_err(|e| db_err("db.read_tag_row", e))?;
         map.entry(item_id).or_default().push(tag);
     }
     Ok(map)
@@ -88,20 +135,20 @@ enum ToggleField {
 
 fn toggle_field(conn: &Connection, field: ToggleField, id: i64, current: i32) -> Result<(), AppError> {
     let new_val = if current != 0 { 0 } else { 1 };
-    let (sql, label) = match field {
-        ToggleField::Pin => ("UPDATE history SET is_pinned = ?1 WHERE id = ?2", "置顶"),
-        ToggleField::Favorite => ("UPDATE history SET is_favorite = ?1 WHERE id = ?2", "收藏"),
+    let (sql, context) = match field {
+        ToggleField::Pin => ("UPDATE history SET is_pinned = ?1 WHERE id = ?2", "db.toggle_pin"),
+        ToggleField::Favorite => ("UPDATE history SET is_favorite = ?1 WHERE id = ?2", "db.toggle_favorite"),
     };
     conn.execute(sql, params![new_val, id])
-        .map_err(|e| db_err(&format!("切换{}失败", label), e))?;
+        .map_err(|e| db_err(context, e))?;
     Ok(())
 }
 
 /// 加载指定条目的附加格式数据
-fn load_formats(conn: &Connection, item_id: i64) -> Result<Vec<ClipFormat>, AppError> {
+pub(crate) fn load_formats(conn: &Connection, item_id: i64) -> Result<Vec<ClipFormat>, AppError> {
     let mut stmt = conn
         .prepare("SELECT format, content FROM clip_formats WHERE item_id = ?1 ORDER BY format")
-        .map_err(|e| db_err("准备格式查询失败", e))?;
+        .map_err(|e| db_err("db.prepare_formats", e))?;
     let rows = stmt
         .query_map(params![item_id], |row| {
             Ok(ClipFormat {
@@ -109,26 +156,26 @@ fn load_formats(conn: &Connection, item_id: i64) -> Result<Vec<ClipFormat>, AppE
                 content: row.get(1)?,
             })
         })
-        .map_err(|e| db_err("查询格式失败", e))?;
+        .map_err(|e| db_err("db.query_formats", e))?;
 
     let mut formats = Vec::new();
     for row in rows {
-        formats.push(row.map_err(|e| db_err("读取格式行失败", e))?);
+        formats.push(row.map_err(|e| db_err("db.read_format_row", e))?);
     }
     Ok(formats)
 }
 
 /// 插入附加格式数据（HTML/RTF/图片路径）
-fn insert_formats(conn: &Connection, item_id: i64, formats: &[(&str, &str)]) -> Result<(), AppError> {
+pub(super) fn insert_formats(conn: &Connection, item_id: i64, formats: &[(&str, &str)]) -> Result<(), AppError> {
     if formats.is_empty() {
         return Ok(());
     }
     let mut stmt = conn
         .prepare("INSERT OR REPLACE INTO clip_formats (item_id, format, content) VALUES (?1, ?2, ?3)")
-        .map_err(|e| db_err("准备格式插入失败", e))?;
+        .map_err(|e| db_err("db.prepare_format_insert", e))?;
     for &(format, content) in formats {
         stmt.execute(params![item_id, format, content])
-            .map_err(|e| db_err(&format!("插入格式 {} 失败", format), e))?;
+            .map_err(|e| db_err_with("db.insert_format", &[("format", &format)], e))?;
     }
     Ok(())
 }
@@ -138,19 +185,50 @@ fn insert_formats(conn: &Connection, item_id: i64, formats: &[(&str, &str)]) ->
 fn auto_clear_before(conn: &mut Connection, cutoff: i64) -> Result<(), AppError> {
     let mut stmt = conn
         .prepare("SELECT id FROM history WHERE timestamp < ?1 AND is_pinned = 0 AND is_favorite = 0")
-        .map_err(|e| db_err("准备自动清理查询失败", e))?;
+        .map_err(|e| db_err("db.prepare_cleanup", e))?;
     let ids: Vec<i64> = stmt
         .query_map(params![cutoff], |row| row.get(0))
-        .map_err(|e| db_err("查询自动清理条目失败", e))?
+        .map_err(|e| db_err("db.query_cleanup_items", e))?
         .collect::<Result<Vec<_>, _>>()
-        .map_err(|e| db_err("读取自动清理条目失败", e))?;
+        .map_err(|e| db_err("db.read_cleanup_items", e))?;
     drop(stmt);
 
+    clear_items(conn, &ids)
+}
+
+/// 清理指定 selection 来源、早于 `cutoff` 的未置顶/未收藏条目
+///
+/// 返回被删除的条目 ID，供调用方通知前端。
+pub(crate) fn auto_clear_selection_before(
+    conn: &mut Connection,
+    selection: &str,
+    cutoff: i64,
+) -> Result<Vec<i64>, AppError> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT h.id FROM history h
+             JOIN clip_formats f ON f.item_id = h.id AND f.format = ?1 AND f.content = ?2
+             WHERE h.timestamp < ?3 AND h.is_pinned = 0 AND h.is_favorite = 0",
+        )
+        .map_err(|e| db_err("db.prepare_stale_sources", e))?;
+    let ids: Vec<i64> = stmt
+        .query_map(params![SELECTION_FORMAT_KEY, selection, cutoff], |row| row.get(0))
+        .map_err(|e| db_err("db.query_stale_sources", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| db_err("db.read_stale_sources", e))?;
+    drop(stmt);
+
+    clear_items(conn, &ids)?;
+    Ok(ids)
+}
+
+/// 按 ID 删除条目及其资源映射，并回收不再被引用的生成资源
+fn clear_items(conn: &mut Connection, ids: &[i64]) -> Result<(), AppError> {
     if ids.is_empty() {
         return Ok(());
     }
 
-    let candidates = super::cleanup::collect_generated_asset_paths_from_ids(conn, &ids)?;
+    let candidates = super::cleanup::collect_generated_asset_paths_from_ids(conn, ids)?;
 
     // 使用已收集的 ID 集合按主键删除，避免重复评估 WHERE 条件
     let delete_sql = format!(
@@ -159,12 +237,12 @@ fn auto_clear_before(conn: &mut Connection, cutoff: i64) -> Result<(), AppError>
     );
     let tx = conn
         .unchecked_transaction()
-        .map_err(|e| db_err("开始自动清理事务失败", e))?;
+        .map_err(|e| db_err("db.begin_cleanup_tx", e))?;
     tx.execute(&delete_sql, params_from_iter(ids.iter()))
-        .map_err(|e| db_err("自动清理删除失败", e))?;
-    super::cleanup::delete_history_assets_for_ids(&tx, &ids)?;
+        .map_err(|e| db_err("db.cleanup_delete", e))?;
+    super::cleanup::delete_history_assets_for_ids(&tx, ids)?;
     tx.commit()
-        .map_err(|e| db_err("提交自动清理事务失败", e))?;
+        .map_err(|e| db_err("db.commit_cleanup_tx", e))?;
 
     super::cleanup::cleanup_generated_assets(conn, candidates)
 }
@@ -200,27 +278,72 @@ fn get_stats(conn: &Connection) -> Result<AppStats, AppError> {
             })
         },
     )
-    .map_err(|e| db_err("查询统计信息失败", e))
+    .map_err(|e| db_err("db.query_stats", e))
 }
 
 /// 查询历史列表
 ///
 /// 直接构造 `ClipItem`（tags 初始为空），再通过 `load_tags_batch`
 /// 统一填充标签，消除原来的 `BaseItem` 中间结构。
-fn get_history(conn: &Connection, limit: i64) -> Result<Vec<ClipItem>, AppError> {
+/// `filter` 中的条件以 AND 组合追加到 WHERE 子句。
+fn get_history(
+    conn: &Connection,
+    limit: i64,
+    filter: &HistoryFilter,
+) -> Result<Vec<ClipItem>, AppError> {
     let limit = limit.clamp(1, 5000);
 
-    let mut stmt = conn
-        .prepare(
-            "SELECT id, text, timestamp, is_pinned, is_snippet, is_favorite, picked_color, content_type
-             FROM history
-             ORDER BY is_pinned DESC, timestamp DESC
-             LIMIT ?1",
-        )
-        .map_err(|e| db_err("准备查询失败", e))?;
+    let mut clauses: Vec<&str> = Vec::new();
+    let mut values: Vec<Box<dyn ToSql>> = Vec::new();
+    if filter.has_image_constraint() {
+        clauses.push(
+            "EXISTS (SELECT 1 FROM image_metadata m
+                     WHERE m.item_id = history.id
+                       AND m.width >= ? AND m.height >= ? AND m.width * m.height >= ?)",
+        );
+        values.push(Box::new(i64::from(filter.min_image_width.unwrap_or(0))));
+        values.push(Box::new(i64::from(filter.min_image_height.unwrap_or(0))));
+        values.push(Box::new(filter.min_image_pixels.unwrap_or(0).min(i64::MAX as u64) as i64));
+    }
+    match filter.selection {
+        Some(SelectionSource::Primary) => clauses.push(
+            "EXISTS (SELECT 1 FROM clip_formats f
+                     WHERE f.item_id = history.id AND f.format = 'selection' AND f.content = 'primary')",
+        ),
+        Some(SelectionSource::Clipboard) => clauses.push(
+            "NOT EXISTS (SELECT 1 FROM clip_formats f
+                         WHERE f.item_id = history.id AND f.format = 'selection')",
+        ),
+        None => {}
+    }
+    if let Some(language) = &filter.language {
+        clauses.push("language = ?");
+        values.push(Box::new(language.clone()));
+    }
+    if let Some(subtype) = &filter.content_subtype {
+        clauses.push("content_subtype = ?");
+        values.push(Box::new(subtype.clone()));
+    }
+    values.push(Box::new(limit));
+
+    let where_sql = if clauses.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", clauses.join(" AND "))
+    };
+    let sql = format!(
+        "SELECT id, text, timestamp, is_pinned, is_snippet, is_favorite, picked_color, content_type,
+                language, language_confidence, content_subtype
+         FROM history
+         {}
+         ORDER BY is_pinned DESC, timestamp DESC
+         LIMIT ?",
+        where_sql
+    );
+    let mut stmt = conn.prepare(&sql).map_err(|e| db_err("db.prepare_query", e))?;
 
     let mut items: Vec<ClipItem> = stmt
-        .query_map(params![limit], |row| {
+        .query_map(params_from_iter(values.iter()), |row| {
             Ok(ClipItem {
                 id: row.get(0)?,
                 text: row.get(1)?,
@@ -232,12 +355,16 @@ fn get_history(conn: &Connection, limit: i64) -> Result<Vec<ClipItem>, AppError>
                 picked_color: row.get(6)?,
                 content_type: row.get::<_, Option<String>>(7)?
                     .unwrap_or_else(|| "text".to_string()),
+                language: row.get(8)?,
+                language_confidence: row.get(9)?,
+                content_subtype: row.get(10)?,
                 formats: Vec::new(),
+                image_meta: None,
             })
         })
-        .map_err(|e| db_err("查询历史失败", e))?
+        .map_err(|e| db_err("db.query_history", e))?
         .collect::<Result<Vec<_>, _>>()
-        .map_err(|e| db_err("读取行失败", e))?;
+        .map_err(|e| db_err("db.read_row", e))?;
 
     if items.is_empty() {
         return Ok(items);
@@ -245,8 +372,10 @@ fn get_history(conn: &Connection, limit: i64) -> Result<Vec<ClipItem>, AppError>
 
     let ids: Vec<i64> = items.iter().map(|item| item.id).collect();
     let mut tags_map = load_tags_batch(conn, &ids)?;
+    let mut meta_map = load_image_metadata_batch(conn, &ids)?;
     for item in &mut items {
         item.tags = tags_map.remove(&item.id).unwrap_or_default();
+        item.image_meta = meta_map.remove(&item.id);
     }
 
     Ok(items)
@@ -256,13 +385,14 @@ fn get_history(conn: &Connection, limit: i64) -> Result<Vec<ClipItem>, AppError>
 ///
 /// 使用与 `get_history` 相同的 `load_tags_batch` 策略加载标签，
 /// 替代原来的 `json_group_array` 子查询 + `serde_json` 解析。
-fn get_clip_by_id(conn: &Connection, id: i64) -> Result<Option<ClipItem>, AppError> {
+pub(super) fn get_clip_by_id(conn: &Connection, id: i64) -> Result<Option<ClipItem>, AppError> {
     let mut stmt = conn
         .prepare(
-            "SELECT id, text, timestamp, is_pinned, is_snippet, is_favorite, picked_color, content_type
+            "SELECT id, text, timestamp, is_pinned, is_snippet, is_favorite, picked_color, content_type,
+                language, language_confidence, content_subtype
              FROM history WHERE id = ?1 LIMIT 1",
         )
-        .map_err(|e| db_err("准备按 ID 查询失败", e))?;
+        .map_err(|e| db_err("db.prepare_query_by_id", e))?;
 
     let mut item = match stmt
         .query_row(params![id], |row| {
@@ -277,11 +407,15 @@ fn get_clip_by_id(conn: &Connection, id: i64) -> Result<Option<ClipItem>, AppErr
                 picked_color: row.get(6)?,
                 content_type: row.get::<_, Option<String>>(7)?
                     .unwrap_or_else(|| "text".to_string()),
+                language: row.get(8)?,
+                language_confidence: row.get(9)?,
+                content_subtype: row.get(10)?,
                 formats: Vec::new(),
+                image_meta: None,
             })
         })
         .optional()
-        .map_err(|e| db_err("按 ID 查询历史失败", e))?
+        .map_err(|e| db_err("db.query_by_id", e))?
     {
         Some(item) => item,
         None => return Ok(None),
@@ -290,10 +424,68 @@ fn get_clip_by_id(conn: &Connection, id: i64) -> Result<Option<ClipItem>, AppErr
     let mut tags_map = load_tags_batch(conn, &[id])?;
     item.tags = tags_map.remove(&id).unwrap_or_default();
     item.formats = load_formats(conn, id)?;
+    item.image_meta = load_image_metadata_batch(conn, &[id])?.remove(&id);
     Ok(Some(item))
 }
 
-fn add_clip(conn: &Connection, text: String, is_snippet: i32) -> Result<Option<i64>, AppError> {
+/// 读取条目的主文本；条目不存在时返回 `None`
+pub(crate) fn load_clip_text(conn: &Connection, id: i64) -> Result<Option<String>, AppError> {
+    conn.query_row("SELECT text FROM history WHERE id = ?1", params![id], |row| row.get(0))
+        .optional()
+        .map_err(|e| db_err("db.query_item_text", e))
+}
+
+/// 按条目文本重新识别编程语言，写入 `language` / `language_confidence` 列
+///
+/// 仅文本与富文本条目参与识别；图片、文件条目的 `text` 是路径，保持为空。
+pub(super) fn sync_item_language(conn: &Connection, item_id: i64, text: &str) -> Result<(), AppError> {
+    let guess = detect_language(text);
+    conn.execute(
+        "UPDATE history SET language = ?1, language_confidence = ?2
+         WHERE id = ?3 AND content_type IN ('text', 'rich')",
+        params![
+            guess.map(|g| g.language.as_str()),
+            guess.map(|g| g.confidence),
+            item_id
+        ],
+    )
+    .map_err(|e| db_err("db.write_language", e))?;
+    Ok(())
+}
+
+/// 按条目文本重新识别内容子类型，写入 `content_subtype` 列
+///
+/// 与语言识别相同，仅文本与富文本条目参与识别。
+pub(super) fn sync_item_subtype(conn: &Connection, item_id: i64, text: &str) -> Result<(), AppError> {
+    conn.execute(
+        "UPDATE history SET content_subtype = ?1
+         WHERE id = ?2 AND content_type IN ('text', 'rich')",
+        params![detect_subtype(text).map(ContentSubtype::as_str), item_id],
+    )
+    .map_err(|e| db_err("db.write_subtype", e))?;
+    Ok(())
+}
+
+/// 按条目已存储的内容子类型返回建议操作；条目不存在或无子类型时为空
+fn get_suggested_actions(conn: &Connection, id: i64) -> Result<Vec<SuggestedAction>, AppError> {
+    let subtype: Option<String> = conn
+        .query_row("SELECT content_subtype FROM history WHERE id = ?1", params![id], |row| row.get(0))
+        .optional()
+        .map_err(|e| db_err("db.query_subtype", e))?
+        .flatten();
+    Ok(subtype
+        .as_deref()
+        .and_then(ContentSubtype::from_id)
+        .map(|subtype| subtype.suggested_actions().to_vec())
+        .unwrap_or_default())
+}
+
+fn add_clip(
+    conn: &Connection,
+    text: String,
+    is_snippet: i32,
+    analysis: &AssetAnalysis,
+) -> Result<Option<i64>, AppError> {
     let is_snippet = normalize_flag(is_snippet);
 
     let text = text.trim().to_string();
@@ -320,14 +512,40 @@ fn add_clip(conn: &Connection, text: String, is_snippet: i32) -> Result<Option<i
         "INSERT INTO history (text, timestamp, is_pinned, is_snippet, content_type) VALUES (?1, ?2, 0, ?3, 'text')",
         params![text, now, is_snippet],
     )
-    .map_err(|e| db_err("插入记录失败", e))?;
+    .map_err(|e| db_err("db.insert_item", e))?;
 
     let inserted_id = conn.last_insert_rowid();
-    super::cleanup::sync_item_assets_for_text(conn, inserted_id, &text)?;
+    super::cleanup::sync_item_assets_for_text(conn, inserted_id, &text, analysis)?;
+    super::color::sync_item_color(conn, inserted_id, &text)?;
+    sync_item_language(conn, inserted_id, &text)?;
+    sync_item_subtype(conn, inserted_id, &text)?;
 
     Ok(Some(inserted_id))
 }
 
+/// 写入来自非 CLIPBOARD selection 的文本条目，并返回完整记录
+///
+/// 与 `add_clip` 共用去重与资源同步逻辑，额外写入 `selection` 来源标记。
+/// 条目与来源标记在同一事务中写入，避免失败时留下未标记来源的条目。
+/// 内容为空或与上一条重复时返回 `None`。
+pub(crate) fn add_selection_clip(
+    conn: &Connection,
+    text: &str,
+    selection: &str,
+    analysis: &AssetAnalysis,
+) -> Result<Option<ClipItem>, AppError> {
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| db_err("db.begin_selection_tx", e))?;
+    let Some(id) = add_clip(&tx, text.to_string(), 0, analysis)? else {
+        return Ok(None);
+    };
+    insert_formats(&tx, id, &[(SELECTION_FORMAT_KEY, selection)])?;
+    tx.commit()
+        .map_err(|e| db_err("db.commit_selection_tx", e))?;
+    get_clip_by_id(conn, id)
+}
+
 /// 剪贴板快照入库参数
 #[derive(Debug, Deserialize)]
 pub struct SnapshotInput {
@@ -347,7 +565,11 @@ pub struct SnapshotInput {
 /// - `"files"` → 编码后的文件列表
 ///
 /// 附加格式（HTML / RTF / 图片路径）存入 `clip_formats` 表。
-fn add_clip_snapshot(conn: &Connection, snapshot: SnapshotInput) -> Result<Option<i64>, AppError> {
+fn add_clip_snapshot(
+    conn: &Connection,
+    snapshot: SnapshotInput,
+    analysis: &AssetAnalysis,
+) -> Result<Option<i64>, AppError> {
     let primary_text = snapshot.text.as_deref().unwrap_or("").trim().to_string();
     if primary_text.is_empty() {
         return Ok(None);
@@ -375,12 +597,15 @@ fn add_clip_snapshot(conn: &Connection, snapshot: SnapshotInput) -> Result<Optio
         "INSERT INTO history (text, timestamp, is_pinned, is_snippet, content_type) VALUES (?1, ?2, 0, 0, ?3)",
         params![primary_text, now, content_type],
     )
-    .map_err(|e| db_err("快照插入记录失败", e))?;
+    .map_err(|e| db_err("db.snapshot_insert", e))?;
 
     let inserted_id = conn.last_insert_rowid();
 
     // 同步资源映射（图片/SVG 路径）
-    super::cleanup::sync_item_assets_for_text(conn, inserted_id, &primary_text)?;
+    super::cleanup::sync_item_assets_for_text(conn, inserted_id, &primary_text, analysis)?;
+    super::color::sync_item_color(conn, inserted_id, &primary_text)?;
+    sync_item_language(conn, inserted_id, &primary_text)?;
+    sync_item_subtype(conn, inserted_id, &primary_text)?;
 
     // 插入附加格式
     let mut extra_formats: Vec<(&str, &str)> = Vec::new();
@@ -398,7 +623,7 @@ fn add_clip_snapshot(conn: &Connection, snapshot: SnapshotInput) -> Result<Optio
         if !image_path.trim().is_empty() {
             extra_formats.push(("image", image_path));
             // 图片路径也需要资源映射
-            super::cleanup::sync_item_assets_for_text(conn, inserted_id, image_path)?;
+            super::cleanup::sync_item_assets_for_text(conn, inserted_id, image_path, analysis)?;
         }
     }
     insert_formats(conn, inserted_id, &extra_formats)?;
@@ -423,17 +648,26 @@ fn bulk_pin(conn: &Connection, ids: &[i64]) -> Result<(), AppError> {
         sql_placeholders(ids.len())
     );
     conn.execute(&sql, params_from_iter(ids.iter()))
-        .map_err(|e| db_err("批量置顶失败", e))?;
+        .map_err(|e| db_err("db.batch_pin", e))?;
     Ok(())
 }
 
-fn update_clip(conn: &Connection, id: i64, new_text: String) -> Result<(), AppError> {
+fn update_clip(
+    conn: &Connection,
+    id: i64,
+    new_text: String,
+    analysis: &AssetAnalysis,
+) -> Result<(), AppError> {
     conn.execute(
         "UPDATE history SET text = ?1 WHERE id = ?2",
         params![new_text, id],
     )
-    .map_err(|e| db_err("更新记录失败", e))?;
-    super::cleanup::sync_item_assets_for_text(conn, id, &new_text)?;
+    .map_err(|e| db_err("db.update_item", e))?;
+    super::cleanup::sync_item_assets_for_text(conn, id, &new_text, analysis)?;
+    super::color::sync_item_color(conn, id, &new_text)?;
+    sync_item_language(conn, id, &new_text)?;
+    sync_item_subtype(conn, id, &new_text)?;
+    super::link_preview::clear_link_preview(conn, id)?;
     Ok(())
 }
 
@@ -442,7 +676,7 @@ fn update_picked_color(conn: &Connection, id: i64, color: Option<String>) -> Res
         "UPDATE history SET picked_color = ?1 WHERE id = ?2",
         params![color, id],
     )
-    .map_err(|e| db_err("更新调色板颜色失败", e))?;
+    .map_err(|e| db_err("db.update_palette_colors", e))?;
     Ok(())
 }
 
@@ -450,10 +684,14 @@ fn update_picked_color(conn: &Connection, id: i64, color: Option<String>) -> Res
 ///
 /// 使用 `sync_item_assets_for_text` 统一维护资源映射，
 /// 替代原来手动调用 `extract_generated_asset_paths` + 逐条 INSERT 的重复逻辑。
-fn import_data(conn: &mut Connection, items: &[ImportItem]) -> Result<(), AppError> {
+fn import_data(
+    conn: &mut Connection,
+    items: &[ImportItem],
+    analysis: &AssetAnalysis,
+) -> Result<(), AppError> {
     let tx = conn
         .unchecked_transaction()
-        .map_err(|e| db_err("开始事务失败", e))?;
+        .map_err(|e| db_err("db.begin_tx", e))?;
 
     for item in items {
         if item.text.trim().is_empty() {
@@ -473,13 +711,16 @@ fn import_data(conn: &mut Connection, items: &[ImportItem]) -> Result<(), AppErr
                 normalize_flag(item.is_snippet.unwrap_or(0)),
             ],
         )
-        .map_err(|e| db_err("导入记录失败", e))?;
+        .map_err(|e| db_err("db.import_item", e))?;
 
         let item_id = tx.last_insert_rowid();
-        super::cleanup::sync_item_assets_for_text(&tx, item_id, &item.text)?;
+        super::cleanup::sync_item_assets_for_text(&tx, item_id, &item.text, analysis)?;
+        super::color::sync_item_color(&tx, item_id, &item.text)?;
+        sync_item_language(&tx, item_id, &item.text)?;
+        sync_item_subtype(&tx, item_id, &item.text)?;
     }
 
-    tx.commit().map_err(|e| db_err("提交事务失败", e))?;
+    tx.commit().map_err(|e| db_err("db.commit_tx", e))?;
     Ok(())
 }
 
@@ -502,30 +743,45 @@ pub fn db_get_stats(state: State<'_, DbState>) -> Result<AppStats, AppError> {
 }
 
 #[tauri::command]
-pub fn db_get_history(state: State<'_, DbState>, limit: i64) -> Result<Vec<ClipItem>, AppError> {
-    super::with_read_conn(&state, |conn| get_history(conn, limit))
+pub fn db_get_history(
+    state: State<'_, DbState>,
+    limit: i64,
+    filter: Option<HistoryFilter>,
+) -> Result<Vec<ClipItem>, AppError> {
+    let filter = filter.unwrap_or_default();
+    super::with_read_conn(&state, |conn| get_history(conn, limit, &filter))
+}
+
+#[tauri::command]
+pub fn db_get_suggested_actions(
+    state: State<'_, DbState>,
+    id: i64,
+) -> Result<Vec<SuggestedAction>, AppError> {
+    super::with_read_conn(&state, |conn| get_suggested_actions(conn, id))
 }
 
 #[tauri::command]
-pub fn db_add_clip(
+pub async fn db_add_clip(
     state: State<'_, DbState>,
     text: String,
     is_snippet: i32,
 ) -> Result<(), AppError> {
+    let analysis = AssetAnalysis::compute(vec![text.clone()]).await;
     super::with_conn_mut(&state, |conn| {
-        let _ = add_clip(conn, text, is_snippet)?;
+        let _ = add_clip(conn, text, is_snippet, &analysis)?;
         Ok(())
     })
 }
 
 #[tauri::command]
-pub fn db_add_clip_and_get(
+pub async fn db_add_clip_and_get(
     state: State<'_, DbState>,
     text: String,
     is_snippet: i32,
 ) -> Result<Option<ClipItem>, AppError> {
+    let analysis = AssetAnalysis::compute(vec![text.clone()]).await;
     super::with_conn_mut(&state, |conn| {
-        let inserted_id = add_clip(conn, text, is_snippet)?;
+        let inserted_id = add_clip(conn, text, is_snippet, &analysis)?;
         match inserted_id {
             Some(id) => get_clip_by_id(conn, id),
             None => Ok(None),
@@ -572,12 +828,13 @@ pub fn db_bulk_pin(state: State<'_, DbState>, ids: Vec<i64>) -> Result<(), AppEr
 }
 
 #[tauri::command]
-pub fn db_update_clip(
+pub async fn db_update_clip(
     state: State<'_, DbState>,
     id: i64,
     new_text: String,
 ) -> Result<(), AppError> {
-    super::with_conn_mut(&state, |conn| update_clip(conn, id, new_text))
+    let analysis = AssetAnalysis::compute(vec![new_text.clone()]).await;
+    super::with_conn_mut(&state, |conn| update_clip(conn, id, new_text, &analysis))
 }
 
 #[tauri::command]
@@ -590,11 +847,12 @@ pub fn db_update_picked_color(
 }
 
 #[tauri::command]
-pub fn db_import_data(
+pub async fn db_import_data(
     state: State<'_, DbState>,
     items: Vec<ImportItem>,
 ) -> Result<(), AppError> {
-    super::with_conn_mut(&state, |conn| import_data(conn, &items))
+    let analysis = AssetAnalysis::compute(items.iter().map(|item| item.text.clone()).collect()).await;
+    super::with_conn_mut(&state, |conn| import_data(conn, &items, &analysis))
 }
 
 /// 将剪贴板快照写入数据库并返回完整 ClipItem
@@ -602,12 +860,19 @@ pub fn db_import_data(
 /// 由前端 `useClipboard` 在收到 `ClipboardSnapshot` 后调用，
 /// 替代原来的 `captureClipboardSnapshot + addClipAndGet` 两步调用。
 #[tauri::command]
-pub fn db_add_clip_snapshot(
+pub async fn db_add_clip_snapshot(
     state: State<'_, DbState>,
     snapshot: SnapshotInput,
 ) -> Result<Option<ClipItem>, AppError> {
+    let analysis = AssetAnalysis::compute(
+        [snapshot.text.clone(), snapshot.image_path.clone()]
+            .into_iter()
+            .flatten()
+            .collect(),
+    )
+    .await;
     super::with_conn_mut(&state, |conn| {
-        match add_clip_snapshot(conn, snapshot)? {
+        match add_clip_snapshot(conn, snapshot, &analysis)? {
             Some(id) => get_clip_by_id(conn, id),
             None => Ok(None),
         }
@@ -631,7 +896,7 @@ fn update_clip_format(conn: &Connection, id: i64, format: &str, content: &str) -
         "UPDATE clip_formats SET content = ?1 WHERE item_id = ?2 AND format = ?3",
         params![content, id, format],
     )
-    .map_err(|e| db_err(&format!("更新格式 {} 失败", format), e))?;
+    .map_err(|e| db_err_with("db.update_format", &[("format", &format)], e))?;
     Ok(())
 }
 
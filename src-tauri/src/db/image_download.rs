@@ -0,0 +1,206 @@
+//! 链接图片转存子模块
+//!
+//! ## 职责
+//! - 下载 HTTP/HTTPS 图片链接并保存为本地图片条目，原链接以 `source_url` 格式保留
+//! - 传入条目 ID 时原地转换该条目，传入链接时新建图片条目
+//!
+//! ## 设计决策
+//! - 下载交给 `ImageServiceState` 的下载队列：与复制链接图片共用 SSRF 防护、域名规则、
+//!   下载缓存、并发上限与进度事件
+//! - GIF / WebP / PNG / JPEG 保留原始字节，其他格式转码为 PNG；按内容寻址写入图片目录，
+//!   并遵循“移除图片 EXIF”设置
+//! - 原地转换保留条目 ID、时间、置顶/收藏与标签，只替换正文并改为 `image` 类型；
+//!   资源映射、主色调、语言与子类型同步与剪贴板图片一致，旧的链接预览、语言与子类型被清除
+//! - 条目改写（或新建）与全部派生数据在同一事务中写入，失败时不留下半转换的条目
+//! - 下载、转码与写文件期间不持有数据库锁
+//!
+//! ## 输入/输出
+//! - 输入：`State<DbState>`、`State<ImageServiceState>`、条目 ID 或链接、可选请求 ID 与图片目录
+//! - 输出：转换后或新建的 `ClipItem`
+//!
+//! ## 错误语义
+//! - 条目不存在时返回 `AppError::NotFound`；已是图片或正文不是 HTTP/HTTPS 链接时返回
+//!   `AppError::InvalidRequest`
+//! - 网络、SSRF 校验、格式与资源上限错误透传为 `AppError::Image`
+//! - SQL 操作失败返回 `AppError::Database`
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use rusqlite::{params, Connection, OptionalExtension};
+use tauri::{AppHandle, State};
+
+use crate::clipboard::save::write_stored_image;
+use crate::error::AppError;
+use crate::i18n::message;
+use crate::image_handler::ImageServiceState;
+use crate::storage::get_images_dir;
+
+use super::cleanup::AssetAnalysis;
+use super::history::{get_clip_by_id, insert_formats, sync_item_language, sync_item_subtype};
+use super::{db_err, ClipItem, DbState};
+
+/// 转存条目记录原始链接的格式名
+pub(crate) const SOURCE_URL_FORMAT_KEY: &str = "source_url";
+
+/// 未指定请求 ID 时的序号（与时间戳组合，避免同一毫秒内重复）
+static REQUEST_SEQ: AtomicU64 = AtomicU64::new(0);
+
+/// 转存目标
+#[derive(Debug, PartialEq, Eq)]
+enum SaveTarget {
+    /// 原地转换已有条目
+    Item { id: i64, url: String },
+    /// 新建图片条目
+    NewClip { url: String },
+}
+
+/// 将文本解析为 HTTP/HTTPS 链接
+fn image_url(text: &str) -> Option<String> {
+    let text = text.trim();
+    if text.is_empty() || text.contains(char::is_whitespace) {
+        return None;
+    }
+    let url = reqwest::Url::parse(text).ok()?;
+    matches!(url.scheme(), "http" | "https").then(|| url.to_string())
+}
+
+/// 解析 `url_or_id`：纯数字按条目 ID 处理，否则按链接处理
+fn resolve_target(conn: &Connection, url_or_id: &str) -> Result<SaveTarget, AppError> {
+    let Ok(id) = url_or_id.trim().parse::<i64>() else {
+        return image_url(url_or_id)
+            .map(|url| SaveTarget::NewClip { url })
+            .ok_or_else(|| AppError::InvalidRequest(message("db.download_requires_http_url", &[])));
+    };
+
+    let row: Option<(String, Option<String>)> = conn
+        .query_row(
+            "SELECT text, content_type FROM history WHERE id = ?1",
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+        .map_err(|e| db_err("db.read_download_item", e))?;
+
+    let Some((text, content_type)) = row else {
+        return Err(AppError::NotFound(message("db.item_not_found", &[("id", &id)])));
+    };
+    if content_type.as_deref() == Some("image") {
+        return Err(AppError::InvalidRequest(message("db.item_already_image", &[("id", &id)])));
+    }
+    image_url(&text)
+        .map(|url| SaveTarget::Item { id, url })
+        .ok_or_else(|| AppError::InvalidRequest(message("db.item_not_image_link", &[("id", &id)])))
+}
+
+/// 同步图片条目的派生数据并写入 `image` / `source_url` 格式
+fn finish_image_clip(
+    conn: &Connection,
+    id: i64,
+    path: &str,
+    url: &str,
+    analysis: &AssetAnalysis,
+) -> Result<(), AppError> {
+    super::cleanup::sync_item_assets_for_text(conn, id, path, analysis)?;
+    super::color::sync_item_color(conn, id, path)?;
+    sync_item_language(conn, id, path)?;
+    sync_item_subtype(conn, id, path)?;
+    insert_formats(conn, id, &[("image", path), (SOURCE_URL_FORMAT_KEY, url)])
+}
+
+/// 将链接条目原地转换为图片条目
+///
+/// 语言与子类型只对文本条目同步，改为图片后需显式清空，否则会残留链接文本的识别结果。
+fn convert_to_image_clip(
+    conn: &Connection,
+    id: i64,
+    path: &str,
+    url: &str,
+    analysis: &AssetAnalysis,
+) -> Result<(), AppError> {
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| db_err("db.begin_convert_tx", e))?;
+    let updated = tx
+        .execute(
+            "UPDATE history SET text = ?1, content_type = 'image',
+                language = NULL, language_confidence = NULL, content_subtype = NULL
+             WHERE id = ?2",
+            params![path, id],
+        )
+        .map_err(|e| db_err("db.convert_image_item", e))?;
+    if updated == 0 {
+        return Err(AppError::NotFound(message("db.item_not_found", &[("id", &id)])));
+    }
+    super::link_preview::clear_link_preview(&tx, id)?;
+    finish_image_clip(&tx, id, path, url, analysis)?;
+    tx.commit().map_err(|e| db_err("db.commit_convert_tx", e))
+}
+
+/// 新建转存图片条目，返回新条目 ID
+fn insert_downloaded_clip(
+    conn: &Connection,
+    path: &str,
+    url: &str,
+    now: i64,
+    analysis: &AssetAnalysis,
+) -> Result<i64, AppError> {
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| db_err("db.begin_download_tx", e))?;
+    tx.execute(
+        "INSERT INTO history (text, timestamp, is_pinned, is_snippet, content_type) VALUES (?1, ?2, 0, 0, 'image')",
+        params![path, now],
+    )
+    .map_err(|e| db_err("db.insert_downloaded_image", e))?;
+    let id = tx.last_insert_rowid();
+    finish_image_clip(&tx, id, path, url, analysis)?;
+    tx.commit()
+        .map_err(|e| db_err("db.commit_download_tx", e))?;
+    Ok(id)
+}
+
+// ── 命令 ─────────────────────────────────────────────────────
+
+/// 下载图片链接并保存为本地图片条目
+///
+/// `url_or_id` 为条目 ID 时原地转换该条目，为链接时新建条目。
+/// 下载进度经 `image-download-progress` 事件上报（`request_id` 省略时自动生成）。
+#[tauri::command]
+pub async fn download_image_to_history(
+    app: AppHandle,
+    state: State<'_, DbState>,
+    images: State<'_, ImageServiceState>,
+    url_or_id: String,
+    request_id: Option<String>,
+    custom_dir: Option<String>,
+) -> Result<ClipItem, AppError> {
+    let target = super::with_read_conn(&state, |conn| resolve_target(conn, &url_or_id))?;
+    let url = match &target {
+        SaveTarget::Item { url, .. } | SaveTarget::NewClip { url } => url.clone(),
+    };
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let request_id = request_id.unwrap_or_else(|| {
+        format!("history-{}-{}", now, REQUEST_SEQ.fetch_add(1, Ordering::Relaxed))
+    });
+    let (bytes, extension) = images.download_url_for_storage(&app, request_id, url.clone(), 0).await?;
+    let path = write_stored_image(&get_images_dir(&app, custom_dir)?, bytes, extension)?;
+    let path = path.to_string_lossy().to_string();
+    let analysis = AssetAnalysis::compute(vec![path.clone()]).await;
+
+    super::with_conn_mut(&state, |conn| {
+        let id = match target {
+            SaveTarget::Item { id, .. } => {
+                convert_to_image_clip(conn, id, &path, &url, &analysis)?;
+                id
+            }
+            SaveTarget::NewClip { .. } => insert_downloaded_clip(conn, &path, &url, now, &analysis)?,
+        };
+        get_clip_by_id(conn, id)?
+            .ok_or_else(|| AppError::Database(message("db.downloaded_item_missing", &[("id", &id)])))
+    })
+}
+
+#[cfg(test)]
+#[path = "tests/image_download_tests.rs"]
+mod tests;
@@ -0,0 +1,123 @@
+//! 图片编辑子模块
+//!
+//! ## 职责
+//! - 对图片条目应用编辑操作（裁剪、旋转/翻转、缩放、马赛克/模糊、矩形/箭头/文字标注）
+//! - 将结果另存为新的图片条目，并记录与原条目的关联
+//!
+//! ## 设计决策
+//! - 非破坏式：只读取原图文件，结果按内容寻址写入图片目录，原条目与原文件保持不变
+//! - 新条目与剪贴板图片快照一致：`history.text` 为图片路径，`clip_formats` 记录 `image`，
+//!   资源映射、图片元数据与主色调同步同样执行
+//! - 关联关系存入 `clip_formats`，格式名 `edited_from`，内容为原条目 ID，无需迁移
+//! - 解码、编辑与写文件期间不持有数据库锁；新条目及其关联数据在同一事务中写入
+//!
+//! ## 输入/输出
+//! - 输入：`State<DbState>`、`State<ImageServiceState>`、条目 ID、操作列表、可选图片目录
+//! - 输出：新建的 `ClipItem`
+//!
+//! ## 错误语义
+//! - 条目不存在时返回 `AppError::NotFound`，不是图片或只有 SVG 时返回 `AppError::InvalidRequest`
+//! - 操作参数、解码与资源上限错误透传为 `AppError::Image`
+//! - SQL 操作失败返回 `AppError::Database`
+
+use rusqlite::{params, Connection, OptionalExtension};
+use tauri::{AppHandle, State};
+
+use crate::clipboard::save::write_stored_image;
+use crate::error::AppError;
+use crate::i18n::message;
+use crate::image_handler::{ImageEditOp, ImageServiceState};
+use crate::storage::get_images_dir;
+
+use super::cleanup::{extract_generated_asset_paths, AssetAnalysis};
+use super::history::{get_clip_by_id, insert_formats, sync_item_language, sync_item_subtype};
+use super::image_meta::pick_raster_path;
+use super::{db_err, ClipItem, DbState};
+
+/// 编辑结果指向原条目的格式名
+pub(crate) const EDITED_FROM_FORMAT_KEY: &str = "edited_from";
+
+/// 读取待编辑条目的位图路径
+fn load_edit_source(conn: &Connection, id: i64) -> Result<String, AppError> {
+    let row: Option<(String, Option<String>)> = conn
+        .query_row(
+            "SELECT text, content_type FROM history WHERE id = ?1",
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+        .map_err(|e| db_err("db.read_edit_item", e))?;
+
+    let Some((text, content_type)) = row else {
+        return Err(AppError::NotFound(message("db.item_not_found", &[("id", &id)])));
+    };
+    if content_type.as_deref() != Some("image") {
+        return Err(AppError::InvalidRequest(message("db.item_not_image", &[("id", &id)])));
+    }
+    pick_raster_path(&extract_generated_asset_paths(&text))
+        .map(|path| path.to_string_lossy().to_string())
+        .ok_or_else(|| AppError::InvalidRequest(message("db.item_has_no_bitmap", &[("id", &id)])))
+}
+
+/// 写入编辑结果条目，返回新条目 ID
+///
+/// 条目、资源映射与附加格式在同一事务中写入，任一步失败都不会留下半成品条目。
+fn insert_edited_clip(
+    conn: &Connection,
+    source_id: i64,
+    path: &str,
+    now: i64,
+    analysis: &AssetAnalysis,
+) -> Result<i64, AppError> {
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| db_err("db.begin_edit_tx", e))?;
+    tx.execute(
+        "INSERT INTO history (text, timestamp, is_pinned, is_snippet, content_type) VALUES (?1, ?2, 0, 0, 'image')",
+        params![path, now],
+    )
+    .map_err(|e| db_err("db.insert_edited_clip", e))?;
+    let id = tx.last_insert_rowid();
+
+    super::cleanup::sync_item_assets_for_text(&tx, id, path, analysis)?;
+    super::color::sync_item_color(&tx, id, path)?;
+    sync_item_language(&tx, id, path)?;
+    sync_item_subtype(&tx, id, path)?;
+
+    let source = source_id.to_string();
+    insert_formats(&tx, id, &[("image", path), (EDITED_FROM_FORMAT_KEY, &source)])?;
+    tx.commit()
+        .map_err(|e| db_err("db.commit_edit_tx", e))?;
+    Ok(id)
+}
+
+// ── 命令 ─────────────────────────────────────────────────────
+
+/// 编辑图片条目并另存为新条目（原条目不变）
+#[tauri::command]
+pub async fn edit_image(
+    app: AppHandle,
+    state: State<'_, DbState>,
+    images: State<'_, ImageServiceState>,
+    id: i64,
+    ops: Vec<ImageEditOp>,
+    custom_dir: Option<String>,
+) -> Result<ClipItem, AppError> {
+    let source = super::with_read_conn(&state, |conn| load_edit_source(conn, id))?;
+
+    let png = images.edit_image_file(&source, ops).await?;
+    let path = write_stored_image(&get_images_dir(&app, custom_dir)?, png, "png")?;
+    let path = path.to_string_lossy().to_string();
+    let analysis = AssetAnalysis::compute(vec![path.clone()]).await;
+
+    let now = chrono::Utc::now().timestamp_millis();
+    super::with_conn_mut(&state, |conn| {
+        let new_id = insert_edited_clip(conn, id, &path, now, &analysis)?;
+        get_clip_by_id(conn, new_id)?
+            .ok_or_else(|| AppError::Database(message("db.edited_item_missing", &[("id", &new_id)])))
+    })
+}
+
+#[cfg(test)]
+#[path = "tests/image_edit_tests.rs"]
+mod tests;
@@ -0,0 +1,163 @@
+//! 图片元数据子模块
+//!
+//! ## 职责
+//! - 条目入库/导入时提取其图片资源的元数据，写入 `image_metadata` 表
+//! - 批量加载元数据，随 `ClipItem` 一并返回给前端
+//!
+//! ## 设计决策
+//! - 每个条目一行（以首个图片资源为准），条目删除时由外键级联清理
+//! - 文本块以 JSON 对象存储，其余字段独立成列，便于按尺寸筛选
+//!
+//! ## 输入/输出
+//! - 输入：`Connection`、条目 ID、资源路径集合
+//! - 输出：`Result<(), AppError>` 或 `HashMap<i64, ImageMetadata>`
+//!
+//! ## 错误语义
+//! - SQL 操作失败返回 `AppError::Database`
+//! - 图片无法识别时不写入元数据，不报错
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::PathBuf;
+
+use rusqlite::{params, params_from_iter, Connection};
+
+use crate::error::AppError;
+use crate::image_handler::{extract_image_metadata, ImageMetadata};
+
+use super::{db_err, sql_placeholders};
+
+/// 用固定顺序选取条目的代表图片（SVG 无像素尺寸，不参与）
+pub(super) fn pick_raster_path(paths: &HashSet<PathBuf>) -> Option<&PathBuf> {
+    let mut candidates: Vec<&PathBuf> = paths
+        .iter()
+        .filter(|p| {
+            p.extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| !e.eq_ignore_ascii_case("svg"))
+        })
+        .collect();
+    candidates.sort();
+    candidates.into_iter().next()
+}
+
+/// 将条目的图片元数据同步为 `paths` 中代表图片的元数据
+pub(crate) fn sync_item_image_metadata(
+    conn: &Connection,
+    item_id: i64,
+    paths: &HashSet<PathBuf>,
+) -> Result<(), AppError> {
+    conn.execute("DELETE FROM image_metadata WHERE item_id = ?1", params![item_id])
+        .map_err(|e| db_err("db.delete_image_meta", e))?;
+
+    let Some(meta) = pick_raster_path(paths).and_then(|path| extract_image_metadata(path)) else {
+        return Ok(());
+    };
+    store_image_metadata(conn, item_id, &meta)
+}
+
+pub(crate) fn store_image_metadata(
+    conn: &Connection,
+    item_id: i64,
+    meta: &ImageMetadata,
+) -> Result<(), AppError> {
+    let text_chunks = if meta.text_chunks.is_empty() {
+        None
+    } else {
+        serde_json::to_string(&meta.text_chunks).ok()
+    };
+    conn.execute(
+        "INSERT OR REPLACE INTO image_metadata (
+            item_id, width, height, color_type, byte_size,
+            camera_make, camera_model, orientation, captured_at, has_gps, text_chunks
+         ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        params![
+            item_id,
+            meta.width,
+            meta.height,
+            meta.color_type,
+            meta.byte_size as i64,
+            meta.camera_make,
+            meta.camera_model,
+            meta.orientation,
+            meta.captured_at,
+            meta.has_gps as i32,
+            text_chunks,
+        ],
+    )
+    .map_err(|e| db_err("db.write_image_meta", e))?;
+    Ok(())
+}
+
+/// 批量加载指定条目的图片元数据
+pub(crate) fn load_image_metadata_batch(
+    conn: &Connection,
+    ids: &[i64],
+) -> Result<HashMap<i64, ImageMetadata>, AppError> {
+    if ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let sql = format!(
+        "SELECT item_id, width, height, color_type, byte_size,
+                camera_make, camera_model, orientation, captured_at, has_gps, text_chunks
+         FROM image_metadata WHERE item_id IN ({})",
+        sql_placeholders(ids.len())
+    );
+    let mut stmt = conn.prepare(&sql).map_err(|e| db_err("db.prepare_image_meta", e))?;
+    let rows = stmt
+        .query_map(params_from_iter(ids.iter()), |row| {
+            let text_chunks: Option<String> = row.get(10)?;
+            Ok((
+                row.get::<_, i64>(0)?,
+                ImageMetadata {
+                    width: row.get(1)?,
+                    height: row.get(2)?,
+                    color_type: row.get(3)?,
+                    byte_size: row.get::<_, i64>(4)?.max(0) as u64,
+                    camera_make: row.get(5)?,
+                    camera_model: row.get(6)?,
+                    orientation: row.get(7)?,
+                    captured_at: row.get(8)?,
+                    has_gps: row.get::<_, i32>(9)? != 0,
+                    text_chunks: text_chunks
+                        .and_then(|json| serde_json::from_str::<BTreeMap<String, String>>(&json).ok())
+                        .unwrap_or_default(),
+                },
+            ))
+        })
+        .map_err(|e| db_err("db.query_image_meta", e))?;
+
+    let mut map = HashMap::new();
+    for row in rows {
+        let (item_id, meta) = row.map_err(|e| db_err("db.read_image_meta", e))?;
+        map.insert(item_id, meta);
+    }
+    Ok(map)
+}
+
+/// 为已有图片条目回填元数据（Schema 迁移时调用）
+pub(crate) fn backfill_image_metadata(conn: &Connection) -> Result<(), AppError> {
+    let rows: Vec<(i64, String)> = {
+        let mut stmt = conn
+            .prepare("SELECT item_id, path FROM history_assets ORDER BY item_id, path")
+            .map_err(|e| db_err("db.prepare_image_meta_backfill", e))?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))
+            .map_err(|e| db_err("db.query_image_meta_backfill", e))?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| db_err("db.read_image_meta_backfill", e))?
+    };
+
+    let mut by_item: HashMap<i64, HashSet<PathBuf>> = HashMap::new();
+    for (item_id, path) in rows {
+        by_item.entry(item_id).or_default().insert(PathBuf::from(path));
+    }
+    for (item_id, paths) in &by_item {
+        sync_item_image_metadata(conn, *item_id, paths)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "tests/image_meta_tests.rs"]
+mod tests;
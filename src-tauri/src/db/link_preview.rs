@@ -0,0 +1,181 @@
+//! 链接预览子模块
+//!
+//! ## 职责
+//! - 为链接条目抓取网页预览（标题、描述、图标、`og:image`），结果写入 `clip_formats`
+//! - 复用已缓存的预览，避免重复请求同一页面
+//!
+//! ## 设计决策
+//! - 复用 `clip_formats` 存储 JSON，格式名为 `link_preview`，无需新增表和迁移
+//! - 预览在 `LINK_PREVIEW_TTL_MS` 内视为新鲜；同一 URL 的其他条目已有新鲜预览时直接复制，
+//!   `force = true` 时忽略缓存重新抓取
+//! - 网络请求交给 `ImageServiceState`，与图片下载共用 SSRF 防护、超时与重定向上限；
+//!   抓取期间不持有数据库锁
+//! - 条目文本被编辑后清除旧预览，下次查看时重新抓取
+//!
+//! ## 输入/输出
+//! - 输入：`Connection` / `State<DbState>`、条目 ID
+//! - 输出：`Option<LinkPreview>`
+//!
+//! ## 错误语义
+//! - SQL 操作失败返回 `AppError::Database`
+//! - 网络、SSRF 校验与页面类型错误透传为 `AppError::Image`
+//! - 条目不存在或不是 HTTP/HTTPS 链接时返回 `None`，不视为错误
+
+use rusqlite::{params, Connection};
+use tauri::State;
+
+use crate::error::AppError;
+use crate::i18n::message;
+use crate::image_handler::{ImageServiceState, LinkPreview};
+
+use super::history::load_clip_text;
+use super::{db_err, DbState};
+
+/// 链接预览在 `clip_formats` 中的格式名
+pub(crate) const LINK_PREVIEW_FORMAT_KEY: &str = "link_preview";
+
+/// 缓存有效期：24 小时
+const LINK_PREVIEW_TTL_MS: i64 = 24 * 60 * 60 * 1000;
+
+// ── 目标地址 ─────────────────────────────────────────────────
+
+/// 将条目文本解析为可抓取的页面地址；`www.` 开头的文本补全为 HTTPS
+fn link_target(text: &str) -> Option<String> {
+    let text = text.trim();
+    if text.is_empty() || text.contains(char::is_whitespace) {
+        return None;
+    }
+    let candidate = if text.get(..4).is_some_and(|prefix| prefix.eq_ignore_ascii_case("www.")) {
+        format!("https://{}", text)
+    } else {
+        text.to_string()
+    };
+    let url = reqwest::Url::parse(&candidate).ok()?;
+    matches!(url.scheme(), "http" | "https").then(|| url.to_string())
+}
+
+// ── 缓存读写 ─────────────────────────────────────────────────
+
+fn is_fresh(preview: &LinkPreview, now: i64) -> bool {
+    now.saturating_sub(preview.fetched_at) < LINK_PREVIEW_TTL_MS
+}
+
+/// 查找可复用的新鲜预览：优先本条目，其次文本相同的其他条目
+///
+/// 返回预览及其所属条目 ID。
+fn find_cached_preview(
+    conn: &Connection,
+    item_id: i64,
+    text: &str,
+    now: i64,
+) -> Result<Option<(i64, LinkPreview)>, AppError> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT f.item_id, f.content FROM clip_formats f
+             JOIN history h ON h.id = f.item_id
+             WHERE f.format = ?1 AND (h.id = ?2 OR h.text = ?3)
+             ORDER BY (h.id = ?2) DESC, h.timestamp DESC",
+        )
+        .map_err(|e| db_err("db.prepare_link_preview", e))?;
+    let rows = stmt
+        .query_map(params![LINK_PREVIEW_FORMAT_KEY, item_id, text], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })
+        .map_err(|e| db_err("db.query_link_preview", e))?;
+    for row in rows {
+        let (owner_id, json) = row.map_err(|e| db_err("db.read_link_preview", e))?;
+        // 损坏的缓存视为不存在，重新抓取后覆盖
+        let preview = serde_json::from_str::<LinkPreview>(&json).ok();
+        if let Some(preview) = preview.filter(|p| is_fresh(p, now)) {
+            return Ok(Some((owner_id, preview)));
+        }
+    }
+    Ok(None)
+}
+
+/// 写入（覆盖）条目的链接预览
+pub(crate) fn store_link_preview(
+    conn: &Connection,
+    item_id: i64,
+    preview: &LinkPreview,
+) -> Result<(), AppError> {
+    let content = serde_json::to_string(preview).map_err(|e| AppError::Database(message("db.serialize_link_preview_failed", &[("detail", &e)])))?;
+    conn.execute(
+        "INSERT OR REPLACE INTO clip_formats (item_id, format, content) VALUES (?1, ?2, ?3)",
+        params![item_id, LINK_PREVIEW_FORMAT_KEY, content],
+    )
+    .map_err(|e| db_err("db.write_link_preview", e))?;
+    Ok(())
+}
+
+/// 条目文本变化后清除旧预览
+pub(super) fn clear_link_preview(conn: &Connection, item_id: i64) -> Result<(), AppError> {
+    conn.execute(
+        "DELETE FROM clip_formats WHERE item_id = ?1 AND format = ?2",
+        params![item_id, LINK_PREVIEW_FORMAT_KEY],
+    )
+    .map_err(|e| db_err("db.delete_link_preview", e))?;
+    Ok(())
+}
+
+/// 抓取前的准备结果
+enum PreviewLookup {
+    /// 条目不存在或不是链接
+    NotLink,
+    /// 命中缓存；`shared` 表示缓存来自文本相同的其他条目
+    Cached { preview: LinkPreview, shared: bool },
+    /// 需要抓取的页面地址
+    Fetch(String),
+}
+
+fn lookup_preview(
+    conn: &Connection,
+    item_id: i64,
+    force: bool,
+    now: i64,
+) -> Result<PreviewLookup, AppError> {
+    let Some(text) = load_clip_text(conn, item_id)? else {
+        return Ok(PreviewLookup::NotLink);
+    };
+    let Some(url) = link_target(&text) else {
+        return Ok(PreviewLookup::NotLink);
+    };
+    if force {
+        return Ok(PreviewLookup::Fetch(url));
+    }
+    Ok(match find_cached_preview(conn, item_id, &text, now)? {
+        Some((owner_id, preview)) => PreviewLookup::Cached { preview, shared: owner_id != item_id },
+        None => PreviewLookup::Fetch(url),
+    })
+}
+
+// ── 命令 ─────────────────────────────────────────────────────
+
+/// 获取链接条目的网页预览（必要时联网抓取并缓存）
+#[tauri::command]
+pub async fn fetch_link_preview(
+    state: State<'_, DbState>,
+    images: State<'_, ImageServiceState>,
+    id: i64,
+    force: Option<bool>,
+) -> Result<Option<LinkPreview>, AppError> {
+    let now = chrono::Utc::now().timestamp_millis();
+    let lookup = super::with_read_conn(&state, |conn| {
+        lookup_preview(conn, id, force.unwrap_or(false), now)
+    })?;
+
+    let preview = match lookup {
+        PreviewLookup::NotLink => return Ok(None),
+        PreviewLookup::Cached { preview, shared: false } => return Ok(Some(preview)),
+        // 来自其他条目的缓存同样写入本条目，之后按 ID 即可命中
+        PreviewLookup::Cached { preview, shared: true } => preview,
+        PreviewLookup::Fetch(url) => images.fetch_link_preview(&url).await?,
+    };
+
+    super::with_conn_mut(&state, |conn| store_link_preview(conn, id, &preview))?;
+    Ok(Some(preview))
+}
+
+#[cfg(test)]
+#[path = "tests/link_preview_tests.rs"]
+mod tests;
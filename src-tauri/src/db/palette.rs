@@ -0,0 +1,304 @@
+//! 图片主色调子模块
+//!
+//! ## 职责
+//! - 条目入库时提取代表图片的主色，写入 `image_palette` 表
+//! - 按条目查询主色（旧数据按需补算）
+//! - 按颜色搜索：查找主色中包含与目标颜色距离在阈值内的图片条目
+//!
+//! ## 设计决策
+//! - 每个条目最多保存 `DEFAULT_PALETTE_SIZE` 个主色，`rank` 按占比降序
+//! - 搜索在 Rust 侧计算 redmean 距离：主色行数少，避免在 SQL 中拼接浮点公式
+//! - 占比过低的点缀色不参与搜索，避免“图里有一个红点”也被匹配
+//! - 旧数据不在迁移中批量解码，查询前仅为已有 `image_metadata` 的条目补算
+//! - 提取主色需要完整解码，不在写锁内进行：入库路径在锁外生成 `AssetAnalysis`，
+//!   补算先用读连接取待算列表，解码后只在写锁内写入；失败记 `palette_failed`，不再重试
+//!
+//! ## 输入/输出
+//! - 输入：`Connection`、条目 ID、目标颜色（CSS 颜色字面量，见 `color::parse_rgb8`）与距离阈值
+//! - 输出：`Vec<PaletteColor>` / `Vec<ColorMatch>`
+//!
+//! ## 错误语义
+//! - SQL 操作失败返回 `AppError::Database`
+//! - 颜色值无法解析返回 `ImageError::InvalidFormat`
+//! - 图片无法解码时不写入主色，记为 `palette_failed`，不报错
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use tauri::State;
+
+use crate::color::parse_rgb8;
+use crate::error::AppError;
+use crate::i18n::message;
+use crate::image_handler::{color_distance, extract_palette_from_file, ImageError, PaletteColor};
+
+use super::cleanup::AssetAnalysis;
+use super::image_meta::pick_raster_path;
+use super::{db_err, DbState};
+
+/// 每个条目保存的主色数量
+const DEFAULT_PALETTE_SIZE: usize = 6;
+/// 默认颜色距离阈值（redmean，0 ~ 约 765）
+const DEFAULT_COLOR_THRESHOLD: f64 = 60.0;
+/// 参与按颜色搜索的最低占比
+const MIN_SEARCH_RATIO: f64 = 0.05;
+
+// ── 数据结构 ─────────────────────────────────────────────────
+
+/// 按颜色搜索的匹配结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ColorMatch {
+    pub id: i64,
+    /// 命中的主色（`#rrggbb`）
+    pub color: String,
+    /// 命中主色在图片中的占比
+    pub ratio: f32,
+    /// 与目标颜色的距离
+    pub distance: f64,
+}
+
+// ── 写入 ─────────────────────────────────────────────────────
+
+fn store_palette(conn: &Connection, item_id: i64, palette: &[PaletteColor]) -> Result<(), AppError> {
+    let mut stmt = conn
+        .prepare(
+            "INSERT OR REPLACE INTO image_palette (item_id, rank, color, r, g, b, ratio)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        )
+        .map_err(|e| db_err("db.prepare_palette_insert", e))?;
+    for (rank, color) in palette.iter().enumerate() {
+        stmt.execute(params![
+            item_id,
+            rank as i64,
+            color.hex,
+            color.r,
+            color.g,
+            color.b,
+            f64::from(color.ratio),
+        ])
+        .map_err(|e| db_err("db.write_palette", e))?;
+    }
+    Ok(())
+}
+
+/// 提取图片主色；无法解码时返回 `None`（不访问数据库，供锁外预计算）
+pub(crate) fn compute_palette(path: &Path) -> Option<Vec<PaletteColor>> {
+    match extract_palette_from_file(path, DEFAULT_PALETTE_SIZE) {
+        Ok(palette) => Some(palette),
+        Err(err) => {
+            log::warn!(
+                "{}",
+                message("db_log.palette_failed", &[("path", &path.display()), ("detail", &err)])
+            );
+            None
+        }
+    }
+}
+
+/// 写入主色计算结果；`None` 记为失败，之后不再补算
+fn store_palette_result(
+    conn: &Connection,
+    item_id: i64,
+    palette: Option<&[PaletteColor]>,
+) -> Result<(), AppError> {
+    match palette {
+        Some(palette) => store_palette(conn, item_id, palette),
+        None => {
+            conn.execute(
+                "UPDATE image_metadata SET palette_failed = 1 WHERE item_id = ?1",
+                params![item_id],
+            )
+            .map_err(|e| db_err("db.record_palette_failure", e))?;
+            Ok(())
+        }
+    }
+}
+
+/// 将条目的主色同步为 `paths` 中代表图片的主色
+///
+/// 只写入 `analysis` 中锁外预计算的结果；未预计算的图片留给查询时补算。
+pub(crate) fn sync_item_palette(
+    conn: &Connection,
+    item_id: i64,
+    paths: &HashSet<PathBuf>,
+    analysis: &AssetAnalysis,
+) -> Result<(), AppError> {
+    conn.execute("DELETE FROM image_palette WHERE item_id = ?1", params![item_id])
+        .map_err(|e| db_err("db.delete_palette", e))?;
+
+    let Some(path) = pick_raster_path(paths) else {
+        return Ok(());
+    };
+    match analysis.palettes.get(path) {
+        Some(palette) => store_palette_result(conn, item_id, palette.as_deref()),
+        None => Ok(()),
+    }
+}
+
+fn item_asset_paths(conn: &Connection, item_id: i64) -> Result<HashSet<PathBuf>, AppError> {
+    let mut stmt = conn
+        .prepare("SELECT path FROM history_assets WHERE item_id = ?1")
+        .map_err(|e| db_err("db.prepare_item_assets", e))?;
+    let rows = stmt
+        .query_map(params![item_id], |row| row.get::<_, String>(0))
+        .map_err(|e| db_err("db.query_item_assets", e))?;
+    rows.map(|row| row.map(PathBuf::from))
+        .collect::<Result<HashSet<_>, _>>()
+        .map_err(|e| db_err("db.read_item_assets", e))
+}
+
+/// 查询已有图片元数据、尚无主色且未失败过的条目及其代表图片
+///
+/// `item_id` 为 `Some` 时只查询该条目。
+fn pending_palette_items(conn: &Connection, item_id: Option<i64>) -> Result<Vec<(i64, PathBuf)>, AppError> {
+    let ids: Vec<i64> = {
+        let mut stmt = conn
+            .prepare(
+                "SELECT m.item_id FROM image_metadata m
+                 WHERE m.palette_failed = 0
+                   AND (?1 IS NULL OR m.item_id = ?1)
+                   AND NOT EXISTS (SELECT 1 FROM image_palette p WHERE p.item_id = m.item_id)",
+            )
+            .map_err(|e| db_err("db.prepare_missing_palette", e))?;
+        let rows = stmt
+            .query_map(params![item_id], |row| row.get::<_, i64>(0))
+            .map_err(|e| db_err("db.query_missing_palette", e))?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| db_err("db.read_missing_palette", e))?
+    };
+
+    let mut pending = Vec::with_capacity(ids.len());
+    for id in ids {
+        if let Some(path) = pick_raster_path(&item_asset_paths(conn, id)?) {
+            pending.push((id, path.clone()));
+        }
+    }
+    Ok(pending)
+}
+
+/// 在一个事务内写入一批主色结果
+fn store_palette_results(
+    conn: &mut Connection,
+    results: &[(i64, Option<Vec<PaletteColor>>)],
+) -> Result<(), AppError> {
+    let tx = conn
+        .transaction()
+        .map_err(|e| db_err("db.begin_tx", e))?;
+    for (item_id, palette) in results {
+        store_palette_result(&tx, *item_id, palette.as_deref())?;
+    }
+    tx.commit().map_err(|e| db_err("db.commit_tx", e))
+}
+
+/// 补算旧条目缺失的主色
+///
+/// 读连接取待算列表 → 不持锁解码 → 写锁内批量写入，解码期间不阻塞其他写操作。
+fn backfill_missing_palettes(state: &State<'_, DbState>, item_id: Option<i64>) -> Result<(), AppError> {
+    let pending = super::with_read_conn(state, |conn| pending_palette_items(conn, item_id))?;
+    if pending.is_empty() {
+        return Ok(());
+    }
+    let results: Vec<(i64, Option<Vec<PaletteColor>>)> = pending
+        .into_iter()
+        .map(|(id, path)| (id, compute_palette(&path)))
+        .collect();
+    super::with_conn_mut(state, |conn| store_palette_results(conn, &results))
+}
+
+// ── 查询 ─────────────────────────────────────────────────────
+
+pub(crate) fn load_palette(conn: &Connection, item_id: i64) -> Result<Vec<PaletteColor>, AppError> {
+    let mut stmt = conn
+        .prepare("SELECT r, g, b, ratio FROM image_palette WHERE item_id = ?1 ORDER BY rank")
+        .map_err(|e| db_err("db.prepare_palette", e))?;
+    let rows = stmt
+        .query_map(params![item_id], |row| {
+            Ok(PaletteColor::from_rgb(
+                [row.get(0)?, row.get(1)?, row.get(2)?],
+                row.get::<_, f64>(3)? as f32,
+            ))
+        })
+        .map_err(|e| db_err("db.query_palette", e))?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| db_err("db.read_palette", e))
+}
+
+/// 查找主色中包含与 `target` 距离不超过 `threshold` 的图片条目（按距离升序）
+pub(crate) fn search_by_color(
+    conn: &Connection,
+    target: [u8; 3],
+    threshold: f64,
+) -> Result<Vec<ColorMatch>, AppError> {
+    let mut stmt = conn
+        .prepare("SELECT item_id, r, g, b, ratio FROM image_palette WHERE ratio >= ?1")
+        .map_err(|e| db_err("db.prepare_color_search", e))?;
+    let rows = stmt
+        .query_map(params![MIN_SEARCH_RATIO], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                PaletteColor::from_rgb([row.get(1)?, row.get(2)?, row.get(3)?], row.get::<_, f64>(4)? as f32),
+            ))
+        })
+        .map_err(|e| db_err("db.search_by_color", e))?;
+
+    let mut best: HashMap<i64, ColorMatch> = HashMap::new();
+    for row in rows {
+        let (id, color) = row.map_err(|e| db_err("db.read_palette", e))?;
+        let distance = color_distance(target, color.rgb());
+        if distance > threshold {
+            continue;
+        }
+        let candidate = ColorMatch {
+            id,
+            color: color.hex,
+            ratio: color.ratio,
+            distance,
+        };
+        match best.get(&id) {
+            Some(existing) if existing.distance <= candidate.distance => {}
+            _ => {
+                best.insert(id, candidate);
+            }
+        }
+    }
+
+    let mut matches: Vec<ColorMatch> = best.into_values().collect();
+    matches.sort_by(|a, b| {
+        a.distance
+            .total_cmp(&b.distance)
+            .then_with(|| b.ratio.total_cmp(&a.ratio))
+            .then_with(|| b.id.cmp(&a.id))
+    });
+    Ok(matches)
+}
+
+// ── Tauri Commands ───────────────────────────────────────────
+
+/// 获取图片条目的主色（按占比降序）
+///
+/// 旧条目尚未计算时先在锁外补算。
+#[tauri::command]
+pub fn get_image_palette(state: State<'_, DbState>, id: i64) -> Result<Vec<PaletteColor>, AppError> {
+    backfill_missing_palettes(&state, Some(id))?;
+    super::with_read_conn(&state, |conn| load_palette(conn, id))
+}
+
+/// 按颜色搜索图片条目
+#[tauri::command]
+pub fn db_search_by_color(
+    state: State<'_, DbState>,
+    color: String,
+    threshold: Option<f64>,
+) -> Result<Vec<ColorMatch>, AppError> {
+    let target = parse_rgb8(&color)
+        .ok_or_else(|| ImageError::InvalidFormat(message("db.invalid_color", &[("color", &color)])))?;
+    let threshold = threshold.unwrap_or(DEFAULT_COLOR_THRESHOLD).clamp(0.0, 765.0);
+    backfill_missing_palettes(&state, None)?;
+    super::with_read_conn(&state, |conn| search_by_color(conn, target, threshold))
+}
+
+#[cfg(test)]
+#[path = "tests/palette_tests.rs"]
+mod tests;
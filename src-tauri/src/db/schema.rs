@@ -4,6 +4,7 @@
 //! - 创建/迁移数据库表结构与索引
 //! - 设置 SQLite 运行参数（WAL、外键）
 //! - 回填 `history_assets` 以兼容旧数据
+//! - 维护内容寻址资源表 `assets` 及其引用计数触发器
 //!
 //! ## 设计决策
 //!
@@ -27,21 +28,22 @@
 use rusqlite::Connection;
 
 use crate::error::AppError;
+use crate::i18n::message;
 
-use super::db_err;
+use super::{db_err, db_err_with};
 
-const SCHEMA_VERSION: i64 = 7;
+const SCHEMA_VERSION: i64 = 17;
 
 // ── 版本管理 ─────────────────────────────────────────────────
 
 fn get_user_version(conn: &Connection) -> Result<i64, AppError> {
     conn.query_row("PRAGMA user_version", [], |row| row.get(0))
-        .map_err(|e| db_err("读取数据库版本失败", e))
+        .map_err(|e| db_err("db.read_version", e))
 }
 
 fn set_user_version(conn: &Connection, version: i64) -> Result<(), AppError> {
     conn.execute_batch(&format!("PRAGMA user_version = {version};"))
-        .map_err(|e| db_err("写入数据库版本失败", e))
+        .map_err(|e| db_err("db.write_version", e))
 }
 
 // ── 外键安全 helper ──────────────────────────────────────────
@@ -55,12 +57,12 @@ fn with_fk_off(
     op: impl FnOnce(&Connection) -> Result<(), AppError>,
 ) -> Result<(), AppError> {
     conn.execute_batch("PRAGMA foreign_keys=OFF;")
-        .map_err(|e| db_err("关闭外键检查失败", e))?;
+        .map_err(|e| db_err("db.disable_foreign_keys", e))?;
     let result = op(conn);
     // 无论操作是否成功，都恢复 FK 检查
     let restore = conn
         .execute_batch("PRAGMA foreign_keys=ON;")
-        .map_err(|e| db_err("恢复外键检查失败", e));
+        .map_err(|e| db_err("db.enable_foreign_keys", e));
     result?;
     restore
 }
@@ -88,24 +90,24 @@ fn rebuild_table(conn: &Connection, spec: &TableRebuildSpec) -> Result<(), AppEr
     with_fk_off(conn, |conn| {
         let tx = conn
             .unchecked_transaction()
-            .map_err(|e| db_err(&format!("开始 {} 重建事务失败", spec.name), e))?;
+            .map_err(|e| db_err_with("db.rebuild_begin_tx", &[("table", &spec.name)], e))?;
         tx.execute_batch(spec.create_ddl)
-            .map_err(|e| db_err(&format!("创建 {}_new 表失败", spec.name), e))?;
+            .map_err(|e| db_err_with("db.rebuild_create_table", &[("table", &spec.name)], e))?;
         tx.execute_batch(spec.copy_sql)
-            .map_err(|e| db_err(&format!("迁移 {} 数据失败", spec.name), e))?;
+            .map_err(|e| db_err_with("db.rebuild_copy_rows", &[("table", &spec.name)], e))?;
         tx.execute_batch(&format!("DROP TABLE IF EXISTS {};", spec.name))
-            .map_err(|e| db_err(&format!("删除旧 {} 表失败", spec.name), e))?;
+            .map_err(|e| db_err_with("db.rebuild_drop_table", &[("table", &spec.name)], e))?;
         tx.execute_batch(&format!(
             "ALTER TABLE {name}_new RENAME TO {name};",
             name = spec.name,
         ))
-        .map_err(|e| db_err(&format!("重命名 {}_new 失败", spec.name), e))?;
+        .map_err(|e| db_err_with("db.rebuild_rename_table", &[("table", &spec.name)], e))?;
         tx.commit()
-            .map_err(|e| db_err(&format!("提交 {} 重建事务失败", spec.name), e))?;
+            .map_err(|e| db_err_with("db.rebuild_commit_tx", &[("table", &spec.name)], e))?;
         Ok(())
     })?;
     conn.execute_batch(spec.index_ddl)
-        .map_err(|e| db_err(&format!("创建 {} 索引失败", spec.name), e))
+        .map_err(|e| db_err_with("db.rebuild_create_indexes", &[("table", &spec.name)], e))
 }
 
 const HISTORY_ASSETS_SPEC: TableRebuildSpec = TableRebuildSpec {
@@ -143,6 +145,23 @@ const ITEM_TAGS_SPEC: TableRebuildSpec = TableRebuildSpec {
          CREATE INDEX IF NOT EXISTS idx_item_tags_tag_id ON item_tags(tag_id);",
 };
 
+const CLIP_FORMATS_SPEC: TableRebuildSpec = TableRebuildSpec {
+    name: "clip_formats",
+    create_ddl:
+        "CREATE TABLE IF NOT EXISTS clip_formats_new (
+            item_id INTEGER NOT NULL,
+            format  TEXT NOT NULL,
+            content TEXT NOT NULL,
+            PRIMARY KEY (item_id, format),
+            FOREIGN KEY (item_id) REFERENCES history(id) ON DELETE CASCADE
+        );",
+    copy_sql:
+        "INSERT OR IGNORE INTO clip_formats_new (item_id, format, content)
+            SELECT item_id, format, content FROM clip_formats;",
+    index_ddl:
+        "CREATE INDEX IF NOT EXISTS idx_clip_formats_item_id ON clip_formats(item_id);",
+};
+
 // ── 基础表创建 ───────────────────────────────────────────────
 
 /// 补充可能缺失的历史表列（兼容旧版数据库）
@@ -168,7 +187,7 @@ fn create_history_indexes(conn: &Connection) -> Result<(), AppError> {
         "CREATE INDEX IF NOT EXISTS idx_history_timestamp ON history(timestamp);
          CREATE INDEX IF NOT EXISTS idx_history_pinned_timestamp ON history(is_pinned, timestamp DESC);
          CREATE INDEX IF NOT EXISTS idx_history_favorite_timestamp ON history(is_favorite, timestamp DESC);"
-    ).map_err(|e| db_err("创建历史索引失败", e))
+    ).map_err(|e| db_err("db.create_history_indexes", e))
 }
 
 fn create_base_tables(conn: &Connection) -> Result<(), AppError> {
@@ -201,7 +220,7 @@ fn create_base_tables(conn: &Connection) -> Result<(), AppError> {
             PRIMARY KEY (item_id, format),
             FOREIGN KEY (item_id) REFERENCES history(id) ON DELETE CASCADE
         );"
-    ).map_err(|e| db_err("创建基础表失败", e))?;
+    ).map_err(|e| db_err("db.create_base_tables", e))?;
 
     ensure_history_columns(conn);
     create_history_indexes(conn)?;
@@ -210,7 +229,7 @@ fn create_base_tables(conn: &Connection) -> Result<(), AppError> {
         "CREATE INDEX IF NOT EXISTS idx_item_tags_item_id ON item_tags(item_id);
          CREATE INDEX IF NOT EXISTS idx_item_tags_tag_id ON item_tags(tag_id);
          CREATE INDEX IF NOT EXISTS idx_clip_formats_item_id ON clip_formats(item_id);"
-    ).map_err(|e| db_err("创建基础索引失败", e))?;
+    ).map_err(|e| db_err("db.create_base_indexes", e))?;
 
     Ok(())
 }
@@ -225,7 +244,70 @@ fn create_history_assets_table(conn: &Connection) -> Result<(), AppError> {
         );
         CREATE INDEX IF NOT EXISTS idx_history_assets_item_id ON history_assets(item_id);
         CREATE INDEX IF NOT EXISTS idx_history_assets_path ON history_assets(path);"
-    ).map_err(|e| db_err("创建历史资源映射失败", e))
+    ).map_err(|e| db_err("db.create_history_assets_table", e))
+}
+
+fn create_assets_table(conn: &Connection) -> Result<(), AppError> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS assets (
+            hash TEXT PRIMARY KEY,
+            path TEXT NOT NULL,
+            mime TEXT NOT NULL,
+            byte_size INTEGER NOT NULL,
+            width INTEGER,
+            height INTEGER,
+            refcount INTEGER NOT NULL DEFAULT 0,
+            created_at INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS idx_assets_path ON assets(path);"
+    ).map_err(|e| db_err("db.create_assets_table", e))?;
+
+    // 列已存在时忽略（同 `ensure_history_columns`）
+    let _ = conn.execute("ALTER TABLE history_assets ADD COLUMN hash TEXT REFERENCES assets(hash)", []);
+    conn.execute_batch("CREATE INDEX IF NOT EXISTS idx_history_assets_hash ON history_assets(hash);")
+        .map_err(|e| db_err("db.create_asset_hash_index", e))
+}
+
+/// `history_assets` 增删改时自动维护 `assets.refcount`
+fn create_asset_refcount_triggers(conn: &Connection) -> Result<(), AppError> {
+    conn.execute_batch(
+        "CREATE TRIGGER IF NOT EXISTS trg_history_assets_ref_insert
+         AFTER INSERT ON history_assets WHEN NEW.hash IS NOT NULL
+         BEGIN
+            UPDATE assets SET refcount = refcount + 1 WHERE hash = NEW.hash;
+         END;
+         CREATE TRIGGER IF NOT EXISTS trg_history_assets_ref_delete
+         AFTER DELETE ON history_assets WHEN OLD.hash IS NOT NULL
+         BEGIN
+            UPDATE assets SET refcount = refcount - 1 WHERE hash = OLD.hash;
+         END;
+         CREATE TRIGGER IF NOT EXISTS trg_history_assets_ref_update
+         AFTER UPDATE OF hash ON history_assets
+         BEGIN
+            UPDATE assets SET refcount = refcount - 1 WHERE OLD.hash IS NOT NULL AND hash = OLD.hash;
+            UPDATE assets SET refcount = refcount + 1 WHERE NEW.hash IS NOT NULL AND hash = NEW.hash;
+         END;"
+    ).map_err(|e| db_err("db.create_asset_ref_triggers", e))
+}
+
+fn create_image_metadata_table(conn: &Connection) -> Result<(), AppError> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS image_metadata (
+            item_id INTEGER PRIMARY KEY,
+            width INTEGER NOT NULL,
+            height INTEGER NOT NULL,
+            color_type TEXT NOT NULL,
+            byte_size INTEGER NOT NULL,
+            camera_make TEXT,
+            camera_model TEXT,
+            orientation INTEGER,
+            captured_at TEXT,
+            has_gps INTEGER NOT NULL DEFAULT 0,
+            text_chunks TEXT,
+            FOREIGN KEY (item_id) REFERENCES history(id) ON DELETE CASCADE
+        );
+        CREATE INDEX IF NOT EXISTS idx_image_metadata_size ON image_metadata(width, height);"
+    ).map_err(|e| db_err("db.create_image_meta_table", e))
 }
 
 // ── 迁移函数 ─────────────────────────────────────────────────
@@ -242,13 +324,13 @@ fn migrate_to_v3(conn: &Connection) -> Result<(), AppError> {
 
 /// v3 → v4: 重建 history 表，添加布尔列的 CHECK 约束
 ///
-/// `ALTER TABLE RENAME` 会重写 `history_assets` / `item_tags` 的 FK 引用为
-/// `REFERENCES history_old(id)`，所以在 DROP `history_old` 后必须重建这两张表。
+/// `ALTER TABLE RENAME` 会重写 `history_assets` / `item_tags` / `clip_formats` 的 FK 引用为
+/// `REFERENCES history_old(id)`，所以在 DROP `history_old` 后必须重建这些表。
 fn migrate_to_v4(conn: &Connection) -> Result<(), AppError> {
     with_fk_off(conn, |conn| {
         let tx = conn
             .unchecked_transaction()
-            .map_err(|e| db_err("开始 v4 迁移事务失败", e))?;
+            .map_err(|e| db_err("db.begin_v4_tx", e))?;
         tx.execute_batch(
             "ALTER TABLE history RENAME TO history_old;
              CREATE TABLE history (
@@ -269,14 +351,15 @@ fn migrate_to_v4(conn: &Connection) -> Result<(), AppError> {
                 picked_color
              FROM history_old;
              DROP TABLE history_old;"
-        ).map_err(|e| db_err("执行 v4 历史表迁移失败", e))?;
-        tx.commit().map_err(|e| db_err("提交 v4 迁移事务失败", e))?;
+        ).map_err(|e| db_err("db.run_v4_migration", e))?;
+        tx.commit().map_err(|e| db_err("db.commit_v4_tx", e))?;
         Ok(())
     })?;
     create_history_indexes(conn)?;
     // RENAME 会破坏关联表外键，必须重建
     rebuild_table(conn, &HISTORY_ASSETS_SPEC)?;
     rebuild_table(conn, &ITEM_TAGS_SPEC)?;
+    rebuild_table(conn, &CLIP_FORMATS_SPEC)?;
     Ok(())
 }
 
@@ -308,11 +391,155 @@ fn migrate_to_v7(conn: &Connection) -> Result<(), AppError> {
             FOREIGN KEY (item_id) REFERENCES history(id) ON DELETE CASCADE
         );
         CREATE INDEX IF NOT EXISTS idx_clip_formats_item_id ON clip_formats(item_id);"
-    ).map_err(|e| db_err("创建 clip_formats 表失败", e))?;
+    ).map_err(|e| db_err("db.create_clip_formats_table", e))?;
+
+    Ok(())
+}
+
+/// v7 → v8: 引入内容寻址资源表
+///
+/// - 新建 `assets` 表（哈希 → 路径/元数据/引用计数）
+/// - `history_assets` 新增 `hash` 列引用 `assets`
+/// - 为旧版 `img_*` / `svg_*` 文件计算哈希并合并重复内容
+/// - 计数回填完成后再挂触发器，避免迁移过程中重复计数
+fn migrate_to_v8(conn: &Connection) -> Result<(), AppError> {
+    create_assets_table(conn)?;
+    super::assets::dedup_existing_assets(conn)?;
+    create_asset_refcount_triggers(conn)
+}
+
+/// v8 → v9: `assets` 新增感知哈希列 `phash`（dHash，按位存为 INTEGER）
+///
+/// 旧资源不在迁移中批量解码，由相似图片查询按需补齐。
+fn migrate_to_v9(conn: &Connection) -> Result<(), AppError> {
+    let _ = conn.execute("ALTER TABLE assets ADD COLUMN phash INTEGER", []);
+    Ok(())
+}
+
+/// v9 → v10: 新建 `image_metadata` 表（每个图片条目一行）并回填已有图片
+fn migrate_to_v10(conn: &Connection) -> Result<(), AppError> {
+    create_image_metadata_table(conn)?;
+    super::image_meta::backfill_image_metadata(conn)
+}
+
+/// v10 → v11: 新建 `image_palette` 表
+///
+/// 主色需要完整解码，旧图片不在迁移中批量计算，由查询按需补齐。
+fn migrate_to_v11(conn: &Connection) -> Result<(), AppError> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS image_palette (
+            item_id INTEGER NOT NULL,
+            rank INTEGER NOT NULL,
+            color TEXT NOT NULL,
+            r INTEGER NOT NULL,
+            g INTEGER NOT NULL,
+            b INTEGER NOT NULL,
+            ratio REAL NOT NULL,
+            PRIMARY KEY (item_id, rank),
+            FOREIGN KEY (item_id) REFERENCES history(id) ON DELETE CASCADE
+        );"
+    ).map_err(|e| db_err("db.create_palette_table", e))
+}
+
+/// v11 → v12: 新建 `transform_chains` 表，保存用户自定义的文本转换链
+fn migrate_to_v12(conn: &Connection) -> Result<(), AppError> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS transform_chains (
+            name TEXT PRIMARY KEY,
+            steps TEXT NOT NULL,
+            updated_at INTEGER NOT NULL
+        );"
+    ).map_err(|e| db_err("db.create_transform_table", e))
+}
+
+/// 对全部文本 / 富文本条目执行一次同步函数（用于新增派生列后的回填）
+///
+/// `column` 为回填的列名，仅用于错误信息；所有写入在同一事务中完成。
+fn backfill_text_items(
+    conn: &Connection,
+    column: &str,
+    sync: fn(&Connection, i64, &str) -> Result<(), AppError>,
+) -> Result<(), AppError> {
+    let rows: Vec<(i64, String)> = {
+        let mut stmt = conn
+            .prepare("SELECT id, text FROM history WHERE content_type IN ('text', 'rich')")
+            .map_err(|e| db_err_with("db.backfill_prepare", &[("column", &column)], e))?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))
+            .map_err(|e| db_err_with("db.backfill_query", &[("column", &column)], e))?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| db_err_with("db.backfill_read", &[("column", &column)], e))?
+    };
+
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| db_err_with("db.backfill_begin_tx", &[("column", &column)], e))?;
+    for (id, text) in rows {
+        sync(&tx, id, &text)?;
+    }
+    tx.commit()
+        .map_err(|e| db_err_with("db.backfill_commit_tx", &[("column", &column)], e))
+}
+
+/// v12 → v13: `history` 新增 `language` / `language_confidence` 列并回填
+///
+/// 语言识别只跑正则，旧条目在迁移中一次性补齐，按语言筛选无需区分新旧数据。
+fn migrate_to_v13(conn: &Connection) -> Result<(), AppError> {
+    let _ = conn.execute("ALTER TABLE history ADD COLUMN language TEXT", []);
+    let _ = conn.execute("ALTER TABLE history ADD COLUMN language_confidence REAL", []);
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_history_language ON history(language)",
+        [],
+    )
+    .map_err(|e| db_err("db.create_language_index", e))?;
+
+    backfill_text_items(conn, "language", super::history::sync_item_language)
+}
+
+/// v13 → v14: `history` 新增 `content_subtype` 列并回填
+///
+/// 子类型识别同样只跑正则与解析，旧条目一次性补齐，按子类型筛选无需区分新旧数据。
+fn migrate_to_v14(conn: &Connection) -> Result<(), AppError> {
+    let _ = conn.execute("ALTER TABLE history ADD COLUMN content_subtype TEXT", []);
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_history_content_subtype ON history(content_subtype)",
+        [],
+    )
+    .map_err(|e| db_err("db.create_subtype_index", e))?;
+
+    backfill_text_items(conn, "content_subtype", super::history::sync_item_subtype)
+}
 
+/// v14 → v15: `assets` 新增 `phash_failed` 标记
+///
+/// 无法解码的图片记为失败，补算感知哈希时不再反复尝试。
+fn migrate_to_v15(conn: &Connection) -> Result<(), AppError> {
+    let _ = conn.execute(
+        "ALTER TABLE assets ADD COLUMN phash_failed INTEGER NOT NULL DEFAULT 0",
+        [],
+    );
+    Ok(())
+}
+
+/// v15 → v16: `image_metadata` 新增 `palette_failed` 标记
+///
+/// 无法提取主色的图片记为失败，按颜色搜索前补算时不再反复解码。
+fn migrate_to_v16(conn: &Connection) -> Result<(), AppError> {
+    let _ = conn.execute(
+        "ALTER TABLE image_metadata ADD COLUMN palette_failed INTEGER NOT NULL DEFAULT 0",
+        [],
+    );
     Ok(())
 }
 
+/// v16 → v17: 修复 v4 遗留的 `clip_formats` 外键悬空
+///
+/// 基础表创建时已建立 `clip_formats`，v4 的 RENAME 同样把它的外键改写为
+/// `history_old`，此前的迁移没有重建它，导致删除或写入附加格式失败。
+fn migrate_to_v17(conn: &Connection) -> Result<(), AppError> {
+    rebuild_table(conn, &CLIP_FORMATS_SPEC)
+}
+
 // ── 迁移注册表 ───────────────────────────────────────────────
 
 type MigrationFn = fn(&Connection) -> Result<(), AppError>;
@@ -330,6 +557,16 @@ const MIGRATIONS: &[(i64, MigrationFn)] = &[
     (5, migrate_to_v5),
     (6, migrate_to_v6),
     (7, migrate_to_v7),
+    (8, migrate_to_v8),
+    (9, migrate_to_v9),
+    (10, migrate_to_v10),
+    (11, migrate_to_v11),
+    (12, migrate_to_v12),
+    (13, migrate_to_v13),
+    (14, migrate_to_v14),
+    (15, migrate_to_v15),
+    (16, migrate_to_v16),
+    (17, migrate_to_v17),
 ];
 
 // ── 入口 ─────────────────────────────────────────────────────
@@ -355,9 +592,9 @@ pub(super) fn initialize_schema(conn: &Connection) -> Result<(), AppError> {
     }
 
     if version != SCHEMA_VERSION {
-        return Err(AppError::Database(format!(
-            "数据库版本不匹配: current={}, expected={}",
-            version, SCHEMA_VERSION
+        return Err(AppError::Database(message(
+            "db.version_mismatch",
+            &[("current", &version), ("expected", &SCHEMA_VERSION)],
         )));
     }
 
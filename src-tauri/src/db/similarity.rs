@@ -0,0 +1,327 @@
+//! 相似图片子模块
+//!
+//! ## 职责
+//! - 计算图片感知哈希（dHash，64 位），写入 `assets.phash`
+//! - 按汉明距离查找与指定条目相似的图片条目
+//! - 汇总近似重复分组，并通过 `bulk_delete_with_cleanup` 批量清理
+//!
+//! ## 设计决策
+//!
+//! ### dHash
+//! 缩放到 9×8 灰度图后比较相邻像素亮度，对缩放/压缩/少量像素差异不敏感。
+//! 计算需要完整解码，因此不在写锁内进行：入库路径先在锁外生成 `AssetAnalysis`，
+//! 查询前的补算也先用读连接取出待算列表，解码后只在写锁内写入结果。
+//! 无法解码的资源记 `phash_failed = 1`，之后不再重试。
+//!
+//! ### 分组策略
+//! 每组以一条代表图片为中心，成员与代表的距离均不超过阈值；不做传递合并，
+//! 避免 A≈B、B≈C 链式相连后把与保留条目相差很远的 C 一并删除。
+//!
+//! ### 保留策略
+//! 置顶/收藏/片段条目始终保留，并优先成为代表；没有保护条目的分组保留最新的一条
+//! （若组内已有保护条目，则非保护条目全部视为重复）。
+//!
+//! ## 输入/输出
+//! - 输入：`Connection`、条目 ID、汉明距离阈值
+//! - 输出：相似条目列表 / 重复分组 / 删除数量
+//!
+//! ## 错误语义
+//! - SQL 操作失败返回 `AppError::Database`
+//! - 图片无法解码时不写入哈希，记为 `phash_failed`，不报错
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use image::imageops::FilterType;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use tauri::State;
+
+use crate::error::AppError;
+
+use super::{db_err, DbState};
+
+/// 默认相似阈值（64 位中允许不同的位数）
+const DEFAULT_SIMILARITY_THRESHOLD: u32 = 10;
+
+// ── 数据结构 ─────────────────────────────────────────────────
+
+/// 相似图片匹配结果
+#[derive(Debug, Clone, Serialize)]
+pub struct SimilarImage {
+    pub id: i64,
+    /// 与目标图片的汉明距离（0 表示感知上完全一致）
+    pub distance: u32,
+}
+
+/// 近似重复图片分组
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateImageGroup {
+    /// 组内全部条目 ID（按时间倒序）
+    pub ids: Vec<i64>,
+    /// 建议保留的条目 ID（置顶/收藏/片段条目与分组代表）
+    pub keep_ids: Vec<i64>,
+    /// 建议删除的条目 ID
+    pub remove_ids: Vec<i64>,
+}
+
+#[derive(Debug, Clone)]
+struct HashedImage {
+    id: i64,
+    timestamp: i64,
+    protected: bool,
+    phash: u64,
+}
+
+// ── 感知哈希 ─────────────────────────────────────────────────
+
+/// 计算图片文件的 dHash；无法解码时返回 `None`
+pub(crate) fn compute_dhash(path: &Path) -> Option<u64> {
+    let image = image::open(path).ok()?;
+    let gray = image.resize_exact(9, 8, FilterType::Triangle).to_luma8();
+
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            let left = gray.get_pixel(x, y)[0];
+            let right = gray.get_pixel(x + 1, y)[0];
+            hash = (hash << 1) | u64::from(left > right);
+        }
+    }
+    Some(hash)
+}
+
+pub(crate) fn hamming_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// 写入感知哈希计算结果；`None` 表示无法解码，记为失败后不再补算
+pub(crate) fn store_phash(conn: &Connection, hash: &str, phash: Option<u64>) -> Result<(), AppError> {
+    conn.execute(
+        "UPDATE assets SET phash = ?1, phash_failed = ?2 WHERE hash = ?3 AND phash IS NULL",
+        params![phash.map(|v| v as i64), i32::from(phash.is_none()), hash],
+    )
+    .map_err(|e| db_err("db.write_phash", e))?;
+    Ok(())
+}
+
+/// 查询尚未计算且未失败过的位图资源 `(hash, path)`
+fn pending_phash_assets(conn: &Connection) -> Result<Vec<(String, String)>, AppError> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT hash, path FROM assets
+             WHERE phash IS NULL AND phash_failed = 0 AND refcount > 0
+               AND mime LIKE 'image/%' AND mime <> 'image/svg+xml'",
+        )
+        .map_err(|e| db_err("db.prepare_missing_phash", e))?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+        .map_err(|e| db_err("db.query_missing_phash", e))?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| db_err("db.read_missing_phash", e))
+}
+
+/// 在一个事务内写入一批感知哈希结果
+fn store_phashes(conn: &mut Connection, results: &[(String, Option<u64>)]) -> Result<(), AppError> {
+    let tx = conn
+        .transaction()
+        .map_err(|e| db_err("db.begin_tx", e))?;
+    for (hash, phash) in results {
+        store_phash(&tx, hash, *phash)?;
+    }
+    tx.commit().map_err(|e| db_err("db.commit_tx", e))
+}
+
+/// 补齐旧资源缺失的感知哈希
+///
+/// 读连接取待算列表 → 不持锁解码 → 写锁内批量写入，解码期间不阻塞其他写操作。
+fn backfill_missing_phashes(state: &State<'_, DbState>) -> Result<(), AppError> {
+    let pending = super::with_read_conn(state, pending_phash_assets)?;
+    if pending.is_empty() {
+        return Ok(());
+    }
+    let results: Vec<(String, Option<u64>)> = pending
+        .into_iter()
+        .map(|(hash, path)| {
+            let phash = compute_dhash(Path::new(&path));
+            (hash, phash)
+        })
+        .collect();
+    super::with_conn_mut(state, |conn| store_phashes(conn, &results))
+}
+
+// ── 查询 ─────────────────────────────────────────────────────
+
+fn load_hashed_images(conn: &Connection) -> Result<Vec<HashedImage>, AppError> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT DISTINCT h.id, h.timestamp, h.is_pinned, h.is_favorite, h.is_snippet, a.phash
+             FROM history h
+             JOIN history_assets ha ON ha.item_id = h.id
+             JOIN assets a ON a.hash = ha.hash
+             WHERE a.phash IS NOT NULL
+             ORDER BY h.timestamp DESC, h.id DESC",
+        )
+        .map_err(|e| db_err("db.prepare_image_hashes", e))?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(HashedImage {
+                id: row.get(0)?,
+                timestamp: row.get(1)?,
+                protected: row.get::<_, i32>(2)? != 0
+                    || row.get::<_, i32>(3)? != 0
+                    || row.get::<_, i32>(4)? != 0,
+                phash: row.get::<_, i64>(5)? as u64,
+            })
+        })
+        .map_err(|e| db_err("db.query_image_hashes", e))?;
+
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| db_err("db.read_image_hashes", e))
+}
+
+/// 查找与指定条目相似的图片条目（按距离升序）
+pub(crate) fn find_similar_images(
+    conn: &Connection,
+    id: i64,
+    threshold: u32,
+) -> Result<Vec<SimilarImage>, AppError> {
+    let images = load_hashed_images(conn)?;
+
+    let targets: Vec<u64> = images.iter().filter(|img| img.id == id).map(|img| img.phash).collect();
+    if targets.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut best: HashMap<i64, u32> = HashMap::new();
+    for image in images.iter().filter(|img| img.id != id) {
+        let distance = targets
+            .iter()
+            .map(|&target| hamming_distance(target, image.phash))
+            .min()
+            .unwrap_or(u32::MAX);
+        if distance <= threshold {
+            best.entry(image.id)
+                .and_modify(|d| *d = (*d).min(distance))
+                .or_insert(distance);
+        }
+    }
+
+    let mut result: Vec<SimilarImage> = best
+        .into_iter()
+        .map(|(id, distance)| SimilarImage { id, distance })
+        .collect();
+    result.sort_by_key(|m| (m.distance, std::cmp::Reverse(m.id)));
+    Ok(result)
+}
+
+/// 对全部图片条目按代表图片聚类，返回成员数 ≥ 2 的分组
+pub(crate) fn find_duplicate_image_groups(
+    conn: &Connection,
+    threshold: u32,
+) -> Result<Vec<DuplicateImageGroup>, AppError> {
+    let images = load_hashed_images(conn)?;
+    Ok(group_duplicates(images, threshold))
+}
+
+/// 按代表图片聚类
+///
+/// 保护条目排在最前，其余按时间倒序，依次归入第一个代表与之距离不超过阈值的分组，
+/// 否则自成一组。于是每组代表都是应保留的条目，被删除的成员必然与它相似。
+fn group_duplicates(mut images: Vec<HashedImage>, threshold: u32) -> Vec<DuplicateImageGroup> {
+    images.sort_by_key(|m| (!m.protected, std::cmp::Reverse(m.timestamp), std::cmp::Reverse(m.id)));
+
+    let mut clusters: Vec<Vec<&HashedImage>> = Vec::new();
+    for image in &images {
+        match clusters
+            .iter_mut()
+            .find(|cluster| hamming_distance(cluster[0].phash, image.phash) <= threshold)
+        {
+            // 同一条目可能关联多张图片，组内按 ID 去重
+            Some(cluster) => {
+                if !cluster.iter().any(|m| m.id == image.id) {
+                    cluster.push(image);
+                }
+            }
+            None => clusters.push(vec![image]),
+        }
+    }
+
+    let mut groups: Vec<DuplicateImageGroup> = clusters
+        .into_iter()
+        .filter(|cluster| cluster.len() >= 2)
+        .map(|cluster| {
+            let mut members: Vec<(&HashedImage, bool)> = cluster
+                .iter()
+                .enumerate()
+                .map(|(index, member)| (*member, index == 0 || member.protected))
+                .collect();
+            members.sort_by_key(|(m, _)| (std::cmp::Reverse(m.timestamp), std::cmp::Reverse(m.id)));
+            DuplicateImageGroup {
+                ids: members.iter().map(|(m, _)| m.id).collect(),
+                keep_ids: members.iter().filter(|(_, keep)| *keep).map(|(m, _)| m.id).collect(),
+                remove_ids: members.iter().filter(|(_, keep)| !*keep).map(|(m, _)| m.id).collect(),
+            }
+        })
+        .collect();
+    groups.sort_by_key(|g| std::cmp::Reverse(g.ids.first().copied().unwrap_or_default()));
+    groups
+}
+
+/// 删除所有近似重复分组中的建议删除条目，返回删除数量
+pub(crate) fn delete_duplicate_images(conn: &mut Connection, threshold: u32) -> Result<usize, AppError> {
+    let groups = find_duplicate_image_groups(conn, threshold)?;
+    // 多图条目可能同时出现在多个分组中：只要在任一分组被保留就不删除
+    let keep: HashSet<i64> = groups.iter().flat_map(|g| g.keep_ids.iter().copied()).collect();
+    let remove_ids: Vec<i64> = groups
+        .into_iter()
+        .flat_map(|group| group.remove_ids)
+        .filter(|id| !keep.contains(id))
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    super::cleanup::bulk_delete_with_cleanup(conn, &remove_ids)?;
+    Ok(remove_ids.len())
+}
+
+fn normalize_threshold(threshold: Option<u32>) -> u32 {
+    threshold.unwrap_or(DEFAULT_SIMILARITY_THRESHOLD).min(64)
+}
+
+// ── Tauri Commands ───────────────────────────────────────────
+
+/// 查找与指定条目视觉相似的图片条目
+///
+/// 查询前先在锁外补齐旧数据缺失的感知哈希。
+#[tauri::command]
+pub fn db_find_similar_images(
+    state: State<'_, DbState>,
+    id: i64,
+    threshold: Option<u32>,
+) -> Result<Vec<SimilarImage>, AppError> {
+    backfill_missing_phashes(&state)?;
+    super::with_read_conn(&state, |conn| find_similar_images(conn, id, normalize_threshold(threshold)))
+}
+
+#[tauri::command]
+pub fn db_find_duplicate_image_groups(
+    state: State<'_, DbState>,
+    threshold: Option<u32>,
+) -> Result<Vec<DuplicateImageGroup>, AppError> {
+    backfill_missing_phashes(&state)?;
+    super::with_read_conn(&state, |conn| find_duplicate_image_groups(conn, normalize_threshold(threshold)))
+}
+
+/// 批量删除近似重复图片（保留置顶/收藏/片段与每组代表条目）
+#[tauri::command]
+pub fn db_delete_duplicate_images(
+    state: State<'_, DbState>,
+    threshold: Option<u32>,
+) -> Result<usize, AppError> {
+    backfill_missing_phashes(&state)?;
+    super::with_conn_mut(&state, |conn| delete_duplicate_images(conn, normalize_threshold(threshold)))
+}
+
+#[cfg(test)]
+#[path = "tests/similarity_tests.rs"]
+mod tests;
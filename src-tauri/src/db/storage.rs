@@ -32,6 +32,7 @@ use serde::Serialize;
 use tauri::{AppHandle, Manager, State};
 
 use crate::error::AppError;
+use crate::i18n::message;
 
 use super::{config, db_err, DbState};
 
@@ -48,7 +49,7 @@ pub struct DbInfo {
 fn get_current_db_path(conn: &Connection) -> Result<PathBuf, AppError> {
     let path_str: String = conn
         .query_row("PRAGMA database_list", [], |row| row.get::<_, String>(2))
-        .map_err(|e| db_err("获取当前数据库路径失败", e))?;
+        .map_err(|e| db_err("db.current_path", e))?;
     Ok(PathBuf::from(path_str))
 }
 
@@ -64,7 +65,7 @@ fn resolve_new_dir_path(app: &AppHandle, new_dir: &str) -> Result<PathBuf, AppEr
     if new_dir.is_empty() {
         app.path()
             .app_data_dir()
-            .map_err(|e| db_err("获取应用数据目录失败", e))
+            .map_err(|e| AppError::Database(message("db.app_data_dir_failed", &[("detail", &e)])))
     } else {
         Ok(PathBuf::from(new_dir))
     }
@@ -76,13 +77,16 @@ fn resolve_new_dir_path(app: &AppHandle, new_dir: &str) -> Result<PathBuf, AppEr
 /// sidecar 文件在 checkpoint 后通常为空/可重建，
 /// 复制失败仅记录警告不中断迁移。
 fn copy_database_files(src: &Path, dst: &Path) -> Result<(), AppError> {
-    fs::copy(src, dst).map_err(|e| db_err("复制数据库文件失败", e))?;
+    fs::copy(src, dst).map_err(|e| AppError::from_io(&message("db.copy_file", &[]), &e, AppError::Database))?;
 
     for ext in ["db-wal", "db-shm"] {
         let sidecar_src = src.with_extension(ext);
         if sidecar_src.exists() {
             if let Err(e) = fs::copy(&sidecar_src, dst.with_extension(ext)) {
-                log::warn!("复制 sidecar {} 失败（checkpoint 后通常安全）: {}", ext, e);
+                log::warn!(
+                    "{}",
+                    message("db_log.copy_sidecar_failed", &[("ext", &ext), ("detail", &e)])
+                );
             }
         }
     }
@@ -94,11 +98,11 @@ fn copy_database_files(src: &Path, dst: &Path) -> Result<(), AppError> {
 /// 返回 `Ok(conn)` 或回退后的 `Err`，保证调用方总有可用连接。
 fn open_and_verify(db_path: &Path) -> Result<Connection, AppError> {
     let conn = Connection::open(db_path)
-        .map_err(|e| db_err("打开数据库失败", e))?;
+        .map_err(|e| db_err("db.open", e))?;
     conn.execute_batch("PRAGMA journal_mode=WAL;").ok();
 
     conn.query_row("SELECT COUNT(*) FROM history", [], |row| row.get::<_, i64>(0))
-        .map_err(|e| db_err("验证数据库失败", e))?;
+        .map_err(|e| db_err("db.verify", e))?;
     Ok(conn)
 }
 
@@ -116,11 +120,11 @@ fn open_or_restore(
             Ok(())
         }
         Err(e) => {
-            log::error!("打开/验证新数据库失败: {}", e);
+            log::error!("{}", message("db_log.open_new_db_failed", &[("detail", &e)]));
             cleanup_file_quietly(new_path);
             // 回退到旧路径
             *conn = Connection::open(old_path)
-                .map_err(|re| db_err("回退旧连接也失败（数据库可能不可用）", re))?;
+                .map_err(|re| db_err("db.rollback_connection", re))?;
             conn.execute_batch("PRAGMA journal_mode=WAL;").ok();
             Err(e)
         }
@@ -131,7 +135,10 @@ fn open_or_restore(
 fn cleanup_file_quietly(path: &Path) {
     if let Err(e) = fs::remove_file(path) {
         if e.kind() != std::io::ErrorKind::NotFound {
-            log::warn!("清理文件 '{}' 失败: {}", path.display(), e);
+            log::warn!(
+                "{}",
+                message("db_log.cleanup_file_failed", &[("path", &path.display()), ("detail", &e)])
+            );
         }
     }
 }
@@ -164,7 +171,7 @@ pub fn db_move_database(
         let new_dir_path = resolve_new_dir_path(&app, &new_dir)?;
 
         fs::create_dir_all(&new_dir_path)
-            .map_err(|e| db_err("创建目标目录失败", e))?;
+            .map_err(|e| AppError::from_io(&message("db.create_target_dir", &[]), &e, AppError::Database))?;
 
         let new_db_path = new_dir_path.join("clipboard.db");
 
@@ -172,30 +179,28 @@ pub fn db_move_database(
             return Ok(build_db_info(&new_db_path));
         }
         if new_db_path.exists() {
-            return Err(AppError::Database(
-                "目标位置已存在数据库文件 clipboard.db，请选择其他目录或先手动删除".into(),
-            ));
+            return Err(AppError::Database(message("db.target_exists", &[])));
         }
 
         // ── Step 1: 将 WAL 数据刷入主文件 ──
         write_conn
             .execute_batch("PRAGMA wal_checkpoint(TRUNCATE);")
-            .map_err(|e| db_err("WAL 检查点失败", e))?;
+            .map_err(|e| db_err("db.wal_checkpoint", e))?;
 
         // ── Step 2: 关闭旧连接释放文件锁 ──
         // 用 in-memory 占位，立即在 Step 4 替换为新/旧连接
         let placeholder = Connection::open_in_memory()
-            .map_err(|e| db_err("创建占位连接失败", e))?;
+            .map_err(|e| db_err("db.create_placeholder_connection", e))?;
         let old_write = std::mem::replace(write_conn, placeholder);
         drop(old_write);
 
         // ── Step 3: 复制文件 ──
         if let Err(e) = copy_database_files(&current_db_path, &new_db_path) {
-            log::error!("复制数据库失败，恢复旧连接: {}", e);
+            log::error!("{}", message("db_log.copy_db_failed", &[("detail", &e)]));
             cleanup_file_quietly(&new_db_path);
             // 直接复用 open_or_restore 的恢复逻辑（new_path 已清理，会直接走回退分支）
             *write_conn = Connection::open(&current_db_path)
-                .map_err(|re| db_err("恢复旧写连接失败", re))?;
+                .map_err(|re| db_err("db.restore_writer", re))?;
             write_conn.execute_batch("PRAGMA journal_mode=WAL;").ok();
             return Err(e);
         }
@@ -206,14 +211,14 @@ pub fn db_move_database(
         // ── Step 5: 替换读连接 ──
         let new_read = Connection::open(&new_db_path)
             .map_err(|e| {
-                log::error!("打开新数据库读连接失败: {}", e);
+                log::error!("{}", message("db_log.open_read_conn_failed", &[("detail", &e)]));
                 cleanup_file_quietly(&new_db_path);
                 // 回退写连接
                 if let Ok(c) = Connection::open(&current_db_path) {
                     c.execute_batch("PRAGMA journal_mode=WAL;").ok();
                     *write_conn = c;
                 }
-                db_err("打开新数据库读连接失败", e)
+                db_err("db.open_new_reader", e)
             })?;
         let old_read = std::mem::replace(read_conn, new_read);
         drop(old_read);
@@ -18,6 +18,7 @@ use rusqlite::{params, Connection};
 use tauri::State;
 
 use crate::error::AppError;
+use crate::i18n::message;
 
 use super::{db_err, DbState, Tag};
 
@@ -27,7 +28,7 @@ use super::{db_err, DbState, Tag};
 fn validate_tag_name(name: &str) -> Result<String, AppError> {
     let trimmed = name.trim().to_string();
     if trimmed.is_empty() {
-        return Err(AppError::Database("标签名称不能为空".into()));
+        return Err(AppError::Database(message("db.tag_name_empty", &[])));
     }
     Ok(trimmed)
 }
@@ -37,7 +38,7 @@ fn validate_tag_name(name: &str) -> Result<String, AppError> {
 fn get_tags(conn: &Connection) -> Result<Vec<Tag>, AppError> {
     let mut stmt = conn
         .prepare("SELECT id, name, color FROM tags ORDER BY name ASC")
-        .map_err(|e| db_err("准备查询失败", e))?;
+        .map_err(|e| db_err("db.prepare_query", e))?;
 
     let tags = stmt
         .query_map([], |row| {
@@ -47,9 +48,9 @@ fn get_tags(conn: &Connection) -> Result<Vec<Tag>, AppError> {
                 color: row.get(2)?,
             })
         })
-        .map_err(|e| db_err("查询标签失败", e))?
+        .map_err(|e| db_err("db.query_tags", e))?
         .collect::<Result<Vec<_>, _>>()
-        .map_err(|e| db_err("读取标签行失败", e))?;
+        .map_err(|e| db_err("db.read_tag_row", e))?;
 
     Ok(tags)
 }
@@ -65,10 +66,10 @@ fn create_tag(conn: &Connection, name: String, color: Option<String>) -> Result<
         // UNIQUE 约束冲突给出友好提示
         if let rusqlite::Error::SqliteFailure(err, _) = &e {
             if err.extended_code == rusqlite::ffi::SQLITE_CONSTRAINT_UNIQUE {
-                return AppError::Database(format!("标签名 '{}' 已存在", name));
+                return AppError::Database(message("db.tag_exists", &[("name", &name)]));
             }
         }
-        db_err("创建标签失败", e)
+        db_err("db.create_tag", e)
     })?;
 
     let id = conn.last_insert_rowid();
@@ -90,10 +91,10 @@ fn update_tag(
     .map_err(|e| {
         if let rusqlite::Error::SqliteFailure(err, _) = &e {
             if err.extended_code == rusqlite::ffi::SQLITE_CONSTRAINT_UNIQUE {
-                return AppError::Database(format!("标签名 '{}' 已存在", name));
+                return AppError::Database(message("db.tag_exists", &[("name", &name)]));
             }
         }
-        db_err("更新标签失败", e)
+        db_err("db.update_tag", e)
     })?;
 
     Ok(())
@@ -101,7 +102,7 @@ fn update_tag(
 
 fn delete_tag(conn: &Connection, id: i64) -> Result<(), AppError> {
     conn.execute("DELETE FROM tags WHERE id = ?1", params![id])
-        .map_err(|e| db_err("删除标签失败", e))?;
+        .map_err(|e| db_err("db.delete_tag", e))?;
     Ok(())
 }
 
@@ -110,7 +111,7 @@ fn add_tag_to_item(conn: &Connection, item_id: i64, tag_id: i64) -> Result<(), A
         "INSERT OR IGNORE INTO item_tags (item_id, tag_id) VALUES (?1, ?2)",
         params![item_id, tag_id],
     )
-    .map_err(|e| db_err("添加标签到条目失败", e))?;
+    .map_err(|e| db_err("db.add_item_tag", e))?;
     Ok(())
 }
 
@@ -119,7 +120,7 @@ fn remove_tag_from_item(conn: &Connection, item_id: i64, tag_id: i64) -> Result<
         "DELETE FROM item_tags WHERE item_id = ?1 AND tag_id = ?2",
         params![item_id, tag_id],
     )
-    .map_err(|e| db_err("从条目移除标签失败", e))?;
+    .map_err(|e| db_err("db.remove_item_tag", e))?;
     Ok(())
 }
 
@@ -0,0 +1,242 @@
+use super::*;
+use crate::db::schema::initialize_schema;
+use crate::storage::write_content_addressed;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn unique_temp_dir(prefix: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let dir = std::env::temp_dir().join(format!("{}_{}", prefix, nanos));
+    let _ = fs::create_dir_all(&dir);
+    dir
+}
+
+/// 构造 v7 结构的数据库（尚无 `assets` 表与 `history_assets.hash` 列）
+fn setup_v7_conn() -> Connection {
+    let conn = Connection::open_in_memory().expect("create memory db");
+    conn.execute_batch(
+        "CREATE TABLE history (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            text TEXT NOT NULL,
+            timestamp INTEGER NOT NULL,
+            is_pinned INTEGER NOT NULL DEFAULT 0 CHECK (is_pinned IN (0, 1)),
+            is_snippet INTEGER NOT NULL DEFAULT 0 CHECK (is_snippet IN (0, 1)),
+            is_favorite INTEGER NOT NULL DEFAULT 0 CHECK (is_favorite IN (0, 1)),
+            picked_color TEXT,
+            content_type TEXT NOT NULL DEFAULT 'text'
+        );
+        CREATE TABLE clip_formats (
+            item_id INTEGER NOT NULL,
+            format  TEXT NOT NULL,
+            content TEXT NOT NULL,
+            PRIMARY KEY (item_id, format),
+            FOREIGN KEY (item_id) REFERENCES history(id) ON DELETE CASCADE
+        );
+        CREATE TABLE history_assets (
+            item_id INTEGER NOT NULL,
+            path TEXT NOT NULL,
+            PRIMARY KEY (item_id, path),
+            FOREIGN KEY (item_id) REFERENCES history(id) ON DELETE CASCADE
+        );
+        PRAGMA user_version = 7;",
+    )
+    .expect("create v7 schema");
+    conn
+}
+
+fn insert_legacy_image(conn: &Connection, path: &Path) -> i64 {
+    let path_str = path.to_string_lossy();
+    conn.execute(
+        "INSERT INTO history (text, timestamp, content_type) VALUES (?1, 0, 'image')",
+        params![path_str],
+    )
+    .expect("insert history");
+    let id = conn.last_insert_rowid();
+    conn.execute(
+        "INSERT INTO clip_formats (item_id, format, content) VALUES (?1, 'image', ?2)",
+        params![id, path_str],
+    )
+    .expect("insert clip format");
+    conn.execute(
+        "INSERT INTO history_assets (item_id, path) VALUES (?1, ?2)",
+        params![id, path_str],
+    )
+    .expect("insert history asset");
+    id
+}
+
+fn refcount(conn: &Connection, hash: &str) -> i64 {
+    conn.query_row("SELECT refcount FROM assets WHERE hash = ?1", params![hash], |row| row.get(0))
+        .expect("query refcount")
+}
+
+// ── v8 迁移去重 ─────────────────────────────────────────────
+
+#[test]
+fn v8_migration_merges_duplicate_legacy_files() {
+    let dir = unique_temp_dir("cliphist_assets_dedup");
+    let first = dir.join("img_20260101010101000.png");
+    let second = dir.join("img_20260101010102000.png");
+    let unique = dir.join("svg_20260101010103000.svg");
+    fs::write(&first, b"same-bytes").unwrap();
+    fs::write(&second, b"same-bytes").unwrap();
+    fs::write(&unique, b"<svg></svg>").unwrap();
+
+    let conn = setup_v7_conn();
+    let first_id = insert_legacy_image(&conn, &first);
+    let second_id = insert_legacy_image(&conn, &second);
+    insert_legacy_image(&conn, &unique);
+
+    initialize_schema(&conn).expect("migrate to v8");
+
+    let first_str = first.to_string_lossy().into_owned();
+    let text: String = conn
+        .query_row("SELECT text FROM history WHERE id = ?1", params![second_id], |row| row.get(0))
+        .unwrap();
+    assert_eq!(text, first_str, "duplicate path should be rewritten to canonical");
+    let format: String = conn
+        .query_row(
+            "SELECT content FROM clip_formats WHERE item_id = ?1 AND format = 'image'",
+            params![second_id],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(format, first_str);
+
+    let hash = content_hash(b"same-bytes");
+    assert_eq!(refcount(&conn, &hash), 2);
+    let mapped: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM history_assets WHERE hash = ?1 AND item_id IN (?2, ?3)",
+            params![hash, first_id, second_id],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(mapped, 2);
+
+    assert!(first.exists());
+    assert!(!second.exists(), "duplicate file should be removed after migration");
+    assert!(unique.exists());
+    assert_eq!(refcount(&conn, &content_hash(b"<svg></svg>")), 1);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn v8_migration_keeps_missing_files_without_hash() {
+    let dir = unique_temp_dir("cliphist_assets_missing");
+    let missing = dir.join("img_20260101010101000.png");
+
+    let conn = setup_v7_conn();
+    insert_legacy_image(&conn, &missing);
+
+    initialize_schema(&conn).expect("migrate to v8");
+
+    let hash: Option<String> = conn
+        .query_row("SELECT hash FROM history_assets", [], |row| row.get(0))
+        .unwrap();
+    assert!(hash.is_none());
+    let assets: i64 = conn
+        .query_row("SELECT COUNT(*) FROM assets", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(assets, 0);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+// ── 引用计数触发器 ──────────────────────────────────────────
+
+#[test]
+fn refcount_follows_history_assets_rows() {
+    let dir = unique_temp_dir("cliphist_assets_refcount");
+    let path = write_content_addressed(&dir, b"png-bytes", "png").unwrap();
+
+    let conn = Connection::open_in_memory().expect("create memory db");
+    initialize_schema(&conn).expect("init schema");
+    conn.execute_batch(
+        "INSERT INTO history (id, text, timestamp) VALUES (1, 'a', 0), (2, 'b', 0);",
+    )
+    .unwrap();
+
+    let hash = register_asset(&conn, &path).unwrap().expect("file exists");
+    assert_eq!(hash, content_hash(b"png-bytes"));
+    assert_eq!(refcount(&conn, &hash), 0);
+
+    for id in [1, 2] {
+        conn.execute(
+            "INSERT INTO history_assets (item_id, path, hash) VALUES (?1, ?2, ?3)",
+            params![id, path.to_string_lossy(), hash],
+        )
+        .unwrap();
+    }
+    assert_eq!(refcount(&conn, &hash), 2);
+
+    conn.execute("DELETE FROM history WHERE id = 1", []).unwrap();
+    assert_eq!(refcount(&conn, &hash), 1, "cascade delete should decrement");
+
+    conn.execute("UPDATE history_assets SET hash = NULL", []).unwrap();
+    assert_eq!(refcount(&conn, &hash), 0);
+
+    let mut orphans = HashSet::new();
+    orphans.insert(path.clone());
+    delete_unreferenced_assets(&conn, &orphans).unwrap();
+    let remaining: i64 = conn
+        .query_row("SELECT COUNT(*) FROM assets", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(remaining, 0);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn write_content_addressed_reuses_existing_file() {
+    let dir = unique_temp_dir("cliphist_assets_write");
+    let first = write_content_addressed(&dir, b"payload", "png").unwrap();
+    let second = write_content_addressed(&dir, b"payload", "png").unwrap();
+
+    assert_eq!(first, second);
+    let hash = content_hash(b"payload");
+    assert_eq!(first, dir.join(&hash[..2]).join(format!("{}.png", hash)));
+    assert_eq!(fs::read(&first).unwrap(), b"payload");
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn concurrent_writes_of_same_content_all_succeed() {
+    let dir = unique_temp_dir("cliphist_assets_concurrent");
+    let payload = vec![7u8; 256 * 1024];
+    let paths: Vec<PathBuf> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..8)
+            .map(|_| scope.spawn(|| write_content_addressed(&dir, &payload, "png").unwrap()))
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    assert!(paths.windows(2).all(|w| w[0] == w[1]));
+    assert_eq!(fs::read(&paths[0]).unwrap(), payload);
+    let leftovers: Vec<_> = fs::read_dir(paths[0].parent().unwrap())
+        .unwrap()
+        .flatten()
+        .filter(|e| e.path() != paths[0])
+        .collect();
+    assert!(leftovers.is_empty(), "{:?}", leftovers);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn mime_for_path_covers_stored_image_extensions() {
+    for (name, mime) in [
+        ("a.png", "image/png"),
+        ("a.JPG", "image/jpeg"),
+        ("a.jpeg", "image/jpeg"),
+        ("a.webp", "image/webp"),
+        ("a.svg", "image/svg+xml"),
+        ("a.bin", "application/octet-stream"),
+    ] {
+        assert_eq!(mime_for_path(Path::new(name)), mime, "{}", name);
+    }
+}
@@ -26,11 +26,58 @@ fn setup_history_conn() -> Connection {
         "CREATE TABLE history_assets (
             item_id INTEGER NOT NULL,
             path TEXT NOT NULL,
+            hash TEXT,
             PRIMARY KEY (item_id, path)
         )",
         [],
     )
     .expect("create history_assets table failed");
+    conn.execute(
+        "CREATE TABLE assets (
+            hash TEXT PRIMARY KEY,
+            path TEXT NOT NULL,
+            mime TEXT NOT NULL,
+            byte_size INTEGER NOT NULL,
+            width INTEGER,
+            height INTEGER,
+            refcount INTEGER NOT NULL DEFAULT 0,
+            created_at INTEGER NOT NULL,
+            phash INTEGER
+        )",
+        [],
+    )
+    .expect("create assets table failed");
+    conn.execute(
+        "CREATE TABLE image_metadata (
+            item_id INTEGER PRIMARY KEY,
+            width INTEGER NOT NULL,
+            height INTEGER NOT NULL,
+            color_type TEXT NOT NULL,
+            byte_size INTEGER NOT NULL,
+            camera_make TEXT,
+            camera_model TEXT,
+            orientation INTEGER,
+            captured_at TEXT,
+            has_gps INTEGER NOT NULL DEFAULT 0,
+            text_chunks TEXT
+        )",
+        [],
+    )
+    .expect("create image_metadata table failed");
+    conn.execute(
+        "CREATE TABLE image_palette (
+            item_id INTEGER NOT NULL,
+            rank INTEGER NOT NULL,
+            color TEXT NOT NULL,
+            r INTEGER NOT NULL,
+            g INTEGER NOT NULL,
+            b INTEGER NOT NULL,
+            ratio REAL NOT NULL,
+            PRIMARY KEY (item_id, rank)
+        )",
+        [],
+    )
+    .expect("create image_palette table failed");
     conn
 }
 
@@ -68,6 +115,23 @@ fn extract_generated_asset_paths_only_collects_managed_files() {
     let _ = fs::remove_dir_all(&dir);
 }
 
+#[test]
+fn extract_generated_asset_paths_accepts_content_addressed_originals() {
+    let dir = unique_temp_dir("cliphist_extract_originals");
+    let hash = "0123456789abcdef".repeat(4);
+    let gif = dir.join(format!("{}.gif", hash));
+    let webp = dir.join(format!("{}.webp", hash));
+    let jpeg = dir.join(format!("{}.jpg", hash));
+    let photo = dir.join("photo.jpg");
+
+    for path in [&gif, &webp, &jpeg] {
+        assert!(extract_generated_asset_paths(&path.to_string_lossy()).contains(path));
+    }
+    assert!(extract_generated_asset_paths(&photo.to_string_lossy()).is_empty());
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
 // ── normalize_local_path ────────────────────────────────────
 
 #[test]
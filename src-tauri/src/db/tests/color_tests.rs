@@ -0,0 +1,79 @@
+use super::*;
+use crate::color::ColorFormat;
+use crate::db::schema::initialize_schema;
+
+fn setup_conn() -> Connection {
+    let conn = Connection::open_in_memory().expect("create memory db");
+    initialize_schema(&conn).expect("init schema");
+    conn
+}
+
+fn insert_clip(conn: &Connection, text: &str) -> i64 {
+    conn.execute(
+        "INSERT INTO history (text, timestamp) VALUES (?1, 1)",
+        params![text],
+    )
+    .expect("insert history");
+    let id = conn.last_insert_rowid();
+    sync_item_color(conn, id, text).expect("sync color");
+    id
+}
+
+fn stored_color(conn: &Connection, id: i64) -> Option<DetectedColor> {
+    conn.query_row(
+        "SELECT content FROM clip_formats WHERE item_id = ?1 AND format = ?2",
+        params![id, COLOR_FORMAT_KEY],
+        |row| row.get::<_, String>(0),
+    )
+    .optional()
+    .expect("query color format")
+    .map(|json| serde_json::from_str(&json).expect("valid json"))
+}
+
+#[test]
+fn sync_stores_detected_color_and_clears_it_on_edit() {
+    let conn = setup_conn();
+    let id = insert_clip(&conn, "hsl(210, 100%, 56%)");
+
+    let detected = stored_color(&conn, id).expect("color stored");
+    assert_eq!(detected.source, ColorFormat::Hsl);
+    assert_eq!(detected.hex, "#1f8fff");
+
+    sync_item_color(&conn, id, "not a color").expect("resync");
+    assert!(stored_color(&conn, id).is_none());
+
+    let plain = insert_clip(&conn, "hello world");
+    assert!(stored_color(&conn, plain).is_none());
+}
+
+#[test]
+fn contrast_between_color_clips() {
+    let conn = setup_conn();
+    let black = insert_clip(&conn, "#000");
+    let white = insert_clip(&conn, "rgb(255 255 255)");
+    let text = insert_clip(&conn, "hello");
+
+    let report = clip_contrast(&conn, black, white).expect("contrast").expect("both colors");
+    assert_eq!(report.ratio, 21.0);
+    assert!(report.aaa_normal);
+
+    assert!(clip_contrast(&conn, black, text).expect("contrast").is_none());
+    assert!(clip_contrast(&conn, black, 9999).expect("contrast").is_none());
+}
+
+#[test]
+fn picked_color_overrides_and_legacy_items_are_parsed_on_demand() {
+    let conn = setup_conn();
+    let id = insert_clip(&conn, "#ffffff");
+    conn.execute("UPDATE history SET picked_color = '#000000' WHERE id = ?1", params![id])
+        .unwrap();
+    let color = load_clip_color(&conn, id).expect("load").expect("color");
+    assert_eq!(color.to_rgb8(), [0, 0, 0]);
+
+    // 识别逻辑上线前入库的条目没有 color 格式
+    conn.execute("INSERT INTO history (text, timestamp) VALUES ('#1e90ff', 2)", [])
+        .unwrap();
+    let legacy = conn.last_insert_rowid();
+    let color = load_clip_color(&conn, legacy).expect("load").expect("color");
+    assert_eq!(color.to_rgb8(), [30, 144, 255]);
+}
@@ -1,10 +1,17 @@
 use rusqlite::{params, Connection};
 
 use super::{
-    add_clip, auto_clear_before, bulk_pin, get_clip_by_id, get_history, get_stats, import_data,
-    load_tags_batch, toggle_favorite, toggle_pin, update_clip, update_picked_color, ImportItem,
+    add_clip, add_selection_clip, auto_clear_before, auto_clear_selection_before, bulk_pin,
+    get_clip_by_id, get_history, get_stats, get_suggested_actions, import_data, load_tags_batch,
+    toggle_favorite, toggle_pin, update_clip, update_picked_color, HistoryFilter, ImportItem,
+    AssetAnalysis, SelectionSource, SELECTION_PRIMARY,
 };
 
+/// 纯文本条目不涉及图片，无需预计算
+fn no_analysis() -> AssetAnalysis {
+    AssetAnalysis::default()
+}
+
 fn setup_conn() -> Connection {
     let conn = Connection::open_in_memory().expect("create memory db");
     conn.execute_batch(
@@ -16,7 +23,10 @@ fn setup_conn() -> Connection {
             is_snippet INTEGER DEFAULT 0,
             is_favorite INTEGER DEFAULT 0,
             picked_color TEXT,
-            content_type TEXT NOT NULL DEFAULT 'text'
+            content_type TEXT NOT NULL DEFAULT 'text',
+            language TEXT,
+            language_confidence REAL,
+            content_subtype TEXT
         );
         CREATE TABLE tags (
             id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -31,6 +41,7 @@ fn setup_conn() -> Connection {
         CREATE TABLE history_assets (
             item_id INTEGER NOT NULL,
             path TEXT NOT NULL,
+            hash TEXT,
             PRIMARY KEY (item_id, path)
         );
         CREATE TABLE clip_formats (
@@ -38,6 +49,29 @@ fn setup_conn() -> Connection {
             format TEXT NOT NULL,
             content TEXT NOT NULL,
             PRIMARY KEY (item_id, format)
+        );
+        CREATE TABLE image_metadata (
+            item_id INTEGER PRIMARY KEY,
+            width INTEGER NOT NULL,
+            height INTEGER NOT NULL,
+            color_type TEXT NOT NULL,
+            byte_size INTEGER NOT NULL,
+            camera_make TEXT,
+            camera_model TEXT,
+            orientation INTEGER,
+            captured_at TEXT,
+            has_gps INTEGER NOT NULL DEFAULT 0,
+            text_chunks TEXT
+        );
+        CREATE TABLE image_palette (
+            item_id INTEGER NOT NULL,
+            rank INTEGER NOT NULL,
+            color TEXT NOT NULL,
+            r INTEGER NOT NULL,
+            g INTEGER NOT NULL,
+            b INTEGER NOT NULL,
+            ratio REAL NOT NULL,
+            PRIMARY KEY (item_id, rank)
         );"
     ).expect("create schema");
     conn
@@ -47,8 +81,8 @@ fn setup_conn() -> Connection {
 fn add_clip_deduplicates_non_snippet() {
     let conn = setup_conn();
 
-    add_clip(&conn, "hello".to_string(), 0).expect("first add");
-    add_clip(&conn, "hello".to_string(), 0).expect("duplicate add");
+    add_clip(&conn, "hello".to_string(), 0, &no_analysis()).expect("first add");
+    add_clip(&conn, "hello".to_string(), 0, &no_analysis()).expect("duplicate add");
 
     let count: i64 = conn
         .query_row("SELECT COUNT(*) FROM history", [], |row| row.get(0))
@@ -59,10 +93,10 @@ fn add_clip_deduplicates_non_snippet() {
 #[test]
 fn add_clip_returns_inserted_id() {
     let conn = setup_conn();
-    let inserted = add_clip(&conn, "first".to_string(), 0).expect("insert should succeed");
+    let inserted = add_clip(&conn, "first".to_string(), 0, &no_analysis()).expect("insert should succeed");
     assert!(inserted.is_some());
 
-    let duplicated = add_clip(&conn, "first".to_string(), 0).expect("dedupe should succeed");
+    let duplicated = add_clip(&conn, "first".to_string(), 0, &no_analysis()).expect("dedupe should succeed");
     assert!(duplicated.is_none());
 }
 
@@ -83,7 +117,7 @@ fn toggle_and_bulk_update_fields() {
     toggle_pin(&conn, first_id, 0).expect("toggle pin");
     toggle_favorite(&conn, first_id, 0).expect("toggle favorite");
     bulk_pin(&conn, &[first_id, second_id]).expect("bulk pin");
-    update_clip(&conn, second_id, "b2".to_string()).expect("update clip");
+    update_clip(&conn, second_id, "b2".to_string(), &no_analysis()).expect("update clip");
     update_picked_color(&conn, second_id, Some("#112233".to_string())).expect("update color");
 
     let first_pin: i32 = conn
@@ -114,8 +148,8 @@ fn import_data_and_history_query_work() {
         ImportItem { text: "y".to_string(), timestamp: 30, is_pinned: Some(0), is_snippet: Some(1) },
     ];
 
-    import_data(&mut conn, &items).expect("import data");
-    let history = get_history(&conn, 10).expect("get history");
+    import_data(&mut conn, &items, &no_analysis()).expect("import data");
+    let history = get_history(&conn, 10, &HistoryFilter::default()).expect("get history");
 
     assert_eq!(history.len(), 2);
     assert_eq!(history[0].text, "x");
@@ -281,8 +315,8 @@ fn get_clip_by_id_returns_none_for_missing() {
 #[test]
 fn add_clip_allows_duplicate_snippet() {
     let conn = setup_conn();
-    add_clip(&conn, "dup".to_string(), 1).expect("first snippet");
-    let second = add_clip(&conn, "dup".to_string(), 1).expect("second snippet");
+    add_clip(&conn, "dup".to_string(), 1, &no_analysis()).expect("first snippet");
+    let second = add_clip(&conn, "dup".to_string(), 1, &no_analysis()).expect("second snippet");
     assert!(second.is_some(), "snippet should not deduplicate");
 
     let count: i64 = conn
@@ -296,6 +330,168 @@ fn add_clip_allows_duplicate_snippet() {
 #[test]
 fn add_clip_rejects_whitespace_only() {
     let conn = setup_conn();
-    let result = add_clip(&conn, "   \n\t  ".to_string(), 0).expect("should not error");
+    let result = add_clip(&conn, "   \n\t  ".to_string(), 0, &no_analysis()).expect("should not error");
     assert!(result.is_none());
 }
+
+// ── get_history 图片尺寸筛选 ────────────────────────────────
+
+#[test]
+fn get_history_filters_by_image_size() {
+    let conn = setup_conn();
+    for (text, width, height) in [("small", 800, 600), ("uhd", 3840, 2160), ("portrait", 2160, 3840)] {
+        conn.execute(
+            "INSERT INTO history (text, timestamp, content_type) VALUES (?1, 1, 'image')",
+            params![text],
+        )
+        .expect("insert image item");
+        conn.execute(
+            "INSERT INTO image_metadata (item_id, width, height, color_type, byte_size)
+             VALUES (?1, ?2, ?3, 'Rgba8', 1)",
+            params![conn.last_insert_rowid(), width, height],
+        )
+        .expect("insert metadata");
+    }
+    add_clip(&conn, "plain text".to_string(), 0, &no_analysis()).expect("insert text");
+
+    let all = get_history(&conn, 10, &HistoryFilter::default()).expect("get history");
+    assert_eq!(all.len(), 4);
+    let uhd = all.iter().find(|item| item.text == "uhd").expect("uhd item");
+    assert_eq!(uhd.image_meta.as_ref().map(|m| (m.width, m.height)), Some((3840, 2160)));
+
+    let by_edges = HistoryFilter {
+        min_image_width: Some(3840),
+        min_image_height: Some(2160),
+        ..Default::default()
+    };
+    let texts: Vec<String> = get_history(&conn, 10, &by_edges)
+        .expect("filter by edges")
+        .into_iter()
+        .map(|item| item.text)
+        .collect();
+    assert_eq!(texts, vec!["uhd".to_string()]);
+
+    let by_pixels = HistoryFilter {
+        min_image_pixels: Some(3840 * 2160),
+        ..Default::default()
+    };
+    assert_eq!(get_history(&conn, 10, &by_pixels).expect("filter by pixels").len(), 2);
+}
+
+#[test]
+fn primary_selection_clips_are_filtered_and_expired_separately() {
+    let mut conn = setup_conn();
+    add_clip(&conn, "from clipboard".to_string(), 0, &no_analysis()).expect("insert clipboard clip");
+    let old = add_selection_clip(&conn, "old primary", SELECTION_PRIMARY, &no_analysis())
+        .expect("insert old primary")
+        .expect("old primary item");
+    let pinned = add_selection_clip(&conn, "pinned primary", SELECTION_PRIMARY, &no_analysis())
+        .expect("insert pinned primary")
+        .expect("pinned primary item");
+    add_selection_clip(&conn, "new primary", SELECTION_PRIMARY, &no_analysis()).expect("insert new primary");
+    conn.execute("UPDATE history SET timestamp = 100 WHERE id IN (?1, ?2)", params![old.id, pinned.id])
+        .expect("age primary clips");
+    toggle_pin(&conn, pinned.id, 0).expect("pin");
+
+    let texts_for = |conn: &Connection, selection| -> Vec<String> {
+        let filter = HistoryFilter { selection: Some(selection), ..Default::default() };
+        let mut texts: Vec<String> = get_history(conn, 10, &filter)
+            .expect("filter by selection")
+            .into_iter()
+            .map(|item| item.text)
+            .collect();
+        texts.sort();
+        texts
+    };
+    assert_eq!(texts_for(&conn, SelectionSource::Clipboard), vec!["from clipboard"]);
+    assert_eq!(
+        texts_for(&conn, SelectionSource::Primary),
+        vec!["new primary", "old primary", "pinned primary"]
+    );
+
+    let removed = auto_clear_selection_before(&mut conn, SELECTION_PRIMARY, 200).expect("expire primary");
+    assert_eq!(removed, vec![old.id]);
+    assert_eq!(texts_for(&conn, SelectionSource::Primary), vec!["new primary", "pinned primary"]);
+    assert_eq!(texts_for(&conn, SelectionSource::Clipboard), vec!["from clipboard"]);
+}
+
+#[test]
+fn selection_clip_is_rolled_back_when_source_tag_fails() {
+    let conn = setup_conn();
+    conn.execute_batch(
+        "CREATE TRIGGER reject_formats BEFORE INSERT ON clip_formats
+         BEGIN SELECT RAISE(ABORT, 'rejected'); END;",
+    )
+    .expect("create trigger");
+
+    assert!(add_selection_clip(&conn, "primary text", SELECTION_PRIMARY, &no_analysis()).is_err());
+    let count: i64 = conn
+        .query_row("SELECT COUNT(*) FROM history", [], |row| row.get(0))
+        .expect("count history");
+    assert_eq!(count, 0, "失败时不应留下未标记来源的条目");
+}
+
+#[test]
+fn captured_code_is_tagged_with_language_and_filterable() {
+    let conn = setup_conn();
+    let rust_id = add_clip(&conn, "fn main() {\n    let mut n = 1;\n}".to_string(), 0, &no_analysis())
+        .expect("insert rust")
+        .expect("rust id");
+    add_clip(&conn, "def run(self):\n    return None".to_string(), 0, &no_analysis()).expect("insert python");
+    add_clip(&conn, "buy milk".to_string(), 0, &no_analysis()).expect("insert note");
+
+    let rust = get_clip_by_id(&conn, rust_id).expect("load rust").expect("rust item");
+    assert_eq!(rust.language.as_deref(), Some("rust"));
+    assert!(rust.language_confidence.is_some_and(|c| c > 0.0 && c <= 1.0));
+
+    let by_language = |language: &str| -> Vec<String> {
+        let filter = HistoryFilter { language: Some(language.to_string()), ..Default::default() };
+        get_history(&conn, 10, &filter)
+            .expect("filter by language")
+            .into_iter()
+            .map(|item| item.text)
+            .collect()
+    };
+    assert_eq!(by_language("python"), vec!["def run(self):\n    return None"]);
+    assert!(by_language("go").is_empty());
+
+    update_clip(&conn, rust_id, "plain words".to_string(), &no_analysis()).expect("edit");
+    let edited = get_clip_by_id(&conn, rust_id).expect("load edited").expect("edited item");
+    assert_eq!(edited.language, None);
+    assert_eq!(edited.language_confidence, None);
+}
+
+#[test]
+fn captured_text_is_tagged_with_subtype_and_suggests_actions() {
+    let conn = setup_conn();
+    let url_id = add_clip(&conn, "https://example.com/docs".to_string(), 0, &no_analysis())
+        .expect("insert url")
+        .expect("url id");
+    add_clip(&conn, "{\"id\": 1, \"tags\": []}".to_string(), 0, &no_analysis()).expect("insert json");
+    let note_id = add_clip(&conn, "buy milk".to_string(), 0, &no_analysis())
+        .expect("insert note")
+        .expect("note id");
+
+    let url = get_clip_by_id(&conn, url_id).expect("load url").expect("url item");
+    assert_eq!(url.content_subtype.as_deref(), Some("url"));
+
+    let by_subtype = |subtype: &str| -> Vec<String> {
+        let filter = HistoryFilter { content_subtype: Some(subtype.to_string()), ..Default::default() };
+        get_history(&conn, 10, &filter)
+            .expect("filter by subtype")
+            .into_iter()
+            .map(|item| item.text)
+            .collect()
+    };
+    assert_eq!(by_subtype("json"), vec!["{\"id\": 1, \"tags\": []}"]);
+    assert!(by_subtype("email").is_empty());
+
+    let actions = get_suggested_actions(&conn, url_id).expect("url actions");
+    assert_eq!(actions.first().map(|a| a.id), Some("open_url"));
+    assert!(get_suggested_actions(&conn, note_id).expect("note actions").is_empty());
+    assert!(get_suggested_actions(&conn, 9999).expect("missing actions").is_empty());
+
+    update_clip(&conn, url_id, "#1e90ff".to_string(), &no_analysis()).expect("edit");
+    let edited = get_clip_by_id(&conn, url_id).expect("load edited").expect("edited item");
+    assert_eq!(edited.content_subtype.as_deref(), Some("hex_color"));
+}
@@ -0,0 +1,115 @@
+use super::*;
+use crate::db::schema::initialize_schema;
+
+fn setup_conn() -> Connection {
+    let conn = Connection::open_in_memory().expect("create memory db");
+    initialize_schema(&conn).expect("init schema");
+    conn
+}
+
+fn insert_clip(conn: &Connection, text: &str, content_type: &str) -> i64 {
+    conn.execute(
+        "INSERT INTO history (text, timestamp, content_type) VALUES (?1, 1, ?2)",
+        params![text, content_type],
+    )
+    .expect("insert history");
+    conn.last_insert_rowid()
+}
+
+fn format_pairs(item: ClipItem) -> Vec<(String, String)> {
+    item.formats.into_iter().map(|f| (f.format, f.content)).collect()
+}
+
+#[test]
+fn resolve_target_accepts_urls_and_url_items_only() {
+    let conn = setup_conn();
+    let url = "https://cdn.example.com/a.png";
+    let link = insert_clip(&conn, &format!("  {}\n", url), "text");
+    let image = insert_clip(&conn, "/data/images/aa/a.png", "image");
+    let note = insert_clip(&conn, "see https://cdn.example.com/a.png", "text");
+    let ftp = insert_clip(&conn, "ftp://cdn.example.com/a.png", "text");
+
+    assert_eq!(
+        resolve_target(&conn, &link.to_string()).expect("url item"),
+        SaveTarget::Item { id: link, url: url.to_string() }
+    );
+    assert_eq!(
+        resolve_target(&conn, url).expect("plain url"),
+        SaveTarget::NewClip { url: url.to_string() }
+    );
+    for input in [image.to_string(), note.to_string(), ftp.to_string(), "file:///a.png".to_string()] {
+        assert!(matches!(resolve_target(&conn, &input), Err(AppError::InvalidRequest(_))), "input {}", input);
+    }
+    assert!(matches!(resolve_target(&conn, "9999"), Err(AppError::NotFound(_))));
+}
+
+#[test]
+fn converted_item_keeps_id_and_records_source_url() {
+    let conn = setup_conn();
+    let url = "https://cdn.example.com/a.png";
+    let id = insert_clip(&conn, url, "text");
+    conn.execute(
+        "UPDATE history SET language = 'rust', language_confidence = 0.9, content_subtype = 'url' WHERE id = ?1",
+        params![id],
+    )
+    .expect("tag link item");
+    let path = format!("/data/images/aa/{}.png", "a".repeat(64));
+
+    convert_to_image_clip(&conn, id, &path, url, &AssetAnalysis::default()).expect("convert");
+
+    let item = get_clip_by_id(&conn, id).expect("query").expect("item exists");
+    assert_eq!(item.text, path);
+    assert_eq!(item.content_type, "image");
+    assert_eq!(item.timestamp, 1);
+    let formats = format_pairs(item);
+    assert!(formats.contains(&("image".to_string(), path.clone())));
+    assert!(formats.contains(&(SOURCE_URL_FORMAT_KEY.to_string(), url.to_string())));
+
+    let asset_count: i64 = conn
+        .query_row("SELECT COUNT(*) FROM history_assets WHERE item_id = ?1", params![id], |row| row.get(0))
+        .expect("count assets");
+    assert_eq!(asset_count, 1);
+    let tags: (Option<String>, Option<f64>, Option<String>) = conn
+        .query_row(
+            "SELECT language, language_confidence, content_subtype FROM history WHERE id = ?1",
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .expect("query tags");
+    assert_eq!(tags, (None, None, None), "链接文本的识别结果不应残留在图片条目上");
+    assert!(matches!(convert_to_image_clip(&conn, 9999, &path, url, &AssetAnalysis::default()), Err(AppError::NotFound(_))));
+}
+
+#[test]
+fn failed_conversion_leaves_link_item_untouched() {
+    let conn = setup_conn();
+    let url = "https://cdn.example.com/a.png";
+    let id = insert_clip(&conn, url, "text");
+    let path = format!("/data/images/aa/{}.png", "a".repeat(64));
+    conn.execute_batch(
+        "CREATE TRIGGER reject_formats BEFORE INSERT ON clip_formats
+         BEGIN SELECT RAISE(ABORT, 'rejected'); END;",
+    )
+    .expect("create trigger");
+
+    assert!(convert_to_image_clip(&conn, id, &path, url, &AssetAnalysis::default()).is_err());
+    let item = get_clip_by_id(&conn, id).expect("query").expect("item exists");
+    assert_eq!((item.text.as_str(), item.content_type.as_str()), (url, "text"));
+    let asset_count: i64 = conn
+        .query_row("SELECT COUNT(*) FROM history_assets WHERE item_id = ?1", params![id], |row| row.get(0))
+        .expect("count assets");
+    assert_eq!(asset_count, 0);
+}
+
+#[test]
+fn downloaded_url_becomes_new_image_item() {
+    let conn = setup_conn();
+    let url = "https://cdn.example.com/b.gif";
+    let path = format!("/data/images/bb/{}.gif", "b".repeat(64));
+
+    let id = insert_downloaded_clip(&conn, &path, url, 42, &AssetAnalysis::default()).expect("insert");
+
+    let item = get_clip_by_id(&conn, id).expect("query").expect("item exists");
+    assert_eq!((item.text.as_str(), item.content_type.as_str(), item.timestamp), (path.as_str(), "image", 42));
+    assert!(format_pairs(item).contains(&(SOURCE_URL_FORMAT_KEY.to_string(), url.to_string())));
+}
@@ -0,0 +1,83 @@
+use super::*;
+use crate::db::schema::initialize_schema;
+
+fn setup_conn() -> Connection {
+    let conn = Connection::open_in_memory().expect("create memory db");
+    initialize_schema(&conn).expect("init schema");
+    conn
+}
+
+fn insert_clip(conn: &Connection, text: &str, content_type: &str) -> i64 {
+    conn.execute(
+        "INSERT INTO history (text, timestamp, content_type) VALUES (?1, 1, ?2)",
+        params![text, content_type],
+    )
+    .expect("insert history");
+    conn.last_insert_rowid()
+}
+
+#[test]
+fn load_edit_source_requires_raster_image_item() {
+    let conn = setup_conn();
+    let hash = "a".repeat(64);
+    let png = format!("/data/images/aa/{}.png", hash);
+    let image = insert_clip(&conn, &png, "image");
+    let svg = insert_clip(&conn, &format!("/data/images/aa/{}.svg", hash), "image");
+    let note = insert_clip(&conn, &png, "text");
+
+    assert_eq!(load_edit_source(&conn, image).expect("raster source"), png);
+    for id in [svg, note] {
+        assert!(matches!(load_edit_source(&conn, id), Err(AppError::InvalidRequest(_))), "item {}", id);
+    }
+    assert!(matches!(load_edit_source(&conn, 9999), Err(AppError::NotFound(_))));
+}
+
+#[test]
+fn edited_clip_is_new_image_item_linked_to_source() {
+    let conn = setup_conn();
+    let source_path = format!("/data/images/aa/{}.png", "a".repeat(64));
+    let source = insert_clip(&conn, &source_path, "image");
+    let edited_path = format!("/data/images/bb/{}.png", "b".repeat(64));
+
+    let id = insert_edited_clip(&conn, source, &edited_path, 42, &AssetAnalysis::of_text(&edited_path)).expect("insert edited clip");
+
+    let item = get_clip_by_id(&conn, id).expect("query").expect("edited item exists");
+    assert_ne!(id, source);
+    assert_eq!(item.text, edited_path);
+    assert_eq!(item.content_type, "image");
+    assert_eq!(item.timestamp, 42);
+    let formats: Vec<(String, String)> = item.formats.into_iter().map(|f| (f.format, f.content)).collect();
+    assert!(formats.contains(&("image".to_string(), edited_path.clone())));
+    assert!(formats.contains(&(EDITED_FROM_FORMAT_KEY.to_string(), source.to_string())));
+
+    let asset_count: i64 = conn
+        .query_row("SELECT COUNT(*) FROM history_assets WHERE item_id = ?1", params![id], |row| row.get(0))
+        .expect("count assets");
+    assert_eq!(asset_count, 1);
+
+    let original = get_clip_by_id(&conn, source).expect("query").expect("source exists");
+    assert_eq!(original.text, source_path);
+    assert!(original.formats.is_empty());
+}
+
+#[test]
+fn failed_format_write_rolls_back_edited_clip() {
+    let conn = setup_conn();
+    let source = insert_clip(&conn, &format!("/data/images/aa/{}.png", "a".repeat(64)), "image");
+    let edited_path = format!("/data/images/bb/{}.png", "b".repeat(64));
+    conn.execute_batch(
+        "CREATE TRIGGER reject_formats BEFORE INSERT ON clip_formats
+         BEGIN SELECT RAISE(ABORT, 'rejected'); END;",
+    )
+    .expect("create trigger");
+
+    assert!(insert_edited_clip(&conn, source, &edited_path, 42, &AssetAnalysis::of_text(&edited_path)).is_err());
+    let count: i64 = conn
+        .query_row("SELECT COUNT(*) FROM history", [], |row| row.get(0))
+        .expect("count history");
+    assert_eq!(count, 1, "失败时不应留下编辑结果条目");
+    let asset_count: i64 = conn
+        .query_row("SELECT COUNT(*) FROM history_assets", [], |row| row.get(0))
+        .expect("count assets");
+    assert_eq!(asset_count, 0);
+}
@@ -0,0 +1,104 @@
+use super::*;
+use crate::db::cleanup::{sync_item_assets_for_text, AssetAnalysis};
+use crate::db::schema::initialize_schema;
+use image::{DynamicImage, ImageBuffer, ImageFormat, Rgb};
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn unique_temp_dir(prefix: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let dir = std::env::temp_dir().join(format!("{}_{}", prefix, nanos));
+    let _ = fs::create_dir_all(&dir);
+    dir
+}
+
+fn write_rgb_png(path: &Path, width: u32, height: u32) {
+    let img = ImageBuffer::from_pixel(width, height, Rgb([10u8, 20, 30]));
+    DynamicImage::ImageRgb8(img).save_with_format(path, ImageFormat::Png).expect("write png");
+}
+
+fn insert_image_clip(conn: &Connection, path: &Path) -> i64 {
+    let text = path.to_string_lossy().to_string();
+    conn.execute(
+        "INSERT INTO history (text, timestamp, content_type) VALUES (?1, 1, 'image')",
+        params![text],
+    )
+    .expect("insert history");
+    let id = conn.last_insert_rowid();
+    sync_item_assets_for_text(conn, id, &text, &AssetAnalysis::of_text(&text)).expect("sync assets");
+    id
+}
+
+#[test]
+fn metadata_is_stored_at_capture_and_loaded_in_batch() {
+    let dir = unique_temp_dir("cliphist_image_meta");
+    let path = dir.join("img_1.png");
+    write_rgb_png(&path, 30, 20);
+
+    let conn = Connection::open_in_memory().expect("create memory db");
+    initialize_schema(&conn).expect("init schema");
+    let id = insert_image_clip(&conn, &path);
+    conn.execute("INSERT INTO history (text, timestamp) VALUES ('plain', 2)", [])
+        .expect("insert text");
+    let text_id = conn.last_insert_rowid();
+
+    let map = load_image_metadata_batch(&conn, &[id, text_id]).expect("load batch");
+    assert_eq!(map.len(), 1);
+    let meta = &map[&id];
+    assert_eq!((meta.width, meta.height), (30, 20));
+    assert_eq!(meta.color_type, "Rgb8");
+    assert!(meta.byte_size > 0);
+
+    // 条目文本改为非图片后，元数据随映射一起清除
+    sync_item_assets_for_text(&conn, id, "not an image", &AssetAnalysis::default()).expect("resync");
+    assert!(load_image_metadata_batch(&conn, &[id]).expect("reload").is_empty());
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn metadata_rows_cascade_with_history_delete() {
+    let dir = unique_temp_dir("cliphist_image_meta_cascade");
+    let path = dir.join("img_2.png");
+    write_rgb_png(&path, 8, 8);
+
+    let conn = Connection::open_in_memory().expect("create memory db");
+    initialize_schema(&conn).expect("init schema");
+    let id = insert_image_clip(&conn, &path);
+
+    conn.execute("DELETE FROM history WHERE id = ?1", params![id]).expect("delete");
+    let count: i64 = conn
+        .query_row("SELECT COUNT(*) FROM image_metadata", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(count, 0);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn text_chunks_round_trip_as_json() {
+    let conn = Connection::open_in_memory().expect("create memory db");
+    initialize_schema(&conn).expect("init schema");
+    conn.execute("INSERT INTO history (text, timestamp) VALUES ('x', 1)", []).unwrap();
+    let id = conn.last_insert_rowid();
+
+    let mut meta = ImageMetadata {
+        width: 3840,
+        height: 2160,
+        color_type: "Rgba8".to_string(),
+        byte_size: 42,
+        camera_model: Some("X100V".to_string()),
+        orientation: Some(1),
+        has_gps: true,
+        ..Default::default()
+    };
+    meta.text_chunks.insert("Software".to_string(), "cliphist".to_string());
+    store_image_metadata(&conn, id, &meta).expect("store");
+
+    let loaded = load_image_metadata_batch(&conn, &[id]).expect("load").remove(&id);
+    assert_eq!(loaded, Some(meta));
+}
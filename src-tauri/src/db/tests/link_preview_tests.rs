@@ -0,0 +1,110 @@
+use super::*;
+use crate::db::schema::initialize_schema;
+
+const NOW: i64 = 1_700_000_000_000;
+
+fn setup_conn() -> Connection {
+    let conn = Connection::open_in_memory().expect("create memory db");
+    initialize_schema(&conn).expect("init schema");
+    conn
+}
+
+fn insert_clip(conn: &Connection, text: &str, timestamp: i64) -> i64 {
+    conn.execute(
+        "INSERT INTO history (text, timestamp) VALUES (?1, ?2)",
+        params![text, timestamp],
+    )
+    .expect("insert history");
+    conn.last_insert_rowid()
+}
+
+fn preview(url: &str, fetched_at: i64) -> LinkPreview {
+    LinkPreview {
+        url: url.to_string(),
+        title: Some("Example".to_string()),
+        description: None,
+        favicon: Some("https://example.com/favicon.ico".to_string()),
+        image: None,
+        fetched_at,
+    }
+}
+
+fn lookup(conn: &Connection, id: i64, force: bool) -> PreviewLookup {
+    lookup_preview(conn, id, force, NOW).expect("lookup preview")
+}
+
+#[test]
+fn link_target_accepts_only_http_links() {
+    assert_eq!(link_target("  https://example.com/a?b=1 \n").as_deref(), Some("https://example.com/a?b=1"));
+    assert_eq!(link_target("www.example.com").as_deref(), Some("https://www.example.com/"));
+    assert_eq!(link_target("WWW.Example.com/x").as_deref(), Some("https://www.example.com/x"));
+    assert_eq!(link_target("ftp://example.com/file"), None);
+    assert_eq!(link_target("see https://example.com"), None);
+    assert_eq!(link_target("mailto:a@example.com"), None);
+    assert_eq!(link_target("中文文本"), None);
+    assert_eq!(link_target(""), None);
+}
+
+#[test]
+fn lookup_skips_missing_and_non_link_items() {
+    let conn = setup_conn();
+    let note = insert_clip(&conn, "buy milk", 1);
+
+    assert!(matches!(lookup(&conn, note, false), PreviewLookup::NotLink));
+    assert!(matches!(lookup(&conn, 9999, false), PreviewLookup::NotLink));
+}
+
+#[test]
+fn lookup_uses_fresh_cache_and_refetches_stale_or_forced() {
+    let conn = setup_conn();
+    let id = insert_clip(&conn, "https://example.com/", 1);
+
+    match lookup(&conn, id, false) {
+        PreviewLookup::Fetch(url) => assert_eq!(url, "https://example.com/"),
+        _ => panic!("uncached link should be fetched"),
+    }
+
+    store_link_preview(&conn, id, &preview("https://example.com/", NOW - 1000)).expect("store");
+    match lookup(&conn, id, false) {
+        PreviewLookup::Cached { preview, shared } => {
+            assert!(!shared);
+            assert_eq!(preview.title.as_deref(), Some("Example"));
+        }
+        _ => panic!("fresh preview should be served from cache"),
+    }
+    assert!(matches!(lookup(&conn, id, true), PreviewLookup::Fetch(_)));
+
+    store_link_preview(&conn, id, &preview("https://example.com/", NOW - LINK_PREVIEW_TTL_MS - 1))
+        .expect("store stale");
+    assert!(matches!(lookup(&conn, id, false), PreviewLookup::Fetch(_)));
+}
+
+#[test]
+fn lookup_shares_preview_between_items_with_same_link() {
+    let conn = setup_conn();
+    let first = insert_clip(&conn, "https://example.com/", 1);
+    let second = insert_clip(&conn, "https://example.com/", 2);
+    let other = insert_clip(&conn, "https://example.org/", 3);
+    store_link_preview(&conn, first, &preview("https://example.com/", NOW)).expect("store");
+
+    assert!(matches!(lookup(&conn, second, false), PreviewLookup::Cached { shared: true, .. }));
+    assert!(matches!(lookup(&conn, other, false), PreviewLookup::Fetch(_)));
+}
+
+#[test]
+fn corrupt_cache_is_ignored_and_clear_removes_preview() {
+    let conn = setup_conn();
+    let id = insert_clip(&conn, "https://example.com/", 1);
+    conn.execute(
+        "INSERT INTO clip_formats (item_id, format, content) VALUES (?1, ?2, 'not json')",
+        params![id, LINK_PREVIEW_FORMAT_KEY],
+    )
+    .expect("insert corrupt preview");
+    assert!(matches!(lookup(&conn, id, false), PreviewLookup::Fetch(_)));
+
+    store_link_preview(&conn, id, &preview("https://example.com/", NOW)).expect("overwrite");
+    assert!(matches!(lookup(&conn, id, false), PreviewLookup::Cached { .. }));
+
+    clear_link_preview(&conn, id).expect("clear");
+    assert!(matches!(lookup(&conn, id, false), PreviewLookup::Fetch(_)));
+}
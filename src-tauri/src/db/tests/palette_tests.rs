@@ -0,0 +1,151 @@
+use super::*;
+use crate::db::cleanup::{sync_item_assets_for_text, AssetAnalysis};
+use crate::db::schema::initialize_schema;
+use image::{DynamicImage, ImageFormat, Rgba, RgbaImage};
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn unique_temp_dir(prefix: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let dir = std::env::temp_dir().join(format!("{}_{}", prefix, nanos));
+    let _ = fs::create_dir_all(&dir);
+    dir
+}
+
+/// 左侧 `main_percent`% 为主色，其余为副色
+fn write_two_tone(path: &Path, main: [u8; 3], accent: [u8; 3], main_percent: u32) {
+    let img = RgbaImage::from_fn(100, 20, |x, _| {
+        let c = if x < main_percent { main } else { accent };
+        Rgba([c[0], c[1], c[2], 255])
+    });
+    DynamicImage::ImageRgba8(img).save_with_format(path, ImageFormat::Png).expect("write png");
+}
+
+fn insert_image_clip(conn: &Connection, path: &Path) -> i64 {
+    let text = path.to_string_lossy().to_string();
+    conn.execute(
+        "INSERT INTO history (text, timestamp, content_type) VALUES (?1, 1, 'image')",
+        params![text],
+    )
+    .expect("insert history");
+    let id = conn.last_insert_rowid();
+    sync_item_assets_for_text(conn, id, &text, &AssetAnalysis::of_text(&text)).expect("sync assets");
+    id
+}
+
+#[test]
+fn palette_is_stored_at_capture_in_ratio_order() {
+    let dir = unique_temp_dir("cliphist_db_palette");
+    let path = dir.join("img_1.png");
+    write_two_tone(&path, [0, 90, 200], [250, 200, 0], 70);
+
+    let conn = Connection::open_in_memory().expect("create memory db");
+    initialize_schema(&conn).expect("init schema");
+    let id = insert_image_clip(&conn, &path);
+
+    let palette = load_palette(&conn, id).expect("palette");
+    // 降采样会在色块交界处产生少量过渡色，只校验前两位
+    assert!(palette.len() >= 2);
+    assert_eq!(palette[0].hex, "#005ac8");
+    assert_eq!(palette[1].hex, "#fac800");
+    assert!(palette[0].ratio > palette[1].ratio);
+
+    // 非图片条目没有主色
+    conn.execute("INSERT INTO history (text, timestamp) VALUES ('text', 2)", []).unwrap();
+    assert!(load_palette(&conn, conn.last_insert_rowid()).expect("empty").is_empty());
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn search_by_color_matches_within_threshold() {
+    let dir = unique_temp_dir("cliphist_db_color_search");
+    let blue_path = dir.join("img_blue.png");
+    let red_path = dir.join("img_red.png");
+    let speck_path = dir.join("img_speck.png");
+    write_two_tone(&blue_path, [0, 90, 200], [255, 255, 255], 60);
+    write_two_tone(&red_path, [210, 30, 30], [255, 255, 255], 60);
+    // 蓝色仅占 2%，低于搜索占比下限
+    write_two_tone(&speck_path, [240, 240, 240], [0, 90, 200], 98);
+
+    let conn = Connection::open_in_memory().expect("create memory db");
+    initialize_schema(&conn).expect("init schema");
+    let blue = insert_image_clip(&conn, &blue_path);
+    let red = insert_image_clip(&conn, &red_path);
+    insert_image_clip(&conn, &speck_path);
+
+    let matches = search_by_color(&conn, [10, 100, 210], 40.0).expect("search blue");
+    assert_eq!(matches.iter().map(|m| m.id).collect::<Vec<_>>(), vec![blue]);
+    assert_eq!(matches[0].color, "#005ac8");
+
+    let whites = search_by_color(&conn, [255, 255, 255], 10.0).expect("search white");
+    let mut ids: Vec<i64> = whites.iter().map(|m| m.id).collect();
+    ids.sort();
+    assert_eq!(ids, vec![blue, red]);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+/// 模拟命令中的锁外补算：读取待算列表、解码、批量写入
+fn run_backfill(conn: &mut Connection, item_id: Option<i64>) {
+    let results: Vec<(i64, Option<Vec<PaletteColor>>)> = pending_palette_items(conn, item_id)
+        .expect("pending")
+        .into_iter()
+        .map(|(id, path)| (id, compute_palette(&path)))
+        .collect();
+    store_palette_results(conn, &results).expect("store");
+}
+
+#[test]
+fn backfill_fills_palettes_missing_for_old_items() {
+    let dir = unique_temp_dir("cliphist_db_palette_backfill");
+    let path = dir.join("img_old.png");
+    write_two_tone(&path, [30, 160, 60], [30, 160, 60], 100);
+
+    let mut conn = Connection::open_in_memory().expect("create memory db");
+    initialize_schema(&conn).expect("init schema");
+    let id = insert_image_clip(&conn, &path);
+    conn.execute("DELETE FROM image_palette", []).unwrap();
+    assert!(search_by_color(&conn, [30, 160, 60], 5.0).expect("search").is_empty());
+
+    assert_eq!(pending_palette_items(&conn, Some(id + 1)).expect("pending").len(), 0);
+    run_backfill(&mut conn, Some(id));
+    assert!(pending_palette_items(&conn, None).expect("pending").is_empty());
+
+    let matches = search_by_color(&conn, [30, 160, 60], 5.0).expect("search");
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].id, id);
+    assert_eq!(matches[0].distance, 0.0);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn undecodable_images_are_marked_failed_and_not_retried() {
+    let dir = unique_temp_dir("cliphist_db_palette_failed");
+    let path = dir.join("img_1.png");
+    write_two_tone(&path, [30, 160, 60], [250, 200, 0], 50);
+
+    let mut conn = Connection::open_in_memory().expect("create memory db");
+    initialize_schema(&conn).expect("init schema");
+    let id = insert_image_clip(&conn, &path);
+    conn.execute("DELETE FROM image_palette", []).unwrap();
+
+    // 元数据只读文件头；截断像素数据后主色提取失败
+    let bytes = fs::read(&path).unwrap();
+    fs::write(&path, &bytes[..bytes.len() / 2]).unwrap();
+    assert_eq!(pending_palette_items(&conn, None).expect("pending").len(), 1);
+    run_backfill(&mut conn, None);
+
+    let failed: i64 = conn
+        .query_row("SELECT palette_failed FROM image_metadata WHERE item_id = ?1", params![id], |row| row.get(0))
+        .unwrap();
+    assert_eq!(failed, 1);
+    assert!(pending_palette_items(&conn, None).expect("pending").is_empty());
+
+    let _ = fs::remove_dir_all(&dir);
+}
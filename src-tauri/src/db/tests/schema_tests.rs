@@ -99,6 +99,9 @@ fn initialize_schema_creates_expected_columns_and_indexes() {
         "is_snippet",
         "is_favorite",
         "picked_color",
+        "language",
+        "language_confidence",
+        "content_subtype",
     ] {
         assert!(
             column_set.contains(required),
@@ -124,6 +127,11 @@ fn initialize_schema_creates_expected_columns_and_indexes() {
         "idx_item_tags_tag_id",
         "idx_history_assets_item_id",
         "idx_history_assets_path",
+        "idx_history_assets_hash",
+        "idx_assets_path",
+        "idx_image_metadata_size",
+        "idx_history_language",
+        "idx_history_content_subtype",
     ] {
         assert!(
             index_set.contains(required),
@@ -345,3 +353,109 @@ fn v6_migration_repairs_broken_item_tags_fk() {
         .expect("query user_version after v6 repair");
     assert_eq!(version, super::SCHEMA_VERSION);
 }
+
+#[test]
+fn v17_migration_repairs_broken_clip_formats_fk() {
+    let conn = Connection::open_in_memory().expect("create memory db");
+    initialize_schema(&conn).expect("init should succeed");
+    conn.execute_batch(
+        "PRAGMA foreign_keys=OFF;
+        DROP TABLE clip_formats;
+        CREATE TABLE clip_formats (
+            item_id INTEGER NOT NULL,
+            format  TEXT NOT NULL,
+            content TEXT NOT NULL,
+            PRIMARY KEY (item_id, format),
+            FOREIGN KEY (item_id) REFERENCES history_old(id) ON DELETE CASCADE
+        );
+        INSERT INTO history (id, text, timestamp) VALUES (1, 'hello', 1);
+        INSERT INTO clip_formats (item_id, format, content) VALUES (1, 'html', '<b>hello</b>');
+        PRAGMA user_version = 16;
+        PRAGMA foreign_keys=ON;"
+    )
+    .expect("prepare broken v16 state");
+
+    let broken = conn.execute("DELETE FROM clip_formats WHERE item_id = 1", []);
+    assert!(broken.is_err(), "应能重现 clip_formats 的 history_old 外键悬空问题");
+
+    initialize_schema(&conn).expect("v17 migration should repair broken clip_formats fk");
+
+    let content: String = conn
+        .query_row("SELECT content FROM clip_formats WHERE item_id = 1", [], |row| row.get(0))
+        .expect("修复后附加格式应保留");
+    assert_eq!(content, "<b>hello</b>");
+    conn.execute("DELETE FROM history WHERE id = 1", [])
+        .expect("修复后删除历史应级联删除附加格式");
+    let remaining: i64 = conn
+        .query_row("SELECT COUNT(*) FROM clip_formats", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(remaining, 0);
+}
+
+#[test]
+fn fresh_database_clip_formats_reference_history() {
+    let conn = Connection::open_in_memory().expect("create memory db");
+    initialize_schema(&conn).expect("init should succeed");
+    let parent: String = conn
+        .query_row("SELECT \"table\" FROM pragma_foreign_key_list('clip_formats')", [], |row| row.get(0))
+        .expect("clip_formats fk");
+    assert_eq!(parent, "history");
+}
+
+#[test]
+fn v13_migration_backfills_language_for_text_items() {
+    let conn = Connection::open_in_memory().expect("create memory db");
+    initialize_schema(&conn).expect("init should succeed");
+    conn.execute_batch(
+        "INSERT INTO history (text, timestamp, content_type)
+         VALUES ('fn main() {\n    let mut n = 1;\n}', 1, 'text'),
+                ('SELECT id FROM users WHERE id = 1;', 2, 'rich'),
+                ('fn main() { let mut n = 1; }', 3, 'files'),
+                ('just a note', 4, 'text');
+         PRAGMA user_version = 12;"
+    )
+    .expect("prepare v12 state");
+
+    initialize_schema(&conn).expect("v13 migration should succeed");
+
+    let languages: Vec<Option<String>> = conn
+        .prepare("SELECT language FROM history ORDER BY id")
+        .expect("prepare language query")
+        .query_map([], |row| row.get(0))
+        .expect("query languages")
+        .collect::<Result<Vec<_>, _>>()
+        .expect("collect languages");
+    assert_eq!(
+        languages,
+        vec![Some("rust".to_string()), Some("sql".to_string()), None, None]
+    );
+}
+
+#[test]
+fn v14_migration_backfills_content_subtype_for_text_items() {
+    let conn = Connection::open_in_memory().expect("create memory db");
+    initialize_schema(&conn).expect("init should succeed");
+    conn.execute_batch(
+        "INSERT INTO history (text, timestamp, content_type)
+         VALUES ('https://example.com/docs', 1, 'text'),
+                ('{\"a\": 1}', 2, 'rich'),
+                ('/tmp/a.png', 3, 'image'),
+                ('just a note', 4, 'text');
+         PRAGMA user_version = 13;"
+    )
+    .expect("prepare v13 state");
+
+    initialize_schema(&conn).expect("v14 migration should succeed");
+
+    let subtypes: Vec<Option<String>> = conn
+        .prepare("SELECT content_subtype FROM history ORDER BY id")
+        .expect("prepare subtype query")
+        .query_map([], |row| row.get(0))
+        .expect("query subtypes")
+        .collect::<Result<Vec<_>, _>>()
+        .expect("collect subtypes");
+    assert_eq!(
+        subtypes,
+        vec![Some("url".to_string()), Some("json".to_string()), None, None]
+    );
+}
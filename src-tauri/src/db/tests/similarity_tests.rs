@@ -0,0 +1,237 @@
+use super::*;
+use crate::db::cleanup::{sync_item_assets_for_text, AssetAnalysis};
+use crate::db::schema::initialize_schema;
+use image::{DynamicImage, ImageBuffer, ImageFormat, Rgba};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn unique_temp_dir(prefix: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let dir = std::env::temp_dir().join(format!("{}_{}", prefix, nanos));
+    let _ = fs::create_dir_all(&dir);
+    dir
+}
+
+/// 水平渐变图；`flip` 为 true 时方向相反，`noise` 在左上角点一个像素
+fn write_gradient(path: &Path, flip: bool, noise: bool) {
+    let mut img = ImageBuffer::from_fn(64, 48, |x, _y| {
+        let v = (x * 4) as u8;
+        let v = if flip { 255 - v } else { v };
+        Rgba([v, v, v, 255])
+    });
+    if noise {
+        img.put_pixel(0, 0, Rgba([255, 0, 0, 255]));
+    }
+    DynamicImage::ImageRgba8(img).save_with_format(path, ImageFormat::Png).expect("write png");
+}
+
+fn insert_image_clip(conn: &Connection, path: &Path, timestamp: i64, pinned: i32) -> i64 {
+    insert_image_clip_with(conn, path, timestamp, pinned, 0, true)
+}
+
+/// `analyze` 为 false 时模拟未预计算的旧数据
+fn insert_image_clip_with(
+    conn: &Connection,
+    path: &Path,
+    timestamp: i64,
+    pinned: i32,
+    snippet: i32,
+    analyze: bool,
+) -> i64 {
+    let text = path.to_string_lossy().to_string();
+    conn.execute(
+        "INSERT INTO history (text, timestamp, is_pinned, is_snippet, content_type) VALUES (?1, ?2, ?3, ?4, 'image')",
+        params![text, timestamp, pinned, snippet],
+    )
+    .expect("insert history");
+    let id = conn.last_insert_rowid();
+    let analysis = if analyze { AssetAnalysis::of_text(&text) } else { AssetAnalysis::default() };
+    sync_item_assets_for_text(conn, id, &text, &analysis).expect("sync assets");
+    id
+}
+
+fn hashed(id: i64, timestamp: i64, protected: bool, phash: u64) -> HashedImage {
+    HashedImage { id, timestamp, protected, phash }
+}
+
+#[test]
+fn dhash_is_stable_for_near_identical_images() {
+    let dir = unique_temp_dir("cliphist_dhash");
+    let a = dir.join("img_1.png");
+    let b = dir.join("img_2.png");
+    let c = dir.join("img_3.png");
+    write_gradient(&a, false, false);
+    write_gradient(&b, false, true);
+    write_gradient(&c, true, false);
+
+    let ha = compute_dhash(&a).expect("hash a");
+    let hb = compute_dhash(&b).expect("hash b");
+    let hc = compute_dhash(&c).expect("hash c");
+
+    assert!(hamming_distance(ha, hb) <= 4);
+    assert!(hamming_distance(ha, hc) > 32);
+    assert!(compute_dhash(&dir.join("missing.png")).is_none());
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn phash_is_stored_at_capture_and_similar_images_are_found() {
+    let dir = unique_temp_dir("cliphist_similar");
+    let a = dir.join("img_1.png");
+    let b = dir.join("img_2.png");
+    let c = dir.join("img_3.png");
+    write_gradient(&a, false, false);
+    write_gradient(&b, false, true);
+    write_gradient(&c, true, false);
+
+    let conn = Connection::open_in_memory().expect("create memory db");
+    initialize_schema(&conn).expect("init schema");
+    let id_a = insert_image_clip(&conn, &a, 1, 0);
+    let id_b = insert_image_clip(&conn, &b, 2, 0);
+    insert_image_clip(&conn, &c, 3, 0);
+
+    let stored: i64 = conn
+        .query_row("SELECT COUNT(*) FROM assets WHERE phash IS NOT NULL", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(stored, 3);
+
+    let matches = find_similar_images(&conn, id_a, 8).expect("find similar");
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].id, id_b);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn duplicate_groups_keep_pinned_or_newest_and_bulk_delete_the_rest() {
+    let dir = unique_temp_dir("cliphist_dup_groups");
+    let paths: Vec<PathBuf> = (0..4).map(|i| dir.join(format!("img_{}.png", i))).collect();
+    write_gradient(&paths[0], false, false);
+    write_gradient(&paths[1], false, true);
+    write_gradient(&paths[2], true, false);
+    write_gradient(&paths[3], true, true);
+
+    let mut conn = Connection::open_in_memory().expect("create memory db");
+    initialize_schema(&conn).expect("init schema");
+    let old_pinned = insert_image_clip(&conn, &paths[0], 1, 1);
+    let newer = insert_image_clip(&conn, &paths[1], 2, 0);
+    let flip_old = insert_image_clip(&conn, &paths[2], 3, 0);
+    let flip_new = insert_image_clip(&conn, &paths[3], 4, 0);
+
+    let groups = find_duplicate_image_groups(&conn, 8).expect("find groups");
+    assert_eq!(groups.len(), 2);
+    let pinned_group = groups.iter().find(|g| g.ids.contains(&old_pinned)).unwrap();
+    assert_eq!(pinned_group.keep_ids, vec![old_pinned]);
+    assert_eq!(pinned_group.remove_ids, vec![newer]);
+    let flip_group = groups.iter().find(|g| g.ids.contains(&flip_old)).unwrap();
+    assert_eq!(flip_group.keep_ids, vec![flip_new]);
+    assert_eq!(flip_group.remove_ids, vec![flip_old]);
+
+    let deleted = delete_duplicate_images(&mut conn, 8).expect("delete duplicates");
+    assert_eq!(deleted, 2);
+    let mut remaining: Vec<i64> = conn
+        .prepare("SELECT id FROM history ORDER BY id")
+        .unwrap()
+        .query_map([], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    remaining.sort();
+    assert_eq!(remaining, vec![old_pinned, flip_new]);
+    assert!(!paths[1].exists(), "orphan image of deleted clip should be removed");
+    assert!(!paths[2].exists());
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn undecodable_assets_are_marked_failed_and_not_retried() {
+    let dir = unique_temp_dir("cliphist_phash_failed");
+    let good = dir.join("img_1.png");
+    let broken = dir.join("img_2.png");
+    write_gradient(&good, false, false);
+    fs::write(&broken, b"not a png").expect("write broken");
+
+    let mut conn = Connection::open_in_memory().expect("create memory db");
+    initialize_schema(&conn).expect("init schema");
+    insert_image_clip(&conn, &broken, 1, 0);
+    let failed: i64 = conn
+        .query_row("SELECT phash_failed FROM assets WHERE phash IS NULL", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(failed, 1, "analysis failure is recorded at capture");
+
+    // 未预计算的旧数据：补算列表只包含它，不包含已失败的资源
+    insert_image_clip_with(&conn, &good, 2, 0, 0, false);
+    let pending = pending_phash_assets(&conn).expect("pending");
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].1, good.to_string_lossy());
+
+    let results: Vec<(String, Option<u64>)> = pending
+        .into_iter()
+        .map(|(hash, path)| {
+            let phash = compute_dhash(Path::new(&path));
+            (hash, phash)
+        })
+        .collect();
+    store_phashes(&mut conn, &results).expect("store");
+    assert!(pending_phash_assets(&conn).expect("pending").is_empty());
+    let stored: i64 = conn
+        .query_row("SELECT COUNT(*) FROM assets WHERE phash IS NOT NULL", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(stored, 1);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn grouping_compares_against_representative_instead_of_chaining() {
+    // A≈B、B≈C，但 A 与 C 相差 8 位：C 为代表时 A 不应被归入同组
+    let images = vec![hashed(1, 1, false, 0), hashed(2, 2, false, 0b1111), hashed(3, 3, false, 0xFF)];
+    let groups = group_duplicates(images, 4);
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0].ids, vec![3, 2]);
+    assert_eq!(groups[0].keep_ids, vec![3]);
+    assert_eq!(groups[0].remove_ids, vec![2]);
+}
+
+#[test]
+fn protected_items_become_representatives() {
+    // 保护条目较旧也优先成为代表：被删除的成员都与它相似
+    let images = vec![hashed(1, 1, true, 0), hashed(2, 2, false, 0b11), hashed(3, 3, false, 0xFF0)];
+    let groups = group_duplicates(images, 4);
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0].keep_ids, vec![1]);
+    assert_eq!(groups[0].remove_ids, vec![2]);
+}
+
+#[test]
+fn snippet_items_are_never_deleted_as_duplicates() {
+    let dir = unique_temp_dir("cliphist_dup_snippet");
+    let a = dir.join("img_1.png");
+    let b = dir.join("img_2.png");
+    write_gradient(&a, false, false);
+    write_gradient(&b, false, true);
+
+    let mut conn = Connection::open_in_memory().expect("create memory db");
+    initialize_schema(&conn).expect("init schema");
+    let snippet = insert_image_clip_with(&conn, &a, 1, 0, 1, true);
+    let newer = insert_image_clip(&conn, &b, 2, 0);
+
+    let groups = find_duplicate_image_groups(&conn, 8).expect("find groups");
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0].keep_ids, vec![snippet]);
+    assert_eq!(groups[0].remove_ids, vec![newer]);
+
+    assert_eq!(delete_duplicate_images(&mut conn, 8).expect("delete"), 1);
+    let remaining: i64 = conn
+        .query_row("SELECT id FROM history", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(remaining, snippet);
+
+    let _ = fs::remove_dir_all(&dir);
+}
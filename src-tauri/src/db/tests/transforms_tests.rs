@@ -0,0 +1,52 @@
+use super::*;
+use crate::db::schema::initialize_schema;
+
+fn setup_conn() -> Connection {
+    let conn = Connection::open_in_memory().expect("create memory db");
+    initialize_schema(&conn).expect("init schema");
+    conn
+}
+
+fn steps(ids: &[&str]) -> Vec<String> {
+    ids.iter().map(|id| id.to_string()).collect()
+}
+
+#[test]
+fn save_upserts_by_name_and_lists_sorted() {
+    let conn = setup_conn();
+    save_transform_chain(&conn, " tidy ", &steps(&["trim", "dedupe_lines"])).expect("save tidy");
+    save_transform_chain(&conn, "b64", &steps(&["base64_encode"])).expect("save b64");
+    save_transform_chain(&conn, "tidy", &steps(&["trim"])).expect("overwrite tidy");
+
+    let chains = get_transform_chains(&conn).expect("list");
+    let names: Vec<&str> = chains.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, vec!["b64", "tidy"]);
+    assert_eq!(chains[1].steps, steps(&["trim"]));
+
+    delete_transform_chain(&conn, "tidy").expect("delete");
+    delete_transform_chain(&conn, "missing").expect("delete is idempotent");
+    assert_eq!(get_transform_chains(&conn).expect("list").len(), 1);
+}
+
+#[test]
+fn save_rejects_blank_name_and_invalid_steps() {
+    let conn = setup_conn();
+    assert!(save_transform_chain(&conn, "  ", &steps(&["trim"])).is_err());
+    assert!(save_transform_chain(&conn, "empty", &[]).is_err());
+    assert!(save_transform_chain(&conn, "bad", &steps(&["trim", "nope"])).is_err());
+    assert!(get_transform_chains(&conn).expect("list").is_empty());
+}
+
+#[test]
+fn unparsable_rows_are_skipped() {
+    let conn = setup_conn();
+    conn.execute(
+        "INSERT INTO transform_chains (name, steps, updated_at) VALUES ('broken', 'not json', 1)",
+        [],
+    )
+    .unwrap();
+    save_transform_chain(&conn, "ok", &steps(&["trim"])).expect("save");
+    let chains = get_transform_chains(&conn).expect("list");
+    assert_eq!(chains.len(), 1);
+    assert_eq!(chains[0].name, "ok");
+}
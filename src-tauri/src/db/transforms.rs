@@ -0,0 +1,125 @@
+//! 文本转换链子模块
+//!
+//! ## 职责
+//! - 持久化用户保存的文本转换链（`transform_chains` 表）
+//! - 暴露转换链的查询、保存、删除 Tauri command
+//!
+//! ## 设计决策
+//! - 以名称为主键，保存同名转换链即覆盖（upsert）
+//! - 步骤以 JSON 数组存储转换 ID，保存前经 `transform::validate_chain` 校验
+//! - 读取时跳过无法解析的行，避免单条脏数据导致整个列表不可用
+//!
+//! ## 输入/输出
+//! - 输入：`State<DbState>`、转换链名称与步骤
+//! - 输出：`Vec<TransformChain>` 或 `Result<(), AppError>`
+//!
+//! ## 错误语义
+//! - SQL 操作失败返回 `AppError::Database`
+//! - 名称为空返回 `AppError::Database`，步骤非法返回 `AppError::Transform`
+//! - 删除不存在的名称静默成功（幂等语义）
+
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use tauri::State;
+
+use crate::error::AppError;
+use crate::i18n::message;
+use crate::transform::validate_chain;
+
+use super::{db_err, DbState};
+
+// ── 数据结构 ─────────────────────────────────────────────────
+
+/// 用户保存的转换链
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TransformChain {
+    pub name: String,
+    /// 按顺序执行的转换 ID
+    pub steps: Vec<String>,
+    pub updated_at: i64,
+}
+
+// ── 业务逻辑 ─────────────────────────────────────────────────
+
+fn get_transform_chains(conn: &Connection) -> Result<Vec<TransformChain>, AppError> {
+    let mut stmt = conn
+        .prepare("SELECT name, steps, updated_at FROM transform_chains ORDER BY name ASC")
+        .map_err(|e| db_err("db.prepare_transform_chain", e))?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, i64>(2)?))
+        })
+        .map_err(|e| db_err("db.query_transform_chain", e))?;
+
+    let mut chains = Vec::new();
+    for row in rows {
+        let (name, steps, updated_at) = row.map_err(|e| db_err("db.read_transform_chain", e))?;
+        match serde_json::from_str::<Vec<String>>(&steps) {
+            Ok(steps) => chains.push(TransformChain { name, steps, updated_at }),
+            Err(err) => log::warn!(
+                "{}",
+                message("db_log.bad_transform_chain_skipped", &[("name", &name), ("detail", &err)])
+            ),
+        }
+    }
+    Ok(chains)
+}
+
+fn save_transform_chain(
+    conn: &Connection,
+    name: &str,
+    steps: &[String],
+) -> Result<TransformChain, AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::Database(message("db.transform_name_empty", &[])));
+    }
+    validate_chain(steps)?;
+
+    let content = serde_json::to_string(steps).map_err(|e| AppError::Database(message("db.serialize_transform_failed", &[("detail", &e)])))?;
+    let now = chrono::Utc::now().timestamp_millis();
+    conn.execute(
+        "INSERT INTO transform_chains (name, steps, updated_at) VALUES (?1, ?2, ?3)
+         ON CONFLICT(name) DO UPDATE SET steps = excluded.steps, updated_at = excluded.updated_at",
+        params![name, content, now],
+    )
+    .map_err(|e| db_err("db.save_transform_chain", e))?;
+
+    Ok(TransformChain {
+        name: name.to_string(),
+        steps: steps.to_vec(),
+        updated_at: now,
+    })
+}
+
+fn delete_transform_chain(conn: &Connection, name: &str) -> Result<(), AppError> {
+    conn.execute("DELETE FROM transform_chains WHERE name = ?1", params![name.trim()])
+        .map_err(|e| db_err("db.delete_transform_chain", e))?;
+    Ok(())
+}
+
+// ── Tauri Commands ───────────────────────────────────────────
+
+#[tauri::command]
+pub fn db_get_transform_chains(state: State<'_, DbState>) -> Result<Vec<TransformChain>, AppError> {
+    super::with_read_conn(&state, get_transform_chains)
+}
+
+/// 保存（或覆盖同名）转换链
+#[tauri::command]
+pub fn db_save_transform_chain(
+    state: State<'_, DbState>,
+    name: String,
+    steps: Vec<String>,
+) -> Result<TransformChain, AppError> {
+    super::with_conn_mut(&state, |conn| save_transform_chain(conn, &name, &steps))
+}
+
+#[tauri::command]
+pub fn db_delete_transform_chain(state: State<'_, DbState>, name: String) -> Result<(), AppError> {
+    super::with_conn_mut(&state, |conn| delete_transform_chain(conn, &name))
+}
+
+#[cfg(test)]
+#[path = "tests/transforms_tests.rs"]
+mod tests;
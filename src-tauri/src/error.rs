@@ -10,12 +10,22 @@
 //!
 //! # 实现思路
 //!
-//! - 使用 `thiserror` 派生可读错误消息。
+//! - 使用 `thiserror` 派生可读错误消息，文案经 `i18n` 按当前后端语言渲染。
 //! - 为 `ImageError` 提供 `From` 转换，无需手动 map。
-//! - 实现 `Serialize` 将错误序列化为字符串，满足 Tauri IPC 要求。
+//! - 序列化为 `{kind, code, message, detail, retryable}`：`code` 为稳定的机器码，
+//!   前端据此区分“数据库被占用”“磁盘已满”等情况，无需匹配消息文本；
+//!   图片错误额外携带 `stage`，图片命令同样返回 `AppError`。
+//! - SQLite、I/O 与剪贴板错误在构造时按底层错误码细分为专用变体
+//!   （`from_sqlite` / `from_io` / `From<arboard::Error>`）。
+//! - `from_sqlite` / `from_io` 的变体字符串只存底层错误原文（即 `detail`），本地化的操作
+//!   上下文经 `Context` 包装进 `message`；`kind` / `code` / `detail` 取自被包装的错误。
 
+use std::io;
+
+use rusqlite::ErrorCode;
 use serde::Serialize;
 
+use crate::i18n::message;
 use crate::image_handler::ImageError;
 
 /// 应用级统一错误类型
@@ -24,41 +34,240 @@ use crate::image_handler::ImageError;
 #[derive(Debug, thiserror::Error)]
 pub enum AppError {
     /// 剪贴板读写操作失败
-    #[error("剪贴板操作失败: {0}")]
+    #[error("{}", message("error.clipboard", &[("detail", .0)]))]
     Clipboard(String),
 
+    /// 剪贴板被其他程序占用（可稍后重试）
+    #[error("{}", message("error.clipboard_busy", &[("detail", .0)]))]
+    ClipboardBusy(String),
+
     /// 图片处理流水线错误（下载 / 解码 / 复制）
     #[error("{0}")]
     Image(#[from] ImageError),
 
     /// 文件系统 I/O 错误
-    #[error("文件系统错误: {0}")]
+    #[error("{}", message("error.io", &[("detail", .0)]))]
     Io(#[from] std::io::Error),
 
     /// 存储目录不可用
-    #[error("存储目录不可用: {0}")]
+    #[error("{}", message("error.storage", &[("detail", .0)]))]
     Storage(String),
 
+    /// 磁盘空间不足
+    #[error("{}", message("error.storage_full", &[("detail", .0)]))]
+    StorageFull(String),
+
     /// 窗口操作失败
-    #[error("窗口操作失败: {0}")]
+    #[error("{}", message("error.window", &[("detail", .0)]))]
     Window(String),
 
     /// 输入模拟失败
-    #[error("输入模拟失败: {0}")]
+    #[error("{}", message("error.input", &[("detail", .0)]))]
     Input(String),
 
+    /// 当前平台不支持该操作
+    #[error("{}", message("error.unsupported", &[("detail", .0)]))]
+    Unsupported(String),
+
     /// 数据库操作失败
-    #[error("数据库错误: {0}")]
+    #[error("{}", message("error.database", &[("detail", .0)]))]
     Database(String),
+
+    /// 数据库被其他连接或进程锁定（可稍后重试）
+    #[error("{}", message("error.database_locked", &[("detail", .0)]))]
+    DatabaseLocked(String),
+
+    /// 数据库文件损坏或不是有效的数据库
+    #[error("{}", message("error.database_corrupt", &[("detail", .0)]))]
+    DatabaseCorrupt(String),
+
+    /// 文本转换失败（未知转换 / 输入格式不符）
+    #[error("{}", message("error.transform", &[("detail", .0)]))]
+    Transform(String),
+
+    /// 请求的条目或资源不存在
+    #[error("{}", message("error.not_found", &[("detail", .0)]))]
+    NotFound(String),
+
+    /// 请求参数或目标不适用于该操作（如对非图片条目执行图片编辑）
+    #[error("{}", message("error.invalid_request", &[("detail", .0)]))]
+    InvalidRequest(String),
+
+    /// 附带本地化操作上下文的错误（消息为“上下文: 原错误消息”）
+    #[error("{context}: {source}")]
+    Context { context: String, source: Box<AppError> },
+}
+
+impl AppError {
+    /// 为错误附加本地化的操作上下文
+    pub(crate) fn with_context(context: impl Into<String>, source: Self) -> Self {
+        Self::Context { context: context.into(), source: Box::new(source) }
+    }
+
+    /// 按 SQLite 错误码细分数据库错误
+    pub(crate) fn from_sqlite(context: &str, e: &rusqlite::Error) -> Self {
+        let detail = e.to_string();
+        let source = match e.sqlite_error_code() {
+            Some(ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked) => Self::DatabaseLocked(detail),
+            Some(ErrorCode::DatabaseCorrupt | ErrorCode::NotADatabase) => Self::DatabaseCorrupt(detail),
+            Some(ErrorCode::DiskFull) => Self::StorageFull(detail),
+            _ => Self::Database(detail),
+        };
+        Self::with_context(context, source)
+    }
+
+    /// 带上下文的 I/O 错误：磁盘已满时返回 `StorageFull`，否则交给 `fallback` 构造
+    pub(crate) fn from_io(context: &str, e: &io::Error, fallback: fn(String) -> Self) -> Self {
+        let detail = e.to_string();
+        let source = if is_storage_full(e) {
+            Self::StorageFull(detail)
+        } else {
+            fallback(detail)
+        };
+        Self::with_context(context, source)
+    }
+
+    /// 错误类别（前端按类别分组展示）
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Clipboard(_) | Self::ClipboardBusy(_) => "clipboard",
+            Self::Image(_) => "image",
+            Self::Io(_) => "io",
+            Self::Storage(_) | Self::StorageFull(_) => "storage",
+            Self::Window(_) => "window",
+            Self::Input(_) | Self::Unsupported(_) => "input",
+            Self::Database(_) | Self::DatabaseLocked(_) | Self::DatabaseCorrupt(_) => "database",
+            Self::Transform(_) => "transform",
+            Self::NotFound(_) | Self::InvalidRequest(_) => "request",
+            Self::Context { source, .. } => source.kind(),
+        }
+    }
+
+    /// 稳定的机器可读错误码
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Clipboard(_) => "E_CLIPBOARD",
+            Self::ClipboardBusy(_) => "E_CLIPBOARD_BUSY",
+            Self::Image(e) => e.code(),
+            Self::Io(e) if is_storage_full(e) => "E_STORAGE_FULL",
+            Self::Io(e) => match e.kind() {
+                io::ErrorKind::NotFound => "E_IO_NOT_FOUND",
+                io::ErrorKind::PermissionDenied => "E_IO_PERMISSION",
+                _ => "E_IO",
+            },
+            Self::Storage(_) => "E_STORAGE_UNAVAILABLE",
+            Self::StorageFull(_) => "E_STORAGE_FULL",
+            Self::Window(_) => "E_WINDOW",
+            Self::Input(_) => "E_INPUT",
+            Self::Unsupported(_) => "E_INPUT_UNSUPPORTED",
+            Self::Database(_) => "E_DB",
+            Self::DatabaseLocked(_) => "E_DB_LOCKED",
+            Self::DatabaseCorrupt(_) => "E_DB_CORRUPT",
+            Self::Transform(_) => "E_TRANSFORM",
+            Self::NotFound(_) => "E_NOT_FOUND",
+            Self::InvalidRequest(_) => "E_INVALID_REQUEST",
+            Self::Context { source, .. } => source.code(),
+        }
+    }
+
+    /// 稍后重试是否可能成功（资源被占用、网络波动等）
+    pub fn retryable(&self) -> bool {
+        match self {
+            Self::ClipboardBusy(_) | Self::DatabaseLocked(_) => true,
+            Self::Image(e) => matches!(
+                e,
+                ImageError::Network(_) | ImageError::Timeout(_) | ImageError::ClipboardBusy(_)
+            ),
+            Self::Io(e) => matches!(e.kind(), io::ErrorKind::Interrupted | io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock),
+            Self::Context { source, .. } => source.retryable(),
+            _ => false,
+        }
+    }
+
+    /// 底层错误原文（不含本地化前缀），用于日志与反馈
+    pub fn detail(&self) -> Option<String> {
+        match self {
+            Self::Clipboard(detail)
+            | Self::ClipboardBusy(detail)
+            | Self::Storage(detail)
+            | Self::StorageFull(detail)
+            | Self::Window(detail)
+            | Self::Input(detail)
+            | Self::Unsupported(detail)
+            | Self::Database(detail)
+            | Self::DatabaseLocked(detail)
+            | Self::DatabaseCorrupt(detail)
+            | Self::Transform(detail)
+            | Self::NotFound(detail)
+            | Self::InvalidRequest(detail) => Some(detail.clone()),
+            Self::Io(e) => Some(match e.raw_os_error() {
+                Some(os_code) => format!("{} (os error {})", e.kind(), os_code),
+                None => e.kind().to_string(),
+            }),
+            Self::Image(e) => Some(e.detail().to_string()),
+            Self::Context { source, .. } => source.detail(),
+        }
+    }
+}
+
+impl AppError {
+    /// 图片错误（含被 `Context` 包装的）所处阶段
+    fn image_stage(&self) -> Option<&'static str> {
+        match self {
+            Self::Image(e) => Some(e.stage()),
+            Self::Context { source, .. } => source.image_stage(),
+            _ => None,
+        }
+    }
+}
+
+/// 磁盘空间或配额耗尽
+fn is_storage_full(e: &io::Error) -> bool {
+    matches!(e.kind(), io::ErrorKind::StorageFull | io::ErrorKind::QuotaExceeded)
+}
+
+impl From<arboard::Error> for AppError {
+    /// 剪贴板被其他程序持有时映射为 `ClipboardBusy`
+    fn from(e: arboard::Error) -> Self {
+        match e {
+            arboard::Error::ClipboardOccupied => Self::ClipboardBusy(e.to_string()),
+            e => Self::Clipboard(e.to_string()),
+        }
+    }
+}
+
+/// 序列化给前端的错误结构
+#[derive(Serialize)]
+struct ErrorPayload {
+    kind: &'static str,
+    code: &'static str,
+    message: String,
+    detail: Option<String>,
+    retryable: bool,
+    /// 仅图片错误携带（download / format / decode / clipboard / resource）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stage: Option<&'static str>,
 }
 
 /// Tauri IPC 要求返回值实现 `Serialize`。
-/// 将错误序列化为人类可读的字符串。
+/// 将错误序列化为 `{kind, code, message, detail, retryable}` 结构。
 impl Serialize for AppError {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(&self.to_string())
+        ErrorPayload {
+            kind: self.kind(),
+            code: self.code(),
+            message: self.to_string(),
+            detail: self.detail(),
+            retryable: self.retryable(),
+            stage: self.image_stage(),
+        }
+        .serialize(serializer)
     }
 }
+
+#[cfg(test)]
+#[path = "tests/error_tests.rs"]
+mod tests;
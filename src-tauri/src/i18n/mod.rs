@@ -0,0 +1,156 @@
+//! # 后端消息本地化模块
+//!
+//! ## 设计思路
+//!
+//! 后端错误与托盘菜单原先直接写死中文，不懂中文的用户无法阅读。本模块提供按消息 ID
+//! 查找的文案目录（`zh-CN` / `en`），`AppError` / `ImageError` 的显示文本与托盘菜单
+//! 标签均经此渲染；语言由设置项 `backendLocale` 控制，默认 `zh-CN`，与原有行为一致。
+//!
+//! ## 实现思路
+//!
+//! - 文案目录为 `locales/<语言>.json` 的扁平键值表，编译期嵌入，首次使用时解析
+//! - 参数以 `{name}` 占位，`message(id, &[("name", &value)])` 渲染；未提供的占位符原样保留
+//! - 当前语言缺少某条文案时回退到 `zh-CN`，仍缺失则返回消息 ID 本身，便于发现遗漏
+//! - 当前语言存于原子变量：设置热更新后，新产生的消息立即使用新语言；
+//!   托盘菜单项经 `TrayMenu` 托管状态重新设置标签
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+
+use tauri::menu::MenuItem;
+use tauri::Wry;
+
+/// 后端消息语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    ZhCn,
+    En,
+}
+
+impl Locale {
+    /// 解析语言标签（`zh` / `zh-CN` / `en` / `en-US` 等，大小写与 `_` / `-` 不敏感）
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let primary = tag.trim().split(['-', '_']).next()?.to_ascii_lowercase();
+        match primary.as_str() {
+            "zh" => Some(Self::ZhCn),
+            "en" => Some(Self::En),
+            _ => None,
+        }
+    }
+
+    pub fn tag(self) -> &'static str {
+        match self {
+            Self::ZhCn => "zh-CN",
+            Self::En => "en",
+        }
+    }
+
+    fn bundle(self) -> &'static HashMap<String, String> {
+        static ZH_CN: OnceLock<HashMap<String, String>> = OnceLock::new();
+        static EN: OnceLock<HashMap<String, String>> = OnceLock::new();
+        match self {
+            Self::ZhCn => ZH_CN.get_or_init(|| parse_bundle(include_str!("locales/zh-CN.json"))),
+            Self::En => EN.get_or_init(|| parse_bundle(include_str!("locales/en.json"))),
+        }
+    }
+}
+
+/// 当前语言（0 = zh-CN，1 = en）
+static CURRENT_LOCALE: AtomicU8 = AtomicU8::new(0);
+
+fn parse_bundle(source: &str) -> HashMap<String, String> {
+    serde_json::from_str(source).unwrap_or_else(|e| {
+        log::error!("文案目录解析失败: {}", e);
+        HashMap::new()
+    })
+}
+
+pub fn current_locale() -> Locale {
+    match CURRENT_LOCALE.load(Ordering::Relaxed) {
+        1 => Locale::En,
+        _ => Locale::ZhCn,
+    }
+}
+
+pub fn set_locale(locale: Locale) {
+    let value = match locale {
+        Locale::ZhCn => 0,
+        Locale::En => 1,
+    };
+    CURRENT_LOCALE.store(value, Ordering::Relaxed);
+}
+
+/// 从设置 JSON 读取 `backendLocale`，返回语言是否发生变化
+///
+/// 缺失或无法识别的值按 `zh-CN` 处理。
+pub fn apply_settings_from_value(settings: &serde_json::Value) -> bool {
+    let locale = settings
+        .get("backendLocale")
+        .and_then(|v| v.as_str())
+        .and_then(Locale::from_tag)
+        .unwrap_or(Locale::ZhCn);
+    let changed = locale != current_locale();
+    set_locale(locale);
+    changed
+}
+
+/// 按当前语言渲染消息
+pub fn message(id: &str, args: &[(&str, &dyn Display)]) -> String {
+    message_in(current_locale(), id, args)
+}
+
+/// 按指定语言渲染消息（缺失时回退到 `zh-CN`，仍缺失则返回消息 ID）
+pub fn message_in(locale: Locale, id: &str, args: &[(&str, &dyn Display)]) -> String {
+    let Some(template) = locale.bundle().get(id).or_else(|| Locale::ZhCn.bundle().get(id)) else {
+        log::warn!("缺少文案: {}", id);
+        return id.to_string();
+    };
+    render(template, args)
+}
+
+/// 替换 `{name}` 占位符
+fn render(template: &str, args: &[(&str, &dyn Display)]) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let value = after
+            .find('}')
+            .and_then(|end| args.iter().find(|(name, _)| *name == &after[..end]).map(|(_, v)| (end, v)));
+        match value {
+            Some((end, value)) => {
+                output.push_str(&value.to_string());
+                rest = &after[end + 1..];
+            }
+            None => {
+                output.push('{');
+                rest = after;
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+/// 托盘菜单项（托管状态），语言切换时重新设置标签
+pub struct TrayMenu {
+    pub show: MenuItem<Wry>,
+    pub quit: MenuItem<Wry>,
+}
+
+impl TrayMenu {
+    pub fn relabel(&self) {
+        for (item, id) in [(&self.show, "tray.show"), (&self.quit, "tray.quit")] {
+            if let Err(err) = item.set_text(message(id, &[])) {
+                log::warn!("更新托盘菜单文案失败: {err}");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/i18n_tests.rs"]
+mod tests;
@@ -0,0 +1,60 @@
+use super::*;
+use std::collections::BTreeSet;
+
+fn placeholders(template: &str) -> BTreeSet<&str> {
+    template
+        .split('{')
+        .skip(1)
+        .filter_map(|part| part.split_once('}').map(|(name, _)| name))
+        .collect()
+}
+
+#[test]
+fn bundles_share_ids_and_placeholders() {
+    let zh = Locale::ZhCn.bundle();
+    let en = Locale::En.bundle();
+    assert!(!zh.is_empty());
+
+    let zh_ids: BTreeSet<_> = zh.keys().collect();
+    let en_ids: BTreeSet<_> = en.keys().collect();
+    assert_eq!(zh_ids, en_ids);
+
+    for (id, template) in zh {
+        assert_eq!(placeholders(template), placeholders(&en[id]), "placeholders of {}", id);
+    }
+}
+
+#[test]
+fn renders_parameters_in_each_locale() {
+    let detail = "database is locked";
+    assert_eq!(
+        message_in(Locale::ZhCn, "error.database_locked", &[("detail", &detail)]),
+        "数据库被占用: database is locked"
+    );
+    assert_eq!(
+        message_in(Locale::En, "error.database_locked", &[("detail", &detail)]),
+        "Database is locked: database is locked"
+    );
+    assert_eq!(message_in(Locale::En, "tray.quit", &[]), "Quit");
+    assert_eq!(message_in(Locale::ZhCn, "tray.show", &[]), "显示");
+}
+
+#[test]
+fn unknown_ids_and_missing_arguments_stay_visible() {
+    assert_eq!(message_in(Locale::En, "no.such.message", &[]), "no.such.message");
+    assert_eq!(message_in(Locale::En, "error.database", &[]), "Database error: {detail}");
+
+    let count = 3;
+    assert_eq!(render("{a} of {b} {", &[("a", &count)]), "3 of {b} {");
+    assert_eq!(render("{{a}}", &[("a", &"x")]), "{x}");
+}
+
+#[test]
+fn locale_tags_are_lenient() {
+    assert_eq!(Locale::from_tag("zh-CN"), Some(Locale::ZhCn));
+    assert_eq!(Locale::from_tag("zh_TW"), Some(Locale::ZhCn));
+    assert_eq!(Locale::from_tag(" EN-us "), Some(Locale::En));
+    assert_eq!(Locale::from_tag("fr"), None);
+    assert_eq!(Locale::from_tag(""), None);
+    assert_eq!(Locale::En.tag(), "en");
+}
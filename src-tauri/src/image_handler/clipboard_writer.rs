@@ -18,6 +18,15 @@
 //!
 //! 非 Windows 平台仍回退到 arboard。
 //!
+//! 输出选项指定 PNG / JPEG 编码时：Windows 以 `PNG` / `JFIF` 格式提供编码字节并附带
+//! DIBv5（含 DPI）；Linux 经 X11 selection 以对应 MIME 目标提供，X11 不可用时回退为位图。
+//!
+//! 携带原始编码字节（GIF / WebP / PNG）时：Windows 额外提供 `GIF` / `image/webp` 格式，
+//! 原图为 PNG 时直接以原始字节作为 `PNG` 格式；Linux 同时提供原始 MIME 目标与 `image/png`。
+//!
+//! SVG 栅格化输出携带的 SVG 源文本不受输出编码影响：Windows 以 `image/svg+xml` 格式、
+//! Linux 以同名 MIME 目标与位图编码一并提供。
+//!
 //! ## 内部结构
 //!
 //! - `RetryPolicy`：聚合重试参数（次数、基础延迟、上限、预算）
@@ -36,7 +45,9 @@
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use super::source::PreparedClipboardImage;
+use crate::i18n::message;
+
+use super::source::{EncodedClipboardImage, PreparedClipboardImage};
 use super::{ImageConfig, ImageError, ImageHandler};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -143,18 +154,21 @@ fn format_win32_error_message(
     let code_str = code
         .map(|c| c.to_string())
         .unwrap_or_else(|| "unknown".to_string());
-    let hint = win32_error_hint(code);
-    format!(
-        "{}失败: format={} hr=0x{:08X} code={} hint={} detail={}",
-        operation,
-        format_name,
-        hr as u32,
-        code_str,
-        hint,
-        detail
+    let hint = message(win32_error_hint(code), &[]);
+    message(
+        "image.win32_failure",
+        &[
+            ("operation", &operation),
+            ("format", &format_name),
+            ("hr", &format!("0x{:08X}", hr as u32)),
+            ("code", &code_str),
+            ("hint", &hint),
+            ("detail", &detail),
+        ],
     )
 }
 
+/// Win32 错误码对应的提示文案 ID
 fn win32_error_hint(code: Option<u32>) -> &'static str {
     #[cfg(target_os = "windows")]
     {
@@ -164,23 +178,23 @@ fn win32_error_hint(code: Option<u32>) -> &'static str {
         };
 
         match code {
-            Some(c) if c == ERROR_ACCESS_DENIED.0 => "剪贴板被其他进程占用或权限不足",
-            Some(c) if c == ERROR_CLIPBOARD_NOT_OPEN.0 => "剪贴板句柄未打开或已失效",
-            Some(c) if c == ERROR_BUSY.0 => "系统忙，资源暂不可用",
-            Some(c) if c == ERROR_NOT_ENOUGH_MEMORY.0 => "内存不足",
-            Some(c) if c == ERROR_OUTOFMEMORY.0 => "系统报告内存耗尽",
-            Some(c) if c == ERROR_NO_SYSTEM_RESOURCES.0 => "系统资源不足",
-            Some(c) if c == ERROR_NOT_ENOUGH_QUOTA.0 => "进程配额不足",
-            Some(_) => "未分类 Win32 错误",
-            None => "无法从 HRESULT 解析 Win32 错误码",
+            Some(c) if c == ERROR_ACCESS_DENIED.0 => "image.win32_hint.access_denied",
+            Some(c) if c == ERROR_CLIPBOARD_NOT_OPEN.0 => "image.win32_hint.clipboard_not_open",
+            Some(c) if c == ERROR_BUSY.0 => "image.win32_hint.busy",
+            Some(c) if c == ERROR_NOT_ENOUGH_MEMORY.0 => "image.win32_hint.not_enough_memory",
+            Some(c) if c == ERROR_OUTOFMEMORY.0 => "image.win32_hint.out_of_memory",
+            Some(c) if c == ERROR_NO_SYSTEM_RESOURCES.0 => "image.win32_hint.no_system_resources",
+            Some(c) if c == ERROR_NOT_ENOUGH_QUOTA.0 => "image.win32_hint.not_enough_quota",
+            Some(_) => "image.win32_hint.other",
+            None => "image.win32_hint.not_win32",
         }
     }
 
     #[cfg(not(target_os = "windows"))]
     {
         match code {
-            Some(_) => "未分类 Win32 错误",
-            None => "无法从 HRESULT 解析 Win32 错误码",
+            Some(_) => "image.win32_hint.other",
+            None => "image.win32_hint.not_win32",
         }
     }
 }
@@ -226,9 +240,14 @@ where
             let elapsed_ms = started.elapsed().as_millis() as u64;
             if elapsed_ms >= policy.budget_ms {
                 log::warn!(
-                    "⏱️ 剪贴板写入重试预算耗尽（{}ms >= {}ms）",
-                    elapsed_ms,
-                    policy.budget_ms
+                    "{}",
+                    message(
+                        "image_log.retry_budget_exhausted",
+                        &[
+                            ("elapsed", &elapsed_ms),
+                            ("budget", &policy.budget_ms),
+                        ],
+                    )
                 );
                 break;
             }
@@ -241,35 +260,50 @@ where
 
             if would_exceed_retry_budget(elapsed_ms, wait_ms, policy.budget_ms) {
                 log::warn!(
-                    "⏱️ 跳过第 {} 次重试：等待 {}ms 会超过预算 {}ms",
-                    attempt,
-                    wait_ms,
-                    policy.budget_ms
+                    "{}",
+                    message(
+                        "image_log.retry_skipped",
+                        &[
+                            ("attempt", &attempt),
+                            ("wait", &wait_ms),
+                            ("budget", &policy.budget_ms),
+                        ],
+                    )
                 );
                 break;
             }
 
             log::debug!(
-                "🔄 重试 {}/{}，等待 {}ms（指数退避+抖动）",
-                attempt,
-                policy.max_attempts,
-                wait_ms
+                "{}",
+                message(
+                    "image_log.retry_waiting",
+                    &[
+                        ("attempt", &attempt),
+                        ("max", &policy.max_attempts),
+                        ("wait", &wait_ms),
+                    ],
+                )
             );
             std::thread::sleep(Duration::from_millis(wait_ms));
         }
 
         match op() {
             Ok(()) => {
-                log::info!("✅ 复制成功 (尝试 {})", attempt);
+                log::info!("{}", message("image_log.copy_succeeded", &[("attempt", &attempt)]));
                 return Ok(());
             }
             Err(failure) => {
                 log::warn!(
-                    "❌ 尝试 {} 失败: {}（kind={:?}, retryable={}）",
-                    attempt,
-                    failure.message,
-                    failure.kind,
-                    failure.is_retryable()
+                    "{}",
+                    message(
+                        "image_log.copy_attempt_failed",
+                        &[
+                            ("attempt", &attempt),
+                            ("detail", &failure.message),
+                            ("kind", &format!("{:?}", failure.kind)),
+                            ("retryable", &failure.is_retryable()),
+                        ],
+                    )
                 );
                 if !failure.is_retryable() {
                     last_failure = Some(failure);
@@ -280,7 +314,7 @@ where
         }
     }
 
-    let f = last_failure.unwrap_or_else(|| ClipboardWriteFailure::fatal("未知错误"));
+    let f = last_failure.unwrap_or_else(|| ClipboardWriteFailure::fatal(message("image.clipboard_unknown_error", &[])));
     if f.kind == ClipboardFailureKind::Busy {
         Err(ImageError::ClipboardBusy(f.message))
     } else {
@@ -295,7 +329,16 @@ impl ImageHandler {
         image: PreparedClipboardImage,
         config: &ImageConfig,
     ) -> Result<(), ImageError> {
-        log::debug!("📋 准备复制到剪贴板 - {}x{}", image.width, image.height);
+        log::debug!(
+            "{}",
+            message(
+                "image_log.copy_preparing",
+                &[
+                    ("width", &image.width),
+                    ("height", &image.height),
+                ],
+            )
+        );
 
         crate::clipboard::remember_internal_image_fingerprint(
             image.width,
@@ -304,17 +347,22 @@ impl ImageHandler {
         );
         let _guard = crate::clipboard::IgnoreGuard::new();
         let policy = RetryPolicy::from_config(config);
-        let width = image.width;
-        let height = image.height;
-        let bytes = image.bytes;
+        let PreparedClipboardImage {
+            width,
+            height,
+            bytes,
+            encoded,
+            dpi,
+            original,
+        } = image;
 
         tokio::task::spawn_blocking(move || {
-            let prepped = Self::prepare_clipboard_buffers(width, height, &bytes)
+            let prepped = Self::prepare_clipboard_buffers(width, height, &bytes, encoded, original, dpi)
                 .map_err(ImageError::Clipboard)?;
             execute_with_retries(&policy, || Self::try_fast_clipboard_write(&prepped))
         })
         .await
-        .map_err(|e| ImageError::Clipboard(format!("线程执行失败：{}", e)))?
+        .map_err(|e| ImageError::Clipboard(message("image.clipboard_thread_failed", &[("detail", &e)])))?
     }
 }
 
@@ -325,8 +373,8 @@ impl ImageHandler {
 #[cfg(target_os = "windows")]
 mod win32 {
     use super::*;
-    use image::codecs::png::PngEncoder;
-    use image::ImageEncoder;
+    use crate::image_handler::output::{dpi_to_pixels_per_meter, encode_png};
+    use crate::image_handler::svg::SVG_MIME;
     use std::mem::size_of;
     use std::ptr::copy_nonoverlapping;
     use windows::Win32::Foundation::{
@@ -348,8 +396,12 @@ mod win32 {
 
     /// 预备好的剪贴板缓冲区（所有编码工作已在此完成）。
     pub(super) struct PreppedBuffers {
-        /// PNG 字节（用于 "PNG" 格式）。
-        pub png_bytes: Vec<u8>,
+        /// 原始编码字节及其注册格式名（"GIF" / "image/webp" / "image/svg+xml"），优先级最高。
+        pub original: Option<(&'static str, Vec<u8>)>,
+        /// 编码字节对应的注册格式名（"PNG" / "JFIF"）。
+        pub encoded_format: &'static str,
+        /// 编码字节（默认即时编码 PNG，输出选项指定编码时沿用流水线结果）。
+        pub encoded_bytes: Vec<u8>,
         /// DIBv5 字节 = BITMAPV5HEADER + ARGB 像素（翻转后）。
         pub dibv5_bytes: Vec<u8>,
     }
@@ -359,33 +411,64 @@ mod win32 {
     const LCS_sRGB: u32 = 0x7352_4742;
 
     impl ImageHandler {
-        /// 在**不持有剪贴板的前提下**，准备好 PNG 与 DIBv5 缓冲。
+        /// 在**不持有剪贴板的前提下**，准备好编码字节（PNG / JPEG）与 DIBv5 缓冲。
         pub(super) fn prepare_clipboard_buffers(
             width: usize,
             height: usize,
             rgba_bytes: &[u8],
+            encoded: Option<EncodedClipboardImage>,
+            original: Option<EncodedClipboardImage>,
+            dpi: Option<u32>,
         ) -> Result<PreppedBuffers, String> {
-            let png_bytes = encode_png(width, height, rgba_bytes)?;
-            let dibv5_bytes = build_dibv5(width, height, rgba_bytes)?;
-            Ok(PreppedBuffers { png_bytes, dibv5_bytes })
+            // SVG 源文本与任意输出编码并存
+            let (mut original_format, original) = match original {
+                Some(o) if o.mime == SVG_MIME => (Some((SVG_MIME, o.bytes)), None),
+                other => (None, other),
+            };
+            let (encoded_format, encoded_bytes) = match (encoded, original) {
+                (Some(EncodedClipboardImage { mime: "image/jpeg", bytes }), _) => ("JFIF", bytes),
+                (Some(EncodedClipboardImage { bytes, .. }), _) => ("PNG", bytes),
+                // 原图即 PNG：直接提供原始字节，保留文本块等元数据
+                (None, Some(EncodedClipboardImage { mime: "image/png", bytes })) => ("PNG", bytes),
+                (None, original) => {
+                    if let Some(o) = original {
+                        let name = if o.mime == "image/gif" { "GIF" } else { o.mime };
+                        original_format = Some((name, o.bytes));
+                    }
+                    (
+                        "PNG",
+                        encode_png(width as u32, height as u32, rgba_bytes, dpi).map_err(|e| e.to_string())?,
+                    )
+                }
+            };
+            let dibv5_bytes = build_dibv5(width, height, rgba_bytes, dpi)?;
+            Ok(PreppedBuffers { original: original_format, encoded_format, encoded_bytes, dibv5_bytes })
         }
 
-        /// 极速写入：OpenClipboard→Empty→Set(PNG)→Set(DIBV5)→Close。
+        /// 极速写入：OpenClipboard→Empty→Set(GIF/WebP)→Set(PNG/JFIF)→Set(DIBV5)→Close。
         ///
         /// 此函数内不做任何编码/转换，只做内存拷贝与 Win32 调用，
         /// 持有剪贴板的时间通常 < 1ms。
         pub(super) fn try_fast_clipboard_write(prepped: &PreppedBuffers) -> Result<(), ClipboardWriteFailure> {
             unsafe {
                 // ── Open ──
-                OpenClipboard(None).map_err(|e| classify_win32_error("打开剪贴板", "N/A", &e))?;
+                OpenClipboard(None).map_err(|e| classify_win32_error("OpenClipboard", "N/A", &e))?;
 
                 if let Err(e) = EmptyClipboard() {
                     let _ = CloseClipboard();
-                    return Err(classify_win32_error("清空剪贴板", "N/A", &e));
+                    return Err(classify_win32_error("EmptyClipboard", "N/A", &e));
                 }
 
-                // ── Set PNG（优先级更高，放在前面）──
-                if let Err(e) = set_raw_format("PNG", &prepped.png_bytes) {
+                // ── Set 原始 GIF / WebP（保留动画，优先级最高）──
+                if let Some((format, bytes)) = &prepped.original {
+                    if let Err(e) = set_raw_format(format, bytes) {
+                        let _ = CloseClipboard();
+                        return Err(e);
+                    }
+                }
+
+                // ── Set PNG / JFIF（优先级更高，放在前面）──
+                if let Err(e) = set_raw_format(prepped.encoded_format, &prepped.encoded_bytes) {
                     let _ = CloseClipboard();
                     return Err(e);
                 }
@@ -414,7 +497,7 @@ mod win32 {
             let wide: Vec<u16> = name.encode_utf16().chain(std::iter::once(0)).collect();
             let format_id = RegisterClipboardFormatW(windows::core::PCWSTR(wide.as_ptr()));
             if format_id == 0 {
-                return Err(ClipboardWriteFailure::fatal(format!("注册格式 '{}' 失败", name)));
+                return Err(ClipboardWriteFailure::fatal(message("image.clipboard_register_format_failed", &[("format", &name)])));
             }
             set_global_data(format_id, name, data)
         }
@@ -433,7 +516,7 @@ mod win32 {
             let ptr = GlobalLock(hglobal) as *mut u8;
             if ptr.is_null() {
                 let _ = GlobalFree(Some(hglobal));
-                return Err(ClipboardWriteFailure::transient("GlobalLock 返回空指针".to_string()));
+                return Err(ClipboardWriteFailure::transient(message("image.clipboard_global_lock_null", &[])));
             }
 
             copy_nonoverlapping(data.as_ptr(), ptr, data.len());
@@ -473,33 +556,23 @@ mod win32 {
         }
     }
 
-    /// PNG 编码辅助，将 RGBA 原始数据编码为 PNG 字节。
-    fn encode_png(width: usize, height: usize, rgba: &[u8]) -> Result<Vec<u8>, String> {
-        let mut buf = Vec::new();
-        let encoder = PngEncoder::new(&mut buf);
-        encoder
-            .write_image(
-                rgba,
-                width as u32,
-                height as u32,
-                image::ColorType::Rgba8.into(),
-            )
-            .map_err(|e| format!("PNG 编码失败: {}", e))?;
-        Ok(buf)
-    }
-
     /// 构建完整的 DIBv5 数据（header + 翻转后的 ARGB 像素）。
     ///
     /// 单次分配：直接将 ARGB 像素写入最终缓冲区，避免中间 Vec 分配。
-    fn build_dibv5(width: usize, height: usize, rgba_bytes: &[u8]) -> Result<Vec<u8>, String> {
+    fn build_dibv5(
+        width: usize,
+        height: usize,
+        rgba_bytes: &[u8],
+        dpi: Option<u32>,
+    ) -> Result<Vec<u8>, String> {
         let header_size = size_of::<BITMAPV5HEADER>();
         let pixel_bytes = width * height * 4;
+        let pels_per_meter = dpi.map(dpi_to_pixels_per_meter).unwrap_or(0) as i32;
 
         if rgba_bytes.len() != pixel_bytes {
-            return Err(format!(
-                "像素长度不匹配: 期望 {} 实际 {}",
-                pixel_bytes,
-                rgba_bytes.len()
+            return Err(message(
+                "image.clipboard_pixel_length_mismatch",
+                &[("expected", &pixel_bytes), ("actual", &rgba_bytes.len())],
             ));
         }
 
@@ -512,8 +585,8 @@ mod win32 {
             bV5BitCount: 32,
             bV5Compression: BI_BITFIELDS,
             bV5SizeImage: pixel_bytes as u32,
-            bV5XPelsPerMeter: 0,
-            bV5YPelsPerMeter: 0,
+            bV5XPelsPerMeter: pels_per_meter,
+            bV5YPelsPerMeter: pels_per_meter,
             bV5ClrUsed: 0,
             bV5ClrImportant: 0,
             bV5RedMask: 0x00ff_0000,
@@ -574,29 +647,40 @@ mod fallback {
     use super::*;
     use std::borrow::Cow;
 
-    /// 预备缓冲（非 Windows 仅持有原始 RGBA 引用信息）。
+    /// 预备缓冲（非 Windows 持有原始 RGBA 与需要以 MIME 目标提供的编码字节）。
     pub(super) struct PreppedBuffers {
         pub width: usize,
         pub height: usize,
         pub rgba_bytes: Vec<u8>,
+        /// 以 MIME 目标提供的字节；为空时写入位图。
+        pub targets: Vec<(String, Vec<u8>)>,
     }
 
     impl ImageHandler {
+        /// arboard 位图不携带 DPI，`_dpi` 仅在编码字节中生效。
         pub(super) fn prepare_clipboard_buffers(
             width: usize,
             height: usize,
             rgba_bytes: &[u8],
+            encoded: Option<EncodedClipboardImage>,
+            original: Option<EncodedClipboardImage>,
+            _dpi: Option<u32>,
         ) -> Result<PreppedBuffers, String> {
             Ok(PreppedBuffers {
                 width,
                 height,
                 rgba_bytes: rgba_bytes.to_vec(),
+                targets: mime_targets(width, height, rgba_bytes, encoded, original)?,
             })
         }
 
         pub(super) fn try_fast_clipboard_write(prepped: &PreppedBuffers) -> Result<(), ClipboardWriteFailure> {
+            if !prepped.targets.is_empty() && write_targets(&prepped.targets) {
+                return Ok(());
+            }
+
             let mut clipboard = arboard::Clipboard::new()
-            .map_err(|e| ClipboardWriteFailure::busy(format!("无法访问剪贴板：{}", e)))?;
+            .map_err(|e| ClipboardWriteFailure::busy(message("image.clipboard_access_failed", &[("detail", &e)])))?;
 
             let image_data = arboard::ImageData {
                 width: prepped.width,
@@ -606,11 +690,83 @@ mod fallback {
 
             clipboard
                 .set_image(image_data)
-                .map_err(|e| ClipboardWriteFailure::transient(format!("复制失败：{}", e)))?;
+                .map_err(|e| ClipboardWriteFailure::transient(message("image.clipboard_copy_failed", &[("detail", &e)])))?;
 
             Ok(())
         }
     }
+
+    /// 组装 MIME 目标：输出编码字节优先；否则为原始编码字节，非 PNG 时追加 `image/png`，
+    /// 保证不识别 GIF / WebP / SVG 的应用仍能粘贴。SVG 源文本与输出编码字节并存。
+    #[cfg(target_os = "linux")]
+    fn mime_targets(
+        width: usize,
+        height: usize,
+        rgba_bytes: &[u8],
+        encoded: Option<EncodedClipboardImage>,
+        original: Option<EncodedClipboardImage>,
+    ) -> Result<Vec<(String, Vec<u8>)>, String> {
+        if let Some(encoded) = encoded {
+            let mut targets = vec![(encoded.mime.to_string(), encoded.bytes)];
+            if let Some(svg) = original.filter(|o| o.mime == crate::image_handler::svg::SVG_MIME) {
+                targets.push((svg.mime.to_string(), svg.bytes));
+            }
+            return Ok(targets);
+        }
+        let Some(original) = original else {
+            return Ok(Vec::new());
+        };
+        let mut targets = vec![(original.mime.to_string(), original.bytes)];
+        if original.mime != "image/png" {
+            let png = crate::image_handler::output::encode_png(width as u32, height as u32, rgba_bytes, None)
+                .map_err(|e| e.to_string())?;
+            targets.push(("image/png".to_string(), png));
+        }
+        Ok(targets)
+    }
+
+    /// 其它平台只能写入位图：显式输出编码已在 `ImageOutputOptions::validate` 中拒绝，
+    /// 此处兜底返回错误；SVG 源文本与保留的原始编码不附带。
+    #[cfg(not(target_os = "linux"))]
+    fn mime_targets(
+        _width: usize,
+        _height: usize,
+        _rgba_bytes: &[u8],
+        encoded: Option<EncodedClipboardImage>,
+        _original: Option<EncodedClipboardImage>,
+    ) -> Result<Vec<(String, Vec<u8>)>, String> {
+        match encoded {
+            Some(encoded) => Err(message("image.encoding_unsupported_on_platform", &[("mime", &encoded.mime)])),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// 以 MIME 目标成为 CLIPBOARD 所有者；失败时返回 `false` 回退为位图。
+    #[cfg(target_os = "linux")]
+    fn write_targets(targets: &[(String, Vec<u8>)]) -> bool {
+        match crate::clipboard::x11_selection::own_selection("CLIPBOARD", targets.to_vec()) {
+            Ok(()) => true,
+            Err(err) => {
+                let mimes: Vec<&str> = targets.iter().map(|(mime, _)| mime.as_str()).collect();
+                log::warn!(
+                    "{}",
+                    message(
+                        "image_log.x11_targets_failed",
+                        &[
+                            ("mimes", &mimes.join(" / ")),
+                            ("detail", &err),
+                        ],
+                    )
+                );
+                false
+            }
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn write_targets(_targets: &[(String, Vec<u8>)]) -> bool {
+        false
+    }
 }
 
 // 根据平台选择具体的 PreppedBuffers 类型
@@ -0,0 +1,246 @@
+//! # 图片格式识别与扩展解码模块
+//!
+//! ## 设计思路
+//!
+//! `image` 默认特性已覆盖 PNG / JPEG / GIF / WebP / BMP / ICO / TIFF / TGA / QOI 等格式，
+//! 但 iPhone 拍摄的 HEIC 与网页常见的 AVIF 需要额外解码器。本模块统一负责：
+//! - 格式识别：HEIF 系（按 `ftyp` 品牌区分 HEIC / AVIF）与 JPEG XL 由文件头识别，其余交给 `image`
+//! - 能力判断：按 cargo 特性决定哪些格式可解码，`loader` 在加载阶段即可拒绝
+//! - 解码分派：读取尺寸与完整解码的统一入口，供 `pipeline` 使用
+//!
+//! ## 实现思路
+//!
+//! - `heif` 特性：经 libheif（`libheif-rs`，需系统安装 libheif）解码 HEIC / HEIF / AVIF，输出 RGBA8
+//! - JPEG XL 可识别，但暂无解码器
+//! - 错误语义：已识别但当前构建无法解码 → `UnsupportedFormat`（`E_FORMAT_UNSUPPORTED`，
+//!   消息注明所需特性）；无法识别或文件头损坏 → `InvalidFormat`（`E_FORMAT_INVALID`）；
+//!   格式受支持但像素数据损坏 → `Decode`
+
+use image::{DynamicImage, ImageFormat, ImageReader};
+use std::io::Cursor;
+
+use crate::i18n::message;
+
+use super::ImageError;
+
+/// ISO BMFF `ftyp` 盒内最多检查的兼容品牌数量。
+const MAX_FTYP_BRANDS: usize = 64;
+/// AVIF 品牌。
+const AVIF_BRANDS: [&[u8; 4]; 2] = [b"avif", b"avis"];
+/// HEIC / HEIF 品牌（`mif1` / `msf1` 为通用 HEIF 结构品牌）。
+const HEIF_BRANDS: [&[u8; 4]; 8] = [b"heic", b"heix", b"hevc", b"hevx", b"heim", b"heis", b"mif1", b"msf1"];
+/// JPEG XL 裸码流签名。
+const JXL_CODESTREAM: [u8; 2] = [0xFF, 0x0A];
+/// JPEG XL 容器签名。
+const JXL_CONTAINER: [u8; 12] = [0, 0, 0, 0x0C, b'J', b'X', b'L', b' ', 0x0D, 0x0A, 0x87, 0x0A];
+
+/// 需要 `image` 之外的解码器才能处理的格式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ExtendedFormat {
+    Heic,
+    Avif,
+    Jxl,
+}
+
+impl ExtendedFormat {
+    /// 按文件头识别；其他格式返回 `None`。
+    pub(crate) fn sniff(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(&JXL_CODESTREAM) || bytes.starts_with(&JXL_CONTAINER) {
+            return Some(Self::Jxl);
+        }
+        let brands = ftyp_brands(bytes)?;
+        if brands.iter().any(|brand| AVIF_BRANDS.contains(brand)) {
+            Some(Self::Avif)
+        } else if brands.iter().any(|brand| HEIF_BRANDS.contains(brand)) {
+            Some(Self::Heic)
+        } else {
+            None
+        }
+    }
+
+    /// 用于错误消息的格式名。
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::Heic => "HEIC",
+            Self::Avif => "AVIF",
+            Self::Jxl => "JPEG XL",
+        }
+    }
+
+    /// 解码所需的 cargo 特性；`None` 表示暂无可用解码器。
+    pub(crate) fn feature(self) -> Option<&'static str> {
+        match self {
+            Self::Heic | Self::Avif => Some("heif"),
+            Self::Jxl => None,
+        }
+    }
+
+    /// 当前构建能否解码该格式。
+    pub(crate) fn is_enabled(self) -> bool {
+        match self {
+            Self::Heic | Self::Avif => cfg!(feature = "heif"),
+            Self::Jxl => false,
+        }
+    }
+
+    fn unsupported(self) -> ImageError {
+        match self.feature() {
+            Some(feature) => ImageError::UnsupportedFormat(message(
+                "image.codec_feature_disabled",
+                &[("format", &self.name()), ("feature", &feature)],
+            )),
+            None => ImageError::UnsupportedFormat(message("image.codec_unavailable", &[("format", &self.name())])),
+        }
+    }
+}
+
+/// 读取 `ftyp` 盒的主品牌与兼容品牌；不是 ISO BMFF 文件时返回 `None`。
+fn ftyp_brands(bytes: &[u8]) -> Option<Vec<&[u8; 4]>> {
+    if bytes.get(4..8)? != b"ftyp" {
+        return None;
+    }
+    let box_size = u32::from_be_bytes(bytes.get(0..4)?.try_into().ok()?) as usize;
+    let end = box_size.clamp(16, 16 + MAX_FTYP_BRANDS * 4).min(bytes.len());
+    let major: &[u8; 4] = bytes.get(8..12)?.try_into().ok()?;
+    // 12..16 为次版本号，其后为兼容品牌列表
+    let compatible = bytes.get(16..end).unwrap_or_default().chunks_exact(4);
+    Some(std::iter::once(major).chain(compatible.filter_map(|brand| brand.try_into().ok())).collect())
+}
+
+/// 校验文件头对应的格式能被当前构建解码（加载阶段调用，可只传入文件头）。
+///
+/// `infer` 识别为图片但无任何解码器的格式（JPEG 2000、PSD、RAW 等）同样返回 `UnsupportedFormat`。
+pub(crate) fn ensure_decodable(bytes: &[u8]) -> Result<(), ImageError> {
+    if let Some(format) = ExtendedFormat::sniff(bytes) {
+        return if format.is_enabled() { Ok(()) } else { Err(format.unsupported()) };
+    }
+    if let Ok(format) = image::guess_format(bytes) {
+        return if format.reading_enabled() {
+            Ok(())
+        } else {
+            Err(ImageError::UnsupportedFormat(message("image.format_not_enabled", &[("format", &format!("{:?}", format))])))
+        };
+    }
+    match infer::get(bytes) {
+        Some(kind) if kind.matcher_type() == infer::MatcherType::Image => Err(ImageError::UnsupportedFormat(
+            message("image.mime_unsupported", &[("mime", &kind.mime_type())]),
+        )),
+        _ => Err(ImageError::InvalidFormat(message("image.type_unrecognized", &[]))),
+    }
+}
+
+/// 仅通过文件头读取宽高，用于完整解码前的像素上限检查。
+pub(crate) fn read_dimensions(bytes: &[u8]) -> Result<(u32, u32), ImageError> {
+    if let Some(format) = ExtendedFormat::sniff(bytes) {
+        return extended_dimensions(format, bytes);
+    }
+    let reader = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|e| ImageError::InvalidFormat(message("image.format_unrecognized_detail", &[("detail", &e)])))?;
+    let format = reader.format();
+    reader.into_dimensions().map_err(|e| match e {
+        image::ImageError::Unsupported(_) => unsupported_image_format(format),
+        e => ImageError::InvalidFormat(message("image.dimensions_failed", &[("detail", &e)])),
+    })
+}
+
+/// 完整解码为 `DynamicImage`。
+pub(crate) fn decode(bytes: &[u8]) -> Result<DynamicImage, ImageError> {
+    if let Some(format) = ExtendedFormat::sniff(bytes) {
+        return extended_decode(format, bytes);
+    }
+    image::load_from_memory(bytes).map_err(|e| match e {
+        image::ImageError::Unsupported(_) => unsupported_image_format(image::guess_format(bytes).ok()),
+        e => ImageError::Decode(message("image.decode_failed", &[("detail", &e)])),
+    })
+}
+
+fn unsupported_image_format(format: Option<ImageFormat>) -> ImageError {
+    match format {
+        Some(format) => ImageError::UnsupportedFormat(message("image.format_not_enabled", &[("format", &format!("{:?}", format))])),
+        None => ImageError::InvalidFormat(message("image.format_unrecognized", &[])),
+    }
+}
+
+#[cfg(feature = "heif")]
+fn extended_dimensions(format: ExtendedFormat, bytes: &[u8]) -> Result<(u32, u32), ImageError> {
+    match format {
+        ExtendedFormat::Heic | ExtendedFormat::Avif => heif::dimensions(bytes),
+        ExtendedFormat::Jxl => Err(format.unsupported()),
+    }
+}
+
+#[cfg(feature = "heif")]
+fn extended_decode(format: ExtendedFormat, bytes: &[u8]) -> Result<DynamicImage, ImageError> {
+    match format {
+        ExtendedFormat::Heic | ExtendedFormat::Avif => heif::decode(bytes),
+        ExtendedFormat::Jxl => Err(format.unsupported()),
+    }
+}
+
+#[cfg(not(feature = "heif"))]
+fn extended_dimensions(format: ExtendedFormat, _bytes: &[u8]) -> Result<(u32, u32), ImageError> {
+    Err(format.unsupported())
+}
+
+#[cfg(not(feature = "heif"))]
+fn extended_decode(format: ExtendedFormat, _bytes: &[u8]) -> Result<DynamicImage, ImageError> {
+    Err(format.unsupported())
+}
+
+/// libheif 解码（HEIC / HEIF / AVIF）。
+#[cfg(feature = "heif")]
+mod heif {
+    use image::{DynamicImage, RgbaImage};
+    use libheif_rs::{ColorSpace, HeifContext, ImageHandle, LibHeif, RgbChroma};
+
+    use crate::i18n::message;
+
+    use super::ImageError;
+
+    fn primary_handle(context: &HeifContext<'_>) -> Result<ImageHandle, ImageError> {
+        context
+            .primary_image_handle()
+            .map_err(|e| ImageError::InvalidFormat(message("image.heif_primary_failed", &[("detail", &e)])))
+    }
+
+    fn read_context(bytes: &[u8]) -> Result<HeifContext<'_>, ImageError> {
+        HeifContext::read_from_bytes(bytes)
+            .map_err(|e| ImageError::InvalidFormat(message("image.heif_container_failed", &[("detail", &e)])))
+    }
+
+    pub(super) fn dimensions(bytes: &[u8]) -> Result<(u32, u32), ImageError> {
+        let context = read_context(bytes)?;
+        let handle = primary_handle(&context)?;
+        Ok((handle.width(), handle.height()))
+    }
+
+    pub(super) fn decode(bytes: &[u8]) -> Result<DynamicImage, ImageError> {
+        let context = read_context(bytes)?;
+        let handle = primary_handle(&context)?;
+        let image = LibHeif::new()
+            .decode(&handle, ColorSpace::Rgb(RgbChroma::Rgba), None)
+            .map_err(|e| ImageError::Decode(message("image.heif_decode_failed", &[("detail", &e)])))?;
+        let plane = image
+            .planes()
+            .interleaved
+            .ok_or_else(|| ImageError::Decode(message("image.heif_missing_rgba_plane", &[])))?;
+
+        // 按行拷贝，跳过每行末尾的对齐填充
+        let (width, height) = (plane.width, plane.height);
+        let row_bytes = width as usize * 4;
+        let mut rgba = Vec::with_capacity(row_bytes * height as usize);
+        for row in plane.data.chunks(plane.stride).take(height as usize) {
+            rgba.extend_from_slice(row.get(..row_bytes).ok_or_else(|| {
+                ImageError::Decode(message("image.heif_bad_row_length", &[]))
+            })?);
+        }
+        RgbaImage::from_raw(width, height, rgba)
+            .map(DynamicImage::ImageRgba8)
+            .ok_or_else(|| ImageError::Decode(message("image.heif_bad_pixel_length", &[])))
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/codecs_tests.rs"]
+mod tests;
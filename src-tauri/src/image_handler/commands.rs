@@ -4,39 +4,28 @@
 //!
 //! 命令层仅做 IPC 参数接收与结果返回，不承载业务逻辑。
 //! 所有实际处理交由 `ImageServiceState`，保持命令函数薄、稳定、易测试。
+//! 错误统一返回 `AppError`（图片错误附带 `stage`），与其它模块的命令一致。
 
-use super::{service, ImageAdvancedConfig, ImageError, ImageSource};
+use super::{service, DownloadCacheInfo, DownloadTaskInfo, ImageAdvancedConfig, ImageOutputOptions, ImageSource};
+use crate::error::AppError;
 use tauri::{AppHandle, State, Wry};
 
-#[derive(Debug, Clone, serde::Serialize)]
-pub struct ImageCommandError {
-    pub code: &'static str,
-    pub stage: &'static str,
-    pub message: String,
-}
-
-impl From<ImageError> for ImageCommandError {
-    fn from(error: ImageError) -> Self {
-        Self {
-            code: error.code(),
-            stage: error.stage(),
-            message: error.to_string(),
-        }
-    }
-}
-
-/// 下载网络图片并复制到系统剪贴板。
+/// 下载网络图片并复制到系统剪贴板（`options` 省略时按默认位图输出）。
+///
+/// `priority` 越大越先出队（省略为 0）；同一 URL 的并发请求合并为一次下载。
+/// PNG / JPEG 输出编码仅 Windows 与 Linux (X11) 支持，其它平台返回 `E_FORMAT_INVALID`。
 #[tauri::command]
 pub async fn download_and_copy_image(
     state: State<'_, service::ImageServiceState>,
     app: AppHandle<Wry>,
     url: String,
     request_id: String,
-) -> Result<(), ImageCommandError> {
+    options: Option<ImageOutputOptions>,
+    priority: Option<i32>,
+) -> Result<(), AppError> {
     state
-        .process_url_with_progress(&app, request_id, url)
-        .await
-        .map_err(ImageCommandError::from)?;
+        .process_url_with_progress(&app, request_id, url, options.unwrap_or_default(), priority.unwrap_or(0))
+        .await?;
     Ok(())
 }
 
@@ -44,33 +33,77 @@ pub async fn download_and_copy_image(
 pub fn cancel_image_download(
     state: State<'_, service::ImageServiceState>,
     request_id: String,
-) -> Result<bool, crate::error::AppError> {
+) -> Result<bool, AppError> {
     Ok(state.cancel_download(&request_id)?)
 }
 
+/// 暂停下载任务（同一 URL 上合并的任务一起暂停），任务不在下载阶段时返回 `false`。
+#[tauri::command]
+pub fn pause_image_download(
+    state: State<'_, service::ImageServiceState>,
+    request_id: String,
+) -> Result<bool, AppError> {
+    Ok(state.pause_download(&request_id)?)
+}
+
+/// 恢复已暂停的下载，或重新发起已失败/取消的下载。
+#[tauri::command]
+pub fn retry_image_download(
+    state: State<'_, service::ImageServiceState>,
+    app: AppHandle<Wry>,
+    request_id: String,
+) -> Result<bool, AppError> {
+    Ok(state.retry_download(&app, &request_id)?)
+}
+
+/// 列出图片下载队列中的任务（排队、进行中与最近结束的任务）。
+#[tauri::command]
+pub fn list_image_downloads(
+    state: State<'_, service::ImageServiceState>,
+) -> Result<Vec<DownloadTaskInfo>, AppError> {
+    Ok(state.list_downloads()?)
+}
+
 /// 将 Base64 图片复制到系统剪贴板。
 #[tauri::command]
 pub async fn copy_base64_image_to_clipboard(
     state: State<'_, service::ImageServiceState>,
     data: String,
-) -> Result<(), ImageCommandError> {
+) -> Result<(), AppError> {
     state
-        .process_source(ImageSource::Base64(data))
-        .await
-        .map_err(ImageCommandError::from)?;
+        .process_source(ImageSource::Base64(data), ImageOutputOptions::default())
+        .await?;
     Ok(())
 }
 
-/// 将本地图片复制到系统剪贴板。
+/// 将本地图片复制到系统剪贴板（`options` 省略时按默认位图输出）。
+///
+/// PNG / JPEG 输出编码仅 Windows 与 Linux (X11) 支持，其它平台返回 `E_FORMAT_INVALID`。
 #[tauri::command]
 pub async fn copy_image_to_clipboard(
     state: State<'_, service::ImageServiceState>,
     path: String,
-) -> Result<(), ImageCommandError> {
+    options: Option<ImageOutputOptions>,
+) -> Result<(), AppError> {
     state
-        .process_source(ImageSource::FilePath(path))
-        .await
-        .map_err(ImageCommandError::from)?;
+        .process_source(ImageSource::FilePath(path), options.unwrap_or_default())
+        .await?;
+    Ok(())
+}
+
+/// 将本地 SVG 栅格化为位图复制到剪贴板，同时附带 SVG 源文本。
+///
+/// `options` 的宽高 / DPI 决定渲染尺寸（省略时按 SVG 固有尺寸），背景色用于合成透明区域。
+/// SVG 源文本仅在 Windows 与 Linux (X11) 上附带，其它平台只写入位图。
+#[tauri::command]
+pub async fn copy_svg_image_to_clipboard(
+    state: State<'_, service::ImageServiceState>,
+    path: String,
+    options: Option<ImageOutputOptions>,
+) -> Result<(), AppError> {
+    state
+        .process_source(ImageSource::Svg(path), options.unwrap_or_default())
+        .await?;
     Ok(())
 }
 
@@ -79,7 +112,7 @@ pub async fn copy_image_to_clipboard(
 pub fn set_image_performance_profile(
     state: State<'_, service::ImageServiceState>,
     profile: String,
-) -> Result<(), crate::error::AppError> {
+) -> Result<(), AppError> {
     state.set_performance_profile(&profile)?;
     Ok(())
 }
@@ -88,7 +121,7 @@ pub fn set_image_performance_profile(
 #[tauri::command]
 pub fn get_image_performance_profile(
     state: State<'_, service::ImageServiceState>,
-) -> Result<String, crate::error::AppError> {
+) -> Result<String, AppError> {
     Ok(state.get_performance_profile()?)
 }
 
@@ -96,7 +129,7 @@ pub fn get_image_performance_profile(
 pub fn set_image_advanced_config(
     state: State<'_, service::ImageServiceState>,
     config: ImageAdvancedConfig,
-) -> Result<(), crate::error::AppError> {
+) -> Result<(), AppError> {
     state.set_advanced_config(config)?;
     Ok(())
 }
@@ -104,6 +137,35 @@ pub fn set_image_advanced_config(
 #[tauri::command]
 pub fn get_image_advanced_config(
     state: State<'_, service::ImageServiceState>,
-) -> Result<ImageAdvancedConfig, crate::error::AppError> {
+) -> Result<ImageAdvancedConfig, AppError> {
     Ok(state.get_advanced_config()?)
 }
+
+/// 获取本地图片缩略图路径（`size` 会向上取整到固定档位）。
+#[tauri::command]
+pub async fn get_thumbnail(
+    state: State<'_, service::ImageServiceState>,
+    path: String,
+    size: u32,
+) -> Result<String, AppError> {
+    state
+        .get_thumbnail(&path, size)
+        .await
+        .map_err(AppError::from)
+}
+
+/// 查询图片 URL 下载缓存的占用情况。
+#[tauri::command]
+pub async fn get_image_download_cache_info(
+    state: State<'_, service::ImageServiceState>,
+) -> Result<DownloadCacheInfo, AppError> {
+    Ok(state.download_cache_info().await?)
+}
+
+/// 清空图片 URL 下载缓存，返回清理后的占用情况。
+#[tauri::command]
+pub async fn clear_image_download_cache(
+    state: State<'_, service::ImageServiceState>,
+) -> Result<DownloadCacheInfo, AppError> {
+    Ok(state.clear_download_cache().await?)
+}
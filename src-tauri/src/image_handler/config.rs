@@ -11,10 +11,17 @@
 //! - `ImagePerformanceProfile` 负责档位字符串解析与反向输出。
 //! - `apply_performance_profile` 将档位转换为具体阈值。
 //! - `infer_performance_profile` 用于从当前配置反推档位（给前端展示状态）。
+//! - `ImageDomainRule` 按主机覆盖代理、请求头、Referer、Cookie、超时与内网访问开关，
+//!   由 `loader` 在构建 HTTP 客户端时应用。
+
+use std::collections::BTreeMap;
 
 use image::imageops::FilterType;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use serde::{Deserialize, Serialize};
 
+use crate::i18n::message;
+
 use super::ImageError;
 
 /// 图片处理配置。
@@ -60,6 +67,12 @@ pub struct ImageConfig {
     pub clipboard_retry_max_total_ms: u64,
     /// 单次退避延迟上限（毫秒）。
     pub clipboard_retry_max_delay_ms: u64,
+    /// URL 下载磁盘缓存的字节预算，超出后按最近访问时间淘汰。
+    pub download_cache_max_bytes: u64,
+    /// 按主机匹配的下载策略，按列表顺序取第一条命中的规则。
+    pub domain_rules: Vec<ImageDomainRule>,
+    /// URL 下载同时进行的传输数上限，超出的任务按优先级排队。
+    pub max_concurrent_downloads: usize,
 }
 
 impl Default for ImageConfig {
@@ -83,10 +96,146 @@ impl Default for ImageConfig {
             clipboard_retry_delay: 100,
             clipboard_retry_max_total_ms: 1_800,
             clipboard_retry_max_delay_ms: 900,
+            download_cache_max_bytes: 256 * 1024 * 1024,
+            domain_rules: Vec::new(),
+            max_concurrent_downloads: DEFAULT_MAX_CONCURRENT_DOWNLOADS,
         }
     }
 }
 
+/// URL 下载默认并发上限。
+const DEFAULT_MAX_CONCURRENT_DOWNLOADS: usize = 3;
+/// URL 下载并发上限的可设置最大值。
+const MAX_CONCURRENT_DOWNLOADS_LIMIT: usize = 16;
+
+/// 域名规则数量上限。
+const MAX_DOMAIN_RULES: usize = 64;
+/// 单条规则的附加请求头数量上限。
+const MAX_RULE_HEADERS: usize = 32;
+/// 由下载链路自行管理、规则不可覆盖的请求头。
+const RESERVED_RULE_HEADERS: [&str; 8] = [
+    "host",
+    "content-length",
+    "connection",
+    "transfer-encoding",
+    "accept",
+    "if-none-match",
+    "if-modified-since",
+    "range",
+];
+
+/// 按主机匹配的下载策略。
+///
+/// `pattern` 语法：`*` 匹配全部主机；`*.example.com` 仅匹配子域；
+/// `example.com` 匹配自身及全部子域。未设置的字段沿用全局配置。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImageDomainRule {
+    pub pattern: String,
+    /// HTTP(S) 代理地址，如 `http://proxy.corp:8080`。
+    pub proxy: Option<String>,
+    /// 附加请求头；与默认值同名时覆盖（如 `User-Agent`）。
+    pub headers: BTreeMap<String, String>,
+    /// 固定 Referer；未设置时使用目标地址的源站。
+    pub referer: Option<String>,
+    /// `Cookie` 请求头原文。
+    pub cookie: Option<String>,
+    /// 覆盖建立连接超时（秒）。
+    pub connect_timeout: Option<u64>,
+    /// 覆盖整体下载超时（秒）。
+    pub download_timeout: Option<u64>,
+    /// 覆盖全局内网访问开关：`true` 允许、`false` 禁止。
+    pub allow_private_network: Option<bool>,
+}
+
+impl ImageDomainRule {
+    /// 主机是否命中本规则（大小写与末尾的 `.` 不敏感）。
+    pub(crate) fn matches(&self, host: &str) -> bool {
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+        let pattern = self.pattern.trim().trim_end_matches('.').to_ascii_lowercase();
+        if pattern == "*" {
+            return true;
+        }
+        let is_subdomain_of = |domain: &str| {
+            host.strip_suffix(domain)
+                .is_some_and(|prefix| prefix.ends_with('.'))
+        };
+        match pattern.strip_prefix("*.") {
+            Some(domain) => is_subdomain_of(domain),
+            None => host == pattern || is_subdomain_of(&pattern),
+        }
+    }
+
+    /// 规则附加的请求头（自定义头 → Referer → Cookie，后者覆盖前者）。
+    pub(crate) fn request_headers(&self) -> Result<HeaderMap, ImageError> {
+        let invalid = |name: &str, reason: String| {
+            ImageError::InvalidFormat(message(
+                "image.rule_header_invalid",
+                &[("pattern", &self.pattern), ("header", &name), ("reason", &reason)],
+            ))
+        };
+        let mut headers = HeaderMap::new();
+        let fixed = [("referer", self.referer.as_deref()), ("cookie", self.cookie.as_deref())];
+        let entries = self
+            .headers
+            .iter()
+            .map(|(name, value)| (name.as_str(), Some(value.as_str())))
+            .chain(fixed);
+        for (name, value) in entries {
+            let Some(value) = value else {
+                continue;
+            };
+            let header_name = HeaderName::from_bytes(name.trim().as_bytes())
+                .map_err(|e| invalid(name, e.to_string()))?;
+            if RESERVED_RULE_HEADERS.contains(&header_name.as_str()) {
+                return Err(invalid(name, message("image.rule_header_reserved", &[])));
+            }
+            let header_value = HeaderValue::from_str(value.trim()).map_err(|e| invalid(name, e.to_string()))?;
+            headers.insert(header_name, header_value);
+        }
+        Ok(headers)
+    }
+
+    /// 校验规则字段的合法性。
+    pub(crate) fn validate(&self) -> Result<(), ImageError> {
+        let pattern = self.pattern.trim();
+        let label_chars_ok = pattern
+            .trim_start_matches("*.")
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | ':' | '[' | ']'));
+        if pattern.is_empty() || (pattern != "*" && !label_chars_ok) {
+            return Err(ImageError::InvalidFormat(message("image.rule_invalid_pattern", &[("pattern", &format!("{:?}", self.pattern))])));
+        }
+        if let Some(proxy) = self.proxy.as_deref() {
+            let parsed = reqwest::Url::parse(proxy)
+                .map_err(|e| ImageError::InvalidFormat(message("image.rule_invalid_proxy", &[("pattern", &pattern), ("detail", &e)])))?;
+            if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+                return Err(ImageError::InvalidFormat(message("image.rule_proxy_scheme", &[("pattern", &pattern)])));
+            }
+        }
+        if self.headers.len() > MAX_RULE_HEADERS {
+            return Err(ImageError::InvalidFormat(message(
+                "image.rule_too_many_headers",
+                &[("pattern", &pattern), ("max", &MAX_RULE_HEADERS)],
+            )));
+        }
+        self.request_headers()?;
+        if self.connect_timeout.is_some_and(|secs| !(1..=120).contains(&secs)) {
+            return Err(ImageError::InvalidFormat(message(
+                "image.rule_seconds_out_of_range",
+                &[("pattern", &pattern), ("field", &"connect_timeout"), ("min", &1), ("max", &120)],
+            )));
+        }
+        if self.download_timeout.is_some_and(|secs| !(1..=600).contains(&secs)) {
+            return Err(ImageError::InvalidFormat(message(
+                "image.rule_seconds_out_of_range",
+                &[("pattern", &pattern), ("field", &"download_timeout"), ("min", &1), ("max", &600)],
+            )));
+        }
+        Ok(())
+    }
+}
+
 /// 图片性能档位（面向产品/用户语义）。
 ///
 /// - `Quality`：尽量保真
@@ -115,10 +264,7 @@ impl ImagePerformanceProfile {
             "quality" => Ok(Self::Quality),
             "balanced" => Ok(Self::Balanced),
             "speed" => Ok(Self::Speed),
-            other => Err(ImageError::InvalidFormat(format!(
-                "未知性能档位：{}（可选：quality / balanced / speed）",
-                other
-            ))),
+            other => Err(ImageError::InvalidFormat(message("image.unknown_performance_profile", &[("value", &other)]))),
         }
     }
 
@@ -140,6 +286,18 @@ impl ImagePerformanceProfile {
 }
 
 impl ImageConfig {
+    /// 主机命中的第一条域名规则。
+    pub(crate) fn domain_rule_for(&self, host: &str) -> Option<&ImageDomainRule> {
+        self.domain_rules.iter().find(|rule| rule.matches(host))
+    }
+
+    /// 主机是否允许访问内网地址（域名规则优先于全局开关）。
+    pub(crate) fn allows_private_network_for(&self, host: &str) -> bool {
+        self.domain_rule_for(host)
+            .and_then(|rule| rule.allow_private_network)
+            .unwrap_or(self.allow_private_network)
+    }
+
     /// 基于当前参数反推性能档位。
     ///
     /// 用于“后端当前生效档位”查询场景。
@@ -199,6 +357,16 @@ pub struct ImageAdvancedConfig {
     pub stream_chunk_timeout_ms: u64,
     pub clipboard_retry_max_total_ms: u64,
     pub clipboard_retry_max_delay_ms: u64,
+    /// 按主机匹配的下载策略（省略时清空）。
+    #[serde(default)]
+    pub domain_rules: Vec<ImageDomainRule>,
+    /// URL 下载并发上限（省略时取默认值）。
+    #[serde(default = "default_max_concurrent_downloads")]
+    pub max_concurrent_downloads: usize,
+}
+
+fn default_max_concurrent_downloads() -> usize {
+    DEFAULT_MAX_CONCURRENT_DOWNLOADS
 }
 
 impl ImageAdvancedConfig {
@@ -213,6 +381,8 @@ impl ImageAdvancedConfig {
             stream_chunk_timeout_ms: config.stream_chunk_timeout_ms,
             clipboard_retry_max_total_ms: config.clipboard_retry_max_total_ms,
             clipboard_retry_max_delay_ms: config.clipboard_retry_max_delay_ms,
+            domain_rules: config.domain_rules.clone(),
+            max_concurrent_downloads: config.max_concurrent_downloads,
         }
     }
 
@@ -226,45 +396,57 @@ impl ImageAdvancedConfig {
         config.stream_chunk_timeout_ms = self.stream_chunk_timeout_ms;
         config.clipboard_retry_max_total_ms = self.clipboard_retry_max_total_ms;
         config.clipboard_retry_max_delay_ms = self.clipboard_retry_max_delay_ms;
+        config.domain_rules = self.domain_rules.clone();
+        config.max_concurrent_downloads = self.max_concurrent_downloads;
     }
 
     /// 校验各字段的合法区间。
     pub(crate) fn validate(&self) -> Result<(), ImageError> {
         if self.max_decoded_bytes < 8 * 1024 * 1024 {
-            return Err(ImageError::InvalidFormat(
-                "max_decoded_bytes 不能小于 8MB".to_string(),
-            ));
+            return Err(ImageError::InvalidFormat(message("image.config_decoded_bytes_too_small", &[])));
         }
         if !(1..=120).contains(&self.connect_timeout) {
-            return Err(ImageError::InvalidFormat(
-                "connect_timeout 必须在 1~120 秒之间".to_string(),
-            ));
+            return Err(ImageError::InvalidFormat(message(
+                "image.config_seconds_out_of_range",
+                &[("field", &"connect_timeout"), ("min", &1), ("max", &120)],
+            )));
         }
         if !(500..=120_000).contains(&self.stream_first_byte_timeout_ms) {
-            return Err(ImageError::InvalidFormat(
-                "stream_first_byte_timeout_ms 必须在 500~120000 毫秒之间".to_string(),
-            ));
+            return Err(ImageError::InvalidFormat(message(
+                "image.config_ms_out_of_range",
+                &[("field", &"stream_first_byte_timeout_ms"), ("min", &500), ("max", &120_000)],
+            )));
         }
         if !(500..=120_000).contains(&self.stream_chunk_timeout_ms) {
-            return Err(ImageError::InvalidFormat(
-                "stream_chunk_timeout_ms 必须在 500~120000 毫秒之间".to_string(),
-            ));
+            return Err(ImageError::InvalidFormat(message(
+                "image.config_ms_out_of_range",
+                &[("field", &"stream_chunk_timeout_ms"), ("min", &500), ("max", &120_000)],
+            )));
         }
         if !(200..=30_000).contains(&self.clipboard_retry_max_total_ms) {
-            return Err(ImageError::InvalidFormat(
-                "clipboard_retry_max_total_ms 必须在 200~30000 毫秒之间".to_string(),
-            ));
+            return Err(ImageError::InvalidFormat(message(
+                "image.config_ms_out_of_range",
+                &[("field", &"clipboard_retry_max_total_ms"), ("min", &200), ("max", &30_000)],
+            )));
         }
         if !(10..=5_000).contains(&self.clipboard_retry_max_delay_ms) {
-            return Err(ImageError::InvalidFormat(
-                "clipboard_retry_max_delay_ms 必须在 10~5000 毫秒之间".to_string(),
-            ));
+            return Err(ImageError::InvalidFormat(message(
+                "image.config_ms_out_of_range",
+                &[("field", &"clipboard_retry_max_delay_ms"), ("min", &10), ("max", &5_000)],
+            )));
         }
         if self.clipboard_retry_max_delay_ms > self.clipboard_retry_max_total_ms {
-            return Err(ImageError::InvalidFormat(
-                "clipboard_retry_max_delay_ms 不能大于 clipboard_retry_max_total_ms".to_string(),
-            ));
+            return Err(ImageError::InvalidFormat(message("image.config_retry_delay_exceeds_total", &[])));
         }
-        Ok(())
+        if !(1..=MAX_CONCURRENT_DOWNLOADS_LIMIT).contains(&self.max_concurrent_downloads) {
+            return Err(ImageError::InvalidFormat(message(
+                "image.config_out_of_range",
+                &[("field", &"max_concurrent_downloads"), ("min", &1), ("max", &MAX_CONCURRENT_DOWNLOADS_LIMIT)],
+            )));
+        }
+        if self.domain_rules.len() > MAX_DOMAIN_RULES {
+            return Err(ImageError::InvalidFormat(message("image.too_many_domain_rules", &[("max", &MAX_DOMAIN_RULES)])));
+        }
+        self.domain_rules.iter().try_for_each(ImageDomainRule::validate)
     }
 }
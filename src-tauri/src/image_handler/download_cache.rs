@@ -0,0 +1,420 @@
+//! # 下载磁盘缓存模块
+//!
+//! ## 设计思路
+//!
+//! `loader` 的内存缓存只保留 25 秒、最多 24 条，仅用于合并短时间内的重复复制；
+//! 从历史记录再次复制同一图片 URL 或重启应用后仍会整段重新下载。本模块把下载结果
+//! 按 URL 持久化到缓存目录，并借助 HTTP 校验器（ETag / Last-Modified）发起条件请求，
+//! 服务端返回 304 时直接复用本地字节。
+//!
+//! ## 实现思路
+//!
+//! 1. 缓存键：URL 的内容哈希；布局 `<cache>/<key[0..2]>/<key>.bin`，同名 `.json` 存元数据
+//! 2. 新鲜度：`Cache-Control: max-age` 期内直接命中；过期后有校验器则发条件请求，
+//!    无校验器时按启发式新鲜期（`HEURISTIC_FRESHNESS_SECS`）命中，之后重新下载
+//! 3. `no-store` 响应与单条超过预算的响应不落盘
+//! 4. 命中时刷新访问时间，写入后按访问时间做 LRU 淘汰，直到总字节数不超过预算
+//! 5. 同一进程内的磁盘操作经互斥锁串行化；函数均为同步 I/O，由调用方放入 `spawn_blocking`
+//! 6. 正文与元数据分别经 `<key>.bin.tmp` / `<key>.json.tmp` 原子写入；临时文件只在持锁写入时存在，
+//!    扫描时遇到即为崩溃残留，直接删除
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bytes::Bytes;
+use once_cell::sync::OnceCell;
+use reqwest::header::{HeaderMap, HeaderName, CACHE_CONTROL, ETAG, LAST_MODIFIED};
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::message;
+use crate::storage::content_hash;
+
+use super::ImageError;
+
+/// 无 `max-age` 且无校验器的响应视为新鲜的时长（秒）。
+const HEURISTIC_FRESHNESS_SECS: u64 = 60 * 60;
+
+/// 缓存正文与元数据的扩展名。
+const BODY_EXTENSION: &str = "bin";
+const META_EXTENSION: &str = "json";
+/// 原子写入的临时文件扩展名（追加在正文/元数据扩展名之后）。
+const TMP_EXTENSION: &str = "tmp";
+
+static DOWNLOAD_CACHE_DIR: OnceCell<PathBuf> = OnceCell::new();
+
+/// 串行化磁盘读写与淘汰，避免并发下载同时淘汰/覆盖同一条目。
+static DISK_LOCK: Mutex<()> = Mutex::new(());
+
+// ─── 缓存目录 ───────────────────────────────────────────────────────
+
+/// 注册下载缓存目录（在 `setup` 阶段调用一次）。
+pub fn init_download_cache(dir: PathBuf) -> Result<(), ImageError> {
+    fs::create_dir_all(&dir)
+        .map_err(|e| ImageError::FileSystem(message("image.cache_dir_failed", &[("detail", &e)])))?;
+    let _ = DOWNLOAD_CACHE_DIR.set(dir);
+    Ok(())
+}
+
+/// 已注册的下载缓存目录；未初始化时磁盘缓存不生效。
+pub(crate) fn download_cache_dir() -> Option<&'static Path> {
+    DOWNLOAD_CACHE_DIR.get().map(PathBuf::as_path)
+}
+
+pub(crate) fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+// ─── 数据模型 ───────────────────────────────────────────────────────
+
+/// 下载命中的缓存层级，随进度事件上报给前端。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DownloadCacheHit {
+    /// 短期内存缓存。
+    Memory,
+    /// 磁盘缓存仍在新鲜期内，未发起网络请求。
+    Disk,
+    /// 条件请求返回 304，复用磁盘副本。
+    Revalidated,
+    /// 网络失败，回退到已过期的磁盘副本。
+    Stale,
+}
+
+impl DownloadCacheHit {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Memory => "memory",
+            Self::Disk => "disk",
+            Self::Revalidated => "revalidated",
+            Self::Stale => "stale",
+        }
+    }
+}
+
+/// 从响应头提取的缓存策略与校验器。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct CachePolicy {
+    pub(crate) etag: Option<String>,
+    pub(crate) last_modified: Option<String>,
+    pub(crate) max_age_secs: Option<u64>,
+    #[serde(skip)]
+    pub(crate) no_store: bool,
+}
+
+impl CachePolicy {
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name: HeaderName| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+
+        let mut policy = Self {
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+            ..Self::default()
+        };
+        for directive in headers
+            .get_all(CACHE_CONTROL)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+        {
+            let directive = directive.trim().to_ascii_lowercase();
+            if directive == "no-store" {
+                policy.no_store = true;
+            } else if let Some(secs) = directive.strip_prefix("max-age=") {
+                policy.max_age_secs = secs.trim_matches('"').parse().ok();
+            }
+        }
+        policy
+    }
+
+    /// 是否可发起条件请求。
+    pub(crate) fn has_validators(&self) -> bool {
+        self.etag.is_some() || self.last_modified.is_some()
+    }
+
+    /// 合并 304 响应携带的新策略：新值优先，缺省时保留旧值。
+    fn refreshed_by(&self, newer: &CachePolicy) -> Self {
+        Self {
+            etag: newer.etag.clone().or_else(|| self.etag.clone()),
+            last_modified: newer.last_modified.clone().or_else(|| self.last_modified.clone()),
+            max_age_secs: newer.max_age_secs.or(self.max_age_secs),
+            no_store: false,
+        }
+    }
+}
+
+/// 缓存条目元数据（`.json` 文件内容）。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct CacheEntryMeta {
+    pub(crate) url: String,
+    pub(crate) size: u64,
+    #[serde(flatten)]
+    pub(crate) policy: CachePolicy,
+    /// 写入或最近一次成功校验的时间（毫秒时间戳）。
+    pub(crate) stored_at_ms: u64,
+    /// 最近一次命中的时间（毫秒时间戳），LRU 淘汰依据。
+    pub(crate) last_access_ms: u64,
+}
+
+impl CacheEntryMeta {
+    /// 是否可以不经网络直接使用。
+    pub(crate) fn is_fresh(&self, now_ms: u64) -> bool {
+        let freshness_secs = match self.policy.max_age_secs {
+            Some(max_age) => max_age,
+            None if self.policy.has_validators() => 0,
+            None => HEURISTIC_FRESHNESS_SECS,
+        };
+        now_ms.saturating_sub(self.stored_at_ms) < freshness_secs.saturating_mul(1000)
+    }
+}
+
+/// 从磁盘读出的缓存条目。
+pub(crate) struct CachedDownload {
+    pub(crate) meta: CacheEntryMeta,
+    pub(crate) bytes: Bytes,
+}
+
+/// 下载缓存占用情况（供设置页展示）。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct DownloadCacheInfo {
+    pub entries: u64,
+    pub total_bytes: u64,
+    pub max_bytes: u64,
+}
+
+// ─── 路径 ───────────────────────────────────────────────────────────
+
+fn entry_path(cache_dir: &Path, url: &str, ext: &str) -> PathBuf {
+    let key = content_hash(url.as_bytes());
+    cache_dir.join(&key[..2]).join(format!("{}.{}", key, ext))
+}
+
+fn lock_disk() -> std::sync::MutexGuard<'static, ()> {
+    DISK_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn remove_entry_files(meta_path: &Path) {
+    for path in [meta_path.to_path_buf(), meta_path.with_extension(BODY_EXTENSION)] {
+        match fs::remove_file(&path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => log::warn!(
+                "{}",
+                message(
+                    "image_log.cache_delete_failed",
+                    &[
+                        ("path", &path.display()),
+                        ("detail", &e),
+                    ],
+                )
+            ),
+        }
+    }
+}
+
+fn write_atomically(path: &Path, bytes: &[u8]) -> Result<(), ImageError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| ImageError::FileSystem(message("image.cache_dir_failed", &[("detail", &e)])))?;
+    }
+    let ext = path.extension().and_then(|ext| ext.to_str()).unwrap_or_default();
+    let tmp_path = path.with_extension(format!("{}.{}", ext, TMP_EXTENSION));
+    fs::write(&tmp_path, bytes)
+        .map_err(|e| ImageError::FileSystem(message("image.cache_write_failed", &[("detail", &e)])))?;
+    fs::rename(&tmp_path, path).map_err(|e| {
+        let _ = fs::remove_file(&tmp_path);
+        ImageError::FileSystem(message("image.cache_write_failed", &[("detail", &e)]))
+    })
+}
+
+fn read_meta(path: &Path) -> Option<CacheEntryMeta> {
+    let raw = fs::read(path).ok()?;
+    serde_json::from_slice(&raw).ok()
+}
+
+fn write_meta(path: &Path, meta: &CacheEntryMeta) -> Result<(), ImageError> {
+    let raw = serde_json::to_vec(meta)
+        .map_err(|e| ImageError::FileSystem(message("image.cache_meta_serialize_failed", &[("detail", &e)])))?;
+    write_atomically(path, &raw)
+}
+
+/// 遍历缓存目录中的全部条目（元数据路径 + 元数据），顺带清理孤立或损坏的文件。
+fn scan_entries(cache_dir: &Path) -> Vec<(PathBuf, CacheEntryMeta)> {
+    let Ok(shards) = fs::read_dir(cache_dir) else {
+        return Vec::new();
+    };
+    let mut entries = Vec::new();
+    for shard in shards.flatten().map(|entry| entry.path()).filter(|path| path.is_dir()) {
+        let Ok(files) = fs::read_dir(&shard) else {
+            continue;
+        };
+        for path in files.flatten().map(|entry| entry.path()) {
+            match path.extension().and_then(|ext| ext.to_str()) {
+                Some(META_EXTENSION) => match read_meta(&path) {
+                    Some(meta) if path.with_extension(BODY_EXTENSION).is_file() => entries.push((path, meta)),
+                    _ => remove_entry_files(&path),
+                },
+                Some(BODY_EXTENSION) if !path.with_extension(META_EXTENSION).is_file() => {
+                    remove_entry_files(&path.with_extension(META_EXTENSION))
+                }
+                // 调用方持有磁盘锁，不会有正在进行的写入
+                Some(TMP_EXTENSION) => {
+                    if let Err(e) = fs::remove_file(&path) {
+                        log::warn!(
+                            "{}",
+                            message(
+                                "image_log.cache_tmp_delete_failed",
+                                &[
+                                    ("path", &path.display()),
+                                    ("detail", &e),
+                                ],
+                            )
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    entries
+}
+
+// ─── 读写 ───────────────────────────────────────────────────────────
+
+/// 读取 URL 对应的缓存条目；元数据与正文不一致时删除并返回 `None`。
+pub(crate) fn lookup(cache_dir: &Path, url: &str) -> Option<CachedDownload> {
+    let _guard = lock_disk();
+    let meta_path = entry_path(cache_dir, url, META_EXTENSION);
+    let meta = read_meta(&meta_path)?;
+    let bytes = fs::read(meta_path.with_extension(BODY_EXTENSION)).ok();
+    match bytes {
+        Some(bytes) if meta.url == url && bytes.len() as u64 == meta.size => Some(CachedDownload {
+            meta,
+            bytes: Bytes::from(bytes),
+        }),
+        _ => {
+            remove_entry_files(&meta_path);
+            None
+        }
+    }
+}
+
+/// 记录一次命中：刷新访问时间；`revalidated` 为 304 响应的策略时同时刷新新鲜期。
+pub(crate) fn touch(cache_dir: &Path, url: &str, revalidated: Option<&CachePolicy>, now_ms: u64) {
+    let _guard = lock_disk();
+    let meta_path = entry_path(cache_dir, url, META_EXTENSION);
+    let Some(mut meta) = read_meta(&meta_path) else {
+        return;
+    };
+    meta.last_access_ms = now_ms;
+    if let Some(newer) = revalidated {
+        meta.policy = meta.policy.refreshed_by(newer);
+        meta.stored_at_ms = now_ms;
+    }
+    if let Err(err) = write_meta(&meta_path, &meta) {
+        log::warn!("{}", message("image_log.cache_meta_update_failed", &[("detail", &err)]));
+    }
+}
+
+/// 写入下载结果并按 LRU 淘汰至预算以内；返回是否实际落盘。
+pub(crate) fn store(
+    cache_dir: &Path,
+    url: &str,
+    bytes: &[u8],
+    policy: &CachePolicy,
+    max_bytes: u64,
+    now_ms: u64,
+) -> Result<bool, ImageError> {
+    if policy.no_store || bytes.is_empty() || bytes.len() as u64 > max_bytes {
+        return Ok(false);
+    }
+
+    let _guard = lock_disk();
+    let meta_path = entry_path(cache_dir, url, META_EXTENSION);
+    // 先删旧元数据再写正文，避免中途失败时留下“新正文 + 旧校验器”的组合
+    remove_entry_files(&meta_path);
+    write_atomically(&meta_path.with_extension(BODY_EXTENSION), bytes)?;
+    let meta = CacheEntryMeta {
+        url: url.to_string(),
+        size: bytes.len() as u64,
+        policy: policy.clone(),
+        stored_at_ms: now_ms,
+        last_access_ms: now_ms,
+    };
+    if let Err(err) = write_meta(&meta_path, &meta) {
+        remove_entry_files(&meta_path);
+        return Err(err);
+    }
+
+    evict_to_budget(cache_dir, max_bytes);
+    Ok(true)
+}
+
+/// 按最近访问时间从旧到新删除条目，直到总字节数不超过预算；返回删除条数。
+fn evict_to_budget(cache_dir: &Path, max_bytes: u64) -> usize {
+    let mut entries = scan_entries(cache_dir);
+    let mut total: u64 = entries.iter().map(|(_, meta)| meta.size).sum();
+    if total <= max_bytes {
+        return 0;
+    }
+
+    entries.sort_by_key(|(_, meta)| meta.last_access_ms);
+    let mut removed = 0;
+    for (meta_path, meta) in entries {
+        if total <= max_bytes {
+            break;
+        }
+        remove_entry_files(&meta_path);
+        total = total.saturating_sub(meta.size);
+        removed += 1;
+    }
+    log::debug!(
+        "{}",
+        message("image_log.cache_evicted", &[("count", &removed), ("bytes", &total)])
+    );
+    removed
+}
+
+/// 统计缓存条目数与占用字节数。
+pub(crate) fn cache_info(cache_dir: &Path, max_bytes: u64) -> DownloadCacheInfo {
+    let _guard = lock_disk();
+    let entries = scan_entries(cache_dir);
+    DownloadCacheInfo {
+        entries: entries.len() as u64,
+        total_bytes: entries.iter().map(|(_, meta)| meta.size).sum(),
+        max_bytes,
+    }
+}
+
+/// 清空缓存目录下的全部条目（保留目录本身）。
+pub(crate) fn clear(cache_dir: &Path) -> Result<(), ImageError> {
+    let _guard = lock_disk();
+    let shards = match fs::read_dir(cache_dir) {
+        Ok(shards) => shards,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(ImageError::FileSystem(message("image.cache_read_dir_failed", &[("detail", &e)]))),
+    };
+    for path in shards.flatten().map(|entry| entry.path()) {
+        let result = if path.is_dir() {
+            fs::remove_dir_all(&path)
+        } else {
+            fs::remove_file(&path)
+        };
+        result.map_err(|e| ImageError::FileSystem(message("image.cache_clear_failed", &[("detail", &e)])))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "tests/download_cache_tests.rs"]
+mod tests;
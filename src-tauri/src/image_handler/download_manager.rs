@@ -0,0 +1,644 @@
+//! # URL 下载管理模块
+//!
+//! ## 设计思路
+//!
+//! 一次拖入多张图片 URL 时，每个 `download_and_copy_image` 调用都会立即发起下载，
+//! 并发过高既抢占带宽，也让每张图片都更晚完成。下载管理器统一调度全部 URL 下载：
+//! - 并发上限：同时进行的传输数不超过 `ImageConfig::max_concurrent_downloads`
+//! - 优先级：排队中的传输按优先级（高者先）、再按登记顺序出队
+//! - 合并：同一 URL 已在排队或传输中时，新任务挂到已有传输上，只下载一次
+//! - 可观测：任务状态、阶段与字节数可随时列出，已结束任务保留最近 `MAX_FINISHED_TASKS` 条
+//!
+//! ## 实现思路
+//!
+//! - 任务（按 `request_id`）与传输（按 URL）集中在一把互斥锁内；调度通过 `Notify` 广播唤醒，
+//!   等待方先登记唤醒再检查状态，避免丢失通知
+//! - 传输结果经 `watch` 通道发布给所有订阅任务，各任务再按自己的输出选项解码并复制
+//! - 暂停作用于整个传输：排队中的传输不再出队，运行中的传输被中断并释放名额，恢复后重新下载
+//! - 取消只作用于单个任务；传输上的任务全部取消后传输才会中断
+//! - `DownloadTaskGuard` / `TransferPublisher` 为 RAII 守卫，异常退出时也会收尾任务与传输
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+use bytes::Bytes;
+use serde::Serialize;
+use tokio::sync::{watch, Notify};
+
+use crate::i18n::message;
+
+use super::download_cache::{now_ms, DownloadCacheHit};
+use super::{ImageError, ImageOutputOptions};
+
+/// 已结束任务的保留条数（超出后淘汰最早结束的任务）。
+const MAX_FINISHED_TASKS: usize = 50;
+
+/// 一次传输的结果：原始字节与命中的缓存层级。
+pub(crate) type TransferResult = Result<(Bytes, Option<DownloadCacheHit>), ImageError>;
+
+// ─── 任务快照 ──────────────────────────────────────────────────────
+
+/// 下载任务状态。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadTaskStatus {
+    Queued,
+    Running,
+    Paused,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl DownloadTaskStatus {
+    fn is_active(self) -> bool {
+        matches!(self, Self::Queued | Self::Running | Self::Paused)
+    }
+}
+
+/// 下载任务用途。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadTaskKind {
+    /// 下载后写入剪贴板（`download_and_copy_image`）。
+    Clipboard,
+    /// 下载后保存为历史图片条目（`download_image_to_history`）。
+    History,
+}
+
+/// 下载任务快照（`list_image_downloads` 的返回项）。
+#[derive(Debug, Clone, Serialize)]
+pub struct DownloadTaskInfo {
+    pub request_id: String,
+    pub url: String,
+    pub kind: DownloadTaskKind,
+    pub priority: i32,
+    pub status: DownloadTaskStatus,
+    /// 运行中为 `download` / `decode`；失败时为出错阶段。
+    pub stage: Option<&'static str>,
+    pub downloaded_bytes: u64,
+    pub total_bytes: Option<u64>,
+    /// 是否合并到了同一 URL 的已有传输上。
+    pub coalesced: bool,
+    pub cache_hit: Option<&'static str>,
+    pub error_code: Option<&'static str>,
+    pub error_message: Option<String>,
+    pub created_at: u64,
+    pub finished_at: Option<u64>,
+}
+
+/// 重新发起一个已结束任务所需的参数。
+pub(crate) struct RetrySpec {
+    pub(crate) request_id: String,
+    pub(crate) url: String,
+    pub(crate) priority: i32,
+    pub(crate) options: ImageOutputOptions,
+}
+
+/// `retry` 的处理结果。
+pub(crate) enum RetryAction {
+    /// 已暂停的任务被恢复。
+    Resumed,
+    /// 已失败/取消的剪贴板任务需要重新发起。
+    Restart(RetrySpec),
+    /// 任务不存在、正在进行中或不是剪贴板任务。
+    Unavailable,
+}
+
+/// 任务登记结果。
+pub(crate) struct Registration {
+    /// 传输结果通道（合并任务与发起任务共享）。
+    pub(crate) result_rx: watch::Receiver<Option<TransferResult>>,
+    /// 是否新建了传输（调用方需要驱动该传输）。
+    pub(crate) starts_transfer: bool,
+}
+
+// ─── 内部状态 ──────────────────────────────────────────────────────
+
+struct TaskEntry {
+    info: DownloadTaskInfo,
+    /// 剪贴板任务的输出选项；保存到历史的任务为 `None`（由调用方重新发起，不支持重试）。
+    options: Option<ImageOutputOptions>,
+    cancelled: bool,
+    seq: u64,
+}
+
+struct TransferEntry {
+    subscribers: Vec<String>,
+    priority: i32,
+    seq: u64,
+    running: bool,
+    paused: bool,
+    downloaded: u64,
+    total: Option<u64>,
+    result_tx: watch::Sender<Option<TransferResult>>,
+}
+
+#[derive(Default)]
+struct ManagerInner {
+    running: usize,
+    next_seq: u64,
+    tasks: HashMap<String, TaskEntry>,
+    transfers: HashMap<String, TransferEntry>,
+}
+
+fn cancelled_error() -> ImageError {
+    ImageError::Cancelled(message("image.download_cancelled", &[]))
+}
+
+fn interrupted_error() -> ImageError {
+    ImageError::Cancelled(message("image.download_interrupted", &[]))
+}
+
+/// 传输上是否仍有未取消的任务。
+fn has_live_subscriber(tasks: &HashMap<String, TaskEntry>, transfer: &TransferEntry) -> bool {
+    transfer
+        .subscribers
+        .iter()
+        .any(|id| tasks.get(id).is_some_and(|task| !task.cancelled && task.info.status.is_active()))
+}
+
+impl ManagerInner {
+    /// 当前应出队的传输：未运行、未暂停、仍有订阅者，按优先级高、登记早排序。
+    fn next_runnable(&self) -> Option<&str> {
+        self.transfers
+            .iter()
+            .filter(|(_, t)| !t.running && !t.paused && has_live_subscriber(&self.tasks, t))
+            .max_by_key(|(_, t)| (t.priority, Reverse(t.seq)))
+            .map(|(url, _)| url.as_str())
+    }
+
+    /// 对传输上仍在进行中的任务应用更新。
+    fn update_subscribers(&mut self, url: &str, mut apply: impl FnMut(&mut DownloadTaskInfo)) -> Vec<String> {
+        let Some(transfer) = self.transfers.get(url) else {
+            return Vec::new();
+        };
+        let mut live = Vec::with_capacity(transfer.subscribers.len());
+        for id in &transfer.subscribers {
+            if let Some(task) = self.tasks.get_mut(id).filter(|t| !t.cancelled && t.info.status.is_active()) {
+                apply(&mut task.info);
+                live.push(id.clone());
+            }
+        }
+        live
+    }
+
+    /// 已结束任务超出保留条数时淘汰最早结束的任务。
+    fn trim_finished(&mut self) {
+        let mut finished: Vec<(u64, u64, String)> = self
+            .tasks
+            .iter()
+            .filter(|(_, t)| !t.info.status.is_active())
+            .map(|(id, t)| (t.info.finished_at.unwrap_or(0), t.seq, id.clone()))
+            .collect();
+        if finished.len() <= MAX_FINISHED_TASKS {
+            return;
+        }
+        finished.sort_unstable();
+        let excess = finished.len() - MAX_FINISHED_TASKS;
+        for (_, _, id) in finished.into_iter().take(excess) {
+            self.tasks.remove(&id);
+        }
+    }
+}
+
+// ─── DownloadManager ───────────────────────────────────────────────
+
+/// URL 下载队列：并发上限、优先级、同 URL 合并与任务状态查询。
+#[derive(Default)]
+pub(crate) struct DownloadManager {
+    inner: Mutex<ManagerInner>,
+    notify: Notify,
+}
+
+impl DownloadManager {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, ManagerInner>, ImageError> {
+        self.inner
+            .lock()
+            .map_err(|_| ImageError::ResourceLimit(message("image.download_queue_lock_poisoned", &[])))
+    }
+
+    /// 登记下载任务；同一 URL 已有传输时合并到该传输。
+    ///
+    /// `options` 为 `None` 表示保存到历史的任务。
+    /// 同一 `request_id` 仍在进行中时拒绝重复登记；已结束的同名任务会被覆盖。
+    pub(crate) fn register(
+        &self,
+        request_id: &str,
+        url: &str,
+        priority: i32,
+        options: Option<ImageOutputOptions>,
+    ) -> Result<(DownloadTaskGuard<'_>, Registration), ImageError> {
+        let mut inner = self.lock()?;
+        if inner.tasks.get(request_id).is_some_and(|t| t.info.status.is_active()) {
+            return Err(ImageError::InvalidFormat(message("image.download_in_progress", &[("request_id", &request_id)])));
+        }
+
+        let seq = inner.next_seq;
+        inner.next_seq += 1;
+
+        let (registration, status, downloaded, total) = match inner.transfers.get_mut(url) {
+            Some(transfer) => {
+                if !transfer.subscribers.iter().any(|id| id == request_id) {
+                    transfer.subscribers.push(request_id.to_string());
+                }
+                transfer.priority = transfer.priority.max(priority);
+                let status = if transfer.paused {
+                    DownloadTaskStatus::Paused
+                } else if transfer.running {
+                    DownloadTaskStatus::Running
+                } else {
+                    DownloadTaskStatus::Queued
+                };
+                let registration = Registration {
+                    result_rx: transfer.result_tx.subscribe(),
+                    starts_transfer: false,
+                };
+                (registration, status, transfer.downloaded, transfer.total)
+            }
+            None => {
+                let (result_tx, result_rx) = watch::channel(None);
+                inner.transfers.insert(
+                    url.to_string(),
+                    TransferEntry {
+                        subscribers: vec![request_id.to_string()],
+                        priority,
+                        seq,
+                        running: false,
+                        paused: false,
+                        downloaded: 0,
+                        total: None,
+                        result_tx,
+                    },
+                );
+                let registration = Registration { result_rx, starts_transfer: true };
+                (registration, DownloadTaskStatus::Queued, 0, None)
+            }
+        };
+
+        let running = status == DownloadTaskStatus::Running;
+        inner.tasks.insert(
+            request_id.to_string(),
+            TaskEntry {
+                info: DownloadTaskInfo {
+                    request_id: request_id.to_string(),
+                    url: url.to_string(),
+                    kind: if options.is_some() { DownloadTaskKind::Clipboard } else { DownloadTaskKind::History },
+                    priority,
+                    status,
+                    stage: running.then_some("download"),
+                    downloaded_bytes: downloaded,
+                    total_bytes: total,
+                    coalesced: !registration.starts_transfer,
+                    cache_hit: None,
+                    error_code: None,
+                    error_message: None,
+                    created_at: now_ms(),
+                    finished_at: None,
+                },
+                options,
+                cancelled: false,
+                seq,
+            },
+        );
+        inner.trim_finished();
+        drop(inner);
+
+        self.notify.notify_waiters();
+        Ok((
+            DownloadTaskGuard { manager: self, request_id: request_id.to_string(), finished: false },
+            registration,
+        ))
+    }
+
+    /// 等待并占用一个并发名额；传输上的任务全部取消时返回 `Cancelled`。
+    pub(crate) async fn acquire_slot(&self, url: &str, limit: usize) -> Result<TransferSlot<'_>, ImageError> {
+        loop {
+            let notified = self.notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            {
+                let mut guard = self.lock()?;
+                let inner = &mut *guard;
+                let Some(transfer) = inner.transfers.get(url) else {
+                    return Err(interrupted_error());
+                };
+                if !has_live_subscriber(&inner.tasks, transfer) {
+                    return Err(cancelled_error());
+                }
+                if inner.running < limit.max(1) && inner.next_runnable() == Some(url) {
+                    inner.running += 1;
+                    if let Some(transfer) = inner.transfers.get_mut(url) {
+                        transfer.running = true;
+                        transfer.downloaded = 0;
+                        transfer.total = None;
+                    }
+                    inner.update_subscribers(url, |info| {
+                        info.status = DownloadTaskStatus::Running;
+                        info.stage = Some("download");
+                        info.downloaded_bytes = 0;
+                        info.total_bytes = None;
+                    });
+                    return Ok(TransferSlot { manager: self, url: url.to_string() });
+                }
+            }
+
+            notified.await;
+        }
+    }
+
+    /// 记录传输进度，返回仍在等待该传输的任务 ID。
+    pub(crate) fn record_progress(&self, url: &str, downloaded: u64, total: Option<u64>) -> Vec<String> {
+        let Ok(mut inner) = self.lock() else {
+            return Vec::new();
+        };
+        if let Some(transfer) = inner.transfers.get_mut(url) {
+            transfer.downloaded = downloaded;
+            transfer.total = total;
+        }
+        inner.update_subscribers(url, |info| {
+            info.downloaded_bytes = downloaded;
+            info.total_bytes = total;
+        })
+    }
+
+    /// 传输是否应中断（已暂停，或其上任务全部取消）。
+    pub(crate) fn is_interrupted(&self, url: &str) -> bool {
+        let Ok(inner) = self.lock() else {
+            return true;
+        };
+        inner
+            .transfers
+            .get(url)
+            .is_none_or(|t| t.paused || !has_live_subscriber(&inner.tasks, t))
+    }
+
+    /// 传输是否处于暂停状态。
+    pub(crate) fn is_paused(&self, url: &str) -> bool {
+        self.lock()
+            .map(|inner| inner.transfers.get(url).is_some_and(|t| t.paused))
+            .unwrap_or(false)
+    }
+
+    /// 创建传输结果发布守卫（未显式发布即退出时发布“已中断”）。
+    pub(crate) fn publisher(&self, url: &str) -> TransferPublisher<'_> {
+        TransferPublisher { manager: self, url: url.to_string(), published: false }
+    }
+
+    fn publish(&self, url: &str, result: TransferResult) {
+        let Ok(mut inner) = self.lock() else {
+            return;
+        };
+        if let Ok((bytes, cache_hit)) = &result {
+            let size = bytes.len() as u64;
+            inner.update_subscribers(url, |info| {
+                info.stage = Some("decode");
+                info.cache_hit = cache_hit.map(DownloadCacheHit::as_str);
+                info.downloaded_bytes = size;
+                info.total_bytes = info.total_bytes.or(Some(size));
+            });
+        }
+        if let Some(transfer) = inner.transfers.remove(url) {
+            transfer.result_tx.send_replace(Some(result));
+        }
+        drop(inner);
+        self.notify.notify_waiters();
+    }
+
+    /// 等待任务所在传输的结果；任务被取消时立即返回 `Cancelled`。
+    pub(crate) async fn wait_transfer(
+        &self,
+        request_id: &str,
+        mut result_rx: watch::Receiver<Option<TransferResult>>,
+    ) -> TransferResult {
+        loop {
+            let notified = self.notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            if let Some(result) = result_rx.borrow_and_update().clone() {
+                return result;
+            }
+            self.ensure_not_cancelled(request_id)?;
+
+            tokio::select! {
+                changed = result_rx.changed() => {
+                    if changed.is_err() {
+                        return result_rx.borrow().clone().unwrap_or_else(|| Err(interrupted_error()));
+                    }
+                }
+                _ = &mut notified => {}
+            }
+        }
+    }
+
+    /// 任务已被取消时返回 `Cancelled`。
+    pub(crate) fn ensure_not_cancelled(&self, request_id: &str) -> Result<(), ImageError> {
+        let inner = self.lock()?;
+        match inner.tasks.get(request_id) {
+            Some(task) if !task.cancelled => Ok(()),
+            _ => Err(cancelled_error()),
+        }
+    }
+
+    /// 取消任务；任务存在且仍在进行中时返回 `true`。
+    pub(crate) fn cancel(&self, request_id: &str) -> Result<bool, ImageError> {
+        let mut inner = self.lock()?;
+        let Some(task) = inner
+            .tasks
+            .get_mut(request_id)
+            .filter(|t| !t.cancelled && t.info.status.is_active())
+        else {
+            return Ok(false);
+        };
+        task.cancelled = true;
+        drop(inner);
+        self.notify.notify_waiters();
+        Ok(true)
+    }
+
+    /// 暂停任务所在的传输（合并在同一传输上的任务一起暂停）。
+    ///
+    /// 任务不存在、已结束或已进入解码阶段时返回 `false`。
+    pub(crate) fn pause(&self, request_id: &str) -> Result<bool, ImageError> {
+        let mut inner = self.lock()?;
+        let Some(url) = inner
+            .tasks
+            .get(request_id)
+            .filter(|t| !t.cancelled && t.info.status.is_active())
+            .map(|t| t.info.url.clone())
+        else {
+            return Ok(false);
+        };
+        match inner.transfers.get_mut(&url) {
+            Some(transfer) if !transfer.paused => transfer.paused = true,
+            _ => return Ok(false),
+        }
+        inner.update_subscribers(&url, |info| {
+            info.status = DownloadTaskStatus::Paused;
+            info.stage = None;
+        });
+        drop(inner);
+        self.notify.notify_waiters();
+        Ok(true)
+    }
+
+    /// 重试任务：已暂停的任务恢复排队，已失败/取消的任务返回重新发起的参数。
+    pub(crate) fn retry(&self, request_id: &str) -> Result<RetryAction, ImageError> {
+        let mut inner = self.lock()?;
+        let Some(task) = inner.tasks.get(request_id) else {
+            return Ok(RetryAction::Unavailable);
+        };
+        match task.info.status {
+            DownloadTaskStatus::Paused if !task.cancelled => {
+                let url = task.info.url.clone();
+                if let Some(transfer) = inner.transfers.get_mut(&url) {
+                    transfer.paused = false;
+                }
+                inner.update_subscribers(&url, |info| info.status = DownloadTaskStatus::Queued);
+                drop(inner);
+                self.notify.notify_waiters();
+                Ok(RetryAction::Resumed)
+            }
+            DownloadTaskStatus::Failed | DownloadTaskStatus::Cancelled => Ok(match &task.options {
+                Some(options) => RetryAction::Restart(RetrySpec {
+                    request_id: task.info.request_id.clone(),
+                    url: task.info.url.clone(),
+                    priority: task.info.priority,
+                    options: options.clone(),
+                }),
+                None => RetryAction::Unavailable,
+            }),
+            _ => Ok(RetryAction::Unavailable),
+        }
+    }
+
+    /// 更新任务阶段。
+    pub(crate) fn set_stage(&self, request_id: &str, stage: &'static str) {
+        if let Ok(mut inner) = self.lock()
+            && let Some(task) = inner.tasks.get_mut(request_id)
+        {
+            task.info.stage = Some(stage);
+        }
+    }
+
+    /// 列出全部任务（按登记顺序）。
+    pub(crate) fn list(&self) -> Result<Vec<DownloadTaskInfo>, ImageError> {
+        let inner = self.lock()?;
+        let mut tasks: Vec<&TaskEntry> = inner.tasks.values().collect();
+        tasks.sort_unstable_by_key(|t| t.seq);
+        Ok(tasks.into_iter().map(|t| t.info.clone()).collect())
+    }
+
+    /// 结束任务并记录最终状态，返回最终的已下载/总字节数。
+    fn finish_task(&self, request_id: &str, result: &Result<Option<DownloadCacheHit>, ImageError>) -> (u64, Option<u64>) {
+        let Ok(mut inner) = self.lock() else {
+            return (0, None);
+        };
+        let Some(task) = inner.tasks.get_mut(request_id) else {
+            return (0, None);
+        };
+        let info = &mut task.info;
+        match result {
+            Ok(cache_hit) => {
+                info.status = DownloadTaskStatus::Completed;
+                info.stage = None;
+                info.cache_hit = cache_hit.map(DownloadCacheHit::as_str);
+            }
+            Err(ImageError::Cancelled(_)) => {
+                info.status = DownloadTaskStatus::Cancelled;
+                info.stage = None;
+                info.error_code = Some("E_CANCELLED");
+            }
+            Err(err) => {
+                info.status = DownloadTaskStatus::Failed;
+                info.stage = Some(err.stage());
+                info.error_code = Some(err.code());
+                info.error_message = Some(err.to_string());
+            }
+        }
+        info.finished_at = Some(now_ms());
+        let snapshot = (info.downloaded_bytes, info.total_bytes);
+
+        inner.trim_finished();
+        drop(inner);
+        self.notify.notify_waiters();
+        snapshot
+    }
+}
+
+// ─── RAII 守卫 ─────────────────────────────────────────────────────
+
+/// 并发名额：释放时归还名额并唤醒排队中的传输。
+pub(crate) struct TransferSlot<'a> {
+    manager: &'a DownloadManager,
+    url: String,
+}
+
+impl Drop for TransferSlot<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut inner) = self.manager.inner.lock() {
+            inner.running = inner.running.saturating_sub(1);
+            if let Some(transfer) = inner.transfers.get_mut(&self.url) {
+                transfer.running = false;
+            }
+        }
+        self.manager.notify.notify_waiters();
+    }
+}
+
+/// 传输结果发布守卫：驱动方异常退出时向订阅任务发布“已中断”。
+pub(crate) struct TransferPublisher<'a> {
+    manager: &'a DownloadManager,
+    url: String,
+    published: bool,
+}
+
+impl TransferPublisher<'_> {
+    pub(crate) fn publish(mut self, result: TransferResult) {
+        self.published = true;
+        self.manager.publish(&self.url, result);
+    }
+}
+
+impl Drop for TransferPublisher<'_> {
+    fn drop(&mut self) {
+        if !self.published {
+            self.manager.publish(&self.url, Err(interrupted_error()));
+        }
+    }
+}
+
+/// 任务守卫：记录最终状态；请求提前退出（如前端页面刷新）时按取消收尾。
+pub(crate) struct DownloadTaskGuard<'a> {
+    manager: &'a DownloadManager,
+    request_id: String,
+    finished: bool,
+}
+
+impl DownloadTaskGuard<'_> {
+    /// 记录任务结果，返回最终的已下载/总字节数（用于最终进度事件）。
+    pub(crate) fn finish(mut self, result: &Result<Option<DownloadCacheHit>, ImageError>) -> (u64, Option<u64>) {
+        self.finished = true;
+        self.manager.finish_task(&self.request_id, result)
+    }
+}
+
+impl Drop for DownloadTaskGuard<'_> {
+    fn drop(&mut self) {
+        if !self.finished {
+            let _ = self.manager.cancel(&self.request_id);
+            self.manager.finish_task(&self.request_id, &Err(interrupted_error()));
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/download_manager_tests.rs"]
+mod tests;
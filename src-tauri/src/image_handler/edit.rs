@@ -0,0 +1,601 @@
+//! # 图片编辑模块
+//!
+//! ## 设计思路
+//!
+//! 截图粘贴前常需要裁剪、打码、标注或旋转。编辑以“操作列表”描述，按顺序作用于
+//! 原图的解码副本，结果编码为新的 PNG，原文件保持不变（非破坏式），
+//! 由 `db::image_edit` 另存为关联原条目的新历史条目。
+//!
+//! ## 实现思路
+//!
+//! - `ImageEditOp` 以 `op` 字段区分类型，坐标均基于**上一步操作后**的图像
+//! - 几何操作（裁剪/旋转/翻转/缩放）复用 `image` 与流水线的缩放路径；
+//!   缩放目标尺寸与复制输出选项共用校验，并受像素/内存上限约束
+//! - 区域操作（马赛克/模糊）与标注（矩形/箭头/文字）只处理与图像相交的部分，
+//!   完全落在图像之外时报错，避免“操作成功但毫无变化”
+//! - 文字标注使用内置 5×7 点阵字体按整数倍放大，仅支持可打印 ASCII
+
+use image::{imageops, DynamicImage, Rgba, RgbaImage};
+use serde::{Deserialize, Serialize};
+
+use crate::color::parse_rgb8;
+use crate::i18n::message;
+
+use super::output::{encode_png, CropRect, ImageOutputOptions};
+use super::{ImageConfig, ImageError, ImageHandler};
+
+/// 单次编辑允许的最大操作数。
+pub(crate) const MAX_EDIT_OPS: usize = 64;
+/// 标注默认颜色（醒目红）。
+const DEFAULT_ANNOTATION_COLOR: &str = "#ff3b30";
+/// 矩形/箭头默认线宽。
+const DEFAULT_STROKE_WIDTH: u32 = 4;
+/// 矩形/箭头线宽上限。
+const MAX_STROKE_WIDTH: u32 = 128;
+/// 马赛克默认色块边长。
+const DEFAULT_PIXELATE_BLOCK: u32 = 12;
+/// 马赛克色块边长范围。
+const PIXELATE_BLOCK_RANGE: std::ops::RangeInclusive<u32> = 2..=512;
+/// 模糊默认强度（高斯 sigma）。
+const DEFAULT_BLUR_SIGMA: f32 = 8.0;
+/// 模糊强度范围。
+const BLUR_SIGMA_RANGE: std::ops::RangeInclusive<f32> = 0.5..=100.0;
+/// 文字默认字号（像素高度）。
+const DEFAULT_TEXT_SIZE: u32 = 24;
+/// 文字字号范围。
+const TEXT_SIZE_RANGE: std::ops::RangeInclusive<u32> = 8..=512;
+/// 文字标注最大字符数。
+const MAX_TEXT_CHARS: usize = 256;
+
+/// 平面坐标（允许落在图像外，绘制时裁剪）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EditPoint {
+    pub x: i32,
+    pub y: i32,
+}
+
+/// 翻转方向。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FlipDirection {
+    Horizontal,
+    Vertical,
+}
+
+/// 单个编辑操作。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum ImageEditOp {
+    /// 裁剪，区域必须完全位于图像内。
+    Crop { rect: CropRect },
+    /// 顺时针旋转 90 / 180 / 270 度。
+    Rotate { degrees: u16 },
+    /// 水平或垂直翻转。
+    Flip { direction: FlipDirection },
+    /// 缩放；仅指定宽或高时按原比例计算另一边。
+    Resize { width: Option<u32>, height: Option<u32> },
+    /// 区域马赛克。
+    Pixelate { rect: CropRect, block_size: Option<u32> },
+    /// 区域高斯模糊。
+    Blur { rect: CropRect, sigma: Option<f32> },
+    /// 矩形框；`fill = true` 时填充整个区域。
+    Rectangle {
+        rect: CropRect,
+        color: Option<String>,
+        stroke_width: Option<u32>,
+        #[serde(default)]
+        fill: bool,
+    },
+    /// 从 `from` 指向 `to` 的箭头。
+    Arrow {
+        from: EditPoint,
+        to: EditPoint,
+        color: Option<String>,
+        stroke_width: Option<u32>,
+    },
+    /// 文字标注，`(x, y)` 为左上角；支持 `\n` 换行。
+    Text {
+        x: i32,
+        y: i32,
+        text: String,
+        color: Option<String>,
+        size: Option<u32>,
+    },
+}
+
+// ── 入口 ─────────────────────────────────────────────────────
+
+impl ImageHandler {
+    /// 读取本地图片，按顺序应用编辑操作并编码为 PNG。
+    pub(crate) async fn render_edited_image(
+        &self,
+        path: &str,
+        ops: Vec<ImageEditOp>,
+    ) -> Result<Vec<u8>, ImageError> {
+        validate_op_count(&ops)?;
+        let config = self.config_snapshot()?;
+        let raw = self.load_from_file(path, &config).await?;
+
+        tokio::task::spawn_blocking(move || {
+            let decoded = ImageHandler::decode_with_limits(raw, &config)?;
+            let edited = apply_edit_ops(decoded.into_rgba8(), &ops, &config)?;
+            log::info!(
+                "{}",
+                message(
+                    "image_log.edit_done",
+                    &[
+                        ("steps", &ops.len()),
+                        ("width", &edited.width()),
+                        ("height", &edited.height()),
+                    ],
+                )
+            );
+            encode_png(edited.width(), edited.height(), edited.as_raw(), None)
+        })
+        .await
+        .map_err(|e| ImageError::Decode(message("image.edit_task_failed", &[("detail", &e)])))?
+    }
+}
+
+fn validate_op_count(ops: &[ImageEditOp]) -> Result<(), ImageError> {
+    if ops.is_empty() {
+        return Err(ImageError::InvalidFormat(message("image.edit_no_ops", &[])));
+    }
+    if ops.len() > MAX_EDIT_OPS {
+        return Err(ImageError::InvalidFormat(message(
+            "image.edit_too_many_ops",
+            &[("count", &ops.len()), ("max", &MAX_EDIT_OPS)],
+        )));
+    }
+    Ok(())
+}
+
+/// 按顺序应用全部操作；参数错误附带出错的步骤序号（从 1 开始）。
+pub(crate) fn apply_edit_ops(
+    canvas: RgbaImage,
+    ops: &[ImageEditOp],
+    config: &ImageConfig,
+) -> Result<RgbaImage, ImageError> {
+    validate_op_count(ops)?;
+    let mut canvas = canvas;
+    for (index, op) in ops.iter().enumerate() {
+        canvas = apply_op(canvas, op, config).map_err(|err| match err {
+            ImageError::InvalidFormat(msg) => {
+                ImageError::InvalidFormat(message("image.edit_step_invalid", &[("step", &(index + 1)), ("detail", &msg)]))
+            }
+            other => other,
+        })?;
+    }
+    Ok(canvas)
+}
+
+fn apply_op(canvas: RgbaImage, op: &ImageEditOp, config: &ImageConfig) -> Result<RgbaImage, ImageError> {
+    let mut canvas = canvas;
+    match op {
+        ImageEditOp::Crop { rect } => {
+            let (width, height) = canvas.dimensions();
+            let fits = rect.width > 0
+                && rect.height > 0
+                && rect.x.checked_add(rect.width).is_some_and(|right| right <= width)
+                && rect.y.checked_add(rect.height).is_some_and(|bottom| bottom <= height);
+            if !fits {
+                return Err(ImageError::InvalidFormat(message(
+                    "image.crop_out_of_bounds",
+                    &[
+                        ("x", &rect.x),
+                        ("y", &rect.y),
+                        ("crop_width", &rect.width),
+                        ("crop_height", &rect.height),
+                        ("width", &width),
+                        ("height", &height),
+                    ],
+                )));
+            }
+            Ok(imageops::crop_imm(&canvas, rect.x, rect.y, rect.width, rect.height).to_image())
+        }
+        ImageEditOp::Rotate { degrees } => match degrees {
+            90 => Ok(imageops::rotate90(&canvas)),
+            180 => Ok(imageops::rotate180(&canvas)),
+            270 => Ok(imageops::rotate270(&canvas)),
+            other => Err(ImageError::InvalidFormat(message(
+                "image.unsupported_rotation",
+                &[("degrees", &other), ("options", &"90 / 180 / 270")],
+            ))),
+        },
+        ImageEditOp::Flip { direction } => Ok(match direction {
+            FlipDirection::Horizontal => imageops::flip_horizontal(&canvas),
+            FlipDirection::Vertical => imageops::flip_vertical(&canvas),
+        }),
+        ImageEditOp::Resize { width, height } => resize(canvas, *width, *height, config),
+        ImageEditOp::Pixelate { rect, block_size } => {
+            let block = block_size.unwrap_or(DEFAULT_PIXELATE_BLOCK);
+            if !PIXELATE_BLOCK_RANGE.contains(&block) {
+                return Err(ImageError::InvalidFormat(message(
+                    "image.edit_pixelate_block_out_of_range",
+                    &[("min", PIXELATE_BLOCK_RANGE.start()), ("max", PIXELATE_BLOCK_RANGE.end())],
+                )));
+            }
+            let region = clip_region(&canvas, rect)?;
+            pixelate(&mut canvas, region, block);
+            Ok(canvas)
+        }
+        ImageEditOp::Blur { rect, sigma } => {
+            let sigma = sigma.unwrap_or(DEFAULT_BLUR_SIGMA);
+            if !BLUR_SIGMA_RANGE.contains(&sigma) {
+                return Err(ImageError::InvalidFormat(message(
+                    "image.edit_blur_sigma_out_of_range",
+                    &[("min", BLUR_SIGMA_RANGE.start()), ("max", BLUR_SIGMA_RANGE.end())],
+                )));
+            }
+            let (x, y, width, height) = clip_region(&canvas, rect)?;
+            let blurred = imageops::blur(&imageops::crop_imm(&canvas, x, y, width, height).to_image(), sigma);
+            imageops::replace(&mut canvas, &blurred, x as i64, y as i64);
+            Ok(canvas)
+        }
+        ImageEditOp::Rectangle { rect, color, stroke_width, fill } => {
+            let color = parse_color(color.as_deref())?;
+            let stroke = parse_stroke(*stroke_width)?;
+            let (x0, y0) = (rect.x as i64, rect.y as i64);
+            let (x1, y1) = (x0 + rect.width as i64, y0 + rect.height as i64);
+            let painted = if *fill {
+                fill_rect(&mut canvas, (x0, y0, x1, y1), color)
+            } else {
+                let s = stroke as i64;
+                // 四条边分别绘制，线宽向矩形内侧延伸
+                let bands = [
+                    (x0, y0, x1, y0 + s),
+                    (x0, y1 - s, x1, y1),
+                    (x0, y0, x0 + s, y1),
+                    (x1 - s, y0, x1, y1),
+                ];
+                bands.into_iter().fold(false, |acc, band| fill_rect(&mut canvas, band, color) | acc)
+            };
+            ensure_painted(painted, "image.edit_rect_outside")?;
+            Ok(canvas)
+        }
+        ImageEditOp::Arrow { from, to, color, stroke_width } => {
+            let color = parse_color(color.as_deref())?;
+            let stroke = parse_stroke(*stroke_width)?;
+            if from == to {
+                return Err(ImageError::InvalidFormat(message("image.edit_arrow_degenerate", &[])));
+            }
+            let painted = draw_arrow(&mut canvas, *from, *to, stroke, color);
+            ensure_painted(painted, "image.edit_arrow_outside")?;
+            Ok(canvas)
+        }
+        ImageEditOp::Text { x, y, text, color, size } => {
+            let color = parse_color(color.as_deref())?;
+            let size = size.unwrap_or(DEFAULT_TEXT_SIZE);
+            if !TEXT_SIZE_RANGE.contains(&size) {
+                return Err(ImageError::InvalidFormat(message(
+                    "image.edit_text_size_out_of_range",
+                    &[("min", TEXT_SIZE_RANGE.start()), ("max", TEXT_SIZE_RANGE.end())],
+                )));
+            }
+            validate_text(text)?;
+            let painted = draw_text(&mut canvas, *x as i64, *y as i64, text, size, color);
+            ensure_painted(painted, "image.edit_text_outside")?;
+            Ok(canvas)
+        }
+    }
+}
+
+// ── 几何 ─────────────────────────────────────────────────────
+
+fn resize(
+    canvas: RgbaImage,
+    width: Option<u32>,
+    height: Option<u32>,
+    config: &ImageConfig,
+) -> Result<RgbaImage, ImageError> {
+    // 目标尺寸的计算与边长校验与复制输出选项保持一致
+    let options = ImageOutputOptions { width, height, ..ImageOutputOptions::default() };
+    options.validate()?;
+    let (src_width, src_height) = canvas.dimensions();
+    let Some((target_width, target_height)) = options.target_size(src_width, src_height) else {
+        return Err(ImageError::InvalidFormat(message("image.edit_resize_needs_size", &[])));
+    };
+    if (target_width, target_height) == (src_width, src_height) {
+        return Ok(canvas);
+    }
+    ImageHandler::validate_pixel_limits(config, target_width, target_height)?;
+    ImageHandler::validate_decoded_memory_limits(config, target_width, target_height)?;
+
+    let image = DynamicImage::ImageRgba8(canvas);
+    let resized = match ImageHandler::resize_with_fast_image_resize(
+        &image,
+        target_width,
+        target_height,
+        config.resize_filter,
+    ) {
+        Ok(resized) => resized,
+        Err(err) => {
+            log::warn!("{}", message("image_log.edit_resize_fallback", &[("detail", &err)]));
+            image.resize_exact(target_width, target_height, config.resize_filter)
+        }
+    };
+    Ok(resized.into_rgba8())
+}
+
+/// 计算区域与图像的交集 `(x, y, width, height)`；无交集时报错。
+fn clip_region(canvas: &RgbaImage, rect: &CropRect) -> Result<(u32, u32, u32, u32), ImageError> {
+    let (width, height) = canvas.dimensions();
+    let right = rect.x.saturating_add(rect.width).min(width);
+    let bottom = rect.y.saturating_add(rect.height).min(height);
+    if rect.x >= right || rect.y >= bottom {
+        return Err(ImageError::InvalidFormat(message(
+            "image.edit_region_outside",
+            &[
+                ("x", &rect.x),
+                ("y", &rect.y),
+                ("region_width", &rect.width),
+                ("region_height", &rect.height),
+                ("width", &width),
+                ("height", &height),
+            ],
+        )));
+    }
+    Ok((rect.x, rect.y, right - rect.x, bottom - rect.y))
+}
+
+// ── 区域处理 ─────────────────────────────────────────────────
+
+/// 将区域按 `block` 边长分块，每块填充为块内平均色。
+fn pixelate(canvas: &mut RgbaImage, region: (u32, u32, u32, u32), block: u32) {
+    let (x, y, width, height) = region;
+    for block_y in (y..y + height).step_by(block as usize) {
+        for block_x in (x..x + width).step_by(block as usize) {
+            let block_right = (block_x + block).min(x + width);
+            let block_bottom = (block_y + block).min(y + height);
+            let mut sum = [0u64; 4];
+            for py in block_y..block_bottom {
+                for px in block_x..block_right {
+                    let pixel = canvas.get_pixel(px, py);
+                    for (total, channel) in sum.iter_mut().zip(pixel.0) {
+                        *total += channel as u64;
+                    }
+                }
+            }
+            let count = ((block_right - block_x) * (block_bottom - block_y)) as u64;
+            let average = Rgba(sum.map(|total| ((total + count / 2) / count) as u8));
+            for py in block_y..block_bottom {
+                for px in block_x..block_right {
+                    canvas.put_pixel(px, py, average);
+                }
+            }
+        }
+    }
+}
+
+// ── 标注绘制 ─────────────────────────────────────────────────
+
+fn parse_color(input: Option<&str>) -> Result<Rgba<u8>, ImageError> {
+    let hex = input.unwrap_or(DEFAULT_ANNOTATION_COLOR);
+    let [r, g, b] =
+        parse_rgb8(hex).ok_or_else(|| ImageError::InvalidFormat(message("image.edit_invalid_color", &[("color", &hex)])))?;
+    Ok(Rgba([r, g, b, 255]))
+}
+
+fn parse_stroke(stroke_width: Option<u32>) -> Result<u32, ImageError> {
+    let stroke = stroke_width.unwrap_or(DEFAULT_STROKE_WIDTH);
+    if stroke == 0 || stroke > MAX_STROKE_WIDTH {
+        return Err(ImageError::InvalidFormat(message("image.edit_stroke_out_of_range", &[("max", &MAX_STROKE_WIDTH)])));
+    }
+    Ok(stroke)
+}
+
+/// 未绘制任何像素时返回 `message_id` 对应的“完全位于图片之外”错误
+fn ensure_painted(painted: bool, message_id: &str) -> Result<(), ImageError> {
+    if painted {
+        Ok(())
+    } else {
+        Err(ImageError::InvalidFormat(message(message_id, &[])))
+    }
+}
+
+/// 填充半开区间 `[x0, x1) × [y0, y1)` 与图像的交集；返回是否绘制了像素。
+fn fill_rect(canvas: &mut RgbaImage, area: (i64, i64, i64, i64), color: Rgba<u8>) -> bool {
+    let (x0, y0, x1, y1) = area;
+    let x_range = x0.max(0)..x1.min(canvas.width() as i64);
+    let y_range = y0.max(0)..y1.min(canvas.height() as i64);
+    if x_range.is_empty() || y_range.is_empty() {
+        return false;
+    }
+    for y in y_range {
+        for x in x_range.clone() {
+            canvas.put_pixel(x as u32, y as u32, color);
+        }
+    }
+    true
+}
+
+/// 绘制线宽为 `stroke` 的圆头线段；返回是否绘制了像素。
+fn draw_segment(canvas: &mut RgbaImage, from: (f64, f64), to: (f64, f64), stroke: u32, color: Rgba<u8>) -> bool {
+    let radius = stroke as f64 / 2.0;
+    let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+    let length_sq = dx * dx + dy * dy;
+
+    let left = (from.0.min(to.0) - radius).floor().max(0.0) as i64;
+    let top = (from.1.min(to.1) - radius).floor().max(0.0) as i64;
+    let right = ((from.0.max(to.0) + radius).ceil() as i64).min(canvas.width() as i64);
+    let bottom = ((from.1.max(to.1) + radius).ceil() as i64).min(canvas.height() as i64);
+
+    let mut painted = false;
+    for y in top..bottom {
+        for x in left..right {
+            let (px, py) = (x as f64 + 0.5, y as f64 + 0.5);
+            // 像素中心到线段的最近点
+            let t = if length_sq == 0.0 {
+                0.0
+            } else {
+                (((px - from.0) * dx + (py - from.1) * dy) / length_sq).clamp(0.0, 1.0)
+            };
+            let (nx, ny) = (from.0 + t * dx - px, from.1 + t * dy - py);
+            if nx * nx + ny * ny <= radius * radius {
+                canvas.put_pixel(x as u32, y as u32, color);
+                painted = true;
+            }
+        }
+    }
+    painted
+}
+
+/// 箭头 = 主干 + 终点处两条 ±30° 的箭翼。
+fn draw_arrow(canvas: &mut RgbaImage, from: EditPoint, to: EditPoint, stroke: u32, color: Rgba<u8>) -> bool {
+    let start = (from.x as f64, from.y as f64);
+    let tip = (to.x as f64, to.y as f64);
+    let (dx, dy) = (tip.0 - start.0, tip.1 - start.1);
+    let length = (dx * dx + dy * dy).sqrt();
+    let head = (stroke as f64 * 4.0).max(12.0).min(length);
+    let angle = dy.atan2(dx);
+
+    let mut painted = draw_segment(canvas, start, tip, stroke, color);
+    for wing in [angle - std::f64::consts::FRAC_PI_6, angle + std::f64::consts::FRAC_PI_6] {
+        let end = (tip.0 - head * wing.cos(), tip.1 - head * wing.sin());
+        painted |= draw_segment(canvas, tip, end, stroke, color);
+    }
+    painted
+}
+
+fn validate_text(text: &str) -> Result<(), ImageError> {
+    if text.trim().is_empty() {
+        return Err(ImageError::InvalidFormat(message("image.edit_text_empty", &[])));
+    }
+    if text.chars().count() > MAX_TEXT_CHARS {
+        return Err(ImageError::InvalidFormat(message("image.edit_text_too_long", &[("max", &MAX_TEXT_CHARS)])));
+    }
+    if let Some(unsupported) = text.chars().find(|&c| c != '\n' && !(' '..='~').contains(&c)) {
+        return Err(ImageError::InvalidFormat(message(
+            "image.edit_text_unsupported_char",
+            &[("char", &format!("{:?}", unsupported))],
+        )));
+    }
+    Ok(())
+}
+
+/// 以 5×7 点阵绘制文字：每个点放大为 `size / 8` 像素的方块，字间距 1 点、行高 8 点。
+fn draw_text(canvas: &mut RgbaImage, x: i64, y: i64, text: &str, size: u32, color: Rgba<u8>) -> bool {
+    let scale = (size / 8).max(1) as i64;
+    let mut painted = false;
+    for (line_index, line) in text.split('\n').enumerate() {
+        let line_top = y + line_index as i64 * 8 * scale;
+        for (char_index, ch) in line.chars().enumerate() {
+            let glyph = &FONT_5X7[(ch as usize) - 0x20];
+            let char_left = x + char_index as i64 * 6 * scale;
+            for (col, bits) in glyph.iter().enumerate() {
+                for row in 0..7 {
+                    if bits & (1 << row) == 0 {
+                        continue;
+                    }
+                    let left = char_left + col as i64 * scale;
+                    let top = line_top + row as i64 * scale;
+                    painted |= fill_rect(canvas, (left, top, left + scale, top + scale), color);
+                }
+            }
+        }
+    }
+    painted
+}
+
+/// ASCII 0x20..=0x7E 的 5×7 点阵，按列存储，最低位为顶行。
+const FONT_5X7: [[u8; 5]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00], // ' '
+    [0x00, 0x00, 0x5F, 0x00, 0x00], // !
+    [0x00, 0x07, 0x00, 0x07, 0x00], // "
+    [0x14, 0x7F, 0x14, 0x7F, 0x14], // #
+    [0x24, 0x2A, 0x7F, 0x2A, 0x12], // $
+    [0x23, 0x13, 0x08, 0x64, 0x62], // %
+    [0x36, 0x49, 0x55, 0x22, 0x50], // &
+    [0x00, 0x05, 0x03, 0x00, 0x00], // '
+    [0x00, 0x1C, 0x22, 0x41, 0x00], // (
+    [0x00, 0x41, 0x22, 0x1C, 0x00], // )
+    [0x08, 0x2A, 0x1C, 0x2A, 0x08], // *
+    [0x08, 0x08, 0x3E, 0x08, 0x08], // +
+    [0x00, 0x50, 0x30, 0x00, 0x00], // ,
+    [0x08, 0x08, 0x08, 0x08, 0x08], // -
+    [0x00, 0x60, 0x60, 0x00, 0x00], // .
+    [0x20, 0x10, 0x08, 0x04, 0x02], // /
+    [0x3E, 0x51, 0x49, 0x45, 0x3E], // 0
+    [0x00, 0x42, 0x7F, 0x40, 0x00], // 1
+    [0x42, 0x61, 0x51, 0x49, 0x46], // 2
+    [0x21, 0x41, 0x45, 0x4B, 0x31], // 3
+    [0x18, 0x14, 0x12, 0x7F, 0x10], // 4
+    [0x27, 0x45, 0x45, 0x45, 0x39], // 5
+    [0x3C, 0x4A, 0x49, 0x49, 0x30], // 6
+    [0x01, 0x71, 0x09, 0x05, 0x03], // 7
+    [0x36, 0x49, 0x49, 0x49, 0x36], // 8
+    [0x06, 0x49, 0x49, 0x29, 0x1E], // 9
+    [0x00, 0x36, 0x36, 0x00, 0x00], // :
+    [0x00, 0x56, 0x36, 0x00, 0x00], // ;
+    [0x08, 0x14, 0x22, 0x41, 0x00], // <
+    [0x14, 0x14, 0x14, 0x14, 0x14], // =
+    [0x00, 0x41, 0x22, 0x14, 0x08], // >
+    [0x02, 0x01, 0x51, 0x09, 0x06], // ?
+    [0x32, 0x49, 0x79, 0x41, 0x3E], // @
+    [0x7E, 0x11, 0x11, 0x11, 0x7E], // A
+    [0x7F, 0x49, 0x49, 0x49, 0x36], // B
+    [0x3E, 0x41, 0x41, 0x41, 0x22], // C
+    [0x7F, 0x41, 0x41, 0x22, 0x1C], // D
+    [0x7F, 0x49, 0x49, 0x49, 0x41], // E
+    [0x7F, 0x09, 0x09, 0x01, 0x01], // F
+    [0x3E, 0x41, 0x41, 0x51, 0x32], // G
+    [0x7F, 0x08, 0x08, 0x08, 0x7F], // H
+    [0x00, 0x41, 0x7F, 0x41, 0x00], // I
+    [0x20, 0x40, 0x41, 0x3F, 0x01], // J
+    [0x7F, 0x08, 0x14, 0x22, 0x41], // K
+    [0x7F, 0x40, 0x40, 0x40, 0x40], // L
+    [0x7F, 0x02, 0x04, 0x02, 0x7F], // M
+    [0x7F, 0x04, 0x08, 0x10, 0x7F], // N
+    [0x3E, 0x41, 0x41, 0x41, 0x3E], // O
+    [0x7F, 0x09, 0x09, 0x09, 0x06], // P
+    [0x3E, 0x41, 0x51, 0x21, 0x5E], // Q
+    [0x7F, 0x09, 0x19, 0x29, 0x46], // R
+    [0x46, 0x49, 0x49, 0x49, 0x31], // S
+    [0x01, 0x01, 0x7F, 0x01, 0x01], // T
+    [0x3F, 0x40, 0x40, 0x40, 0x3F], // U
+    [0x1F, 0x20, 0x40, 0x20, 0x1F], // V
+    [0x7F, 0x20, 0x18, 0x20, 0x7F], // W
+    [0x63, 0x14, 0x08, 0x14, 0x63], // X
+    [0x03, 0x04, 0x78, 0x04, 0x03], // Y
+    [0x61, 0x51, 0x49, 0x45, 0x43], // Z
+    [0x00, 0x7F, 0x41, 0x41, 0x00], // [
+    [0x02, 0x04, 0x08, 0x10, 0x20], // \
+    [0x00, 0x41, 0x41, 0x7F, 0x00], // ]
+    [0x04, 0x02, 0x01, 0x02, 0x04], // ^
+    [0x40, 0x40, 0x40, 0x40, 0x40], // _
+    [0x00, 0x01, 0x02, 0x04, 0x00], // `
+    [0x20, 0x54, 0x54, 0x54, 0x78], // a
+    [0x7F, 0x48, 0x44, 0x44, 0x38], // b
+    [0x38, 0x44, 0x44, 0x44, 0x20], // c
+    [0x38, 0x44, 0x44, 0x48, 0x7F], // d
+    [0x38, 0x54, 0x54, 0x54, 0x18], // e
+    [0x08, 0x7E, 0x09, 0x01, 0x02], // f
+    [0x08, 0x54, 0x54, 0x54, 0x3C], // g
+    [0x7F, 0x08, 0x04, 0x04, 0x78], // h
+    [0x00, 0x44, 0x7D, 0x40, 0x00], // i
+    [0x20, 0x40, 0x44, 0x3D, 0x00], // j
+    [0x7F, 0x10, 0x28, 0x44, 0x00], // k
+    [0x00, 0x41, 0x7F, 0x40, 0x00], // l
+    [0x7C, 0x04, 0x18, 0x04, 0x78], // m
+    [0x7C, 0x08, 0x04, 0x04, 0x78], // n
+    [0x38, 0x44, 0x44, 0x44, 0x38], // o
+    [0x7C, 0x14, 0x14, 0x14, 0x08], // p
+    [0x08, 0x14, 0x14, 0x18, 0x7C], // q
+    [0x7C, 0x08, 0x04, 0x04, 0x08], // r
+    [0x48, 0x54, 0x54, 0x54, 0x20], // s
+    [0x04, 0x3F, 0x44, 0x40, 0x20], // t
+    [0x3C, 0x40, 0x40, 0x20, 0x7C], // u
+    [0x1C, 0x20, 0x40, 0x20, 0x1C], // v
+    [0x3C, 0x40, 0x30, 0x40, 0x3C], // w
+    [0x44, 0x28, 0x10, 0x28, 0x44], // x
+    [0x0C, 0x50, 0x50, 0x50, 0x3C], // y
+    [0x44, 0x64, 0x54, 0x4C, 0x44], // z
+    [0x00, 0x08, 0x36, 0x41, 0x00], // {
+    [0x00, 0x00, 0x7F, 0x00, 0x00], // |
+    [0x00, 0x41, 0x36, 0x08, 0x00], // }
+    [0x08, 0x04, 0x08, 0x10, 0x08], // ~
+];
+
+#[cfg(test)]
+#[path = "tests/edit_tests.rs"]
+mod tests;
@@ -3,38 +3,43 @@
 //! ## 设计思路
 //!
 //! 使用单一错误枚举承载图片链路中的所有错误来源，避免字符串拼接式错误处理。
-//! 通过 `thiserror` 保持人类可读错误，同时让调用侧可按分支匹配。
+//! 通过 `thiserror` 保持人类可读错误（文案经 `i18n` 本地化），同时让调用侧可按分支匹配。
+
+use crate::i18n::message;
 
 /// 图片处理统一错误类型。
 ///
 /// 该类型会在命令层被上转为 `AppError`，最终透传给前端。
-#[derive(Debug, thiserror::Error)]
+#[derive(Debug, Clone, thiserror::Error)]
 pub enum ImageError {
-    #[error("网络错误：{0}")]
+    #[error("{}", message("image_error.network", &[("detail", .0)]))]
     Network(String),
 
-    #[error("解码错误：{0}")]
+    #[error("{}", message("image_error.decode", &[("detail", .0)]))]
     Decode(String),
 
-    #[error("格式错误：{0}")]
+    #[error("{}", message("image_error.invalid_format", &[("detail", .0)]))]
     InvalidFormat(String),
 
-    #[error("剪贴板错误：{0}")]
+    #[error("{}", message("image_error.unsupported_format", &[("detail", .0)]))]
+    UnsupportedFormat(String),
+
+    #[error("{}", message("image_error.clipboard", &[("detail", .0)]))]
     Clipboard(String),
 
-    #[error("剪贴板占用：{0}")]
+    #[error("{}", message("image_error.clipboard_busy", &[("detail", .0)]))]
     ClipboardBusy(String),
 
-    #[error("文件错误：{0}")]
+    #[error("{}", message("image_error.file_system", &[("detail", .0)]))]
     FileSystem(String),
 
-    #[error("超时错误：{0}")]
+    #[error("{}", message("image_error.timeout", &[("detail", .0)]))]
     Timeout(String),
 
-    #[error("操作已取消：{0}")]
+    #[error("{}", message("image_error.cancelled", &[("detail", .0)]))]
     Cancelled(String),
 
-    #[error("资源限制：{0}")]
+    #[error("{}", message("image_error.resource_limit", &[("detail", .0)]))]
     ResourceLimit(String),
 }
 
@@ -44,6 +49,7 @@ impl ImageError {
             ImageError::Network(_) => "E_NET_REQUEST",
             ImageError::Timeout(_) => "E_NET_TIMEOUT",
             ImageError::InvalidFormat(_) => "E_FORMAT_INVALID",
+            ImageError::UnsupportedFormat(_) => "E_FORMAT_UNSUPPORTED",
             ImageError::Decode(_) => "E_DECODE_FAILED",
             ImageError::Clipboard(_) => "E_CLIPBOARD_WRITE",
             ImageError::ClipboardBusy(_) => "E_CLIPBOARD_BUSY",
@@ -56,7 +62,7 @@ impl ImageError {
     pub fn stage(&self) -> &'static str {
         match self {
             ImageError::Network(_) | ImageError::Timeout(_) => "download",
-            ImageError::InvalidFormat(_) => "format",
+            ImageError::InvalidFormat(_) | ImageError::UnsupportedFormat(_) => "format",
             ImageError::Decode(_) => "decode",
             ImageError::Clipboard(_) | ImageError::ClipboardBusy(_) => "clipboard",
             ImageError::ResourceLimit(_) => "resource",
@@ -64,6 +70,22 @@ impl ImageError {
             ImageError::FileSystem(_) => "unknown",
         }
     }
+
+    /// 底层错误原文（不含本地化前缀）
+    pub fn detail(&self) -> &str {
+        match self {
+            ImageError::Network(detail)
+            | ImageError::Decode(detail)
+            | ImageError::InvalidFormat(detail)
+            | ImageError::UnsupportedFormat(detail)
+            | ImageError::Clipboard(detail)
+            | ImageError::ClipboardBusy(detail)
+            | ImageError::FileSystem(detail)
+            | ImageError::Timeout(detail)
+            | ImageError::Cancelled(detail)
+            | ImageError::ResourceLimit(detail) => detail,
+        }
+    }
 }
 
 impl From<ImageError> for String {
@@ -6,7 +6,7 @@
 //! 处理链路固定为：
 //! 1. 读取配置快照
 //! 2. 按来源加载原始字节
-//! 3. 解码并准备 RGBA 数据
+//! 3. 解码（SVG 为栅格化）并准备 RGBA 数据（应用输出选项）
 //! 4. 写入剪贴板（含重试）
 //!
 //! ## 实现思路
@@ -20,11 +20,19 @@ use std::collections::HashMap;
 use std::sync::{Arc, Mutex, RwLock};
 use std::time::Instant;
 
-use super::{ImageAdvancedConfig, ImageConfig, ImageError, ImagePerformanceProfile, ImageSource};
+use crate::i18n::message;
+
+use super::download_cache::{self, DownloadCacheHit, DownloadCacheInfo};
+use super::source::RawImageData;
+use super::{
+    ImageAdvancedConfig, ImageConfig, ImageError, ImageOutputOptions, ImagePerformanceProfile, ImageSource,
+};
 
 /// 图片处理器。
 ///
 /// 封装了配置状态与 HTTP 客户端，并编排各子模块实现完整流程。
+/// 内部状态均为共享引用，`clone` 得到的是同一处理器的句柄（供后台下载任务持有）。
+#[derive(Clone)]
 pub struct ImageHandler {
     pub(super) config: Arc<RwLock<ImageConfig>>,
     pub(super) download_cache: Arc<Mutex<HashMap<String, CachedUrlDownload>>>,
@@ -62,7 +70,7 @@ impl ImageHandler {
         self.config
             .read()
             .map(|cfg| cfg.clone())
-            .map_err(|_| ImageError::ResourceLimit("配置读取锁已中毒".to_string()))
+            .map_err(|_| ImageError::ResourceLimit(message("image.config_read_lock_poisoned", &[])))
     }
 
     /// 设置性能档位。
@@ -79,16 +87,21 @@ impl ImageHandler {
         let mut config = self
             .config
             .write()
-            .map_err(|_| ImageError::ResourceLimit("配置写入锁已中毒".to_string()))?;
+            .map_err(|_| ImageError::ResourceLimit(message("image.config_write_lock_poisoned", &[])))?;
         config.apply_performance_profile(profile);
 
         log::info!(
-            "⚙️ 已切换图片性能档位：{:?}（adaptive_resize={}, target_pixels={}, max_dim={}, filter={:?}）",
-            profile,
-            config.adaptive_resize,
-            config.clipboard_target_pixels,
-            config.clipboard_max_dimension,
-            config.resize_filter
+            "{}",
+            message(
+                "image_log.profile_switched",
+                &[
+                    ("profile", &format!("{:?}", profile)),
+                    ("adaptive_resize", &config.adaptive_resize),
+                    ("target_pixels", &config.clipboard_target_pixels),
+                    ("max_dim", &config.clipboard_max_dimension),
+                    ("filter", &format!("{:?}", config.resize_filter)),
+                ],
+            )
         );
 
         Ok(())
@@ -108,7 +121,7 @@ impl ImageHandler {
         let config = self
             .config
             .read()
-            .map_err(|_| ImageError::ResourceLimit("配置读取锁已中毒".to_string()))?;
+            .map_err(|_| ImageError::ResourceLimit(message("image.config_read_lock_poisoned", &[])))?;
         Ok(config.infer_performance_profile())
     }
 
@@ -118,7 +131,7 @@ impl ImageHandler {
         let mut config = self
             .config
             .write()
-            .map_err(|_| ImageError::ResourceLimit("配置写入锁已中毒".to_string()))?;
+            .map_err(|_| ImageError::ResourceLimit(message("image.config_write_lock_poisoned", &[])))?;
         advanced.apply_to(&mut config);
         Ok(())
     }
@@ -128,43 +141,55 @@ impl ImageHandler {
         let config = self
             .config
             .read()
-            .map_err(|_| ImageError::ResourceLimit("配置读取锁已中毒".to_string()))?;
+            .map_err(|_| ImageError::ResourceLimit(message("image.config_read_lock_poisoned", &[])))?;
         Ok(ImageAdvancedConfig::from_full(&config))
     }
 
-    /// 处理主入口：从任意来源加载并复制图片。
+    /// 处理主入口：从任意来源加载图片，按输出选项处理后复制。
     ///
     /// # 示例
     /// ```rust,ignore
-    /// use clipboard_history::image_handler::{ImageConfig, ImageHandler, ImageSource};
+    /// use clipboard_history::image_handler::{ImageConfig, ImageHandler, ImageOutputOptions, ImageSource};
     ///
     /// # async fn demo() -> Result<(), clipboard_history::image_handler::ImageError> {
     /// let handler = ImageHandler::new(ImageConfig::default())?;
     /// handler
-    ///     .process_and_copy(ImageSource::FilePath("C:/tmp/test.png".into()))
+    ///     .process_and_copy(ImageSource::FilePath("C:/tmp/test.png".into()), ImageOutputOptions::default())
     ///     .await?;
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn process_and_copy(&self, source: ImageSource) -> Result<(), ImageError> {
+    pub async fn process_and_copy(
+        &self,
+        source: ImageSource,
+        options: ImageOutputOptions,
+    ) -> Result<(), ImageError> {
+        // 选项非法时在加载前拒绝，避免无谓的下载与解码
+        options.validate()?;
         let config = self.config_snapshot()?;
         let total_start = Instant::now();
 
         let load_start = Instant::now();
+        let is_svg = matches!(source, ImageSource::Svg(_));
         let raw = match source {
             ImageSource::Url(url) => self.load_from_url(&url, &config).await?,
             ImageSource::Base64(data) => self.load_from_base64(&data, &config)?,
             ImageSource::FilePath(path) => self.load_from_file(&path, &config).await?,
+            ImageSource::Svg(path) => self.load_svg_from_file(&path, &config).await?,
         };
         let load_elapsed = load_start.elapsed();
 
         let decode_start = Instant::now();
         let config_for_decode = config.clone();
         let prepared = tokio::task::spawn_blocking(move || {
-            Self::decode_and_prepare_for_clipboard(raw, &config_for_decode)
+            if is_svg {
+                Self::rasterize_svg_for_clipboard(raw, &config_for_decode, &options)
+            } else {
+                Self::decode_and_prepare_for_clipboard(raw, &config_for_decode, &options)
+            }
         })
         .await
-        .map_err(|e| ImageError::Decode(format!("解码任务调度失败：{}", e)))??;
+        .map_err(|e| ImageError::Decode(message("image.decode_task_failed", &[("detail", &e)])))??;
         let decode_elapsed = decode_start.elapsed();
 
         let copy_start = Instant::now();
@@ -173,59 +198,125 @@ impl ImageHandler {
 
         let total_elapsed = total_start.elapsed();
         log::info!(
-            "✅ 图片处理完成 - load={}ms decode={}ms copy={}ms total={}ms",
-            load_elapsed.as_millis(),
-            decode_elapsed.as_millis(),
-            copy_elapsed.as_millis(),
-            total_elapsed.as_millis()
+            "{}",
+            message(
+                "image_log.process_done",
+                &[
+                    ("load", &load_elapsed.as_millis()),
+                    ("decode", &decode_elapsed.as_millis()),
+                    ("copy", &copy_elapsed.as_millis()),
+                    ("total", &total_elapsed.as_millis()),
+                ],
+            )
         );
 
         Ok(())
     }
 
-    pub async fn process_url_and_copy_with_progress<P, C>(
+    /// URL 下载阶段：带进度与取消回调拉取原始字节，返回命中的缓存层级（未命中为 `None`）。
+    ///
+    /// 由下载管理器驱动，合并到同一传输的任务共享返回的字节。
+    pub(crate) async fn download_url<P, C>(
         &self,
         url: &str,
+        config: &ImageConfig,
         on_progress: P,
         is_cancelled: C,
-    ) -> Result<(), ImageError>
+    ) -> Result<(Bytes, Option<DownloadCacheHit>), ImageError>
     where
         P: Fn(u64, Option<u64>) + Send + Sync,
         C: Fn() -> bool + Send + Sync,
     {
-        let config = self.config_snapshot()?;
-        let total_start = Instant::now();
-
         let load_start = Instant::now();
-        let raw = self
-            .load_from_url_with_hooks(url, &config, &on_progress, &is_cancelled)
+        let (raw, cache_hit) = self
+            .load_from_url_with_hooks(url, config, &on_progress, &is_cancelled)
             .await?;
-        let load_elapsed = load_start.elapsed();
+        log::info!(
+            "{}",
+            message(
+                "image_log.url_downloaded",
+                &[
+                    ("load", &load_start.elapsed().as_millis()),
+                    ("size", &raw.bytes.len()),
+                    ("cache", &cache_hit.map(DownloadCacheHit::as_str).unwrap_or("miss")),
+                ],
+            )
+        );
+        Ok((raw.bytes, cache_hit))
+    }
+
+    /// URL 处理阶段：解码已下载的字节，按输出选项处理后写入剪贴板。
+    pub(crate) async fn copy_downloaded_url(
+        &self,
+        bytes: Bytes,
+        options: ImageOutputOptions,
+        config: &ImageConfig,
+    ) -> Result<(), ImageError> {
+        options.validate()?;
+        let raw = RawImageData { bytes, source_hint: "url" };
 
         let decode_start = Instant::now();
         let config_for_decode = config.clone();
         let prepared = tokio::task::spawn_blocking(move || {
-            Self::decode_and_prepare_for_clipboard(raw, &config_for_decode)
+            Self::decode_and_prepare_for_clipboard(raw, &config_for_decode, &options)
         })
         .await
-        .map_err(|e| ImageError::Decode(format!("解码任务调度失败：{}", e)))??;
+        .map_err(|e| ImageError::Decode(message("image.decode_task_failed", &[("detail", &e)])))??;
         let decode_elapsed = decode_start.elapsed();
 
         let copy_start = Instant::now();
-        self.copy_to_clipboard_with_retry(prepared, &config).await?;
-        let copy_elapsed = copy_start.elapsed();
+        self.copy_to_clipboard_with_retry(prepared, config).await?;
 
-        let total_elapsed = total_start.elapsed();
         log::info!(
-            "✅ URL 图片处理完成 - load={}ms decode={}ms copy={}ms total={}ms",
-            load_elapsed.as_millis(),
-            decode_elapsed.as_millis(),
-            copy_elapsed.as_millis(),
-            total_elapsed.as_millis()
+            "{}",
+            message(
+                "image_log.url_process_done",
+                &[
+                    ("decode", &decode_elapsed.as_millis()),
+                    ("copy", &copy_start.elapsed().as_millis()),
+                ],
+            )
         );
-
         Ok(())
     }
+
+    /// 将已下载的 URL 图片整理为可落盘的编码（保存到历史用），返回字节与扩展名。
+    pub(crate) async fn prepare_downloaded_for_storage(
+        &self,
+        bytes: Bytes,
+        config: &ImageConfig,
+    ) -> Result<(Vec<u8>, &'static str), ImageError> {
+        let raw = RawImageData { bytes, source_hint: "url" };
+        let config = config.clone();
+        tokio::task::spawn_blocking(move || Self::encode_for_storage(raw, &config))
+            .await
+            .map_err(|e| ImageError::Decode(message("image.encode_task_failed", &[("detail", &e)])))?
+    }
+
+    /// 查询 URL 下载磁盘缓存的占用情况（缓存目录未初始化时为空）。
+    pub(crate) async fn download_cache_info(&self) -> Result<DownloadCacheInfo, ImageError> {
+        let max_bytes = self.config_snapshot()?.download_cache_max_bytes;
+        let Some(cache_dir) = download_cache::download_cache_dir() else {
+            return Ok(DownloadCacheInfo { max_bytes, ..DownloadCacheInfo::default() });
+        };
+        tokio::task::spawn_blocking(move || download_cache::cache_info(cache_dir, max_bytes))
+            .await
+            .map_err(|e| ImageError::FileSystem(message("image.cache_info_task_failed", &[("detail", &e)])))
+    }
+
+    /// 清空 URL 下载缓存（内存与磁盘），返回清理后的占用情况。
+    pub(crate) async fn clear_download_cache(&self) -> Result<DownloadCacheInfo, ImageError> {
+        if let Ok(mut cache) = self.download_cache.lock() {
+            cache.clear();
+        }
+        if let Some(cache_dir) = download_cache::download_cache_dir() {
+            tokio::task::spawn_blocking(move || download_cache::clear(cache_dir))
+                .await
+                .map_err(|e| ImageError::FileSystem(message("image.cache_clear_task_failed", &[("detail", &e)])))??;
+            log::info!("{}", message("image_log.cache_cleared", &[]));
+        }
+        self.download_cache_info().await
+    }
 }
 
 #[cfg(test)]
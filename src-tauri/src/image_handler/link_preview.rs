@@ -0,0 +1,332 @@
+//! # 链接预览模块
+//!
+//! ## 设计思路
+//!
+//! 复制链接后，用户更关心“这是什么页面”。本模块下载网页 `<head>`，
+//! 提取标题、描述、站点图标与 `og:image`，供列表展示链接卡片。
+//!
+//! 网页请求与图片下载面对同样的 SSRF 风险，因此完全复用 `loader` 的安全链路：
+//! 每一跳都经过 `validate_url_and_build_clients`（协议 + 主机 + DNS 绑定），
+//! 重定向目标再经 `validate_url_safety` 校验，超时与重定向上限取自同一份 `ImageConfig`，
+//! 域名规则（代理、请求头、Cookie 等）同样生效。
+//!
+//! ## 实现思路
+//!
+//! - 只读取到 `</head>` 或 `LINK_PREVIEW_MAX_HEAD_BYTES` 为止，不下载正文。
+//! - 解析使用正则提取 `<meta>` / `<link>` / `<title>` / `<base>`，
+//!   属性顺序与引号风格不敏感；文本统一做实体反转义与空白折叠。
+//! - 相对地址按 `<base href>`（若有）或最终页面地址解析为绝对地址，仅保留 HTTP/HTTPS。
+//! - 页面未声明图标时回退到站点根目录的 `/favicon.ico`。
+
+use std::time::Duration;
+
+use bytes::BytesMut;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::message;
+
+use super::{ImageConfig, ImageError, ImageHandler};
+
+/// 最多读取的页面字节数（多数站点的 `<head>` 远小于此值）
+const LINK_PREVIEW_MAX_HEAD_BYTES: usize = 512 * 1024;
+const TITLE_MAX_CHARS: usize = 300;
+const DESCRIPTION_MAX_CHARS: usize = 1000;
+const HEAD_CLOSE_TAG: &[u8] = b"</head>";
+
+const TITLE_META_KEYS: &[&str] = &["og:title", "twitter:title"];
+const DESCRIPTION_META_KEYS: &[&str] = &["og:description", "description", "twitter:description"];
+const IMAGE_META_KEYS: &[&str] = &[
+    "og:image",
+    "og:image:url",
+    "og:image:secure_url",
+    "twitter:image",
+    "twitter:image:src",
+];
+
+static META_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?is)<meta\b([^>]*)>").unwrap());
+static LINK_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?is)<link\b([^>]*)>").unwrap());
+static BASE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?is)<base\b([^>]*)>").unwrap());
+static TITLE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?is)<title\b[^>]*>(.*?)</title\s*>").unwrap());
+static ATTR_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?s)([a-zA-Z_:][-a-zA-Z0-9_:.]*)\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'=<>`]+))"#).unwrap()
+});
+
+/// 链接预览元数据。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LinkPreview {
+    /// 跟随重定向后的最终页面地址
+    pub url: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    /// 站点图标绝对地址
+    pub favicon: Option<String>,
+    /// `og:image` / `twitter:image` 绝对地址
+    pub image: Option<String>,
+    /// 抓取时间（Unix 毫秒）
+    pub fetched_at: i64,
+}
+
+impl ImageHandler {
+    /// 抓取网页头部并提取链接预览。
+    pub(super) async fn fetch_link_preview(&self, url: &str) -> Result<LinkPreview, ImageError> {
+        log::info!(
+            "{}",
+            message("image_log.link_preview_start", &[("url", &Self::redact_url_for_log(url))])
+        );
+
+        let config = self.config_snapshot()?;
+        let (page_url, head) = self.fetch_page_head(url, &config).await?;
+        let mut preview = parse_link_preview(&head, &page_url);
+        preview.fetched_at = chrono::Utc::now().timestamp_millis();
+        Ok(preview)
+    }
+
+    /// 按图片下载相同的安全策略请求网页，返回最终地址与 `<head>` 部分的文本。
+    async fn fetch_page_head(
+        &self,
+        url: &str,
+        config: &ImageConfig,
+    ) -> Result<(reqwest::Url, String), ImageError> {
+        let mut current_url = reqwest::Url::parse(url)
+            .map_err(|e| ImageError::InvalidFormat(message("image.invalid_url", &[("detail", &e)])))?;
+
+        for redirect_count in 0..=config.max_redirects {
+            let clients = Self::validate_url_and_build_clients(&current_url, config).await?;
+            let response = self.send_page_request(&clients, &current_url, config).await?;
+
+            if response.status().is_redirection() {
+                if redirect_count >= config.max_redirects {
+                    return Err(ImageError::Network(message(
+                        "image.too_many_redirects",
+                        &[("limit", &config.max_redirects)],
+                    )));
+                }
+
+                let location = response
+                    .headers()
+                    .get(reqwest::header::LOCATION)
+                    .ok_or_else(|| ImageError::Network(message("image.redirect_missing_location", &[])))?
+                    .to_str()
+                    .map_err(|e| ImageError::InvalidFormat(message("image.invalid_redirect_location", &[("detail", &e)])))?;
+                let next_url = current_url
+                    .join(location)
+                    .map_err(|e| ImageError::InvalidFormat(message("image.invalid_redirect_url", &[("detail", &e)])))?;
+
+                Self::validate_url_safety(next_url.as_str(), config).await?;
+                log::debug!(
+                    "{}",
+                    message(
+                        "image_log.redirect",
+                        &[("url", &Self::redact_url_for_log(next_url.as_str()))],
+                    )
+                );
+                current_url = next_url;
+                continue;
+            }
+
+            if !response.status().is_success() {
+                return Err(Self::http_status_error(response.status().as_u16()));
+            }
+
+            let content_type = response
+                .headers()
+                .get(reqwest::header::CONTENT_TYPE)
+                .and_then(|ct| ct.to_str().ok());
+            if let Some(content_type) = content_type.filter(|ct| !is_html_content_type(ct)) {
+                return Err(ImageError::InvalidFormat(message("image.not_html_content_type", &[("content_type", &content_type)])));
+            }
+
+            let head = Self::read_page_head(response, config).await?;
+            return Ok((current_url, head));
+        }
+
+        Err(ImageError::Network(message("image.download_ended_unexpectedly", &[])))
+    }
+
+    /// 依次尝试 DNS 绑定的各个客户端，连接类错误时换下一个地址。
+    async fn send_page_request(
+        &self,
+        clients: &[reqwest::Client],
+        url: &reqwest::Url,
+        config: &ImageConfig,
+    ) -> Result<reqwest::Response, ImageError> {
+        let mut last_err = None;
+        for client in clients {
+            let result = client
+                .get(url.clone())
+                .header(reqwest::header::ACCEPT, "text/html,application/xhtml+xml;q=0.9,*/*;q=0.5")
+                .send()
+                .await;
+            match result {
+                Ok(response) => return Ok(response),
+                Err(err) if Self::is_retryable_network_error(&err) => last_err = Some(err),
+                Err(err) => return Err(self.map_reqwest_error(err, url.as_str(), config)),
+            }
+        }
+
+        match last_err {
+            Some(err) => Err(self.map_reqwest_error(err, url.as_str(), config)),
+            None => Err(ImageError::Network(message("image.no_http_client", &[]))),
+        }
+    }
+
+    /// 流式读取页面，遇到 `</head>` 或达到字节上限即停止。
+    async fn read_page_head(
+        mut response: reqwest::Response,
+        config: &ImageConfig,
+    ) -> Result<String, ImageError> {
+        let mut buffer = BytesMut::new();
+        let mut received_first_chunk = false;
+
+        while buffer.len() < LINK_PREVIEW_MAX_HEAD_BYTES {
+            let read_timeout = if received_first_chunk {
+                Duration::from_millis(config.stream_chunk_timeout_ms)
+            } else {
+                Duration::from_millis(config.stream_first_byte_timeout_ms)
+            };
+            let chunk = tokio::time::timeout(read_timeout, response.chunk())
+                .await
+                .map_err(|_| ImageError::Timeout(message("image.page_read_timeout", &[])))?
+                .map_err(|e| ImageError::Network(message("image.page_read_failed", &[("detail", &e)])))?;
+            let Some(chunk) = chunk else {
+                break;
+            };
+            received_first_chunk = true;
+
+            // 只需在新数据及其前方可能跨块的几个字节中查找 `</head>`
+            let search_from = buffer.len().saturating_sub(HEAD_CLOSE_TAG.len());
+            buffer.extend_from_slice(&chunk);
+            if contains_head_close(&buffer[search_from..]) {
+                break;
+            }
+        }
+
+        buffer.truncate(LINK_PREVIEW_MAX_HEAD_BYTES);
+        Ok(String::from_utf8_lossy(&buffer).into_owned())
+    }
+}
+
+fn is_html_content_type(content_type: &str) -> bool {
+    let base = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    base == "text/html" || base == "application/xhtml+xml"
+}
+
+fn contains_head_close(bytes: &[u8]) -> bool {
+    bytes
+        .windows(HEAD_CLOSE_TAG.len())
+        .any(|window| window.eq_ignore_ascii_case(HEAD_CLOSE_TAG))
+}
+
+// ─── HTML 解析 ──────────────────────────────────────────────────────
+
+/// 从页面 HTML 中提取预览信息（`fetched_at` 由调用方填写）。
+pub(super) fn parse_link_preview(html: &str, page_url: &reqwest::Url) -> LinkPreview {
+    let head = match html.to_ascii_lowercase().find("</head") {
+        Some(end) => &html[..end],
+        None => html,
+    };
+
+    let base_url = BASE_RE
+        .captures(head)
+        .and_then(|caps| attr_value(&caps[1], "href"))
+        .and_then(|href| page_url.join(&href).ok())
+        .unwrap_or_else(|| page_url.clone());
+
+    let metas: Vec<(String, String)> = META_RE
+        .captures_iter(head)
+        .filter_map(|caps| {
+            let attrs = &caps[1];
+            let key = attr_value(attrs, "property").or_else(|| attr_value(attrs, "name"))?;
+            let content = attr_value(attrs, "content")?;
+            Some((key.to_ascii_lowercase(), content))
+        })
+        .collect();
+    let meta = |keys: &[&str]| -> Option<String> {
+        keys.iter().find_map(|key| {
+            metas
+                .iter()
+                .find(|(name, content)| name == key && !content.trim().is_empty())
+                .map(|(_, content)| content.clone())
+        })
+    };
+
+    let title = meta(TITLE_META_KEYS)
+        .or_else(|| TITLE_RE.captures(head).map(|caps| caps[1].to_string()))
+        .and_then(|raw| clean_text(&raw, TITLE_MAX_CHARS));
+    let description = meta(DESCRIPTION_META_KEYS).and_then(|raw| clean_text(&raw, DESCRIPTION_MAX_CHARS));
+    let image = meta(IMAGE_META_KEYS).and_then(|raw| resolve_http_url(&base_url, &raw));
+    let favicon = find_favicon(head, &base_url).or_else(|| {
+        page_url
+            .join("/favicon.ico")
+            .ok()
+            .map(|url| url.to_string())
+    });
+
+    LinkPreview {
+        url: page_url.to_string(),
+        title,
+        description,
+        favicon,
+        image,
+        fetched_at: 0,
+    }
+}
+
+/// 选出 `rel` 含 `icon` 的 `<link>`；普通图标优先于 Apple 触控图标。
+fn find_favicon(head: &str, base_url: &reqwest::Url) -> Option<String> {
+    LINK_RE
+        .captures_iter(head)
+        .filter_map(|caps| {
+            let attrs = &caps[1];
+            let rel = attr_value(attrs, "rel")?.to_ascii_lowercase();
+            let rank = rel.split_ascii_whitespace().find_map(|token| match token {
+                "icon" => Some(0),
+                "apple-touch-icon" | "apple-touch-icon-precomposed" => Some(1),
+                _ => None,
+            })?;
+            let href = resolve_http_url(base_url, &attr_value(attrs, "href")?)?;
+            Some((rank, href))
+        })
+        .min_by_key(|(rank, _)| *rank)
+        .map(|(_, href)| href)
+}
+
+/// 读取标签属性值（属性名大小写不敏感），并做实体反转义。
+fn attr_value(attrs: &str, name: &str) -> Option<String> {
+    ATTR_RE.captures_iter(attrs).find_map(|caps| {
+        if !caps[1].eq_ignore_ascii_case(name) {
+            return None;
+        }
+        let raw = caps.get(2).or_else(|| caps.get(3)).or_else(|| caps.get(4))?.as_str();
+        Some(unescape(raw))
+    })
+}
+
+fn resolve_http_url(base_url: &reqwest::Url, href: &str) -> Option<String> {
+    let resolved = base_url.join(href.trim()).ok()?;
+    matches!(resolved.scheme(), "http" | "https").then(|| resolved.to_string())
+}
+
+/// 反转义、折叠空白并按字符数截断；结果为空时返回 `None`。
+fn clean_text(raw: &str, max_chars: usize) -> Option<String> {
+    let text = unescape(raw).split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.is_empty() {
+        return None;
+    }
+    Some(text.chars().take(max_chars).collect())
+}
+
+fn unescape(raw: &str) -> String {
+    crate::transform::html_unescape(raw).unwrap_or_else(|_| raw.to_string())
+}
+
+#[cfg(test)]
+#[path = "tests/link_preview_tests.rs"]
+mod tests;
@@ -7,10 +7,14 @@
 //!
 //! ## 实现思路
 //!
-//! - URL：协议 + 主机安全 + 内容类型 + 体积校验 + 流式下载。
+//! - URL：协议 + 主机安全 + 内容类型 + 体积校验 + 流式下载；命中域名规则时
+//!   按规则设置代理、请求头、超时与内网访问开关。
 //! - Base64：格式解析 + 解码后体积限制。
 //! - 文件：存在性 + metadata 体积限制 + 读取。
 //! - 网络错误统一映射到 `ImageError`，便于上层处理。
+//! - URL 下载先查短期内存缓存，再查 `download_cache` 磁盘缓存；磁盘副本过期且带校验器时
+//!   发起条件请求，304 时复用本地字节；仅在传输失败、超时或 5xx 时回退到过期副本，
+//!   403 / 404 / 410 等明确的拒绝或删除不会被旧副本掩盖。
 
 use base64::{Engine as _, engine::general_purpose};
 use bytes::Bytes;
@@ -20,9 +24,13 @@ use std::time::{SystemTime, UNIX_EPOCH};
 use std::time::Duration;
 use tokio::net::lookup_host;
 
+use crate::i18n::message;
+
+use super::codecs;
+use super::download_cache::{self, CachePolicy, CachedDownload, DownloadCacheHit};
 use super::handler::CachedUrlDownload;
 use super::source::RawImageData;
-use super::{ImageConfig, ImageError, ImageHandler};
+use super::{ImageConfig, ImageDomainRule, ImageError, ImageHandler};
 
 const STREAM_SIGNATURE_PROBE_BYTES: usize = 4096;
 const NETWORK_RETRY_MAX_ATTEMPTS: u8 = 3;
@@ -30,6 +38,15 @@ const NETWORK_RETRY_BASE_DELAY_MS: u64 = 180;
 const BUFFER_INITIAL_CAPACITY: usize = 16 * 1024;
 const DOWNLOAD_CACHE_TTL_SECS: u64 = 25;
 const DOWNLOAD_CACHE_MAX_ENTRIES: usize = 24;
+const DEFAULT_USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0.0.0 Safari/537.36";
+
+/// 单个 URL 的下载结果。
+enum DownloadOutcome {
+    /// 完整响应体及其缓存策略。
+    Body(Bytes, CachePolicy),
+    /// 条件请求命中（304），附带响应中的新缓存策略。
+    NotModified(CachePolicy),
+}
 
 impl ImageHandler {
     /// 从 URL 加载图片原始字节。
@@ -38,7 +55,8 @@ impl ImageHandler {
         url: &str,
         config: &ImageConfig,
     ) -> Result<RawImageData, ImageError> {
-        self.load_from_url_with_hooks(url, config, |_, _| {}, || false).await
+        let (raw, _) = self.load_from_url_with_hooks(url, config, |_, _| {}, || false).await?;
+        Ok(raw)
     }
 
     pub(super) async fn load_from_url_with_hooks<P, C>(
@@ -47,24 +65,30 @@ impl ImageHandler {
         config: &ImageConfig,
         on_progress: P,
         is_cancelled: C,
-    ) -> Result<RawImageData, ImageError>
+    ) -> Result<(RawImageData, Option<DownloadCacheHit>), ImageError>
     where
         P: Fn(u64, Option<u64>) + Send + Sync,
         C: Fn() -> bool + Send + Sync,
     {
-        log::info!("🌐 开始下载图片 - URL: {}", Self::redact_url_for_log(url));
+        log::info!(
+            "{}",
+            message("image_log.download_start", &[("url", &Self::redact_url_for_log(url))])
+        );
 
         // URL 安全校验已统一到 download 内部的 validate_url_and_build_clients 中，
         // 避免与 build_request_clients_for_url 重复 DNS 解析。
-        let bytes = self
+        let (bytes, cache_hit) = self
             .download_with_validation_with_hooks(url, config, &on_progress, &is_cancelled)
             .await?;
         Self::validate_image_signature(&bytes)?;
 
-        Ok(RawImageData {
-            bytes,
-            source_hint: "url",
-        })
+        Ok((
+            RawImageData {
+                bytes,
+                source_hint: "url",
+            },
+            cache_hit,
+        ))
     }
 
     /// 从 Base64 字符串加载图片原始字节。
@@ -73,7 +97,7 @@ impl ImageHandler {
         data: &str,
         config: &ImageConfig,
     ) -> Result<RawImageData, ImageError> {
-        log::info!("📝 开始处理 base64 图片");
+        log::info!("{}", message("image_log.base64_start", &[]));
 
         let bytes = Self::parse_base64_with_limit(data, config.max_file_size)?;
         // parse_base64_with_limit 已通过上界估算拒绝超限输入，
@@ -94,37 +118,60 @@ impl ImageHandler {
         path: &str,
         config: &ImageConfig,
     ) -> Result<RawImageData, ImageError> {
-        log::info!("📁 开始读取本地图片 - 路径: {}", path);
+        log::info!("{}", message("image_log.file_start", &[("path", &path)]));
+
+        let bytes = Self::read_file_with_limit(path, config).await?;
+        Self::validate_image_signature(&bytes)?;
+
+        Ok(RawImageData {
+            bytes: Bytes::from(bytes),
+            source_hint: "file",
+        })
+    }
+
+    /// 读取本地 SVG 文件（体积受 `max_file_size` 约束，内容由栅格化阶段解析校验）。
+    pub(super) async fn load_svg_from_file(
+        &self,
+        path: &str,
+        config: &ImageConfig,
+    ) -> Result<RawImageData, ImageError> {
+        log::info!("{}", message("image_log.svg_file_start", &[("path", &path)]));
+
+        let bytes = Self::read_file_with_limit(path, config).await?;
+        if bytes.is_empty() {
+            return Err(ImageError::InvalidFormat(message("image.svg_empty", &[])));
+        }
+
+        Ok(RawImageData {
+            bytes: Bytes::from(bytes),
+            source_hint: "svg",
+        })
+    }
 
+    /// 检查文件体积后读取全部字节。
+    async fn read_file_with_limit(path: &str, config: &ImageConfig) -> Result<Vec<u8>, ImageError> {
         let file_path = Path::new(path);
 
         let metadata = tokio::fs::metadata(file_path)
             .await
             .map_err(|e| {
                 if e.kind() == std::io::ErrorKind::NotFound {
-                    ImageError::FileSystem(format!("文件不存在：{}", path))
+                    ImageError::FileSystem(message("image.file_not_found", &[("path", &path)]))
                 } else {
-                    ImageError::FileSystem(format!("无法读取文件信息：{}", e))
+                    ImageError::FileSystem(message("image.file_metadata_failed", &[("detail", &e)]))
                 }
             })?;
 
         if metadata.len() > config.max_file_size {
-            return Err(ImageError::ResourceLimit(format!(
-                "文件过大：{:.2} MB（限制：{:.2} MB）",
-                metadata.len() as f64 / 1024.0 / 1024.0,
-                config.max_file_size as f64 / 1024.0 / 1024.0
+            return Err(ImageError::ResourceLimit(message(
+                "image.file_too_large",
+                &[("size", &format_mb(metadata.len())), ("limit", &format_mb(config.max_file_size))],
             )));
         }
 
-        let bytes = tokio::fs::read(file_path)
+        tokio::fs::read(file_path)
             .await
-            .map_err(|e| ImageError::FileSystem(format!("无法读取图片文件：{}", e)))?;
-        Self::validate_image_signature(&bytes)?;
-
-        Ok(RawImageData {
-            bytes: Bytes::from(bytes),
-            source_hint: "file",
-        })
+            .map_err(|e| ImageError::FileSystem(message("image.read_file_failed", &[("detail", &e)])))
     }
 
     /// 执行带校验的网络下载。
@@ -138,6 +185,7 @@ impl ImageHandler {
     ) -> Result<Bytes, ImageError> {
         self.download_with_validation_with_hooks(url, config, |_, _| {}, || false)
             .await
+            .map(|(bytes, _)| bytes)
     }
 
     pub(super) async fn download_with_validation_with_hooks<P, C>(
@@ -146,21 +194,27 @@ impl ImageHandler {
         config: &ImageConfig,
         on_progress: P,
         is_cancelled: C,
-    ) -> Result<Bytes, ImageError>
+    ) -> Result<(Bytes, Option<DownloadCacheHit>), ImageError>
     where
         P: Fn(u64, Option<u64>) + Send + Sync,
         C: Fn() -> bool + Send + Sync,
     {
-        log::debug!("📡 发送 HTTP 请求...");
+        log::debug!("{}", message("image_log.http_request", &[]));
         let primary = reqwest::Url::parse(url)
-            .map_err(|e| ImageError::InvalidFormat(format!("URL 格式错误：{}", e)))?;
+            .map_err(|e| ImageError::InvalidFormat(message("image.invalid_url", &[("detail", &e)])))?;
         let primary_url = primary.to_string();
 
         if let Some(cached) = self.get_cached_download(&primary_url) {
             let total = cached.len() as u64;
             on_progress(total, Some(total));
-            log::debug!("♻️ 命中下载缓存 - URL: {}", Self::redact_url_for_log(&primary_url));
-            return Ok(cached);
+            log::debug!(
+                "{}",
+                message(
+                    "image_log.memory_cache_hit",
+                    &[("url", &Self::redact_url_for_log(&primary_url))],
+                )
+            );
+            return Ok((cached, Some(DownloadCacheHit::Memory)));
         }
 
         let mut candidates = vec![primary_url.clone()];
@@ -174,8 +228,11 @@ impl ImageHandler {
         for (idx, candidate_url) in candidates.iter().enumerate() {
             if idx > 0 {
                 log::warn!(
-                    "⚠️ 主链接下载失败，尝试回源地址: {}",
-                    Self::redact_url_for_log(candidate_url)
+                    "{}",
+                    message(
+                        "image_log.fallback_url",
+                        &[("url", &Self::redact_url_for_log(candidate_url))],
+                    )
                 );
                 // Bing 回源地址的安全校验由 download_single_url 内部的
                 // validate_url_and_build_clients 统一处理，避免重复 DNS 解析。
@@ -184,26 +241,81 @@ impl ImageHandler {
             if let Some(cached) = self.get_cached_download(candidate_url) {
                 let total = cached.len() as u64;
                 on_progress(total, Some(total));
-                return Ok(cached);
+                return Ok((cached, Some(DownloadCacheHit::Memory)));
             }
 
+            let disk_entry = Self::lookup_disk_cache(candidate_url, config).await;
+            if let Some(entry) = disk_entry
+                .as_ref()
+                .filter(|entry| entry.meta.is_fresh(download_cache::now_ms()))
+            {
+                log::debug!(
+                    "{}",
+                    message(
+                        "image_log.disk_cache_hit",
+                        &[("url", &Self::redact_url_for_log(candidate_url))],
+                    )
+                );
+                Self::touch_disk_cache(candidate_url, None).await;
+                return Ok(self.finish_cache_hit(&primary_url, candidate_url, entry, DownloadCacheHit::Disk, &on_progress));
+            }
+
+            let validators = disk_entry
+                .as_ref()
+                .map(|entry| &entry.meta.policy)
+                .filter(|policy| policy.has_validators());
             match self
                 .download_single_url(
                     candidate_url,
                     config,
+                    validators,
                     &on_progress,
                     &is_cancelled,
                 )
                 .await
             {
-                Ok(bytes) => {
+                Ok(DownloadOutcome::Body(bytes, policy)) => {
                     self.store_download_cache(candidate_url, &bytes);
                     if candidate_url != &primary_url {
                         self.store_download_cache(&primary_url, &bytes);
                     }
-                    return Ok(bytes);
+                    Self::store_disk_cache(candidate_url, &bytes, policy, config).await;
+                    return Ok((bytes, None));
+                }
+                Ok(DownloadOutcome::NotModified(policy)) => {
+                    // 仅在携带校验器时才会收到 304，此时磁盘条目必然存在
+                    let Some(entry) = disk_entry.as_ref() else {
+                        return Err(ImageError::Network(message("image.not_modified_without_cache", &[])));
+                    };
+                    log::debug!(
+                        "{}",
+                        message(
+                            "image_log.revalidated",
+                            &[("url", &Self::redact_url_for_log(candidate_url))],
+                        )
+                    );
+                    Self::touch_disk_cache(candidate_url, Some(policy)).await;
+                    return Ok(self.finish_cache_hit(&primary_url, candidate_url, entry, DownloadCacheHit::Revalidated, &on_progress));
                 }
                 Err(err) => {
+                    // 传输失败、超时或服务端故障时回退到本地过期副本，而不是直接报错
+                    if let Some(entry) = disk_entry
+                        .as_ref()
+                        .filter(|_| Self::should_serve_stale(&err))
+                    {
+                        log::warn!(
+                            "{}",
+                            message(
+                                "image_log.stale_cache_used",
+                                &[
+                                    ("url", &Self::redact_url_for_log(candidate_url)),
+                                    ("detail", &err),
+                                ],
+                            )
+                        );
+                        return Ok(self.finish_cache_hit(&primary_url, candidate_url, entry, DownloadCacheHit::Stale, &on_progress));
+                    }
+
                     let should_try_next = idx == 0
                         && candidates.len() > 1
                         && Self::should_try_bing_upstream_fallback(&err);
@@ -218,7 +330,7 @@ impl ImageHandler {
             }
         }
 
-        Err(last_err.unwrap_or_else(|| ImageError::Network("下载流程异常结束".to_string())))
+        Err(last_err.unwrap_or_else(|| ImageError::Network(message("image.download_ended_unexpectedly", &[]))))
     }
 
     /// 执行单个 URL 的带校验流式下载。
@@ -228,36 +340,36 @@ impl ImageHandler {
         &self,
         url: &str,
         config: &ImageConfig,
+        validators: Option<&CachePolicy>,
         on_progress: &P,
         is_cancelled: &C,
-    ) -> Result<Bytes, ImageError>
+    ) -> Result<DownloadOutcome, ImageError>
     where
         P: Fn(u64, Option<u64>) + Send + Sync,
         C: Fn() -> bool + Send + Sync,
     {
         let mut current_url = reqwest::Url::parse(url)
-            .map_err(|e| ImageError::InvalidFormat(format!("URL 格式错误：{}", e)))?;
+            .map_err(|e| ImageError::InvalidFormat(message("image.invalid_url", &[("detail", &e)])))?;
 
         for redirect_count in 0..=config.max_redirects {
             if is_cancelled() {
-                return Err(ImageError::Cancelled("图片下载已取消".to_string()));
+                return Err(ImageError::Cancelled(message("image.download_cancelled", &[])));
             }
 
-            let referer = format!("{}://{}/", current_url.scheme(), current_url.host_str().unwrap_or(""));
             // 合并 URL 安全校验 + DNS 解析 + 客户端构建，单次解析即可。
             let request_clients = Self::validate_url_and_build_clients(&current_url, config).await?;
             let response = {
                 let mut attempt: u8 = 1;
                 loop {
                     if is_cancelled() {
-                        return Err(ImageError::Cancelled("图片下载已取消".to_string()));
+                        return Err(ImageError::Cancelled(message("image.download_cancelled", &[])));
                     }
 
                     let client_idx = (attempt.saturating_sub(1) as usize) % request_clients.len();
                     let request_client = &request_clients[client_idx];
 
                     let send_result = self
-                        .send_with_client(&request_client, current_url.clone(), &referer)
+                        .send_with_client(request_client, current_url.clone(), validators)
                         .await;
 
                     match send_result {
@@ -271,11 +383,16 @@ impl ImageHandler {
                                 );
 
                                 log::warn!(
-                                    "⚠️ HTTP {}（第 {}/{} 次，可重试）；{}ms 后重试",
-                                    resp.status().as_u16(),
-                                    attempt,
-                                    NETWORK_RETRY_MAX_ATTEMPTS,
-                                    delay_ms
+                                    "{}",
+                                    message(
+                                        "image_log.http_retry",
+                                        &[
+                                            ("status", &resp.status().as_u16()),
+                                            ("attempt", &attempt),
+                                            ("max", &NETWORK_RETRY_MAX_ATTEMPTS),
+                                            ("delay", &delay_ms),
+                                        ],
+                                    )
                                 );
 
                                 tokio::time::sleep(Duration::from_millis(delay_ms)).await;
@@ -298,11 +415,16 @@ impl ImageHandler {
                                 current_url.as_str(),
                             );
                             log::warn!(
-                                "⚠️ 网络请求失败（第 {}/{} 次，可重试）：{}；{}ms 后重试",
-                                attempt,
-                                NETWORK_RETRY_MAX_ATTEMPTS,
-                                err_msg,
-                                delay_ms
+                                "{}",
+                                message(
+                                    "image_log.network_retry",
+                                    &[
+                                        ("attempt", &attempt),
+                                        ("max", &NETWORK_RETRY_MAX_ATTEMPTS),
+                                        ("detail", &err_msg),
+                                        ("delay", &delay_ms),
+                                    ],
+                                )
                             );
                             tokio::time::sleep(Duration::from_millis(delay_ms)).await;
                             attempt = attempt.saturating_add(1);
@@ -311,52 +433,60 @@ impl ImageHandler {
                 }
             };
 
+            // 304 同属 3xx，需在重定向处理之前判断
+            if response.status() == reqwest::StatusCode::NOT_MODIFIED && validators.is_some() {
+                return Ok(DownloadOutcome::NotModified(CachePolicy::from_headers(response.headers())));
+            }
+
             if response.status().is_redirection() {
                 if redirect_count >= config.max_redirects {
-                    return Err(ImageError::Network(format!(
-                        "重定向次数超过限制（{}）",
-                        config.max_redirects
+                    return Err(ImageError::Network(message(
+                        "image.too_many_redirects",
+                        &[("limit", &config.max_redirects)],
                     )));
                 }
 
                 let location = response
                     .headers()
                     .get(reqwest::header::LOCATION)
-                    .ok_or_else(|| ImageError::Network("重定向响应缺少 Location 头".to_string()))?;
+                    .ok_or_else(|| ImageError::Network(message("image.redirect_missing_location", &[])))?;
 
                 let location_str = location
                     .to_str()
-                    .map_err(|e| ImageError::InvalidFormat(format!("重定向地址无效：{}", e)))?;
+                    .map_err(|e| ImageError::InvalidFormat(message("image.invalid_redirect_location", &[("detail", &e)])))?;
 
                 let next_url = current_url
                     .join(location_str)
-                    .map_err(|e| ImageError::InvalidFormat(format!("重定向 URL 解析失败：{}", e)))?;
+                    .map_err(|e| ImageError::InvalidFormat(message("image.invalid_redirect_url", &[("detail", &e)])))?;
 
                 // 对重定向目标做轻量安全校验（协议+主机名+IP），
                 // DNS 级别的校验由下一轮循环顶部的 validate_url_and_build_clients 处理。
                 Self::validate_url_safety(next_url.as_str(), config).await?;
 
-                log::debug!("↪️ 跳转到: {}", Self::redact_url_for_log(next_url.as_str()));
+                log::debug!(
+                    "{}",
+                    message(
+                        "image_log.redirect",
+                        &[("url", &Self::redact_url_for_log(next_url.as_str()))],
+                    )
+                );
                 current_url = next_url;
                 continue;
             }
 
             if !response.status().is_success() {
-                return Err(ImageError::Network(format!(
-                    "HTTP {}: {}",
-                    response.status().as_u16(),
-                    Self::status_message(response.status().as_u16())
-                )));
+                return Err(Self::http_status_error(response.status().as_u16()));
             }
 
             if let Some(ct) = response.headers().get(reqwest::header::CONTENT_TYPE) {
                 if let Ok(ct_str) = ct.to_str() {
                     if !Self::is_image_content_type(ct_str) {
-                        return Err(ImageError::InvalidFormat(format!("不是图片类型：{}", ct_str)));
+                        return Err(ImageError::InvalidFormat(message("image.not_image_content_type", &[("content_type", &ct_str)])));
                     }
                 }
             }
 
+            let policy = CachePolicy::from_headers(response.headers());
             let total_len = response
                 .headers()
                 .get(reqwest::header::CONTENT_LENGTH)
@@ -369,10 +499,9 @@ impl ImageHandler {
                 if let Ok(cl_str) = cl.to_str() {
                     if let Ok(size) = cl_str.parse::<u64>() {
                         if size > config.max_file_size {
-                            return Err(ImageError::ResourceLimit(format!(
-                                "文件过大：{:.2} MB（限制：{:.2} MB）",
-                                size as f64 / 1024.0 / 1024.0,
-                                config.max_file_size as f64 / 1024.0 / 1024.0
+                            return Err(ImageError::ResourceLimit(message(
+                                "image.file_too_large",
+                                &[("size", &format_mb(size)), ("limit", &format_mb(config.max_file_size))],
                             )));
                         }
                     }
@@ -400,14 +529,14 @@ impl ImageHandler {
                     .await
                     .map_err(|_| {
                         if received_first_chunk {
-                            ImageError::Timeout("下载数据流读取超时".to_string())
+                            ImageError::Timeout(message("image.stream_read_timeout", &[]))
                         } else {
-                            ImageError::Timeout("下载首包超时".to_string())
+                            ImageError::Timeout(message("image.first_byte_timeout", &[]))
                         }
                     })?;
 
                 let Some(chunk) = next_chunk_result
-                    .map_err(|e| ImageError::Network(format!("下载失败：{}", e)))?
+                    .map_err(|e| ImageError::Network(message("image.download_failed", &[("detail", &e)])))?
                 else {
                     break;
                 };
@@ -415,12 +544,12 @@ impl ImageHandler {
                 received_first_chunk = true;
 
                 if is_cancelled() {
-                    return Err(ImageError::Cancelled("图片下载已取消".to_string()));
+                    return Err(ImageError::Cancelled(message("image.download_cancelled", &[])));
                 }
 
                 total = total.saturating_add(chunk.len() as u64);
                 if total > config.max_file_size {
-                    return Err(ImageError::ResourceLimit("下载后文件超过大小限制".to_string()));
+                    return Err(ImageError::ResourceLimit(message("image.download_exceeds_limit", &[])));
                 }
                 buffer.extend_from_slice(&chunk);
 
@@ -439,121 +568,151 @@ impl ImageHandler {
             }
 
             on_progress(total, total_len.or(Some(total)));
-            log::debug!("✅ 下载完成 - {} bytes", total);
+            log::debug!("{}", message("image_log.download_done", &[("bytes", &total)]));
 
-            return Ok(Bytes::from(buffer));
+            return Ok(DownloadOutcome::Body(Bytes::from(buffer), policy));
         }
 
-        Err(ImageError::Network("下载流程异常结束".to_string()))
+        Err(ImageError::Network(message("image.download_ended_unexpectedly", &[])))
     }
 
     async fn send_with_client(
         &self,
         client: &reqwest::Client,
         url: reqwest::Url,
-        referer: &str,
+        validators: Option<&CachePolicy>,
     ) -> Result<reqwest::Response, reqwest::Error> {
-        client
+        // User-Agent / Referer / 域名规则请求头已作为客户端默认头设置
+        let mut request = client
             .get(url)
-            .header(reqwest::header::USER_AGENT, "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/124.0.0.0 Safari/537.36")
-            .header(reqwest::header::ACCEPT, "image/avif,image/webp,image/apng,image/svg+xml,image/*,*/*;q=0.8")
-            .header(reqwest::header::REFERER, referer)
-            .send()
-            .await
+            .header(reqwest::header::ACCEPT, "image/avif,image/webp,image/apng,image/svg+xml,image/*,*/*;q=0.8");
+        if let Some(etag) = validators.and_then(|policy| policy.etag.as_deref()) {
+            request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = validators.and_then(|policy| policy.last_modified.as_deref()) {
+            request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+        }
+        request.send().await
     }
 
     /// 合并 URL 安全校验 + DNS 解析 + HTTP 客户端构建，避免重复解析。
     ///
     /// 将原来 `validate_url_safety` + `build_request_clients_for_url` 两步合为一步，
-    /// 对同一主机只做一次 DNS 解析。
-    async fn validate_url_and_build_clients(
+    /// 对同一主机只做一次 DNS 解析。命中的域名规则决定内网访问开关，
+    /// 其代理、请求头与超时写入返回的客户端。
+    pub(super) async fn validate_url_and_build_clients(
         url: &reqwest::Url,
         config: &ImageConfig,
     ) -> Result<Vec<reqwest::Client>, ImageError> {
         // 1. 协议校验
         if url.scheme() != "http" && url.scheme() != "https" {
-            return Err(ImageError::InvalidFormat("仅支持 HTTP/HTTPS".to_string()));
+            return Err(ImageError::InvalidFormat(message("image.http_only", &[])));
         }
 
         let host = url
             .host_str()
-            .ok_or_else(|| ImageError::InvalidFormat("URL 缺少主机地址".to_string()))?;
+            .ok_or_else(|| ImageError::InvalidFormat(message("image.url_missing_host", &[])))?;
 
         // 2. localhost 系主机名始终阻止（即使 allow_private_network=true），
         //    防止通过重定向绕过安全校验。
         if Self::is_local_hostname(host) {
-            return Err(ImageError::InvalidFormat(format!(
-                "禁止访问本地网络地址：{}",
-                host
-            )));
+            return Err(ImageError::InvalidFormat(message("image.local_host_blocked", &[("host", &host)])));
         }
 
-        // 3. 允许内网 → 直接返回基础客户端
-        if config.allow_private_network {
-            return Ok(vec![Self::build_base_http_client(config)?]);
+        let rule = config.domain_rule_for(host);
+
+        // 3. 允许内网（域名规则优先于全局开关）→ 直接返回基础客户端
+        if config.allows_private_network_for(host) {
+            return Ok(vec![Self::build_http_client(url, config, rule, None)?]);
         }
 
         // 4. 纯 IP 地址路径
         if let Ok(ip) = host.parse::<IpAddr>() {
             if Self::is_private_or_local_ip(ip) {
-                return Err(ImageError::InvalidFormat(format!(
-                    "禁止访问内网 IP：{}",
-                    ip
-                )));
+                return Err(ImageError::InvalidFormat(message("image.private_ip_blocked", &[("ip", &ip)])));
             }
-            return Ok(vec![Self::build_base_http_client(config)?]);
+            return Ok(vec![Self::build_http_client(url, config, rule, None)?]);
         }
 
         // 5. 不需要 DNS 解析校验 → 返回基础客户端
         if !config.resolve_dns_for_url_safety {
-            return Ok(vec![Self::build_base_http_client(config)?]);
+            return Ok(vec![Self::build_http_client(url, config, rule, None)?]);
         }
 
         // 6. DNS 解析 + SSRF 校验 + 构建绑定客户端（单次解析）
         let port = url
             .port_or_known_default()
-            .ok_or_else(|| ImageError::InvalidFormat("URL 缺少端口信息".to_string()))?;
+            .ok_or_else(|| ImageError::InvalidFormat(message("image.url_missing_port", &[])))?;
 
         let pinned = Self::resolve_public_socket_addrs(host, port).await?;
         if pinned.is_empty() {
-            return Err(ImageError::InvalidFormat("URL 未解析到有效公网地址".to_string()));
+            return Err(ImageError::InvalidFormat(message("image.no_public_address", &[])));
         }
 
         let mut clients = Vec::with_capacity(pinned.len());
         for addr in pinned {
-            let client = reqwest::Client::builder()
-                .timeout(Duration::from_secs(config.download_timeout))
-                .connect_timeout(Duration::from_secs(config.connect_timeout))
-                .redirect(reqwest::redirect::Policy::none())
-                .resolve(host, addr)
-                .build()
-                .map_err(|e| ImageError::Network(format!("无法创建 DNS 绑定客户端：{}", e)))?;
-            clients.push(client);
+            clients.push(Self::build_http_client(url, config, rule, Some((host, addr)))?);
         }
 
         Ok(clients)
     }
 
-    fn build_base_http_client(config: &ImageConfig) -> Result<reqwest::Client, ImageError> {
-        reqwest::Client::builder()
-            .timeout(Duration::from_secs(config.download_timeout))
-            .connect_timeout(Duration::from_secs(config.connect_timeout))
+    /// 构建单个 HTTP 客户端：超时、代理与默认请求头按域名规则覆盖全局配置。
+    ///
+    /// `pinned` 为 DNS 校验后绑定的地址；经代理访问时由代理解析主机，绑定不生效。
+    fn build_http_client(
+        url: &reqwest::Url,
+        config: &ImageConfig,
+        rule: Option<&ImageDomainRule>,
+        pinned: Option<(&str, SocketAddr)>,
+    ) -> Result<reqwest::Client, ImageError> {
+        let download_timeout = rule.and_then(|r| r.download_timeout).unwrap_or(config.download_timeout);
+        let connect_timeout = rule.and_then(|r| r.connect_timeout).unwrap_or(config.connect_timeout);
+
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(
+            reqwest::header::USER_AGENT,
+            reqwest::header::HeaderValue::from_static(DEFAULT_USER_AGENT),
+        );
+        let origin = format!("{}://{}/", url.scheme(), url.host_str().unwrap_or(""));
+        if let Ok(referer) = reqwest::header::HeaderValue::from_str(&origin) {
+            headers.insert(reqwest::header::REFERER, referer);
+        }
+        if let Some(rule) = rule {
+            for (name, value) in rule.request_headers()?.iter() {
+                headers.insert(name.clone(), value.clone());
+            }
+        }
+
+        let mut builder = reqwest::Client::builder()
+            .timeout(Duration::from_secs(download_timeout))
+            .connect_timeout(Duration::from_secs(connect_timeout))
             .redirect(reqwest::redirect::Policy::none())
+            .default_headers(headers);
+        if let Some(proxy) = rule.and_then(|r| r.proxy.as_deref()) {
+            let proxy = reqwest::Proxy::all(proxy)
+                .map_err(|e| ImageError::InvalidFormat(message("image.invalid_proxy", &[("detail", &e)])))?;
+            builder = builder.proxy(proxy);
+        }
+        if let Some((host, addr)) = pinned {
+            builder = builder.resolve(host, addr);
+        }
+        builder
             .build()
-            .map_err(|e| ImageError::Network(format!("无法创建 HTTP 客户端：{}", e)))
+            .map_err(|e| ImageError::Network(message("image.http_client_failed", &[("detail", &e)])))
     }
 
     async fn resolve_public_socket_addrs(host: &str, port: u16) -> Result<Vec<SocketAddr>, ImageError> {
         let addrs = lookup_host((host, port))
             .await
-            .map_err(|e| ImageError::InvalidFormat(format!("URL 主机解析失败：{}", e)))?;
+            .map_err(|e| ImageError::InvalidFormat(message("image.resolve_host_failed", &[("detail", &e)])))?;
 
         let mut result = Vec::new();
         for addr in addrs {
             if Self::is_private_or_local_ip(addr.ip()) {
-                return Err(ImageError::InvalidFormat(format!(
-                    "URL 解析结果命中内网地址：{}",
-                    addr.ip()
+                return Err(ImageError::InvalidFormat(message(
+                    "image.resolved_private_address",
+                    &[("ip", &addr.ip())],
                 )));
             }
 
@@ -597,7 +756,7 @@ impl ImageHandler {
             .unwrap_or(false)
     }
 
-    fn redact_url_for_log(url: &str) -> String {
+    pub(super) fn redact_url_for_log(url: &str) -> String {
         let Ok(parsed) = reqwest::Url::parse(url) else {
             return "<invalid-url>".to_string();
         };
@@ -668,6 +827,69 @@ impl ImageHandler {
         );
     }
 
+    /// 命中磁盘缓存后的收尾：上报进度并回填内存缓存。
+    fn finish_cache_hit<P>(
+        &self,
+        primary_url: &str,
+        candidate_url: &str,
+        entry: &CachedDownload,
+        hit: DownloadCacheHit,
+        on_progress: &P,
+    ) -> (Bytes, Option<DownloadCacheHit>)
+    where
+        P: Fn(u64, Option<u64>) + Send + Sync,
+    {
+        let total = entry.bytes.len() as u64;
+        on_progress(total, Some(total));
+        self.store_download_cache(candidate_url, &entry.bytes);
+        if candidate_url != primary_url {
+            self.store_download_cache(primary_url, &entry.bytes);
+        }
+        (entry.bytes.clone(), Some(hit))
+    }
+
+    /// 查询磁盘缓存（未初始化或副本超过当前大小上限时视为未命中）。
+    async fn lookup_disk_cache(url: &str, config: &ImageConfig) -> Option<CachedDownload> {
+        let cache_dir = download_cache::download_cache_dir()?;
+        let url = url.to_string();
+        let max_file_size = config.max_file_size;
+        tokio::task::spawn_blocking(move || download_cache::lookup(cache_dir, &url))
+            .await
+            .ok()
+            .flatten()
+            .filter(|entry| entry.meta.size <= max_file_size)
+    }
+
+    async fn touch_disk_cache(url: &str, revalidated: Option<CachePolicy>) {
+        let Some(cache_dir) = download_cache::download_cache_dir() else {
+            return;
+        };
+        let url = url.to_string();
+        let _ = tokio::task::spawn_blocking(move || {
+            download_cache::touch(cache_dir, &url, revalidated.as_ref(), download_cache::now_ms())
+        })
+        .await;
+    }
+
+    /// 写入磁盘缓存；失败只记录日志，不影响本次下载结果。
+    async fn store_disk_cache(url: &str, bytes: &Bytes, policy: CachePolicy, config: &ImageConfig) {
+        let Some(cache_dir) = download_cache::download_cache_dir() else {
+            return;
+        };
+        let url = url.to_string();
+        let bytes = bytes.clone();
+        let max_bytes = config.download_cache_max_bytes;
+        let result = tokio::task::spawn_blocking(move || {
+            download_cache::store(cache_dir, &url, &bytes, &policy, max_bytes, download_cache::now_ms())
+        })
+        .await;
+        match result {
+            Ok(Ok(_)) => {}
+            Ok(Err(err)) => log::warn!("{}", message("image_log.disk_cache_write_failed", &[("detail", &err)])),
+            Err(err) => log::warn!("{}", message("image_log.disk_cache_task_failed", &[("detail", &err)])),
+        }
+    }
+
     fn extract_bing_upstream_url(url: &reqwest::Url) -> Option<String> {
         let host = url.host_str()?.to_ascii_lowercase();
         if !host.ends_with("bing.com") {
@@ -695,39 +917,63 @@ impl ImageHandler {
         matches!(err, ImageError::Network(_) | ImageError::Timeout(_))
     }
 
+    /// 下载失败时是否可以回退到过期的磁盘副本。
+    ///
+    /// 仅限传输失败、超时与 5xx；服务器明确返回的 4xx（如 403 / 404 / 410）说明资源已不可用，
+    /// 不应被旧副本掩盖。
+    pub(super) fn should_serve_stale(err: &ImageError) -> bool {
+        match err {
+            ImageError::Timeout(_) => true,
+            ImageError::Network(_) => Self::http_status_of(err).is_none_or(|code| code >= 500),
+            _ => false,
+        }
+    }
+
+    /// 构造非成功 HTTP 状态的下载错误（格式与 `http_status_of` 对应）。
+    ///
+    /// 各语言的 `image.http_status` 文案均以 `HTTP {code}:` 开头，状态码可从消息中取回。
+    pub(super) fn http_status_error(code: u16) -> ImageError {
+        ImageError::Network(message(
+            "image.http_status",
+            &[("code", &code), ("reason", &Self::status_message(code))],
+        ))
+    }
+
+    /// 取出 `http_status_error` 产生的状态码；传输层错误返回 `None`。
+    fn http_status_of(err: &ImageError) -> Option<u16> {
+        let ImageError::Network(message) = err else {
+            return None;
+        };
+        message.strip_prefix("HTTP ")?.split(':').next()?.parse().ok()
+    }
+
     /// 校验 URL 安全性。
     ///
     /// 默认阻止本地/内网目标，防止 SSRF 风险。
-    async fn validate_url_safety(url: &str, config: &ImageConfig) -> Result<(), ImageError> {
+    pub(super) async fn validate_url_safety(url: &str, config: &ImageConfig) -> Result<(), ImageError> {
         let parsed = reqwest::Url::parse(url)
-            .map_err(|e| ImageError::InvalidFormat(format!("URL 格式错误：{}", e)))?;
+            .map_err(|e| ImageError::InvalidFormat(message("image.invalid_url", &[("detail", &e)])))?;
 
         if parsed.scheme() != "http" && parsed.scheme() != "https" {
-            return Err(ImageError::InvalidFormat("仅支持 HTTP/HTTPS".to_string()));
+            return Err(ImageError::InvalidFormat(message("image.http_only", &[])));
         }
 
         let host = parsed
             .host_str()
-            .ok_or_else(|| ImageError::InvalidFormat("URL 缺少主机地址".to_string()))?;
+            .ok_or_else(|| ImageError::InvalidFormat(message("image.url_missing_host", &[])))?;
 
         // localhost 系主机名始终阻止，即使 allow_private_network=true。
         if Self::is_local_hostname(host) {
-            return Err(ImageError::InvalidFormat(format!(
-                "禁止访问本地网络地址：{}",
-                host
-            )));
+            return Err(ImageError::InvalidFormat(message("image.local_host_blocked", &[("host", &host)])));
         }
 
-        if config.allow_private_network {
+        if config.allows_private_network_for(host) {
             return Ok(());
         }
 
         if let Ok(ip) = host.parse::<IpAddr>() {
             if Self::is_private_or_local_ip(ip) {
-                return Err(ImageError::InvalidFormat(format!(
-                    "禁止访问内网 IP：{}",
-                    ip
-                )));
+                return Err(ImageError::InvalidFormat(message("image.private_ip_blocked", &[("ip", &ip)])));
             }
 
             return Ok(());
@@ -736,10 +982,10 @@ impl ImageHandler {
         if config.resolve_dns_for_url_safety {
             let port = parsed
                 .port_or_known_default()
-                .ok_or_else(|| ImageError::InvalidFormat("URL 缺少端口信息".to_string()))?;
+                .ok_or_else(|| ImageError::InvalidFormat(message("image.url_missing_port", &[])))?;
 
             if Self::resolve_public_socket_addrs(host, port).await?.is_empty() {
-                return Err(ImageError::InvalidFormat("URL 未解析到有效地址".to_string()));
+                return Err(ImageError::InvalidFormat(message("image.no_address", &[])));
             }
         }
 
@@ -783,12 +1029,12 @@ impl ImageHandler {
         let len = base64_data.trim().len() as u64;
         let groups = len
             .checked_add(3)
-            .ok_or_else(|| ImageError::ResourceLimit("Base64 输入长度溢出".to_string()))?
+            .ok_or_else(|| ImageError::ResourceLimit(message("image.base64_length_overflow", &[])))?
             / 4;
 
         groups
             .checked_mul(3)
-            .ok_or_else(|| ImageError::ResourceLimit("Base64 解码体积估算溢出".to_string()))
+            .ok_or_else(|| ImageError::ResourceLimit(message("image.base64_size_overflow", &[])))
     }
 
     fn parse_base64_with_limit(data: &str, max_file_size: u64) -> Result<Vec<u8>, ImageError> {
@@ -797,47 +1043,45 @@ impl ImageHandler {
         if normalized.starts_with("data:image/") {
             let base64_start = normalized
                 .find(";base64,")
-                .ok_or_else(|| ImageError::InvalidFormat("缺少 base64 标记".to_string()))?;
+                .ok_or_else(|| ImageError::InvalidFormat(message("image.base64_missing_marker", &[])))?;
             let base64_data = &normalized[base64_start + 8..];
             let estimated_len = Self::estimate_base64_decoded_upper_bound_len(base64_data)?;
 
             if estimated_len > max_file_size {
-                return Err(ImageError::ResourceLimit(format!(
-                    "Base64 预计解码体积过大：{:.2} MB（限制：{:.2} MB）",
-                    estimated_len as f64 / 1024.0 / 1024.0,
-                    max_file_size as f64 / 1024.0 / 1024.0
+                return Err(ImageError::ResourceLimit(message(
+                    "image.base64_too_large",
+                    &[("size", &format_mb(estimated_len)), ("limit", &format_mb(max_file_size))],
                 )));
             }
 
             return general_purpose::STANDARD
                 .decode(base64_data)
-                .map_err(|e| ImageError::Decode(format!("Base64 解码失败：{}", e)));
+                .map_err(|e| ImageError::Decode(message("image.base64_decode_failed", &[("detail", &e)])));
         }
 
         let estimated_len = Self::estimate_base64_decoded_upper_bound_len(normalized)?;
         if estimated_len > max_file_size {
-            return Err(ImageError::ResourceLimit(format!(
-                "Base64 预计解码体积过大：{:.2} MB（限制：{:.2} MB）",
-                estimated_len as f64 / 1024.0 / 1024.0,
-                max_file_size as f64 / 1024.0 / 1024.0
+            return Err(ImageError::ResourceLimit(message(
+                "image.base64_too_large",
+                &[("size", &format_mb(estimated_len)), ("limit", &format_mb(max_file_size))],
             )));
         }
 
         general_purpose::STANDARD
             .decode(normalized)
-            .map_err(|e| ImageError::Decode(format!("Base64 解码失败：{}", e)))
+            .map_err(|e| ImageError::Decode(message("image.base64_decode_failed", &[("detail", &e)])))
     }
 
     /// 统一映射 reqwest 错误到业务错误。
-    fn map_reqwest_error(&self, e: reqwest::Error, url: &str, config: &ImageConfig) -> ImageError {
+    pub(super) fn map_reqwest_error(&self, e: reqwest::Error, url: &str, config: &ImageConfig) -> ImageError {
         let err_msg = Self::sanitize_error_message_with_redacted_url(&e.to_string(), url);
 
         if e.is_timeout() {
-            ImageError::Timeout(format!("下载超时（{}秒）", config.download_timeout))
+            ImageError::Timeout(message("image.download_timeout", &[("seconds", &config.download_timeout)]))
         } else if e.is_connect() {
-            ImageError::Network(format!("无法连接：{}", err_msg))
+            ImageError::Network(message("image.connect_failed", &[("detail", &err_msg)]))
         } else {
-            ImageError::Network(format!("请求失败：{}", err_msg))
+            ImageError::Network(message("image.request_failed", &[("detail", &err_msg)]))
         }
     }
 
@@ -846,7 +1090,7 @@ impl ImageHandler {
         error_msg.replace(url, &redacted)
     }
 
-    fn is_retryable_network_error(error: &reqwest::Error) -> bool {
+    pub(super) fn is_retryable_network_error(error: &reqwest::Error) -> bool {
         if error.is_timeout() || error.is_connect() {
             return true;
         }
@@ -860,32 +1104,34 @@ impl ImageHandler {
     }
 
     /// 常见 HTTP 状态码本地化文案。
-    fn status_message(code: u16) -> &'static str {
-        match code {
-            404 => "未找到",
-            403 => "访问被拒绝",
-            500..=599 => "服务器错误",
-            _ => "请求失败",
-        }
+    pub(super) fn status_message(code: u16) -> String {
+        let id = match code {
+            404 => "image.http_reason.not_found",
+            403 => "image.http_reason.forbidden",
+            500..=599 => "image.http_reason.server_error",
+            _ => "image.http_reason.other",
+        };
+        message(id, &[])
     }
 
-    /// 通过文件签名（magic bytes）校验输入是否为图片。
+    /// 通过文件签名（magic bytes）校验输入是否为当前构建可解码的图片。
+    ///
+    /// 已识别但无法解码的格式（如未启用 `heif` 特性时的 HEIC / AVIF）返回 `UnsupportedFormat`。
     fn validate_image_signature(bytes: &[u8]) -> Result<(), ImageError> {
         if bytes.is_empty() {
-            return Err(ImageError::InvalidFormat("图片内容为空".to_string()));
+            return Err(ImageError::InvalidFormat(message("image.empty_image", &[])));
         }
 
-        let kind = infer::get(bytes)
-            .ok_or_else(|| ImageError::InvalidFormat("无法识别图片类型".to_string()))?;
-
-        if kind.matcher_type() != infer::MatcherType::Image {
-            return Err(ImageError::InvalidFormat(format!(
-                "文件签名不是图片类型：{}",
-                kind.mime_type()
+        if let Some(kind) = infer::get(bytes)
+            && kind.matcher_type() != infer::MatcherType::Image
+        {
+            return Err(ImageError::InvalidFormat(message(
+                "image.signature_not_image",
+                &[("mime", &kind.mime_type())],
             )));
         }
 
-        Ok(())
+        codecs::ensure_decodable(bytes)
     }
 
     /// 流式下载阶段的签名探测：尽早识别并拒绝非图片内容。
@@ -901,18 +1147,24 @@ impl ImageHandler {
 
         if let Some(kind) = infer::get(bytes) {
             if kind.matcher_type() != infer::MatcherType::Image {
-                return Err(ImageError::InvalidFormat(format!(
-                    "下载内容不是图片类型：{}",
-                    kind.mime_type()
+                return Err(ImageError::InvalidFormat(message(
+                    "image.download_not_image",
+                    &[("mime", &kind.mime_type())],
                 )));
             }
+            // 可识别但无法解码的格式无需下载完整内容
+            codecs::ensure_decodable(bytes)?;
+            return Ok(true);
+        }
+        if image::guess_format(bytes).is_ok() {
+            codecs::ensure_decodable(bytes)?;
             return Ok(true);
         }
 
         if bytes.len() >= probe_limit {
-            return Err(ImageError::InvalidFormat(format!(
-                "下载前 {} 字节内无法识别图片类型",
-                probe_limit
+            return Err(ImageError::InvalidFormat(message(
+                "image.signature_unrecognized",
+                &[("bytes", &probe_limit)],
             )));
         }
 
@@ -920,6 +1172,11 @@ impl ImageHandler {
     }
 }
 
+/// 以 MB 为单位格式化字节数（两位小数），用于体积超限提示。
+pub(super) fn format_mb(bytes: u64) -> String {
+    format!("{:.2}", bytes as f64 / 1024.0 / 1024.0)
+}
+
 #[cfg(test)]
 #[path = "tests/loader_tests.rs"]
 mod tests;
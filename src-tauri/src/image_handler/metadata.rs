@@ -0,0 +1,255 @@
+//! # 图片元数据模块
+//!
+//! ## 设计思路
+//!
+//! 图片条目原本只有文件路径。本模块在入库时一次性读取可展示/可筛选的元数据：
+//! 尺寸、色彩类型、文件大小、EXIF（相机、方向、拍摄时间、是否含 GPS）与 PNG 文本块。
+//!
+//! ## 实现思路
+//!
+//! - 尺寸与色彩类型只读取文件头（`ImageDecoder`），不做完整解码
+//! - EXIF 通过解码器取出原始 TIFF 块，再交给 `kamadak-exif` 解析
+//! - PNG 文本块（tEXt/zTXt/iTXt）使用 `png` crate 读取，仅覆盖 IDAT 之前的块
+//! - `strip_exif` 在字节层面移除 PNG `eXIf` 块、JPEG `APP1 Exif` 段与 WebP `EXIF` 块，
+//!   GPS 信息位于 EXIF 中，会一并移除
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use image::{ImageDecoder, ImageReader};
+use serde::{Deserialize, Serialize};
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+const JPEG_EXIF_HEADER: &[u8] = b"Exif\0\0";
+/// WebP `VP8X` 头中表示存在 EXIF 块的标志位。
+const WEBP_VP8X_EXIF_FLAG: u8 = 0x08;
+
+/// 单张图片的元数据。
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImageMetadata {
+    pub width: u32,
+    pub height: u32,
+    /// 色彩类型（如 `Rgba8`、`Rgb8`、`L8`）
+    pub color_type: String,
+    /// 文件大小（字节）
+    pub byte_size: u64,
+    pub camera_make: Option<String>,
+    pub camera_model: Option<String>,
+    /// EXIF 方向（1-8）
+    pub orientation: Option<u32>,
+    /// 拍摄时间（EXIF `DateTimeOriginal` 原样保留）
+    pub captured_at: Option<String>,
+    pub has_gps: bool,
+    /// PNG 文本块（keyword → text）
+    #[serde(default)]
+    pub text_chunks: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Default)]
+struct ExifSummary {
+    camera_make: Option<String>,
+    camera_model: Option<String>,
+    orientation: Option<u32>,
+    captured_at: Option<String>,
+    has_gps: bool,
+}
+
+fn ascii_field(exif: &exif::Exif, tag: exif::Tag) -> Option<String> {
+    let field = exif.get_field(tag, exif::In::PRIMARY)?;
+    match &field.value {
+        exif::Value::Ascii(values) => values
+            .first()
+            .map(|raw| String::from_utf8_lossy(raw).trim_matches(char::from(0)).trim().to_string())
+            .filter(|s| !s.is_empty()),
+        _ => None,
+    }
+}
+
+fn parse_exif(raw: Vec<u8>) -> Option<ExifSummary> {
+    let exif = exif::Reader::new().read_raw(raw).ok()?;
+    Some(ExifSummary {
+        camera_make: ascii_field(&exif, exif::Tag::Make),
+        camera_model: ascii_field(&exif, exif::Tag::Model),
+        orientation: exif
+            .get_field(exif::Tag::Orientation, exif::In::PRIMARY)
+            .and_then(|f| f.value.get_uint(0)),
+        captured_at: ascii_field(&exif, exif::Tag::DateTimeOriginal)
+            .or_else(|| ascii_field(&exif, exif::Tag::DateTime)),
+        has_gps: exif.get_field(exif::Tag::GPSInfoIFDPointer, exif::In::PRIMARY).is_some()
+            || exif.fields().any(|f| f.tag.context() == exif::Context::Gps),
+    })
+}
+
+fn read_png_text_chunks(path: &Path) -> BTreeMap<String, String> {
+    let mut chunks = BTreeMap::new();
+    let Ok(file) = File::open(path) else {
+        return chunks;
+    };
+    let Ok(reader) = png::Decoder::new(BufReader::new(file)).read_info() else {
+        return chunks;
+    };
+    let info = reader.info();
+
+    for chunk in &info.uncompressed_latin1_text {
+        chunks.insert(chunk.keyword.clone(), chunk.text.clone());
+    }
+    for chunk in &info.compressed_latin1_text {
+        if let Ok(text) = chunk.get_text() {
+            chunks.insert(chunk.keyword.clone(), text);
+        }
+    }
+    for chunk in &info.utf8_text {
+        if let Ok(text) = chunk.get_text() {
+            chunks.insert(chunk.keyword.clone(), text);
+        }
+    }
+    chunks
+}
+
+/// 读取图片文件元数据；文件不存在或无法识别格式时返回 `None`。
+pub fn extract_image_metadata(path: &Path) -> Option<ImageMetadata> {
+    let byte_size = std::fs::metadata(path).ok()?.len();
+    let reader = ImageReader::open(path).ok()?.with_guessed_format().ok()?;
+    let is_png = reader.format() == Some(image::ImageFormat::Png);
+    let mut decoder = reader.into_decoder().ok()?;
+
+    let (width, height) = decoder.dimensions();
+    let color_type = format!("{:?}", decoder.color_type());
+    let exif = decoder
+        .exif_metadata()
+        .ok()
+        .flatten()
+        .and_then(parse_exif)
+        .unwrap_or_default();
+    drop(decoder);
+
+    Some(ImageMetadata {
+        width,
+        height,
+        color_type,
+        byte_size,
+        camera_make: exif.camera_make,
+        camera_model: exif.camera_model,
+        orientation: exif.orientation,
+        captured_at: exif.captured_at,
+        has_gps: exif.has_gps,
+        text_chunks: if is_png { read_png_text_chunks(path) } else { BTreeMap::new() },
+    })
+}
+
+// ─── EXIF 剥离 ──────────────────────────────────────────────────────
+
+fn strip_png_exif(bytes: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(bytes.len());
+    out.extend_from_slice(PNG_SIGNATURE);
+    let mut offset = PNG_SIGNATURE.len();
+
+    while offset + 8 <= bytes.len() {
+        let len = u32::from_be_bytes(bytes[offset..offset + 4].try_into().ok()?) as usize;
+        let chunk_type = &bytes[offset + 4..offset + 8];
+        let end = offset.checked_add(12)?.checked_add(len)?;
+        if end > bytes.len() {
+            return None;
+        }
+        if chunk_type != b"eXIf" {
+            out.extend_from_slice(&bytes[offset..end]);
+        }
+        offset = end;
+    }
+
+    (offset == bytes.len()).then_some(out)
+}
+
+fn strip_jpeg_exif(bytes: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(bytes.len());
+    out.extend_from_slice(&bytes[..2]);
+    let mut offset = 2;
+
+    while offset + 4 <= bytes.len() {
+        if bytes[offset] != 0xFF {
+            return None;
+        }
+        let marker = bytes[offset + 1];
+        // SOS 之后是熵编码数据，原样复制剩余部分
+        if marker == 0xDA {
+            out.extend_from_slice(&bytes[offset..]);
+            return Some(out);
+        }
+        let len = u16::from_be_bytes([bytes[offset + 2], bytes[offset + 3]]) as usize;
+        let end = offset + 2 + len;
+        if len < 2 || end > bytes.len() {
+            return None;
+        }
+        let is_exif = marker == 0xE1 && bytes[offset + 4..end].starts_with(JPEG_EXIF_HEADER);
+        if !is_exif {
+            out.extend_from_slice(&bytes[offset..end]);
+        }
+        offset = end;
+    }
+
+    None
+}
+
+/// 移除 WebP（RIFF 容器）的 `EXIF` 块并清除 `VP8X` 中的对应标志。
+///
+/// RIFF 声明长度之后的尾随字节（如剪贴板内存块的填充）一并丢弃。
+fn strip_webp_exif(bytes: &[u8]) -> Option<Vec<u8>> {
+    if bytes.len() < 12 || &bytes[..4] != b"RIFF" || &bytes[8..12] != b"WEBP" {
+        return None;
+    }
+    let riff_end = (u32::from_le_bytes(bytes[4..8].try_into().ok()?) as usize).checked_add(8)?;
+    if riff_end > bytes.len() {
+        return None;
+    }
+
+    let mut out = Vec::with_capacity(riff_end);
+    out.extend_from_slice(&bytes[..12]);
+    let mut offset = 12;
+
+    while offset + 8 <= riff_end {
+        let fourcc = &bytes[offset..offset + 4];
+        let len = u32::from_le_bytes(bytes[offset + 4..offset + 8].try_into().ok()?) as usize;
+        // 块数据按偶数字节对齐
+        let end = offset.checked_add(8)?.checked_add(len)?.checked_add(len & 1)?;
+        if end > riff_end {
+            return None;
+        }
+        if fourcc == b"VP8X" && len > 0 {
+            let start = out.len();
+            out.extend_from_slice(&bytes[offset..end]);
+            out[start + 8] &= !WEBP_VP8X_EXIF_FLAG;
+        } else if fourcc != b"EXIF" {
+            out.extend_from_slice(&bytes[offset..end]);
+        }
+        offset = end;
+    }
+    if offset != riff_end {
+        return None;
+    }
+
+    let riff_size = u32::try_from(out.len() - 8).ok()?;
+    out[4..8].copy_from_slice(&riff_size.to_le_bytes());
+    Some(out)
+}
+
+/// 移除编码字节中的 EXIF（含 GPS）。
+///
+/// 支持 PNG、JPEG 与 WebP；其他格式或结构异常时原样返回，保证不破坏图片。
+pub fn strip_exif(bytes: &[u8]) -> Vec<u8> {
+    let stripped = if bytes.starts_with(PNG_SIGNATURE) {
+        strip_png_exif(bytes)
+    } else if bytes.starts_with(&[0xFF, 0xD8]) {
+        strip_jpeg_exif(bytes)
+    } else if bytes.starts_with(b"RIFF") {
+        strip_webp_exif(bytes)
+    } else {
+        None
+    };
+    stripped.unwrap_or_else(|| bytes.to_vec())
+}
+
+#[cfg(test)]
+#[path = "tests/metadata_tests.rs"]
+mod tests;
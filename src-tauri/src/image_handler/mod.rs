@@ -9,8 +9,18 @@
 //! - `service`：承载可注入状态（`ImageServiceState`）
 //! - `handler`：编排整条处理流水线
 //! - `loader`：负责 URL/Base64/文件加载与安全校验
-//! - `pipeline`：负责解码、像素限制、降采样
+//! - `link_preview`：复用 `loader` 的安全链路抓取网页头部，提取链接预览
+//! - `pipeline`：负责解码、像素限制、降采样，并应用输出选项
+//! - `codecs`：格式识别与扩展解码器分派（HEIC / AVIF 经 `heif` 特性），不可解码格式的错误语义
+//! - `svg`：SVG 栅格化（resvg，`svg` 特性），位图与 SVG 源文本一并写入剪贴板
+//! - `output`：复制输出选项（编码、缩放、裁剪、旋转、背景色、DPI）与编码辅助
+//! - `edit`：非破坏式图片编辑操作（几何变换、马赛克/模糊、矩形/箭头/文字标注）
 //! - `clipboard_writer`：负责写入剪贴板与重试
+//! - `thumbnail`：缩略图懒生成、磁盘缓存与失效
+//! - `download_cache`：URL 下载的磁盘缓存（条件请求重新验证 + LRU 字节预算）
+//! - `download_manager`：URL 下载队列（并发上限、优先级、同 URL 合并、暂停/重试）
+//! - `metadata`：图片尺寸/EXIF/文本块提取与 EXIF 剥离
+//! - `palette`：主色调提取（中位切分）与颜色距离
 //! - `config/error/source`：配置、错误、中间数据模型
 //!
 //! ## 实现思路
@@ -31,7 +41,7 @@
 //!    ↓
 //! handler.rs（统一编排 + 阶段耗时日志）
 //!    ├─ loader.rs（来源加载 + URL/体积安全校验）
-//!    ├─ pipeline.rs（解码 + 像素限制 + 降采样）
+//!    ├─ pipeline.rs（解码 + 像素限制 + 降采样 + 输出选项）
 //!    └─ clipboard_writer.rs（写剪贴板 + 重试）
 //!    ↓
 //! 返回 AppError 给前端
@@ -47,30 +57,55 @@
 
 pub mod commands;
 mod clipboard_writer;
+mod codecs;
 mod config;
+mod download_cache;
+mod download_manager;
+mod edit;
 mod error;
 mod handler;
+mod link_preview;
 mod loader;
+mod metadata;
+mod output;
+mod palette;
 mod pipeline;
 mod service;
 mod source;
+mod svg;
+mod thumbnail;
 
 pub use commands::{
     cancel_image_download,
+    clear_image_download_cache,
     copy_base64_image_to_clipboard,
     copy_image_to_clipboard,
+    copy_svg_image_to_clipboard,
     download_and_copy_image,
     get_image_advanced_config,
+    get_image_download_cache_info,
     get_image_performance_profile,
+    get_thumbnail,
+    list_image_downloads,
+    pause_image_download,
+    retry_image_download,
     set_image_advanced_config,
     set_image_performance_profile,
 };
-pub use config::{ImageConfig, ImagePerformanceProfile};
+pub use config::{ImageConfig, ImageDomainRule, ImagePerformanceProfile};
+pub use download_cache::{init_download_cache, DownloadCacheInfo};
+pub use download_manager::{DownloadTaskInfo, DownloadTaskKind, DownloadTaskStatus};
+pub use edit::{EditPoint, FlipDirection, ImageEditOp};
 pub use error::ImageError;
+pub use link_preview::LinkPreview;
+pub use metadata::{extract_image_metadata, strip_exif, ImageMetadata};
+pub use output::{CropRect, ImageOutputOptions, OutputEncoding};
+pub use palette::{color_distance, extract_palette_from_file, PaletteColor};
 pub use config::ImageAdvancedConfig;
 pub use service::IMAGE_DOWNLOAD_PROGRESS_EVENT;
 pub use service::ImageServiceState;
-pub use source::ImageSource;
+pub use source::{ImageSource, PreservedImageFormat};
+pub use thumbnail::{init_thumbnail_cache, invalidate_thumbnails, THUMBNAIL_SIZES};
 
 /// 内部核心编排器，不直接暴露给 Tauri 命令层。
 pub(crate) use handler::ImageHandler;
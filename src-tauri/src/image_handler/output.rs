@@ -0,0 +1,241 @@
+//! # 输出选项模块
+//!
+//! ## 设计思路
+//!
+//! 默认链路把图片解码为 RGBA 位图写入剪贴板，只做自适应降采样。部分目标应用需要
+//! JPEG、固定宽度、局部裁剪或去除透明通道，因此复制命令可附带 `ImageOutputOptions`，
+//! 由 `pipeline` 在写入剪贴板前统一应用。
+//!
+//! ## 实现思路
+//!
+//! - 变换顺序固定：裁剪 → 旋转 → 缩放 → 背景合成 → 编码，裁剪坐标始终基于原图
+//! - 未指定任何选项时（`is_passthrough`）与旧链路行为完全一致
+//! - 显式缩放后跳过自适应降采样，但目标尺寸仍受像素/内存上限约束
+//! - JPEG 不支持透明通道，未指定背景色时合成到白色
+//! - DPI 写入 PNG `pHYs`、JPEG JFIF 密度与 Windows DIBv5 头；
+//!   非 Windows 平台的位图输出无法携带 DPI
+//! - 编码字节只能在 Windows 与 Linux (X11) 上写入剪贴板，其它平台在校验阶段拒绝
+//!   显式 PNG / JPEG 编码，而不是静默回退为位图
+
+use image::buffer::ConvertBuffer;
+use image::codecs::jpeg::{JpegEncoder, PixelDensity};
+use image::{ExtendedColorType, RgbImage, RgbaImage};
+use serde::{Deserialize, Serialize};
+
+use crate::color::parse_rgb8;
+use crate::i18n::message;
+
+use super::ImageError;
+
+/// 当前平台能否以编码字节（PNG / JPEG）写入剪贴板。
+pub(crate) const ENCODED_OUTPUT_SUPPORTED: bool = cfg!(any(target_os = "windows", target_os = "linux"));
+/// JPEG 默认质量。
+pub(crate) const DEFAULT_JPEG_QUALITY: u8 = 90;
+/// 显式缩放时单边允许的最大值。
+const MAX_OUTPUT_DIMENSION: u32 = 16_384;
+/// DPI 元数据允许范围。
+const DPI_RANGE: std::ops::RangeInclusive<u32> = 1..=2_400;
+
+/// 写入剪贴板的编码格式。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputEncoding {
+    /// 平台原生位图（Windows 同时附带 PNG），与旧链路一致。
+    #[default]
+    Bitmap,
+    /// 仅提供 PNG 编码字节。
+    Png,
+    /// 仅提供 JPEG 编码字节（透明通道会被合成到背景色）。
+    Jpeg,
+}
+
+impl OutputEncoding {
+    /// 编码后的 MIME 类型；位图无编码字节时返回 `None`。
+    pub(crate) fn mime(self) -> Option<&'static str> {
+        match self {
+            Self::Bitmap => None,
+            Self::Png => Some("image/png"),
+            Self::Jpeg => Some("image/jpeg"),
+        }
+    }
+}
+
+/// 裁剪区域（基于原图像素坐标）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CropRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// 复制图片时的输出选项，全部字段可省略。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImageOutputOptions {
+    /// 输出编码。
+    pub encoding: OutputEncoding,
+    /// JPEG 质量（1~100），默认 90。
+    pub jpeg_quality: Option<u8>,
+    /// 目标宽度；仅指定宽或高时按原比例计算另一边。
+    pub width: Option<u32>,
+    /// 目标高度。
+    pub height: Option<u32>,
+    /// 裁剪区域，在旋转与缩放之前应用。
+    pub crop: Option<CropRect>,
+    /// 顺时针旋转角度：0 / 90 / 180 / 270。
+    pub rotation: u16,
+    /// 透明通道合成背景色（`#rgb` / `#rrggbb`）。
+    pub background: Option<String>,
+    /// 写入输出的 DPI 元数据。
+    pub dpi: Option<u32>,
+}
+
+impl ImageOutputOptions {
+    /// 是否为默认选项（不做任何额外处理）。
+    pub(crate) fn is_passthrough(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// 是否指定了显式缩放。
+    pub(crate) fn has_explicit_resize(&self) -> bool {
+        self.width.is_some() || self.height.is_some()
+    }
+
+    /// 校验各字段的合法区间（与图片尺寸相关的检查在 `pipeline` 中完成）。
+    pub(crate) fn validate(&self) -> Result<(), ImageError> {
+        if self.jpeg_quality.is_some_and(|quality| !(1..=100).contains(&quality)) {
+            return Err(ImageError::InvalidFormat(message("image.config_out_of_range", &[("field", &"jpeg_quality"), ("min", &1), ("max", &100)])));
+        }
+        for (name, value) in [("width", self.width), ("height", self.height)] {
+            if value.is_some_and(|v| v == 0 || v > MAX_OUTPUT_DIMENSION) {
+                return Err(ImageError::InvalidFormat(message(
+                    "image.config_out_of_range",
+                    &[("field", &name), ("min", &1), ("max", &MAX_OUTPUT_DIMENSION)],
+                )));
+            }
+        }
+        if self.crop.is_some_and(|crop| crop.width == 0 || crop.height == 0) {
+            return Err(ImageError::InvalidFormat(message("image.crop_empty", &[])));
+        }
+        if !matches!(self.rotation, 0 | 90 | 180 | 270) {
+            return Err(ImageError::InvalidFormat(message(
+                "image.unsupported_rotation",
+                &[("degrees", &self.rotation), ("options", &"0 / 90 / 180 / 270")],
+            )));
+        }
+        self.background_rgb()?;
+        if self.dpi.is_some_and(|dpi| !DPI_RANGE.contains(&dpi)) {
+            return Err(ImageError::InvalidFormat(message(
+                "image.config_out_of_range",
+                &[("field", &"dpi"), ("min", DPI_RANGE.start()), ("max", DPI_RANGE.end())],
+            )));
+        }
+        if let Some(mime) = self.encoding.mime().filter(|_| !ENCODED_OUTPUT_SUPPORTED) {
+            return Err(ImageError::InvalidFormat(message("image.encoding_unsupported_on_platform", &[("mime", &mime)])));
+        }
+        Ok(())
+    }
+
+    /// 解析显式指定的背景色。
+    fn background_rgb(&self) -> Result<Option<[u8; 3]>, ImageError> {
+        match self.background.as_deref() {
+            None => Ok(None),
+            Some(hex) => parse_rgb8(hex)
+                .map(Some)
+                .ok_or_else(|| ImageError::InvalidFormat(message("image.invalid_background", &[("color", &hex)]))),
+        }
+    }
+
+    /// 实际用于合成透明通道的背景色：显式指定优先，JPEG 默认白色。
+    pub(crate) fn effective_background(&self) -> Result<Option<[u8; 3]>, ImageError> {
+        let explicit = self.background_rgb()?;
+        Ok(match (explicit, self.encoding) {
+            (Some(rgb), _) => Some(rgb),
+            (None, OutputEncoding::Jpeg) => Some([255, 255, 255]),
+            (None, _) => None,
+        })
+    }
+
+    /// 根据源尺寸计算显式缩放的目标尺寸；未指定时返回 `None`。
+    pub(crate) fn target_size(&self, width: u32, height: u32) -> Option<(u32, u32)> {
+        let scaled = |value: u32, numerator: u32, denominator: u32| {
+            ((value as f64 * numerator as f64 / denominator.max(1) as f64).round() as u32).max(1)
+        };
+        match (self.width, self.height) {
+            (Some(w), Some(h)) => Some((w, h)),
+            (Some(w), None) => Some((w, scaled(height, w, width))),
+            (None, Some(h)) => Some((scaled(width, h, height), h)),
+            (None, None) => None,
+        }
+    }
+}
+
+/// DPI 换算为每米像素数（PNG `pHYs` 与 DIBv5 头使用）。
+pub(crate) fn dpi_to_pixels_per_meter(dpi: u32) -> u32 {
+    (dpi as f64 / 0.0254).round() as u32
+}
+
+/// 将透明像素按 alpha 合成到纯色背景上，输出完全不透明。
+pub(crate) fn flatten_alpha(image: &mut RgbaImage, background: [u8; 3]) {
+    for pixel in image.pixels_mut() {
+        let alpha = pixel[3] as u32;
+        if alpha == 255 {
+            continue;
+        }
+        for channel in 0..3 {
+            let blended = (pixel[channel] as u32 * alpha + background[channel] as u32 * (255 - alpha) + 127) / 255;
+            pixel[channel] = blended as u8;
+        }
+        pixel[3] = 255;
+    }
+}
+
+/// 将 RGBA 像素编码为 PNG，可选写入 `pHYs` DPI 信息。
+pub(crate) fn encode_png(
+    width: u32,
+    height: u32,
+    rgba: &[u8],
+    dpi: Option<u32>,
+) -> Result<Vec<u8>, ImageError> {
+    let mut out = Vec::new();
+    let mut encoder = png::Encoder::new(&mut out, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    if let Some(dpi) = dpi {
+        let ppm = dpi_to_pixels_per_meter(dpi);
+        encoder.set_pixel_dims(Some(png::PixelDimensions {
+            xppu: ppm,
+            yppu: ppm,
+            unit: png::Unit::Meter,
+        }));
+    }
+    let mut writer = encoder
+        .write_header()
+        .map_err(|e| ImageError::Decode(message("image.png_encode_failed", &[("detail", &e)])))?;
+    writer
+        .write_image_data(rgba)
+        .map_err(|e| ImageError::Decode(message("image.png_encode_failed", &[("detail", &e)])))?;
+    writer
+        .finish()
+        .map_err(|e| ImageError::Decode(message("image.png_encode_failed", &[("detail", &e)])))?;
+    Ok(out)
+}
+
+/// 将（已去除透明通道的）RGBA 像素编码为 JPEG，可选写入 JFIF 密度。
+pub(crate) fn encode_jpeg(image: &RgbaImage, quality: u8, dpi: Option<u32>) -> Result<Vec<u8>, ImageError> {
+    let rgb: RgbImage = image.convert();
+    let mut out = Vec::new();
+    let mut encoder = JpegEncoder::new_with_quality(&mut out, quality);
+    if let Some(dpi) = dpi {
+        encoder.set_pixel_density(PixelDensity::dpi(dpi.min(u16::MAX as u32) as u16));
+    }
+    encoder
+        .encode(rgb.as_raw(), rgb.width(), rgb.height(), ExtendedColorType::Rgb8)
+        .map_err(|e| ImageError::Decode(message("image.jpeg_encode_failed", &[("detail", &e)])))?;
+    Ok(out)
+}
+
+#[cfg(test)]
+#[path = "tests/output_tests.rs"]
+mod tests;
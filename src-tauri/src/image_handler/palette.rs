@@ -0,0 +1,185 @@
+//! # 主色调提取模块
+//!
+//! ## 设计思路
+//!
+//! 为图片条目计算前 N 个主色（颜色 + 占比），供列表展示与“按颜色找图”使用。
+//! 颜色分析只需要像素统计，因此先经 `pipeline.rs` 降采样到很小的 RGBA 缓冲再计算。
+//!
+//! ## 实现思路
+//!
+//! - 中位切分（median-cut）：每次选择“通道跨度 × 像素数”最大的盒子，
+//!   沿跨度最大的通道一分为二，直到盒子数达到 N。
+//!   切分点取该通道取值范围的中点而非像素数中位数：
+//!   大面积纯色不会被拦腰切开，避免同一颜色占据多个名额
+//! - 每个盒子的平均色即为一个主色，占比为盒内像素数 / 有效像素数；
+//!   平均色过于接近的盒子最后合并
+//! - 半透明像素（alpha < 128）不参与统计，避免透明背景被当作主色
+//! - 颜色距离使用 redmean 加权欧氏距离（0 ~ 约 765），比纯 RGB 距离更贴近人眼
+
+use std::path::Path;
+
+use bytes::Bytes;
+use image::RgbaImage;
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::message;
+
+use super::source::RawImageData;
+use super::{ImageConfig, ImageError, ImageHandler};
+
+/// 分析缓冲的长边上限（约 4K 像素，足以稳定统计主色）
+const ANALYSIS_MAX_EDGE: u32 = 64;
+const MIN_OPAQUE_ALPHA: u8 = 128;
+/// 平均色距离低于该值的盒子视为同一主色
+const MERGE_DISTANCE: f64 = 12.0;
+
+/// 单个主色。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PaletteColor {
+    /// `#rrggbb`
+    pub hex: String,
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    /// 占有效像素的比例（0~1）
+    pub ratio: f32,
+}
+
+impl PaletteColor {
+    pub(crate) fn from_rgb(rgb: [u8; 3], ratio: f32) -> Self {
+        Self {
+            hex: format!("#{:02x}{:02x}{:02x}", rgb[0], rgb[1], rgb[2]),
+            r: rgb[0],
+            g: rgb[1],
+            b: rgb[2],
+            ratio,
+        }
+    }
+
+    pub fn rgb(&self) -> [u8; 3] {
+        [self.r, self.g, self.b]
+    }
+}
+
+// ─── 颜色工具 ───────────────────────────────────────────────────────
+
+/// redmean 加权 RGB 距离。
+pub fn color_distance(a: [u8; 3], b: [u8; 3]) -> f64 {
+    let r_mean = (f64::from(a[0]) + f64::from(b[0])) / 2.0;
+    let dr = f64::from(a[0]) - f64::from(b[0]);
+    let dg = f64::from(a[1]) - f64::from(b[1]);
+    let db = f64::from(a[2]) - f64::from(b[2]);
+    (((512.0 + r_mean) * dr * dr) / 256.0 + 4.0 * dg * dg + ((767.0 - r_mean) * db * db) / 256.0).sqrt()
+}
+
+// ─── 中位切分 ───────────────────────────────────────────────────────
+
+/// 返回 (跨度最大的通道, 该通道最小值, 跨度)
+fn widest_channel(pixels: &[[u8; 3]]) -> (usize, u8, u8) {
+    let mut min = [u8::MAX; 3];
+    let mut max = [u8::MIN; 3];
+    for p in pixels {
+        for c in 0..3 {
+            min[c] = min[c].min(p[c]);
+            max[c] = max[c].max(p[c]);
+        }
+    }
+    (0..3)
+        .map(|c| (c, min[c], max[c].saturating_sub(min[c])))
+        .max_by_key(|&(_, _, range)| range)
+        .unwrap_or((0, 0, 0))
+}
+
+fn average(pixels: &[[u8; 3]]) -> [u8; 3] {
+    let mut sum = [0u64; 3];
+    for p in pixels {
+        for c in 0..3 {
+            sum[c] += u64::from(p[c]);
+        }
+    }
+    let n = pixels.len().max(1) as u64;
+    [(sum[0] / n) as u8, (sum[1] / n) as u8, (sum[2] / n) as u8]
+}
+
+/// 对 RGBA 缓冲做中位切分，返回按占比降序的主色（最多 `max_colors` 个）。
+pub fn extract_palette(image: &RgbaImage, max_colors: usize) -> Vec<PaletteColor> {
+    let pixels: Vec<[u8; 3]> = image
+        .pixels()
+        .filter(|p| p[3] >= MIN_OPAQUE_ALPHA)
+        .map(|p| [p[0], p[1], p[2]])
+        .collect();
+    let total = pixels.len();
+    if total == 0 || max_colors == 0 {
+        return Vec::new();
+    }
+
+    let mut boxes = vec![pixels];
+    while boxes.len() < max_colors {
+        let candidate = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| b.len() > 1)
+            .map(|(i, b)| {
+                let (channel, min, range) = widest_channel(b);
+                (i, channel, min, range, u64::from(range) * b.len() as u64)
+            })
+            .filter(|&(_, _, _, _, score)| score > 0)
+            .max_by_key(|&(_, _, _, _, score)| score);
+        let Some((index, channel, min, range, _)) = candidate else {
+            break;
+        };
+
+        // range > 0 保证两侧都非空
+        let mid = min + range / 2;
+        let mut lower = boxes.swap_remove(index);
+        lower.sort_unstable_by_key(|p| p[channel]);
+        let split = lower.partition_point(|p| p[channel] <= mid);
+        let upper = lower.split_off(split);
+        boxes.push(lower);
+        boxes.push(upper);
+    }
+
+    // (平均色, 像素数)，按像素数降序后逐个并入已有的相近主色
+    let mut clusters: Vec<([u8; 3], usize)> = boxes.iter().map(|b| (average(b), b.len())).collect();
+    clusters.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+    let mut merged: Vec<([u8; 3], usize)> = Vec::with_capacity(clusters.len());
+    for (color, count) in clusters {
+        match merged
+            .iter_mut()
+            .find(|(existing, _)| color_distance(*existing, color) < MERGE_DISTANCE)
+        {
+            Some((existing, existing_count)) => {
+                let sum = *existing_count + count;
+                for c in 0..3 {
+                    let weighted = usize::from(existing[c]) * *existing_count + usize::from(color[c]) * count;
+                    existing[c] = (weighted / sum) as u8;
+                }
+                *existing_count = sum;
+            }
+            None => merged.push((color, count)),
+        }
+    }
+
+    let mut palette: Vec<PaletteColor> = merged
+        .into_iter()
+        .map(|(color, count)| PaletteColor::from_rgb(color, count as f32 / total as f32))
+        .collect();
+    palette.sort_by(|a, b| b.ratio.total_cmp(&a.ratio).then_with(|| a.hex.cmp(&b.hex)));
+    palette
+}
+
+/// 读取图片文件并提取主色；解码受 `ImageConfig` 的像素/内存上限约束。
+pub fn extract_palette_from_file(path: &Path, max_colors: usize) -> Result<Vec<PaletteColor>, ImageError> {
+    let bytes = std::fs::read(path)
+        .map_err(|e| ImageError::FileSystem(message("image.read_file_failed", &[("detail", &e)])))?;
+    let raw = RawImageData {
+        bytes: Bytes::from(bytes),
+        source_hint: "palette",
+    };
+    let rgba = ImageHandler::render_analysis_rgba(raw, ANALYSIS_MAX_EDGE, &ImageConfig::default())?;
+    Ok(extract_palette(&rgba, max_colors))
+}
+
+#[cfg(test)]
+#[path = "tests/palette_tests.rs"]
+mod tests;
@@ -7,27 +7,35 @@
 //!
 //! ## 实现思路
 //!
-//! 1. 读取 header 尺寸（格式识别由 ImageReader 统一完成）
+//! 1. 读取 header 尺寸（格式识别与扩展解码器分派由 `codecs` 统一完成）
 //! 2. 按像素上限快速拒绝
 //! 3. 完整解码，尽早释放编码字节缓冲
 //! 4. 一次性转换 RGBA（`into_rgba8` 对已有 RGBA8 零拷贝）
-//! 5. 根据配置决定是否降采样
-//! 6. 校验字节长度一致性
+//! 5. 应用输出选项的几何变换（裁剪 → 旋转 → 显式缩放），未显式缩放时按配置决定是否降采样
+//! 6. 按输出选项合成背景色并编码（PNG / JPEG），校验字节长度一致性
+//! 7. 来源为文件/下载且未指定输出选项时，保留 GIF / WebP / PNG 原始编码字节，
+//!    与位图一并写入剪贴板（动图与 PNG 元数据不会因解码而丢失）
 
 use fast_image_resize as fr;
-use image::{DynamicImage, GenericImageView, ImageBuffer, ImageReader, Rgba};
+use image::{DynamicImage, GenericImageView, ImageBuffer, Rgba, RgbaImage};
 use std::io::Cursor;
 
-use super::source::{PreparedClipboardImage, RawImageData};
+use crate::i18n::message;
+
+use super::codecs;
+use super::loader::format_mb;
+use super::output::{self, ImageOutputOptions, OutputEncoding, DEFAULT_JPEG_QUALITY};
+use super::source::{EncodedClipboardImage, PreparedClipboardImage, PreservedImageFormat, RawImageData};
 use super::{ImageConfig, ImageError, ImageHandler};
 
 impl ImageHandler {
-    /// 将原始字节解码为可写入剪贴板的 RGBA 数据。
+    /// 将原始字节解码为可写入剪贴板的 RGBA 数据，并应用输出选项。
     ///
     /// 该函数为纯计算函数（不依赖 `&self`），可安全在 `spawn_blocking` 中调用。
     pub(crate) fn decode_and_prepare_for_clipboard(
         raw: RawImageData,
         config: &ImageConfig,
+        options: &ImageOutputOptions,
     ) -> Result<PreparedClipboardImage, ImageError> {
         let RawImageData {
             bytes: raw_bytes,
@@ -38,9 +46,9 @@ impl ImageHandler {
         Self::validate_pixel_limits(config, header_width, header_height)?;
         Self::validate_decoded_memory_limits(config, header_width, header_height)?;
 
-        let decoded = image::load_from_memory(&raw_bytes)
-            .map_err(|e| ImageError::Decode(format!("图片解码失败：{}", e)))?;
+        let decoded = codecs::decode(&raw_bytes)?;
 
+        let original = Self::preserved_original(&raw_bytes, source_hint, options);
         // 编码字节在完成解码后不再需要，尽早释放以降低峰值内存
         drop(raw_bytes);
 
@@ -51,73 +59,300 @@ impl ImageHandler {
             Self::validate_decoded_memory_limits(config, raw_width, raw_height)?;
         }
 
-        let optimized = Self::maybe_downscale_for_clipboard(decoded, config)?;
-        let (width, height) = optimized.dimensions();
+        let transformed = Self::apply_output_geometry(decoded, config, options)?;
+        // 显式缩放以调用方给定的尺寸为准，不再叠加自适应降采样
+        let optimized = if options.has_explicit_resize() {
+            transformed
+        } else {
+            Self::maybe_downscale_for_clipboard(transformed, config)?
+        };
+        let prepared = Self::finish_for_clipboard(optimized, options, original)?;
+
+        log::info!(
+            "{}",
+            message(
+                "image_log.decoded",
+                &[
+                    ("source", &source_hint),
+                    ("raw_width", &raw_width),
+                    ("raw_height", &raw_height),
+                    ("width", &prepared.width),
+                    ("height", &prepared.height),
+                ],
+            )
+        );
+
+        Ok(prepared)
+    }
+
+    /// 对几何变换后的图像合成背景色、按输出编码生成字节，并校验像素长度。
+    ///
+    /// 位图解码与 SVG 栅格化共用。
+    pub(super) fn finish_for_clipboard(
+        image: DynamicImage,
+        options: &ImageOutputOptions,
+        original: Option<EncodedClipboardImage>,
+    ) -> Result<PreparedClipboardImage, ImageError> {
+        let (width, height) = image.dimensions();
 
         // into_rgba8 对已经是 RGBA8 的图像零拷贝移动，避免 to_rgba8 的深拷贝
-        let rgba = optimized.into_rgba8();
+        let mut rgba = image.into_rgba8();
+        if let Some(background) = options.effective_background()? {
+            output::flatten_alpha(&mut rgba, background);
+        }
+        let encoded = Self::encode_for_output(&rgba, options)?;
         let bytes = rgba.into_raw();
 
         let expected_len = (width as usize)
             .checked_mul(height as usize)
             .and_then(|pixels| pixels.checked_mul(4))
-            .ok_or_else(|| ImageError::ResourceLimit("图片尺寸导致内存溢出风险".to_string()))?;
+            .ok_or_else(|| ImageError::ResourceLimit(message("image.dimensions_overflow", &[])))?;
 
         if bytes.len() != expected_len {
-            return Err(ImageError::Decode("解码后像素数据长度异常".to_string()));
+            return Err(ImageError::Decode(message("image.decoded_length_mismatch", &[])));
         }
 
-        log::info!(
-            "✅ 图片解码成功 - 来源: {} 原始尺寸: {}x{} 输出尺寸: {}x{}",
-            source_hint,
-            raw_width,
-            raw_height,
-            width,
-            height
-        );
-
         Ok(PreparedClipboardImage {
             width: width as usize,
             height: height as usize,
             bytes,
+            encoded,
+            dpi: options.dpi,
+            original,
         })
     }
 
+    /// 需要随位图一并提供的原始编码字节。
+    ///
+    /// 仅在来源为文件/下载且未指定输出选项时保留：输出选项会改变像素，原始字节不再等价。
+    fn preserved_original(
+        bytes: &[u8],
+        source_hint: &str,
+        options: &ImageOutputOptions,
+    ) -> Option<EncodedClipboardImage> {
+        if !options.is_passthrough() || !matches!(source_hint, "file" | "url") {
+            return None;
+        }
+        let format = PreservedImageFormat::sniff(bytes)?;
+        Some(EncodedClipboardImage { mime: format.mime(), bytes: bytes.to_vec() })
+    }
+
+    /// 按输出选项依次执行裁剪、旋转与显式缩放。
+    ///
+    /// 裁剪坐标基于原图；显式缩放的目标尺寸同样受像素/内存上限约束。
+    pub(super) fn apply_output_geometry(
+        image: DynamicImage,
+        config: &ImageConfig,
+        options: &ImageOutputOptions,
+    ) -> Result<DynamicImage, ImageError> {
+        if options.is_passthrough() {
+            return Ok(image);
+        }
+
+        let mut image = image;
+        if let Some(crop) = options.crop {
+            let (width, height) = image.dimensions();
+            let fits = crop.x.checked_add(crop.width).is_some_and(|right| right <= width)
+                && crop.y.checked_add(crop.height).is_some_and(|bottom| bottom <= height);
+            if !fits {
+                return Err(ImageError::InvalidFormat(message(
+                    "image.crop_out_of_bounds",
+                    &[
+                        ("x", &crop.x),
+                        ("y", &crop.y),
+                        ("crop_width", &crop.width),
+                        ("crop_height", &crop.height),
+                        ("width", &width),
+                        ("height", &height),
+                    ],
+                )));
+            }
+            image = image.crop_imm(crop.x, crop.y, crop.width, crop.height);
+        }
+
+        image = match options.rotation {
+            90 => image.rotate90(),
+            180 => image.rotate180(),
+            270 => image.rotate270(),
+            _ => image,
+        };
+
+        let (width, height) = image.dimensions();
+        let Some((target_width, target_height)) = options.target_size(width, height) else {
+            return Ok(image);
+        };
+        if (target_width, target_height) == (width, height) {
+            return Ok(image);
+        }
+        Self::validate_pixel_limits(config, target_width, target_height)?;
+        Self::validate_decoded_memory_limits(config, target_width, target_height)?;
+
+        log::info!(
+            "{}",
+            message(
+                "image_log.explicit_resize",
+                &[
+                    ("width", &width),
+                    ("height", &height),
+                    ("target_width", &target_width),
+                    ("target_height", &target_height),
+                    ("filter", &format!("{:?}", config.resize_filter)),
+                ],
+            )
+        );
+
+        match Self::resize_with_fast_image_resize(&image, target_width, target_height, config.resize_filter) {
+            Ok(resized) => Ok(resized),
+            Err(err) => {
+                log::warn!(
+                    "{}",
+                    message("image_log.explicit_resize_fallback", &[("detail", &err)])
+                );
+                Ok(image.resize_exact(target_width, target_height, config.resize_filter))
+            }
+        }
+    }
+
+    /// 按输出编码生成编码字节；位图输出返回 `None`。
+    fn encode_for_output(
+        rgba: &RgbaImage,
+        options: &ImageOutputOptions,
+    ) -> Result<Option<EncodedClipboardImage>, ImageError> {
+        let Some(mime) = options.encoding.mime() else {
+            return Ok(None);
+        };
+        let bytes = match options.encoding {
+            OutputEncoding::Jpeg => output::encode_jpeg(
+                rgba,
+                options.jpeg_quality.unwrap_or(DEFAULT_JPEG_QUALITY),
+                options.dpi,
+            )?,
+            _ => output::encode_png(rgba.width(), rgba.height(), rgba.as_raw(), options.dpi)?,
+        };
+        Ok(Some(EncodedClipboardImage { mime, bytes }))
+    }
+
+    /// 校验头部尺寸后完整解码，宽或高为 0 时报错。
+    ///
+    /// 缩略图与图片编辑共用，先检查像素/内存上限再分配解码缓冲。
+    pub(crate) fn decode_with_limits(
+        raw: RawImageData,
+        config: &ImageConfig,
+    ) -> Result<DynamicImage, ImageError> {
+        let (header_width, header_height) = Self::inspect_dimensions_from_memory(&raw.bytes)?;
+        Self::validate_pixel_limits(config, header_width, header_height)?;
+        Self::validate_decoded_memory_limits(config, header_width, header_height)?;
+
+        let decoded = codecs::decode(&raw.bytes)?;
+        drop(raw);
+
+        if decoded.width() == 0 || decoded.height() == 0 {
+            return Err(ImageError::Decode(message("image.zero_dimension_scale", &[])));
+        }
+        Ok(decoded)
+    }
+
+    /// 将下载的原始字节整理为可落盘的编码，返回字节与扩展名。
+    ///
+    /// GIF / WebP / PNG / JPEG 校验头部尺寸与像素上限后保留原始字节（动图与元数据不丢失，
+    /// 照片也不会因转码 PNG 而体积膨胀），其他可解码格式完整解码后转码为 PNG，
+    /// 与剪贴板截图的落盘格式一致。
+    pub(crate) fn encode_for_storage(
+        raw: RawImageData,
+        config: &ImageConfig,
+    ) -> Result<(Vec<u8>, &'static str), ImageError> {
+        // JPEG 不属于复制时保留的格式，仅在入库时沿用原始字节
+        let extension = PreservedImageFormat::sniff(&raw.bytes)
+            .map(PreservedImageFormat::extension)
+            .or_else(|| (image::guess_format(&raw.bytes).ok() == Some(image::ImageFormat::Jpeg)).then_some("jpg"));
+        if let Some(extension) = extension {
+            let (width, height) = Self::inspect_dimensions_from_memory(&raw.bytes)?;
+            if width == 0 || height == 0 {
+                return Err(ImageError::Decode(message("image.zero_dimension", &[])));
+            }
+            Self::validate_pixel_limits(config, width, height)?;
+            return Ok((raw.bytes.to_vec(), extension));
+        }
+
+        let decoded = Self::decode_with_limits(raw, config)?;
+        let mut png = Vec::new();
+        decoded
+            .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+            .map_err(|e| ImageError::Decode(message("image.png_encode_failed", &[("detail", &e)])))?;
+        Ok((png, "png"))
+    }
+
+    /// 将原始字节解码并等比缩放为缩略图（长边不超过 `max_edge`，不放大）。
+    ///
+    /// 与剪贴板链路共享像素/内存上限校验与 `fast_image_resize` 缩放路径。
+    /// 该函数为纯计算函数，可安全在 `spawn_blocking` 中调用。
+    pub(crate) fn render_thumbnail(
+        raw: RawImageData,
+        max_edge: u32,
+        config: &ImageConfig,
+    ) -> Result<DynamicImage, ImageError> {
+        let decoded = Self::decode_with_limits(raw, config)?;
+
+        let (width, height) = decoded.dimensions();
+        if width <= max_edge && height <= max_edge {
+            return Ok(decoded);
+        }
+
+        let scale = (max_edge as f64 / width as f64).min(max_edge as f64 / height as f64);
+        let target_width = ((width as f64 * scale).round() as u32).max(1);
+        let target_height = ((height as f64 * scale).round() as u32).max(1);
+
+        match Self::resize_with_fast_image_resize(&decoded, target_width, target_height, config.resize_filter) {
+            Ok(resized) => Ok(resized),
+            Err(err) => {
+                log::warn!(
+                    "{}",
+                    message("image_log.thumbnail_resize_fallback", &[("detail", &err)])
+                );
+                Ok(decoded.resize_exact(target_width, target_height, config.resize_filter))
+            }
+        }
+    }
+
+    /// 将原始字节解码为用于颜色分析的小尺寸 RGBA 缓冲（长边不超过 `max_edge`）。
+    ///
+    /// 复用缩略图路径的像素/内存上限校验，调用方只关心像素统计，不关心画质。
+    pub(crate) fn render_analysis_rgba(
+        raw: RawImageData,
+        max_edge: u32,
+        config: &ImageConfig,
+    ) -> Result<image::RgbaImage, ImageError> {
+        Self::render_thumbnail(raw, max_edge, config).map(DynamicImage::into_rgba8)
+    }
+
     /// 仅通过内存中的图片头信息读取宽高。
     ///
     /// 用于在完整解码前做像素限制检查。
     fn inspect_dimensions_from_memory(bytes: &[u8]) -> Result<(u32, u32), ImageError> {
-        let cursor = Cursor::new(bytes);
-        let reader = ImageReader::new(cursor)
-            .with_guessed_format()
-            .map_err(|e| ImageError::InvalidFormat(format!("无法识别图片格式：{}", e)))?;
-
-        reader
-            .into_dimensions()
-            .map_err(|e| ImageError::InvalidFormat(format!("无法读取图片尺寸：{}", e)))
+        codecs::read_dimensions(bytes)
     }
 
     /// 校验像素数量是否超过配置上限。
-    fn validate_pixel_limits(
+    pub(super) fn validate_pixel_limits(
         config: &ImageConfig,
         width: u32,
         height: u32,
     ) -> Result<(), ImageError> {
         let pixels = (width as u64)
             .checked_mul(height as u64)
-            .ok_or_else(|| ImageError::ResourceLimit("图片像素数溢出".to_string()))?;
+            .ok_or_else(|| ImageError::ResourceLimit(message("image.pixel_count_overflow", &[])))?;
 
         if pixels > config.max_decoded_pixels {
-            return Err(ImageError::ResourceLimit(format!(
-                "图片像素过大：{} 像素（限制：{} 像素）",
-                pixels, config.max_decoded_pixels
+            return Err(ImageError::ResourceLimit(message(
+                "image.too_many_pixels",
+                &[("pixels", &pixels), ("limit", &config.max_decoded_pixels)],
             )));
         }
 
         Ok(())
     }
 
-    fn validate_decoded_memory_limits(
+    pub(super) fn validate_decoded_memory_limits(
         config: &ImageConfig,
         width: u32,
         height: u32,
@@ -125,13 +360,12 @@ impl ImageHandler {
         let estimated = (width as u64)
             .checked_mul(height as u64)
             .and_then(|pixels| pixels.checked_mul(4))
-            .ok_or_else(|| ImageError::ResourceLimit("图片解码内存估算溢出".to_string()))?;
+            .ok_or_else(|| ImageError::ResourceLimit(message("image.decoded_size_overflow", &[])))?;
 
         if estimated > config.max_decoded_bytes {
-            return Err(ImageError::ResourceLimit(format!(
-                "图片解码预计内存过大：{:.2} MB（限制：{:.2} MB）",
-                estimated as f64 / 1024.0 / 1024.0,
-                config.max_decoded_bytes as f64 / 1024.0 / 1024.0
+            return Err(ImageError::ResourceLimit(message(
+                "image.decoded_too_large",
+                &[("size", &format_mb(estimated)), ("limit", &format_mb(config.max_decoded_bytes))],
             )));
         }
 
@@ -151,12 +385,12 @@ impl ImageHandler {
 
         let (width, height) = image.dimensions();
         if width == 0 || height == 0 {
-            return Err(ImageError::Decode("图片宽或高为 0，无法计算缩放比".to_string()));
+            return Err(ImageError::Decode(message("image.zero_dimension_scale", &[])));
         }
 
         let source_pixels = (width as u64)
             .checked_mul(height as u64)
-            .ok_or_else(|| ImageError::ResourceLimit("图片像素数溢出".to_string()))?;
+            .ok_or_else(|| ImageError::ResourceLimit(message("image.pixel_count_overflow", &[])))?;
 
         let over_dimension = width > config.clipboard_max_dimension
             || height > config.clipboard_max_dimension;
@@ -173,19 +407,24 @@ impl ImageHandler {
         let scale = dimension_scale.min(pixel_scale).min(1.0);
 
         if scale <= 0.0 {
-            return Err(ImageError::ResourceLimit("缩放比例计算异常".to_string()));
+            return Err(ImageError::ResourceLimit(message("image.bad_scale", &[])));
         }
 
         let target_width = ((width as f64 * scale).floor() as u32).max(1);
         let target_height = ((height as f64 * scale).floor() as u32).max(1);
 
         log::info!(
-            "🧩 自适应降采样：{}x{} -> {}x{}（filter={:?}）",
-            width,
-            height,
-            target_width,
-            target_height,
-            config.resize_filter
+            "{}",
+            message(
+                "image_log.adaptive_resize",
+                &[
+                    ("width", &width),
+                    ("height", &height),
+                    ("target_width", &target_width),
+                    ("target_height", &target_height),
+                    ("filter", &format!("{:?}", config.resize_filter)),
+                ],
+            )
         );
 
         match Self::resize_with_fast_image_resize(&image, target_width, target_height, config.resize_filter)
@@ -193,8 +432,8 @@ impl ImageHandler {
             Ok(resized) => Ok(resized),
             Err(err) => {
                 log::warn!(
-                    "⚠️ fast_image_resize 降采样失败，回退 image::resize_exact：{}",
-                    err
+                    "{}",
+                    message("image_log.adaptive_resize_fallback", &[("detail", &err)])
                 );
                 Ok(image.resize_exact(
                     target_width,
@@ -205,7 +444,7 @@ impl ImageHandler {
         }
     }
 
-    fn resize_with_fast_image_resize(
+    pub(super) fn resize_with_fast_image_resize(
         image: &DynamicImage,
         target_width: u32,
         target_height: u32,
@@ -220,7 +459,7 @@ impl ImageHandler {
             src.into_raw(),
             fr::PixelType::U8x4,
         )
-        .map_err(|e| ImageError::Decode(format!("构建源图像缓冲失败：{}", e)))?;
+        .map_err(|e| ImageError::Decode(message("image.resize_source_buffer_failed", &[("detail", &e)])))?;
 
         let mut dst_image = fr::images::Image::new(target_width, target_height, fr::PixelType::U8x4);
 
@@ -231,14 +470,14 @@ impl ImageHandler {
 
         resizer
             .resize(&src_image, &mut dst_image, Some(&options))
-            .map_err(|e| ImageError::Decode(format!("fast_image_resize 执行失败：{}", e)))?;
+            .map_err(|e| ImageError::Decode(message("image.resize_failed", &[("detail", &e)])))?;
 
         let rgba = ImageBuffer::<Rgba<u8>, Vec<u8>>::from_raw(
             target_width,
             target_height,
             dst_image.into_vec(),
         )
-        .ok_or_else(|| ImageError::Decode("fast_image_resize 输出缓冲长度异常".to_string()))?;
+        .ok_or_else(|| ImageError::Decode(message("image.resize_output_length_mismatch", &[])))?;
 
         Ok(DynamicImage::ImageRgba8(rgba))
     }
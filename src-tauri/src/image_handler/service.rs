@@ -11,24 +11,40 @@
 //! ## 实现思路
 //!
 //! 对外仅暴露少量稳定 API：
-//! - `process_source`：执行完整图片处理链路
-//! - `process_url_with_progress`：带进度事件的 URL 处理
-//! - `cancel_download`：取消正在进行的下载
+//! - `process_source`：执行完整图片处理链路（可附带输出选项）
+//! - `process_url_with_progress`：带进度事件的 URL 处理（经下载队列调度）
+//! - `download_url_for_storage`：带进度事件地下载 URL 图片并整理为可落盘编码（保存到历史）
+//! - `cancel_download` / `pause_download` / `retry_download`：取消、暂停、重试下载任务
+//! - `list_downloads`：列出排队、进行中与已结束的下载任务
+//! - `download_cache_info` / `clear_download_cache`：查看/清空 URL 下载缓存
 //! - `set/get_performance_profile`：切换/读取性能档位
 //! - `set/get_advanced_config`：设置/读取高级参数
+//! - `get_thumbnail`：获取（必要时生成）本地图片缩略图
+//! - `fetch_link_preview`：抓取网页头部并提取链接预览
 //!
 //! ## 架构细节
 //!
-//! - `ProgressReporter`：封装进度节流、事件发射和最终状态汇总
-//! - `CancelFlagGuard`：RAII 守卫，确保取消标志在 panic / 提前返回时自动清理
+//! - `DownloadManager`：并发上限、优先级与同 URL 合并；传输在后台任务中驱动，
+//!   请求方只等待结果，因此请求被取消或提前退出不影响合并在同一传输上的其他请求
+//! - `ProgressReporter`：按传输节流进度事件，并发射给该传输上的全部请求
+//! - `emit_final`：按请求汇总最终状态事件
 
-use std::collections::HashMap;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::future::Future;
+use std::path::Path;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
-use super::{ImageAdvancedConfig, ImageConfig, ImageError, ImageHandler, ImagePerformanceProfile, ImageSource};
-use tauri::{AppHandle, Emitter, Wry};
+use bytes::Bytes;
+use super::download_cache::{DownloadCacheHit, DownloadCacheInfo};
+use super::download_manager::{DownloadManager, DownloadTaskInfo, RetryAction};
+use super::thumbnail::{self, ThumbnailService};
+use super::{
+    ImageAdvancedConfig, ImageConfig, ImageEditOp, ImageError, ImageHandler, ImageOutputOptions, ImagePerformanceProfile,
+    ImageSource, LinkPreview,
+};
+use tauri::{AppHandle, Emitter, Manager, Wry};
+
+use crate::i18n::message;
 
 pub const IMAGE_DOWNLOAD_PROGRESS_EVENT: &str = "image-download-progress";
 
@@ -52,6 +68,8 @@ pub struct ImageDownloadProgressPayload {
     pub stage: Option<&'static str>,
     pub error_code: Option<&'static str>,
     pub error_message: Option<String>,
+    /// 完成事件中标记命中的缓存层级：`memory` / `disk` / `revalidated` / `stale`。
+    pub cache_hit: Option<&'static str>,
 }
 
 // ─── 进度节流状态（ProgressReporter 内部） ─────────────────────────
@@ -108,62 +126,90 @@ impl ThrottleState {
 
 // ─── ProgressReporter ──────────────────────────────────────────────
 
-/// 下载进度事件发射器。
+/// 将已下载量/总量映射为 0-100 百分比。
+fn compute_percent(status: &str, downloaded: u64, total: Option<u64>) -> u8 {
+    if status == "completed" {
+        return 100;
+    }
+    match total {
+        Some(0) | None => 0,
+        Some(t) => (downloaded.saturating_mul(100) / t).min(100) as u8,
+    }
+}
+
+/// 构造一条不含错误信息的进度事件。
+fn progress_payload(
+    request_id: &str,
+    status: &'static str,
+    downloaded: u64,
+    total: Option<u64>,
+) -> ImageDownloadProgressPayload {
+    ImageDownloadProgressPayload {
+        request_id: request_id.to_owned(),
+        progress: compute_percent(status, downloaded, total),
+        downloaded_bytes: downloaded,
+        total_bytes: total,
+        status,
+        stage: None,
+        error_code: None,
+        error_message: None,
+        cache_hit: None,
+    }
+}
+
+fn emit_payload(app: &AppHandle<Wry>, payload: ImageDownloadProgressPayload) {
+    let _ = app.emit(IMAGE_DOWNLOAD_PROGRESS_EVENT, payload);
+}
+
+/// 发射最终状态事件（completed / cancelled / failed）。
+fn emit_final(
+    app: &AppHandle<Wry>,
+    request_id: &str,
+    result: &Result<Option<DownloadCacheHit>, ImageError>,
+    downloaded: u64,
+    total: Option<u64>,
+) {
+    let payload = match result {
+        Ok(cache_hit) => {
+            let downloaded = downloaded.max(1);
+            ImageDownloadProgressPayload {
+                cache_hit: cache_hit.map(DownloadCacheHit::as_str),
+                ..progress_payload(request_id, "completed", downloaded, total.or(Some(downloaded)))
+            }
+        }
+        Err(ImageError::Cancelled(_)) => ImageDownloadProgressPayload {
+            error_code: Some("E_CANCELLED"),
+            ..progress_payload(request_id, "cancelled", downloaded, total)
+        },
+        Err(err) => ImageDownloadProgressPayload {
+            stage: Some(err.stage()),
+            error_code: Some(err.code()),
+            error_message: Some(err.to_string()),
+            ..progress_payload(request_id, "failed", downloaded, total)
+        },
+    };
+    emit_payload(app, payload);
+}
+
+/// 传输进度事件发射器。
 ///
-/// 封装节流逻辑与事件序列化，使 `process_url_with_progress` 方法体保持简洁。
+/// 每个传输一份节流状态；合并在同一传输上的请求收到相同的进度。
 struct ProgressReporter<'a> {
-    request_id: &'a str,
     app: &'a AppHandle<Wry>,
     throttle: Mutex<ThrottleState>,
 }
 
 impl<'a> ProgressReporter<'a> {
-    fn new(request_id: &'a str, app: &'a AppHandle<Wry>) -> Self {
+    fn new(app: &'a AppHandle<Wry>) -> Self {
         Self {
-            request_id,
             app,
             throttle: Mutex::new(ThrottleState::new()),
         }
     }
 
-    /// 将已下载量/总量映射为 0-100 百分比。
-    fn compute_percent(status: &str, downloaded: u64, total: Option<u64>) -> u8 {
-        if status == "completed" {
-            return 100;
-        }
-        match total {
-            Some(0) | None => 0,
-            Some(t) => (downloaded.saturating_mul(100) / t).min(100) as u8,
-        }
-    }
-
-    /// 发射一条原始进度事件（不节流）。
-    fn emit_raw(
-        &self,
-        status: &'static str,
-        downloaded: u64,
-        total: Option<u64>,
-        stage: Option<&'static str>,
-        error_code: Option<&'static str>,
-        error_message: Option<String>,
-    ) {
-        let progress = Self::compute_percent(status, downloaded, total);
-        let payload = ImageDownloadProgressPayload {
-            request_id: self.request_id.to_owned(),
-            progress,
-            downloaded_bytes: downloaded,
-            total_bytes: total,
-            status,
-            stage,
-            error_code,
-            error_message,
-        };
-        let _ = self.app.emit(IMAGE_DOWNLOAD_PROGRESS_EVENT, payload);
-    }
-
-    /// 发射 downloading 事件（带节流）。
-    fn emit_downloading(&self, downloaded: u64, total: Option<u64>) {
-        let progress = Self::compute_percent("downloading", downloaded, total);
+    /// 向传输上的全部请求发射 downloading 事件（带节流）。
+    fn emit_downloading(&self, request_ids: &[String], downloaded: u64, total: Option<u64>) {
+        let progress = compute_percent("downloading", downloaded, total);
 
         let mut guard = match self.throttle.lock() {
             Ok(g) => g,
@@ -175,96 +221,50 @@ impl<'a> ProgressReporter<'a> {
         guard.update(progress, downloaded, total);
         drop(guard);
 
-        self.emit_raw("downloading", downloaded, total, None, None, None);
-    }
-
-    /// 读取最后一次记录的进度快照。
-    fn last_snapshot(&self) -> (u64, Option<u64>) {
-        match self.throttle.lock() {
-            Ok(g) => (g.last_downloaded, g.last_total),
-            Err(_) => (0, None),
-        }
-    }
-
-    /// 发射最终状态事件（completed / cancelled / failed）。
-    fn emit_final(&self, result: &Result<(), ImageError>) {
-        let (last_downloaded, last_total) = self.last_snapshot();
-
-        match result {
-            Ok(()) => {
-                let completed_total = last_total.or(Some(last_downloaded.max(1)));
-                self.emit_raw(
-                    "completed",
-                    last_downloaded.max(1),
-                    completed_total,
-                    None,
-                    None,
-                    None,
-                );
-            }
-            Err(ImageError::Cancelled(_)) => {
-                self.emit_raw(
-                    "cancelled",
-                    last_downloaded,
-                    last_total,
-                    None,
-                    Some("E_CANCELLED"),
-                    None,
-                );
-            }
-            Err(err) => {
-                self.emit_raw(
-                    "failed",
-                    last_downloaded,
-                    last_total,
-                    Some(err.stage()),
-                    Some(err.code()),
-                    Some(err.to_string()),
-                );
-            }
+        for request_id in request_ids {
+            emit_payload(self.app, progress_payload(request_id, "downloading", downloaded, total));
         }
     }
 }
 
-// ─── CancelFlagGuard ───────────────────────────────────────────────
-
-/// RAII 守卫：在作用域结束时自动从 `cancel_flags` 中移除对应条目。
+/// 驱动一次 URL 传输：排队占用并发名额 → 下载 → 发布结果。
 ///
-/// 防止 panic 或提前 return 导致取消标志泄漏。
-struct CancelFlagGuard<'a> {
-    cancel_flags: &'a Mutex<HashMap<String, Arc<AtomicBool>>>,
-    request_id: String,
-}
+/// 传输被暂停时释放名额并等待恢复后重新下载；其上任务全部取消时以 `Cancelled` 结束。
+async fn drive_transfer(
+    handler: ImageHandler,
+    downloads: Arc<DownloadManager>,
+    app: AppHandle<Wry>,
+    url: String,
+    config: ImageConfig,
+) {
+    let publisher = downloads.publisher(&url);
+    let reporter = ProgressReporter::new(&app);
+
+    let result = loop {
+        let slot = match downloads.acquire_slot(&url, config.max_concurrent_downloads).await {
+            Ok(slot) => slot,
+            Err(err) => break Err(err),
+        };
+        let result = handler
+            .download_url(
+                &url,
+                &config,
+                |downloaded, total| {
+                    let request_ids = downloads.record_progress(&url, downloaded, total);
+                    reporter.emit_downloading(&request_ids, downloaded, total);
+                },
+                || downloads.is_interrupted(&url),
+            )
+            .await;
+        drop(slot);
 
-impl<'a> CancelFlagGuard<'a> {
-    /// 注册取消标志并返回守卫 + 共享标志引用。
-    fn register(
-        cancel_flags: &'a Mutex<HashMap<String, Arc<AtomicBool>>>,
-        request_id: String,
-    ) -> Result<(Self, Arc<AtomicBool>), ImageError> {
-        let flag = Arc::new(AtomicBool::new(false));
-        {
-            let mut guard = cancel_flags
-                .lock()
-                .map_err(|_| ImageError::ResourceLimit("下载取消标志锁已中毒".to_string()))?;
-            guard.insert(request_id.clone(), Arc::clone(&flag));
+        match result {
+            Err(ImageError::Cancelled(_)) if downloads.is_paused(&url) => continue,
+            other => break other,
         }
-        Ok((
-            Self {
-                cancel_flags,
-                request_id,
-            },
-            flag,
-        ))
-    }
-}
+    };
 
-impl Drop for CancelFlagGuard<'_> {
-    fn drop(&mut self) {
-        if let Ok(mut guard) = self.cancel_flags.lock() {
-            guard.remove(&self.request_id);
-        }
-    }
+    publisher.publish(result);
 }
 
 // ─── ImageServiceState ─────────────────────────────────────────────
@@ -274,7 +274,8 @@ impl Drop for CancelFlagGuard<'_> {
 /// 作为 Tauri `State` 注入到命令层，内部持有 `ImageHandler`。
 pub struct ImageServiceState {
     handler: ImageHandler,
-    cancel_flags: Mutex<HashMap<String, Arc<AtomicBool>>>,
+    downloads: Arc<DownloadManager>,
+    thumbnails: ThumbnailService,
 }
 
 impl ImageServiceState {
@@ -308,76 +309,159 @@ impl ImageServiceState {
         let handler = ImageHandler::new(config)?;
         Ok(Self {
             handler,
-            cancel_flags: Mutex::new(HashMap::new()),
+            downloads: Arc::new(DownloadManager::new()),
+            thumbnails: ThumbnailService::new(),
         })
     }
 
-    /// 执行完整处理流程：加载→解码→写入剪贴板。
+    /// 执行完整处理流程：加载→解码→（按输出选项变换与编码）→写入剪贴板。
     ///
     /// # 示例
     /// ```rust,no_run
-    /// use clipboard_history::image_handler::{ImageServiceState, ImageSource};
+    /// use clipboard_history::image_handler::{ImageOutputOptions, ImageServiceState, ImageSource};
     ///
     /// # async fn demo() -> Result<(), clipboard_history::image_handler::ImageError> {
     /// let service = ImageServiceState::new()?;
     /// service
-    ///     .process_source(ImageSource::Url("https://example.com/a.png".into()))
+    ///     .process_source(ImageSource::Url("https://example.com/a.png".into()), ImageOutputOptions::default())
     ///     .await?;
     /// # Ok(())
     /// # }
     /// ```
-    pub async fn process_source(&self, source: ImageSource) -> Result<(), ImageError> {
-        self.handler.process_and_copy(source).await
+    pub async fn process_source(
+        &self,
+        source: ImageSource,
+        options: ImageOutputOptions,
+    ) -> Result<(), ImageError> {
+        self.handler.process_and_copy(source, options).await
     }
 
     /// 带进度上报的 URL 下载处理流程。
     ///
-    /// 进度通过 Tauri 事件发射到前端，节流策略避免高频发射。
-    /// 取消标志使用 RAII 守卫管理，确保异常路径不泄漏。
+    /// 下载经队列调度（见 `run_url_request`），完成后按本请求的输出选项解码并写入剪贴板。
     pub async fn process_url_with_progress(
         &self,
         app: &AppHandle<Wry>,
         request_id: String,
         url: String,
+        options: ImageOutputOptions,
+        priority: i32,
     ) -> Result<(), ImageError> {
-        let (_cancel_guard, cancel_flag) =
-            CancelFlagGuard::register(&self.cancel_flags, request_id.clone())?;
+        // 选项非法时在排队前拒绝，避免无谓的下载
+        options.validate()?;
+        self.run_url_request(app, request_id, url, Some(options.clone()), priority, |bytes, config| async move {
+            self.handler.copy_downloaded_url(bytes, options, &config).await
+        })
+        .await
+    }
 
-        let reporter = ProgressReporter::new(&request_id, app);
-        reporter.emit_downloading(0, None);
+    /// 带进度上报地下载 URL 图片，整理为可落盘的编码（保存到历史用）。
+    ///
+    /// 与 `process_url_with_progress` 共用下载队列、安全校验、缓存与进度事件；
+    /// 返回编码字节与文件扩展名。
+    pub async fn download_url_for_storage(
+        &self,
+        app: &AppHandle<Wry>,
+        request_id: String,
+        url: String,
+        priority: i32,
+    ) -> Result<(Vec<u8>, &'static str), ImageError> {
+        self.run_url_request(app, request_id, url, None, priority, |bytes, config| async move {
+            self.handler.prepare_downloaded_for_storage(bytes, &config).await
+        })
+        .await
+    }
 
-        let result = self
-            .handler
-            .process_url_and_copy_with_progress(
-                &url,
-                |downloaded, total| reporter.emit_downloading(downloaded, total),
-                || cancel_flag.load(Ordering::SeqCst),
-            )
-            .await;
+    /// URL 请求的公共流程：登记到下载队列 → 等待传输 → 执行后续处理 → 发射最终事件。
+    ///
+    /// 同一 URL 已在排队或传输中时合并为一次传输，否则新建传输并在后台按并发上限与
+    /// 优先级调度。进度通过 Tauri 事件发射到前端，节流策略避免高频发射。
+    async fn run_url_request<T, F, Fut>(
+        &self,
+        app: &AppHandle<Wry>,
+        request_id: String,
+        url: String,
+        options: Option<ImageOutputOptions>,
+        priority: i32,
+        process: F,
+    ) -> Result<T, ImageError>
+    where
+        F: FnOnce(Bytes, ImageConfig) -> Fut,
+        Fut: Future<Output = Result<T, ImageError>>,
+    {
+        let config = self.handler.config_snapshot()?;
+        let (task, registration) = self.downloads.register(&request_id, &url, priority, options)?;
+
+        emit_payload(app, progress_payload(&request_id, "downloading", 0, None));
+        if registration.starts_transfer {
+            tauri::async_runtime::spawn(drive_transfer(
+                self.handler.clone(),
+                Arc::clone(&self.downloads),
+                app.clone(),
+                url,
+                config.clone(),
+            ));
+        }
 
-        // 显式 drop 守卫：在发射最终事件前移除取消标志，
-        // 使 cancel_download 在下载已结束后正确返回 false。
-        drop(_cancel_guard);
+        let result = async {
+            let (bytes, cache_hit) = self
+                .downloads
+                .wait_transfer(&request_id, registration.result_rx)
+                .await?;
+            self.downloads.ensure_not_cancelled(&request_id)?;
+            self.downloads.set_stage(&request_id, "decode");
+            let value = process(bytes, config).await?;
+            Ok((value, cache_hit))
+        }
+        .await;
 
-        reporter.emit_final(&result);
-        result
+        let outcome = result.as_ref().map(|(_, cache_hit)| *cache_hit).map_err(Clone::clone);
+        let (downloaded, total) = task.finish(&outcome);
+        emit_final(app, &request_id, &outcome, downloaded, total);
+        result.map(|(value, _)| value)
     }
 
     /// 请求取消指定下载。
     ///
-    /// 若目标 request_id 存在且仍在下载中，标记取消并返回 `true`。
+    /// 若目标 request_id 存在且仍在进行中，标记取消并返回 `true`。
+    /// 合并在同一传输上的其他请求不受影响。
     pub fn cancel_download(&self, request_id: &str) -> Result<bool, ImageError> {
-        let guard = self
-            .cancel_flags
-            .lock()
-            .map_err(|_| ImageError::ResourceLimit("下载取消标志锁已中毒".to_string()))?;
-
-        if let Some(flag) = guard.get(request_id) {
-            flag.store(true, Ordering::SeqCst);
-            Ok(true)
-        } else {
-            Ok(false)
-        }
+        self.downloads.cancel(request_id)
+    }
+
+    /// 暂停指定下载所在的传输（合并在同一传输上的请求一起暂停）。
+    ///
+    /// 运行中的传输会被中断并让出并发名额，恢复后重新下载。
+    pub fn pause_download(&self, request_id: &str) -> Result<bool, ImageError> {
+        self.downloads.pause(request_id)
+    }
+
+    /// 重试指定下载：已暂停的恢复排队，已失败/取消的以原参数重新发起。
+    ///
+    /// 重新发起的请求在后台执行，结果通过进度事件通知前端。
+    pub fn retry_download(&self, app: &AppHandle<Wry>, request_id: &str) -> Result<bool, ImageError> {
+        let spec = match self.downloads.retry(request_id)? {
+            RetryAction::Resumed => return Ok(true),
+            RetryAction::Restart(spec) => spec,
+            RetryAction::Unavailable => return Ok(false),
+        };
+
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let state = app.state::<ImageServiceState>();
+            if let Err(err) = state
+                .process_url_with_progress(&app, spec.request_id, spec.url, spec.options, spec.priority)
+                .await
+            {
+                log::warn!("{}", message("image_log.download_retry_failed", &[("detail", &err)]));
+            }
+        });
+        Ok(true)
+    }
+
+    /// 列出下载队列中的任务（排队、进行中与最近结束的任务）。
+    pub fn list_downloads(&self) -> Result<Vec<DownloadTaskInfo>, ImageError> {
+        self.downloads.list()
     }
 
     /// 设置性能档位。
@@ -420,6 +504,36 @@ impl ImageServiceState {
     pub fn get_advanced_config(&self) -> Result<ImageAdvancedConfig, ImageError> {
         self.handler.get_advanced_config()
     }
+
+    /// 获取本地图片的缩略图路径（按固定档位懒生成并缓存）。
+    pub async fn get_thumbnail(&self, path: &str, size: u32) -> Result<String, ImageError> {
+        let cache_dir = thumbnail::thumbnail_cache_dir()?;
+        let thumb = self
+            .thumbnails
+            .get_or_create(&self.handler, cache_dir, Path::new(path), size)
+            .await?;
+        Ok(thumb.to_string_lossy().to_string())
+    }
+
+    /// 抓取网页头部并提取链接预览（与图片下载共用 SSRF 防护、超时与重定向上限）。
+    pub async fn fetch_link_preview(&self, url: &str) -> Result<LinkPreview, ImageError> {
+        self.handler.fetch_link_preview(url).await
+    }
+
+    /// 查询 URL 下载磁盘缓存的条目数、占用字节与预算。
+    pub async fn download_cache_info(&self) -> Result<DownloadCacheInfo, ImageError> {
+        self.handler.download_cache_info().await
+    }
+
+    /// 清空 URL 下载缓存（内存与磁盘）。
+    pub async fn clear_download_cache(&self) -> Result<DownloadCacheInfo, ImageError> {
+        self.handler.clear_download_cache().await
+    }
+
+    /// 对本地图片应用编辑操作，返回编码后的 PNG 字节（不修改原文件）。
+    pub async fn edit_image_file(&self, path: &str, ops: Vec<ImageEditOp>) -> Result<Vec<u8>, ImageError> {
+        self.handler.render_edited_image(path, ops).await
+    }
 }
 
 #[cfg(test)]
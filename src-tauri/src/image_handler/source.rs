@@ -5,7 +5,9 @@
 //! 将“外部输入类型”和“流水线中间结果”解耦：
 //! - `ImageSource` 表示外部来源语义
 //! - `RawImageData` 表示已加载但未解码的字节（使用 `bytes::Bytes` 实现零拷贝缓存共享）
-//! - `PreparedClipboardImage` 表示可直接写入剪贴板的 RGBA 数据
+//! - `PreparedClipboardImage` 表示可直接写入剪贴板的 RGBA 数据（可附带按输出选项编码的字节，
+//!   以及来源文件/下载的原始编码字节）
+//! - `PreservedImageFormat` 表示需要保留原始编码的格式（动图 GIF/WebP、带元数据的 PNG）
 
 use bytes::Bytes;
 
@@ -17,6 +19,8 @@ pub enum ImageSource {
     Base64(String),
     /// 本地文件路径来源。
     FilePath(String),
+    /// 本地 SVG 文件路径来源（栅格化为位图，并附带 SVG 源文本）。
+    Svg(String),
 }
 
 /// 加载阶段输出：原始字节与来源标识。
@@ -38,4 +42,58 @@ pub(crate) struct PreparedClipboardImage {
     pub(crate) height: usize,
     /// RGBA 字节数组（`width * height * 4`）。
     pub(crate) bytes: Vec<u8>,
+    /// 按输出选项编码的字节（PNG / JPEG）；为 `None` 时写入平台原生位图。
+    pub(crate) encoded: Option<EncodedClipboardImage>,
+    /// 写入输出的 DPI 元数据。
+    pub(crate) dpi: Option<u32>,
+    /// 来源的原始编码字节（GIF / WebP / PNG），与位图一并提供，保留动画与元数据。
+    pub(crate) original: Option<EncodedClipboardImage>,
+}
+
+/// 已编码的图片字节及其 MIME 类型。
+pub(crate) struct EncodedClipboardImage {
+    /// MIME 类型（`image/png` / `image/jpeg` / `image/gif` / `image/webp`）。
+    pub(crate) mime: &'static str,
+    /// 编码后的字节。
+    pub(crate) bytes: Vec<u8>,
+}
+
+/// 需要保留原始编码字节的图片格式。
+///
+/// 解码为单帧 RGBA 会丢失 GIF/WebP 动画与 PNG 元数据，复制与入库时优先沿用原始字节。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreservedImageFormat {
+    Gif,
+    Webp,
+    Png,
+}
+
+impl PreservedImageFormat {
+    /// 按文件头识别格式；其他格式返回 `None`。
+    pub fn sniff(bytes: &[u8]) -> Option<Self> {
+        match image::guess_format(bytes).ok()? {
+            image::ImageFormat::Gif => Some(Self::Gif),
+            image::ImageFormat::WebP => Some(Self::Webp),
+            image::ImageFormat::Png => Some(Self::Png),
+            _ => None,
+        }
+    }
+
+    /// MIME 类型。
+    pub fn mime(self) -> &'static str {
+        match self {
+            Self::Gif => "image/gif",
+            Self::Webp => "image/webp",
+            Self::Png => "image/png",
+        }
+    }
+
+    /// 落盘使用的扩展名。
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Gif => "gif",
+            Self::Webp => "webp",
+            Self::Png => "png",
+        }
+    }
 }
@@ -0,0 +1,146 @@
+//! # SVG 栅格化模块
+//!
+//! ## 设计思路
+//!
+//! 聊天软件与办公软件大多只能粘贴位图，SVG 条目原先只能按文本或文件复制。
+//! `ImageSource::Svg` 将 SVG 渲染为位图写入剪贴板，同时以 `image/svg+xml`
+//! 附带 SVG 源文本，支持矢量的应用仍能取得原图。
+//!
+//! ## 实现思路
+//!
+//! - 使用纯 Rust 的 resvg 渲染（`svg` 特性）；未启用特性的构建返回 `UnsupportedFormat`
+//! - 输出尺寸：显式宽高优先（仅给一边时按比例），否则为 SVG 固有尺寸 × `dpi / 96`；
+//!   直接按目标尺寸渲染，矢量放大不失真
+//! - 分配画布前按目标尺寸校验像素/内存上限
+//! - 渲染结果为预乘 RGBA，反预乘后交给流水线继续裁剪、旋转、合成背景色与编码；
+//!   裁剪坐标基于栅格化后的位图
+//! - `<image>` 只接受 `data:` URL 内嵌的图片：本地路径与外链一律忽略，避免剪贴板里的
+//!   SVG 读取本机文件；系统字体只加载一次并在渲染间共享
+
+use image::{DynamicImage, RgbaImage};
+
+use crate::i18n::message;
+
+use super::output::ImageOutputOptions;
+use super::source::{EncodedClipboardImage, PreparedClipboardImage, RawImageData};
+use super::{ImageConfig, ImageError, ImageHandler};
+
+/// SVG 源文本的 MIME 类型（同时作为 Windows 剪贴板格式名）。
+pub(crate) const SVG_MIME: &str = "image/svg+xml";
+/// SVG 固有尺寸（CSS 像素）对应的 DPI。
+const CSS_DPI: f64 = 96.0;
+
+/// 根据 SVG 固有尺寸与输出选项计算栅格化尺寸。
+///
+/// 指定宽或高时按 `ImageOutputOptions::target_size` 计算；否则按 `dpi / 96` 缩放固有尺寸。
+#[cfg_attr(not(feature = "svg"), allow(dead_code))]
+pub(crate) fn raster_size(intrinsic: (f32, f32), options: &ImageOutputOptions) -> Result<(u32, u32), ImageError> {
+    let (width, height) = intrinsic;
+    if !(width.is_finite() && height.is_finite() && width > 0.0 && height > 0.0) {
+        return Err(ImageError::Decode(message("image.svg_invalid_size", &[("width", &width), ("height", &height)])));
+    }
+    let scale = options.dpi.map_or(1.0, |dpi| dpi as f64 / CSS_DPI);
+    let scaled = |value: f32| (value as f64 * scale).round().clamp(1.0, u32::MAX as f64) as u32;
+    let (base_width, base_height) = (scaled(width), scaled(height));
+    Ok(options.target_size(base_width, base_height).unwrap_or((base_width, base_height)))
+}
+
+impl ImageHandler {
+    /// 将 SVG 栅格化为可写入剪贴板的位图，并附带 SVG 源文本。
+    ///
+    /// 该函数为纯计算函数（不依赖 `&self`），可安全在 `spawn_blocking` 中调用。
+    pub(crate) fn rasterize_svg_for_clipboard(
+        raw: RawImageData,
+        config: &ImageConfig,
+        options: &ImageOutputOptions,
+    ) -> Result<PreparedClipboardImage, ImageError> {
+        let rendered = render_svg(&raw.bytes, config, options)?;
+        let (raster_width, raster_height) = rendered.dimensions();
+
+        // 已按目标尺寸渲染，几何变换只剩裁剪与旋转
+        let geometry = ImageOutputOptions { width: None, height: None, ..options.clone() };
+        let transformed = Self::apply_output_geometry(DynamicImage::ImageRgba8(rendered), config, &geometry)?;
+        let source = EncodedClipboardImage { mime: SVG_MIME, bytes: raw.bytes.to_vec() };
+        let prepared = Self::finish_for_clipboard(transformed, options, Some(source))?;
+
+        log::info!(
+            "{}",
+            message(
+                "image_log.svg_rasterized",
+                &[
+                    ("source", &raw.source_hint),
+                    ("raster_width", &raster_width),
+                    ("raster_height", &raster_height),
+                    ("width", &prepared.width),
+                    ("height", &prepared.height),
+                ],
+            )
+        );
+        Ok(prepared)
+    }
+}
+
+/// 解析 SVG 并按目标尺寸渲染为 RGBA（非预乘）。
+#[cfg(feature = "svg")]
+fn render_svg(bytes: &[u8], config: &ImageConfig, options: &ImageOutputOptions) -> Result<RgbaImage, ImageError> {
+    use resvg::{tiny_skia, usvg};
+
+    let parse_options = usvg::Options {
+        fontdb: font_database(),
+        image_href_resolver: usvg::ImageHrefResolver {
+            resolve_data: usvg::ImageHrefResolver::default_data_resolver(),
+            resolve_string: Box::new(|href: &str, _: &usvg::Options| {
+                log::debug!(
+                    "{}",
+                    message("image_log.svg_external_href_ignored", &[("href", &href)])
+                );
+                None
+            }),
+        },
+        ..usvg::Options::default()
+    };
+    let tree = usvg::Tree::from_data(bytes, &parse_options)
+        .map_err(|e| ImageError::InvalidFormat(message("image.svg_parse_failed", &[("detail", &e)])))?;
+
+    let size = tree.size();
+    let (width, height) = raster_size((size.width(), size.height()), options)?;
+    ImageHandler::validate_pixel_limits(config, width, height)?;
+    ImageHandler::validate_decoded_memory_limits(config, width, height)?;
+
+    let mut pixmap = tiny_skia::Pixmap::new(width, height)
+        .ok_or_else(|| ImageError::ResourceLimit(message("image.svg_canvas_failed", &[("width", &width), ("height", &height)])))?;
+    let transform = tiny_skia::Transform::from_scale(width as f32 / size.width(), height as f32 / size.height());
+    resvg::render(&tree, transform, &mut pixmap.as_mut());
+
+    let mut rgba = Vec::with_capacity(pixmap.data().len());
+    for pixel in pixmap.pixels() {
+        let color = pixel.demultiply();
+        rgba.extend_from_slice(&[color.red(), color.green(), color.blue(), color.alpha()]);
+    }
+    RgbaImage::from_raw(width, height, rgba)
+        .ok_or_else(|| ImageError::Decode(message("image.svg_bad_pixel_length", &[])))
+}
+
+#[cfg(not(feature = "svg"))]
+fn render_svg(_bytes: &[u8], _config: &ImageConfig, _options: &ImageOutputOptions) -> Result<RgbaImage, ImageError> {
+    Err(ImageError::UnsupportedFormat(message("image.svg_feature_disabled", &[])))
+}
+
+/// 进程内共享的系统字体库（首次渲染时加载）。
+#[cfg(feature = "svg")]
+fn font_database() -> std::sync::Arc<resvg::usvg::fontdb::Database> {
+    use std::sync::{Arc, OnceLock};
+
+    static FONTS: OnceLock<Arc<resvg::usvg::fontdb::Database>> = OnceLock::new();
+    FONTS
+        .get_or_init(|| {
+            let mut fonts = resvg::usvg::fontdb::Database::new();
+            fonts.load_system_fonts();
+            Arc::new(fonts)
+        })
+        .clone()
+}
+
+#[cfg(test)]
+#[path = "tests/svg_tests.rs"]
+mod tests;
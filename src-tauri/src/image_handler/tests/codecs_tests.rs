@@ -0,0 +1,136 @@
+use super::*;
+use image::{ImageBuffer, Rgb, Rgba};
+
+fn encode(image: DynamicImage, format: ImageFormat) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    image.write_to(&mut Cursor::new(&mut bytes), format).expect("encode fixture");
+    bytes
+}
+
+fn rgba_fixture(width: u32, height: u32) -> DynamicImage {
+    DynamicImage::ImageRgba8(ImageBuffer::from_fn(width, height, |x, y| {
+        Rgba([(x * 20) as u8, (y * 20) as u8, 128, if x == 0 { 0 } else { 255 }])
+    }))
+}
+
+/// 最小 HEIF 文件头：`ftyp` 盒（主品牌 + 兼容品牌）
+fn ftyp_header(major: &[u8; 4], compatible: &[&[u8; 4]]) -> Vec<u8> {
+    let size = 16 + compatible.len() * 4;
+    let mut bytes = (size as u32).to_be_bytes().to_vec();
+    bytes.extend_from_slice(b"ftyp");
+    bytes.extend_from_slice(major);
+    bytes.extend_from_slice(&[0, 0, 0, 0]);
+    for brand in compatible {
+        bytes.extend_from_slice(*brand);
+    }
+    bytes.extend_from_slice(&[0, 0, 0, 8, b'm', b'e', b't', b'a']);
+    bytes
+}
+
+#[test]
+fn builtin_formats_decode_through_codecs() {
+    let rgb = DynamicImage::ImageRgb8(ImageBuffer::from_pixel(7, 5, Rgb([10u8, 20, 30])));
+    let fixtures = [
+        ("bmp rgb", encode(rgb.clone(), ImageFormat::Bmp), (7, 5)),
+        ("bmp rgba", encode(rgba_fixture(6, 4), ImageFormat::Bmp), (6, 4)),
+        ("ico", encode(rgba_fixture(16, 16), ImageFormat::Ico), (16, 16)),
+        ("tiff rgb", encode(rgb, ImageFormat::Tiff), (7, 5)),
+        ("tiff rgba", encode(rgba_fixture(6, 4), ImageFormat::Tiff), (6, 4)),
+    ];
+
+    for (name, bytes, size) in fixtures {
+        assert_eq!(ExtendedFormat::sniff(&bytes), None, "{}", name);
+        ensure_decodable(&bytes).unwrap_or_else(|e| panic!("{} should be decodable: {}", name, e));
+        assert_eq!(read_dimensions(&bytes).expect(name), size, "{}", name);
+        let decoded = decode(&bytes).expect(name);
+        assert_eq!((decoded.width(), decoded.height()), size, "{}", name);
+    }
+}
+
+#[test]
+fn sniff_distinguishes_heif_brands_and_jpeg_xl() {
+    let avif = encode(rgba_fixture(4, 4), ImageFormat::Avif);
+    assert_eq!(ExtendedFormat::sniff(&avif), Some(ExtendedFormat::Avif));
+    assert_eq!(ExtendedFormat::sniff(&ftyp_header(b"heic", &[b"mif1", b"heic"])), Some(ExtendedFormat::Heic));
+    assert_eq!(ExtendedFormat::sniff(&ftyp_header(b"mif1", &[b"miaf", b"avif"])), Some(ExtendedFormat::Avif));
+    assert_eq!(ExtendedFormat::sniff(&ftyp_header(b"isom", &[b"mp41"])), None);
+
+    assert_eq!(ExtendedFormat::sniff(&[0xFF, 0x0A, 0xFA, 0x1F]), Some(ExtendedFormat::Jxl));
+    assert_eq!(ExtendedFormat::sniff(&JXL_CONTAINER), Some(ExtendedFormat::Jxl));
+    assert_eq!(ExtendedFormat::sniff(&encode(rgba_fixture(2, 2), ImageFormat::Png)), None);
+    assert_eq!(ExtendedFormat::sniff(b"ftyp"), None);
+}
+
+#[cfg(not(feature = "heif"))]
+#[test]
+fn heif_formats_name_the_missing_feature() {
+    let avif = encode(rgba_fixture(4, 4), ImageFormat::Avif);
+    let heic = ftyp_header(b"heic", &[b"mif1"]);
+
+    for bytes in [avif, heic] {
+        for result in [ensure_decodable(&bytes), read_dimensions(&bytes).map(drop), decode(&bytes).map(drop)] {
+            match result {
+                Err(err @ ImageError::UnsupportedFormat(_)) => {
+                    assert_eq!(err.code(), "E_FORMAT_UNSUPPORTED");
+                    assert!(err.to_string().contains("heif"), "{}", err);
+                }
+                other => panic!("expected UnsupportedFormat, got {:?}", other),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "heif")]
+#[test]
+fn heif_feature_decodes_avif() {
+    let avif = encode(rgba_fixture(8, 6), ImageFormat::Avif);
+
+    ensure_decodable(&avif).expect("avif decodable");
+    assert_eq!(read_dimensions(&avif).expect("dimensions"), (8, 6));
+    let decoded = decode(&avif).expect("decode avif");
+    assert_eq!((decoded.width(), decoded.height()), (8, 6));
+}
+
+/// libheif + x265 编码的 16x8 HEIC：左半红（230, 20, 20），右半蓝（20, 20, 230）
+#[cfg(feature = "heif")]
+const HEIC_FIXTURE: &[u8] = include_bytes!("fixtures/solid_16x8.heic");
+
+#[cfg(feature = "heif")]
+#[test]
+fn heif_feature_decodes_real_heic_photo() {
+    assert_eq!(ExtendedFormat::sniff(HEIC_FIXTURE), Some(ExtendedFormat::Heic));
+    ensure_decodable(HEIC_FIXTURE).expect("heic decodable");
+    assert_eq!(read_dimensions(HEIC_FIXTURE).expect("dimensions"), (16, 8));
+
+    let decoded = decode(HEIC_FIXTURE).expect("decode heic").to_rgba8();
+    assert_eq!(decoded.dimensions(), (16, 8));
+    // 有损编码，按容差比较
+    let close = |pixel: &Rgba<u8>, expected: [u8; 3]| {
+        pixel.0[..3].iter().zip(expected).all(|(&a, b)| a.abs_diff(b) <= 12) && pixel.0[3] == 255
+    };
+    assert!(close(decoded.get_pixel(3, 4), [230, 20, 20]), "{:?}", decoded.get_pixel(3, 4));
+    assert!(close(decoded.get_pixel(12, 4), [20, 20, 230]), "{:?}", decoded.get_pixel(12, 4));
+}
+
+#[test]
+fn jpeg_xl_and_unknown_images_are_unsupported_while_garbage_is_invalid() {
+    let jxl = [0xFF, 0x0A, 0xFA, 0x1F, 0x00, 0x00];
+    let psd = *b"8BPS\x00\x01\x00\x00\x00\x00\x00\x00\x00\x03";
+    for bytes in [&jxl[..], &psd[..]] {
+        let err = ensure_decodable(bytes).expect_err("unsupported");
+        assert!(matches!(err, ImageError::UnsupportedFormat(_)), "{:?}", err);
+    }
+    assert!(matches!(decode(&jxl), Err(ImageError::UnsupportedFormat(_))));
+
+    for bytes in [&b"hello world"[..], &[0u8; 16][..]] {
+        let err = ensure_decodable(bytes).expect_err("invalid");
+        assert_eq!(err.code(), "E_FORMAT_INVALID");
+    }
+}
+
+#[test]
+fn corrupt_pixel_data_reports_decode_error() {
+    let mut tiff = encode(rgba_fixture(6, 4), ImageFormat::Tiff);
+    tiff.truncate(16);
+    assert!(matches!(decode(&tiff), Err(ImageError::Decode(_)) | Err(ImageError::InvalidFormat(_))));
+}
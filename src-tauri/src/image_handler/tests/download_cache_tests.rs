@@ -0,0 +1,165 @@
+use super::*;
+use reqwest::header::HeaderValue;
+
+fn unique_temp_dir(prefix: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let dir = std::env::temp_dir().join(format!("{}_{}", prefix, nanos));
+    let _ = fs::create_dir_all(&dir);
+    dir
+}
+
+fn policy(etag: Option<&str>, max_age_secs: Option<u64>) -> CachePolicy {
+    CachePolicy {
+        etag: etag.map(str::to_string),
+        max_age_secs,
+        ..CachePolicy::default()
+    }
+}
+
+fn meta(policy: CachePolicy, stored_at_ms: u64) -> CacheEntryMeta {
+    CacheEntryMeta {
+        url: "https://example.com/a.png".to_string(),
+        size: 1,
+        policy,
+        stored_at_ms,
+        last_access_ms: stored_at_ms,
+    }
+}
+
+#[test]
+fn policy_reads_validators_and_cache_control() {
+    let mut headers = HeaderMap::new();
+    headers.insert(ETAG, HeaderValue::from_static("\"v1\""));
+    headers.insert(LAST_MODIFIED, HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"));
+    headers.append(CACHE_CONTROL, HeaderValue::from_static("public, Max-Age=600"));
+    headers.append(CACHE_CONTROL, HeaderValue::from_static("no-store"));
+
+    let parsed = CachePolicy::from_headers(&headers);
+    assert_eq!(parsed.etag.as_deref(), Some("\"v1\""));
+    assert_eq!(parsed.last_modified.as_deref(), Some("Wed, 21 Oct 2015 07:28:00 GMT"));
+    assert_eq!(parsed.max_age_secs, Some(600));
+    assert!(parsed.no_store);
+    assert!(parsed.has_validators());
+
+    assert_eq!(CachePolicy::from_headers(&HeaderMap::new()), CachePolicy::default());
+}
+
+#[test]
+fn freshness_follows_max_age_then_validators_then_heuristic() {
+    let stored = 1_000_000;
+    let with_max_age = meta(policy(Some("e"), Some(60)), stored);
+    assert!(with_max_age.is_fresh(stored + 59_000));
+    assert!(!with_max_age.is_fresh(stored + 60_000));
+
+    // 有校验器但无 max-age：每次都需要条件请求
+    assert!(!meta(policy(Some("e"), None), stored).is_fresh(stored));
+
+    let heuristic = meta(CachePolicy::default(), stored);
+    assert!(heuristic.is_fresh(stored + (HEURISTIC_FRESHNESS_SECS - 1) * 1000));
+    assert!(!heuristic.is_fresh(stored + HEURISTIC_FRESHNESS_SECS * 1000));
+}
+
+#[test]
+fn store_then_lookup_roundtrip_and_drops_corrupt_entries() {
+    let dir = unique_temp_dir("cliphist_dlcache_roundtrip");
+    let url = "https://example.com/cat.png";
+
+    assert!(store(&dir, url, b"image-bytes", &policy(Some("\"a\""), None), 1024, 10).expect("store"));
+    let entry = lookup(&dir, url).expect("cached entry");
+    assert_eq!(&entry.bytes[..], b"image-bytes");
+    assert_eq!(entry.meta.policy.etag.as_deref(), Some("\"a\""));
+    assert_eq!(entry.meta.stored_at_ms, 10);
+    assert!(lookup(&dir, "https://example.com/dog.png").is_none());
+
+    // 正文被截断时条目作废并被删除
+    fs::write(entry_path(&dir, url, BODY_EXTENSION), b"image").expect("truncate body");
+    assert!(lookup(&dir, url).is_none());
+    assert!(!entry_path(&dir, url, META_EXTENSION).exists());
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn scan_removes_leftover_temp_files_of_both_kinds() {
+    let dir = unique_temp_dir("cliphist_dlcache_tmp");
+    let url = "https://example.com/cat.png";
+    store(&dir, url, b"image-bytes", &CachePolicy::default(), 1024, 1).expect("store");
+
+    let body_tmp = entry_path(&dir, url, "bin.tmp");
+    let meta_tmp = entry_path(&dir, url, "json.tmp");
+    assert_ne!(body_tmp, meta_tmp);
+    fs::write(&body_tmp, b"partial").expect("write body tmp");
+    fs::write(&meta_tmp, b"{").expect("write meta tmp");
+
+    assert_eq!(cache_info(&dir, 1024).entries, 1);
+    assert!(!body_tmp.exists());
+    assert!(!meta_tmp.exists());
+    assert!(lookup(&dir, url).is_some());
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn no_store_oversized_and_empty_bodies_are_not_cached() {
+    let dir = unique_temp_dir("cliphist_dlcache_skip");
+    let no_store = CachePolicy { no_store: true, ..CachePolicy::default() };
+
+    assert!(!store(&dir, "https://a.test/1", b"data", &no_store, 1024, 1).expect("no-store"));
+    assert!(!store(&dir, "https://a.test/2", &[0u8; 64], &CachePolicy::default(), 32, 1).expect("oversized"));
+    assert!(!store(&dir, "https://a.test/3", b"", &CachePolicy::default(), 1024, 1).expect("empty"));
+    assert_eq!(cache_info(&dir, 1024).entries, 0);
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn eviction_removes_least_recently_used_entries_first() {
+    let dir = unique_temp_dir("cliphist_dlcache_lru");
+    let body = [7u8; 40];
+    let none = CachePolicy::default();
+
+    store(&dir, "https://a.test/old", &body, &none, 100, 1).expect("store old");
+    store(&dir, "https://a.test/mid", &body, &none, 100, 2).expect("store mid");
+    // 访问最早写入的条目，使 mid 成为最久未使用
+    touch(&dir, "https://a.test/old", None, 3);
+    store(&dir, "https://a.test/new", &body, &none, 100, 4).expect("store new");
+
+    assert!(lookup(&dir, "https://a.test/old").is_some());
+    assert!(lookup(&dir, "https://a.test/mid").is_none());
+    assert!(lookup(&dir, "https://a.test/new").is_some());
+    assert_eq!(cache_info(&dir, 100), DownloadCacheInfo { entries: 2, total_bytes: 80, max_bytes: 100 });
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn revalidation_refreshes_policy_and_stored_time() {
+    let dir = unique_temp_dir("cliphist_dlcache_touch");
+    let url = "https://a.test/img";
+    store(&dir, url, b"data", &policy(Some("\"v1\""), None), 1024, 5).expect("store");
+
+    touch(&dir, url, Some(&policy(None, Some(30))), 50);
+
+    let entry = lookup(&dir, url).expect("entry");
+    assert_eq!(entry.meta.policy.etag.as_deref(), Some("\"v1\""));
+    assert_eq!(entry.meta.policy.max_age_secs, Some(30));
+    assert_eq!(entry.meta.stored_at_ms, 50);
+    assert_eq!(entry.meta.last_access_ms, 50);
+    assert!(entry.meta.is_fresh(50 + 29_000));
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn clear_removes_all_entries_but_keeps_directory() {
+    let dir = unique_temp_dir("cliphist_dlcache_clear");
+    for idx in 0..3 {
+        store(&dir, &format!("https://a.test/{}", idx), b"data", &CachePolicy::default(), 1024, idx)
+            .expect("store");
+    }
+    assert_eq!(cache_info(&dir, 1024).total_bytes, 12);
+
+    clear(&dir).expect("clear");
+
+    assert!(dir.is_dir());
+    assert_eq!(cache_info(&dir, 1024), DownloadCacheInfo { entries: 0, total_bytes: 0, max_bytes: 1024 });
+    let _ = fs::remove_dir_all(dir);
+}
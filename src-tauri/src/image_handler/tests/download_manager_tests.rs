@@ -0,0 +1,180 @@
+use super::*;
+use std::time::Duration;
+
+const A: &str = "https://a.test/a.png";
+const B: &str = "https://a.test/b.png";
+const C: &str = "https://a.test/c.png";
+
+fn register<'a>(
+    manager: &'a DownloadManager,
+    request_id: &str,
+    url: &str,
+    priority: i32,
+) -> (DownloadTaskGuard<'a>, Registration) {
+    manager
+        .register(request_id, url, priority, Some(ImageOutputOptions::default()))
+        .expect("register task")
+}
+
+fn status_of(manager: &DownloadManager, request_id: &str) -> DownloadTaskStatus {
+    manager
+        .list()
+        .expect("list tasks")
+        .into_iter()
+        .find(|t| t.request_id == request_id)
+        .map(|t| t.status)
+        .expect("task listed")
+}
+
+async fn acquire_within<'a>(
+    manager: &'a DownloadManager,
+    url: &str,
+    limit: usize,
+) -> Option<Result<TransferSlot<'a>, ImageError>> {
+    tokio::time::timeout(Duration::from_millis(30), manager.acquire_slot(url, limit))
+        .await
+        .ok()
+}
+
+#[tokio::test]
+async fn identical_urls_share_one_transfer() {
+    let manager = &DownloadManager::new();
+    let (_first, leader) = register(manager, "r1", A, 0);
+    let (_second, follower) = register(manager, "r2", A, 0);
+    assert!(leader.starts_transfer);
+    assert!(!follower.starts_transfer);
+
+    manager.publisher(A).publish(Ok((Bytes::from_static(b"png"), None)));
+
+    for (id, registration) in [("r1", leader), ("r2", follower)] {
+        let (bytes, _) = manager.wait_transfer(id, registration.result_rx).await.expect("shared result");
+        assert_eq!(&bytes[..], b"png");
+    }
+    let tasks = manager.list().expect("list");
+    assert_eq!(tasks.iter().map(|t| t.coalesced).collect::<Vec<_>>(), vec![false, true]);
+    assert!(tasks.iter().all(|t| t.stage == Some("decode") && t.downloaded_bytes == 3));
+}
+
+#[tokio::test]
+async fn queue_respects_concurrency_limit_then_priority() {
+    let manager = &DownloadManager::new();
+    let _a = register(manager, "a", A, 0);
+    let _b = register(manager, "b", B, 0);
+    let _c = register(manager, "c", C, 5);
+
+    // 优先级最高的 C 先出队
+    assert!(acquire_within(manager, A, 1).await.is_none());
+    let slot_c = acquire_within(manager, C, 1).await.expect("c runs").expect("slot");
+    assert_eq!(status_of(manager, "c"), DownloadTaskStatus::Running);
+
+    // 名额占满时其余传输继续排队
+    assert!(acquire_within(manager, A, 1).await.is_none());
+    drop(slot_c);
+    manager.publisher(C).publish(Ok((Bytes::new(), None)));
+
+    // 同优先级按登记顺序
+    assert!(acquire_within(manager, B, 1).await.is_none());
+    let _slot_a = acquire_within(manager, A, 1).await.expect("a runs").expect("slot");
+    assert!(acquire_within(manager, B, 2).await.is_some());
+}
+
+#[tokio::test]
+async fn cancel_affects_one_task_until_all_subscribers_cancel() {
+    let manager = &DownloadManager::new();
+    let (_first, first) = register(manager, "r1", A, 0);
+    let (_second, _) = register(manager, "r2", A, 0);
+
+    assert!(manager.cancel("r1").expect("cancel r1"));
+    assert!(!manager.cancel("r1").expect("cancel twice"));
+    assert!(matches!(
+        manager.wait_transfer("r1", first.result_rx).await,
+        Err(ImageError::Cancelled(_))
+    ));
+    assert!(!manager.is_interrupted(A));
+
+    assert!(manager.cancel("r2").expect("cancel r2"));
+    assert!(manager.is_interrupted(A));
+    assert!(matches!(manager.acquire_slot(A, 1).await, Err(ImageError::Cancelled(_))));
+}
+
+#[tokio::test]
+async fn pause_holds_transfer_until_retry_resumes_it() {
+    let manager = &DownloadManager::new();
+    let _first = register(manager, "r1", A, 0);
+    let _second = register(manager, "r2", A, 0);
+
+    assert!(manager.pause("r1").expect("pause"));
+    assert!(!manager.pause("r2").expect("already paused"));
+    assert_eq!(status_of(manager, "r2"), DownloadTaskStatus::Paused);
+    assert!(manager.is_interrupted(A));
+    assert!(acquire_within(manager, A, 1).await.is_none());
+
+    assert!(matches!(manager.retry("r2").expect("resume"), RetryAction::Resumed));
+    assert_eq!(status_of(manager, "r1"), DownloadTaskStatus::Queued);
+    assert!(acquire_within(manager, A, 1).await.expect("resumed").is_ok());
+}
+
+#[tokio::test]
+async fn finished_tasks_record_outcome_and_can_be_restarted() {
+    let manager = &DownloadManager::new();
+    let (task, _) = register(manager, "r1", A, 3);
+    assert!(matches!(manager.retry("r1").expect("running"), RetryAction::Unavailable));
+    assert!(manager.register("r1", A, 0, Some(ImageOutputOptions::default())).is_err());
+
+    task.finish(&Err(ImageError::Network("timeout".to_string())));
+    let info = &manager.list().expect("list")[0];
+    assert_eq!(info.status, DownloadTaskStatus::Failed);
+    assert_eq!(info.kind, DownloadTaskKind::Clipboard);
+    assert_eq!(info.error_code, Some("E_NET_REQUEST"));
+    assert!(info.finished_at.is_some());
+
+    match manager.retry("r1").expect("retry failed") {
+        RetryAction::Restart(spec) => {
+            assert_eq!((spec.request_id.as_str(), spec.url.as_str(), spec.priority), ("r1", A, 3));
+        }
+        _ => panic!("failed task should restart"),
+    }
+    assert!(matches!(manager.retry("missing").expect("missing"), RetryAction::Unavailable));
+
+    // 保存到历史的任务由调用方重新发起
+    let (history, _) = manager.register("h1", B, 0, None).expect("register history task");
+    history.finish(&Err(ImageError::Network("timeout".to_string())));
+    assert!(matches!(manager.retry("h1").expect("history"), RetryAction::Unavailable));
+
+    // 同名任务结束后可以再次登记
+    assert_eq!(status_of(manager, "r1"), DownloadTaskStatus::Failed);
+    let _again = register(manager, "r1", A, 0);
+    assert_eq!(status_of(manager, "r1"), DownloadTaskStatus::Queued);
+}
+
+#[tokio::test]
+async fn dropped_guards_cancel_task_and_interrupt_waiters() {
+    let manager = &DownloadManager::new();
+    let (task, leader) = register(manager, "r1", A, 0);
+    let (_other, follower) = register(manager, "r2", A, 0);
+
+    drop(task);
+    assert_eq!(status_of(manager, "r1"), DownloadTaskStatus::Cancelled);
+
+    drop(manager.publisher(A));
+    drop(leader);
+    assert!(matches!(
+        manager.wait_transfer("r2", follower.result_rx).await,
+        Err(ImageError::Cancelled(_))
+    ));
+}
+
+#[test]
+fn finished_history_is_bounded() {
+    let manager = &DownloadManager::new();
+    for idx in 0..MAX_FINISHED_TASKS + 5 {
+        let (task, _) = register(manager, &format!("r{}", idx), &format!("https://a.test/{}", idx), 0);
+        task.finish(&Ok(None));
+    }
+    let (_active, _) = register(manager, "active", A, 0);
+
+    let tasks = manager.list().expect("list");
+    assert_eq!(tasks.len(), MAX_FINISHED_TASKS + 1);
+    assert_eq!(tasks[0].request_id, "r5");
+    assert_eq!(tasks.last().map(|t| t.status), Some(DownloadTaskStatus::Queued));
+}
@@ -0,0 +1,263 @@
+use super::*;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+const RED: Rgba<u8> = Rgba([0xff, 0x3b, 0x30, 255]);
+
+fn canvas(width: u32, height: u32) -> RgbaImage {
+    RgbaImage::from_pixel(width, height, WHITE)
+}
+
+fn unique_temp_dir(prefix: &str) -> std::path::PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let dir = std::env::temp_dir().join(format!("{}_{}", prefix, nanos));
+    let _ = std::fs::create_dir_all(&dir);
+    dir
+}
+
+fn rect(x: u32, y: u32, width: u32, height: u32) -> CropRect {
+    CropRect { x, y, width, height }
+}
+
+fn apply(image: RgbaImage, ops: &[ImageEditOp]) -> Result<RgbaImage, ImageError> {
+    apply_edit_ops(image, ops, &ImageConfig::default())
+}
+
+fn count_color(image: &RgbaImage, color: Rgba<u8>) -> usize {
+    image.pixels().filter(|p| **p == color).count()
+}
+
+#[test]
+fn deserializes_tagged_ops_with_defaults() {
+    let ops: Vec<ImageEditOp> = serde_json::from_str(
+        r##"[
+            {"op":"crop","rect":{"x":1,"y":2,"width":3,"height":4}},
+            {"op":"flip","direction":"vertical"},
+            {"op":"resize","width":100},
+            {"op":"rectangle","rect":{"x":0,"y":0,"width":5,"height":5},"color":"#00f"},
+            {"op":"arrow","from":{"x":-5,"y":0},"to":{"x":10,"y":10}}
+        ]"##,
+    )
+    .expect("parse ops");
+
+    assert_eq!(ops[0], ImageEditOp::Crop { rect: rect(1, 2, 3, 4) });
+    assert_eq!(ops[1], ImageEditOp::Flip { direction: FlipDirection::Vertical });
+    assert_eq!(ops[2], ImageEditOp::Resize { width: Some(100), height: None });
+    assert!(matches!(&ops[3], ImageEditOp::Rectangle { fill: false, stroke_width: None, .. }));
+    assert!(matches!(ops[4], ImageEditOp::Arrow { from: EditPoint { x: -5, y: 0 }, .. }));
+}
+
+#[test]
+fn geometry_ops_apply_in_order() {
+    let mut image = canvas(40, 20);
+    image.put_pixel(0, 0, RED);
+
+    let result = apply(
+        image,
+        &[
+            ImageEditOp::Crop { rect: rect(0, 0, 30, 20) },
+            ImageEditOp::Rotate { degrees: 90 },
+            ImageEditOp::Flip { direction: FlipDirection::Horizontal },
+            ImageEditOp::Resize { width: Some(10), height: None },
+        ],
+    )
+    .expect("apply geometry");
+
+    // 30x20 旋转后为 20x30，等比缩放到宽 10
+    assert_eq!(result.dimensions(), (10, 15));
+}
+
+#[test]
+fn rotate_and_flip_move_pixels() {
+    let mut image = canvas(3, 2);
+    image.put_pixel(0, 0, RED);
+
+    let rotated = apply(image.clone(), &[ImageEditOp::Rotate { degrees: 90 }]).expect("rotate");
+    assert_eq!(rotated.dimensions(), (2, 3));
+    assert_eq!(*rotated.get_pixel(1, 0), RED);
+
+    let flipped = apply(image, &[ImageEditOp::Flip { direction: FlipDirection::Vertical }]).expect("flip");
+    assert_eq!(*flipped.get_pixel(0, 1), RED);
+}
+
+#[test]
+fn pixelate_averages_blocks_inside_region_only() {
+    let mut image = canvas(8, 4);
+    for y in 0..4 {
+        for x in 0..4 {
+            if (x + y) % 2 == 0 {
+                image.put_pixel(x, y, Rgba([0, 0, 0, 255]));
+            }
+        }
+    }
+
+    let result = apply(
+        image,
+        &[ImageEditOp::Pixelate { rect: rect(0, 0, 4, 4), block_size: Some(2) }],
+    )
+    .expect("pixelate");
+
+    let averaged = Rgba([128, 128, 128, 255]);
+    for y in 0..4 {
+        for x in 0..4 {
+            assert_eq!(*result.get_pixel(x, y), averaged, "({}, {})", x, y);
+        }
+        assert_eq!(*result.get_pixel(6, y), WHITE);
+    }
+}
+
+#[test]
+fn blur_is_clipped_to_image_and_keeps_outside_untouched() {
+    let mut image = canvas(20, 20);
+    image.put_pixel(15, 15, Rgba([0, 0, 0, 255]));
+    image.put_pixel(2, 2, Rgba([0, 0, 0, 255]));
+
+    let result = apply(
+        image,
+        &[ImageEditOp::Blur { rect: rect(10, 10, 100, 100), sigma: Some(2.0) }],
+    )
+    .expect("blur");
+
+    assert_ne!(*result.get_pixel(15, 15), Rgba([0, 0, 0, 255]));
+    assert_eq!(*result.get_pixel(2, 2), Rgba([0, 0, 0, 255]));
+}
+
+#[test]
+fn rectangle_draws_stroke_or_fill() {
+    let outline = apply(
+        canvas(20, 20),
+        &[ImageEditOp::Rectangle { rect: rect(5, 5, 10, 10), color: None, stroke_width: Some(2), fill: false }],
+    )
+    .expect("outline");
+    assert_eq!(*outline.get_pixel(5, 5), RED);
+    assert_eq!(*outline.get_pixel(14, 10), RED);
+    assert_eq!(*outline.get_pixel(10, 10), WHITE);
+    assert_eq!(count_color(&outline, RED), 100 - 36);
+
+    let filled = apply(
+        canvas(20, 20),
+        &[ImageEditOp::Rectangle {
+            rect: rect(15, 15, 10, 10),
+            color: Some("#000".to_string()),
+            stroke_width: None,
+            fill: true,
+        }],
+    )
+    .expect("fill");
+    assert_eq!(count_color(&filled, Rgba([0, 0, 0, 255])), 25);
+}
+
+#[test]
+fn arrow_reaches_tip_and_draws_head() {
+    let result = apply(
+        canvas(60, 60),
+        &[ImageEditOp::Arrow {
+            from: EditPoint { x: 5, y: 30 },
+            to: EditPoint { x: 50, y: 30 },
+            color: None,
+            stroke_width: Some(2),
+        }],
+    )
+    .expect("arrow");
+
+    assert_eq!(*result.get_pixel(20, 30), RED);
+    assert_eq!(*result.get_pixel(49, 30), RED);
+    // 箭翼向起点方向展开，位于主干上下两侧
+    assert!((36..48).any(|x| *result.get_pixel(x, 24) == RED));
+    assert!((36..48).any(|x| *result.get_pixel(x, 36) == RED));
+    assert_eq!(*result.get_pixel(20, 20), WHITE);
+}
+
+#[test]
+fn text_renders_ascii_glyphs_scaled() {
+    let result = apply(
+        canvas(40, 20),
+        &[ImageEditOp::Text { x: 0, y: 0, text: "I".to_string(), color: None, size: Some(16) }],
+    )
+    .expect("text");
+
+    // 字号 16 → 每点 2 像素；"I" 的中间列为整列 7 点
+    let stem_x = 2 * 2;
+    for y in 0..14 {
+        assert_eq!(*result.get_pixel(stem_x, y), RED, "y = {}", y);
+    }
+    assert_eq!(*result.get_pixel(stem_x, 15), WHITE);
+    assert_eq!(count_color(&result, RED), (7 + 2 * 2) * 4);
+}
+
+#[test]
+fn invalid_ops_report_step_index() {
+    let cases: Vec<Vec<ImageEditOp>> = vec![
+        vec![ImageEditOp::Flip { direction: FlipDirection::Horizontal }, ImageEditOp::Rotate { degrees: 45 }],
+        vec![ImageEditOp::Crop { rect: rect(0, 0, 11, 5) }],
+        vec![ImageEditOp::Resize { width: None, height: None }],
+        vec![ImageEditOp::Pixelate { rect: rect(20, 20, 5, 5), block_size: None }],
+        vec![ImageEditOp::Blur { rect: rect(0, 0, 5, 5), sigma: Some(f32::NAN) }],
+        vec![ImageEditOp::Rectangle { rect: rect(0, 0, 5, 5), color: Some("nope".to_string()), stroke_width: None, fill: false }],
+        vec![ImageEditOp::Arrow {
+            from: EditPoint { x: -50, y: -50 },
+            to: EditPoint { x: -20, y: -20 },
+            color: None,
+            stroke_width: None,
+        }],
+        vec![ImageEditOp::Text { x: 0, y: 0, text: "中文".to_string(), color: None, size: None }],
+    ];
+
+    for ops in cases {
+        let step = ops.len();
+        match apply(canvas(10, 10), &ops) {
+            Err(ImageError::InvalidFormat(msg)) => {
+                assert!(msg.starts_with(&format!("第 {} 步", step)), "{}", msg)
+            }
+            other => panic!("{:?} should be rejected, got {:?}", ops, other.map(|img| img.dimensions())),
+        }
+    }
+}
+
+#[test]
+fn rejects_empty_and_oversized_op_lists() {
+    assert!(matches!(apply(canvas(4, 4), &[]), Err(ImageError::InvalidFormat(_))));
+
+    let too_many = vec![ImageEditOp::Rotate { degrees: 180 }; MAX_EDIT_OPS + 1];
+    assert!(matches!(apply(canvas(4, 4), &too_many), Err(ImageError::InvalidFormat(_))));
+}
+
+#[test]
+fn resize_respects_pixel_limits() {
+    let config = ImageConfig { max_decoded_pixels: 1_000, ..ImageConfig::default() };
+    let result = apply_edit_ops(
+        canvas(10, 10),
+        &[ImageEditOp::Resize { width: Some(100), height: Some(100) }],
+        &config,
+    );
+    assert!(matches!(result, Err(ImageError::ResourceLimit(_))));
+}
+
+#[tokio::test]
+async fn render_edited_image_leaves_source_untouched() {
+    let dir = unique_temp_dir("cliphist_edit");
+    let source = dir.join("source.png");
+    canvas(30, 10).save(&source).expect("write source");
+    let original = std::fs::read(&source).expect("read source");
+
+    let handler = ImageHandler::new(ImageConfig::default()).expect("handler init failed");
+    let png = handler
+        .render_edited_image(
+            &source.to_string_lossy(),
+            vec![
+                ImageEditOp::Rotate { degrees: 270 },
+                ImageEditOp::Rectangle { rect: rect(0, 0, 10, 30), color: None, stroke_width: Some(1), fill: false },
+            ],
+        )
+        .await
+        .expect("render edit");
+
+    let edited = image::load_from_memory(&png).expect("decode edited").into_rgba8();
+    assert_eq!(edited.dimensions(), (10, 30));
+    assert_eq!(*edited.get_pixel(0, 0), RED);
+    assert_eq!(std::fs::read(&source).expect("reread source"), original);
+    let _ = std::fs::remove_dir_all(dir);
+}
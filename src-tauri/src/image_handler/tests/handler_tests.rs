@@ -1,5 +1,6 @@
 use super::*;
-use crate::image_handler::config::ImageAdvancedConfig;
+use crate::image_handler::config::{ImageAdvancedConfig, ImageDomainRule};
+use crate::image_handler::output::{CropRect, OutputEncoding};
 use crate::image_handler::source::{PreparedClipboardImage, RawImageData};
 use base64::{Engine as _, engine::general_purpose};
 use image::{DynamicImage, ImageBuffer, ImageFormat, Rgba};
@@ -36,7 +37,7 @@ fn perf_decode_pipeline_multiple_sizes() {
         let prepared = ImageHandler::decode_and_prepare_for_clipboard(RawImageData {
                 bytes: png.clone().into(),
                 source_hint: "test",
-            }, &config)
+            }, &config, &ImageOutputOptions::default())
             .expect("decode pipeline should succeed");
 
         let elapsed = start.elapsed();
@@ -67,7 +68,7 @@ fn stress_rejects_too_many_pixels() {
     let result = ImageHandler::decode_and_prepare_for_clipboard(RawImageData {
         bytes: png.into(),
         source_hint: "test",
-    }, &config);
+    }, &config, &ImageOutputOptions::default());
 
     assert!(matches!(result, Err(ImageError::ResourceLimit(_))));
 }
@@ -88,7 +89,7 @@ fn perf_base64_parse_and_decode() {
     let prepared = ImageHandler::decode_and_prepare_for_clipboard(RawImageData {
             bytes: decoded.into(),
             source_hint: "base64-test",
-        }, &config)
+        }, &config, &ImageOutputOptions::default())
         .expect("decode pipeline should succeed");
     let decode_elapsed = decode_start.elapsed();
 
@@ -112,7 +113,7 @@ fn adaptive_resize_downscales_large_image() {
     let prepared = ImageHandler::decode_and_prepare_for_clipboard(RawImageData {
             bytes: png.into(),
             source_hint: "adaptive-test",
-        }, &config)
+        }, &config, &ImageOutputOptions::default())
         .expect("decode pipeline should succeed");
 
     assert!(prepared.width < 3840);
@@ -120,6 +121,192 @@ fn adaptive_resize_downscales_large_image() {
     assert_eq!(prepared.bytes.len(), prepared.width * prepared.height * 4);
 }
 
+fn decode_with(png: Vec<u8>, options: &ImageOutputOptions) -> Result<PreparedClipboardImage, ImageError> {
+    let config = ImageConfig::default();
+    ImageHandler::decode_and_prepare_for_clipboard(
+        RawImageData {
+            bytes: png.into(),
+            source_hint: "output-options-test",
+        },
+        &config,
+        options,
+    )
+}
+
+#[test]
+fn output_options_crop_rotate_then_resize() {
+    // 400x200 → 裁剪 100x150 → 顺时针旋转 90° 得 150x100 → 按宽 75 等比缩放
+    let options = ImageOutputOptions {
+        crop: Some(CropRect { x: 100, y: 50, width: 100, height: 150 }),
+        rotation: 90,
+        width: Some(75),
+        ..ImageOutputOptions::default()
+    };
+
+    let prepared = decode_with(create_png_bytes(400, 200), &options).expect("apply options");
+
+    assert_eq!((prepared.width, prepared.height), (75, 50));
+    assert_eq!(prepared.bytes.len(), 75 * 50 * 4);
+    assert!(prepared.encoded.is_none());
+}
+
+#[test]
+fn output_options_explicit_resize_skips_adaptive_downscale() {
+    let options = ImageOutputOptions {
+        width: Some(3000),
+        ..ImageOutputOptions::default()
+    };
+
+    let prepared = decode_with(create_png_bytes(3840, 2160), &options).expect("apply options");
+
+    assert_eq!((prepared.width, prepared.height), (3000, 1688));
+}
+
+#[test]
+fn output_options_reject_crop_outside_image() {
+    let options = ImageOutputOptions {
+        crop: Some(CropRect { x: 90, y: 0, width: 20, height: 10 }),
+        ..ImageOutputOptions::default()
+    };
+
+    let result = decode_with(create_png_bytes(100, 100), &options);
+
+    assert!(matches!(result, Err(ImageError::InvalidFormat(_))));
+}
+
+#[test]
+fn output_options_jpeg_flattens_alpha_and_encodes() {
+    let transparent = ImageBuffer::from_pixel(4, 4, Rgba([0u8, 0, 0, 0]));
+    let mut cursor = Cursor::new(Vec::new());
+    DynamicImage::ImageRgba8(transparent)
+        .write_to(&mut cursor, ImageFormat::Png)
+        .expect("encode test image");
+    let options = ImageOutputOptions {
+        encoding: OutputEncoding::Jpeg,
+        dpi: Some(144),
+        ..ImageOutputOptions::default()
+    };
+
+    let prepared = decode_with(cursor.into_inner(), &options).expect("apply options");
+
+    assert!(prepared.bytes.chunks_exact(4).all(|px| px == [255, 255, 255, 255]));
+    let encoded = prepared.encoded.expect("jpeg bytes");
+    assert_eq!(encoded.mime, "image/jpeg");
+    assert_eq!(&encoded.bytes[..2], &[0xFF, 0xD8]);
+    assert_eq!(prepared.dpi, Some(144));
+}
+
+#[test]
+fn output_options_png_keeps_alpha() {
+    let options = ImageOutputOptions {
+        encoding: OutputEncoding::Png,
+        ..ImageOutputOptions::default()
+    };
+
+    let prepared = decode_with(create_png_bytes(16, 8), &options).expect("apply options");
+
+    let encoded = prepared.encoded.expect("png bytes");
+    assert_eq!(encoded.mime, "image/png");
+    let decoded = image::load_from_memory(&encoded.bytes).expect("decode png");
+    assert_eq!((decoded.width(), decoded.height()), (16, 8));
+    assert!(decoded.color().has_alpha());
+}
+
+fn create_gif_bytes(width: u32, height: u32) -> Vec<u8> {
+    let image = ImageBuffer::from_pixel(width, height, Rgba([200u8, 30, 30, 255]));
+    let mut bytes = Vec::new();
+    DynamicImage::ImageRgba8(image)
+        .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Gif)
+        .expect("encode gif");
+    bytes
+}
+
+fn decode_from(source_hint: &'static str, bytes: Vec<u8>, options: &ImageOutputOptions) -> PreparedClipboardImage {
+    ImageHandler::decode_and_prepare_for_clipboard(
+        RawImageData { bytes: bytes.into(), source_hint },
+        &ImageConfig::default(),
+        options,
+    )
+    .expect("decode")
+}
+
+#[test]
+fn file_and_url_sources_keep_original_encoded_bytes() {
+    let gif = create_gif_bytes(6, 4);
+    let png = create_png_bytes(6, 4);
+
+    let from_file = decode_from("file", gif.clone(), &ImageOutputOptions::default());
+    let original = from_file.original.expect("gif kept");
+    assert_eq!(original.mime, "image/gif");
+    assert_eq!(original.bytes, gif);
+    assert_eq!((from_file.width, from_file.height), (6, 4));
+
+    let from_url = decode_from("url", png.clone(), &ImageOutputOptions::default());
+    let original = from_url.original.expect("png kept");
+    assert_eq!(original.mime, "image/png");
+    assert_eq!(original.bytes, png);
+}
+
+#[test]
+fn original_bytes_are_dropped_when_pixels_change_or_source_is_not_a_file() {
+    let gif = create_gif_bytes(6, 4);
+    let rotated = ImageOutputOptions { rotation: 90, ..ImageOutputOptions::default() };
+
+    assert!(decode_from("file", gif.clone(), &rotated).original.is_none());
+    assert!(decode_from("base64", gif, &ImageOutputOptions::default()).original.is_none());
+
+    let mut jpeg = Vec::new();
+    DynamicImage::ImageRgb8(ImageBuffer::from_pixel(4, 4, image::Rgb([1u8, 2, 3])))
+        .write_to(&mut Cursor::new(&mut jpeg), ImageFormat::Jpeg)
+        .expect("encode jpeg");
+    assert!(decode_from("file", jpeg, &ImageOutputOptions::default()).original.is_none());
+}
+
+#[test]
+fn storage_encoding_keeps_gif_and_jpeg_and_transcodes_others_to_png() {
+    let encode = |bytes: Vec<u8>| {
+        ImageHandler::encode_for_storage(RawImageData { bytes: bytes.into(), source_hint: "url" }, &ImageConfig::default())
+    };
+    let gif = create_gif_bytes(6, 4);
+    assert_eq!(encode(gif.clone()).expect("gif"), (gif, "gif"));
+
+    let mut jpeg = Vec::new();
+    DynamicImage::ImageRgb8(ImageBuffer::from_pixel(5, 3, image::Rgb([1u8, 2, 3])))
+        .write_to(&mut Cursor::new(&mut jpeg), ImageFormat::Jpeg)
+        .expect("encode jpeg");
+    assert_eq!(encode(jpeg.clone()).expect("jpeg"), (jpeg, "jpg"));
+
+    let mut bmp = Vec::new();
+    DynamicImage::ImageRgb8(ImageBuffer::from_pixel(5, 3, image::Rgb([1u8, 2, 3])))
+        .write_to(&mut Cursor::new(&mut bmp), ImageFormat::Bmp)
+        .expect("encode bmp");
+    let (png, extension) = encode(bmp).expect("bmp");
+    assert_eq!(extension, "png");
+    assert_eq!(image::load_from_memory(&png).expect("decode png").width(), 5);
+
+    let tiny_limit = ImageConfig { max_decoded_pixels: 10, ..ImageConfig::default() };
+    let oversized = ImageHandler::encode_for_storage(
+        RawImageData { bytes: create_png_bytes(6, 4).into(), source_hint: "url" },
+        &tiny_limit,
+    );
+    assert!(matches!(oversized, Err(ImageError::ResourceLimit(_))));
+}
+
+#[tokio::test]
+async fn process_and_copy_rejects_invalid_options_before_loading() {
+    let handler = ImageHandler::new(ImageConfig::default()).expect("handler init failed");
+    let options = ImageOutputOptions {
+        rotation: 45,
+        ..ImageOutputOptions::default()
+    };
+
+    let result = handler
+        .process_and_copy(ImageSource::FilePath("/definitely/missing.png".into()), options)
+        .await;
+
+    assert!(matches!(result, Err(ImageError::InvalidFormat(_))));
+}
+
 fn default_advanced() -> ImageAdvancedConfig {
     ImageAdvancedConfig::from_full(&ImageConfig::default())
 }
@@ -163,6 +350,14 @@ fn advanced_config_rejects_invalid_stream_timeouts() {
         ..default_advanced()
     });
     assert!(matches!(retry_max_delay_result, Err(ImageError::InvalidFormat(_))));
+
+    for max_concurrent_downloads in [0, 17] {
+        let result = handler.set_advanced_config(&ImageAdvancedConfig {
+            max_concurrent_downloads,
+            ..default_advanced()
+        });
+        assert!(matches!(result, Err(ImageError::InvalidFormat(_))));
+    }
 }
 
 #[test]
@@ -178,6 +373,8 @@ fn advanced_config_accepts_valid_timeout_ranges() {
         stream_chunk_timeout_ms: 18_000,
         clipboard_retry_max_total_ms: 2_400,
         clipboard_retry_max_delay_ms: 1_200,
+        domain_rules: Vec::new(),
+        max_concurrent_downloads: 6,
     };
 
     handler
@@ -194,6 +391,98 @@ fn advanced_config_accepts_valid_timeout_ranges() {
     assert_eq!(got.stream_chunk_timeout_ms, 18_000);
     assert_eq!(got.clipboard_retry_max_total_ms, 2_400);
     assert_eq!(got.clipboard_retry_max_delay_ms, 1_200);
+    assert_eq!(got.max_concurrent_downloads, 6);
+}
+
+fn domain_rule(pattern: &str) -> ImageDomainRule {
+    ImageDomainRule { pattern: pattern.to_string(), ..ImageDomainRule::default() }
+}
+
+#[test]
+fn domain_rules_match_hosts_in_order() {
+    let config = ImageConfig {
+        domain_rules: vec![
+            ImageDomainRule { allow_private_network: Some(true), ..domain_rule("*.intranet.test") },
+            ImageDomainRule { referer: Some("https://pixiv.net/".to_string()), ..domain_rule("pximg.net") },
+            domain_rule("*"),
+        ],
+        ..ImageConfig::default()
+    };
+
+    assert_eq!(config.domain_rule_for("i.PXIMG.net.").map(|r| r.pattern.as_str()), Some("pximg.net"));
+    assert_eq!(config.domain_rule_for("pximg.net").map(|r| r.pattern.as_str()), Some("pximg.net"));
+    assert_eq!(config.domain_rule_for("notpximg.net").map(|r| r.pattern.as_str()), Some("*"));
+    // `*.` 只匹配子域，不匹配自身
+    assert_eq!(config.domain_rule_for("intranet.test").map(|r| r.pattern.as_str()), Some("*"));
+    assert!(config.allows_private_network_for("cdn.intranet.test"));
+    assert!(!config.allows_private_network_for("example.com"));
+}
+
+#[test]
+fn domain_rule_can_deny_private_network_when_globally_allowed() {
+    let config = ImageConfig {
+        allow_private_network: true,
+        domain_rules: vec![ImageDomainRule { allow_private_network: Some(false), ..domain_rule("10.0.0.8") }],
+        ..ImageConfig::default()
+    };
+
+    assert!(!config.allows_private_network_for("10.0.0.8"));
+    assert!(config.allows_private_network_for("10.0.0.9"));
+}
+
+#[test]
+fn domain_rule_headers_combine_custom_referer_and_cookie() {
+    let rule = ImageDomainRule {
+        headers: [("User-Agent".to_string(), "Grabber/1.0".to_string())].into_iter().collect(),
+        referer: Some("https://example.com/gallery".to_string()),
+        cookie: Some("session=abc".to_string()),
+        ..domain_rule("example.com")
+    };
+
+    let headers = rule.request_headers().expect("valid headers");
+    assert_eq!(headers.len(), 3);
+    assert_eq!(headers["user-agent"], "Grabber/1.0");
+    assert_eq!(headers["referer"], "https://example.com/gallery");
+    assert_eq!(headers["cookie"], "session=abc");
+}
+
+#[test]
+fn advanced_config_round_trips_and_validates_domain_rules() {
+    let handler = ImageHandler::new(ImageConfig::default()).expect("handler init failed");
+    let rule = ImageDomainRule {
+        proxy: Some("http://proxy.corp:8080".to_string()),
+        connect_timeout: Some(20),
+        ..domain_rule("*.example.com")
+    };
+
+    handler
+        .set_advanced_config(&ImageAdvancedConfig { domain_rules: vec![rule.clone()], ..default_advanced() })
+        .expect("valid domain rule");
+    assert_eq!(handler.get_advanced_config().expect("read config").domain_rules, vec![rule]);
+    assert_eq!(handler.config_snapshot().expect("snapshot").domain_rules.len(), 1);
+
+    let invalid_rules = [
+        domain_rule(""),
+        domain_rule("exa mple.com"),
+        ImageDomainRule { proxy: Some("socks5://127.0.0.1:1080".to_string()), ..domain_rule("a.com") },
+        ImageDomainRule { proxy: Some("not a url".to_string()), ..domain_rule("a.com") },
+        ImageDomainRule {
+            headers: [("Host".to_string(), "evil.test".to_string())].into_iter().collect(),
+            ..domain_rule("a.com")
+        },
+        ImageDomainRule {
+            headers: [("X-Bad".to_string(), "line\nbreak".to_string())].into_iter().collect(),
+            ..domain_rule("a.com")
+        },
+        ImageDomainRule { download_timeout: Some(0), ..domain_rule("a.com") },
+    ];
+    for rule in invalid_rules {
+        let result = handler.set_advanced_config(&ImageAdvancedConfig {
+            domain_rules: vec![rule.clone()],
+            ..default_advanced()
+        });
+        assert!(matches!(result, Err(ImageError::InvalidFormat(_))), "{:?}", rule);
+    }
 }
 
 #[test]
@@ -212,7 +501,7 @@ fn perf_decode_vs_clipboard_write_stage() {
         let prepared = ImageHandler::decode_and_prepare_for_clipboard(RawImageData {
                 bytes: png.into(),
                 source_hint: "clipboard-stage-test",
-            }, &config)
+            }, &config, &ImageOutputOptions::default())
             .expect("decode pipeline should succeed");
         let decode_elapsed = decode_start.elapsed();
 
@@ -224,6 +513,9 @@ fn perf_decode_vs_clipboard_write_stage() {
                 width: prepared.width,
                 height: prepared.height,
                 bytes: prepared.bytes.clone(),
+                encoded: None,
+                dpi: None,
+                original: None,
             };
 
             let write_start = Instant::now();
@@ -0,0 +1,187 @@
+use super::*;
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::thread;
+
+fn page_url(url: &str) -> reqwest::Url {
+    reqwest::Url::parse(url).expect("valid url")
+}
+
+/// 本地替身服务器：依次接受 `responses.len()` 个连接，每个连接返回一段原始 HTTP 响应。
+///
+/// 响应写完后阻塞读取，直到客户端断开，便于验证客户端在 `</head>` 处主动停止读取。
+fn serve(responses: Vec<String>) -> (u16, thread::JoinHandle<()>) {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind test server failed");
+    let port = listener.local_addr().expect("read local addr failed").port();
+    let server = thread::spawn(move || {
+        for response in responses {
+            let (mut stream, _) = listener.accept().expect("accept failed");
+            let mut req_buf = [0u8; 2048];
+            let _ = stream.read(&mut req_buf);
+            stream.write_all(response.as_bytes()).expect("write response failed");
+            stream.flush().expect("flush failed");
+            let _ = stream.read(&mut req_buf);
+        }
+    });
+    (port, server)
+}
+
+fn html_response(body: &str) -> String {
+    format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    )
+}
+
+fn local_handler() -> ImageHandler {
+    let config = ImageConfig {
+        allow_private_network: true,
+        download_timeout: 5,
+        connect_timeout: 3,
+        stream_first_byte_timeout_ms: 2000,
+        stream_chunk_timeout_ms: 2000,
+        ..ImageConfig::default()
+    };
+    ImageHandler::new(config).expect("handler init failed")
+}
+
+// ── 解析 ───────────────────────────────────────────────
+
+#[test]
+fn parse_prefers_open_graph_and_resolves_relative_urls() {
+    let html = r#"<!doctype html><html><head>
+        <title>Fallback title</title>
+        <base href="/assets/">
+        <META content="Rust &amp; friends" property="og:title">
+        <meta name='description' content='  A   language
+            empowering everyone '>
+        <meta property="og:image" content="img/cover.png">
+        <link rel="apple-touch-icon" href="/touch.png">
+        <link rel="shortcut icon" href="favicon.svg" type="image/svg+xml">
+        </head><body><meta property="og:title" content="ignored body meta"></body></html>"#;
+
+    let preview = parse_link_preview(html, &page_url("https://example.com/blog/post"));
+
+    assert_eq!(preview.url, "https://example.com/blog/post");
+    assert_eq!(preview.title.as_deref(), Some("Rust & friends"));
+    assert_eq!(preview.description.as_deref(), Some("A language empowering everyone"));
+    assert_eq!(preview.image.as_deref(), Some("https://example.com/assets/img/cover.png"));
+    assert_eq!(preview.favicon.as_deref(), Some("https://example.com/assets/favicon.svg"));
+}
+
+#[test]
+fn parse_falls_back_to_title_tag_and_default_favicon() {
+    let html = "<html><head><title>\n  Plain &#x4E2D;&#25991; page\n</title>\
+                <meta property=\"og:image\" content=\"javascript:alert(1)\"></head></html>";
+
+    let preview = parse_link_preview(html, &page_url("http://example.org:8080/a/b"));
+
+    assert_eq!(preview.title.as_deref(), Some("Plain 中文 page"));
+    assert_eq!(preview.description, None);
+    assert_eq!(preview.image, None, "非 HTTP 地址不应作为预览图");
+    assert_eq!(preview.favicon.as_deref(), Some("http://example.org:8080/favicon.ico"));
+}
+
+#[test]
+fn parse_truncates_long_text() {
+    let long_title = "字".repeat(TITLE_MAX_CHARS + 50);
+    let html = format!("<head><title>{}</title></head>", long_title);
+
+    let preview = parse_link_preview(&html, &page_url("https://example.com/"));
+
+    assert_eq!(preview.title.map(|t| t.chars().count()), Some(TITLE_MAX_CHARS));
+}
+
+#[test]
+fn html_content_type_detection() {
+    assert!(is_html_content_type("text/html; charset=utf-8"));
+    assert!(is_html_content_type("APPLICATION/XHTML+XML"));
+    assert!(!is_html_content_type("image/png"));
+    assert!(!is_html_content_type("application/json"));
+}
+
+// ── 本地替身服务器 ─────────────────────────────────────
+
+#[tokio::test]
+async fn fetch_follows_redirect_and_extracts_preview() {
+    let body = r#"<html><head><meta property="og:title" content="Stand-in"><link rel="icon" href="/i.png"></head><body>ok</body></html>"#;
+    let (port, server) = serve(vec![
+        "HTTP/1.1 301 Moved Permanently\r\nLocation: /final\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string(),
+        html_response(body),
+    ]);
+
+    let handler = local_handler();
+    let preview = handler
+        .fetch_link_preview(&format!("http://127.0.0.1:{}/start", port))
+        .await
+        .expect("fetch preview");
+    server.join().expect("server thread failed");
+
+    assert_eq!(preview.url, format!("http://127.0.0.1:{}/final", port));
+    assert_eq!(preview.title.as_deref(), Some("Stand-in"));
+    assert_eq!(preview.favicon, Some(format!("http://127.0.0.1:{}/i.png", port)));
+    assert!(preview.fetched_at > 0);
+}
+
+// 替身服务器要等客户端断开连接才退出，而断开由 hyper 的后台连接任务完成，
+// 需要多线程运行时才能在 `server.join()` 阻塞期间继续调度该任务。
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn fetch_stops_reading_after_head() {
+    // 声明的正文远大于实际发送量：若客户端继续等待正文，会触发读取超时
+    let head = "<html><head><title>Only head</title></head>";
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: 1000000\r\n\r\n{}",
+        head
+    );
+    let (port, server) = serve(vec![response]);
+
+    let handler = local_handler();
+    let preview = handler
+        .fetch_link_preview(&format!("http://127.0.0.1:{}/", port))
+        .await
+        .expect("fetch preview");
+    server.join().expect("server thread failed");
+
+    assert_eq!(preview.title.as_deref(), Some("Only head"));
+}
+
+#[tokio::test]
+async fn fetch_rejects_non_html_responses() {
+    let response =
+        "HTTP/1.1 200 OK\r\nContent-Type: application/octet-stream\r\nContent-Length: 3\r\nConnection: close\r\n\r\nabc"
+            .to_string();
+    let (port, server) = serve(vec![response]);
+
+    let result = local_handler()
+        .fetch_link_preview(&format!("http://127.0.0.1:{}/file.bin", port))
+        .await;
+    server.join().expect("server thread failed");
+
+    assert!(matches!(result, Err(ImageError::InvalidFormat(_))));
+}
+
+#[tokio::test]
+async fn fetch_applies_loader_ssrf_protection() {
+    let handler = ImageHandler::new(ImageConfig::default()).expect("handler init failed");
+
+    for url in ["http://127.0.0.1:9/", "http://localhost/", "file:///etc/passwd"] {
+        let result = handler.fetch_link_preview(url).await;
+        assert!(matches!(result, Err(ImageError::InvalidFormat(_))), "{} should be blocked", url);
+    }
+}
+
+#[tokio::test]
+async fn fetch_blocks_redirect_to_localhost() {
+    let (port, server) = serve(vec![
+        "HTTP/1.1 302 Found\r\nLocation: http://localhost/admin\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+            .to_string(),
+    ]);
+
+    let result = local_handler()
+        .fetch_link_preview(&format!("http://127.0.0.1:{}/", port))
+        .await;
+    server.join().expect("server thread failed");
+
+    assert!(matches!(result, Err(ImageError::InvalidFormat(_))));
+}
@@ -1,8 +1,9 @@
 use super::*;
-use crate::image_handler::ImageConfig;
+use crate::image_handler::{ImageConfig, ImageDomainRule};
 use std::io::{Read, Write};
 use std::net::TcpListener;
 use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 #[tokio::test]
 async fn url_safety_blocks_private_targets_by_default() {
@@ -61,6 +62,17 @@ fn retryable_http_status_is_expected() {
     assert!(!ImageHandler::is_retryable_http_status(reqwest::StatusCode::BAD_REQUEST));
 }
 
+#[test]
+fn stale_copies_are_served_only_for_transport_and_server_failures() {
+    assert!(ImageHandler::should_serve_stale(&ImageError::Timeout("30s".to_string())));
+    assert!(ImageHandler::should_serve_stale(&ImageError::Network("无法连接：refused".to_string())));
+    assert!(ImageHandler::should_serve_stale(&ImageHandler::http_status_error(503)));
+    for code in [403, 404, 410] {
+        assert!(!ImageHandler::should_serve_stale(&ImageHandler::http_status_error(code)), "HTTP {}", code);
+    }
+    assert!(!ImageHandler::should_serve_stale(&ImageError::InvalidFormat("不是图片类型：text/html".to_string())));
+}
+
 #[test]
 fn redact_url_for_log_removes_query_and_fragment() {
     let redacted = ImageHandler::redact_url_for_log(
@@ -86,6 +98,21 @@ fn stream_signature_probe_rejects_non_image_payload() {
     assert!(matches!(result, Err(ImageError::InvalidFormat(_))));
 }
 
+#[test]
+fn signature_checks_reject_undecodable_images_before_full_download() {
+    // JPEG XL 码流可识别但无解码器：探测阶段即拒绝，无需下载完整内容
+    let jxl = [0xFF_u8, 0x0A, 0xFA, 0x1F, 0x00, 0x00, 0x00, 0x00];
+    assert!(matches!(
+        ImageHandler::validate_stream_signature_probe(&jxl, 64),
+        Err(ImageError::UnsupportedFormat(_))
+    ));
+    assert!(matches!(ImageHandler::validate_image_signature(&jxl), Err(ImageError::UnsupportedFormat(_))));
+
+    // infer 不识别、但 image 可解码的格式（QOI）同样放行
+    let qoi = b"qoif\x00\x00\x00\x02\x00\x00\x00\x02\x04\x00";
+    assert!(matches!(ImageHandler::validate_stream_signature_probe(qoi, 64), Ok(true)));
+}
+
 #[tokio::test]
 async fn load_from_url_rejects_non_image_body_even_when_content_type_is_image() {
     let listener = TcpListener::bind("127.0.0.1:0").expect("bind test server failed");
@@ -195,3 +222,176 @@ async fn download_with_validation_blocks_private_ip_before_connection() {
 
     assert!(matches!(result, Err(ImageError::InvalidFormat(_))));
 }
+
+/// 全部测试共用同一个磁盘缓存目录（`init_download_cache` 进程内只生效一次）。
+fn init_test_download_cache() {
+    let dir = std::env::temp_dir().join(format!("cliphist_loader_dlcache_{}", std::process::id()));
+    crate::image_handler::init_download_cache(dir).expect("init download cache");
+}
+
+/// 磁盘缓存：首次下载落盘，之后以条件请求重新验证（304），服务不可用时回退到过期副本。
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn disk_cache_revalidates_with_etag_and_falls_back_when_offline() {
+    init_test_download_cache();
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind test server failed");
+    let addr = listener.local_addr().expect("read local addr failed");
+    let png_signature = [137_u8, 80, 78, 71, 13, 10, 26, 10, 0, 0, 0, 13, 73, 72, 68, 82];
+
+    let server = thread::spawn(move || {
+        let mut requests = Vec::new();
+        for first in [true, false] {
+            let (mut stream, _) = listener.accept().expect("accept failed");
+            let mut req_buf = [0u8; 2048];
+            let len = stream.read(&mut req_buf).unwrap_or(0);
+            requests.push(String::from_utf8_lossy(&req_buf[..len]).to_ascii_lowercase());
+
+            if first {
+                let head = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: image/png\r\nETag: \"v1\"\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    png_signature.len()
+                );
+                stream.write_all(head.as_bytes()).expect("write headers failed");
+                stream.write_all(&png_signature).expect("write body failed");
+            } else {
+                stream
+                    .write_all(b"HTTP/1.1 304 Not Modified\r\nETag: \"v1\"\r\nConnection: close\r\n\r\n")
+                    .expect("write 304 failed");
+            }
+            stream.flush().expect("flush failed");
+        }
+        requests
+    });
+
+    let config = ImageConfig {
+        allow_private_network: true,
+        download_timeout: 5,
+        connect_timeout: 3,
+        ..ImageConfig::default()
+    };
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+    let url = format!("http://127.0.0.1:{}/cached-{}.png", addr.port(), nanos);
+    // 每次使用新的处理器，绕过短期内存缓存
+    let download = || async {
+        ImageHandler::new(config.clone())
+            .expect("handler init failed")
+            .download_with_validation_with_hooks(&url, &config, |_, _| {}, || false)
+            .await
+    };
+
+    let (first, first_hit) = download().await.expect("first download");
+    let (second, second_hit) = download().await.expect("revalidated download");
+    let requests = server.join().expect("server thread failed");
+    let (third, third_hit) = download().await.expect("stale download");
+
+    assert_eq!(&first[..], &png_signature);
+    assert_eq!(first_hit, None);
+    assert_eq!(second, first);
+    assert_eq!(second_hit, Some(DownloadCacheHit::Revalidated));
+    assert!(requests[1].contains("if-none-match: \"v1\""), "{}", requests[1]);
+    assert_eq!(third, first);
+    assert_eq!(third_hit, Some(DownloadCacheHit::Stale));
+}
+
+/// 磁盘缓存：服务器对过期副本返回 404 时如实报错，不回退到旧副本。
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn disk_cache_does_not_serve_stale_copy_after_not_found() {
+    init_test_download_cache();
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind test server failed");
+    let addr = listener.local_addr().expect("read local addr failed");
+    let png_signature = [137_u8, 80, 78, 71, 13, 10, 26, 10, 0, 0, 0, 13, 73, 72, 68, 82];
+
+    let server = thread::spawn(move || {
+        for first in [true, false] {
+            let (mut stream, _) = listener.accept().expect("accept failed");
+            let mut req_buf = [0u8; 2048];
+            let _ = stream.read(&mut req_buf);
+
+            if first {
+                let head = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: image/png\r\nETag: \"v1\"\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    png_signature.len()
+                );
+                stream.write_all(head.as_bytes()).expect("write headers failed");
+                stream.write_all(&png_signature).expect("write body failed");
+            } else {
+                stream
+                    .write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                    .expect("write 404 failed");
+            }
+            stream.flush().expect("flush failed");
+        }
+    });
+
+    let config = ImageConfig {
+        allow_private_network: true,
+        download_timeout: 5,
+        connect_timeout: 3,
+        ..ImageConfig::default()
+    };
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+    let url = format!("http://127.0.0.1:{}/removed-{}.png", addr.port(), nanos);
+    let download = || async {
+        ImageHandler::new(config.clone())
+            .expect("handler init failed")
+            .download_with_validation_with_hooks(&url, &config, |_, _| {}, || false)
+            .await
+    };
+
+    let (_, first_hit) = download().await.expect("first download");
+    let second = download().await;
+    server.join().expect("server thread failed");
+
+    assert_eq!(first_hit, None);
+    match second {
+        Err(err) => assert_eq!(ImageHandler::http_status_of(&err), Some(404), "{}", err),
+        Ok((_, hit)) => panic!("404 不应回退到磁盘副本，实际命中 {:?}", hit),
+    }
+}
+
+/// 域名规则：放行指定主机的内网访问，并以规则的 UA / Referer / Cookie 覆盖默认请求头。
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn domain_rule_headers_and_private_network_override_are_applied() {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind test server failed");
+    let addr = listener.local_addr().expect("read local addr failed");
+    let png_signature = [137_u8, 80, 78, 71, 13, 10, 26, 10, 0, 0, 0, 13, 73, 72, 68, 82];
+
+    let server = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().expect("accept failed");
+        let mut req_buf = [0u8; 2048];
+        let len = stream.read(&mut req_buf).unwrap_or(0);
+        let head = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: image/png\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            png_signature.len()
+        );
+        stream.write_all(head.as_bytes()).expect("write headers failed");
+        stream.write_all(&png_signature).expect("write body failed");
+        stream.flush().expect("flush failed");
+        String::from_utf8_lossy(&req_buf[..len]).to_ascii_lowercase()
+    });
+
+    let config = ImageConfig {
+        allow_private_network: false,
+        download_timeout: 5,
+        connect_timeout: 3,
+        domain_rules: vec![ImageDomainRule {
+            pattern: "127.0.0.1".to_string(),
+            headers: [("User-Agent".to_string(), "RuleAgent/2.0".to_string())].into_iter().collect(),
+            referer: Some("https://gallery.test/page".to_string()),
+            cookie: Some("token=xyz".to_string()),
+            allow_private_network: Some(true),
+            ..ImageDomainRule::default()
+        }],
+        ..ImageConfig::default()
+    };
+    let handler = ImageHandler::new(config.clone()).expect("handler init failed");
+    let url = format!("http://127.0.0.1:{}/rule.png", addr.port());
+
+    let bytes = handler.download_with_validation(&url, &config).await.expect("download with rule");
+    let request = server.join().expect("server thread failed");
+
+    assert_eq!(&bytes[..], &png_signature);
+    assert!(request.contains("user-agent: ruleagent/2.0"), "{}", request);
+    assert!(!request.contains("mozilla/5.0"), "{}", request);
+    assert!(request.contains("referer: https://gallery.test/page"), "{}", request);
+    assert!(request.contains("cookie: token=xyz"), "{}", request);
+}
@@ -0,0 +1,187 @@
+use super::*;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn unique_temp_dir(prefix: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let dir = std::env::temp_dir().join(format!("{}_{}", prefix, nanos));
+    let _ = std::fs::create_dir_all(&dir);
+    dir
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in bytes {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+fn png_chunk(kind: &[u8; 4], data: &[u8]) -> Vec<u8> {
+    let mut chunk = Vec::new();
+    chunk.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    chunk.extend_from_slice(kind);
+    chunk.extend_from_slice(data);
+    let mut crc_input = kind.to_vec();
+    crc_input.extend_from_slice(data);
+    chunk.extend_from_slice(&crc32(&crc_input).to_be_bytes());
+    chunk
+}
+
+fn encode_png(width: u32, height: u32, text: &[(&str, &str)]) -> Vec<u8> {
+    let mut out = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut out, width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        for (keyword, value) in text {
+            encoder
+                .add_text_chunk(keyword.to_string(), value.to_string())
+                .expect("add text chunk");
+        }
+        let mut writer = encoder.write_header().expect("write header");
+        writer
+            .write_image_data(&vec![200u8; (width * height * 4) as usize])
+            .expect("write image data");
+    }
+    out
+}
+
+/// 大端 TIFF：Make = "Canon"、Orientation = 6、GPS IFD（含 GPSVersionID）
+fn sample_exif_tiff() -> Vec<u8> {
+    let mut tiff = b"MM\0\x2a\0\0\0\x08".to_vec();
+    tiff.extend_from_slice(&3u16.to_be_bytes());
+    // Make: ASCII, 6 字节，偏移 50
+    tiff.extend_from_slice(&[0x01, 0x0F, 0x00, 0x02, 0, 0, 0, 6, 0, 0, 0, 50]);
+    // Orientation: SHORT = 6
+    tiff.extend_from_slice(&[0x01, 0x12, 0x00, 0x03, 0, 0, 0, 1, 0, 6, 0, 0]);
+    // GPSInfoIFDPointer: LONG，偏移 56
+    tiff.extend_from_slice(&[0x88, 0x25, 0x00, 0x04, 0, 0, 0, 1, 0, 0, 0, 56]);
+    tiff.extend_from_slice(&0u32.to_be_bytes());
+    tiff.extend_from_slice(b"Canon\0");
+    // GPS IFD: GPSVersionID = 2.2.0.0
+    tiff.extend_from_slice(&1u16.to_be_bytes());
+    tiff.extend_from_slice(&[0x00, 0x00, 0x00, 0x01, 0, 0, 0, 4, 2, 2, 0, 0]);
+    tiff.extend_from_slice(&0u32.to_be_bytes());
+    tiff
+}
+
+/// 在 IHDR 之后插入一个 `eXIf` 块
+fn insert_exif_chunk(png_bytes: &[u8], tiff: &[u8]) -> Vec<u8> {
+    let ihdr_end = PNG_SIGNATURE.len() + 12 + 13;
+    let mut out = png_bytes[..ihdr_end].to_vec();
+    out.extend_from_slice(&png_chunk(b"eXIf", tiff));
+    out.extend_from_slice(&png_bytes[ihdr_end..]);
+    out
+}
+
+#[test]
+fn extract_reads_dimensions_color_type_and_text_chunks() {
+    let dir = unique_temp_dir("cliphist_meta_png");
+    let path = dir.join("sample.png");
+    let bytes = encode_png(12, 7, &[("Software", "cliphist"), ("Title", "Screenshot")]);
+    std::fs::write(&path, &bytes).expect("write png");
+
+    let meta = extract_image_metadata(&path).expect("metadata");
+    assert_eq!((meta.width, meta.height), (12, 7));
+    assert_eq!(meta.color_type, "Rgba8");
+    assert_eq!(meta.byte_size, bytes.len() as u64);
+    assert_eq!(meta.text_chunks.get("Software").map(String::as_str), Some("cliphist"));
+    assert!(!meta.has_gps);
+    assert!(meta.camera_make.is_none());
+
+    assert!(extract_image_metadata(&dir.join("missing.png")).is_none());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn extract_reads_exif_from_png_exif_chunk() {
+    let dir = unique_temp_dir("cliphist_meta_exif");
+    let path = dir.join("exif.png");
+    std::fs::write(&path, insert_exif_chunk(&encode_png(4, 4, &[]), &sample_exif_tiff()))
+        .expect("write png");
+
+    let meta = extract_image_metadata(&path).expect("metadata");
+    assert_eq!(meta.camera_make.as_deref(), Some("Canon"));
+    assert_eq!(meta.orientation, Some(6));
+    assert!(meta.has_gps);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn strip_exif_removes_png_exif_chunk_only() {
+    let original = encode_png(4, 4, &[("Software", "cliphist")]);
+    let with_exif = insert_exif_chunk(&original, &sample_exif_tiff());
+
+    assert_eq!(strip_exif(&with_exif), original);
+    assert_eq!(strip_exif(&original), original);
+}
+
+#[test]
+fn strip_exif_removes_jpeg_app1_exif_segment() {
+    let mut app1 = JPEG_EXIF_HEADER.to_vec();
+    app1.extend_from_slice(&sample_exif_tiff());
+    let dqt = [0xFF, 0xDB, 0x00, 0x04, 0x01, 0x02];
+    let scan = [0xFF, 0xDA, 0x00, 0x02, 0x11, 0x22, 0xFF, 0xD9];
+
+    let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE1];
+    jpeg.extend_from_slice(&((app1.len() + 2) as u16).to_be_bytes());
+    jpeg.extend_from_slice(&app1);
+    jpeg.extend_from_slice(&dqt);
+    jpeg.extend_from_slice(&scan);
+
+    let mut expected = vec![0xFF, 0xD8];
+    expected.extend_from_slice(&dqt);
+    expected.extend_from_slice(&scan);
+    assert_eq!(strip_exif(&jpeg), expected);
+}
+
+fn riff_chunk(fourcc: &[u8; 4], data: &[u8]) -> Vec<u8> {
+    let mut chunk = fourcc.to_vec();
+    chunk.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    chunk.extend_from_slice(data);
+    if data.len() % 2 == 1 {
+        chunk.push(0);
+    }
+    chunk
+}
+
+fn webp(chunks: &[Vec<u8>]) -> Vec<u8> {
+    let body: Vec<u8> = chunks.concat();
+    let mut out = b"RIFF".to_vec();
+    out.extend_from_slice(&((body.len() + 4) as u32).to_le_bytes());
+    out.extend_from_slice(b"WEBP");
+    out.extend_from_slice(&body);
+    out
+}
+
+#[test]
+fn strip_exif_removes_webp_exif_chunk_and_flag() {
+    let vp8x = |flags: u8| riff_chunk(b"VP8X", &[flags, 0, 0, 0, 3, 0, 0, 3, 0, 0]);
+    let image_data = riff_chunk(b"VP8L", &[0x2F, 0x03, 0xC0, 0x00, 0x07]);
+    let with_exif = webp(&[vp8x(0x08 | 0x10), image_data.clone(), riff_chunk(b"EXIF", &sample_exif_tiff())]);
+
+    let expected = webp(&[vp8x(0x10), image_data]);
+    let mut padded = with_exif.clone();
+    padded.extend_from_slice(&[0; 16]);
+
+    assert_eq!(strip_exif(&with_exif), expected);
+    assert_eq!(strip_exif(&padded), expected, "剪贴板填充字节应被丢弃");
+    assert_eq!(strip_exif(&expected), expected);
+}
+
+#[test]
+fn strip_exif_keeps_unknown_or_truncated_input() {
+    assert_eq!(strip_exif(b"GIF89a..."), b"GIF89a...".to_vec());
+    let truncated = [0xFF, 0xD8, 0xFF, 0xE1, 0x10, 0x00];
+    assert_eq!(strip_exif(&truncated), truncated.to_vec());
+    let truncated_webp = b"RIFF\xff\x00\x00\x00WEBPVP8L";
+    assert_eq!(strip_exif(truncated_webp), truncated_webp.to_vec());
+}
@@ -0,0 +1,124 @@
+use super::*;
+
+fn options() -> ImageOutputOptions {
+    ImageOutputOptions::default()
+}
+
+#[test]
+fn default_options_are_passthrough_and_valid() {
+    let opts = options();
+    assert!(opts.is_passthrough());
+    assert!(!opts.has_explicit_resize());
+    assert!(opts.validate().is_ok());
+    assert_eq!(opts.effective_background().unwrap(), None);
+}
+
+#[test]
+fn deserializes_partial_options_with_defaults() {
+    let opts: ImageOutputOptions = serde_json::from_str(
+        r#"{"encoding":"jpeg","width":800,"crop":{"x":1,"y":2,"width":3,"height":4}}"#,
+    )
+    .expect("parse options");
+
+    assert_eq!(opts.encoding, OutputEncoding::Jpeg);
+    assert_eq!(opts.width, Some(800));
+    assert_eq!(opts.crop, Some(CropRect { x: 1, y: 2, width: 3, height: 4 }));
+    assert_eq!(opts.rotation, 0);
+    assert!(!opts.is_passthrough());
+}
+
+#[test]
+fn validate_rejects_out_of_range_fields() {
+    let invalid = [
+        ImageOutputOptions { jpeg_quality: Some(0), ..options() },
+        ImageOutputOptions { width: Some(0), ..options() },
+        ImageOutputOptions { height: Some(MAX_OUTPUT_DIMENSION + 1), ..options() },
+        ImageOutputOptions { crop: Some(CropRect { x: 0, y: 0, width: 0, height: 10 }), ..options() },
+        ImageOutputOptions { rotation: 45, ..options() },
+        ImageOutputOptions { background: Some("not-a-color".to_string()), ..options() },
+        ImageOutputOptions { dpi: Some(0), ..options() },
+    ];
+
+    for opts in invalid {
+        assert!(matches!(opts.validate(), Err(ImageError::InvalidFormat(_))), "{:?}", opts);
+    }
+}
+
+#[test]
+fn explicit_encoding_is_rejected_where_it_cannot_be_written() {
+    for encoding in [OutputEncoding::Png, OutputEncoding::Jpeg] {
+        let result = ImageOutputOptions { encoding, ..options() }.validate();
+        if ENCODED_OUTPUT_SUPPORTED {
+            assert!(result.is_ok(), "{:?}", encoding);
+        } else {
+            assert!(matches!(result, Err(ImageError::InvalidFormat(_))), "{:?}", encoding);
+        }
+    }
+}
+
+#[test]
+fn jpeg_flattens_onto_white_unless_background_given() {
+    let jpeg = ImageOutputOptions { encoding: OutputEncoding::Jpeg, ..options() };
+    assert_eq!(jpeg.effective_background().unwrap(), Some([255, 255, 255]));
+
+    let custom = ImageOutputOptions { background: Some("#102030".to_string()), ..jpeg };
+    assert_eq!(custom.effective_background().unwrap(), Some([0x10, 0x20, 0x30]));
+
+    let png = ImageOutputOptions { encoding: OutputEncoding::Png, ..options() };
+    assert_eq!(png.effective_background().unwrap(), None);
+}
+
+#[test]
+fn target_size_keeps_aspect_ratio_for_single_edge() {
+    let width_only = ImageOutputOptions { width: Some(400), ..options() };
+    assert_eq!(width_only.target_size(1600, 900), Some((400, 225)));
+
+    let height_only = ImageOutputOptions { height: Some(90), ..options() };
+    assert_eq!(height_only.target_size(1600, 900), Some((160, 90)));
+
+    let both = ImageOutputOptions { width: Some(10), height: Some(20), ..options() };
+    assert_eq!(both.target_size(1600, 900), Some((10, 20)));
+
+    assert_eq!(options().target_size(1600, 900), None);
+}
+
+#[test]
+fn flatten_alpha_blends_with_background() {
+    let mut image = RgbaImage::from_raw(
+        3,
+        1,
+        vec![255, 0, 0, 255, 0, 0, 0, 0, 0, 0, 255, 128],
+    )
+    .expect("build image");
+
+    flatten_alpha(&mut image, [255, 255, 255]);
+
+    assert_eq!(image.get_pixel(0, 0).0, [255, 0, 0, 255]);
+    assert_eq!(image.get_pixel(1, 0).0, [255, 255, 255, 255]);
+    assert_eq!(image.get_pixel(2, 0).0, [127, 127, 255, 255]);
+}
+
+#[test]
+fn png_encoding_writes_dpi_metadata() {
+    let rgba = vec![0u8; 2 * 2 * 4];
+    let bytes = encode_png(2, 2, &rgba, Some(300)).expect("encode png");
+
+    let reader = png::Decoder::new(std::io::Cursor::new(bytes))
+        .read_info()
+        .expect("decode png");
+    let dims = reader.info().pixel_dims.expect("pHYs chunk");
+    assert_eq!(dims.xppu, dpi_to_pixels_per_meter(300));
+    assert_eq!(dims.yppu, 11_811);
+    assert_eq!(dims.unit, png::Unit::Meter);
+}
+
+#[test]
+fn jpeg_encoding_produces_decodable_image() {
+    let image = RgbaImage::from_pixel(8, 4, image::Rgba([10, 200, 30, 255]));
+    let bytes = encode_jpeg(&image, DEFAULT_JPEG_QUALITY, Some(96)).expect("encode jpeg");
+
+    assert_eq!(&bytes[..2], &[0xFF, 0xD8]);
+    let decoded = image::load_from_memory_with_format(&bytes, image::ImageFormat::Jpeg)
+        .expect("decode jpeg");
+    assert_eq!((decoded.width(), decoded.height()), (8, 4));
+}
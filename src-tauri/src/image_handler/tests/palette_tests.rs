@@ -0,0 +1,83 @@
+use super::*;
+use image::{ImageFormat, Rgba};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn unique_temp_dir(prefix: &str) -> std::path::PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let dir = std::env::temp_dir().join(format!("{}_{}", prefix, nanos));
+    let _ = std::fs::create_dir_all(&dir);
+    dir
+}
+
+/// 左 3/4 红色、右 1/4 蓝色
+fn red_blue_image(width: u32, height: u32) -> RgbaImage {
+    RgbaImage::from_fn(width, height, |x, _| {
+        if x < width * 3 / 4 {
+            Rgba([220, 20, 30, 255])
+        } else {
+            Rgba([10, 40, 200, 255])
+        }
+    })
+}
+
+#[test]
+fn median_cut_finds_dominant_colors_with_ratios() {
+    let palette = extract_palette(&red_blue_image(40, 10), 4);
+
+    assert_eq!(palette[0].hex, "#dc141e");
+    assert!((palette[0].ratio - 0.75).abs() < 0.01);
+    let blue = palette.iter().find(|c| c.hex == "#0a28c8").expect("blue in palette");
+    assert!((blue.ratio - 0.25).abs() < 0.01);
+    let total: f32 = palette.iter().map(|c| c.ratio).sum();
+    assert!((total - 1.0).abs() < 0.001);
+}
+
+#[test]
+fn median_cut_stops_when_colors_cannot_be_split() {
+    let solid = RgbaImage::from_pixel(8, 8, Rgba([1, 2, 3, 255]));
+    let palette = extract_palette(&solid, 6);
+    assert_eq!(palette.len(), 1);
+    assert_eq!(palette[0].rgb(), [1, 2, 3]);
+    assert_eq!(palette[0].ratio, 1.0);
+}
+
+#[test]
+fn transparent_pixels_are_ignored() {
+    let image = RgbaImage::from_fn(10, 10, |x, _| {
+        if x < 8 { Rgba([0, 0, 0, 0]) } else { Rgba([0, 200, 0, 255]) }
+    });
+    let palette = extract_palette(&image, 3);
+    assert_eq!(palette.len(), 1);
+    assert_eq!(palette[0].hex, "#00c800");
+
+    assert!(extract_palette(&RgbaImage::from_pixel(4, 4, Rgba([9, 9, 9, 0])), 3).is_empty());
+}
+
+#[test]
+fn extract_from_file_downscales_before_analysis() {
+    let dir = unique_temp_dir("cliphist_palette");
+    let path = dir.join("big.png");
+    image::DynamicImage::ImageRgba8(red_blue_image(800, 200))
+        .save_with_format(&path, ImageFormat::Png)
+        .expect("write png");
+
+    let palette = extract_palette_from_file(&path, 2).expect("palette");
+    assert_eq!(palette.len(), 2);
+    assert!(color_distance(palette[0].rgb(), [220, 20, 30]) < 20.0);
+    assert!(color_distance(palette[1].rgb(), [10, 40, 200]) < 20.0);
+
+    assert!(extract_palette_from_file(&dir.join("missing.png"), 2).is_err());
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn color_distance_is_symmetric_and_zero_for_equal_colors() {
+    let a = [200, 10, 10];
+    let b = [10, 10, 200];
+    assert_eq!(color_distance(a, a), 0.0);
+    assert_eq!(color_distance(a, b), color_distance(b, a));
+    assert!(color_distance([0, 0, 0], [255, 255, 255]) > 700.0);
+}
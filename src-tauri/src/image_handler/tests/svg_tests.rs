@@ -0,0 +1,106 @@
+use super::*;
+
+const BADGE: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" width="40" height="20" viewBox="0 0 40 20">
+  <rect x="0" y="0" width="20" height="20" fill="#ff0000"/>
+</svg>"##;
+
+fn options(width: Option<u32>, height: Option<u32>, dpi: Option<u32>) -> ImageOutputOptions {
+    ImageOutputOptions { width, height, dpi, ..ImageOutputOptions::default() }
+}
+
+#[test]
+fn raster_size_scales_intrinsic_size_by_dpi() {
+    assert_eq!(raster_size((40.0, 20.0), &options(None, None, None)).expect("size"), (40, 20));
+    assert_eq!(raster_size((40.0, 20.0), &options(None, None, Some(192))).expect("size"), (80, 40));
+    assert_eq!(raster_size((0.4, 0.2), &options(None, None, None)).expect("size"), (1, 1));
+}
+
+#[test]
+fn raster_size_prefers_explicit_dimensions() {
+    assert_eq!(raster_size((40.0, 20.0), &options(Some(100), None, Some(300))).expect("size"), (100, 50));
+    assert_eq!(raster_size((40.0, 20.0), &options(None, Some(10), None)).expect("size"), (20, 10));
+    assert_eq!(raster_size((40.0, 20.0), &options(Some(7), Some(9), None)).expect("size"), (7, 9));
+}
+
+#[test]
+fn raster_size_rejects_degenerate_svg() {
+    for size in [(0.0, 20.0), (40.0, -1.0), (f32::NAN, 20.0), (f32::INFINITY, 1.0)] {
+        assert!(matches!(raster_size(size, &ImageOutputOptions::default()), Err(ImageError::Decode(_))));
+    }
+}
+
+fn rasterize(svg: &str, config: &ImageConfig, options: &ImageOutputOptions) -> Result<PreparedClipboardImage, ImageError> {
+    let raw = RawImageData { bytes: svg.as_bytes().to_vec().into(), source_hint: "svg" };
+    ImageHandler::rasterize_svg_for_clipboard(raw, config, options)
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn rasterized_svg_keeps_source_and_composites_background() {
+    let opts = ImageOutputOptions { background: Some("#0000ff".to_string()), ..options(Some(80), None, None) };
+    let prepared = rasterize(BADGE, &ImageConfig::default(), &opts).expect("rasterize");
+
+    assert_eq!((prepared.width, prepared.height), (80, 40));
+    let pixel = |x: usize, y: usize| &prepared.bytes[(y * prepared.width + x) * 4..][..4];
+    assert_eq!(pixel(10, 10), &[255, 0, 0, 255]);
+    assert_eq!(pixel(70, 10), &[0, 0, 255, 255]);
+
+    let source = prepared.original.expect("svg source kept");
+    assert_eq!((source.mime, source.bytes.as_slice()), (SVG_MIME, BADGE.as_bytes()));
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn rasterize_respects_pixel_limit_and_rejects_invalid_svg() {
+    let tiny = ImageConfig { max_decoded_pixels: 100, ..ImageConfig::default() };
+    assert!(matches!(
+        rasterize(BADGE, &tiny, &ImageOutputOptions::default()),
+        Err(ImageError::ResourceLimit(_))
+    ));
+    assert!(matches!(
+        rasterize("<html></html>", &ImageConfig::default(), &ImageOutputOptions::default()),
+        Err(ImageError::InvalidFormat(_))
+    ));
+}
+
+#[cfg(feature = "svg")]
+#[test]
+fn embedded_images_load_only_from_data_urls() {
+    use base64::Engine as _;
+    use image::{ImageBuffer, ImageFormat, Rgba};
+    use std::io::Cursor;
+
+    let mut png = Vec::new();
+    image::DynamicImage::ImageRgba8(ImageBuffer::from_pixel(2, 2, Rgba([0u8, 255, 0, 255])))
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .expect("encode png");
+    let path = std::env::temp_dir().join(format!("cliphist_svg_href_{}.png", std::process::id()));
+    std::fs::write(&path, &png).expect("write png");
+
+    let with_href = |href: &str| {
+        format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="20" height="20"><image href="{}" width="20" height="20"/></svg>"#,
+            href
+        )
+    };
+    let center_pixel = |svg: String| {
+        let prepared = rasterize(&svg, &ImageConfig::default(), &ImageOutputOptions::default()).expect("rasterize");
+        prepared.bytes[(10 * prepared.width + 10) * 4..][..4].to_vec()
+    };
+
+    let data_url = format!("data:image/png;base64,{}", base64::engine::general_purpose::STANDARD.encode(&png));
+    assert_eq!(center_pixel(with_href(&data_url)), vec![0, 255, 0, 255]);
+    assert_eq!(center_pixel(with_href(&path.to_string_lossy()))[3], 0, "本地文件不应被读取");
+    assert_eq!(center_pixel(with_href("https://example.com/a.png"))[3], 0);
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[cfg(not(feature = "svg"))]
+#[test]
+fn rasterize_requires_svg_feature() {
+    assert!(matches!(
+        rasterize(BADGE, &ImageConfig::default(), &ImageOutputOptions::default()),
+        Err(ImageError::UnsupportedFormat(_))
+    ));
+}
@@ -0,0 +1,95 @@
+use super::*;
+use crate::image_handler::ImageConfig;
+use image::{ImageBuffer, Rgba};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::runtime::Runtime;
+
+fn unique_temp_dir(prefix: &str) -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let dir = std::env::temp_dir().join(format!("{}_{}", prefix, nanos));
+    let _ = fs::create_dir_all(&dir);
+    dir
+}
+
+fn write_png(path: &Path, width: u32, height: u32) {
+    let img = ImageBuffer::from_fn(width, height, |x, y| {
+        Rgba([(x % 255) as u8, (y % 255) as u8, 128, 255])
+    });
+    DynamicImage::ImageRgba8(img)
+        .save_with_format(path, ImageFormat::Png)
+        .expect("write test png");
+}
+
+#[test]
+fn snap_thumbnail_size_rounds_up_to_fixed_tiers() {
+    assert_eq!(snap_thumbnail_size(0), 128);
+    assert_eq!(snap_thumbnail_size(128), 128);
+    assert_eq!(snap_thumbnail_size(129), 256);
+    assert_eq!(snap_thumbnail_size(400), 512);
+    assert_eq!(snap_thumbnail_size(4096), 512);
+}
+
+#[test]
+fn thumbnail_path_uses_content_hash_for_content_addressed_sources() {
+    let cache = Path::new("/cache");
+    let hash = content_hash(b"bytes");
+    let source = PathBuf::from(format!("/images/{}/{}.png", &hash[..2], hash));
+
+    let path = thumbnail_path(cache, &source, 256, "webp");
+    assert_eq!(path, cache.join(&hash[..2]).join(format!("{}_256.webp", hash)));
+
+    let legacy = Path::new("/images/img_20260101010101000.png");
+    let legacy_path = thumbnail_path(cache, legacy, 256, "webp");
+    assert_ne!(legacy_path, thumbnail_path(cache, Path::new("/images/other.png"), 256, "webp"));
+}
+
+#[test]
+fn get_or_create_generates_caches_and_invalidates() {
+    let dir = unique_temp_dir("cliphist_thumbnail");
+    let cache_dir = dir.join("thumbs");
+    let source = dir.join("img_20260101010101000.png");
+    write_png(&source, 1000, 500);
+
+    let rt = Runtime::new().expect("create runtime");
+    let handler = ImageHandler::new(ImageConfig::default()).expect("handler init failed");
+    let service = ThumbnailService::new();
+
+    let first = rt
+        .block_on(service.get_or_create(&handler, &cache_dir, &source, 200))
+        .expect("thumbnail should be generated");
+    assert!(first.exists());
+    let (width, height) = image::image_dimensions(&first).expect("read thumbnail dims");
+    assert_eq!((width, height), (256, 128));
+
+    let second = rt
+        .block_on(service.get_or_create(&handler, &cache_dir, &source, 256))
+        .expect("thumbnail should be cached");
+    assert_eq!(first, second);
+
+    remove_thumbnails_in(&cache_dir, &source);
+    assert!(!first.exists());
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn get_or_create_does_not_upscale_small_images() {
+    let dir = unique_temp_dir("cliphist_thumbnail_small");
+    let cache_dir = dir.join("thumbs");
+    let source = dir.join("img_20260101010102000.png");
+    write_png(&source, 40, 30);
+
+    let rt = Runtime::new().expect("create runtime");
+    let handler = ImageHandler::new(ImageConfig::default()).expect("handler init failed");
+    let service = ThumbnailService::new();
+
+    let thumb = rt
+        .block_on(service.get_or_create(&handler, &cache_dir, &source, 512))
+        .expect("thumbnail should be generated");
+    assert_eq!(image::image_dimensions(&thumb).expect("dims"), (40, 30));
+
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,279 @@
+//! # 缩略图服务模块
+//!
+//! ## 设计思路
+//!
+//! 画廊直接加载原图（大尺寸截图）既慢又占内存。本模块按固定档位
+//! （`THUMBNAIL_SIZES`）懒生成缩略图并缓存到磁盘，后续请求直接命中文件。
+//!
+//! ## 实现思路
+//!
+//! 1. 请求尺寸向上取整到最近的固定档位，避免同一图片生成过多变体
+//! 2. 缓存键：内容寻址文件直接使用文件名哈希，旧版文件使用路径哈希 + mtime 校验
+//! 3. 解码/缩放复用 `pipeline.rs` 的 `fast_image_resize` 路径，在 `spawn_blocking` 中执行
+//! 4. 信号量限制并发生成数（后台池），同一缓存文件的并发请求串行化，避免重复计算
+//! 5. 优先编码为 WebP（无损），失败时回退 PNG
+//!
+//! ## 失效
+//!
+//! 源文件被 `cleanup_generated_assets` 删除时调用 `invalidate_thumbnails`，
+//! 移除该源文件所有档位的缩略图。
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use image::{DynamicImage, ImageFormat};
+use once_cell::sync::OnceCell;
+use tokio::sync::Semaphore;
+
+use crate::i18n::message;
+use crate::storage::{content_hash, is_content_hash};
+
+use super::{ImageError, ImageHandler};
+
+/// 缩略图固定档位（长边像素）。
+pub const THUMBNAIL_SIZES: [u32; 3] = [128, 256, 512];
+
+/// 缩略图缓存文件扩展名（按优先级）。
+const THUMBNAIL_EXTENSIONS: [&str; 2] = ["webp", "png"];
+
+/// 后台池最大并发生成数。
+const MAX_CONCURRENT_RENDERS: usize = 2;
+
+static THUMBNAIL_CACHE_DIR: OnceCell<PathBuf> = OnceCell::new();
+
+// ─── 缓存目录 ───────────────────────────────────────────────────────
+
+/// 注册缩略图缓存目录（在 `setup` 阶段调用一次）。
+pub fn init_thumbnail_cache(dir: PathBuf) -> Result<(), ImageError> {
+    fs::create_dir_all(&dir)
+        .map_err(|e| ImageError::FileSystem(message("image.thumbnail_cache_dir_failed", &[("detail", &e)])))?;
+    let _ = THUMBNAIL_CACHE_DIR.set(dir);
+    Ok(())
+}
+
+pub(crate) fn thumbnail_cache_dir() -> Result<&'static Path, ImageError> {
+    THUMBNAIL_CACHE_DIR
+        .get()
+        .map(PathBuf::as_path)
+        .ok_or_else(|| ImageError::FileSystem(message("image.thumbnail_cache_uninitialized", &[])))
+}
+
+// ─── 路径与档位 ─────────────────────────────────────────────────────
+
+/// 将请求尺寸向上取整到最近的固定档位（超出最大档位时取最大档位）。
+pub(crate) fn snap_thumbnail_size(requested: u32) -> u32 {
+    THUMBNAIL_SIZES
+        .iter()
+        .copied()
+        .find(|&size| size >= requested)
+        .unwrap_or(THUMBNAIL_SIZES[THUMBNAIL_SIZES.len() - 1])
+}
+
+/// 源文件的缓存键：内容寻址文件取文件名，否则取路径哈希。
+fn thumbnail_key(source: &Path) -> String {
+    let stem = source.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+    if is_content_hash(stem) {
+        return stem.to_string();
+    }
+    content_hash(source.to_string_lossy().as_bytes())
+}
+
+/// 缩略图路径：`<cache>/<key[0..2]>/<key>_<size>.<ext>`
+pub(crate) fn thumbnail_path(cache_dir: &Path, source: &Path, size: u32, ext: &str) -> PathBuf {
+    let key = thumbnail_key(source);
+    cache_dir.join(&key[..2]).join(format!("{}_{}.{}", key, size, ext))
+}
+
+/// 缓存文件是否仍然有效（不早于源文件修改时间）。
+fn is_fresh(thumb: &Path, source: &Path) -> bool {
+    let Ok(thumb_meta) = fs::metadata(thumb) else {
+        return false;
+    };
+    match (thumb_meta.modified(), fs::metadata(source).and_then(|m| m.modified())) {
+        (Ok(thumb_time), Ok(source_time)) => thumb_time >= source_time,
+        _ => false,
+    }
+}
+
+fn find_cached(cache_dir: &Path, source: &Path, size: u32) -> Option<PathBuf> {
+    THUMBNAIL_EXTENSIONS
+        .iter()
+        .map(|ext| thumbnail_path(cache_dir, source, size, ext))
+        .find(|path| is_fresh(path, source))
+}
+
+// ─── 失效 ───────────────────────────────────────────────────────────
+
+/// 删除指定源文件在缓存目录中的全部缩略图。
+pub(crate) fn remove_thumbnails_in(cache_dir: &Path, source: &Path) {
+    for size in THUMBNAIL_SIZES {
+        for ext in THUMBNAIL_EXTENSIONS {
+            let path = thumbnail_path(cache_dir, source, size, ext);
+            match fs::remove_file(&path) {
+                Ok(()) => log::debug!(
+                    "{}",
+                    message("image_log.thumbnail_deleted", &[("path", &path.display())])
+                ),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => log::warn!(
+                    "{}",
+                    message(
+                        "image_log.thumbnail_delete_failed",
+                        &[
+                            ("path", &path.display()),
+                            ("detail", &e),
+                        ],
+                    )
+                ),
+            }
+        }
+    }
+}
+
+/// 源文件被删除后使其缩略图失效（缓存目录未初始化时为空操作）。
+pub fn invalidate_thumbnails(source: &Path) {
+    if let Some(cache_dir) = THUMBNAIL_CACHE_DIR.get() {
+        remove_thumbnails_in(cache_dir, source);
+    }
+}
+
+// ─── 编码与落盘 ─────────────────────────────────────────────────────
+
+fn encode_thumbnail(image: &DynamicImage) -> Result<(Vec<u8>, &'static str), ImageError> {
+    let rgba = DynamicImage::ImageRgba8(image.to_rgba8());
+    let mut buffer = Vec::new();
+    match rgba.write_to(&mut Cursor::new(&mut buffer), ImageFormat::WebP) {
+        Ok(()) => Ok((buffer, "webp")),
+        Err(err) => {
+            log::warn!("{}", message("image_log.thumbnail_webp_fallback", &[("detail", &err)]));
+            buffer.clear();
+            rgba.write_to(&mut Cursor::new(&mut buffer), ImageFormat::Png)
+                .map_err(|e| ImageError::Decode(message("image.thumbnail_encode_failed", &[("detail", &e)])))?;
+            Ok((buffer, "png"))
+        }
+    }
+}
+
+fn write_atomically(path: &Path, bytes: &[u8]) -> Result<(), ImageError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| ImageError::FileSystem(message("image.thumbnail_dir_failed", &[("detail", &e)])))?;
+    }
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, bytes)
+        .map_err(|e| ImageError::FileSystem(message("image.thumbnail_write_failed", &[("detail", &e)])))?;
+    fs::rename(&tmp_path, path).map_err(|e| {
+        let _ = fs::remove_file(&tmp_path);
+        ImageError::FileSystem(message("image.thumbnail_write_failed", &[("detail", &e)]))
+    })
+}
+
+// ─── 服务 ───────────────────────────────────────────────────────────
+
+/// 缩略图生成服务：限制并发并合并同一缓存文件的重复请求。
+pub(crate) struct ThumbnailService {
+    permits: Semaphore,
+    in_flight: Mutex<HashMap<PathBuf, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+impl ThumbnailService {
+    pub(crate) fn new() -> Self {
+        Self {
+            permits: Semaphore::new(MAX_CONCURRENT_RENDERS),
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 获取缩略图路径；缓存未命中时生成。
+    pub(crate) async fn get_or_create(
+        &self,
+        handler: &ImageHandler,
+        cache_dir: &Path,
+        source: &Path,
+        requested_size: u32,
+    ) -> Result<PathBuf, ImageError> {
+        let size = snap_thumbnail_size(requested_size);
+        if let Some(cached) = find_cached(cache_dir, source, size) {
+            return Ok(cached);
+        }
+
+        // 同一源文件 + 档位的请求串行化：后到者等待后直接命中缓存
+        let key_path = thumbnail_path(cache_dir, source, size, "");
+        let key_lock = {
+            let mut in_flight = self
+                .in_flight
+                .lock()
+                .map_err(|_| ImageError::ResourceLimit(message("image.thumbnail_lock_poisoned", &[])))?;
+            in_flight.entry(key_path.clone()).or_default().clone()
+        };
+        let result = {
+            let _key_guard = key_lock.lock().await;
+            match find_cached(cache_dir, source, size) {
+                Some(cached) => Ok(cached),
+                None => self.render(handler, cache_dir, source, size).await,
+            }
+        };
+
+        if let Ok(mut in_flight) = self.in_flight.lock() {
+            // 仅剩任务表自身持有时移除，避免无限增长
+            if in_flight.get(&key_path).is_some_and(|lock| Arc::strong_count(lock) <= 2) {
+                in_flight.remove(&key_path);
+            }
+        }
+
+        result
+    }
+
+    async fn render(
+        &self,
+        handler: &ImageHandler,
+        cache_dir: &Path,
+        source: &Path,
+        size: u32,
+    ) -> Result<PathBuf, ImageError> {
+        let _permit = self
+            .permits
+            .acquire()
+            .await
+            .map_err(|_| ImageError::Cancelled(message("image.thumbnail_service_closed", &[])))?;
+
+        let config = handler.config_snapshot()?;
+        let raw = handler.load_from_file(&source.to_string_lossy(), &config).await?;
+
+        let source_owned = source.to_path_buf();
+        let cache_owned = cache_dir.to_path_buf();
+        tokio::task::spawn_blocking(move || {
+            let image = ImageHandler::render_thumbnail(raw, size, &config)?;
+            let (bytes, ext) = encode_thumbnail(&image)?;
+            let path = thumbnail_path(&cache_owned, &source_owned, size, ext);
+            write_atomically(&path, &bytes)?;
+            log::debug!(
+                "{}",
+                message(
+                    "image_log.thumbnail_generated",
+                    &[
+                        ("width", &image.width()),
+                        ("height", &image.height()),
+                        ("path", &path.display()),
+                    ],
+                )
+            );
+            Ok(path)
+        })
+        .await
+        .map_err(|e| ImageError::Decode(message("image.thumbnail_task_failed", &[("detail", &e)])))?
+    }
+}
+
+impl Default for ThumbnailService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/thumbnail_tests.rs"]
+mod tests;
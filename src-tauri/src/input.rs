@@ -28,9 +28,10 @@ pub fn copy_file_to_clipboard(path: String) -> Result<(), AppError> {
     commands::copy_file_to_clipboard(path)
 }
 
+/// 复制（`cut = true` 时为剪切）文件列表到剪贴板
 #[tauri::command]
-pub fn copy_files_to_clipboard(paths: Vec<String>) -> Result<(), AppError> {
-    commands::copy_files_to_clipboard(paths)
+pub fn copy_files_to_clipboard(paths: Vec<String>, cut: Option<bool>) -> Result<(), AppError> {
+    commands::copy_files_to_clipboard(paths, cut.unwrap_or(false))
 }
 
 #[tauri::command]
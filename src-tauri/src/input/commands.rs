@@ -14,8 +14,8 @@ pub fn copy_file_to_clipboard(path: String) -> Result<(), AppError> {
     services::copy_file_to_clipboard(path)
 }
 
-pub fn copy_files_to_clipboard(paths: Vec<String>) -> Result<(), AppError> {
-    services::copy_files_to_clipboard(paths)
+pub fn copy_files_to_clipboard(paths: Vec<String>, cut: bool) -> Result<(), AppError> {
+    services::copy_files_to_clipboard(paths, cut)
 }
 
 pub async fn open_file(path: String) -> Result<(), AppError> {
@@ -1,7 +1,8 @@
 //! 平台相关实现
 //!
 //! - **Windows**: Win32 Shell / GDI / Clipboard API 封装
-//! - **非 Windows**: 占位桩实现（返回 `Err` 或 `None`）
+//! - **Linux**: 文件剪贴板经由 X11 selection（`clipboard::x11_selection`），其余走 `xdg-open`
+//! - **其他平台**: 占位桩实现（返回 `Err` 或 `None`）
 //!
 //! ## 设计要点
 //!
@@ -11,6 +12,7 @@
 //! - 画刷创建/销毁封装为 `fill_rect_color` 辅助函数，消除重复模式
 
 use crate::error::AppError;
+use crate::i18n::message;
 
 // ═══════════════════════════════════════════════════════════
 //  Windows — 通用工具
@@ -69,7 +71,7 @@ impl ComGuard {
         } else if hr == RPC_E_CHANGED_MODE {
             Ok(Self(false))
         } else {
-            Err(AppError::Input(format!("COM 初始化失败: {:?}", hr)))
+            Err(AppError::Input(message("input.com_init_failed", &[("detail", &format!("{:?}", hr))])))
         }
     }
 }
@@ -128,7 +130,7 @@ impl ClipboardSession {
     fn open() -> Result<Self, AppError> {
         unsafe {
             windows::Win32::System::DataExchange::OpenClipboard(None)
-                .map_err(|e| AppError::Clipboard(format!("打开剪贴板失败: {:?}", e)))?;
+                .map_err(|e| AppError::ClipboardBusy(message("clipboard.open_failed", &[("detail", &format!("{:?}", e))])))?;
         }
         Ok(Self)
     }
@@ -271,17 +273,22 @@ pub fn open_file(path: &str) -> Result<(), AppError> {
 
     if result.0 as isize <= 32 {
         log::warn!(
-            "open_file 失败: {} (ShellExecuteW={})",
-            log_path(path),
-            result.0 as isize
+            "{}",
+            message(
+                "input_log.open_file_failed",
+                &[
+                    ("path", &log_path(path)),
+                    ("code", &(result.0 as isize)),
+                ],
+            )
         );
-        return Err(AppError::Input(format!(
-            "打开文件失败: ShellExecuteW 返回 {}",
-            result.0 as isize
+        return Err(AppError::Input(message(
+            "input.open_file_shell_failed",
+            &[("code", &(result.0 as isize))],
         )));
     }
 
-    log::debug!("open_file 成功: {}", log_path(path));
+    log::debug!("{}", message("input_log.open_file_ok", &[("path", &log_path(path))]));
     Ok(())
 }
 
@@ -290,7 +297,7 @@ pub fn open_file(path: &str) -> Result<(), AppError> {
     std::process::Command::new("open")
         .arg(path)
         .spawn()
-        .map_err(|e| AppError::Input(format!("打开文件失败: {}", e)))?;
+        .map_err(|e| AppError::Input(message("input.open_file_failed", &[("detail", &e)])))?;
     Ok(())
 }
 
@@ -299,7 +306,7 @@ pub fn open_file(path: &str) -> Result<(), AppError> {
     std::process::Command::new("xdg-open")
         .arg(path)
         .spawn()
-        .map_err(|e| AppError::Input(format!("打开文件失败: {}", e)))?;
+        .map_err(|e| AppError::Input(message("input.open_file_failed", &[("detail", &e)])))?;
     Ok(())
 }
 
@@ -316,8 +323,8 @@ pub fn open_file_location(path: &str) -> Result<(), AppError> {
         Some(p) => p,
         None => {
             log::debug!(
-                "open_file_location: 无法解析父目录，走 fallback: {}",
-                log_path(path)
+                "{}",
+                message("input_log.open_location_no_parent", &[("path", &log_path(path))])
             );
             return open_file_location_fallback(path);
         }
@@ -369,16 +376,21 @@ pub fn open_file_location(path: &str) -> Result<(), AppError> {
         match select_result {
             Ok(()) => {
                 log::debug!(
-                    "open_file_location: SHOpenFolderAndSelectItems 成功: {}",
-                    log_path(path)
+                    "{}",
+                    message("input_log.open_location_ok", &[("path", &log_path(path))])
                 );
                 Ok(())
             }
             Err(e) => {
                 log::debug!(
-                    "open_file_location: SHOpenFolderAndSelectItems 失败，回退 explorer /select: {} ({})",
-                    log_path(path),
-                    e
+                    "{}",
+                    message(
+                        "input_log.open_location_select_failed",
+                        &[
+                            ("path", &log_path(path)),
+                            ("detail", &e),
+                        ],
+                    )
                 );
                 open_file_location_fallback(path)
             }
@@ -408,20 +420,22 @@ fn open_file_location_fallback(path: &str) -> Result<(), AppError> {
 
     if result.0 as isize <= 32 {
         log::warn!(
-            "open_file_location fallback 失败: {} (ShellExecuteW={})",
-            log_path(path),
-            result.0 as isize
+            "{}",
+            message(
+                "input_log.open_location_fallback_failed",
+                &[
+                    ("path", &log_path(path)),
+                    ("code", &(result.0 as isize)),
+                ],
+            )
         );
-        return Err(AppError::Input(format!(
-            "打开文件位置失败: ShellExecuteW 返回 {}",
-            result.0 as isize
+        return Err(AppError::Input(message(
+            "input.open_file_location_shell_failed",
+            &[("code", &(result.0 as isize))],
         )));
     }
 
-    log::debug!(
-        "open_file_location fallback 成功: {}",
-        log_path(path)
-    );
+    log::debug!("{}", message("input_log.open_location_fallback_ok", &[("path", &log_path(path))]));
     Ok(())
 }
 
@@ -430,7 +444,7 @@ pub fn open_file_location(path: &str) -> Result<(), AppError> {
     std::process::Command::new("open")
         .args(["-R", path])
         .spawn()
-        .map_err(|e| AppError::Input(format!("打开文件位置失败: {}", e)))?;
+        .map_err(|e| AppError::Input(message("input.open_file_location_failed", &[("detail", &e)])))?;
     Ok(())
 }
 
@@ -443,7 +457,7 @@ pub fn open_file_location(path: &str) -> Result<(), AppError> {
     std::process::Command::new("xdg-open")
         .arg(&parent)
         .spawn()
-        .map_err(|e| AppError::Input(format!("打开文件位置失败: {}", e)))?;
+        .map_err(|e| AppError::Input(message("input.open_file_location_failed", &[("detail", &e)])))?;
     Ok(())
 }
 
@@ -451,20 +465,13 @@ pub fn open_file_location(path: &str) -> Result<(), AppError> {
 //  剪贴板操作
 // ═══════════════════════════════════════════════════════════
 
-#[cfg(target_os = "windows")]
 pub fn copy_file_to_clipboard(path: String) -> Result<(), AppError> {
-    copy_files_to_clipboard(vec![path])
-}
-
-#[cfg(not(target_os = "windows"))]
-pub fn copy_file_to_clipboard(_path: String) -> Result<(), AppError> {
-    Err(AppError::Input(
-        "文件剪贴板复制仅在 Windows 上支持".to_string(),
-    ))
+    copy_files_to_clipboard(vec![path], false)
 }
 
+/// `cut` 为 `true` 时附带 `Preferred DropEffect = DROPEFFECT_MOVE`，资源管理器粘贴后移动文件。
 #[cfg(target_os = "windows")]
-pub fn copy_files_to_clipboard(paths: Vec<String>) -> Result<(), AppError> {
+pub fn copy_files_to_clipboard(paths: Vec<String>, cut: bool) -> Result<(), AppError> {
     use std::ffi::OsStr;
     use std::os::windows::ffi::OsStrExt;
     use windows::Win32::Foundation::GlobalFree;
@@ -474,7 +481,7 @@ pub fn copy_files_to_clipboard(paths: Vec<String>) -> Result<(), AppError> {
     use windows::Win32::UI::Shell::DROPFILES;
 
     if paths.is_empty() {
-        return Err(AppError::Clipboard("没有可复制的文件路径".to_string()));
+        return Err(AppError::Clipboard(message("input.no_file_paths", &[])));
     }
 
     let encoded_paths: Vec<Vec<u16>> = paths
@@ -494,7 +501,7 @@ pub fn copy_files_to_clipboard(paths: Vec<String>) -> Result<(), AppError> {
 
     unsafe {
         EmptyClipboard()
-            .map_err(|e| AppError::Clipboard(format!("清空剪贴板失败: {:?}", e)))?;
+            .map_err(|e| AppError::Clipboard(message("clipboard.empty_failed", &[("detail", &format!("{:?}", e))])))?;
 
         // 计算 DROPFILES + 所有路径（含 null 终止符）+ 双 null 终止符
         let size = std::mem::size_of::<DROPFILES>()
@@ -505,16 +512,19 @@ pub fn copy_files_to_clipboard(paths: Vec<String>) -> Result<(), AppError> {
             + std::mem::size_of::<u16>();
 
         let hglobal = GlobalAlloc(GMEM_MOVEABLE, size)
-            .map_err(|e| AppError::Clipboard(format!("分配内存失败: {:?}", e)))?;
+            .map_err(|e| AppError::Clipboard(message(
+                "clipboard.alloc_failed",
+                &[("format", &"CF_HDROP"), ("detail", &format!("{:?}", e))],
+            )))?;
 
         let ptr = GlobalLock(hglobal) as *mut u8;
         if ptr.is_null() {
             let _ = GlobalFree(Some(hglobal));
             log::warn!(
-                "copy_files_to_clipboard 失败: 锁定内存失败 (count={})",
-                paths.len()
+                "{}",
+                message("input_log.copy_files_lock_failed", &[("count", &paths.len())])
             );
-            return Err(AppError::Clipboard("锁定内存失败".to_string()));
+            return Err(AppError::Clipboard(message("clipboard.lock_memory_failed", &[("format", &"CF_HDROP")])));
         }
 
         // 填充 DROPFILES 头
@@ -542,26 +552,99 @@ pub fn copy_files_to_clipboard(paths: Vec<String>) -> Result<(), AppError> {
             let _ = GlobalFree(Some(hglobal));
             let first = paths.first().map(String::as_str).unwrap_or("");
             log::warn!(
-                "copy_files_to_clipboard 失败: SetClipboardData (count={}, first={})",
-                paths.len(),
-                log_path(first)
+                "{}",
+                message(
+                    "input_log.copy_files_set_data_failed",
+                    &[
+                        ("count", &paths.len()),
+                        ("first", &log_path(first)),
+                    ],
+                )
             );
-            return Err(AppError::Clipboard(format!(
-                "设置剪贴板数据失败: {:?}",
-                e
+            return Err(AppError::Clipboard(message(
+                "clipboard.set_data_failed",
+                &[("format", &"CF_HDROP"), ("detail", &format!("{:?}", e))],
             )));
         }
 
-        log::info!("文件已复制到剪贴板: {} 个", paths.len());
+        if cut {
+            set_preferred_drop_effect_move();
+        }
+
+        let id = if cut { "input_log.files_cut" } else { "input_log.files_copied" };
+        log::info!("{}", message(id, &[("count", &paths.len())]));
         Ok(())
     }
 }
 
-#[cfg(not(target_os = "windows"))]
-pub fn copy_files_to_clipboard(_paths: Vec<String>) -> Result<(), AppError> {
-    Err(AppError::Input(
-        "文件剪贴板复制仅在 Windows 上支持".to_string(),
-    ))
+/// 写入 `Preferred DropEffect = DROPEFFECT_MOVE`，标记本次为剪切
+///
+/// 调用前必须已打开剪贴板；失败仅记录日志（粘贴时退化为复制）。
+#[cfg(target_os = "windows")]
+unsafe fn set_preferred_drop_effect_move() {
+    use windows::Win32::Foundation::GlobalFree;
+    use windows::Win32::System::DataExchange::{RegisterClipboardFormatW, SetClipboardData};
+    use windows::Win32::System::Memory::{GlobalAlloc, GlobalLock, GlobalUnlock, GMEM_MOVEABLE};
+    use windows::Win32::System::Ole::DROPEFFECT_MOVE;
+    use windows::core::w;
+
+    unsafe {
+        let format_id = RegisterClipboardFormatW(w!("Preferred DropEffect"));
+        if format_id == 0 {
+            log::warn!("{}", message("input_log.drop_effect_register_failed", &[]));
+            return;
+        }
+        let Ok(hglobal) = GlobalAlloc(GMEM_MOVEABLE, std::mem::size_of::<u32>()) else {
+            log::warn!("{}", message("input_log.drop_effect_alloc_failed", &[]));
+            return;
+        };
+        let ptr = GlobalLock(hglobal) as *mut u32;
+        if ptr.is_null() {
+            let _ = GlobalFree(Some(hglobal));
+            return;
+        }
+        *ptr = DROPEFFECT_MOVE.0;
+        let _ = GlobalUnlock(hglobal);
+
+        if let Err(e) = SetClipboardData(format_id, Some(windows::Win32::Foundation::HANDLE(hglobal.0))) {
+            let _ = GlobalFree(Some(hglobal));
+            log::warn!(
+                "{}",
+                message("input_log.drop_effect_set_failed", &[("detail", &format!("{:?}", e))])
+            );
+        }
+    }
+}
+
+/// X11：成为 CLIPBOARD 所有者，提供 `x-special/gnome-copied-files`、`text/uri-list`
+/// 与纯文本路径，供 Nautilus / Dolphin / Thunar 粘贴。
+#[cfg(target_os = "linux")]
+pub fn copy_files_to_clipboard(paths: Vec<String>, cut: bool) -> Result<(), AppError> {
+    if paths.is_empty() {
+        return Err(AppError::Clipboard(message("input.no_file_paths", &[])));
+    }
+
+    // 文件管理器只接受绝对路径的 file:// URI
+    let paths: Vec<String> = paths
+        .iter()
+        .map(|p| {
+            std::path::absolute(p)
+                .map(|abs| abs.to_string_lossy().to_string())
+                .unwrap_or_else(|_| p.clone())
+        })
+        .collect();
+
+    let _ignore = crate::clipboard::IgnoreGuard::new();
+    crate::clipboard::x11_selection::copy_files_to_clipboard(&paths, cut)?;
+
+    let id = if cut { "input_log.files_cut" } else { "input_log.files_copied" };
+    log::info!("{}", message(id, &[("count", &paths.len())]));
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn copy_files_to_clipboard(_paths: Vec<String>, _cut: bool) -> Result<(), AppError> {
+    Err(AppError::Unsupported(message("input.file_clipboard_unsupported", &[])))
 }
 
 // ═══════════════════════════════════════════════════════════
@@ -582,7 +665,7 @@ pub async fn get_file_icon(input: String) -> Result<Option<String>, AppError> {
     let key = cache_key.clone();
     let icon = tokio::task::spawn_blocking(move || get_file_icon_blocking(&input))
         .await
-        .map_err(|e| AppError::Input(format!("图标任务执行失败: {}", e)))??;
+        .map_err(|e| AppError::Input(message("input.icon_task_failed", &[("detail", &e)])))??;
 
     // 缓存 Some 和 None，避免对不存在图标的重复 GDI + PNG 开销
     if let Ok(mut cache) = FILE_ICON_CACHE.lock() {
@@ -794,12 +877,12 @@ fn get_file_icon_blocking(input: &str) -> Result<Option<String>, AppError> {
         // ── 5. PNG 编码 + Base64 ──
 
         let img = image::RgbaImage::from_raw(size as u32, size as u32, rgba)
-            .ok_or_else(|| AppError::Input("创建图标缓冲区失败".to_string()))?;
+            .ok_or_else(|| AppError::Input(message("input.icon_buffer_failed", &[])))?;
 
         // 预分配 4KB（32x32 图标 PNG 一般 1-3KB）
         let mut buf = std::io::Cursor::new(Vec::with_capacity(4096));
         img.write_to(&mut buf, image::ImageFormat::Png)
-            .map_err(|e| AppError::Input(format!("编码图标 PNG 失败: {}", e)))?;
+            .map_err(|e| AppError::Input(message("input.icon_encode_failed", &[("detail", &e)])))?;
 
         let b64 = general_purpose::STANDARD.encode(buf.into_inner());
         Ok(Some(format!("data:image/png;base64,{}", b64)))
@@ -23,6 +23,7 @@ use tauri::Manager;
 use tokio::time::sleep;
 
 use crate::error::AppError;
+use crate::i18n::message;
 use crate::ipc::WINDOW_LABEL_MAIN;
 use crate::window_position;
 
@@ -43,7 +44,7 @@ const CLICK_SETTLE_MS: u64 = 100;
 /// 创建 `Enigo` 输入模拟实例。
 fn create_enigo() -> Result<Enigo, AppError> {
     Enigo::new(&Settings::default())
-        .map_err(|e| AppError::Input(format!("初始化输入模拟失败: {}", e)))
+        .map_err(|e| AppError::Input(message("input.enigo_init_failed", &[("detail", &e)])))
 }
 
 /// 隐藏主窗口和所有 HUD 窗口。
@@ -65,7 +66,7 @@ fn simulate_paste(enigo: &mut Enigo) -> Result<(), AppError> {
         .key(modifier, Press)
         .and_then(|_| enigo.key(Key::Unicode('v'), Click))
         .and_then(|_| enigo.key(modifier, Release))
-        .map_err(|e| AppError::Input(format!("模拟粘贴按键失败: {}", e)))
+        .map_err(|e| AppError::Input(message("input.paste_key_failed", &[("detail", &e)])))
 }
 
 // ═══════════════════════════════════════════════════════════
@@ -93,14 +94,14 @@ pub async fn click_and_paste(app: tauri::AppHandle) -> Result<(), AppError> {
     {
         enigo
             .button(Button::Left, Click)
-            .map_err(|e| AppError::Input(format!("模拟鼠标点击失败: {}", e)))?;
-        log::debug!("已模拟鼠标点击");
+            .map_err(|e| AppError::Input(message("input.mouse_click_failed", &[("detail", &e)])))?;
+        log::debug!("{}", message("input_log.mouse_clicked", &[]));
     }
 
     sleep(Duration::from_millis(CLICK_SETTLE_MS)).await;
 
     simulate_paste(&mut enigo)?;
-    log::debug!("已点击并粘贴");
+    log::debug!("{}", message("input_log.clicked_and_pasted", &[]));
     Ok(())
 }
 
@@ -108,8 +109,8 @@ pub fn copy_file_to_clipboard(path: String) -> Result<(), AppError> {
     platform::copy_file_to_clipboard(path)
 }
 
-pub fn copy_files_to_clipboard(paths: Vec<String>) -> Result<(), AppError> {
-    platform::copy_files_to_clipboard(paths)
+pub fn copy_files_to_clipboard(paths: Vec<String>, cut: bool) -> Result<(), AppError> {
+    platform::copy_files_to_clipboard(paths, cut)
 }
 
 pub async fn open_file(path: String) -> Result<(), AppError> {
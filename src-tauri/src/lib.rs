@@ -16,6 +16,7 @@
 //! │       ↕            后端 (Rust)                           │
 //! │                                                          │
 //! │  ┌─ error ────── AppError (统一错误类型)                  │
+//! │  ├─ i18n ─────── 文案目录 zh-CN / en                     │
 //! │  │                                                       │
 //! │  ├─ db ───────── SQLite (rusqlite) CRUD + 统计             │
 //! │  │                                                       │
@@ -24,6 +25,8 @@
 //! │  │   └─ code_detection 正则代码特征                       │
 //! │  │                                                       │
 //! │  ├─ image_handler      图片下载·解码·复制                 │
+//! │  ├─ color              颜色识别·格式互转·对比度           │
+//! │  ├─ transform          文本转换链·转换并粘贴              │
 //! │  ├─ input              键盘鼠标模拟 / 文件复制            │
 //! │  ├─ storage            图片存储目录 (返回 Result)         │
 //! │  └─ window_position    窗口定位·多屏·状态切换             │
@@ -35,18 +38,24 @@
 //! | 模块 | 职责 |
 //! |------|------|
 //! | [`error`] | 统一错误类型 `AppError`，所有 Tauri command 的返回类型 |
+//! | [`i18n`] | 后端文案目录（zh-CN / en）、错误与托盘菜单本地化 |
 //! | [`db`] | SQLite 数据库 CRUD、统计、导入导出、自动清理 |
 //! | [`clipboard`] | 剪贴板监控、IgnoreGuard RAII、代码检测、图片/SVG 保存 |
 //! | [`image_handler`] | 从 URL/Base64/文件加载图片并复制到剪贴板 |
+//! | [`color`] | 颜色字面量识别、hex/rgb/hsl/hwb/oklch/CMYK 互转、WCAG 对比度 |
+//! | [`transform`] | 可组合的文本转换注册表、转换预览与“转换并粘贴” |
 //! | [`input`] | 模拟键盘粘贴、鼠标点击、Windows 文件路径复制 |
 //! | [`storage`] | 图片存储目录的获取与自动创建 |
 //! | [`window_position`] | 跨平台光标定位、多显示器窗口摆放、状态切换 |
 
 pub mod error;
+pub mod i18n;
 pub mod ipc;
 pub mod clipboard;
 pub mod db;
 pub mod image_handler;
+pub mod color;
+pub mod transform;
 pub mod input;
 pub mod storage;
 pub mod settings;
@@ -6,7 +6,9 @@
 //! 本文件仅负责应用初始化与插件/命令注册。
 //! 业务逻辑分布在各子模块中，详见 `lib.rs` 架构文档。
 
-use clipboard_history::{clipboard, db, image_handler, input, settings, storage, window_position};
+use clipboard_history::{
+    clipboard, color, db, i18n, image_handler, input, settings, storage, transform, window_position,
+};
 use clipboard_history::ipc::WINDOW_LABEL_MAIN;
 use tauri::Manager;
 use tauri::image::Image;
@@ -55,8 +57,13 @@ fn main() {
             let app_icon = Image::from_bytes(include_bytes!("../icons/Clipboard-256x256.png"))?;
             log::info!("setup: icon loaded");
 
-            // 初始化数据库并注册为托管状态
+            // 后端消息语言需在产生任何错误文案与托盘菜单之前确定
             let handle = app.handle().clone();
+            if let Ok(Some(app_settings)) = settings::get_app_settings(handle.clone()) {
+                i18n::apply_settings_from_value(&app_settings);
+            }
+
+            // 初始化数据库并注册为托管状态
             match db::init_db(&handle) {
                 Ok(db_state) => {
                     app.manage(db_state);
@@ -77,6 +84,18 @@ fn main() {
                 }
             }
 
+            match app.path().app_cache_dir() {
+                Ok(cache_dir) => {
+                    if let Err(err) = image_handler::init_thumbnail_cache(cache_dir.join("thumbnails")) {
+                        log::error!("setup: 缩略图缓存初始化失败: {err}");
+                    }
+                    if let Err(err) = image_handler::init_download_cache(cache_dir.join("image_downloads")) {
+                        log::error!("setup: 图片下载缓存初始化失败: {err}");
+                    }
+                }
+                Err(err) => log::error!("setup: 无法解析缓存目录，缩略图与下载缓存不可用: {err}"),
+            }
+
             // 显式设置主窗口图标，避免平台默认图标与配置不一致
             if let Some(main_window) = app.get_webview_window(WINDOW_LABEL_MAIN) {
                 let _ = main_window.set_icon(app_icon.clone());
@@ -84,14 +103,17 @@ fn main() {
 
             log::info!("setup: main window icon set");
 
-            // 启动剪贴板监控
+            // 启动剪贴板监控（Linux 另启动可选的 PRIMARY selection 监听）
+            #[cfg(target_os = "linux")]
+            clipboard::start_primary_monitoring(handle.clone());
             clipboard::start_monitoring(handle);
             log::info!("setup: clipboard monitor stage done");
 
             // 创建托盘菜单
-            let quit_i = MenuItem::with_id(app, "quit", "退出", true, None::<&str>)?;
-            let show_i = MenuItem::with_id(app, "show", "显示", true, None::<&str>)?;
+            let quit_i = MenuItem::with_id(app, "quit", i18n::message("tray.quit", &[]), true, None::<&str>)?;
+            let show_i = MenuItem::with_id(app, "show", i18n::message("tray.show", &[]), true, None::<&str>)?;
             let menu = Menu::with_items(app, &[&show_i, &quit_i])?;
+            app.manage(i18n::TrayMenu { show: show_i, quit: quit_i });
 
             // 创建托盘图标（失败时回退显示主窗口，避免进程在后台无入口）
             let tray_result = TrayIconBuilder::new()
@@ -202,15 +224,23 @@ fn main() {
             clipboard::save::copy_svg_from_file,
             clipboard::save::read_clipboard_files,
             clipboard::save::write_text_to_clipboard,
+            clipboard::restore::copy_clip_to_clipboard,
             // 图片处理
             image_handler::commands::download_and_copy_image,
             image_handler::commands::cancel_image_download,
+            image_handler::commands::pause_image_download,
+            image_handler::commands::retry_image_download,
+            image_handler::commands::list_image_downloads,
             image_handler::commands::copy_base64_image_to_clipboard,
             image_handler::commands::copy_image_to_clipboard,
+            image_handler::commands::copy_svg_image_to_clipboard,
             image_handler::commands::set_image_performance_profile,
             image_handler::commands::get_image_performance_profile,
             image_handler::commands::set_image_advanced_config,
             image_handler::commands::get_image_advanced_config,
+            image_handler::commands::get_thumbnail,
+            image_handler::commands::get_image_download_cache_info,
+            image_handler::commands::clear_image_download_cache,
             // 输入模拟 & 文件操作
             input::paste_text,
             input::click_and_paste,
@@ -243,6 +273,10 @@ fn main() {
             db::db_auto_clear,
             db::db_get_stats,
             db::db_get_history,
+            db::db_get_suggested_actions,
+            db::fetch_link_preview,
+            db::edit_image,
+            db::download_image_to_history,
             db::db_add_clip,
             db::db_add_clip_and_get,
             db::db_toggle_pin,
@@ -257,6 +291,20 @@ fn main() {
             db::db_add_clip_snapshot,
             db::db_get_clip_formats,
             db::db_update_clip_format,
+            db::db_find_similar_images,
+            db::db_find_duplicate_image_groups,
+            db::db_delete_duplicate_images,
+            db::get_image_palette,
+            db::db_search_by_color,
+            db::db_color_contrast,
+            color::convert_color,
+            // 文本转换
+            transform::list_transforms,
+            transform::transform_text,
+            transform::transform_and_paste,
+            db::db_get_transform_chains,
+            db::db_save_transform_chain,
+            db::db_delete_transform_chain,
             // 标签操作
             db::db_get_tags,
             db::db_create_tag,
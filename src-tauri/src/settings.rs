@@ -5,15 +5,16 @@ use tauri::{AppHandle, Manager};
 
 use crate::clipboard;
 use crate::error::AppError;
+use crate::i18n::{self, message};
 
 fn settings_file_path(app: &AppHandle) -> Result<PathBuf, AppError> {
     let app_data_dir = app
         .path()
         .app_data_dir()
-        .map_err(|e| AppError::Storage(format!("获取应用数据目录失败: {}", e)))?;
+        .map_err(|e| AppError::Storage(message("settings.app_data_dir_failed", &[("detail", &e)])))?;
 
     fs::create_dir_all(&app_data_dir)
-        .map_err(|e| AppError::Storage(format!("创建应用数据目录失败: {}", e)))?;
+        .map_err(|e| AppError::Storage(message("settings.create_dir_failed", &[("detail", &e)])))?;
 
     Ok(app_data_dir.join("settings.json"))
 }
@@ -27,7 +28,7 @@ pub fn get_app_settings(app: AppHandle) -> Result<Option<serde_json::Value>, App
 
     let content = fs::read_to_string(&settings_path)?;
     let parsed = serde_json::from_str::<serde_json::Value>(&content)
-        .map_err(|e| AppError::Storage(format!("解析设置文件失败: {}", e)))?;
+        .map_err(|e| AppError::Storage(message("settings.parse_failed", &[("detail", &e)])))?;
 
     Ok(Some(parsed))
 }
@@ -37,9 +38,14 @@ pub fn set_app_settings(app: AppHandle, settings: serde_json::Value) -> Result<(
     let settings_path = settings_file_path(&app)?;
 
     clipboard::apply_runtime_settings(&settings);
+    if i18n::apply_settings_from_value(&settings)
+        && let Some(tray) = app.try_state::<i18n::TrayMenu>()
+    {
+        tray.relabel();
+    }
 
     let content = serde_json::to_string_pretty(&settings)
-        .map_err(|e| AppError::Storage(format!("序列化设置失败: {}", e)))?;
+        .map_err(|e| AppError::Storage(message("settings.serialize_failed", &[("detail", &e)])))?;
 
     fs::write(settings_path, content)?;
     Ok(())
@@ -11,14 +11,22 @@
 //! - 未设置时回退到应用默认数据目录下的 `images` 子目录。
 //! - 目录不存在时自动 `create_dir_all`，避免上层判断。
 //! - 所有可能失败的操作均返回 `Result`，不使用 `expect()` / `unwrap()`。
+//! - 剪贴板捕获的资源按内容寻址存储：文件名为 SHA-256，
+//!   并按哈希前两位分片到子目录，相同内容只落盘一次。
 
 use serde::Serialize;
+use sha2::{Digest, Sha256};
 use tauri::AppHandle;
 use tauri::Manager;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::fs;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 use crate::error::AppError;
+use crate::i18n::message;
+
+/// 临时文件序号：与进程号一起保证并发写入同一哈希时临时文件名互不冲突
+static TMP_SEQ: AtomicU64 = AtomicU64::new(0);
 
 /// 存储目录信息
 #[derive(Debug, Clone, Serialize)]
@@ -59,7 +67,7 @@ fn walk_dir_stats(dir: &PathBuf, recursive: bool) -> (u64, u64) {
 ///
 /// # 返回
 /// - `Ok(PathBuf)` — 可用的图片存储目录
-/// - `Err(AppError::Storage)` — 无法获取或创建目录
+/// - `Err(AppError::Storage)` — 无法获取或创建目录（磁盘已满时为 `AppError::StorageFull`）
 pub fn get_images_dir(app: &AppHandle, custom_dir: Option<String>) -> Result<PathBuf, AppError> {
     // 优先使用用户自定义目录
     if let Some(dir) = custom_dir {
@@ -67,7 +75,7 @@ pub fn get_images_dir(app: &AppHandle, custom_dir: Option<String>) -> Result<Pat
         if !dir.is_empty() {
             let path = PathBuf::from(dir);
             fs::create_dir_all(&path).map_err(|e| {
-                AppError::Storage(format!("创建自定义目录 '{}' 失败: {}", dir, e))
+                AppError::from_io(&message("storage.create_custom_dir", &[("path", &dir)]), &e, AppError::Storage)
             })?;
             return Ok(path);
         }
@@ -75,15 +83,82 @@ pub fn get_images_dir(app: &AppHandle, custom_dir: Option<String>) -> Result<Pat
 
     // 使用应用默认数据目录
     let app_data_dir = app.path().app_data_dir().map_err(|e| {
-        AppError::Storage(format!("获取应用数据目录失败: {}", e))
+        AppError::with_context(message("storage.app_data_dir", &[]), AppError::Storage(e.to_string()))
     })?;
     let images_dir = app_data_dir.join("images");
-    fs::create_dir_all(&images_dir).map_err(|e| {
-        AppError::Storage(format!("创建图片目录失败: {}", e))
-    })?;
+    fs::create_dir_all(&images_dir)
+        .map_err(|e| AppError::from_io(&message("storage.create_images_dir", &[]), &e, AppError::Storage))?;
     Ok(images_dir)
 }
 
+/// 计算字节内容的 SHA-256（小写十六进制）
+pub fn content_hash(bytes: &[u8]) -> String {
+    let digest = Sha256::digest(bytes);
+    let mut hex = String::with_capacity(digest.len() * 2);
+    for byte in digest {
+        hex.push_str(&format!("{:02x}", byte));
+    }
+    hex
+}
+
+/// 判断字符串是否为合法的内容哈希（64 位小写十六进制）
+pub fn is_content_hash(value: &str) -> bool {
+    value.len() == 64 && value.bytes().all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+}
+
+/// 内容寻址路径：`<root>/<hash[0..2]>/<hash>.<ext>`
+pub fn sharded_asset_path(root: &Path, hash: &str, ext: &str) -> PathBuf {
+    root.join(&hash[..2]).join(format!("{}.{}", hash, ext))
+}
+
+/// 按内容寻址写入资源文件，返回最终路径
+///
+/// 目标文件已存在且大小一致时直接复用，不重复写入；
+/// 否则先写临时文件再重命名，避免中途失败留下半截文件。
+/// 临时文件名含进程号与序号（`<hash>.<pid>.<seq>.tmp`），并发捕获相同内容时互不截断；
+/// 重命名失败但目标已由另一写入方落盘时视为成功。
+pub fn write_content_addressed(root: &Path, bytes: &[u8], ext: &str) -> Result<PathBuf, AppError> {
+    let hash = content_hash(bytes);
+    let path = sharded_asset_path(root, &hash, ext);
+
+    let reusable = |path: &Path| {
+        fs::metadata(path)
+            .map(|m| m.is_file() && m.len() == bytes.len() as u64)
+            .unwrap_or(false)
+    };
+    if reusable(&path) {
+        log::debug!("{}", message("storage_log.asset_reused", &[("path", &path.display())]));
+        return Ok(path);
+    }
+
+    let shard_dir = path
+        .parent()
+        .ok_or_else(|| AppError::Storage(message("storage.invalid_asset_path", &[("path", &path.display())])))?;
+    fs::create_dir_all(shard_dir).map_err(|e| {
+        AppError::from_io(&message("storage.create_shard_dir", &[("path", &shard_dir.display())]), &e, AppError::Storage)
+    })?;
+
+    let seq = TMP_SEQ.fetch_add(1, Ordering::Relaxed);
+    let tmp_path = shard_dir.join(format!("{}.{}.{}.tmp", hash, std::process::id(), seq));
+    if let Err(e) = fs::write(&tmp_path, bytes) {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(AppError::from_io(&message("storage.write_asset", &[("path", &path.display())]), &e, AppError::Storage));
+    }
+    if let Err(e) = fs::rename(&tmp_path, &path) {
+        let _ = fs::remove_file(&tmp_path);
+        if reusable(&path) {
+            log::debug!(
+                "{}",
+                message("storage_log.asset_reused_concurrent", &[("path", &path.display())])
+            );
+            return Ok(path);
+        }
+        return Err(AppError::from_io(&message("storage.write_asset", &[("path", &path.display())]), &e, AppError::Storage));
+    }
+
+    Ok(path)
+}
+
 /// 获取图片存储目录信息（路径 + 占用大小 + 文件数）
 #[tauri::command]
 pub fn get_images_dir_info(
@@ -0,0 +1,107 @@
+use super::*;
+use rusqlite::{ffi, Connection};
+
+fn sqlite_failure(code: i32) -> rusqlite::Error {
+    rusqlite::Error::SqliteFailure(ffi::Error::new(code), None)
+}
+
+fn to_json(error: &AppError) -> serde_json::Value {
+    serde_json::to_value(error).expect("serialize error")
+}
+
+#[test]
+fn sqlite_errors_map_to_stable_codes() {
+    let cases = [
+        (ffi::SQLITE_BUSY, "E_DB_LOCKED", true),
+        (ffi::SQLITE_LOCKED, "E_DB_LOCKED", true),
+        (ffi::SQLITE_CORRUPT, "E_DB_CORRUPT", false),
+        (ffi::SQLITE_NOTADB, "E_DB_CORRUPT", false),
+        (ffi::SQLITE_FULL, "E_STORAGE_FULL", false),
+        (ffi::SQLITE_CONSTRAINT, "E_DB", false),
+    ];
+    for (code, expected, retryable) in cases {
+        let error = AppError::from_sqlite("写入失败", &sqlite_failure(code));
+        assert_eq!(error.code(), expected, "sqlite code {}", code);
+        assert_eq!(error.retryable(), retryable, "sqlite code {}", code);
+        assert!(error.to_string().contains("写入失败"), "{}", error);
+    }
+
+    let conn = Connection::open_in_memory().expect("open memory db");
+    let missing = conn.execute("SELECT * FROM missing_table", []).expect_err("no such table");
+    assert_eq!(AppError::from_sqlite("查询失败", &missing).kind(), "database");
+}
+
+#[test]
+fn context_stays_out_of_detail() {
+    let raw = sqlite_failure(ffi::SQLITE_BUSY);
+    let json = to_json(&AppError::from_sqlite("写入失败", &raw));
+    assert_eq!(json["detail"], raw.to_string());
+    assert_eq!(json["message"], format!("写入失败: 数据库被占用: {}", raw));
+    assert_eq!((json["code"].as_str(), json["retryable"].as_bool()), (Some("E_DB_LOCKED"), Some(true)));
+
+    let full = io::Error::from(io::ErrorKind::StorageFull);
+    let json = to_json(&AppError::from_io("写入资源文件失败", &full, AppError::Storage));
+    assert_eq!(json["detail"], full.to_string());
+    assert_eq!(json["kind"], "storage");
+}
+
+#[test]
+fn io_errors_distinguish_full_disk() {
+    let full = io::Error::from(io::ErrorKind::StorageFull);
+    assert_eq!(AppError::from_io("写入资源文件失败", &full, AppError::Storage).code(), "E_STORAGE_FULL");
+    assert_eq!(AppError::Io(full).code(), "E_STORAGE_FULL");
+
+    let denied = io::Error::from(io::ErrorKind::PermissionDenied);
+    assert_eq!(AppError::from_io("创建图片目录失败", &denied, AppError::Storage).code(), "E_STORAGE_UNAVAILABLE");
+    assert_eq!(AppError::Io(denied).code(), "E_IO_PERMISSION");
+    assert_eq!(AppError::Io(io::Error::from(io::ErrorKind::NotFound)).code(), "E_IO_NOT_FOUND");
+}
+
+#[test]
+fn occupied_clipboard_is_busy_and_retryable() {
+    let busy = AppError::from(arboard::Error::ClipboardOccupied);
+    assert_eq!((busy.kind(), busy.code(), busy.retryable()), ("clipboard", "E_CLIPBOARD_BUSY", true));
+
+    let other = AppError::from(arboard::Error::ContentNotAvailable);
+    assert_eq!((other.code(), other.retryable()), ("E_CLIPBOARD", false));
+}
+
+#[test]
+fn serializes_structured_payload() {
+    let error = AppError::DatabaseLocked("database is locked".to_string());
+    assert_eq!(
+        to_json(&error),
+        serde_json::json!({
+            "kind": "database",
+            "code": "E_DB_LOCKED",
+            "message": "数据库被占用: database is locked",
+            "detail": "database is locked",
+            "retryable": true,
+        })
+    );
+
+    let unsupported = to_json(&AppError::Unsupported("文件剪贴板复制".to_string()));
+    assert_eq!(unsupported["code"], "E_INPUT_UNSUPPORTED");
+    assert_eq!(unsupported["kind"], "input");
+}
+
+#[test]
+fn request_errors_have_their_own_codes() {
+    let missing = to_json(&AppError::NotFound("条目 7 不存在".to_string()));
+    assert_eq!((missing["kind"].as_str(), missing["code"].as_str()), (Some("request"), Some("E_NOT_FOUND")));
+    assert_eq!(missing["detail"], "条目 7 不存在");
+    assert_eq!(missing["retryable"], false);
+
+    let invalid = AppError::InvalidRequest("条目 7 不是图片".to_string());
+    assert_eq!((invalid.kind(), invalid.code()), ("request", "E_INVALID_REQUEST"));
+}
+
+#[test]
+fn image_errors_keep_code_and_stage() {
+    let json = to_json(&AppError::from(ImageError::Timeout("30s".to_string())));
+    assert_eq!(json["kind"], "image");
+    assert_eq!(json["code"], "E_NET_TIMEOUT");
+    assert_eq!(json["stage"], "download");
+    assert_eq!(json["retryable"], true);
+    assert_eq!(json["detail"], "30s");
+}
@@ -0,0 +1,168 @@
+//! # 文本转换模块（transform）
+//!
+//! ## 设计思路
+//!
+//! 粘贴前常见的整理动作（去空白、大小写、JSON 美化/压缩、Base64/URL 编解码、
+//! 行排序去重、shell/SQL/正则转义、哈希）统一为具名转换，
+//! 前端按 ID 组成转换链，先预览再“转换并粘贴”。
+//!
+//! - `ops`：各转换的具体实现
+//! - 本文件：转换注册表、链式执行与 Tauri command
+//!
+//! ## 实现思路
+//!
+//! - 注册表是静态数组，每项为 `(id, 名称, 分类, fn)`；新增转换只需追加一行
+//! - 转换链按顺序执行，任一步失败即中止，错误信息带上步骤序号与转换名称
+//! - “转换并粘贴”复用 `IgnoreGuard` 保护的纯文本写入与 `paste_text`，
+//!   转换结果不会被监听器再次记入历史
+//! - 用户保存的转换链持久化在数据库 `transform_chains` 表（见 `db::transforms`）
+
+mod ops;
+
+pub(crate) use ops::html_unescape;
+
+use serde::Serialize;
+use tauri::State;
+
+use crate::db::{self, DbState};
+use crate::error::AppError;
+
+use ops::TransformResult;
+
+/// 单条转换链允许的最大步骤数
+pub const MAX_CHAIN_LEN: usize = 32;
+
+// ── 注册表 ───────────────────────────────────────────────────
+
+struct TransformSpec {
+    id: &'static str,
+    label: &'static str,
+    category: &'static str,
+    apply: fn(&str) -> TransformResult,
+}
+
+const fn spec(
+    id: &'static str,
+    label: &'static str,
+    category: &'static str,
+    apply: fn(&str) -> TransformResult,
+) -> TransformSpec {
+    TransformSpec { id, label, category, apply }
+}
+
+const TRANSFORMS: &[TransformSpec] = &[
+    spec("trim", "去除首尾空白", "whitespace", ops::trim),
+    spec("trim_lines", "去除每行首尾空白", "whitespace", ops::trim_lines),
+    spec("collapse_whitespace", "合并连续空白", "whitespace", ops::collapse_whitespace),
+    spec("remove_empty_lines", "删除空行", "whitespace", ops::remove_empty_lines),
+    spec("upper_case", "转大写", "case", ops::upper_case),
+    spec("lower_case", "转小写", "case", ops::lower_case),
+    spec("title_case", "首字母大写", "case", ops::title_case),
+    spec("snake_case", "snake_case", "case", ops::snake_case),
+    spec("kebab_case", "kebab-case", "case", ops::kebab_case),
+    spec("camel_case", "camelCase", "case", ops::camel_case),
+    spec("pascal_case", "PascalCase", "case", ops::pascal_case),
+    spec("sort_lines", "行升序排序", "lines", ops::sort_lines),
+    spec("sort_lines_desc", "行降序排序", "lines", ops::sort_lines_desc),
+    spec("reverse_lines", "行倒序", "lines", ops::reverse_lines),
+    spec("dedupe_lines", "行去重", "lines", ops::dedupe_lines),
+    spec("json_pretty", "JSON 格式化", "json", ops::json_pretty),
+    spec("json_minify", "JSON 压缩", "json", ops::json_minify),
+    spec("base64_encode", "Base64 编码", "encoding", ops::base64_encode),
+    spec("base64_decode", "Base64 解码", "encoding", ops::base64_decode),
+    spec("url_encode", "URL 编码", "encoding", ops::url_encode),
+    spec("url_decode", "URL 解码", "encoding", ops::url_decode),
+    spec("html_escape", "HTML 转义", "encoding", ops::html_escape),
+    spec("html_unescape", "HTML 反转义", "encoding", ops::html_unescape),
+    spec("shell_escape", "Shell 单引号转义", "escape", ops::shell_escape),
+    spec("sql_escape", "SQL 字符串转义", "escape", ops::sql_escape),
+    spec("regex_escape", "正则转义", "escape", ops::regex_escape),
+    spec("sha256", "SHA-256", "hash", ops::sha256),
+    spec("sha512", "SHA-512", "hash", ops::sha512),
+];
+
+fn find_transform(id: &str) -> Option<&'static TransformSpec> {
+    TRANSFORMS.iter().find(|t| t.id == id)
+}
+
+/// 前端展示用的转换描述
+#[derive(Debug, Clone, Serialize)]
+pub struct TransformInfo {
+    pub id: &'static str,
+    pub label: &'static str,
+    pub category: &'static str,
+}
+
+// ── 链式执行 ─────────────────────────────────────────────────
+
+/// 校验转换链：非空、不超过上限且每一步都是已注册的转换
+pub fn validate_chain(chain: &[String]) -> Result<(), AppError> {
+    if chain.is_empty() {
+        return Err(AppError::Transform("转换链不能为空".into()));
+    }
+    if chain.len() > MAX_CHAIN_LEN {
+        return Err(AppError::Transform(format!(
+            "转换链过长: {} 步（上限 {}）",
+            chain.len(),
+            MAX_CHAIN_LEN
+        )));
+    }
+    match chain.iter().find(|id| find_transform(id).is_none()) {
+        Some(unknown) => Err(AppError::Transform(format!("未知的转换: {}", unknown))),
+        None => Ok(()),
+    }
+}
+
+/// 按顺序对文本执行转换链
+pub fn apply_chain(text: &str, chain: &[String]) -> Result<String, AppError> {
+    validate_chain(chain)?;
+    let mut current = text.to_string();
+    for (index, id) in chain.iter().enumerate() {
+        // validate_chain 已保证存在
+        let Some(transform) = find_transform(id) else {
+            continue;
+        };
+        current = (transform.apply)(&current).map_err(|reason| {
+            AppError::Transform(format!("第 {} 步「{}」失败: {}", index + 1, transform.label, reason))
+        })?;
+    }
+    Ok(current)
+}
+
+// ── Tauri Commands ───────────────────────────────────────────
+
+/// 列出所有可用转换（按注册顺序）
+#[tauri::command]
+pub fn list_transforms() -> Result<Vec<TransformInfo>, AppError> {
+    Ok(TRANSFORMS
+        .iter()
+        .map(|t| TransformInfo { id: t.id, label: t.label, category: t.category })
+        .collect())
+}
+
+/// 预览转换链的结果
+#[tauri::command]
+pub fn transform_text(text: String, chain: Vec<String>) -> Result<String, AppError> {
+    apply_chain(&text, &chain)
+}
+
+/// 转换指定条目的文本，写入剪贴板并模拟粘贴
+#[tauri::command]
+pub async fn transform_and_paste(
+    app: tauri::AppHandle,
+    state: State<'_, DbState>,
+    id: i64,
+    chain: Vec<String>,
+    hide_on_action: Option<bool>,
+) -> Result<(), AppError> {
+    let text = db::with_read_conn(&state, |conn| db::load_clip_text(conn, id))?
+        .ok_or_else(|| AppError::Database(format!("记录不存在: {}", id)))?;
+    let result = apply_chain(&text, &chain)?;
+
+    crate::clipboard::save::write_plain_text(result)?;
+    crate::input::paste_text(app, hide_on_action.unwrap_or(true)).await
+}
+
+#[cfg(test)]
+#[path = "tests/transform_tests.rs"]
+mod tests;
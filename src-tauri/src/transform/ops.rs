@@ -0,0 +1,326 @@
+//! 各文本转换的具体实现
+//!
+//! 每个函数签名统一为 `fn(&str) -> Result<String, String>`，
+//! 错误信息只描述原因，由调用方补充转换名称。
+
+use base64::Engine;
+use sha2::{Digest, Sha256, Sha512};
+
+pub(crate) type TransformResult = Result<String, String>;
+
+// ── 空白与行 ─────────────────────────────────────────────────
+
+pub(crate) fn trim(text: &str) -> TransformResult {
+    Ok(text.trim().to_string())
+}
+
+pub(crate) fn trim_lines(text: &str) -> TransformResult {
+    Ok(map_lines(text, str::trim))
+}
+
+pub(crate) fn collapse_whitespace(text: &str) -> TransformResult {
+    Ok(text.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+pub(crate) fn remove_empty_lines(text: &str) -> TransformResult {
+    Ok(text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+pub(crate) fn sort_lines(text: &str) -> TransformResult {
+    let mut lines: Vec<&str> = text.lines().collect();
+    lines.sort_unstable();
+    Ok(lines.join("\n"))
+}
+
+pub(crate) fn sort_lines_desc(text: &str) -> TransformResult {
+    let mut lines: Vec<&str> = text.lines().collect();
+    lines.sort_unstable_by(|a, b| b.cmp(a));
+    Ok(lines.join("\n"))
+}
+
+pub(crate) fn reverse_lines(text: &str) -> TransformResult {
+    Ok(text.lines().rev().collect::<Vec<_>>().join("\n"))
+}
+
+/// 去重保留首次出现的顺序
+pub(crate) fn dedupe_lines(text: &str) -> TransformResult {
+    let mut seen = std::collections::HashSet::new();
+    Ok(text
+        .lines()
+        .filter(|line| seen.insert(*line))
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+fn map_lines(text: &str, f: impl Fn(&str) -> &str) -> String {
+    text.lines().map(f).collect::<Vec<_>>().join("\n")
+}
+
+// ── 大小写 ───────────────────────────────────────────────────
+
+pub(crate) fn upper_case(text: &str) -> TransformResult {
+    Ok(text.to_uppercase())
+}
+
+pub(crate) fn lower_case(text: &str) -> TransformResult {
+    Ok(text.to_lowercase())
+}
+
+/// 每个单词首字母大写，其余小写；保留原有空白与标点
+pub(crate) fn title_case(text: &str) -> TransformResult {
+    let mut out = String::with_capacity(text.len());
+    let mut at_word_start = true;
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            if at_word_start {
+                out.extend(c.to_uppercase());
+            } else {
+                out.extend(c.to_lowercase());
+            }
+            at_word_start = false;
+        } else {
+            out.push(c);
+            at_word_start = c != '\'';
+        }
+    }
+    Ok(out)
+}
+
+/// 按分隔符与驼峰边界拆分标识符单词（`fooBar-baz_QUX` → foo / bar / baz / qux）
+fn split_words(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let chars: Vec<char> = text.chars().collect();
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        let prev = i.checked_sub(1).map(|p| chars[p]);
+        let next = chars.get(i + 1).copied();
+        let boundary = c.is_uppercase()
+            && match prev {
+                Some(p) if p.is_lowercase() || p.is_numeric() => true,
+                // `HTTPServer` 在 `S` 处断开
+                Some(p) if p.is_uppercase() => next.is_some_and(char::is_lowercase),
+                _ => false,
+            };
+        if boundary && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+pub(crate) fn snake_case(text: &str) -> TransformResult {
+    Ok(split_words(text).join("_"))
+}
+
+pub(crate) fn kebab_case(text: &str) -> TransformResult {
+    Ok(split_words(text).join("-"))
+}
+
+pub(crate) fn camel_case(text: &str) -> TransformResult {
+    let words = split_words(text);
+    let mut out = String::new();
+    for (i, word) in words.iter().enumerate() {
+        if i == 0 {
+            out.push_str(word);
+        } else {
+            out.push_str(&capitalize(word));
+        }
+    }
+    Ok(out)
+}
+
+pub(crate) fn pascal_case(text: &str) -> TransformResult {
+    Ok(split_words(text).iter().map(|w| capitalize(w)).collect())
+}
+
+// ── JSON ─────────────────────────────────────────────────────
+
+fn parse_json(text: &str) -> Result<serde_json::Value, String> {
+    serde_json::from_str(text.trim()).map_err(|e| format!("不是有效的 JSON: {}", e))
+}
+
+pub(crate) fn json_pretty(text: &str) -> TransformResult {
+    serde_json::to_string_pretty(&parse_json(text)?).map_err(|e| e.to_string())
+}
+
+pub(crate) fn json_minify(text: &str) -> TransformResult {
+    serde_json::to_string(&parse_json(text)?).map_err(|e| e.to_string())
+}
+
+// ── 编码 ─────────────────────────────────────────────────────
+
+pub(crate) fn base64_encode(text: &str) -> TransformResult {
+    Ok(base64::engine::general_purpose::STANDARD.encode(text.as_bytes()))
+}
+
+/// 同时接受标准与 URL-safe 字母表，忽略空白与缺失的填充
+pub(crate) fn base64_decode(text: &str) -> TransformResult {
+    let compact: String = text
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| match c {
+            '-' => '+',
+            '_' => '/',
+            c => c,
+        })
+        .collect();
+    let bytes = base64::engine::general_purpose::STANDARD_NO_PAD
+        .decode(compact.trim_end_matches('='))
+        .map_err(|e| format!("不是有效的 Base64: {}", e))?;
+    String::from_utf8(bytes).map_err(|_| "解码结果不是 UTF-8 文本".to_string())
+}
+
+/// RFC 3986 百分号编码（仅保留非保留字符）
+pub(crate) fn url_encode(text: &str) -> TransformResult {
+    let mut out = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(byte as char)
+            }
+            _ => out.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    Ok(out)
+}
+
+/// 百分号解码；`+` 按表单编码视为空格
+pub(crate) fn url_decode(text: &str) -> TransformResult {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {
+                let hex = bytes
+                    .get(i + 1..i + 3)
+                    .and_then(|h| std::str::from_utf8(h).ok())
+                    .and_then(|h| u8::from_str_radix(h, 16).ok())
+                    .ok_or_else(|| format!("位置 {} 处的百分号编码无效", i))?;
+                out.push(hex);
+                i += 3;
+            }
+            b'+' => {
+                out.push(b' ');
+                i += 1;
+            }
+            b => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(out).map_err(|_| "解码结果不是 UTF-8 文本".to_string())
+}
+
+pub(crate) fn html_escape(text: &str) -> TransformResult {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    Ok(out)
+}
+
+/// 解码常见命名实体与数字实体；无法识别的实体原样保留
+pub(crate) fn html_unescape(text: &str) -> TransformResult {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest
+            .find(';')
+            .filter(|&end| end <= 10)
+            .and_then(|end| decode_entity(&rest[1..end]).map(|c| (c, end)));
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn decode_entity(entity: &str) -> Option<char> {
+    let code = match entity {
+        "amp" => return Some('&'),
+        "lt" => return Some('<'),
+        "gt" => return Some('>'),
+        "quot" => return Some('"'),
+        "apos" => return Some('\''),
+        "nbsp" => return Some('\u{a0}'),
+        _ => {
+            let num = entity.strip_prefix('#')?;
+            match num.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => num.parse().ok()?,
+            }
+        }
+    };
+    char::from_u32(code)
+}
+
+// ── 转义 ─────────────────────────────────────────────────────
+
+/// POSIX shell 单引号字面量：`it's` → `'it'\''s'`
+pub(crate) fn shell_escape(text: &str) -> TransformResult {
+    Ok(format!("'{}'", text.replace('\'', r"'\''")))
+}
+
+/// SQL 字符串字面量：单引号加倍
+pub(crate) fn sql_escape(text: &str) -> TransformResult {
+    Ok(format!("'{}'", text.replace('\'', "''")))
+}
+
+pub(crate) fn regex_escape(text: &str) -> TransformResult {
+    Ok(regex::escape(text))
+}
+
+// ── 哈希 ─────────────────────────────────────────────────────
+
+fn hex_digest(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub(crate) fn sha256(text: &str) -> TransformResult {
+    Ok(hex_digest(&Sha256::digest(text.as_bytes())))
+}
+
+pub(crate) fn sha512(text: &str) -> TransformResult {
+    Ok(hex_digest(&Sha512::digest(text.as_bytes())))
+}
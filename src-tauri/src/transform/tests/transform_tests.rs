@@ -0,0 +1,91 @@
+use super::*;
+
+fn chain(ids: &[&str]) -> Vec<String> {
+    ids.iter().map(|id| id.to_string()).collect()
+}
+
+fn run(text: &str, ids: &[&str]) -> String {
+    apply_chain(text, &chain(ids)).expect("transform")
+}
+
+#[test]
+fn registry_ids_are_unique_and_listed() {
+    let listed = list_transforms().expect("list");
+    assert_eq!(listed.len(), TRANSFORMS.len());
+    let mut ids: Vec<&str> = listed.iter().map(|t| t.id).collect();
+    ids.sort_unstable();
+    ids.dedup();
+    assert_eq!(ids.len(), TRANSFORMS.len());
+}
+
+#[test]
+fn whitespace_and_line_transforms() {
+    assert_eq!(run("  a b \n", &["trim"]), "a b");
+    assert_eq!(run(" a \n  b", &["trim_lines"]), "a\nb");
+    assert_eq!(run("a \t b\n\nc", &["collapse_whitespace"]), "a b c");
+    assert_eq!(run("a\n\n  \nb", &["remove_empty_lines"]), "a\nb");
+    assert_eq!(run("b\na\nc", &["sort_lines"]), "a\nb\nc");
+    assert_eq!(run("b\na\nc", &["sort_lines_desc"]), "c\nb\na");
+    assert_eq!(run("1\n2\n3", &["reverse_lines"]), "3\n2\n1");
+    assert_eq!(run("b\na\nb\na\nc", &["dedupe_lines"]), "b\na\nc");
+}
+
+#[test]
+fn case_transforms() {
+    assert_eq!(run("Hello", &["upper_case"]), "HELLO");
+    assert_eq!(run("Hello", &["lower_case"]), "hello");
+    assert_eq!(run("it's a NEW day", &["title_case"]), "It's A New Day");
+    assert_eq!(run("parseHTTPResponse v2", &["snake_case"]), "parse_http_response_v2");
+    assert_eq!(run("user_id-value", &["camel_case"]), "userIdValue");
+    assert_eq!(run("user id", &["pascal_case"]), "UserId");
+    assert_eq!(run("SomeValue", &["kebab_case"]), "some-value");
+}
+
+#[test]
+fn json_transforms() {
+    assert_eq!(run(r#" {"a": [1, 2]} "#, &["json_minify"]), r#"{"a":[1,2]}"#);
+    assert_eq!(run(r#"{"a":1}"#, &["json_pretty"]), "{\n  \"a\": 1\n}");
+    assert!(apply_chain("{oops", &chain(&["json_pretty"])).is_err());
+}
+
+#[test]
+fn encoding_transforms_round_trip() {
+    assert_eq!(run("你好, world", &["base64_encode"]), "5L2g5aW9LCB3b3JsZA==");
+    assert_eq!(run("5L2g5aW9LCB3b3JsZA", &["base64_decode"]), "你好, world");
+    assert_eq!(run("a b&c=d/é", &["url_encode"]), "a%20b%26c%3Dd%2F%C3%A9");
+    assert_eq!(run("a+b%26c", &["url_decode"]), "a b&c");
+    assert_eq!(run("<a href=\"x\">'&'</a>", &["html_escape"]), "&lt;a href=&quot;x&quot;&gt;&#39;&amp;&#39;&lt;/a&gt;");
+    assert_eq!(run("&lt;b&gt; &#x4e2d;&#25991; &unknown; & done", &["html_unescape"]), "<b> 中文 &unknown; & done");
+
+    let text = "multi\nline ✓";
+    for (encode, decode) in [("base64_encode", "base64_decode"), ("url_encode", "url_decode"), ("html_escape", "html_unescape")] {
+        assert_eq!(run(text, &[encode, decode]), text);
+    }
+    assert!(apply_chain("%zz", &chain(&["url_decode"])).is_err());
+    assert!(apply_chain("//8=", &chain(&["base64_decode"])).is_err());
+}
+
+#[test]
+fn escape_and_hash_transforms() {
+    assert_eq!(run("it's", &["shell_escape"]), r"'it'\''s'");
+    assert_eq!(run("O'Brien", &["sql_escape"]), "'O''Brien'");
+    assert_eq!(run("a.b*c", &["regex_escape"]), r"a\.b\*c");
+    assert_eq!(
+        run("abc", &["sha256"]),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    assert_eq!(run("abc", &["sha512"]).len(), 128);
+}
+
+#[test]
+fn chains_run_in_order_and_report_failing_step() {
+    assert_eq!(run("  b\na\nb  ", &["trim", "dedupe_lines", "sort_lines", "upper_case"]), "A\nB");
+
+    let err = apply_chain("x", &chain(&["trim", "json_minify"])).unwrap_err();
+    assert!(err.to_string().contains("第 2 步"), "{}", err);
+
+    assert!(apply_chain("x", &[]).is_err());
+    assert!(apply_chain("x", &chain(&["no_such_transform"])).is_err());
+    assert!(apply_chain("x", &vec!["trim".to_string(); MAX_CHAIN_LEN + 1]).is_err());
+    assert_eq!(transform_text("  x ".into(), chain(&["trim"])).expect("preview"), "x");
+}
@@ -45,8 +45,14 @@ use serde::Deserialize;
 use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize, WebviewUrl, WebviewWindowBuilder, Window};
 use tauri::window::Color;
 use crate::error::AppError;
+use crate::i18n::message;
 use std::time::Instant;
 
+/// 窗口操作失败：`id` 为本地化的操作上下文，`detail` 保留 Tauri 错误原文
+pub(crate) fn window_err(id: &str, e: impl std::fmt::Display) -> AppError {
+    AppError::with_context(message(id, &[]), AppError::Window(e.to_string()))
+}
+
 /// 在 Windows 上通过 SetWindowPos 强制将 HUD 窗口置于 Z 轴最顶层。
 ///
 /// 当主窗口和 HUD 窗口都处于 TOPMOST 带时，`set_always_on_top(true)` 不一定能
@@ -186,7 +192,7 @@ fn get_or_create_hud_host(app: &AppHandle) -> Result<tauri::WebviewWindow, AppEr
     .devtools(false)
     .visible(false)
     .build()
-    .map_err(|e| AppError::Window(format!("创建 HUD 宿主窗口失败: {}", e)))
+    .map_err(|e| window_err("window.hud_host_create_failed", e))
 }
 
 // ── 主窗口置顶切换 ──
@@ -195,10 +201,10 @@ fn get_or_create_hud_host(app: &AppHandle) -> Result<tauri::WebviewWindow, AppEr
 pub async fn set_always_on_top(app: AppHandle, enabled: bool) -> Result<(), AppError> {
     let window = app
         .get_webview_window(WINDOW_LABEL_MAIN)
-        .ok_or_else(|| AppError::Window("主窗口不存在".to_string()))?;
+        .ok_or_else(|| AppError::Window(message("window.main_missing", &[])))?;
     window
         .set_always_on_top(enabled)
-        .map_err(|e| AppError::Window(format!("设置主窗口置顶失败: {}", e)))?;
+        .map_err(|e| window_err("window.main_always_on_top_failed", e))?;
     log::info!("set_always_on_top: enabled={enabled}");
     Ok(())
 }
@@ -375,12 +381,12 @@ fn calculate_monitor_center_position(
 fn restore_if_minimized(window: &Window) -> Result<(), AppError> {
     let is_minimized = window
         .is_minimized()
-        .map_err(|e| AppError::Window(format!("Failed to query minimized state: {}", e)))?;
+        .map_err(|e| window_err("window.minimized_query_failed", e))?;
 
     if is_minimized {
         window
             .unminimize()
-            .map_err(|e| AppError::Window(format!("Failed to restore minimized window: {}", e)))?;
+            .map_err(|e| window_err("window.unminimize_failed", e))?;
     }
 
     Ok(())
@@ -394,7 +400,7 @@ async fn compute_target_position(
 
     let t0 = Instant::now();
     let current_monitor = window.current_monitor()
-        .map_err(|e| AppError::Window(format!("Failed to get current monitor: {}", e)))?;
+        .map_err(|e| window_err("window.current_monitor_failed", e))?;
     let current_monitor_cost = t0.elapsed();
 
     let t1 = Instant::now();
@@ -403,12 +409,12 @@ async fn compute_target_position(
 
     let t2 = Instant::now();
     let window_size = window.outer_size()
-        .map_err(|e| AppError::Window(format!("Failed to get window size: {}", e)))?;
+        .map_err(|e| window_err("window.size_failed", e))?;
     let size_cost = t2.elapsed();
 
     let t3 = Instant::now();
     let monitors = window.available_monitors()
-        .map_err(|e| AppError::Window(format!("Failed to get available monitors: {}", e)))?;
+        .map_err(|e| window_err("window.available_monitors_failed", e))?;
     let monitors_cost = t3.elapsed();
 
     let t4 = Instant::now();
@@ -423,14 +429,14 @@ async fn compute_target_position(
         WindowPlacementMode::CursorTopLeft => {
             let monitor = monitor::detect_cursor_monitor(cursor_pos, &monitors)
                 .or(fallback_monitor)
-                .ok_or_else(|| AppError::Window("No monitor available".to_string()))?;
+                .ok_or_else(|| AppError::Window(message("window.no_monitor", &[])))?;
 
             clamp_position_to_monitor(cursor_pos, window_size, monitor)
         }
         WindowPlacementMode::CursorCenter => {
             let monitor = monitor::detect_cursor_monitor(cursor_pos, &monitors)
                 .or(fallback_monitor)
-                .ok_or_else(|| AppError::Window("No monitor available".to_string()))?;
+                .ok_or_else(|| AppError::Window(message("window.no_monitor", &[])))?;
 
             let ideal = tauri::PhysicalPosition::new(
                 cursor_pos.x - window_size.width as i32 / 2,
@@ -443,7 +449,7 @@ async fn compute_target_position(
             let anchor_y = config.custom_y.unwrap_or(0);
             let monitor = monitor::detect_cursor_monitor(cursor_pos, &monitors)
                 .or(fallback_monitor)
-                .ok_or_else(|| AppError::Window("No monitor available".to_string()))?;
+                .ok_or_else(|| AppError::Window(message("window.no_monitor", &[])))?;
 
             let ideal = tauri::PhysicalPosition::new(
                 cursor_pos.x - anchor_x,
@@ -454,13 +460,13 @@ async fn compute_target_position(
         WindowPlacementMode::MonitorCenter => {
             let monitor = monitor::detect_cursor_monitor(cursor_pos, &monitors)
                 .or(fallback_monitor)
-                .ok_or_else(|| AppError::Window("No monitor available".to_string()))?;
+                .ok_or_else(|| AppError::Window(message("window.no_monitor", &[])))?;
 
             calculate_monitor_center_position(monitor, window_size)
         }
         WindowPlacementMode::ScreenCenter => {
             let monitor = monitors.first().or(fallback_monitor)
-                .ok_or_else(|| AppError::Window("No monitor available".to_string()))?;
+                .ok_or_else(|| AppError::Window(message("window.no_monitor", &[])))?;
 
             calculate_monitor_center_position(monitor, window_size)
         }
@@ -471,14 +477,14 @@ async fn compute_target_position(
 
             let monitor = detect_monitor_from_point(custom_pos, &monitors)
                 .or(fallback_monitor)
-                .ok_or_else(|| AppError::Window("No monitor available".to_string()))?;
+                .ok_or_else(|| AppError::Window(message("window.no_monitor", &[])))?;
 
             clamp_position_to_monitor(custom_pos, window_size, monitor)
         }
         WindowPlacementMode::LastPosition => {
             window
                 .outer_position()
-                .map_err(|e| AppError::Window(format!("Failed to get current window position: {}", e)))?
+                .map_err(|e| window_err("window.position_failed", e))?
         }
     };
     let calc_cost = t4.elapsed();
@@ -527,13 +533,13 @@ pub async fn show_window_at_cursor(window: Window) -> Result<(), AppError> {
     let target_position = compute_target_position(&window, &default_placement_config()).await?;
 
     window.set_position(target_position)
-        .map_err(|e| AppError::Window(format!("Failed to set window position: {}", e)))?;
+        .map_err(|e| window_err("window.set_position_failed", e))?;
 
     window.show()
-        .map_err(|e| AppError::Window(format!("Failed to show window: {}", e)))?;
+        .map_err(|e| window_err("window.show_failed", e))?;
 
     window.set_focus()
-        .map_err(|e| AppError::Window(format!("Failed to set window focus: {}", e)))?;
+        .map_err(|e| window_err("window.focus_failed", e))?;
 
     Ok(())
 }
@@ -560,10 +566,10 @@ pub async fn reposition_and_focus(window: Window) -> Result<(), AppError> {
     let target_position = compute_target_position(&window, &default_placement_config()).await?;
 
     window.set_position(target_position)
-        .map_err(|e| AppError::Window(format!("Failed to set window position: {}", e)))?;
+        .map_err(|e| window_err("window.set_position_failed", e))?;
 
     window.set_focus()
-        .map_err(|e| AppError::Window(format!("Failed to set window focus: {}", e)))?;
+        .map_err(|e| window_err("window.focus_failed", e))?;
 
     Ok(())
 }
@@ -610,30 +616,30 @@ async fn toggle_window_with_config(
             log::debug!("窗口处于隐藏状态，正在显示到光标附近");
             let target_position = compute_target_position(&window, &config).await?;
             window.set_position(target_position)
-                .map_err(|e| AppError::Window(format!("Failed to set window position: {}", e)))?;
+                .map_err(|e| window_err("window.set_position_failed", e))?;
             window.show()
-                .map_err(|e| AppError::Window(format!("Failed to show window: {}", e)))?;
+                .map_err(|e| window_err("window.show_failed", e))?;
             // 主窗口显示后后台预热 HUD 窗口，确保悬停时即刻响应
             if is_main_window {
                 warmup_hud_in_background(window.app_handle());
             }
             window.set_focus()
-                .map_err(|e| AppError::Window(format!("Failed to set window focus: {}", e)))
+                .map_err(|e| window_err("window.focus_failed", e))
         }
         (true, false) => {
             log::debug!("窗口可见但未聚焦，正在重定位并恢复焦点");
             let target_position = compute_target_position(&window, &config).await?;
             window.set_position(target_position)
-                .map_err(|e| AppError::Window(format!("Failed to set window position: {}", e)))?;
+                .map_err(|e| window_err("window.set_position_failed", e))?;
             window.set_focus()
-                .map_err(|e| AppError::Window(format!("Failed to set window focus: {}", e)))
+                .map_err(|e| window_err("window.focus_failed", e))
         }
         (true, true) => {
             log::debug!("窗口可见且已聚焦，正在隐藏");
             // 同步隐藏所有 HUD 子窗口，防止主窗口隐藏后 HUD 残留
             hide_all_hud_windows(window.app_handle());
             window.hide()
-                .map_err(|e| AppError::Window(format!("Failed to hide window: {}", e)))
+                .map_err(|e| window_err("window.hide_failed", e))
         }
     }
 }
@@ -667,15 +673,15 @@ pub async fn show_download_hud(app: AppHandle) -> Result<(), AppError> {
 
     window
         .set_size(tauri::PhysicalSize::new(DOWNLOAD_HUD_WIDTH, DOWNLOAD_HUD_HEIGHT))
-        .map_err(|e| AppError::Window(format!("重置下载 HUD 窗口尺寸失败: {}", e)))?;
+        .map_err(|e| window_err("window.download_hud_resize_failed", e))?;
 
     window
         .set_ignore_cursor_events(true)
-        .map_err(|e| AppError::Window(format!("设置下载 HUD 鼠标穿透失败: {}", e)))?;
+        .map_err(|e| window_err("window.download_hud_ignore_cursor_failed", e))?;
 
     window
         .show()
-        .map_err(|e| AppError::Window(format!("显示 HUD 窗口失败: {}", e)))?;
+        .map_err(|e| window_err("window.hud_show_failed", e))?;
 
     // 强制 HUD 在所有 TOPMOST 窗口之上（含主窗口）
     force_hud_topmost(&window);
@@ -695,7 +701,7 @@ pub async fn position_download_hud_near_cursor(app: AppHandle) -> Result<(), App
 
     let current_monitor = window
         .current_monitor()
-        .map_err(|e| AppError::Window(format!("读取 HUD 当前显示器失败: {}", e)))?;
+        .map_err(|e| window_err("window.hud_monitor_failed", e))?;
 
     let cursor_pos = cursor::get_cursor_position_with_retry(current_monitor.as_ref()).await;
 
@@ -703,17 +709,17 @@ pub async fn position_download_hud_near_cursor(app: AppHandle) -> Result<(), App
 
     window
         .set_size(window_size)
-        .map_err(|e| AppError::Window(format!("重置 HUD 窗口尺寸失败: {}", e)))?;
+        .map_err(|e| window_err("window.hud_resize_failed", e))?;
 
     let monitors = window
         .available_monitors()
-        .map_err(|e| AppError::Window(format!("读取可用显示器失败: {}", e)))?;
+        .map_err(|e| window_err("window.available_monitors_failed", e))?;
 
     let fallback_monitor = current_monitor.as_ref().or_else(|| monitors.first());
 
     let target_monitor = detect_monitor_from_point(cursor_pos, &monitors)
         .or(fallback_monitor)
-        .ok_or_else(|| AppError::Window("没有可用的显示器用于定位下载 HUD".to_string()))?;
+        .ok_or_else(|| AppError::Window(message("window.no_monitor_for_download_hud", &[])))?;
 
     let ideal = PhysicalPosition::new(
         cursor_pos.x.saturating_add(DOWNLOAD_HUD_OFFSET_X),
@@ -724,7 +730,7 @@ pub async fn position_download_hud_near_cursor(app: AppHandle) -> Result<(), App
 
     window
         .set_position(target)
-        .map_err(|e| AppError::Window(format!("移动 HUD 窗口失败: {}", e)))?;
+        .map_err(|e| window_err("window.hud_move_failed", e))?;
 
     Ok(())
 }
@@ -735,7 +741,7 @@ pub async fn show_clipitem_hud(app: AppHandle) -> Result<(), AppError> {
 
     window
         .show()
-        .map_err(|e| AppError::Window(format!("显示 ClipItem HUD 窗口失败: {}", e)))?;
+        .map_err(|e| window_err("window.clip_hud_show_failed", e))?;
 
     // 强制 HUD 在所有 TOPMOST 窗口之上（含主窗口）
     force_hud_topmost(&window);
@@ -763,17 +769,17 @@ pub async fn position_clipitem_hud_near_cursor(
 
     let current_monitor = window
         .current_monitor()
-        .map_err(|e| AppError::Window(format!("读取 ClipItem HUD 当前显示器失败: {}", e)))?;
+        .map_err(|e| window_err("window.clip_hud_monitor_failed", e))?;
 
     let cursor_pos = cursor::get_cursor_position_with_retry(current_monitor.as_ref()).await;
 
     let (axis, size, target) = if let Some(main_window) = app.get_webview_window(WINDOW_LABEL_MAIN) {
         let main_pos = main_window
             .outer_position()
-            .map_err(|e| AppError::Window(format!("读取主窗口位置失败: {}", e)))?;
+            .map_err(|e| window_err("window.main_position_failed", e))?;
         let main_size = main_window
             .outer_size()
-            .map_err(|e| AppError::Window(format!("读取主窗口尺寸失败: {}", e)))?;
+            .map_err(|e| window_err("window.main_size_failed", e))?;
         compute_clipitem_hud_edge_position(cursor_pos, main_pos, main_size)
     } else {
         (
@@ -791,11 +797,11 @@ pub async fn position_clipitem_hud_near_cursor(
 
     window
         .set_size(size)
-        .map_err(|e| AppError::Window(format!("调整 ClipItem HUD 窗口尺寸失败: {}", e)))?;
+        .map_err(|e| window_err("window.clip_hud_resize_failed", e))?;
 
     window
         .set_position(target)
-        .map_err(|e| AppError::Window(format!("移动 ClipItem HUD 窗口失败: {}", e)))?;
+        .map_err(|e| window_err("window.clip_hud_move_failed", e))?;
 
     Ok(axis.as_str().to_string())
 }
@@ -816,14 +822,14 @@ pub async fn position_clipitem_hud_at_main_edge(
 
     let main_window = app
         .get_webview_window(WINDOW_LABEL_MAIN)
-        .ok_or_else(|| AppError::Window("主窗口不存在".to_string()))?;
+        .ok_or_else(|| AppError::Window(message("window.main_missing", &[])))?;
 
     let main_pos = main_window
         .outer_position()
-        .map_err(|e| AppError::Window(format!("读取主窗口位置失败: {}", e)))?;
+        .map_err(|e| window_err("window.main_position_failed", e))?;
     let main_size = main_window
         .outer_size()
-        .map_err(|e| AppError::Window(format!("读取主窗口尺寸失败: {}", e)))?;
+        .map_err(|e| window_err("window.main_size_failed", e))?;
 
     let parsed_edge = match edge.as_str() {
         "top" => MainWindowNearestEdge::Top,
@@ -837,11 +843,11 @@ pub async fn position_clipitem_hud_at_main_edge(
 
     window
         .set_size(size)
-        .map_err(|e| AppError::Window(format!("调整 ClipItem HUD 窗口尺寸失败: {}", e)))?;
+        .map_err(|e| window_err("window.clip_hud_resize_failed", e))?;
 
     window
         .set_position(target)
-        .map_err(|e| AppError::Window(format!("移动 ClipItem HUD 窗口失败: {}", e)))?;
+        .map_err(|e| window_err("window.clip_hud_move_failed", e))?;
 
     Ok(axis.as_str().to_string())
 }
@@ -854,7 +860,7 @@ pub async fn set_clipitem_hud_mouse_passthrough(
     if let Some(window) = app.get_webview_window(WINDOW_LABEL_HUD_HOST) {
         window
             .set_ignore_cursor_events(passthrough)
-            .map_err(|e| AppError::Window(format!("设置 ClipItem HUD 鼠标穿透失败: {}", e)))?;
+            .map_err(|e| window_err("window.clip_hud_ignore_cursor_failed", e))?;
     }
     Ok(())
 }
@@ -875,12 +881,12 @@ pub async fn open_radial_menu_at_cursor(
     // 0. 重置为正确的正方形尺寸
     window
         .set_size(tauri::PhysicalSize::new(RADIAL_MENU_SIZE, RADIAL_MENU_SIZE))
-        .map_err(|e| AppError::Window(format!("重置径向菜单尺寸失败: {}", e)))?;
+        .map_err(|e| window_err("window.radial_resize_failed", e))?;
 
     // 1. 定位：光标居中
     let current_monitor = window
         .current_monitor()
-        .map_err(|e| AppError::Window(format!("读取径向菜单当前显示器失败: {}", e)))?;
+        .map_err(|e| window_err("window.radial_monitor_failed", e))?;
     let cursor_pos = cursor::get_cursor_position_with_retry(current_monitor.as_ref()).await;
     let half = RADIAL_MENU_SIZE as i32 / 2;
     let target = PhysicalPosition::new(
@@ -889,23 +895,23 @@ pub async fn open_radial_menu_at_cursor(
     );
     window
         .set_position(target)
-        .map_err(|e| AppError::Window(format!("移动径向菜单窗口失败: {}", e)))?;
+        .map_err(|e| window_err("window.radial_move_failed", e))?;
 
     // 2. 发送快照到 HUD 宿主窗口
     use tauri::Emitter;
     window
         .emit("radial-menu-snapshot", &snapshot)
-        .map_err(|e| AppError::Window(format!("发送径向菜单快照失败: {}", e)))?;
+        .map_err(|e| window_err("window.radial_snapshot_emit_failed", e))?;
 
     // 3. 取消鼠标穿透
     window
         .set_ignore_cursor_events(false)
-        .map_err(|e| AppError::Window(format!("设置径向菜单鼠标穿透失败: {}", e)))?;
+        .map_err(|e| window_err("window.radial_ignore_cursor_failed", e))?;
 
     // 4. 显示 + 强制置顶
     window
         .show()
-        .map_err(|e| AppError::Window(format!("显示径向菜单窗口失败: {}", e)))?;
+        .map_err(|e| window_err("window.radial_show_failed", e))?;
     force_hud_topmost(&window);
 
     Ok(())
@@ -917,7 +923,7 @@ pub async fn show_radial_menu(app: AppHandle) -> Result<(), AppError> {
 
     window
         .show()
-        .map_err(|e| AppError::Window(format!("显示径向菜单窗口失败: {}", e)))?;
+        .map_err(|e| window_err("window.radial_show_failed", e))?;
 
     // 强制 HUD 在所有 TOPMOST 窗口之上（含主窗口）
     force_hud_topmost(&window);
@@ -937,11 +943,11 @@ pub async fn position_radial_menu_at_cursor(app: AppHandle) -> Result<(), AppErr
 
     window
         .set_size(tauri::PhysicalSize::new(RADIAL_MENU_SIZE, RADIAL_MENU_SIZE))
-        .map_err(|e| AppError::Window(format!("重置径向菜单尺寸失败: {}", e)))?;
+        .map_err(|e| window_err("window.radial_resize_failed", e))?;
 
     let current_monitor = window
         .current_monitor()
-        .map_err(|e| AppError::Window(format!("读取径向菜单当前显示器失败: {}", e)))?;
+        .map_err(|e| window_err("window.radial_monitor_failed", e))?;
 
     let cursor_pos = cursor::get_cursor_position_with_retry(current_monitor.as_ref()).await;
 
@@ -953,7 +959,7 @@ pub async fn position_radial_menu_at_cursor(app: AppHandle) -> Result<(), AppErr
 
     window
         .set_position(target)
-        .map_err(|e| AppError::Window(format!("移动径向菜单窗口失败: {}", e)))?;
+        .map_err(|e| window_err("window.radial_move_failed", e))?;
 
     Ok(())
 }
@@ -966,7 +972,7 @@ pub async fn set_radial_menu_mouse_passthrough(
     if let Some(window) = app.get_webview_window(WINDOW_LABEL_HUD_HOST) {
         window
             .set_ignore_cursor_events(passthrough)
-            .map_err(|e| AppError::Window(format!("设置径向菜单鼠标穿透失败: {}", e)))?;
+            .map_err(|e| window_err("window.radial_ignore_cursor_failed", e))?;
     }
     Ok(())
 }
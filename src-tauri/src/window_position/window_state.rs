@@ -94,10 +94,10 @@ pub struct WindowState {
 /// ```
 pub fn get_window_state(window: &Window) -> Result<WindowState, crate::error::AppError> {
     let is_visible = window.is_visible()
-        .map_err(|e| crate::error::AppError::Window(format!("Failed to query window visibility: {}", e)))?;
+        .map_err(|e| super::window_err("window.visibility_query_failed", e))?;
 
     let is_focused = window.is_focused()
-        .map_err(|e| crate::error::AppError::Window(format!("Failed to query window focus status: {}", e)))?;
+        .map_err(|e| super::window_err("window.focus_query_failed", e))?;
 
     Ok(WindowState {
         is_visible,
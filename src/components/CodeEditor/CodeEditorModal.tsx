@@ -9,7 +9,7 @@ import { EditorState, type Extension } from '@codemirror/state';
 import { useAppContext } from '../../contexts/AppContext';
 import { ClipboardDB } from '../../services/db';
 import type { ClipFormat } from '../../types';
-import { detectLanguage, loadLanguageExtension, type LanguageId } from '../../utils/languageDetect';
+import { detectClipLanguage, detectLanguage, loadLanguageExtension, type LanguageId } from '../../utils/languageDetect';
 import { backdropVariants, modalVariants } from '../../utils/motionPresets';
 
 import { EditorHeader } from './EditorHeader';
@@ -136,7 +136,7 @@ export function CodeEditorModal() {
     if (editingClip) {
       setContent(editingClip.text);
       setTextContent(editingClip.text);
-      setLangId(detectLanguage(editingClip.text).id);
+      setLangId(detectClipLanguage(editingClip.text, editingClip.language).id);
       setContentFormat('text');
       setClipFormats([]);
       formatsLoadedRef.current = false;
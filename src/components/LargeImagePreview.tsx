@@ -2,7 +2,7 @@ import React, { useState, useEffect, useRef, useCallback } from 'react';
 import { motion, AnimatePresence } from 'motion/react';
 import { X, ZoomIn, ZoomOut, Copy, Download, RotateCcw, type LucideIcon } from 'lucide-react';
 import { TauriService } from '../services/tauri';
-import { detectImageType } from '../utils';
+import { detectImageType, toErrorMessage } from '../utils';
 import { formatBytes, extractFormatLabel } from '../utils/imageUrl';
 import { useImageResource } from '../hooks/useImageResource';
 import { backdropVariants, SPRING_UI } from '../utils/motionPresets';
@@ -151,13 +151,13 @@ export const LargeImagePreview = React.memo(function LargeImagePreview({
       if (url.startsWith('data:image/')) {
         await TauriService.writeImageBase64(url);
       } else if (url.startsWith('http')) {
-        await TauriService.downloadAndCopyImage(url, 'large-preview-manual-copy');
+        await TauriService.downloadAndCopyImage(url, TauriService.createImageDownloadRequestId());
       } else {
         await TauriService.copyImageFromFile(url);
       }
       toast.success('已复制到剪贴板');
     } catch (err: unknown) {
-      toast.error(`复制图片失败: ${err}`);
+      toast.error(`复制图片失败: ${toErrorMessage(err)}`);
       console.error('Copy large image failed:', err);
     }
   }, [url]);
@@ -167,9 +167,9 @@ export const LargeImagePreview = React.memo(function LargeImagePreview({
     if (!url) return;
     try {
       // 通过 Tauri 保存文件(降级调用之前遗漏的函数接口)
-      await TauriService.downloadAndCopyImage(url, 'download-only');
+      await TauriService.downloadAndCopyImage(url, TauriService.createImageDownloadRequestId());
     } catch (err: unknown) {
-      toast.error(`下载失败: ${err}`);
+      toast.error(`下载失败: ${toErrorMessage(err)}`);
       console.error('Download large image failed:', err);
     }
   }, [url]);
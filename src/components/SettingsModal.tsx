@@ -3,7 +3,7 @@ import { motion, AnimatePresence } from 'motion/react';
 import { X, Settings, Keyboard, Monitor, HardDrive } from 'lucide-react';
 import { useAppContext } from '../contexts/AppContext';
 import { TauriService } from '../services/tauri';
-import { getGlobalShortcutConflict, getImmersiveShortcutConflict } from '../utils';
+import { getGlobalShortcutConflict, getImmersiveShortcutConflict, toErrorMessage } from '../utils';
 import { backdropVariants, modalVariants } from '../utils/motionPresets';
 import type { WindowPlacementMode } from '../types';
 import './SettingsModal/styles/settings-modal.css';
@@ -196,7 +196,7 @@ export const SettingsModal = React.memo(function SettingsModal({ show, onClose }
           }
 
           setBackendProfileSyncState('failed');
-          setBackendProfileError(error instanceof Error ? error.message : String(error));
+          setBackendProfileError(toErrorMessage(error));
           return;
         }
       }
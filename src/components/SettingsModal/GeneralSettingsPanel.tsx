@@ -80,6 +80,23 @@ export function GeneralSettingsPanel({
         ))}
       </section>
 
+      <section className="sm-panel__section" data-theme={dark ? 'dark' : 'light'}>
+        <h3 className="sm-panel__section-title">语言</h3>
+        <div className="sm-panel__block--tight">
+          <p className="sm-panel__label">后端消息语言</p>
+          <select
+            value={settings.backendLocale}
+            onChange={(e) => updateSettings({ backendLocale: e.target.value as typeof settings.backendLocale })}
+            className="sm-field__select"
+            data-theme={dark ? 'dark' : 'light'}
+          >
+            <option value="zh-CN">简体中文</option>
+            <option value="en">English</option>
+          </select>
+          <p className="sm-panel__muted">错误提示与托盘菜单使用的语言，切换后立即生效</p>
+        </div>
+      </section>
+
       <section className="sm-panel__section" data-theme={dark ? 'dark' : 'light'}>
         <h3 className="sm-panel__section-title">历史记录</h3>
         <div className="sm-panel__fields-grid">
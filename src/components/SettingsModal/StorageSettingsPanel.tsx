@@ -93,6 +93,14 @@ export function StorageSettingsPanel({
           <ToggleSwitch dark={dark} on={!!settings.showImagePreview} onToggle={() => updateSettings({ showImagePreview: !settings.showImagePreview })} />
         </SettingRow>
 
+        <SettingRow title="移除图片 EXIF" desc="保存图片前去除相机、拍摄时间与 GPS 位置等信息">
+          <ToggleSwitch dark={dark} on={!!settings.stripImageExif} onToggle={() => updateSettings({ stripImageExif: !settings.stripImageExif })} />
+        </SettingRow>
+
+        <SettingRow title="自动保存图片链接" desc="复制图片链接后自动下载图片，并将该条目转为本地图片">
+          <ToggleSwitch dark={dark} on={!!settings.autoSaveImageUrls} onToggle={() => updateSettings({ autoSaveImageUrls: !settings.autoSaveImageUrls })} />
+        </SettingRow>
+
         <div className="sm-panel__block--tight">
           <p className="sm-panel__label">图片处理性能档位</p>
           <select
@@ -197,6 +205,19 @@ export function StorageSettingsPanel({
             <p className="sm-panel__muted">建立网络连接（TCP/TLS）允许等待的最长时间</p>
           </div>
 
+          <div className="sm-panel__block--tight">
+            <p className="sm-panel__label">同时下载数</p>
+            <LocalNumberInput
+              dark={dark}
+              min={1}
+              max={16}
+              step={1}
+              value={settings.imageMaxConcurrentDownloads}
+              onChangeComplete={(val) => updateSettings({ imageMaxConcurrentDownloads: val })}
+            />
+            <p className="sm-panel__muted">同时进行的图片链接下载数量，超出的按优先级排队；相同链接只下载一次</p>
+          </div>
+
           <div className="sm-panel__block--tight">
             <p className="sm-panel__label">首包超时（毫秒）</p>
             <LocalNumberInput
@@ -255,6 +276,72 @@ export function StorageSettingsPanel({
         </div>
       </section>
 
+      <section className="sm-panel__section" data-theme={dark ? 'dark' : 'light'}>
+        <h3 className="sm-panel__section-title">主选区（仅 Linux X11）</h3>
+
+        <SettingRow title="捕获选中文本" desc="将鼠标选中的文本（PRIMARY 选区）作为单独来源记录到历史">
+          <ToggleSwitch
+            dark={dark}
+            on={settings.primarySelectionCapture}
+            onToggle={() => updateSettings({ primarySelectionCapture: !settings.primarySelectionCapture })}
+          />
+        </SettingRow>
+
+        <div className="sm-panel__block--tight">
+          <p className="sm-panel__label">选区稳定时间（毫秒）</p>
+          <LocalNumberInput
+            dark={dark}
+            min={100}
+            max={5000}
+            step={50}
+            value={settings.primarySelectionDebounceMs}
+            onChangeComplete={(val) => updateSettings({ primarySelectionDebounceMs: val })}
+          />
+          <p className="sm-panel__muted">拖选过程中选区持续变化，停止变化超过该时间才记录（默认 600ms）</p>
+        </div>
+
+        <div className="sm-panel__block--tight">
+          <p className="sm-panel__label">最少字符数</p>
+          <LocalNumberInput
+            dark={dark}
+            min={1}
+            max={1000}
+            step={1}
+            value={settings.primarySelectionMinLength}
+            onChangeComplete={(val) => updateSettings({ primarySelectionMinLength: val })}
+          />
+          <p className="sm-panel__muted">忽略双击选中单个短词等误触选区</p>
+        </div>
+
+        <div className="sm-panel__block--tight">
+          <p className="sm-panel__label">自动过期（分钟）</p>
+          <LocalNumberInput
+            dark={dark}
+            min={0}
+            max={10080}
+            step={10}
+            value={settings.primarySelectionExpireMinutes}
+            onChangeComplete={(val) => updateSettings({ primarySelectionExpireMinutes: val })}
+          />
+          <p className="sm-panel__muted">0 表示不过期；置顶与收藏的条目不会被清理</p>
+        </div>
+
+        <div className="sm-panel__block--tight">
+          <p className="sm-panel__label">与剪贴板同步</p>
+          <select
+            value={settings.primarySelectionSync}
+            onChange={(e) => updateSettings({ primarySelectionSync: e.target.value as typeof settings.primarySelectionSync })}
+            className="sm-field__select"
+            data-theme={dark ? 'dark' : 'light'}
+          >
+            <option value="off">不同步</option>
+            <option value="to_clipboard">选中文本 → 剪贴板</option>
+            <option value="to_primary">剪贴板 → 选中文本</option>
+            <option value="both">双向同步</option>
+          </select>
+        </div>
+      </section>
+
       <section className="sm-panel__section" data-theme={dark ? 'dark' : 'light'}>
         <h3 className="sm-panel__section-title">存储路径</h3>
         <PathSelector
@@ -18,6 +18,8 @@ export const DEFAULT_SETTINGS: AppSettings = {
   prefetchImageOnDragStart: false,
   showImagePreview: true,
   imagesDir: '',
+  stripImageExif: false,
+  autoSaveImageUrls: false,
   imagePerformanceProfile: 'balanced',
   allowPrivateNetwork: false,
   resolveDnsForUrlSafety: true,
@@ -27,7 +29,15 @@ export const DEFAULT_SETTINGS: AppSettings = {
   imageChunkTimeoutMs: 15_000,
   imageClipboardRetryMaxTotalMs: 1_800,
   imageClipboardRetryMaxDelayMs: 900,
+  imageDomainRules: [],
+  imageMaxConcurrentDownloads: 3,
   clipboardEventMinIntervalMs: 80,
+  primarySelectionCapture: false,
+  primarySelectionDebounceMs: 600,
+  primarySelectionMinLength: 3,
+  primarySelectionSync: 'off',
+  primarySelectionExpireMinutes: 0,
+  backendLocale: 'zh-CN',
   galleryDisplayMode: 'carousel',
   galleryScrollDirection: 'horizontal',
   galleryWheelMode: 'ctrl',
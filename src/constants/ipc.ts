@@ -22,6 +22,14 @@ export const IMAGE_DOWNLOAD_EVENTS = {
   progress: 'image-download-progress',
 } as const;
 
+/** X11 PRIMARY 选区监听（仅 Linux，由后端发送到主窗口） */
+export const PRIMARY_SELECTION_EVENTS = {
+  /** 新的 PRIMARY 条目已入库，载荷为 ClipItem */
+  captured: 'primary-selection-captured',
+  /** 过期的 PRIMARY 条目已删除，载荷为 id 列表 */
+  expired: 'primary-selection-expired',
+} as const;
+
 export const HUD_HOST_EVENTS = {
   /** HUD 宿主窗口前端就绪（React 已挂载，所有事件监听器已注册） */
   ready: 'hud-host-ready',
@@ -19,11 +19,9 @@ import { useShortcuts } from '../hooks/useShortcuts';
 import { useKeyboardNavigation } from '../hooks/useKeyboardNavigation';
 import { confirm } from '@tauri-apps/plugin-dialog';
 import { isTauri, TauriService } from '../services/tauri';
+import { toErrorMessage } from '../utils/appError';
 import { requestSync as requestHudSync, notifyExternalHide as notifyHudExternalHide } from '../hud/clipitem/clipItemHudManager';
 
-const toMsg = (err: unknown): string =>
-  err instanceof Error ? err.message : String(err);
-
 // Re-export for backward compatibility
 export type { FilterType } from './UIContext';
 
@@ -206,7 +204,7 @@ function AppBridge({ children }: { children: React.ReactNode }) {
         await clearAllRaw();
       }
     } catch (err) {
-      alert(`清空失败：${toMsg(err)}`);
+      alert(`清空失败：${toErrorMessage(err)}`);
     }
   }, [clearAllRaw]);
 
@@ -216,7 +214,7 @@ function AppBridge({ children }: { children: React.ReactNode }) {
       const err = await importDataRaw(e);
       alert(err ? `导入失败：${err}` : '导入成功！');
     } catch (err) {
-      alert(`导入失败：${toMsg(err)}`);
+      alert(`导入失败：${toErrorMessage(err)}`);
     }
   }, [importDataRaw]);
 
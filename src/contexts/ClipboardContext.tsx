@@ -8,11 +8,13 @@
 import React, { createContext, useContext, useState, useCallback, useEffect, useMemo } from 'react';
 import { ClipItem, AppSettings, Tag } from '../types';
 import { ClipboardDB } from '../services/db';
-import { isTauri } from '../services/tauri';
+import { isTauri, TauriService } from '../services/tauri';
 import { useClipboard } from '../hooks/useClipboard';
 import { useStats } from '../hooks/useStats';
 import { AppStats } from '../types';
 import { downloadJSON } from '../utils/download';
+import { toErrorMessage } from '../utils/appError';
+import { subscribeTauriEvent } from '../hud/subscribe';
 
 // ============================================================================
 // 类型
@@ -68,9 +70,6 @@ export function useClipboardContext(): ClipboardContextValue {
 // 错误工具
 // ============================================================================
 
-const toMsg = (err: unknown): string =>
-  err instanceof Error ? err.message : String(err);
-
 function insertCapturedItem(prev: ClipItem[], item: ClipItem, maxItems: number): ClipItem[] {
   const deduped = prev.filter(existing => existing.id !== item.id);
 
@@ -101,7 +100,7 @@ function makeHandler<Args extends unknown[]>(
     try {
       await fn(...args);
     } catch (err) {
-      setError(`${label}: ${toMsg(err)}`);
+      setError(`${label}: ${toErrorMessage(err)}`);
     }
   };
 }
@@ -145,7 +144,7 @@ export function ClipboardProvider({
         await Promise.all([loadHistory(), loadTags()]);
       } catch (err) {
         if (!disposed) {
-          setError('数据库自动清理失败: ' + toMsg(err));
+          setError('数据库自动清理失败: ' + toErrorMessage(err));
         }
       }
     };
@@ -157,6 +156,16 @@ export function ClipboardProvider({
     };
   }, [settings.autoClearDays, loadHistory, loadTags]);
 
+  // 后端清理过期 PRIMARY 条目后从列表移除
+  useEffect(() => {
+    if (!isTauri) return;
+    return subscribeTauriEvent(TauriService.listenPrimarySelectionExpired, (ids) => {
+      const removed = new Set(ids);
+      setHistory(prev => prev.filter(item => !removed.has(item.id)));
+      void updateStats();
+    });
+  }, [updateStats]);
+
   // 剪贴板监听 & 复制（注入 setError 实现错误上报）
   const handleClipboardError = useCallback((msg: string) => setError(msg), []);
   const handleCapturedItem = useCallback(async (item: ClipItem) => {
@@ -193,7 +202,7 @@ export function ClipboardProvider({
           ? { ...entry, is_favorite: currentFavorite }
           : entry
       )));
-      setError(`切换收藏失败: ${toMsg(err)}`);
+      setError(`切换收藏失败: ${toErrorMessage(err)}`);
     }
   }, [history, loadHistory]);
 
@@ -308,7 +317,7 @@ export function ClipboardProvider({
           await loadHistory();
           resolve(null);
         } catch (err) {
-          resolve(`解析失败: ${toMsg(err)}`);
+          resolve(`解析失败: ${toErrorMessage(err)}`);
         }
       };
       reader.onerror = () => resolve('文件读取失败');
@@ -12,7 +12,7 @@ import { PhysicalPosition } from '@tauri-apps/api/window';
 import { ClipItem, AppSettings, DownloadState, ImageType } from '../types';
 import { executeCopyStrategy, resolveCopyStrategy } from '../services/copyRouter';
 import { TauriService, isTauri } from '../services/tauri';
-import { detectType, detectImageType, detectContentType, normalizeFilePath, isFileList, encodeFileList, isAllImageFiles } from '../utils';
+import { detectType, detectImageType, detectContentType, normalizeFilePath, isFileList, encodeFileList, isAllImageFiles, parseAppError, toErrorMessage } from '../utils';
 import { setClipItemHudDragging, setClipItemHudVisible } from '../hud/clipitem/clipItemHudManager';
 
 // ============================================================================
@@ -40,32 +40,6 @@ const FILTER_PREDICATES: Record<FilterType, (item: ClipItem, type: string) => bo
 
 type CopyTextFallback = (text: string) => Promise<void>;
 
-interface ImageCommandErrorLike {
-  code?: string;
-  stage?: string;
-  message?: string;
-}
-
-/** 错误信息提取 */
-const toMsg = (err: unknown): string =>
-  err instanceof Error ? err.message : String(err);
-
-const isObjectRecord = (value: unknown): value is Record<string, unknown> =>
-  typeof value === 'object' && value !== null;
-
-const parseImageCommandError = (err: unknown): ImageCommandErrorLike | null => {
-  if (isObjectRecord(err)) {
-    const code = typeof err.code === 'string' ? err.code : undefined;
-    const stage = typeof err.stage === 'string' ? err.stage : undefined;
-    const message = typeof err.message === 'string' ? err.message : undefined;
-    if (code || stage || message) {
-      return { code, stage, message };
-    }
-  }
-
-  return null;
-};
-
 const isCancelledCode = (code?: string): boolean => code === 'E_CANCELLED';
 
 /** 将后端 failed 事件中的 error_code/stage + error_message 映射为用户提示 */
@@ -77,6 +51,8 @@ const mapHttpImageFailedEventError = (errorCode?: string, stage?: string, errorM
         return '图片下载失败';
       case 'E_FORMAT_INVALID':
         return '图片格式校验失败';
+      case 'E_FORMAT_UNSUPPORTED':
+        return '暂不支持该图片格式';
       case 'E_DECODE_FAILED':
         return '图片解码失败';
       case 'E_CLIPBOARD_BUSY':
@@ -150,8 +126,8 @@ async function dispatchDragCopy(
     try {
       await TauriService.downloadAndCopyImage(text, requestId);
     } catch (err) {
-      const commandError = parseImageCommandError(err);
-      const msg = commandError?.message ?? toMsg(err);
+      const commandError = parseAppError(err);
+      const msg = commandError?.message ?? toErrorMessage(err);
       const isCancelled = isCancelledCode(commandError?.code);
       if (isCancelled) {
         activeDownloadRequestIdRef.current = null;
@@ -180,8 +156,8 @@ async function dispatchDragCopy(
   if (strategy === 'base64-image' || strategy === 'local-image' || strategy === 'svg-file') {
     try { await executeCopyStrategy(strategy, text); }
     catch (err) {
-      const commandError = parseImageCommandError(err);
-      const msg = commandError?.message ?? toMsg(err);
+      const commandError = parseAppError(err);
+      const msg = commandError?.message ?? toErrorMessage(err);
       const isCancelled = isCancelledCode(commandError?.code);
       if (isCancelled) {
         setDownloadState({ isDownloading: false, progress: 0, error: null });
@@ -523,7 +499,7 @@ export function UIProvider({
         } catch { /* 继续执行 */ }
       }
     } catch (err) {
-      setDownloadState({ isDownloading: false, progress: 0, error: `拖拽操作失败: ${toMsg(err)}` });
+      setDownloadState({ isDownloading: false, progress: 0, error: `拖拽操作失败: ${toErrorMessage(err)}` });
       pendingDragTextRef.current = null;
       pendingDragCopyRef.current = null;
       resetPrefetchState();
@@ -582,8 +558,8 @@ export function UIProvider({
           try {
             await prefetchPromise;
           } catch (err) {
-            const commandError = parseImageCommandError(err);
-            const msg = commandError?.message ?? toMsg(err);
+            const commandError = parseAppError(err);
+            const msg = commandError?.message ?? toErrorMessage(err);
             const isCancelled = isCancelledCode(commandError?.code);
 
             if (isCancelled) {
@@ -611,7 +587,7 @@ export function UIProvider({
       try {
         await TauriService.clickAndPaste();
       } catch (err) {
-        setDownloadState({ isDownloading: false, progress: 0, error: `粘贴操作失败: ${toMsg(err)}。请手动使用 Ctrl+V 粘贴。` });
+        setDownloadState({ isDownloading: false, progress: 0, error: `粘贴操作失败: ${toErrorMessage(err)}。请手动使用 Ctrl+V 粘贴。` });
       }
 
       if (settings.hideAfterDrag) {
@@ -633,7 +609,7 @@ export function UIProvider({
         stopDownloadHudFollow();
       }
     } catch (err) {
-      setDownloadState({ isDownloading: false, progress: 0, error: `拖拽结束处理失败: ${toMsg(err)}` });
+      setDownloadState({ isDownloading: false, progress: 0, error: `拖拽结束处理失败: ${toErrorMessage(err)}` });
     } finally {
       clearPendingDrag();
       resetPrefetchState();
@@ -3,8 +3,12 @@ import { listen } from '@tauri-apps/api/event';
 import { ClipboardDB } from '../services/db';
 import { TauriService, isTauri } from '../services/tauri';
 import { dispatchCopyByStrategy } from '../services/copyRouter';
+import { subscribeTauriEvent } from '../hud/subscribe';
+import { ImageType } from '../types';
 import type { ClipItem, AppSettings } from '../types';
 import { COPY_FEEDBACK_DURATION_MS } from '../constants';
+import { detectImageType } from '../utils/imageDetect';
+import { toErrorMessage } from '../utils/appError';
 
 /** 后端发送的剪贴板变化事件负载 */
 interface ClipboardEventPayload {
@@ -18,10 +22,6 @@ interface ClipboardEventPayload {
 /** 自身复制操作后抑制捕获的窗口期 */
 const DEDUP_RESET_MS = 1_000;
 
-/** 错误信息提取 */
-const toMsg = (err: unknown): string =>
-  err instanceof Error ? err.message : String(err);
-
 // ============================================================================
 // Hook
 // ============================================================================
@@ -38,6 +38,8 @@ export function useClipboard(
   onErrorRef.current = onError;
   const imagesDirRef = useRef(settings.imagesDir);
   imagesDirRef.current = settings.imagesDir;
+  const autoSaveImageUrlsRef = useRef(settings.autoSaveImageUrls);
+  autoSaveImageUrlsRef.current = settings.autoSaveImageUrls;
   const debounceIntervalRef = useRef(settings.clipboardEventMinIntervalMs);
   debounceIntervalRef.current = settings.clipboardEventMinIntervalMs;
 
@@ -51,6 +53,18 @@ export function useClipboard(
 
   const [copiedId, setCopiedId] = useState<number | null>(null);
 
+  // ── 图片链接自动转存：后台下载，完成后以图片条目替换原链接条目 ──
+
+  const saveImageUrl = useCallback((item: ClipItem) => {
+    if (!autoSaveImageUrlsRef.current || item.content_type === 'image') return;
+    if (detectImageType(item.text.trim()) !== ImageType.HttpUrl) return;
+
+    const requestId = TauriService.createImageDownloadRequestId();
+    ClipboardDB.downloadImageToHistory(String(item.id), requestId, imagesDirRef.current)
+      .then(converted => onCapturedRef.current(converted))
+      .catch(err => console.warn('自动保存图片链接失败:', toErrorMessage(err)));
+  }, []);
+
   // ── 核心：读取剪贴板快照并入库 ──
 
   const processSnapshot = useCallback(async () => {
@@ -67,7 +81,10 @@ export function useClipboard(
         const snapshot = await TauriService.captureClipboardSnapshot(imagesDirRef.current);
         if (snapshot && snapshot.text && snapshot.text !== selfCopyRef.current) {
           const inserted = await ClipboardDB.addClipSnapshot(snapshot);
-          if (inserted) await onCapturedRef.current(inserted);
+          if (inserted) {
+            await onCapturedRef.current(inserted);
+            saveImageUrl(inserted);
+          }
         }
 
         // 去重窗口持续到最后一次快照后 DEDUP_RESET_MS
@@ -78,11 +95,11 @@ export function useClipboard(
         }, DEDUP_RESET_MS);
       } while (hasPendingRef.current);
     } catch (err) {
-      onErrorRef.current(`读取剪贴板失败: ${toMsg(err)}`);
+      onErrorRef.current(`读取剪贴板失败: ${toErrorMessage(err)}`);
     } finally {
       isProcessingRef.current = false;
     }
-  }, []); // 零依赖 — 全部通过 ref 读取最新值
+  }, [saveImageUrl]); // saveImageUrl 稳定不变，其余通过 ref 读取最新值
 
   // ── 事件监听 ──
 
@@ -114,6 +131,14 @@ export function useClipboard(
     };
   }, [settings.autoCapture, processSnapshot]);
 
+  // PRIMARY 选区条目由后端直接入库，这里只同步到列表
+  useEffect(() => {
+    if (!isTauri) return;
+    return subscribeTauriEvent(TauriService.listenPrimarySelectionCaptured, (item) => {
+      void onCapturedRef.current(item);
+    });
+  }, []);
+
   // copiedId 反馈定时器清理
   useEffect(() => () => {
     if (copiedIdTimerRef.current) clearTimeout(copiedIdTimerRef.current);
@@ -128,7 +153,12 @@ export function useClipboard(
     try {
       const textToCopy = item.picked_color || item.text;
       selfCopyRef.current = textToCopy;
-      await dispatchCopyByStrategy(textToCopy);
+      if (isTauri && item.content_type === 'rich' && !item.picked_color) {
+        // 富文本条目由后端一次写回纯文本 + HTML + RTF，保留格式
+        await TauriService.copyClipToClipboard(item.id, 'full');
+      } else {
+        await dispatchCopyByStrategy(textToCopy);
+      }
 
       if (!options?.suppressCopiedIdFeedback) {
         setCopiedId(item.id);
@@ -139,7 +169,7 @@ export function useClipboard(
         }, COPY_FEEDBACK_DURATION_MS);
       }
     } catch (err) {
-      onErrorRef.current(`复制失败: ${toMsg(err)}`);
+      onErrorRef.current(`复制失败: ${toErrorMessage(err)}`);
     }
   }, []);
 
@@ -148,7 +178,7 @@ export function useClipboard(
       selfCopyRef.current = text;
       await TauriService.writeClipboard(text);
     } catch (err) {
-      onErrorRef.current(`复制文本失败: ${toMsg(err)}`);
+      onErrorRef.current(`复制文本失败: ${toErrorMessage(err)}`);
     }
   }, []);
 
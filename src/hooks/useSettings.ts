@@ -76,6 +76,8 @@ const FIELD_CONSTRAINTS: ReadonlyArray<
   // ── 布尔 ──
   ['allowPrivateNetwork',              BOOL],
   ['resolveDnsForUrlSafety',           BOOL],
+  ['stripImageExif',                   BOOL],
+  ['autoSaveImageUrls',                BOOL],
   ['showDragDownloadHud',              BOOL],
   ['prefetchImageOnDragStart',         BOOL],
   ['clipItemFloatingActionsEnabled',   BOOL],
@@ -83,6 +85,7 @@ const FIELD_CONSTRAINTS: ReadonlyArray<
   ['clipItemHudRadialMenuEnabled',     BOOL],
   ['clipItemHudRadialMenuFancyFx',     BOOL],
   ['alwaysOnTop',                      BOOL],
+  ['primarySelectionCapture',          BOOL],
 
   // ── 枚举 ──
   ['imagePerformanceProfile',            enumOf('quality', 'balanced', 'speed')],
@@ -94,6 +97,8 @@ const FIELD_CONSTRAINTS: ReadonlyArray<
   ['clipItemHudTriggerMouseMode',        enumOf('click', 'press_release')],
   ['clipItemHudRadialMenuLayoutProfile', enumOf('compact', 'standard', 'relaxed')],
   ['clipItemHudPositionMode',            enumOf('dynamic', 'top', 'bottom', 'left', 'right')],
+  ['primarySelectionSync',               enumOf('off', 'to_clipboard', 'to_primary', 'both')],
+  ['backendLocale',                      enumOf('zh-CN', 'en')],
 
   // ── 数值（门限：越界 → 重置为默认值）──
   ['maxDecodedBytes',                  gateInt(8 * 1024 * 1024)],
@@ -104,7 +109,11 @@ const FIELD_CONSTRAINTS: ReadonlyArray<
   ['imageClipboardRetryMaxDelayMs',   gateInt(10)],
 
   // ── 数值（钳位：越界 → 夹到最近边界）──
+  ['imageMaxConcurrentDownloads',     clampInt(1, 16)],
   ['clipboardEventMinIntervalMs',     clampInt(20, 5_000)],
+  ['primarySelectionDebounceMs',      clampInt(100, 5_000)],
+  ['primarySelectionMinLength',       clampInt(1, 1_000)],
+  ['primarySelectionExpireMinutes',   clampInt(0, 10_080)],
   ['galleryListMaxVisibleItems',      clampInt(1, 30)],
   ['fileListMaxVisibleItems',         clampInt(1, 30)],
   ['clipItemTimeMetaAutoHideWidthPx', clampInt(0, 1_600)],
@@ -313,6 +322,8 @@ export function useSettings() {
         stream_chunk_timeout_ms: settings.imageChunkTimeoutMs,
         clipboard_retry_max_total_ms: settings.imageClipboardRetryMaxTotalMs,
         clipboard_retry_max_delay_ms: settings.imageClipboardRetryMaxDelayMs,
+        domain_rules: Array.isArray(settings.imageDomainRules) ? settings.imageDomainRules : [],
+        max_concurrent_downloads: settings.imageMaxConcurrentDownloads,
       })
       .catch((err) => console.warn('同步图片高级配置失败：', err));
   }, [
@@ -324,6 +335,8 @@ export function useSettings() {
     settings.imageChunkTimeoutMs,
     settings.imageClipboardRetryMaxTotalMs,
     settings.imageClipboardRetryMaxDelayMs,
+    settings.imageDomainRules,
+    settings.imageMaxConcurrentDownloads,
   ]);
 
   useEffect(() => {
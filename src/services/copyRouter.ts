@@ -43,9 +43,15 @@ export async function executeCopyStrategy(kind: CopyStrategyKind, text: string):
       }
       return;
     }
-    case 'svg-file':
-      await TauriService.copySvgFromFile(text);
+    case 'svg-file': {
+      const normalizedPath = normalizeFilePath(text);
+      try {
+        await TauriService.copySvgImage(normalizedPath);
+      } catch {
+        await TauriService.copySvgFromFile(normalizedPath);
+      }
       return;
+    }
     case 'local-image': {
       const normalizedPath = normalizeFilePath(text);
       try {
@@ -1,10 +1,14 @@
 import { invoke } from '@tauri-apps/api/core';
-import { ClipItem, ClipFormat, ClipboardSnapshot, AppStats, Tag } from '../types';
+import { ClipItem, ClipFormat, ClipboardSnapshot, AppStats, HistoryFilter, ImageEditOp, LinkPreview, SuggestedAction, Tag } from '../types';
 
 const DB_COMMANDS = {
   autoClear: 'db_auto_clear',
   getStats: 'db_get_stats',
   getHistory: 'db_get_history',
+  getSuggestedActions: 'db_get_suggested_actions',
+  fetchLinkPreview: 'fetch_link_preview',
+  editImage: 'edit_image',
+  downloadImageToHistory: 'download_image_to_history',
   addClip: 'db_add_clip',
   addClipAndGet: 'db_add_clip_and_get',
   addClipSnapshot: 'db_add_clip_snapshot',
@@ -68,8 +72,28 @@ export const ClipboardDB = {
   getStats: () =>
     ipc<AppStats>(DB_COMMANDS.getStats),
 
-  getHistory: (limit: number) =>
-    ipc<ClipItem[]>(DB_COMMANDS.getHistory, { limit }),
+  getHistory: (limit: number, filter?: HistoryFilter) =>
+    ipc<ClipItem[]>(DB_COMMANDS.getHistory, { limit, filter: filter ?? null }),
+
+  /** 按条目的内容子类型获取建议操作（普通文本为空数组） */
+  getSuggestedActions: (id: number) =>
+    ipc<SuggestedAction[]>(DB_COMMANDS.getSuggestedActions, { id }),
+
+  /** 获取链接条目的网页预览；非链接条目返回 null，force 为 true 时忽略缓存 */
+  fetchLinkPreview: (id: number, force = false) =>
+    ipc<LinkPreview | null>(DB_COMMANDS.fetchLinkPreview, { id, force }),
+
+  /** 编辑图片条目，结果另存为新条目并返回（原条目不变） */
+  editImage: (id: number, ops: ImageEditOp[], customDir?: string) =>
+    ipc<ClipItem>(DB_COMMANDS.editImage, { id, ops, customDir: customDir || null }),
+
+  /** 下载图片链接保存为本地图片条目；传条目 ID 时原地转换，传链接时新建条目 */
+  downloadImageToHistory: (urlOrId: string, requestId?: string, customDir?: string) =>
+    ipc<ClipItem>(DB_COMMANDS.downloadImageToHistory, {
+      urlOrId,
+      requestId: requestId || null,
+      customDir: customDir || null,
+    }),
 
   addClip: (text: string, isSnippet = 0) => {
     if (!hasNonWhitespaceText(text)) return Promise.resolve();
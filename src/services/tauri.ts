@@ -6,6 +6,9 @@ import { open } from '@tauri-apps/plugin-dialog';
 import { open as openPath } from '@tauri-apps/plugin-shell';
 import type {
   ImageAdvancedConfig,
+  ImageDownloadCacheInfo,
+  ImageDownloadTask,
+  ImageOutputOptions,
   ImageDownloadProgressEvent,
   ImagePerformanceProfile,
   ClipItemHudActionEvent,
@@ -14,12 +17,15 @@ import type {
   RadialMenuActionEvent,
   WindowPlacementSettings,
   ClipboardSnapshot,
+  ClipCopyMode,
+  ClipItem,
 } from '../types';
 import {
   CLIPITEM_HUD_EVENTS,
   RADIAL_MENU_EVENTS,
   IMAGE_DOWNLOAD_EVENTS,
   HUD_HOST_EVENTS,
+  PRIMARY_SELECTION_EVENTS,
   WINDOW_LABELS,
 } from '../constants/ipc';
 
@@ -152,6 +158,10 @@ const radialPtrUp    = eventChannel<PointerUpPayload>(WINDOW_LABELS.radialMenu,
 const hudHostReady = signalChannel(WINDOW_LABELS.main, HUD_HOST_EVENTS.ready);
 const imageDownloadProgress = eventChannel<ImageDownloadProgressEvent>(WINDOW_LABELS.hudHost, IMAGE_DOWNLOAD_EVENTS.progress);
 
+// ── PRIMARY 选区（后端 → 主窗口）──
+const primarySelectionCaptured = eventChannel<ClipItem>(WINDOW_LABELS.main, PRIMARY_SELECTION_EVENTS.captured);
+const primarySelectionExpired = eventChannel<number[]>(WINDOW_LABELS.main, PRIMARY_SELECTION_EVENTS.expired);
+
 // ============================================================================
 // TauriService — 统一 Tauri API 门面
 // ============================================================================
@@ -281,6 +291,11 @@ export const TauriService = {
 
   listenImageDownloadProgress: imageDownloadProgress.listen,
 
+  // ──────────────────────────── PRIMARY 选区事件 ────────────────────────────
+
+  listenPrimarySelectionCaptured: primarySelectionCaptured.listen,
+  listenPrimarySelectionExpired: primarySelectionExpired.listen,
+
   // ──────────────────────────── 剪贴板读写 ────────────────────────────
 
   /** 将文本写入剪贴板（Tauri 由后端处理含 IgnoreGuard；Web 回退到 navigator） */
@@ -292,6 +307,10 @@ export const TauriService = {
     }
   },
 
+  /** 按模式将历史条目写回剪贴板（纯文本 / HTML / RTF 一次写入，含 IgnoreGuard） */
+  copyClipToClipboard: (id: number, mode: ClipCopyMode = 'full') =>
+    ipcVoid('copy_clip_to_clipboard', { id, mode }),
+
   /** 将 Base64 图片写入剪贴板 */
   writeImageBase64: (base64DataUrl: string) =>
     ipcVoid('copy_base64_image_to_clipboard', { data: base64DataUrl }),
@@ -311,15 +330,34 @@ export const TauriService = {
   copySvgFromFile: (path: string) =>
     copyFileViaBackend(path, 'copy_svg_from_file'),
 
-  /** 下载网络图片并复制到剪贴板 */
-  downloadAndCopyImage: (url: string, requestId: string) =>
-    ipcVoid('download_and_copy_image', { url, requestId }),
+  /**
+   * 下载网络图片并复制到剪贴板（options 省略时按默认位图输出）。
+   * priority 越大越先出队（默认 0）；同一 URL 的并发请求只下载一次。
+   */
+  downloadAndCopyImage: (url: string, requestId: string, options?: ImageOutputOptions, priority?: number) =>
+    ipcVoid('download_and_copy_image', { url, requestId, options: options ?? null, priority: priority ?? null }),
 
   cancelImageDownload(requestId: string): Promise<boolean> {
     if (!isTauri || !requestId) return Promise.resolve(false);
     return invoke<boolean>('cancel_image_download', { requestId });
   },
 
+  /** 暂停下载（同一 URL 上合并的请求一起暂停） */
+  pauseImageDownload(requestId: string): Promise<boolean> {
+    if (!isTauri || !requestId) return Promise.resolve(false);
+    return invoke<boolean>('pause_image_download', { requestId });
+  },
+
+  /** 恢复已暂停的下载，或重新发起已失败/取消的下载 */
+  retryImageDownload(requestId: string): Promise<boolean> {
+    if (!isTauri || !requestId) return Promise.resolve(false);
+    return invoke<boolean>('retry_image_download', { requestId });
+  },
+
+  /** 列出下载队列中的任务（排队、进行中与最近结束的任务） */
+  listImageDownloads: () =>
+    ipc<ImageDownloadTask[] | null>('list_image_downloads'),
+
   createImageDownloadRequestId(): string {
     return typeof crypto !== 'undefined' && typeof crypto.randomUUID === 'function'
       ? crypto.randomUUID()
@@ -330,9 +368,13 @@ export const TauriService = {
   copyBase64Image: (data: string) =>
     ipcVoid('copy_base64_image_to_clipboard', { data }),
 
-  /** 将本地图片路径复制到剪贴板 */
-  copyLocalImage: (path: string) =>
-    ipcVoid('copy_image_to_clipboard', { path }),
+  /** 将本地图片路径复制到剪贴板（可指定输出编码、缩放、裁剪等选项） */
+  copyLocalImage: (path: string, options?: ImageOutputOptions) =>
+    ipcVoid('copy_image_to_clipboard', { path, options: options ?? null }),
+
+  /** 将 SVG 栅格化为位图复制（同时附带 SVG 源文本）；宽高 / DPI 决定渲染尺寸 */
+  copySvgImage: (path: string, options?: ImageOutputOptions) =>
+    ipcVoid('copy_svg_image_to_clipboard', { path, options: options ?? null }),
 
   /** 设置图片处理性能档位 */
   setImagePerformanceProfile: (profile: ImagePerformanceProfile) =>
@@ -350,13 +392,21 @@ export const TauriService = {
   getImageAdvancedConfig: () =>
     ipc<ImageAdvancedConfig | null>('get_image_advanced_config'),
 
-  /** 将文件路径以 CF_HDROP 格式复制到剪贴板 */
+  /** 获取图片 URL 下载缓存占用情况 */
+  getImageDownloadCacheInfo: () =>
+    ipc<ImageDownloadCacheInfo | null>('get_image_download_cache_info'),
+
+  /** 清空图片 URL 下载缓存，返回清理后的占用情况 */
+  clearImageDownloadCache: () =>
+    ipc<ImageDownloadCacheInfo | null>('clear_image_download_cache'),
+
+  /** 将文件路径复制到剪贴板（Windows CF_HDROP / X11 文件列表） */
   copyFileToClipboard: (path: string) =>
     ipcVoid('copy_file_to_clipboard', { path }),
 
-  /** 将多个文件路径以 CF_HDROP 格式复制到剪贴板 */
-  copyFilesToClipboard(paths: string[]): Promise<void> {
-    return paths.length === 0 ? Promise.resolve() : ipcVoid('copy_files_to_clipboard', { paths });
+  /** 将多个文件路径复制到剪贴板；`cut` 为 true 时文件管理器粘贴后移动文件 */
+  copyFilesToClipboard(paths: string[], cut = false): Promise<void> {
+    return paths.length === 0 ? Promise.resolve() : ipcVoid('copy_files_to_clipboard', { paths, cut });
   },
 
   // ──────────────────────────── 输入模拟 ────────────────────────────
@@ -442,7 +492,7 @@ export const TauriService = {
   // ──────────────────────────── 存储 & 数据库信息 ────────────────────────────
 
   /** 获取图片目录信息（路径 + 占用大小 + 文件数） */
-  getImagesDirInfo: (customDir?: string, recursive = false) =>
+  getImagesDirInfo: (customDir?: string, recursive = true) =>
     ipc<{ path: string; total_size: number; file_count: number } | null>(
       'get_images_dir_info',
       { customDir: customDir || null, recursive },
@@ -42,14 +42,84 @@ export interface ImageAdvancedConfig {
   stream_chunk_timeout_ms: number;
   clipboard_retry_max_total_ms: number;
   clipboard_retry_max_delay_ms: number;
+  /** 按主机匹配的下载策略，按顺序取第一条命中的规则 */
+  domain_rules?: ImageDomainRule[];
+  /** URL 下载同时进行的传输数上限（1~16），超出的按优先级排队 */
+  max_concurrent_downloads?: number;
 }
 
+/**
+ * 图片下载的域名规则。
+ * pattern：`*` 匹配全部；`*.example.com` 仅匹配子域；`example.com` 匹配自身及子域。
+ */
+export interface ImageDomainRule {
+  pattern: string;
+  /** http:// 或 https:// 代理地址 */
+  proxy?: string | null;
+  /** 附加请求头，同名时覆盖默认值（如 User-Agent） */
+  headers?: Record<string, string>;
+  referer?: string | null;
+  cookie?: string | null;
+  /** 覆盖连接超时（秒） */
+  connect_timeout?: number | null;
+  /** 覆盖整体下载超时（秒） */
+  download_timeout?: number | null;
+  /** 覆盖全局内网访问开关 */
+  allow_private_network?: boolean | null;
+}
+
+/** 复制图片时的输出编码：bitmap 为平台原生位图（默认），png / jpeg 直接提供编码字节 */
+export type ImageOutputEncoding = 'bitmap' | 'png' | 'jpeg';
+
+/** 复制图片的输出选项，全部可省略；处理顺序为裁剪 → 旋转 → 缩放 → 背景合成 → 编码 */
+export interface ImageOutputOptions {
+  encoding?: ImageOutputEncoding;
+  /** JPEG 质量 1~100，默认 90 */
+  jpeg_quality?: number;
+  /** 目标宽高；只给一边时按比例计算另一边 */
+  width?: number;
+  height?: number;
+  /** 裁剪区域（原图像素坐标） */
+  crop?: { x: number; y: number; width: number; height: number };
+  /** 顺时针旋转角度 */
+  rotation?: 0 | 90 | 180 | 270;
+  /** 透明通道合成背景色（#rgb / #rrggbb），JPEG 未指定时为白色 */
+  background?: string;
+  dpi?: number;
+}
+
+/** 图片像素区域 */
+export interface ImageRect {
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+}
+
+/**
+ * 非破坏式图片编辑操作，按数组顺序执行，坐标基于上一步的结果。
+ * 标注颜色默认 #ff3b30，线宽默认 4；文字暂仅支持可打印 ASCII。
+ */
+export type ImageEditOp =
+  | { op: 'crop'; rect: ImageRect }
+  | { op: 'rotate'; degrees: 90 | 180 | 270 }
+  | { op: 'flip'; direction: 'horizontal' | 'vertical' }
+  | { op: 'resize'; width?: number; height?: number }
+  | { op: 'pixelate'; rect: ImageRect; block_size?: number }
+  | { op: 'blur'; rect: ImageRect; sigma?: number }
+  | { op: 'rectangle'; rect: ImageRect; color?: string; stroke_width?: number; fill?: boolean }
+  | { op: 'arrow'; from: { x: number; y: number }; to: { x: number; y: number }; color?: string; stroke_width?: number }
+  | { op: 'text'; x: number; y: number; text: string; color?: string; size?: number };
+
 /** 剪贴板附加格式（HTML / RTF / 图片路径等） */
 export interface ClipFormat {
   format: string;
   content: string;
 }
 
+/** 历史条目回写模式：full = 纯文本 + HTML + RTF，plain = 仅纯文本，html = HTML + 纯文本 */
+export type ClipCopyMode = 'full' | 'plain' | 'html';
+
 /** 后端 capture_clipboard_snapshot 返回的完整快照 */
 export interface ClipboardSnapshot {
   content_type: 'text' | 'image' | 'files' | 'rich';
@@ -72,10 +142,86 @@ export interface ClipItem {
   picked_color: string | null;
   /** 内容类型：text | image | files | rich */
   content_type: string;
+  /** 捕获时后端识别的编程语言（如 'rust'），非代码为 null */
+  language?: string | null;
+  /** 语言识别置信度（0~1） */
+  language_confidence?: number | null;
+  /** 捕获时后端识别的内容子类型（如 'url'、'json'），普通文本为 null */
+  content_subtype?: ContentSubtype | null;
   /** 附加格式（按需加载，默认为空） */
   formats: ClipFormat[];
+  /** 图片元数据（仅图片条目存在） */
+  image_meta?: ImageMetadata | null;
+}
+
+/** 后端提取的图片元数据 */
+export interface ImageMetadata {
+  width: number;
+  height: number;
+  color_type: string;
+  byte_size: number;
+  camera_make: string | null;
+  camera_model: string | null;
+  orientation: number | null;
+  captured_at: string | null;
+  has_gps: boolean;
+  text_chunks: Record<string, string>;
+}
+
+/** db_get_history 筛选条件（字段均可选） */
+export interface HistoryFilter {
+  min_image_width?: number;
+  min_image_height?: number;
+  min_image_pixels?: number;
+  /** 捕获来源：系统剪贴板或 X11 PRIMARY 选区 */
+  selection?: 'clipboard' | 'primary';
+  /** 后端识别的编程语言（如 'rust'、'python'） */
+  language?: string;
+  /** 后端识别的内容子类型 */
+  content_subtype?: ContentSubtype;
+}
+
+/** 后端识别的文本内容子类型 */
+export type ContentSubtype =
+  | 'url'
+  | 'email'
+  | 'phone'
+  | 'file_path'
+  | 'ip_address'
+  | 'uuid'
+  | 'json'
+  | 'xml'
+  | 'table'
+  | 'markdown'
+  | 'number'
+  | 'date_time'
+  | 'unix_timestamp'
+  | 'hex_color'
+  | 'code';
+
+/** 建议操作：open 交给系统打开，transform 对应同名文本转换，edit 打开编辑器，convert 展示换算结果，file 作为文件复制 */
+export interface SuggestedAction {
+  id: string;
+  label: string;
+  kind: 'open' | 'transform' | 'edit' | 'convert' | 'file';
 }
 
+/** 链接条目的网页预览；各字段缺失时为 null，fetched_at 为抓取时间（毫秒） */
+export interface LinkPreview {
+  url: string;
+  title: string | null;
+  description: string | null;
+  favicon: string | null;
+  image: string | null;
+  fetched_at: number;
+}
+
+/** 后端消息语言（错误提示、托盘菜单） */
+export type BackendLocale = 'zh-CN' | 'en';
+
+/** PRIMARY ↔ CLIPBOARD 同步方向（仅 Linux X11） */
+export type PrimarySelectionSyncMode = 'off' | 'to_clipboard' | 'to_primary' | 'both';
+
 export interface AppSettings {
   autoCapture: boolean;
   maxItems: number;
@@ -91,6 +237,8 @@ export interface AppSettings {
   prefetchImageOnDragStart: boolean; // Start URL image download when drag starts
   showImagePreview: boolean;
   imagesDir: string; // Directory to save images, empty string for default
+  stripImageExif: boolean; // Remove EXIF (incl. GPS) from stored image copies
+  autoSaveImageUrls: boolean; // Download captured image URLs into local image items
   imagePerformanceProfile: ImagePerformanceProfile;
   allowPrivateNetwork: boolean;
   resolveDnsForUrlSafety: boolean;
@@ -100,7 +248,20 @@ export interface AppSettings {
   imageChunkTimeoutMs: number;
   imageClipboardRetryMaxTotalMs: number;
   imageClipboardRetryMaxDelayMs: number;
+  /** 图片下载的域名规则（代理、请求头、Referer、Cookie、超时、内网访问） */
+  imageDomainRules: ImageDomainRule[];
+  /** URL 图片同时下载的数量上限 */
+  imageMaxConcurrentDownloads: number;
   clipboardEventMinIntervalMs: number;
+  /** 捕获 X11 PRIMARY 选区到历史（仅 Linux） */
+  primarySelectionCapture: boolean;
+  primarySelectionDebounceMs: number;
+  primarySelectionMinLength: number;
+  primarySelectionSync: PrimarySelectionSyncMode;
+  /** PRIMARY 条目过期分钟数，0 表示不过期 */
+  primarySelectionExpireMinutes: number;
+  /** 后端错误提示与托盘菜单的语言 */
+  backendLocale: BackendLocale;
   galleryDisplayMode: GalleryDisplayMode;
   galleryScrollDirection: GalleryScrollDirection;
   galleryWheelMode: GalleryWheelMode;
@@ -147,6 +308,7 @@ export type ImageDownloadErrorCode =
   | 'E_NET_REQUEST'
   | 'E_NET_TIMEOUT'
   | 'E_FORMAT_INVALID'
+  | 'E_FORMAT_UNSUPPORTED'
   | 'E_DECODE_FAILED'
   | 'E_CLIPBOARD_BUSY'
   | 'E_CLIPBOARD_WRITE'
@@ -154,6 +316,21 @@ export type ImageDownloadErrorCode =
   | 'E_RESOURCE_LIMIT'
   | 'E_CANCELLED';
 
+/** 后端 `AppError` 序列化结构（所有 Tauri 命令的错误返回值） */
+export interface AppErrorPayload {
+  kind: 'clipboard' | 'image' | 'io' | 'storage' | 'window' | 'input' | 'database' | 'transform' | 'request';
+  /** 稳定的机器码，如 `E_DB_LOCKED`、`E_STORAGE_FULL`、`E_CLIPBOARD_BUSY` */
+  code: string;
+  /** 本地化的完整错误消息 */
+  message: string;
+  /** 底层错误原文 */
+  detail: string | null;
+  /** 稍后重试是否可能成功 */
+  retryable: boolean;
+  /** 仅图片错误携带 */
+  stage?: ImageDownloadProgressEvent['stage'];
+}
+
 export interface ImageDownloadProgressEvent {
   request_id: string;
   progress: number;
@@ -163,6 +340,40 @@ export interface ImageDownloadProgressEvent {
   stage?: 'download' | 'format' | 'decode' | 'clipboard' | 'resource' | 'unknown';
   error_code?: ImageDownloadErrorCode;
   error_message?: string;
+  /** 完成事件中命中的下载缓存层级（未命中时缺省） */
+  cache_hit?: 'memory' | 'disk' | 'revalidated' | 'stale' | null;
+}
+
+export type ImageDownloadTaskStatus = 'queued' | 'running' | 'paused' | 'completed' | 'failed' | 'cancelled';
+
+/** 任务用途：复制到剪贴板，或保存为历史图片条目 */
+export type ImageDownloadTaskKind = 'clipboard' | 'history';
+
+/** 下载队列中的任务快照（`list_image_downloads`） */
+export interface ImageDownloadTask {
+  request_id: string;
+  url: string;
+  priority: number;
+  kind: ImageDownloadTaskKind;
+  status: ImageDownloadTaskStatus;
+  /** 运行中为 download / decode；失败时为出错阶段 */
+  stage: string | null;
+  downloaded_bytes: number;
+  total_bytes: number | null;
+  /** 是否合并到了同一 URL 的已有传输上 */
+  coalesced: boolean;
+  cache_hit: 'memory' | 'disk' | 'revalidated' | 'stale' | null;
+  error_code: ImageDownloadErrorCode | null;
+  error_message: string | null;
+  created_at: number;
+  finished_at: number | null;
+}
+
+/** 图片 URL 下载磁盘缓存占用情况 */
+export interface ImageDownloadCacheInfo {
+  entries: number;
+  total_bytes: number;
+  max_bytes: number;
 }
 
 import type { DateParts } from '../utils/formatDate';
@@ -0,0 +1,34 @@
+import type { AppErrorPayload } from '../types';
+
+const isObjectRecord = (value: unknown): value is Record<string, unknown> =>
+  typeof value === 'object' && value !== null;
+
+/**
+ * 识别后端返回的结构化错误（`{kind, code, message, detail, retryable}`）
+ */
+export function parseAppError(err: unknown): AppErrorPayload | null {
+  if (!isObjectRecord(err)) return null;
+  const { kind, code, message, detail, retryable, stage } = err;
+  if (typeof kind !== 'string' || typeof code !== 'string' || typeof message !== 'string') {
+    return null;
+  }
+  return {
+    kind: kind as AppErrorPayload['kind'],
+    code,
+    message,
+    detail: typeof detail === 'string' ? detail : null,
+    retryable: retryable === true,
+    stage: typeof stage === 'string' ? stage as AppErrorPayload['stage'] : undefined,
+  };
+}
+
+/**
+ * 错误信息提取：兼容 `Error`、后端结构化错误与字符串错误
+ */
+export function toErrorMessage(err: unknown): string {
+  if (err instanceof Error) return err.message;
+  const appError = parseAppError(err);
+  if (appError) return appError.message;
+  if (isObjectRecord(err) && typeof err.message === 'string') return err.message;
+  return String(err);
+}
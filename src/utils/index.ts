@@ -18,6 +18,7 @@ export { ImageLRUCache, getImageCache, fetchAndCacheImage } from './imageCache';
 
 // 通用工具
 export { escapeRegExp } from './stringUtils';
+export { parseAppError, toErrorMessage } from './appError';
 export { formatDateParts } from './formatDate';
 export type { DateParts } from './formatDate';
 export { normalizeShortcut, areShortcutsEquivalent, getGlobalShortcutConflict, getImmersiveShortcutConflict, getLikelySystemShortcutWarning, matchesShortcut, isReservedAppShortcut, formatShortcutFromEvent, normalizeCodeName, normalizeEventKey, MODIFIER_KEYS, RESERVED_APP_SHORTCUTS } from './shortcut';
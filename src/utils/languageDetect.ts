@@ -410,6 +410,18 @@ export function detectLanguage(code: string): DetectResult {
   return result('plaintext', '纯文本', key);
 }
 
+/**
+ * 条目语言：优先采用后端捕获时识别的结果（`ClipItem.language`），
+ * 缺失或不在前端语言表中时回退到 `detectLanguage`
+ */
+export function detectClipLanguage(code: string, backendLanguage?: string | null): DetectResult {
+  const known = backendLanguage
+    ? LANGUAGES.find(lang => lang.id === backendLanguage)
+    : undefined;
+  if (known) return { id: known.id, label: known.label };
+  return detectLanguage(code);
+}
+
 function result(id: LanguageId, label: string, cacheKeyStr: string): DetectResult {
   const r: DetectResult = { id, label };
   cachePut(cacheKeyStr, r);
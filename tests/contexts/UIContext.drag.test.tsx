@@ -437,9 +437,12 @@ describe('UIContext drag flow', () => {
       }
 
       rejectDownload({
+        kind: 'image',
         code: 'E_NET_REQUEST',
         stage: 'download',
         message: '网络波动',
+        detail: '网络波动',
+        retryable: true,
       });
 
       await dragEndPromise;
@@ -13,6 +13,7 @@ const { mockTauriService } = vi.hoisted(() => ({
     downloadAndCopyImage: vi.fn(),
     writeClipboard: vi.fn(),
     copySvgFromFile: vi.fn(),
+    copySvgImage: vi.fn(),
     copyLocalImage: vi.fn(),
     copyImageFromFile: vi.fn(),
     createImageDownloadRequestId: vi.fn(() => 'req-test'),
@@ -131,4 +132,36 @@ describe('useClipboard', () => {
     ]);
     expect(onError).not.toHaveBeenCalled();
   });
+
+  it('复制 SVG 文件时优先栅格化为位图，失败后回退为文本复制', async () => {
+    mockTauriService.copySvgImage.mockRejectedValueOnce(new Error('SVG 解析失败'));
+    mockTauriService.copySvgFromFile.mockResolvedValue(undefined);
+
+    const settings = { ...DEFAULT_SETTINGS, autoCapture: false };
+    const onCaptured = vi.fn();
+    const onError = vi.fn();
+
+    const { result } = renderHook(() => useClipboard(settings, onCaptured, onError));
+
+    const item: ClipItem = {
+      id: 4,
+      text: 'C:\\images\\logo.svg',
+      timestamp: Date.now(),
+      is_pinned: 0,
+      is_snippet: 0,
+      is_favorite: 0,
+      tags: [],
+      picked_color: null,
+      content_type: 'image',
+      formats: [],
+    };
+
+    await act(async () => {
+      await result.current.copyToClipboard(item);
+    });
+
+    expect(mockTauriService.copySvgImage).toHaveBeenCalledWith('C:\\images\\logo.svg');
+    expect(mockTauriService.copySvgFromFile).toHaveBeenCalledWith('C:\\images\\logo.svg');
+    expect(onError).not.toHaveBeenCalled();
+  });
 });
@@ -100,7 +100,31 @@ describe('useSettings', () => {
         stream_chunk_timeout_ms: 14000,
         clipboard_retry_max_total_ms: 2600,
         clipboard_retry_max_delay_ms: 700,
+        domain_rules: [],
+        max_concurrent_downloads: 3,
       });
     });
   });
+
+  it('后端消息语言回填并写回后端，非法值回退为中文', async () => {
+    mockTauriService.getAppSettings.mockResolvedValue({ backendLocale: 'fr' });
+
+    const { result } = renderHook(() => useSettings());
+
+    await waitFor(() => {
+      expect(mockTauriService.getAppSettings).toHaveBeenCalledTimes(1);
+    });
+    expect(result.current.settings.backendLocale).toBe('zh-CN');
+
+    mockTauriService.setAppSettings.mockClear();
+    act(() => {
+      result.current.updateSettings({ backendLocale: 'en' });
+    });
+
+    await waitFor(() => {
+      expect(mockTauriService.setAppSettings).toHaveBeenCalledWith(
+        expect.objectContaining({ backendLocale: 'en' }),
+      );
+    });
+  });
 });
@@ -0,0 +1,48 @@
+import { describe, it, expect } from 'vitest';
+import { parseAppError, toErrorMessage } from '../../src/utils/appError';
+
+const lockedError = {
+  kind: 'database',
+  code: 'E_DB_LOCKED',
+  message: '数据库被占用: database is locked',
+  detail: 'database is locked',
+  retryable: true,
+};
+
+describe('parseAppError', () => {
+  it('should parse structured backend errors', () => {
+    expect(parseAppError(lockedError)).toEqual({ ...lockedError, stage: undefined });
+  });
+
+  it('should keep image stage and default missing optional fields', () => {
+    const parsed = parseAppError({ kind: 'image', code: 'E_NET_TIMEOUT', message: '超时错误：30s', stage: 'download' });
+    expect(parsed).toEqual({
+      kind: 'image',
+      code: 'E_NET_TIMEOUT',
+      message: '超时错误：30s',
+      detail: null,
+      retryable: false,
+      stage: 'download',
+    });
+  });
+
+  it('should return null for strings, Error instances and incomplete objects', () => {
+    expect(parseAppError('数据库错误')).toBeNull();
+    expect(parseAppError(new Error('boom'))).toBeNull();
+    expect(parseAppError({ code: 'E_NET_REQUEST', message: 'x' })).toBeNull();
+    expect(parseAppError(null)).toBeNull();
+  });
+});
+
+describe('toErrorMessage', () => {
+  it('should prefer the localized message of structured errors', () => {
+    expect(toErrorMessage(lockedError)).toBe('数据库被占用: database is locked');
+  });
+
+  it('should handle Error instances, image command errors and plain values', () => {
+    expect(toErrorMessage(new Error('boom'))).toBe('boom');
+    expect(toErrorMessage({ code: 'E_NET_REQUEST', stage: 'download', message: '网络错误：404' })).toBe('网络错误：404');
+    expect(toErrorMessage('plain')).toBe('plain');
+    expect(toErrorMessage(42)).toBe('42');
+  });
+});